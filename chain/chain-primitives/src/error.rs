@@ -452,5 +452,8 @@ pub mod epoch_sync {
         IOErr(#[from] std::io::Error),
         #[error(transparent)]
         ChainErr(#[from] crate::Error),
+        /// `EpochSyncInfo` received from a peer doesn't prove the epoch it claims to.
+        #[error("Invalid EpochSyncInfo: {0}")]
+        InvalidEpochSyncInfo(String),
     }
 }
@@ -1,6 +1,7 @@
 use crate::chain::BlockMissingChunks;
 use crate::near_chain_primitives::error::BlockKnownError::KnownInProcessing;
 use crate::orphan::OrphanMissingChunks;
+use crate::types::ChainReorg;
 use crate::Provenance;
 use near_async::time::Instant;
 use near_primitives::block::Block;
@@ -66,6 +67,7 @@ pub struct BlockProcessingArtifact {
     pub blocks_missing_chunks: Vec<BlockMissingChunks>,
     pub challenges: Vec<ChallengeBody>,
     pub invalid_chunks: Vec<ShardChunkHeader>,
+    pub reorgs: Vec<ChainReorg>,
 }
 
 /// This struct defines the callback function that will be called after apply chunks are finished
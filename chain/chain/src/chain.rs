@@ -15,8 +15,8 @@ use crate::store::{ChainStore, ChainStoreAccess, ChainStoreUpdate};
 
 use crate::rayon_spawner::RayonAsyncComputationSpawner;
 use crate::types::{
-    AcceptedBlock, ApplyChunkBlockContext, BlockEconomicsConfig, ChainConfig, RuntimeAdapter,
-    StorageDataSource,
+    AcceptedBlock, ApplyChunkBlockContext, BlockEconomicsConfig, ChainConfig, ChainReorg,
+    RuntimeAdapter, StorageDataSource,
 };
 pub use crate::update_shard::{
     apply_new_chunk, apply_old_chunk, NewChunkData, NewChunkResult, OldChunkData, OldChunkResult,
@@ -56,6 +56,8 @@ use near_primitives::challenge::{
 };
 use near_primitives::checked_feature;
 #[cfg(feature = "new_epoch_sync")]
+use near_primitives::epoch_manager::epoch_info::EpochInfo;
+#[cfg(feature = "new_epoch_sync")]
 use near_primitives::epoch_manager::epoch_sync::EpochSyncInfo;
 #[cfg(feature = "new_epoch_sync")]
 use near_primitives::errors::epoch_sync::EpochSyncHashType;
@@ -1535,6 +1537,26 @@ impl Chain {
         }
     }
 
+    /// Finds the blocks reverted and applied by replacing `old_head` with `new_head`, by walking
+    /// back from both heads to their common ancestor.
+    fn get_chain_reorg(&self, old_head: Tip, new_head: Tip) -> Result<ChainReorg, Error> {
+        let mut reverted_blocks = vec![];
+        let mut applied_blocks = vec![];
+        let mut old_header = self.get_block_header(&old_head.last_block_hash)?;
+        let mut new_header = self.get_block_header(&new_head.last_block_hash)?;
+        while old_header.hash() != new_header.hash() {
+            if new_header.height() >= old_header.height() {
+                applied_blocks.push(*new_header.hash());
+                new_header = self.get_previous_header(&new_header)?;
+            } else {
+                reverted_blocks.push(*old_header.hash());
+                old_header = self.get_previous_header(&old_header)?;
+            }
+        }
+        applied_blocks.reverse();
+        Ok(ChainReorg { old_head, new_head, reverted_blocks, applied_blocks })
+    }
+
     /// Set the new head after state sync was completed if it is indeed newer.
     /// Check for potentially unlocked orphans after this update.
     pub fn reset_heads_post_state_sync(
@@ -1878,7 +1900,21 @@ impl Chain {
         // Determine the block status of this block (whether it is a side fork and updates the chain head)
         // Block status is needed in Client::on_block_accepted_with_optional_chunk_produce to
         // decide to how to update the tx pool.
-        let block_status = self.determine_status(new_head, prev_head);
+        let block_status = self.determine_status(new_head.clone(), prev_head.clone());
+        if let (BlockStatus::Reorg(_), Some(new_head)) = (&block_status, new_head) {
+            match self.get_chain_reorg(prev_head, new_head) {
+                Ok(reorg) => {
+                    debug!(target: "chain", old_head = ?reorg.old_head.last_block_hash, new_head = ?reorg.new_head.last_block_hash, reverted_blocks = reorg.reverted_blocks.len(), "Chain reorg");
+                    metrics::CHAIN_REORGS_TOTAL.inc();
+                    metrics::CHAIN_REORG_DEPTH.observe(reorg.reverted_blocks.len() as f64);
+                    block_processing_artifacts.reorgs.push(reorg);
+                }
+                Err(err) => {
+                    warn!(target: "chain", ?err, "Failed to find blocks reverted by the reorg");
+                    metrics::CHAIN_REORGS_FAILED_TOTAL.inc();
+                }
+            }
+        }
         Ok(AcceptedBlock { hash: *block.hash(), status: block_status, provenance })
     }
 
@@ -3544,7 +3580,14 @@ impl Chain {
             let prev_hash = head.prev_block_hash;
             let epoch_height = self.epoch_manager.get_epoch_height_from_prev_block(&prev_hash)?;
             let shard_layout = &self.epoch_manager.get_shard_layout_from_prev_block(&prev_hash)?;
-            let shard_uids = shard_layout.shard_uids().collect();
+            // Shards without flat storage, e.g. the ones no longer tracked, can't be snapshotted.
+            let flat_storage_manager = self.runtime_adapter.get_flat_storage_manager();
+            let shard_uids = shard_layout
+                .shard_uids()
+                .filter(|shard_uid| {
+                    flat_storage_manager.get_flat_storage_for_shard(*shard_uid).is_some()
+                })
+                .collect();
             let last_block = self.get_block(&head.last_block_hash)?;
             let make_snapshot_callback = &snapshot_callbacks.make_snapshot_callback;
             make_snapshot_callback(prev_hash, epoch_height, shard_uids, last_block);
@@ -4264,11 +4307,170 @@ impl Chain {
 /// Epoch sync specific functions.
 #[cfg(feature = "new_epoch_sync")]
 impl Chain {
-    /// TODO(posvyatokum): validate `epoch_sync_info` before `store_update` commit.
+    /// Returns `EpochSyncInfo`s of up to `max_epochs` consecutive epochs, starting with `epoch_id`.
+    /// Stops at the first epoch which is not finalised yet.
+    pub fn get_epoch_sync_infos(
+        &self,
+        epoch_id: &EpochId,
+        max_epochs: usize,
+    ) -> Result<Vec<EpochSyncInfo>, EpochSyncInfoError> {
+        let mut epoch_sync_infos = vec![];
+        let mut epoch_id = epoch_id.clone();
+        while epoch_sync_infos.len() < max_epochs {
+            let epoch_sync_info = match self.chain_store().get_epoch_sync_info(&epoch_id) {
+                Ok(epoch_sync_info) => epoch_sync_info,
+                Err(Error::DBNotFoundErr(_)) => break,
+                Err(err) => return Err(err.into()),
+            };
+            epoch_id = epoch_sync_info.get_next_epoch_id()?.clone();
+            epoch_sync_infos.push(epoch_sync_info);
+        }
+        Ok(epoch_sync_infos)
+    }
+
+    /// Checks that `epoch_sync_info` proves the epoch directly following the header head.
+    ///
+    /// `EpochInfo`s of the epoch and of the next epoch must already be known, either from
+    /// genesis or from the previously recorded `EpochSyncInfo`. Starting from them:
+    /// - headers to save must be signed by the block producers of the epoch,
+    /// - the first header of the next epoch must be signed and approved by the block
+    ///   producers of the next epoch,
+    /// - that header commits to all block hashes of the epoch with `block_merkle_root`,
+    ///   and to all `EpochInfo`s of `epoch_sync_info` with `epoch_sync_data_hash`.
+    fn verify_epoch_sync_info(
+        &self,
+        epoch_sync_info: &EpochSyncInfo,
+    ) -> Result<(), EpochSyncInfoError> {
+        let invalid = |msg: String| EpochSyncInfoError::InvalidEpochSyncInfo(msg);
+
+        for (hash, header) in &epoch_sync_info.headers {
+            if hash != header.hash() {
+                return Err(invalid(format!("header {} is keyed by {}", header.hash(), hash)));
+            }
+        }
+
+        let epoch_id = epoch_sync_info.get_epoch_id()?;
+        let next_epoch_id = epoch_sync_info.get_next_epoch_id()?;
+        let first_header = epoch_sync_info.get_epoch_first_header()?;
+        let last_header = epoch_sync_info.get_epoch_last_header()?;
+        let next_epoch_first_header = epoch_sync_info.get_header(
+            epoch_sync_info.next_epoch_first_hash,
+            EpochSyncHashType::NextEpochFirstBlock,
+        )?;
+
+        // The epoch must continue the chain we already know.
+        let header_head = self.header_head()?;
+        if first_header.prev_hash() != &header_head.last_block_hash {
+            return Err(invalid(format!(
+                "epoch {:?} starts after {}, but header head is {}",
+                epoch_id,
+                first_header.prev_hash(),
+                header_head.last_block_hash
+            )));
+        }
+        if next_epoch_id != &EpochId(*first_header.prev_hash()) {
+            return Err(invalid(format!(
+                "next epoch {:?} doesn't follow epoch {:?}",
+                next_epoch_id, epoch_id
+            )));
+        }
+        if next_epoch_first_header.prev_hash() != last_header.hash() {
+            return Err(invalid(format!(
+                "next epoch first block {} doesn't follow epoch last block {}",
+                next_epoch_first_header.hash(),
+                last_header.hash()
+            )));
+        }
+
+        // `EpochInfo`s we already trust.
+        let epoch_info = self.epoch_manager.get_epoch_info(epoch_id).map_err(Error::from)?;
+        let next_epoch_info =
+            self.epoch_manager.get_epoch_info(next_epoch_id).map_err(Error::from)?;
+        if *epoch_info != epoch_sync_info.epoch_info
+            || *next_epoch_info != epoch_sync_info.next_epoch_info
+        {
+            return Err(invalid(format!("unexpected EpochInfo for epoch {:?}", epoch_id)));
+        }
+
+        let verify_signature = |header: &BlockHeader, epoch_info: &EpochInfo| {
+            let block_producer =
+                epoch_info.get_validator(epoch_info.sample_block_producer(header.height()));
+            if header.signature().verify(header.hash().as_ref(), block_producer.public_key()) {
+                Ok(())
+            } else {
+                Err(invalid(format!("invalid signature of header {}", header.hash())))
+            }
+        };
+        let all_block_hashes = epoch_sync_info.all_block_hashes.iter().collect::<HashSet<_>>();
+        if !epoch_sync_info.headers_to_save.contains(first_header.hash())
+            || !epoch_sync_info.headers_to_save.contains(last_header.hash())
+        {
+            return Err(invalid("first or last header of the epoch is not saved".to_string()));
+        }
+        for hash in &epoch_sync_info.headers_to_save {
+            let header = epoch_sync_info.get_header(*hash, EpochSyncHashType::BlockToSave)?;
+            if header.epoch_id() != epoch_id || !all_block_hashes.contains(hash) {
+                return Err(invalid(format!("header {} is not in epoch {:?}", hash, epoch_id)));
+            }
+            verify_signature(header, &epoch_sync_info.epoch_info)?;
+        }
+        verify_signature(next_epoch_first_header, &epoch_sync_info.next_epoch_info)?;
+        self.epoch_manager
+            .verify_approvals_and_threshold_orphan(
+                next_epoch_id,
+                &|approvals, stakes| {
+                    Doomslug::can_approved_block_be_produced(
+                        self.doomslug_threshold_mode,
+                        approvals,
+                        stakes,
+                    )
+                },
+                last_header.hash(),
+                last_header.height(),
+                next_epoch_first_header.height(),
+                next_epoch_first_header.approvals(),
+            )
+            .map_err(|err| {
+                invalid(format!("approvals of header {}: {}", next_epoch_first_header.hash(), err))
+            })?;
+
+        // Block hashes of the epoch must be committed to by the block merkle roots.
+        let mut block_merkle_tree =
+            (*self.chain_store().get_block_merkle_tree(first_header.prev_hash())?).clone();
+        let mut prev_hash = first_header.prev_hash();
+        for hash in &epoch_sync_info.all_block_hashes {
+            block_merkle_tree.insert(*prev_hash);
+            if let Some(header) = epoch_sync_info.headers.get(hash) {
+                if header.prev_hash() != prev_hash
+                    || header.block_merkle_root() != &block_merkle_tree.root()
+                {
+                    return Err(invalid(format!("header {} is out of order", hash)));
+                }
+            }
+            prev_hash = hash;
+        }
+        block_merkle_tree.insert(*prev_hash);
+        if next_epoch_first_header.block_merkle_root() != &block_merkle_tree.root() {
+            return Err(invalid(format!("block hashes of epoch {:?} don't match", epoch_id)));
+        }
+
+        // All `EpochInfo`s are committed to by the first header of the next epoch.
+        if epoch_sync_info.get_epoch_sync_data_hash()?
+            != Some(epoch_sync_info.calculate_epoch_sync_data_hash()?)
+        {
+            return Err(invalid(format!("epoch sync data hash of epoch {:?} mismatch", epoch_id)));
+        }
+        Ok(())
+    }
+
+    /// Verifies `epoch_sync_info` and records the epoch it proves, moving header head
+    /// to the last block of the epoch.
     pub fn validate_and_record_epoch_sync_info(
         &mut self,
         epoch_sync_info: &EpochSyncInfo,
     ) -> Result<(), EpochSyncInfoError> {
+        self.verify_epoch_sync_info(epoch_sync_info)?;
+
         let store = self.chain_store().store().clone();
         let epoch_manager = self.epoch_manager.clone();
         let mut chain_store_update = self.chain_store.store_update();
@@ -4347,8 +4549,6 @@ impl Chain {
         epoch_manager
             .force_update_aggregator(epoch_id, epoch_sync_info.get_epoch_last_finalised_hash()?);
 
-        // TODO(posvyatokum): add EpochSyncInfo validation.
-
         chain_store_update.merge(store_update);
         chain_store_update.commit()?;
         Ok(())
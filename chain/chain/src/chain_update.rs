@@ -357,6 +357,13 @@ impl<'a> ChainUpdate<'a> {
                     apply_result.proof,
                     apply_result.applied_receipts_hash,
                 );
+                if let Some(execution_report) = apply_result.execution_report {
+                    self.chain_store_update.save_chunk_execution_report(
+                        *block_hash,
+                        shard_id,
+                        execution_report,
+                    );
+                }
                 if let Some(resharding_results) = resharding_results {
                     self.process_resharding_results(block, &shard_uid, resharding_results)?;
                 }
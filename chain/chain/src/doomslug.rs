@@ -9,7 +9,7 @@ use near_primitives::types::{AccountId, ApprovalStake, Balance, BlockHeight, Blo
 use near_primitives::validator_signer::ValidatorSigner;
 use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
-use tracing::{debug, debug_span, field, info};
+use tracing::{debug, debug_span, field, info, warn};
 
 /// Have that many iterations in the timer instead of `loop` to prevent potential bugs from blocking
 /// the node
@@ -440,6 +440,14 @@ impl Doomslug {
         self.history.iter().cloned().collect::<Vec<_>>()
     }
 
+    /// Returns the latest approval history entry for the given target height.
+    pub fn get_approval_history_entry(
+        &self,
+        target_height: BlockHeight,
+    ) -> Option<&ApprovalHistoryEntry> {
+        self.history.iter().rev().find(|entry| entry.target_height == target_height)
+    }
+
     /// Adds new approval to the history.
     fn update_history(&mut self, entry: ApprovalHistoryEntry) {
         while self.history.len() >= MAX_HISTORY_SIZE {
@@ -536,9 +544,14 @@ impl Doomslug {
     }
 
     fn create_approval(&self, target_height: BlockHeight) -> Option<Approval> {
-        self.signer.as_ref().map(|signer| {
-            Approval::new(self.tip.block_hash, self.tip.height, target_height, &**signer)
-        })
+        let signer = self.signer.as_ref()?;
+        match Approval::new(self.tip.block_hash, self.tip.height, target_height, &**signer) {
+            Ok(approval) => Some(approval),
+            Err(err) => {
+                warn!(target: "doomslug", target_height, ?err, "Failed to sign the approval");
+                None
+            }
+        }
     }
 
     /// Determines whether a block has enough approvals to be produced.
@@ -956,7 +969,7 @@ mod tests {
         // "test1", 2 -> 2
         assert_eq!(
             ds.on_approval_message_internal(
-                &Approval::new(hash(&[1]), 1, 2, &signers[0]),
+                &Approval::new(hash(&[1]), 1, 2, &signers[0]).unwrap(),
                 &stakes,
             ),
             DoomslugBlockProductionReadiness::NotReady,
@@ -965,7 +978,7 @@ mod tests {
         // "test3", 4 -> 3
         assert_eq!(
             ds.on_approval_message_internal(
-                &Approval::new(hash(&[1]), 1, 4, &signers[2]),
+                &Approval::new(hash(&[1]), 1, 4, &signers[2]).unwrap(),
                 &stakes,
             ),
             DoomslugBlockProductionReadiness::NotReady,
@@ -974,7 +987,7 @@ mod tests {
         // "test4", 4 -> 4
         assert_eq!(
             ds.on_approval_message_internal(
-                &Approval::new(hash(&[1]), 1, 4, &signers[3]),
+                &Approval::new(hash(&[1]), 1, 4, &signers[3]).unwrap(),
                 &stakes,
             ),
             DoomslugBlockProductionReadiness::NotReady,
@@ -985,7 +998,7 @@ mod tests {
         // "test1", 4 -> same account, still 5
         assert_eq!(
             ds.on_approval_message_internal(
-                &Approval::new(hash(&[1]), 1, 4, &signers[3]),
+                &Approval::new(hash(&[1]), 1, 4, &signers[3]).unwrap(),
                 &stakes,
             ),
             DoomslugBlockProductionReadiness::NotReady,
@@ -994,7 +1007,7 @@ mod tests {
         // "test2", 4 -> 5
         assert_eq!(
             ds.on_approval_message_internal(
-                &Approval::new(hash(&[1]), 1, 4, &signers[1]),
+                &Approval::new(hash(&[1]), 1, 4, &signers[1]).unwrap(),
                 &stakes,
             ),
             DoomslugBlockProductionReadiness::ReadySince(clock.now()),
@@ -1003,7 +1016,7 @@ mod tests {
         // "test1", 4 -> 7
         assert_eq!(
             ds.on_approval_message_internal(
-                &Approval::new(hash(&[1]), 1, 4, &signers[0]),
+                &Approval::new(hash(&[1]), 1, 4, &signers[0]).unwrap(),
                 &stakes,
             ),
             DoomslugBlockProductionReadiness::ReadySince(clock.now()),
@@ -1012,7 +1025,7 @@ mod tests {
         // "test4", 2 -> 3
         assert_eq!(
             ds.on_approval_message_internal(
-                &Approval::new(hash(&[1]), 1, 2, &signers[3]),
+                &Approval::new(hash(&[1]), 1, 2, &signers[3]).unwrap(),
                 &stakes,
             ),
             DoomslugBlockProductionReadiness::NotReady,
@@ -1023,7 +1036,7 @@ mod tests {
         // "test3", 2 -> 6
         assert_eq!(
             ds.on_approval_message_internal(
-                &Approval::new(hash(&[1]), 1, 2, &signers[2]),
+                &Approval::new(hash(&[1]), 1, 2, &signers[2]).unwrap(),
                 &stakes,
             ),
             DoomslugBlockProductionReadiness::ReadySince(clock.now()),
@@ -1032,7 +1045,7 @@ mod tests {
         // A different parent hash
         assert_eq!(
             ds.on_approval_message_internal(
-                &Approval::new(hash(&[2]), 2, 4, &signers[1]),
+                &Approval::new(hash(&[2]), 2, 4, &signers[1]).unwrap(),
                 &stakes,
             ),
             DoomslugBlockProductionReadiness::NotReady,
@@ -1059,13 +1072,13 @@ mod tests {
         let clock = FakeClock::new(Utc::UNIX_EPOCH);
         let mut tracker = DoomslugApprovalsTrackersAtHeight::new(clock.clock());
 
-        let a1_1 = Approval::new(hash(&[1]), 1, 4, &signers[0]);
-        let a1_2 = Approval::new(hash(&[1]), 1, 4, &signers[1]);
-        let a1_3 = Approval::new(hash(&[1]), 1, 4, &signers[2]);
+        let a1_1 = Approval::new(hash(&[1]), 1, 4, &signers[0]).unwrap();
+        let a1_2 = Approval::new(hash(&[1]), 1, 4, &signers[1]).unwrap();
+        let a1_3 = Approval::new(hash(&[1]), 1, 4, &signers[2]).unwrap();
 
-        let a2_1 = Approval::new(hash(&[3]), 3, 4, &signers[0]);
-        let a2_2 = Approval::new(hash(&[3]), 3, 4, &signers[1]);
-        let a2_3 = Approval::new(hash(&[3]), 3, 4, &signers[2]);
+        let a2_1 = Approval::new(hash(&[3]), 3, 4, &signers[0]).unwrap();
+        let a2_2 = Approval::new(hash(&[3]), 3, 4, &signers[1]).unwrap();
+        let a2_3 = Approval::new(hash(&[3]), 3, 4, &signers[2]).unwrap();
 
         // Process first approval, and then process it again and make sure it works
         tracker.process_approval(&a1_1, &stakes, DoomslugThresholdMode::TwoThirds);
@@ -4,12 +4,15 @@ use std::{fmt, io};
 
 use near_chain_configs::GCConfig;
 use near_chain_primitives::Error;
+use near_epoch_manager::shard_tracker::ShardTracker;
 use near_epoch_manager::EpochManagerAdapter;
 use near_primitives::block::Block;
 use near_primitives::hash::CryptoHash;
 use near_primitives::shard_layout::get_block_shard_uid;
 use near_primitives::state_sync::{StateHeaderKey, StatePartKey};
-use near_primitives::types::{BlockHeight, BlockHeightDelta, EpochId, NumBlocks, ShardId};
+use near_primitives::types::{
+    AccountId, BlockHeight, BlockHeightDelta, EpochId, NumBlocks, ShardId,
+};
 use near_primitives::utils::{get_block_shard_id, get_outcome_id_block_hash, index_to_bytes};
 use near_store::flat::store_helper;
 use near_store::{DBCol, KeyForStateChanges, ShardTries, ShardUId};
@@ -110,7 +113,12 @@ impl Chain {
     //    and the Trie is updated with having only Genesis data.
     // 4. State Sync Clearing happens in `reset_data_pre_state_sync()`.
     //
-    pub fn clear_data(&mut self, tries: ShardTries, gc_config: &GCConfig) -> Result<(), Error> {
+    pub fn clear_data(
+        &mut self,
+        tries: ShardTries,
+        gc_config: &GCConfig,
+        me: &Option<AccountId>,
+    ) -> Result<(), Error> {
         let _span = tracing::debug_span!(target: "garbage_collection", "clear_data").entered();
 
         let head = self.chain_store().head()?;
@@ -164,6 +172,7 @@ impl Chain {
                 .collect::<Vec<_>>();
             let epoch_manager = self.epoch_manager.clone();
             let runtime = self.runtime_adapter.clone();
+            let shard_tracker = self.shard_tracker.clone();
             let mut chain_store_update = self.mut_chain_store().store_update();
             if let Some(block_hash) = blocks_current_height.first() {
                 let prev_hash = *chain_store_update.get_block_header(block_hash)?.prev_hash();
@@ -183,6 +192,13 @@ impl Chain {
                         epoch_manager.as_ref(),
                         *block_hash,
                     )?;
+                    chain_store_update.clear_untracked_shards_data(
+                        runtime.as_ref(),
+                        epoch_manager.as_ref(),
+                        &shard_tracker,
+                        me.as_ref(),
+                        *block_hash,
+                    )?;
                     gc_blocks_remaining -= 1;
                 } else {
                     return Err(Error::GCError(
@@ -515,6 +531,94 @@ impl<'a> ChainStoreUpdate<'a> {
         Ok(())
     }
 
+    /// Removes the state of shards which the node stopped tracking, after the tracked shards were
+    /// changed while the node was running.
+    ///
+    /// Like `clear_resharding_data`, this runs at the last block of an epoch which is garbage
+    /// collected. The state of a shard is removed only if the tracked config was updated after
+    /// that epoch, the shard was tracked by the config in that epoch, and the node doesn't care
+    /// about the shard in any of the later epochs, including the next epoch of the head for which
+    /// the state may be caught up. Shards which the node had only because of its validator duties
+    /// are left alone, as without config updates. If the shard layout changed in any of the later
+    /// epochs, the state is removed by `clear_resharding_data` instead.
+    ///
+    /// The in-memory trie of the shard is unloaded, so that it isn't used if the shard is caught
+    /// up again. The state snapshot is a separate checkpoint of the store, which keeps serving the
+    /// state of the shard until the next snapshot, which doesn't include shards without flat
+    /// storage.
+    fn clear_untracked_shards_data(
+        &mut self,
+        runtime: &dyn RuntimeAdapter,
+        epoch_manager: &dyn EpochManagerAdapter,
+        shard_tracker: &ShardTracker,
+        me: Option<&AccountId>,
+        block_hash: CryptoHash,
+    ) -> Result<(), Error> {
+        if !epoch_manager.is_last_block_in_finished_epoch(&block_hash)? {
+            return Ok(());
+        }
+        let header = self.get_block_header(&block_hash)?;
+        let epoch_id = header.epoch_id().clone();
+        if !shard_tracker.is_tracked_config_updated_after(&epoch_id)? {
+            return Ok(());
+        }
+        let shard_layout = epoch_manager.get_shard_layout(&epoch_id)?;
+
+        // Collect the last blocks of all epochs from the one of `block_hash` to the one before
+        // the head, which are the previous blocks of the first blocks of the later epochs.
+        let head = self.head()?;
+        let mut prev_hashes = vec![head.last_block_hash];
+        let mut last_block_hash = head.last_block_hash;
+        while last_block_hash != block_hash {
+            let block_info = epoch_manager.get_block_info(&last_block_hash)?;
+            let first_block_header = self.get_block_header(block_info.epoch_first_block())?;
+            if first_block_header.height() <= header.height() {
+                // The head is on a chain which doesn't contain `block_hash`.
+                return Ok(());
+            }
+            last_block_hash = *first_block_header.prev_hash();
+            prev_hashes.push(last_block_hash);
+        }
+        for prev_hash in &prev_hashes {
+            if epoch_manager.get_shard_layout_from_prev_block(prev_hash)? != shard_layout {
+                return Ok(());
+            }
+        }
+        let next_epoch_id =
+            epoch_manager.get_next_epoch_id_from_prev_block(&head.last_block_hash)?;
+        if epoch_manager.get_shard_layout(&next_epoch_id)? != shard_layout {
+            return Ok(());
+        }
+
+        let mut store_update = self.store().store_update();
+        for shard_uid in shard_layout.shard_uids() {
+            let key = get_block_shard_uid(&block_hash, &shard_uid);
+            if !self.store().exists(DBCol::ChunkExtra, &key)? {
+                continue;
+            }
+            let shard_id = shard_uid.shard_id();
+            if !shard_tracker.tracks_shard_by_config(shard_id, &epoch_id)? {
+                continue;
+            }
+            let tracked = prev_hashes
+                .iter()
+                .any(|prev_hash| shard_tracker.care_about_shard(me, prev_hash, shard_id, true))
+                || shard_tracker.will_care_about_shard(me, &head.last_block_hash, shard_id, true);
+            if tracked {
+                continue;
+            }
+            tracing::info!(target: "garbage_collection", ?block_hash, ?shard_uid, "GC untracked shard");
+            runtime.get_tries().unload_mem_trie(&shard_uid);
+            runtime.get_tries().delete_trie_for_shard(shard_uid, &mut store_update);
+            runtime
+                .get_flat_storage_manager()
+                .remove_flat_storage_for_shard(shard_uid, &mut store_update)?;
+        }
+
+        self.merge(store_update);
+        Ok(())
+    }
+
     // Clearing block data of `block_hash`, if on a fork.
     // Clearing block data of `block_hash.prev`, if on the Canonical Chain.
     pub fn clear_block_data(
@@ -587,6 +691,7 @@ impl<'a> ChainStoreUpdate<'a> {
             self.gc_outgoing_receipts(&block_hash, shard_id);
             self.gc_col(DBCol::IncomingReceipts, &block_shard_id);
             self.gc_col(DBCol::StateTransitionData, &block_shard_id);
+            self.gc_col(DBCol::ChunkExecutionReport, &block_shard_id);
 
             // For incoming State Parts it's done in chain.clear_downloaded_parts()
             // The following code is mostly for outgoing State Parts.
@@ -660,10 +765,12 @@ impl<'a> ChainStoreUpdate<'a> {
     }
 
     // Delete all data in rocksdb that are partially or wholly indexed and can be looked up by hash of the current head of the chain
-    // and that indicates a link between current head and its prev block
+    // and that indicates a link between current head and its prev block.
+    // State inserted by applying the head block is reverted, as if the head block was on a fork.
     pub fn clear_head_block_data(
         &mut self,
         epoch_manager: &dyn EpochManagerAdapter,
+        tries: &ShardTries,
     ) -> Result<(), Error> {
         let header_head = self.header_head().unwrap();
         let header_head_height = header_head.height;
@@ -680,8 +787,16 @@ impl<'a> ChainStoreUpdate<'a> {
         for shard_id in 0..block.header().chunk_mask().len() as ShardId {
             let shard_uid = epoch_manager.shard_id_to_uid(shard_id, epoch_id).unwrap();
             let block_shard_id = get_block_shard_uid(&block_hash, &shard_uid);
+            // Chunk execution reports are keyed by shard id rather than by shard uid.
+            let report_key = get_block_shard_id(&block_hash, shard_id);
 
-            // delete TrieChanges
+            // revert insertions from TrieChanges and delete them
+            let trie_changes = self.store().get_ser(DBCol::TrieChanges, &block_shard_id)?;
+            if let Some(trie_changes) = trie_changes {
+                let mut store_update = self.store().store_update();
+                tries.revert_insertions(&trie_changes, shard_uid, &mut store_update);
+                self.merge(store_update);
+            }
             self.gc_col(DBCol::TrieChanges, &block_shard_id);
 
             // delete Receipts
@@ -689,6 +804,7 @@ impl<'a> ChainStoreUpdate<'a> {
             self.gc_col(DBCol::IncomingReceipts, &block_shard_id);
 
             self.gc_col(DBCol::StateTransitionData, &block_shard_id);
+            self.gc_col(DBCol::ChunkExecutionReport, &report_key);
 
             // delete DBCol::ChunkExtra based on shard_uid since it's indexed by shard_uid in the storage
             self.gc_col(DBCol::ChunkExtra, &block_shard_id);
@@ -725,6 +841,9 @@ impl<'a> ChainStoreUpdate<'a> {
             self.gc_col(DBCol::StateChanges, &key);
         }
         self.gc_col(DBCol::BlockRefCount, block_hash.as_bytes());
+        // Refcount may have been decremented earlier in this update, when the child of the block
+        // was cleared. Drop it, so that it is not written back on commit.
+        self.chain_store_cache_update.block_refcounts.remove(&block_hash);
         self.gc_outcomes(&block)?;
         self.gc_col(DBCol::BlockInfo, block_hash.as_bytes());
         self.gc_col(DBCol::StateDlInfos, block_hash.as_bytes());
@@ -978,12 +1097,17 @@ impl<'a> ChainStoreUpdate<'a> {
             DBCol::StateTransitionData => {
                 store_update.delete(col, key);
             }
+            DBCol::ChunkExecutionReport => {
+                store_update.delete(col, key);
+            }
             DBCol::DbVersion
             | DBCol::BlockMisc
             | DBCol::_GCCount
             | DBCol::BlockHeight  // block sync needs it + genesis should be accessible
             | DBCol::_Peers
             | DBCol::RecentOutboundConnections
+            | DBCol::PeerReputation
+            | DBCol::PeerAdminOverrides
             | DBCol::BlockMerkleTree
             | DBCol::AccountAnnouncements
             | DBCol::EpochLightClientBlocks
@@ -7,7 +7,7 @@ pub use near_chain_primitives::{self, Error};
 pub use near_primitives::receipt::ReceiptResult;
 pub use store::{ChainStore, ChainStoreAccess, ChainStoreUpdate};
 pub use store_validator::{ErrorMessage, StoreValidator};
-pub use types::{Block, BlockHeader, BlockStatus, ChainGenesis, Provenance};
+pub use types::{Block, BlockHeader, BlockStatus, ChainGenesis, ChainReorg, Provenance};
 
 mod block_processing_utils;
 pub mod blocks_delay_tracker;
@@ -27,7 +27,7 @@ pub mod resharding;
 pub mod runtime;
 mod state_request_tracker;
 pub mod state_snapshot_actor;
-mod stateless_validation;
+pub mod stateless_validation;
 mod store;
 pub mod store_validator;
 pub mod test_utils;
@@ -55,6 +55,28 @@ pub static VALIDATOR_ACTIVE_TOTAL: Lazy<IntGauge> = Lazy::new(|| {
 });
 pub static NUM_ORPHANS: Lazy<IntGauge> =
     Lazy::new(|| try_create_int_gauge("near_num_orphans", "Number of orphan blocks.").unwrap());
+pub static CHAIN_REORGS_TOTAL: Lazy<IntCounter> = Lazy::new(|| {
+    try_create_int_counter(
+        "near_chain_reorgs_total",
+        "Number of times the head was replaced by a block which doesn't descend from the previous head",
+    )
+    .unwrap()
+});
+pub static CHAIN_REORGS_FAILED_TOTAL: Lazy<IntCounter> = Lazy::new(|| {
+    try_create_int_counter(
+        "near_chain_reorgs_failed_total",
+        "Number of reorgs whose reverted blocks couldn't be found, so they weren't reported",
+    )
+    .unwrap()
+});
+pub static CHAIN_REORG_DEPTH: Lazy<Histogram> = Lazy::new(|| {
+    try_create_histogram_with_buckets(
+        "near_chain_reorg_depth",
+        "Number of blocks reverted from the canonical chain by a reorg",
+        exponential_buckets(1.0, 2.0, 6).unwrap(),
+    )
+    .unwrap()
+});
 pub static HEADER_HEAD_HEIGHT: Lazy<IntGauge> = Lazy::new(|| {
     try_create_int_gauge("near_header_head_height", "Height of the header head").unwrap()
 });
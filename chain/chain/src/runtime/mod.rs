@@ -75,6 +75,8 @@ pub struct NightshadeRuntime {
     epoch_manager: Arc<EpochManagerHandle>,
     migration_data: Arc<MigrationData>,
     gc_num_epochs_to_keep: u64,
+    /// Whether to collect per-receipt execution reports when applying chunks.
+    record_execution_reports: bool,
 }
 
 impl NightshadeRuntime {
@@ -88,6 +90,7 @@ impl NightshadeRuntime {
         gc_num_epochs_to_keep: u64,
         trie_config: TrieConfig,
         state_snapshot_config: StateSnapshotConfig,
+        record_execution_reports: bool,
     ) -> Arc<Self> {
         let runtime_config_store = match runtime_config_store {
             Some(store) => store,
@@ -125,6 +128,7 @@ impl NightshadeRuntime {
             epoch_manager,
             migration_data,
             gc_num_epochs_to_keep: gc_num_epochs_to_keep.max(MIN_GC_NUM_EPOCHS_TO_KEEP),
+            record_execution_reports,
         })
     }
 
@@ -152,6 +156,7 @@ impl NightshadeRuntime {
                 state_snapshot_subdir: PathBuf::from("state_snapshot"),
                 compaction_enabled: false,
             },
+            false,
         )
     }
 
@@ -179,6 +184,7 @@ impl NightshadeRuntime {
                 state_snapshot_subdir: PathBuf::from("state_snapshot"),
                 compaction_enabled: false,
             },
+            false,
         )
     }
 
@@ -365,6 +371,7 @@ impl NightshadeRuntime {
                 is_first_block_of_version,
                 is_first_block_with_chunk_of_version,
             },
+            record_execution_report: self.record_execution_reports,
         };
 
         let instant = Instant::now();
@@ -439,6 +446,7 @@ impl NightshadeRuntime {
             proof: apply_result.proof,
             processed_delayed_receipts: apply_result.processed_delayed_receipts,
             applied_receipts_hash: hash(&borsh::to_vec(receipts).unwrap()),
+            execution_report: apply_result.execution_report,
         };
 
         Ok(result)
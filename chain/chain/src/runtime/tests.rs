@@ -215,6 +215,7 @@ impl TestEnv {
                 state_snapshot_subdir: PathBuf::from("state_snapshot"),
                 compaction_enabled: false,
             },
+            false,
         );
         let state_roots = get_genesis_state_roots(&store).unwrap().unwrap();
         let genesis_hash = hash(&[0]);
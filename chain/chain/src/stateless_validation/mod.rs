@@ -1,2 +1,3 @@
 pub(crate) mod chunk_endorsement;
+pub mod saved_witnesses;
 pub(crate) mod state_transition_data;
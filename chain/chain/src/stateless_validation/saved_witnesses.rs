@@ -0,0 +1,164 @@
+use std::sync::Mutex;
+
+use near_chain_primitives::error::Error;
+use near_primitives::stateless_validation::{ChunkStateWitness, SavedChunkStateWitness};
+use near_primitives::types::{BlockHeight, ShardId};
+use near_store::db::{INVALID_CHUNK_STATE_WITNESSES_KEY, LATEST_CHUNK_STATE_WITNESSES_KEY};
+use near_store::{DBCol, Store};
+
+/// Maximum number of invalid witnesses kept in the database. The oldest ones are
+/// removed when a new invalid witness is saved.
+pub const MAX_SAVED_INVALID_WITNESSES: usize = 100;
+
+/// Serializes updates of the saved witnesses, as witnesses are validated on
+/// multiple threads and each update modifies the list of saved keys.
+static SAVE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Chunk state witnesses saved for debugging are stored in two separate columns,
+/// each bounded to a number of the most recent witnesses.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SavedWitnessKind {
+    /// Witnesses which failed validation.
+    Invalid,
+    /// The most recent witnesses, whether valid or not.
+    Latest,
+}
+
+impl SavedWitnessKind {
+    fn col(self) -> DBCol {
+        match self {
+            SavedWitnessKind::Invalid => DBCol::InvalidChunkStateWitnesses,
+            SavedWitnessKind::Latest => DBCol::LatestChunkStateWitnesses,
+        }
+    }
+
+    fn keys_key(self) -> &'static [u8] {
+        match self {
+            SavedWitnessKind::Invalid => INVALID_CHUNK_STATE_WITNESSES_KEY,
+            SavedWitnessKind::Latest => LATEST_CHUNK_STATE_WITNESSES_KEY,
+        }
+    }
+}
+
+/// Key of a saved witness: BlockHeight || ShardId || ChunkHash, with big-endian integers
+/// so that the witnesses are ordered by height in the column.
+fn saved_witness_key(witness: &ChunkStateWitness) -> Vec<u8> {
+    let chunk_header = &witness.inner.chunk_header;
+    let mut key = Vec::with_capacity(48);
+    key.extend_from_slice(&chunk_header.height_created().to_be_bytes());
+    key.extend_from_slice(&chunk_header.shard_id().to_be_bytes());
+    key.extend_from_slice(chunk_header.chunk_hash().as_bytes());
+    key
+}
+
+fn parse_saved_witness_key(key: &[u8]) -> Option<(BlockHeight, ShardId)> {
+    let height = BlockHeight::from_be_bytes(key.get(0..8)?.try_into().ok()?);
+    let shard_id = ShardId::from_be_bytes(key.get(8..16)?.try_into().ok()?);
+    Some((height, shard_id))
+}
+
+/// Saves the witness together with the result of its validation, removing the oldest
+/// saved witnesses of the same kind so that at most `limit` of them are kept.
+pub fn save_chunk_state_witness(
+    store: &Store,
+    kind: SavedWitnessKind,
+    witness: &ChunkStateWitness,
+    validation_error: Option<String>,
+    limit: usize,
+) -> Result<(), Error> {
+    if limit == 0 {
+        return Ok(());
+    }
+    let _lock = SAVE_LOCK.lock().unwrap();
+    let col = kind.col();
+    let key = saved_witness_key(witness);
+    let mut keys: Vec<Vec<u8>> = store.get_ser(DBCol::Misc, kind.keys_key())?.unwrap_or_default();
+    let mut store_update = store.store_update();
+    if !keys.contains(&key) {
+        keys.push(key.clone());
+    }
+    while keys.len() > limit {
+        store_update.delete(col, &keys.remove(0));
+    }
+    store_update.set_ser(
+        col,
+        &key,
+        &SavedChunkStateWitness { witness: witness.clone(), validation_error },
+    )?;
+    store_update.set_ser(DBCol::Misc, kind.keys_key(), &keys)?;
+    store_update.commit()?;
+    Ok(())
+}
+
+/// Returns the saved witnesses of the given kind, from the oldest to the newest,
+/// optionally filtered by the height at which the chunk was created and by shard.
+pub fn get_saved_chunk_state_witnesses(
+    store: &Store,
+    kind: SavedWitnessKind,
+    height: Option<BlockHeight>,
+    shard_id: Option<ShardId>,
+) -> Result<Vec<SavedChunkStateWitness>, Error> {
+    let keys: Vec<Vec<u8>> = store.get_ser(DBCol::Misc, kind.keys_key())?.unwrap_or_default();
+    let mut witnesses = vec![];
+    for key in keys {
+        let Some((key_height, key_shard_id)) = parse_saved_witness_key(&key) else {
+            continue;
+        };
+        if height.is_some_and(|height| height != key_height)
+            || shard_id.is_some_and(|shard_id| shard_id != key_shard_id)
+        {
+            continue;
+        }
+        if let Some(witness) = store.get_ser(kind.col(), &key)? {
+            witnesses.push(witness);
+        }
+    }
+    Ok(witnesses)
+}
+
+#[cfg(test)]
+mod tests {
+    use near_primitives::hash::hash;
+    use near_primitives::stateless_validation::ChunkStateWitness;
+    use near_store::test_utils::create_test_store;
+    use near_store::DBCol;
+
+    use super::{get_saved_chunk_state_witnesses, save_chunk_state_witness, SavedWitnessKind};
+
+    #[test]
+    fn test_saved_witnesses_are_bounded() {
+        let store = create_test_store();
+        for height in 1..=5 {
+            let witness = ChunkStateWitness::new_dummy(height, 0, hash(b"prev"));
+            save_chunk_state_witness(
+                &store,
+                SavedWitnessKind::Invalid,
+                &witness,
+                Some(format!("error at {height}")),
+                3,
+            )
+            .unwrap();
+        }
+        let saved =
+            get_saved_chunk_state_witnesses(&store, SavedWitnessKind::Invalid, None, None).unwrap();
+        let heights = saved
+            .iter()
+            .map(|saved| saved.witness.inner.chunk_header.height_created())
+            .collect::<Vec<_>>();
+        assert_eq!(heights, vec![3, 4, 5]);
+        assert_eq!(saved[0].validation_error.as_deref(), Some("error at 3"));
+        assert_eq!(store.iter(DBCol::InvalidChunkStateWitnesses).count(), 3);
+        assert!(get_saved_chunk_state_witnesses(&store, SavedWitnessKind::Latest, None, None)
+            .unwrap()
+            .is_empty());
+
+        let saved =
+            get_saved_chunk_state_witnesses(&store, SavedWitnessKind::Invalid, Some(4), Some(0))
+                .unwrap();
+        assert_eq!(saved.len(), 1);
+        assert_eq!(saved[0].witness.inner.chunk_header.height_created(), 4);
+        assert!(get_saved_chunk_state_witnesses(&store, SavedWitnessKind::Invalid, None, Some(1))
+            .unwrap()
+            .is_empty());
+    }
+}
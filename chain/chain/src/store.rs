@@ -13,6 +13,7 @@ use near_primitives::checked_feature;
 #[cfg(feature = "new_epoch_sync")]
 use near_primitives::epoch_manager::epoch_sync::EpochSyncInfo;
 use near_primitives::errors::InvalidTxError;
+use near_primitives::execution_report::ChunkExecutionReport;
 use near_primitives::hash::CryptoHash;
 use near_primitives::merkle::{MerklePath, PartialMerkleTree};
 use near_primitives::receipt::Receipt;
@@ -1419,7 +1420,7 @@ pub(crate) struct ChainStoreCacheUpdate {
     receipt_id_to_shard_id: HashMap<CryptoHash, ShardId>,
     transactions: HashMap<CryptoHash, Arc<SignedTransaction>>,
     receipts: HashMap<CryptoHash, Arc<Receipt>>,
-    block_refcounts: HashMap<CryptoHash, u64>,
+    pub(crate) block_refcounts: HashMap<CryptoHash, u64>,
     block_merkle_tree: HashMap<CryptoHash, Arc<PartialMerkleTree>>,
     block_ordinal_to_hash: HashMap<NumBlocks, CryptoHash>,
     processed_block_heights: HashSet<BlockHeight>,
@@ -1441,6 +1442,7 @@ pub struct ChainStoreUpdate<'a> {
     largest_target_height: Option<BlockHeight>,
     trie_changes: Vec<WrappedTrieChanges>,
     state_transition_data: HashMap<(CryptoHash, ShardId), StoredChunkStateTransitionData>,
+    chunk_execution_reports: HashMap<(CryptoHash, ShardId), ChunkExecutionReport>,
     // All state changes made by a chunk, this is only used for resharding.
     add_state_changes_for_resharding: HashMap<(CryptoHash, ShardId), StateChangesForResharding>,
     remove_all_state_changes_for_resharding: bool,
@@ -1469,6 +1471,7 @@ impl<'a> ChainStoreUpdate<'a> {
             largest_target_height: None,
             trie_changes: vec![],
             state_transition_data: Default::default(),
+            chunk_execution_reports: Default::default(),
             add_state_changes_for_resharding: HashMap::new(),
             remove_all_state_changes_for_resharding: false,
             add_blocks_to_catchup: vec![],
@@ -2097,6 +2100,15 @@ impl<'a> ChainStoreUpdate<'a> {
         }
     }
 
+    pub fn save_chunk_execution_report(
+        &mut self,
+        block_hash: CryptoHash,
+        shard_id: ShardId,
+        report: ChunkExecutionReport,
+    ) {
+        self.chunk_execution_reports.insert((block_hash, shard_id), report);
+    }
+
     pub fn add_state_changes_for_resharding(
         &mut self,
         block_hash: CryptoHash,
@@ -2546,6 +2558,13 @@ impl<'a> ChainStoreUpdate<'a> {
                 &state_transition_data,
             )?;
         }
+        for ((block_hash, shard_id), report) in self.chunk_execution_reports.drain() {
+            store_update.set_ser(
+                DBCol::ChunkExecutionReport,
+                &get_block_shard_id(&block_hash, shard_id),
+                &report,
+            )?;
+        }
         for ((block_hash, shard_id), state_changes) in self.add_state_changes_for_resharding.drain()
         {
             store_update.set_ser(
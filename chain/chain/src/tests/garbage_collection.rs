@@ -191,7 +191,11 @@ fn gc_fork_common(simple_chains: Vec<SimpleChain>, max_changes: usize) {
 
     // GC execution
     chain1
-        .clear_data(tries1.clone(), &GCConfig { gc_blocks_limit: 1000, ..GCConfig::default() })
+        .clear_data(
+            tries1.clone(),
+            &GCConfig { gc_blocks_limit: 1000, ..GCConfig::default() },
+            &None,
+        )
         .unwrap();
 
     let tries2 = get_chain_with_num_shards(Clock::real(), num_shards).runtime_adapter.get_tries();
@@ -643,6 +647,7 @@ fn test_fork_far_away_from_epoch_end() {
                 gc_fork_clean_step: fork_clean_step,
                 ..GCConfig::default()
             },
+            &None,
         )
         .expect("Clear data failed");
 
@@ -685,7 +690,7 @@ fn test_fork_far_away_from_epoch_end() {
         );
     }
     chain
-        .clear_data(tries, &GCConfig { gc_blocks_limit: 100, ..GCConfig::default() })
+        .clear_data(tries, &GCConfig { gc_blocks_limit: 100, ..GCConfig::default() }, &None)
         .expect("Clear data failed");
     // And now all these blocks should be safely removed.
     for i in 6..50 {
@@ -723,7 +728,9 @@ fn test_clear_old_data() {
     }
 
     let trie = chain.runtime_adapter.get_tries();
-    chain.clear_data(trie, &GCConfig { gc_blocks_limit: 100, ..GCConfig::default() }).unwrap();
+    chain
+        .clear_data(trie, &GCConfig { gc_blocks_limit: 100, ..GCConfig::default() }, &None)
+        .unwrap();
 
     for i in 0..=max_height {
         println!("height = {} hash = {}", i, blocks[i].hash());
@@ -910,7 +917,7 @@ fn test_clear_old_data_too_many_heights_common(gc_blocks_limit: NumBlocks) {
     for iter in 0..10 {
         println!("ITERATION #{:?}", iter);
         assert!(chain
-            .clear_data(trie.clone(), &GCConfig { gc_blocks_limit, ..GCConfig::default() })
+            .clear_data(trie.clone(), &GCConfig { gc_blocks_limit, ..GCConfig::default() }, &None)
             .is_ok());
 
         // epoch didn't change so no data is garbage collected.
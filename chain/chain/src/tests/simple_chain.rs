@@ -1,6 +1,6 @@
 use crate::near_chain_primitives::error::BlockKnownError;
-use crate::test_utils::{setup, wait_for_all_blocks_in_processing};
-use crate::{Block, BlockProcessingArtifact, ChainStoreAccess, Error};
+use crate::test_utils::{process_block_sync, setup, wait_for_all_blocks_in_processing};
+use crate::{Block, BlockProcessingArtifact, ChainStoreAccess, Error, Provenance};
 use assert_matches::assert_matches;
 use near_async::time::{Clock, Duration, FakeClock, Utc};
 use near_o11y::testonly::init_test_logger;
@@ -89,7 +89,8 @@ fn build_chain_with_orphans() {
         *last_block.header().next_bp_hash(),
         CryptoHash::default(),
         Utc::UNIX_EPOCH,
-    );
+    )
+    .unwrap();
     assert_matches!(chain.process_block_test(&None, block).unwrap_err(), Error::Orphan);
     assert_matches!(
         chain.process_block_test(&None, blocks.pop().unwrap()).unwrap_err(),
@@ -229,6 +230,59 @@ fn blocks_at_height() {
     assert_eq!(chain.get_block_header_by_height(7).unwrap().hash(), &e_7_hash);
 }
 
+/// Verifies that the reorgs are reported with the blocks reverted and applied by them.
+/// Chain looks as follows (variable name + height):
+///
+/// 0 -> b1 -> b2 -> d3 -> d5
+///  \
+///   -> c1 -> c3
+#[test]
+fn chain_reorgs() {
+    init_test_logger();
+    let (mut chain, _, _, signer) = setup(Clock::real());
+    let genesis = chain.get_block_by_height(0).unwrap();
+    let b_1 = TestBlockBuilder::new(Clock::real(), &genesis, signer.clone()).height(1).build();
+    let b_2 = TestBlockBuilder::new(Clock::real(), &b_1, signer.clone()).height(2).build();
+    let c_1 = TestBlockBuilder::new(Clock::real(), &genesis, signer.clone()).height(1).build();
+    let c_3 = TestBlockBuilder::new(Clock::real(), &c_1, signer.clone()).height(3).build();
+    let d_3 = TestBlockBuilder::new(Clock::real(), &b_2, signer.clone()).height(3).build();
+    let d_5 = TestBlockBuilder::new(Clock::real(), &d_3, signer).height(5).build();
+    let [b_1_hash, b_2_hash, c_1_hash, c_3_hash, d_3_hash, d_5_hash] =
+        [&b_1, &b_2, &c_1, &c_3, &d_3, &d_5].map(|block| *block.hash());
+
+    let mut process_block = |block: Block| {
+        let mut block_processing_artifacts = BlockProcessingArtifact::default();
+        process_block_sync(
+            &mut chain,
+            &None,
+            block.into(),
+            Provenance::PRODUCED,
+            &mut block_processing_artifacts,
+        )
+        .unwrap();
+        block_processing_artifacts.reorgs
+    };
+    assert!(process_block(b_1).is_empty());
+    assert!(process_block(b_2).is_empty());
+    // Forks which don't replace the head aren't reorgs.
+    assert!(process_block(c_1).is_empty());
+
+    let reorgs = process_block(c_3);
+    assert_eq!(reorgs.len(), 1);
+    assert_eq!(reorgs[0].old_head.last_block_hash, b_2_hash);
+    assert_eq!(reorgs[0].new_head.last_block_hash, c_3_hash);
+    assert_eq!(reorgs[0].reverted_blocks, vec![b_2_hash, b_1_hash]);
+    assert_eq!(reorgs[0].applied_blocks, vec![c_1_hash, c_3_hash]);
+
+    assert!(process_block(d_3).is_empty());
+    let reorgs = process_block(d_5);
+    assert_eq!(reorgs.len(), 1);
+    assert_eq!(reorgs[0].old_head.last_block_hash, c_3_hash);
+    assert_eq!(reorgs[0].new_head.last_block_hash, d_5_hash);
+    assert_eq!(reorgs[0].reverted_blocks, vec![c_3_hash, c_1_hash]);
+    assert_eq!(reorgs[0].applied_blocks, vec![b_1_hash, b_2_hash, d_3_hash, d_5_hash]);
+}
+
 #[test]
 fn next_blocks() {
     init_test_logger();
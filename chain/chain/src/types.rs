@@ -11,6 +11,7 @@ pub use near_primitives::block::{Block, BlockHeader, Tip};
 use near_primitives::challenge::{ChallengesResult, PartialState};
 use near_primitives::checked_feature;
 use near_primitives::errors::InvalidTxError;
+use near_primitives::execution_report::ChunkExecutionReport;
 use near_primitives::hash::CryptoHash;
 use near_primitives::merkle::{merklize, MerklePath};
 use near_primitives::receipt::Receipt;
@@ -76,6 +77,20 @@ pub struct AcceptedBlock {
     pub provenance: Provenance,
 }
 
+/// Replacement of the chain head by a block which doesn't descend from the previous head.
+/// Only non-final blocks can be reverted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChainReorg {
+    pub old_head: Tip,
+    pub new_head: Tip,
+    /// Blocks which are no longer on the canonical chain, from the old head down to
+    /// the common ancestor of both heads (exclusive).
+    pub reverted_blocks: Vec<CryptoHash>,
+    /// Blocks which became canonical, from the common ancestor of both heads (exclusive)
+    /// up to the new head.
+    pub applied_blocks: Vec<CryptoHash>,
+}
+
 #[derive(Debug)]
 pub struct ApplyResultForResharding {
     pub shard_uid: ShardUId,
@@ -113,6 +128,9 @@ pub struct ApplyChunkResult {
     /// Note that applied receipts are not necessarily executed as they can
     /// be delayed.
     pub applied_receipts_hash: CryptoHash,
+    /// Per-receipt execution statistics, only collected when the runtime is
+    /// configured to record execution reports.
+    pub execution_report: Option<ChunkExecutionReport>,
 }
 
 impl ApplyChunkResult {
@@ -558,7 +576,7 @@ mod tests {
         assert!(b1.header().verify_block_producer(&signer.public_key()));
         let other_signer = create_test_signer("other2");
         let approvals =
-            vec![Some(Box::new(Approval::new(*b1.hash(), 1, 2, &other_signer).signature))];
+            vec![Some(Box::new(Approval::new(*b1.hash(), 1, 2, &other_signer).unwrap().signature))];
         let b2 =
             TestBlockBuilder::new(Clock::real(), &b1, signer.clone()).approvals(approvals).build();
         b2.header().verify_block_producer(&signer.public_key());
@@ -2,6 +2,7 @@ use std::collections::HashMap;
 
 use actix::Message;
 
+use near_chain_configs::TransactionSelectionStrategy;
 use near_pool::types::TransactionGroupIterator;
 use near_pool::{InsertTransactionResult, PoolIteratorWrapper, TransactionPool};
 use near_primitives::shard_layout::{account_id_to_shard_uid, ShardLayout, ShardUId};
@@ -42,15 +43,25 @@ pub struct ShardedTransactionPool {
     /// If set, new transactions that bring the size of the pool over this limit will be rejected.
     /// The size is tracked and enforced separately for each shard.
     pool_size_limit: Option<u64>,
+
+    /// Order in which transactions are taken from the pool of a shard.
+    selection_strategy: TransactionSelectionStrategy,
 }
 
 impl ShardedTransactionPool {
-    pub fn new(rng_seed: RngSeed, pool_size_limit: Option<u64>) -> Self {
-        Self { tx_pools: HashMap::new(), rng_seed, pool_size_limit }
+    pub fn new(
+        rng_seed: RngSeed,
+        pool_size_limit: Option<u64>,
+        selection_strategy: TransactionSelectionStrategy,
+    ) -> Self {
+        Self { tx_pools: HashMap::new(), rng_seed, pool_size_limit, selection_strategy }
     }
 
     pub fn get_pool_iterator(&mut self, shard_uid: ShardUId) -> Option<PoolIteratorWrapper<'_>> {
-        self.tx_pools.get_mut(&shard_uid).map(|pool| pool.pool_iterator())
+        let selection_strategy = self.selection_strategy;
+        self.tx_pools
+            .get_mut(&shard_uid)
+            .map(|pool| pool.pool_iterator_with_strategy(selection_strategy))
     }
 
     /// Tries to insert the transaction into the pool for a given shard.
@@ -144,6 +155,7 @@ impl ShardedTransactionPool {
 #[cfg(test)]
 mod tests {
     use crate::client::ShardedTransactionPool;
+    use near_chain_configs::TransactionSelectionStrategy;
     use near_crypto::{InMemorySigner, KeyType};
     use near_o11y::testonly::init_test_logger;
     use near_pool::types::TransactionGroupIterator;
@@ -185,7 +197,8 @@ mod tests {
         let old_shard_layout = ShardLayout::get_simple_nightshade_layout();
         let new_shard_layout = ShardLayout::get_simple_nightshade_layout_v2();
 
-        let mut pool = ShardedTransactionPool::new(TEST_SEED, None);
+        let mut pool =
+            ShardedTransactionPool::new(TEST_SEED, None, TransactionSelectionStrategy::default());
 
         let mut shard_id_to_accounts = HashMap::new();
         shard_id_to_accounts.insert(0, vec!["aaa", "abcd", "a-a-a-a-a"]);
@@ -10,7 +10,8 @@ use near_async::{
 use near_chain::chunks_store::ReadOnlyChunksStore;
 use near_epoch_manager::EpochManagerAdapter;
 use near_network::{
-    shards_manager::ShardsManagerRequestFromNetwork, test_loop::SupportsRoutingLookup,
+    shards_manager::ShardsManagerRequestFromNetwork,
+    test_loop::{LinkConfig, SimulatedNetwork, SupportsRoutingLookup},
     types::PeerManagerMessageRequest,
 };
 use near_primitives::{
@@ -104,12 +105,13 @@ fn basic_setup(config: BasicSetupConfig) -> ShardsManagerTestLoop {
         })
         .collect::<Vec<_>>();
     let mut test = builder.build(data);
+    let network = SimulatedNetwork::new(0, LinkConfig::with_latency(NETWORK_DELAY));
     for idx in 0..test.data.len() {
         test.register_handler(handle_adhoc_events::<TestData>().widen().for_index(idx));
         test.register_handler(forward_client_request_to_shards_manager().widen().for_index(idx));
         test.register_handler(forward_network_request_to_shards_manager().widen().for_index(idx));
         test.register_handler(capture_events::<ShardsManagerResponse>().widen().for_index(idx));
-        test.register_handler(route_shards_manager_network_messages(NETWORK_DELAY));
+        test.register_handler(route_shards_manager_network_messages(network.clone()));
         test.register_handler(
             periodically_resend_chunk_requests(CHUNK_REQUEST_RETRY).widen().for_index(idx),
         );
@@ -13,7 +13,7 @@ use near_epoch_manager::{
 };
 use near_network::{
     shards_manager::ShardsManagerRequestFromNetwork,
-    test_loop::SupportsRoutingLookup,
+    test_loop::{SimulatedNetwork, SupportsRoutingLookup},
     types::{NetworkRequests, PeerManagerMessageRequest},
 };
 use near_primitives::{
@@ -51,7 +51,7 @@ pub fn forward_network_request_to_shards_manager(
 }
 
 /// Routes network messages that are issued by ShardsManager to other instances
-/// in a multi-instance test.
+/// in a multi-instance test, over the simulated network.
 ///
 /// TODO: This logic should ideally not be duplicated from the real
 /// PeerManagerActor and PeerActor.
@@ -61,7 +61,7 @@ pub fn route_shards_manager_network_messages<
         + From<PeerManagerMessageRequest>
         + From<ShardsManagerRequestFromNetwork>,
 >(
-    network_delay: time::Duration,
+    network: SimulatedNetwork,
 ) -> LoopEventHandler<Data, (usize, Event)> {
     let mut route_back_lookup: HashMap<CryptoHash, usize> = HashMap::new();
     let mut next_hash: u64 = 0;
@@ -72,70 +72,61 @@ pub fn route_shards_manager_network_messages<
             let (idx, event) = event;
             let message = event.try_into_or_self().map_err(|e| (idx, e.into()))?;
             match message {
-                PeerManagerMessageRequest::NetworkRequests(request) => {
-                    match request {
-                        NetworkRequests::PartialEncodedChunkRequest { target, request, .. } => {
-                            let target_idx = data.index_for_account(&target.account_id.unwrap());
-                            let route_back = CryptoHash::hash_borsh(next_hash);
-                            route_back_lookup.insert(route_back, idx);
-                            next_hash += 1;
-                            context.sender.send_with_delay(
-                            (target_idx,
+                PeerManagerMessageRequest::NetworkRequests(request) => match request {
+                    NetworkRequests::PartialEncodedChunkRequest { target, request, .. } => {
+                        let target_idx = data.index_for_account(&target.account_id.unwrap());
+                        let route_back = CryptoHash::hash_borsh(next_hash);
+                        route_back_lookup.insert(route_back, idx);
+                        next_hash += 1;
+                        network.send(context, idx, target_idx, request, |request| {
                             ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkRequest {
                                 partial_encoded_chunk_request: request,
                                 route_back,
-                            }.into()),
-                            network_delay,
-                        );
-                            Ok(())
-                        }
-                        NetworkRequests::PartialEncodedChunkResponse { route_back, response } => {
-                            let target_idx =
-                                *route_back_lookup.get(&route_back).expect("Route back not found");
-                            context.sender.send_with_delay(
-                            (target_idx,
+                            }
+                            .into()
+                        });
+                        Ok(())
+                    }
+                    NetworkRequests::PartialEncodedChunkResponse { route_back, response } => {
+                        let target_idx =
+                            *route_back_lookup.get(&route_back).expect("Route back not found");
+                        let received_time = context.clock.now();
+                        network.send(context, idx, target_idx, response, |response| {
                             ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkResponse {
                                 partial_encoded_chunk_response: response,
-                                received_time: context.clock.now().into(), // TODO: use clock
-                            }.into()),
-                            network_delay,
-                        );
-                            Ok(())
-                        }
-                        NetworkRequests::PartialEncodedChunkMessage {
-                            account_id,
-                            partial_encoded_chunk,
-                        } => {
-                            let target_idx = data.index_for_account(&account_id);
-                            context.sender.send_with_delay(
-                                (
-                                    target_idx,
-                                    ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk(
-                                        partial_encoded_chunk.into(),
-                                    )
-                                    .into(),
-                                ),
-                                network_delay,
-                            );
-                            Ok(())
-                        }
-                        NetworkRequests::PartialEncodedChunkForward { account_id, forward } => {
-                            let target_idx = data.index_for_account(&account_id);
-                            context.sender.send_with_delay(
-                            (target_idx,
+                                received_time,
+                            }
+                            .into()
+                        });
+                        Ok(())
+                    }
+                    NetworkRequests::PartialEncodedChunkMessage {
+                        account_id,
+                        partial_encoded_chunk,
+                    } => {
+                        let target_idx = data.index_for_account(&account_id);
+                        let partial_encoded_chunk: PartialEncodedChunk =
+                            partial_encoded_chunk.into();
+                        network.send(context, idx, target_idx, partial_encoded_chunk, |chunk| {
+                            ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunk(chunk)
+                                .into()
+                        });
+                        Ok(())
+                    }
+                    NetworkRequests::PartialEncodedChunkForward { account_id, forward } => {
+                        let target_idx = data.index_for_account(&account_id);
+                        network.send(context, idx, target_idx, forward, |forward| {
                             ShardsManagerRequestFromNetwork::ProcessPartialEncodedChunkForward(
                                 forward,
-                            ).into()),
-                            network_delay,
-                        );
-                            Ok(())
-                        }
-                        other_message => Err((
-                            idx,
-                            PeerManagerMessageRequest::NetworkRequests(other_message).into(),
-                        )),
+                            )
+                            .into()
+                        });
+                        Ok(())
+                    }
+                    other_message => {
+                        Err((idx, PeerManagerMessageRequest::NetworkRequests(other_message).into()))
                     }
-                }
+                },
                 message => Err((idx, message.into())),
             }
         },
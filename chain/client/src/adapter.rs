@@ -48,8 +48,14 @@ pub fn client_sender_for_network(
         transaction: client_addr.clone().into_sender(),
         tx_status_request: view_client_addr.clone().into_sender(),
         tx_status_response: view_client_addr.clone().into_sender(),
-        announce_account: view_client_addr.into_sender(),
+        announce_account: view_client_addr.clone().into_sender(),
+        light_client_block_request: view_client_addr.clone().into_sender(),
+        epoch_block_producers_request: view_client_addr.clone().into_sender(),
+        epoch_sync_request: view_client_addr.into_sender(),
+        epoch_sync_response: client_addr.clone().into_sender(),
         chunk_state_witness: client_addr.clone().into_sender(),
-        chunk_endorsement: client_addr.into_sender(),
+        chunk_endorsement: client_addr.clone().into_sender(),
+        partial_encoded_state_witness: client_addr.clone().into_sender(),
+        partial_encoded_state_witness_forward: client_addr.into_sender(),
     }
 }
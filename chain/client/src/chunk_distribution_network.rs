@@ -411,7 +411,7 @@ mod tests {
             tx_root: mock_hashes.next().unwrap(),
             prev_validator_proposals: Vec::new(),
         });
-        let header = ShardChunkHeaderV3::from_inner(header_inner, &signer);
+        let header = ShardChunkHeaderV3::from_inner(header_inner, &signer).unwrap();
         PartialEncodedChunk::V2(PartialEncodedChunkV2 {
             header: ShardChunkHeader::V3(header),
             parts: Vec::new(),
@@ -12,6 +12,7 @@ use crate::sync::block::BlockSync;
 use crate::sync::epoch::EpochSync;
 use crate::sync::header::HeaderSync;
 use crate::sync::state::{StateSync, StateSyncResult};
+use crate::validator_performance::ValidatorPerformanceTracker;
 use crate::SyncAdapter;
 use crate::SyncMessage;
 use crate::{metrics, SyncStatus};
@@ -35,7 +36,7 @@ use near_chain::types::{
     StorageDataSource,
 };
 use near_chain::{
-    BlockProcessingArtifact, BlockStatus, Chain, ChainGenesis, ChainStoreAccess,
+    BlockProcessingArtifact, BlockStatus, Chain, ChainGenesis, ChainReorg, ChainStoreAccess,
     DoneApplyChunkCallback, Doomslug, DoomslugThresholdMode, Provenance,
 };
 use near_chain_configs::{ClientConfig, LogSummaryStyle, UpdateableClientConfig};
@@ -49,7 +50,7 @@ use near_client_primitives::debug::ChunkProduction;
 use near_client_primitives::types::{
     format_shard_sync_phase_per_shard, Error, ShardSyncDownload, ShardSyncStatus,
 };
-use near_epoch_manager::shard_tracker::ShardTracker;
+use near_epoch_manager::shard_tracker::{ShardTracker, TrackedConfig};
 use near_epoch_manager::EpochManagerAdapter;
 use near_network::client::ProcessTxResponse;
 use near_network::types::{AccountKeys, ChainInfo, PeerManagerMessageRequest, SetChainInfo};
@@ -78,7 +79,7 @@ use near_primitives::types::chunk_extra::ChunkExtra;
 use near_primitives::types::{AccountId, ApprovalStake, BlockHeight, EpochId, NumBlocks, ShardId};
 use near_primitives::unwrap_or_return;
 use near_primitives::utils::MaybeValidated;
-use near_primitives::validator_signer::ValidatorSigner;
+use near_primitives::validator_signer::{signer_for_epoch, ValidatorSigner};
 use near_primitives::version::PROTOCOL_VERSION;
 use near_primitives::views::{CatchupStatusView, DroppedReason};
 use near_store::metadata::DbKind;
@@ -90,13 +91,12 @@ use std::sync::RwLock;
 use tracing::{debug, debug_span, error, info, trace, warn};
 
 const NUM_REBROADCAST_BLOCKS: usize = 30;
+/// Number of chain reorgs buffered for each subscriber which hasn't received them yet.
+const CHAIN_REORGS_CHANNEL_CAPACITY: usize = 128;
 
 /// The time we wait for the response to a Epoch Sync request before retrying
 // TODO #3488 set 30_000
 pub const EPOCH_SYNC_REQUEST_TIMEOUT: Duration = Duration::milliseconds(1_000);
-/// How frequently a Epoch Sync response can be sent to a particular peer
-// TODO #3488 set 60_000
-pub const EPOCH_SYNC_PEER_TIMEOUT: Duration = Duration::milliseconds(10);
 /// Drop blocks whose height are beyond head + horizon if it is not in the current epoch.
 const BLOCK_HORIZON: u64 = 500;
 
@@ -171,6 +171,8 @@ pub struct Client {
     pub block_production_info: BlockProductionTracker,
     /// Chunk production timing information. Used only for debug purposes.
     pub chunk_production_info: lru::LruCache<(BlockHeight, ShardId), ChunkProduction>,
+    /// Duties of this node as a validator and their outcomes. Used only for debug purposes.
+    pub validator_performance: Arc<ValidatorPerformanceTracker>,
 
     /// Cached precomputed set of TIER1 accounts.
     /// See send_network_chain_info().
@@ -192,6 +194,10 @@ pub struct Client {
 
     // Optional value used for the Chunk Distribution Network Feature.
     chunk_distribution_network: Option<ChunkDistributionNetwork>,
+    /// Broadcasts chain reorgs to the subscribers, e.g. the indexer.
+    chain_reorgs_sender: tokio::sync::broadcast::Sender<ChainReorg>,
+    /// Head for which the validator keys have been updated last time.
+    validator_keys_head: Option<CryptoHash>,
 }
 
 impl Client {
@@ -201,6 +207,73 @@ impl Client {
         self.config
             .produce_chunk_add_transactions_time_limit
             .update(update_client_config.produce_chunk_add_transactions_time_limit);
+        let tracked_config = TrackedConfig::from_updateable_config(&update_client_config);
+        match self.chain.head() {
+            Ok(head) => {
+                if let Err(err) =
+                    self.shard_tracker.update_tracked_config(tracked_config, &head.epoch_id)
+                {
+                    tracing::warn!(target: "client", ?err, "failed to update tracked shards");
+                }
+            }
+            Err(err) => tracing::warn!(target: "client", ?err, "failed to update tracked shards"),
+        }
+        if let Some(signers) = update_client_config.validator_signers {
+            match self.validator_signer.as_ref().and_then(|signer| signer.as_rotating()) {
+                Some(signer) => signer.set_signers(signers),
+                None => {
+                    tracing::warn!(target: "client", "validator keys can't be changed while the node is running, restart the node to apply the change")
+                }
+            }
+        }
+    }
+
+    /// Updates the validator keys once the head changes. The active key becomes the
+    /// validator's key in the epoch of the next block, so that the key is switched right
+    /// at the epoch boundary. The keys in the recent epochs are registered as well, so
+    /// that e.g. chunks of the previous epoch are still endorsed with its key.
+    /// Keys can be switched only if the node has been started with a `RotatingValidatorSigner`.
+    pub(crate) fn update_validator_key(&mut self) {
+        let Some(validator_signer) = self.validator_signer.clone() else {
+            return;
+        };
+        let Some(signer) = validator_signer.as_rotating() else {
+            return;
+        };
+        let Ok(head) = self.chain.head() else {
+            return;
+        };
+        if self.validator_keys_head == Some(head.last_block_hash) {
+            return;
+        }
+        let Ok(epoch_id) = self.epoch_manager.get_epoch_id_from_prev_block(&head.last_block_hash)
+        else {
+            return;
+        };
+        let mut epoch_ids = vec![head.epoch_id, head.next_epoch_id];
+        if let Ok(prev_epoch_id) = self.epoch_manager.get_epoch_id(&head.prev_block_hash) {
+            epoch_ids.push(prev_epoch_id);
+        }
+        // The validator may not be a part of the validator set of an epoch.
+        let epoch_keys: HashMap<_, _> = epoch_ids
+            .into_iter()
+            .filter_map(|epoch_id| {
+                let (stake, _) = self
+                    .epoch_manager
+                    .get_validator_by_account_id(
+                        &epoch_id,
+                        &head.last_block_hash,
+                        signer.validator_id(),
+                    )
+                    .ok()?;
+                Some((epoch_id, stake.public_key().clone()))
+            })
+            .collect();
+        if let Some(public_key) = epoch_keys.get(&epoch_id) {
+            signer.activate(public_key);
+        }
+        signer.set_epoch_keys(epoch_keys);
+        self.validator_keys_head = Some(head.last_block_hash);
     }
 }
 
@@ -280,25 +353,17 @@ impl Client {
             chain.chain_store(),
             chain_config.background_migration_threads,
         )?;
-        let sharded_tx_pool =
-            ShardedTransactionPool::new(rng_seed, config.transaction_pool_size_limit);
+        let sharded_tx_pool = ShardedTransactionPool::new(
+            rng_seed,
+            config.transaction_pool_size_limit,
+            config.transaction_selection_strategy,
+        );
         let sync_status = SyncStatus::AwaitingPeers;
-        let genesis_block = chain.genesis_block();
         let epoch_sync = EpochSync::new(
             clock.clone(),
             network_adapter.clone(),
-            genesis_block.header().epoch_id().clone(),
-            genesis_block.header().next_epoch_id().clone(),
-            epoch_manager
-                .get_epoch_block_producers_ordered(
-                    genesis_block.header().epoch_id(),
-                    genesis_block.hash(),
-                )?
-                .iter()
-                .map(|x| x.0.clone())
-                .collect(),
+            chain.genesis().epoch_id().clone(),
             EPOCH_SYNC_REQUEST_TIMEOUT,
-            EPOCH_SYNC_PEER_TIMEOUT,
         );
         let header_sync = HeaderSync::new(
             clock.clone(),
@@ -354,14 +419,18 @@ impl Client {
         );
         let chunk_endorsement_tracker =
             Arc::new(ChunkEndorsementTracker::new(epoch_manager.clone()));
+        let validator_performance = Arc::new(ValidatorPerformanceTracker::new(clock.clone()));
         let chunk_validator = ChunkValidator::new(
             validator_signer.clone(),
             epoch_manager.clone(),
             network_adapter.clone().into_sender(),
             runtime_adapter.clone(),
             chunk_endorsement_tracker.clone(),
+            validator_performance.clone(),
             config.orphan_state_witness_pool_size,
             async_computation_spawner,
+            config.save_invalid_witnesses,
+            config.save_latest_witnesses,
         );
         let chunk_distribution_network = ChunkDistributionNetwork::from_config(&config);
         Ok(Self {
@@ -398,6 +467,7 @@ impl Client {
             last_time_head_progress_made: clock.now(),
             block_production_info: BlockProductionTracker::new(),
             chunk_production_info: lru::LruCache::new(PRODUCTION_TIMES_CACHE_SIZE),
+            validator_performance,
             tier1_accounts_cache: None,
             flat_storage_creator,
             last_time_sync_block_requested: None,
@@ -405,9 +475,16 @@ impl Client {
             chunk_inclusion_tracker: ChunkInclusionTracker::new(),
             chunk_endorsement_tracker,
             chunk_distribution_network,
+            chain_reorgs_sender: tokio::sync::broadcast::channel(CHAIN_REORGS_CHANNEL_CAPACITY).0,
+            validator_keys_head: None,
         })
     }
 
+    /// Subscribes to the chain reorgs, which are sent once the blocks that caused them are processed.
+    pub fn subscribe_chain_reorgs(&self) -> tokio::sync::broadcast::Receiver<ChainReorg> {
+        self.chain_reorgs_sender.subscribe()
+    }
+
     // Checks if it's been at least `stall_timeout` since the last time the head was updated, or
     // this method was called. If yes, rebroadcasts the current head.
     pub fn check_head_progress_stalled(&mut self, stall_timeout: Duration) -> Result<(), Error> {
@@ -537,6 +614,8 @@ impl Client {
             let prev_prev_hash = prev_header.prev_hash();
             if !self.chain.prev_block_is_caught_up(prev_prev_hash, prev_hash)? {
                 debug!(target: "client", height, "Skipping block production, prev block is not caught up");
+                self.validator_performance
+                    .record_block_failure(height, "Previous block is not caught up".to_string());
                 return Ok(false);
             }
         }
@@ -591,6 +670,7 @@ impl Client {
 
         // Check that we are were called at the block that we are producer for.
         let epoch_id = self.epoch_manager.get_epoch_id_from_prev_block(&prev_hash).unwrap();
+        let validator_signer = signer_for_epoch(&validator_signer, &epoch_id);
         let next_block_proposer = self.epoch_manager.get_block_producer(&epoch_id, height)?;
 
         let prev = self.chain.get_block_header(&prev_hash)?;
@@ -623,6 +703,10 @@ impl Client {
                 local_validator_key = ?validator_signer.public_key(),
                 ?validator_pk,
                 "Local validator key does not match expected validator key, skipping block production");
+            self.validator_performance.record_block_failure(
+                height,
+                "Local validator key does not match the expected validator key".to_string(),
+            );
             #[cfg(not(feature = "test_features"))]
             return Ok(None);
             #[cfg(feature = "test_features")]
@@ -649,6 +733,8 @@ impl Client {
         // If we are producing empty blocks and there are no transactions.
         if !self.config.produce_empty_blocks && new_chunks.is_empty() {
             debug!(target: "client", "Empty blocks, skipping block production");
+            self.validator_performance
+                .record_block_failure(height, "No new chunks to include".to_string());
             return Ok(None);
         }
 
@@ -773,7 +859,7 @@ impl Client {
         let next_epoch_protocol_version =
             self.epoch_manager.get_epoch_protocol_version(&next_epoch_id)?;
 
-        let block = Block::produce(
+        let block = match Block::produce(
             this_epoch_protocol_version,
             next_epoch_protocol_version,
             prev_header,
@@ -795,7 +881,15 @@ impl Client {
             next_bp_hash,
             block_merkle_root,
             block_timestamp,
-        );
+        ) {
+            Ok(block) => block,
+            Err(err) => {
+                error!(target: "client", height, ?err, "Failed to sign the block, skipping block production");
+                self.validator_performance
+                    .record_block_failure(height, format!("Failed to sign the block: {err}"));
+                return Ok(None);
+            }
+        };
 
         // Update latest known even before returning block out, to prevent race conditions.
         self.chain
@@ -803,6 +897,7 @@ impl Client {
             .save_latest_known(LatestKnown { height, seen: block.header().raw_timestamp() })?;
 
         metrics::BLOCK_PRODUCED_TOTAL.inc();
+        self.validator_performance.record_block_produced(height, *block.hash());
 
         Ok(Some(block))
     }
@@ -819,11 +914,10 @@ impl Client {
         let _timer =
             metrics::PRODUCE_CHUNK_TIME.with_label_values(&[&shard_id.to_string()]).start_timer();
         let _span = tracing::debug_span!(target: "client", "produce_chunk", next_height, shard_id, ?epoch_id).entered();
-        let validator_signer = self
-            .validator_signer
-            .as_ref()
-            .ok_or_else(|| Error::ChunkProducer("Called without block producer info.".to_string()))?
-            .clone();
+        let validator_signer = self.validator_signer.as_ref().ok_or_else(|| {
+            Error::ChunkProducer("Called without block producer info.".to_string())
+        })?;
+        let validator_signer = signer_for_epoch(validator_signer, epoch_id);
 
         let chunk_proposer =
             self.epoch_manager.get_chunk_producer(epoch_id, next_height, shard_id).unwrap();
@@ -905,6 +999,11 @@ impl Client {
             "Produced chunk");
 
         metrics::CHUNK_PRODUCED_TOTAL.inc();
+        self.validator_performance.record_chunk_produced(
+            next_height,
+            shard_id,
+            &encoded_chunk.chunk_hash(),
+        );
         self.chunk_production_info.put(
             (next_height, shard_id),
             ChunkProduction {
@@ -1026,7 +1125,13 @@ impl Client {
     pub fn send_challenges(&mut self, challenges: Vec<ChallengeBody>) {
         if let Some(validator_signer) = &self.validator_signer {
             for body in challenges {
-                let challenge = Challenge::produce(body, &**validator_signer);
+                let challenge = match Challenge::produce(body, &**validator_signer) {
+                    Ok(challenge) => challenge,
+                    Err(err) => {
+                        warn!(target: "client", ?err, "Failed to sign the challenge");
+                        continue;
+                    }
+                };
                 self.challenges.insert(challenge.hash, challenge.clone());
                 self.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(
                     NetworkRequests::Challenge(challenge),
@@ -1253,25 +1358,23 @@ impl Client {
 
         // Send out challenge if the block was found to be invalid.
         if let Some(validator_signer) = self.validator_signer.as_ref() {
-            if let Err(e) = &result {
-                match e {
-                    near_chain::Error::InvalidChunkProofs(chunk_proofs) => {
-                        self.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(
-                            NetworkRequests::Challenge(Challenge::produce(
-                                ChallengeBody::ChunkProofs(*chunk_proofs.clone()),
-                                &**validator_signer,
-                            )),
-                        ));
-                    }
-                    near_chain::Error::InvalidChunkState(chunk_state) => {
+            let body = match &result {
+                Err(near_chain::Error::InvalidChunkProofs(chunk_proofs)) => {
+                    Some(ChallengeBody::ChunkProofs(*chunk_proofs.clone()))
+                }
+                Err(near_chain::Error::InvalidChunkState(chunk_state)) => {
+                    Some(ChallengeBody::ChunkState(*chunk_state.clone()))
+                }
+                _ => None,
+            };
+            if let Some(body) = body {
+                match Challenge::produce(body, &**validator_signer) {
+                    Ok(challenge) => {
                         self.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(
-                            NetworkRequests::Challenge(Challenge::produce(
-                                ChallengeBody::ChunkState(*chunk_state.clone()),
-                                &**validator_signer,
-                            )),
+                            NetworkRequests::Challenge(challenge),
                         ));
                     }
-                    _ => {}
+                    Err(err) => warn!(target: "client", ?err, "Failed to sign the challenge"),
                 }
             }
         }
@@ -1333,6 +1436,7 @@ impl Client {
             blocks_missing_chunks,
             challenges,
             invalid_chunks,
+            reorgs,
         } = block_processing_artifacts;
         // Send out challenges that accumulated via on_challenge.
         self.send_challenges(challenges);
@@ -1355,6 +1459,11 @@ impl Client {
                 error!(target: "client", ?err, "Failed to ban chunk producer for producing invalid chunk");
             }
         }
+
+        for reorg in reorgs {
+            // Sending fails only if there are no subscribers.
+            let _ = self.chain_reorgs_sender.send(reorg);
+        }
     }
 
     fn ban_chunk_producer_for_producing_invalid_chunk(
@@ -1497,6 +1606,7 @@ impl Client {
         let next_epoch_id = self.epoch_manager.get_epoch_id_from_prev_block(parent_hash)?;
         let next_block_producer =
             self.epoch_manager.get_block_producer(&next_epoch_id, approval.target_height)?;
+        self.record_approval_performance(&approval, None);
         if Some(&next_block_producer) == self.validator_signer.as_ref().map(|x| x.validator_id()) {
             self.collect_block_approval(&approval, ApprovalType::SelfApproval);
         } else {
@@ -1543,6 +1653,9 @@ impl Client {
             }
         };
 
+        // The head may have moved to the last block of an epoch, in which case the next
+        // blocks, chunks and approvals are to be signed with the key of the next epoch.
+        self.update_validator_key();
         let _ = self.check_and_update_doomslug_tip();
 
         // If we produced the block, then it should have already been broadcasted.
@@ -1572,6 +1685,9 @@ impl Client {
                 self.chain.get_block_header(last_final_block).map_or(0, |header| header.height())
             };
             self.chain.blocks_with_missing_chunks.prune_blocks_below_height(last_finalized_height);
+            self.chunk_validator
+                .partial_witness_tracker
+                .remove_parts_below_final_height(last_finalized_height);
 
             {
                 let _span = tracing::debug_span!(
@@ -1770,6 +1886,11 @@ impl Client {
                 Ok(None) => {}
                 Err(err) => {
                     error!(target: "client", ?err, "Error producing chunk");
+                    self.validator_performance.record_chunk_failure(
+                        next_height,
+                        shard_id,
+                        err.to_string(),
+                    );
                 }
             }
         }
@@ -2527,7 +2648,8 @@ impl Client {
         // A RPC node should do regular garbage collection.
         if !self.config.archive {
             let tries = self.runtime_adapter.get_tries();
-            return self.chain.clear_data(tries, &self.config.gc);
+            let me = self.validator_signer.as_ref().map(|signer| signer.validator_id().clone());
+            return self.chain.clear_data(tries, &self.config.gc, &me);
         }
 
         // An archival node with split storage should perform garbage collection
@@ -2538,7 +2660,8 @@ impl Client {
         let kind = store.get_db_kind()?;
         if kind == Some(DbKind::Hot) {
             let tries = self.runtime_adapter.get_tries();
-            return self.chain.clear_data(tries, &self.config.gc);
+            let me = self.validator_signer.as_ref().map(|signer| signer.validator_id().clone());
+            return self.chain.clear_data(tries, &self.config.gc, &me);
         }
 
         // An archival node with legacy storage or in the midst of migration to split
@@ -28,20 +28,22 @@ use near_chain::test_utils::format_hash;
 use near_chain::ChainStoreAccess;
 use near_chain::{
     byzantine_assert, near_chain_primitives, Block, BlockHeader, BlockProcessingArtifact,
-    DoneApplyChunkCallback, Provenance,
+    ChainReorg, DoneApplyChunkCallback, Provenance,
 };
 use near_chain_configs::{ClientConfig, LogSummaryStyle};
 use near_chain_primitives::error::EpochErrorResultToChainError;
 use near_chunks::client::ShardsManagerResponse;
 use near_chunks::logic::cares_about_shard_this_or_next_epoch;
 use near_client_primitives::types::{
-    Error, GetClientConfig, GetClientConfigError, GetNetworkInfo, NetworkInfoResponse,
-    StateSyncStatus, Status, StatusError, StatusSyncInfo, SyncStatus,
+    Error, GetClientConfig, GetClientConfigError, GetNetworkInfo, GetValidatorPerformance,
+    GetValidatorPerformanceError, NetworkInfoResponse, StateSyncStatus, Status, StatusError,
+    StatusSyncInfo, SyncStatus,
 };
 use near_network::client::{
     BlockApproval, BlockHeadersResponse, BlockResponse, ChunkEndorsementMessage,
-    ChunkStateWitnessMessage, ProcessTxRequest, ProcessTxResponse, RecvChallenge, SetNetworkInfo,
-    StateResponse,
+    ChunkStateWitnessMessage, EpochSyncResponse, PartialEncodedStateWitnessForwardMessage,
+    PartialEncodedStateWitnessMessage, ProcessTxRequest, ProcessTxResponse, RecvChallenge,
+    SetNetworkInfo, StateResponse,
 };
 use near_network::types::ReasonForBan;
 use near_network::types::{
@@ -60,7 +62,7 @@ use near_primitives::unwrap_or_return;
 use near_primitives::utils::MaybeValidated;
 use near_primitives::validator_signer::ValidatorSigner;
 use near_primitives::version::PROTOCOL_VERSION;
-use near_primitives::views::{DetailedDebugStatus, ValidatorInfo};
+use near_primitives::views::{DetailedDebugStatus, ValidatorInfo, ValidatorPerformanceView};
 #[cfg(feature = "test_features")]
 use near_store::DBCol;
 use near_store::ShardUId;
@@ -374,6 +376,30 @@ impl ClientActionHandler<BlockHeadersResponse> for ClientActions {
     }
 }
 
+impl ClientActionHandler<EpochSyncResponse> for ClientActions {
+    type Result = ();
+
+    fn handle(&mut self, msg: EpochSyncResponse) {
+        let EpochSyncResponse(response, peer_id) = msg;
+        #[cfg(feature = "new_epoch_sync")]
+        if let Err(err) =
+            self.client.epoch_sync.on_response(&mut self.client.chain, response, &peer_id)
+        {
+            warn!(target: "sync", ?err, ?peer_id, "Received invalid EpochSyncInfo");
+            // Request the epoch from another peer right away.
+            if let Err(err) = self.client.epoch_sync.run(
+                &mut self.client.sync_status,
+                &self.client.chain,
+                &self.network_info.highest_height_peers,
+            ) {
+                error!(target: "sync", ?err, "Failed to run epoch sync");
+            }
+        }
+        #[cfg(not(feature = "new_epoch_sync"))]
+        debug!(target: "sync", epoch_id = ?response.epoch_id, ?peer_id, "Ignoring EpochSyncInfo, epoch sync is not supported");
+    }
+}
+
 impl ClientActionHandler<BlockApproval> for ClientActions {
     type Result = ();
 
@@ -794,11 +820,11 @@ impl ClientActions {
             debug!(target: "client", "Sending announce account for {}", validator_signer.validator_id());
             self.last_validator_announce_time = Some(now);
 
-            let signature = validator_signer.sign_account_announce(
+            let signature = unwrap_or_return!(validator_signer.sign_account_announce(
                 validator_signer.validator_id(),
                 &self.node_id,
                 &next_epoch_id,
-            );
+            ));
             self.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(
                 NetworkRequests::AnnounceAccount(AnnounceAccount {
                     account_id: validator_signer.validator_id().clone(),
@@ -936,6 +962,9 @@ impl ClientActions {
             return Ok(());
         };
 
+        // No block is produced below the latest known height anymore.
+        self.client.validator_performance.record_abandoned_heights(latest_known.height);
+
         // For debug purpose, we record the approvals we have seen so far to the future blocks
         for height in latest_known.height + 1..=self.client.doomslug.get_largest_approval_height() {
             let next_block_producer_account =
@@ -946,6 +975,10 @@ impl ClientActions {
                     height,
                     self.client.doomslug.approval_status_at_height(&height),
                 );
+                self.client.validator_performance.set_waiting_for_approvals(
+                    height,
+                    height > self.client.doomslug.get_largest_height_crossing_threshold(),
+                );
             }
         }
 
@@ -1015,6 +1048,7 @@ impl ClientActions {
                 self.client.update_client_config(updateable_client_config)
             });
         }
+        self.client.update_validator_key();
 
         // Check block height to trigger expected shutdown
         if let Ok(head) = self.client.chain.head() {
@@ -1127,10 +1161,12 @@ impl ClientActions {
                     || self.client.is_validator(&head.next_epoch_id, &head.last_block_hash)
                 {
                     for approval in approvals {
-                        if let Err(e) =
-                            self.client.send_approval(&self.client.doomslug.get_tip().0, approval)
+                        if let Err(e) = self
+                            .client
+                            .send_approval(&self.client.doomslug.get_tip().0, approval.clone())
                         {
                             error!("Error while sending an approval {:?}", e);
+                            self.client.record_approval_performance(&approval, Some(e.to_string()));
                         }
                     }
                 }
@@ -1143,7 +1179,11 @@ impl ClientActions {
     /// Can return error, should be called with `produce_block` to handle errors and reschedule.
     fn produce_block(&mut self, next_height: BlockHeight) -> Result<(), Error> {
         let _span = tracing::debug_span!(target: "client", "produce_block", next_height).entered();
-        if let Some(block) = self.client.produce_block_on_head(next_height, false)? {
+        let block = self.client.produce_block_on_head(next_height, false).map_err(|err| {
+            self.client.validator_performance.record_block_failure(next_height, err.to_string());
+            err
+        })?;
+        if let Some(block) = block {
             // If we produced the block, send it out before we apply the block.
             self.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(
                 NetworkRequests::Block { block: block.clone() },
@@ -1488,6 +1528,18 @@ impl ClientActions {
                 if !currently_syncing {
                     info!(target: "client", ?sync, "enabling sync");
                 }
+                // A new node first syncs the finalised epochs, then downloads the headers
+                // following the last of them.
+                #[cfg(feature = "new_epoch_sync")]
+                if self.client.config.epoch_sync_enabled
+                    && unwrap_and_report!(self.client.epoch_sync.run(
+                        &mut self.client.sync_status,
+                        &self.client.chain,
+                        &self.network_info.highest_height_peers
+                    ))
+                {
+                    return;
+                }
                 // Run each step of syncing separately.
                 unwrap_and_report!(self.client.header_sync.run(
                     &mut self.client.sync_status,
@@ -1828,6 +1880,32 @@ impl ClientActionHandler<GetClientConfig> for ClientActions {
     }
 }
 
+impl ClientActionHandler<GetValidatorPerformance> for ClientActions {
+    type Result = Result<ValidatorPerformanceView, GetValidatorPerformanceError>;
+
+    fn handle(&mut self, msg: GetValidatorPerformance) -> Self::Result {
+        tracing::debug!(target: "client", ?msg);
+
+        Ok(self.client.get_validator_performance()?)
+    }
+}
+
+/// Subscribes to the chain reorgs, see `ChainReorg`.
+#[derive(actix::Message, Debug)]
+#[rtype(result = "ChainReorgReceiver")]
+pub struct SubscribeChainReorgs;
+
+#[derive(actix::MessageResponse, Debug)]
+pub struct ChainReorgReceiver(pub tokio::sync::broadcast::Receiver<ChainReorg>);
+
+impl ClientActionHandler<SubscribeChainReorgs> for ClientActions {
+    type Result = ChainReorgReceiver;
+
+    fn handle(&mut self, _msg: SubscribeChainReorgs) -> Self::Result {
+        ChainReorgReceiver(self.client.subscribe_chain_reorgs())
+    }
+}
+
 impl ClientActionHandler<SyncMessage> for ClientActions {
     type Result = ();
 
@@ -1849,6 +1927,32 @@ impl ClientActionHandler<ChunkStateWitnessMessage> for ClientActions {
     }
 }
 
+impl ClientActionHandler<PartialEncodedStateWitnessMessage> for ClientActions {
+    type Result = ();
+
+    #[perf]
+    fn handle(&mut self, msg: PartialEncodedStateWitnessMessage) -> Self::Result {
+        if let Err(err) = self.client.process_partial_encoded_state_witness(msg.0, None) {
+            tracing::error!(target: "client", ?err, "Error processing partial encoded state witness");
+        }
+    }
+}
+
+impl ClientActionHandler<PartialEncodedStateWitnessForwardMessage> for ClientActions {
+    type Result = ();
+
+    #[perf]
+    fn handle(&mut self, msg: PartialEncodedStateWitnessForwardMessage) -> Self::Result {
+        if let Err(err) = self.client.process_partial_encoded_state_witness_forward(
+            msg.part,
+            &msg.sender_accounts,
+            None,
+        ) {
+            tracing::error!(target: "client", ?err, "Error processing forwarded partial encoded state witness");
+        }
+    }
+}
+
 impl ClientActionHandler<ChunkEndorsementMessage> for ClientActions {
     type Result = ();
 
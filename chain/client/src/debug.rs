@@ -34,7 +34,8 @@ use near_client_primitives::debug::{DebugBlockStatus, DebugChunkStatus};
 use near_network::types::{ConnectedPeerInfo, NetworkInfo, PeerType};
 use near_primitives::sharding::ChunkHash;
 use near_primitives::views::{
-    AccountDataView, KnownProducerView, NetworkInfoView, PeerInfoView, Tier1ProxyView,
+    AccountDataView, KnownProducerView, NetworkInfoView, PeerInfoView, PeerSendClassView,
+    Tier1ProxyView,
 };
 
 // Constants for debug requests.
@@ -665,6 +666,16 @@ fn new_peer_info_view(chain: &Chain, connected_peer_info: &ConnectedPeerInfo) ->
             .whole_milliseconds() as u64,
         is_outbound_peer: connected_peer_info.peer_type == PeerType::Outbound,
         nonce: connected_peer_info.nonce,
+        send_classes: connected_peer_info
+            .send_classes
+            .iter()
+            .map(|c| PeerSendClassView {
+                class: c.class.to_string(),
+                sent_bytes: c.sent_bytes,
+                delayed_messages: c.delayed_messages,
+                dropped_messages: c.dropped_messages,
+            })
+            .collect(),
     }
 }
 
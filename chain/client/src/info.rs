@@ -16,7 +16,7 @@ use near_primitives::types::{
     ValidatorInfoIdentifier,
 };
 use near_primitives::unwrap_or_return;
-use near_primitives::validator_signer::ValidatorSigner;
+use near_primitives::validator_signer::{ValidatorSigner, ValidatorSignerError};
 use near_primitives::version::Version;
 use near_primitives::views::{
     CatchupStatusView, ChunkProcessingStatus, CurrentEpochValidatorInfo, EpochValidatorInfo,
@@ -474,7 +474,7 @@ impl InfoHelper {
         self.num_chunks_in_blocks_processed = 0;
         self.gas_used = 0;
 
-        self.telemetry_sender.send(TelemetryEvent::new(self.telemetry_info(
+        match self.telemetry_info(
             head,
             sync_status,
             node_id,
@@ -483,7 +483,10 @@ impl InfoHelper {
             cpu_usage,
             memory_usage,
             is_validator,
-        )));
+        ) {
+            Ok(info) => self.telemetry_sender.send(TelemetryEvent::new(info)),
+            Err(err) => tracing::warn!(target: "telemetry", ?err, "Failed to sign telemetry"),
+        }
     }
 
     fn telemetry_info(
@@ -496,7 +499,7 @@ impl InfoHelper {
         cpu_usage: f32,
         memory_usage: u64,
         is_validator: bool,
-    ) -> serde_json::Value {
+    ) -> Result<serde_json::Value, ValidatorSignerError> {
         let info = TelemetryInfo {
             agent: TelemetryAgentInfo {
                 name: "near-rs".to_string(),
@@ -535,7 +538,7 @@ impl InfoHelper {
         if let Some(vs) = self.validator_signer.as_ref() {
             vs.sign_telemetry(&info)
         } else {
-            serde_json::to_value(&info).expect("Telemetry must serialize to json")
+            Ok(serde_json::to_value(&info).expect("Telemetry must serialize to json"))
         }
     }
 
@@ -928,27 +931,29 @@ mod tests {
         )
         .unwrap();
 
-        let telemetry = info_helper.telemetry_info(
-            &chain.head().unwrap(),
-            &SyncStatus::AwaitingPeers,
-            &peer_id_from_seed("zxc"),
-            &NetworkInfo {
-                connected_peers: vec![],
-                num_connected_peers: 0,
-                peer_max_count: 0,
-                highest_height_peers: vec![],
-                sent_bytes_per_sec: 0,
-                received_bytes_per_sec: 0,
-                known_producers: vec![],
-                tier1_connections: vec![],
-                tier1_accounts_keys: vec![],
-                tier1_accounts_data: vec![],
-            },
-            &config,
-            0.0,
-            0,
-            false,
-        );
+        let telemetry = info_helper
+            .telemetry_info(
+                &chain.head().unwrap(),
+                &SyncStatus::AwaitingPeers,
+                &peer_id_from_seed("zxc"),
+                &NetworkInfo {
+                    connected_peers: vec![],
+                    num_connected_peers: 0,
+                    peer_max_count: 0,
+                    highest_height_peers: vec![],
+                    sent_bytes_per_sec: 0,
+                    received_bytes_per_sec: 0,
+                    known_producers: vec![],
+                    tier1_connections: vec![],
+                    tier1_accounts_keys: vec![],
+                    tier1_accounts_data: vec![],
+                },
+                &config,
+                0.0,
+                0,
+                false,
+            )
+            .unwrap();
         println!("Got telemetry info: {:?}", telemetry);
         assert_matches!(
             telemetry["extra_info"].as_str().unwrap().find("\"max_block_production_delay\":2.34,"),
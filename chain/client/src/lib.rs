@@ -4,18 +4,24 @@ pub use near_client_primitives::types::{
     GetExecutionOutcomesForBlock, GetGasPrice, GetMaintenanceWindows, GetNetworkInfo,
     GetNextLightClientBlock, GetProtocolConfig, GetReceipt, GetSplitStorageInfo, GetStateChanges,
     GetStateChangesInBlock, GetStateChangesWithCauseInBlock,
-    GetStateChangesWithCauseInBlockForTrackedShards, GetValidatorInfo, GetValidatorOrdered, Query,
-    QueryError, Status, StatusResponse, SyncStatus, TxStatus, TxStatusError,
+    GetStateChangesWithCauseInBlockForTrackedShards, GetValidatorInfo, GetValidatorOrdered,
+    GetValidatorPerformance, Query, QueryError, Status, StatusResponse, SyncStatus, TxStatus,
+    TxStatusError,
 };
 
 pub use crate::client::{Client, ProduceChunkResult};
 #[cfg(feature = "test_features")]
 pub use crate::client_actions::NetworkAdversarialMessage;
+pub use crate::client_actions::{ChainReorgReceiver, SubscribeChainReorgs};
 pub use crate::client_actor::{start_client, ClientActor};
 pub use crate::config_updater::ConfigUpdater;
 pub use crate::stateless_validation::chunk_validator::orphan_witness_handling::{
     HandleOrphanWitnessOutcome, MAX_ORPHAN_WITNESS_SIZE,
 };
+pub use crate::stateless_validation::chunk_validator::{
+    apply_chunk_state_witness, pre_validate_chunk_state_witness, AppliedStateWitness,
+    TransitionPostStateRoot,
+};
 pub use crate::sync::adapter::{SyncAdapter, SyncMessage};
 pub use crate::view_client::{start_view_client, ViewClientActor};
 pub use near_client_primitives::debug::DebugStatus;
@@ -42,4 +48,5 @@ mod sync_jobs_actor;
 pub mod test_utils;
 #[cfg(test)]
 mod tests;
+mod validator_performance;
 mod view_client;
@@ -1,17 +1,22 @@
 pub mod orphan_witness_handling;
 pub mod orphan_witness_pool;
+pub mod partial_witness_tracker;
 
 use super::processing_tracker::ProcessingDoneTracker;
 use crate::stateless_validation::chunk_endorsement_tracker::ChunkEndorsementTracker;
+use crate::validator_performance::ValidatorPerformanceTracker;
 use crate::{metrics, Client};
 use itertools::Itertools;
 use near_async::futures::{AsyncComputationSpawner, AsyncComputationSpawnerExt};
-use near_async::messaging::Sender;
+use near_async::messaging::{CanSend, Sender};
 use near_chain::chain::{
     apply_new_chunk, apply_old_chunk, NewChunkData, NewChunkResult, OldChunkData, OldChunkResult,
     ShardContext, StorageContext,
 };
 use near_chain::sharding::shuffle_receipt_proofs;
+use near_chain::stateless_validation::saved_witnesses::{
+    save_chunk_state_witness, SavedWitnessKind, MAX_SAVED_INVALID_WITNESSES,
+};
 use near_chain::types::{
     ApplyChunkBlockContext, ApplyChunkResult, PreparedTransactions, RuntimeAdapter,
     RuntimeStorageConfig, StorageDataSource,
@@ -27,14 +32,16 @@ use near_primitives::merkle::merklize;
 use near_primitives::receipt::Receipt;
 use near_primitives::sharding::{ChunkHash, ReceiptProof, ShardChunkHeader};
 use near_primitives::stateless_validation::{
-    ChunkEndorsement, ChunkStateWitness, ChunkStateWitnessInner,
+    witness_part_counts, ChunkEndorsement, ChunkStateWitness, ChunkStateWitnessInner,
+    PartialEncodedStateWitness,
 };
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::chunk_extra::ChunkExtra;
-use near_primitives::types::ShardId;
-use near_primitives::validator_signer::ValidatorSigner;
-use near_store::PartialStorage;
+use near_primitives::types::{AccountId, ShardId};
+use near_primitives::validator_signer::{signer_for_epoch, ValidatorSigner};
+use near_store::{PartialStorage, Store};
 use orphan_witness_pool::OrphanStateWitnessPool;
+use partial_witness_tracker::PartialEncodedStateWitnessTracker;
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -57,8 +64,11 @@ pub struct ChunkValidator {
     network_sender: Sender<PeerManagerMessageRequest>,
     runtime_adapter: Arc<dyn RuntimeAdapter>,
     chunk_endorsement_tracker: Arc<ChunkEndorsementTracker>,
+    validator_performance: Arc<ValidatorPerformanceTracker>,
     orphan_witness_pool: OrphanStateWitnessPool,
+    pub(crate) partial_witness_tracker: PartialEncodedStateWitnessTracker,
     validation_spawner: Arc<dyn AsyncComputationSpawner>,
+    witness_saver: WitnessSaver,
 }
 
 impl ChunkValidator {
@@ -68,17 +78,28 @@ impl ChunkValidator {
         network_sender: Sender<PeerManagerMessageRequest>,
         runtime_adapter: Arc<dyn RuntimeAdapter>,
         chunk_endorsement_tracker: Arc<ChunkEndorsementTracker>,
+        validator_performance: Arc<ValidatorPerformanceTracker>,
         orphan_witness_pool_size: usize,
         validation_spawner: Arc<dyn AsyncComputationSpawner>,
+        save_invalid_witnesses: bool,
+        save_latest_witnesses: usize,
     ) -> Self {
+        let witness_saver = WitnessSaver {
+            store: runtime_adapter.store().clone(),
+            save_invalid_witnesses,
+            save_latest_witnesses,
+        };
         Self {
             my_signer,
             epoch_manager,
             network_sender,
             runtime_adapter,
             chunk_endorsement_tracker,
+            validator_performance,
             orphan_witness_pool: OrphanStateWitnessPool::new(orphan_witness_pool_size),
+            partial_witness_tracker: PartialEncodedStateWitnessTracker::default(),
             validation_spawner,
+            witness_saver,
         }
     }
 
@@ -97,6 +118,8 @@ impl ChunkValidator {
             return Err(Error::InvalidChunkStateWitness("Invalid signature".to_string()));
         }
 
+        // The witness is only cloned when it may need to be saved for debugging.
+        let saved_witness = self.witness_saver.is_enabled().then(|| state_witness.clone());
         let state_witness_inner = state_witness.inner;
         let chunk_header = state_witness_inner.chunk_header.clone();
         let Some(my_signer) = self.my_signer.as_ref() else {
@@ -116,28 +139,54 @@ impl ChunkValidator {
             return Err(Error::NotAChunkValidator);
         }
 
-        let pre_validation_result = pre_validate_chunk_state_witness(
+        let pre_validation_result = match pre_validate_chunk_state_witness(
             &state_witness_inner,
             chain,
             self.epoch_manager.as_ref(),
             self.runtime_adapter.as_ref(),
-        )?;
+        ) {
+            Ok(pre_validation_result) => pre_validation_result,
+            Err(err) => {
+                if let Some(witness) = &saved_witness {
+                    self.witness_saver.save(witness, Some(&err));
+                }
+                self.validator_performance.record_endorsement(
+                    chunk_header.height_created(),
+                    chunk_header.shard_id(),
+                    &chunk_header.chunk_hash(),
+                    Some(err.to_string()),
+                );
+                return Err(err);
+            }
+        };
 
         let network_sender = self.network_sender.clone();
-        let signer = my_signer.clone();
+        let signer = signer_for_epoch(my_signer, &epoch_id);
         let epoch_manager = self.epoch_manager.clone();
         let runtime_adapter = self.runtime_adapter.clone();
         let chunk_endorsement_tracker = self.chunk_endorsement_tracker.clone();
+        let witness_saver = self.witness_saver.clone();
+        let validator_performance = self.validator_performance.clone();
         self.validation_spawner.spawn("stateless_validation", move || {
             // processing_done_tracker must survive until the processing is finished.
             let _processing_done_tracker_capture = processing_done_tracker;
 
-            match validate_chunk_state_witness(
+            let validation_result = validate_chunk_state_witness(
                 state_witness_inner,
                 pre_validation_result,
                 epoch_manager.as_ref(),
                 runtime_adapter.as_ref(),
-            ) {
+            );
+            if let Some(witness) = &saved_witness {
+                witness_saver.save(witness, validation_result.as_ref().err());
+            }
+            validator_performance.record_endorsement(
+                chunk_header.height_created(),
+                chunk_header.shard_id(),
+                &chunk_header.chunk_hash(),
+                validation_result.as_ref().err().map(|err| err.to_string()),
+            );
+            match validation_result {
                 Ok(()) => {
                     send_chunk_endorsement_to_block_producers(
                         &chunk_header,
@@ -156,6 +205,50 @@ impl ChunkValidator {
     }
 }
 
+/// Saves chunk state witnesses in the database for debugging, if enabled in the config.
+#[derive(Clone)]
+struct WitnessSaver {
+    store: Store,
+    save_invalid_witnesses: bool,
+    save_latest_witnesses: usize,
+}
+
+impl WitnessSaver {
+    fn is_enabled(&self) -> bool {
+        self.save_invalid_witnesses || self.save_latest_witnesses > 0
+    }
+
+    /// Saves the witness according to the result of its validation. Failures are only
+    /// logged, saving witnesses must not affect the validation itself.
+    fn save(&self, witness: &ChunkStateWitness, validation_error: Option<&Error>) {
+        let validation_error = validation_error.map(|err| err.to_string());
+        let mut saves = vec![];
+        if self.save_invalid_witnesses && validation_error.is_some() {
+            saves.push((SavedWitnessKind::Invalid, MAX_SAVED_INVALID_WITNESSES));
+        }
+        if self.save_latest_witnesses > 0 {
+            saves.push((SavedWitnessKind::Latest, self.save_latest_witnesses));
+        }
+        for (kind, limit) in saves {
+            if let Err(err) = save_chunk_state_witness(
+                &self.store,
+                kind,
+                witness,
+                validation_error.clone(),
+                limit,
+            ) {
+                tracing::warn!(
+                    target: "stateless_validation",
+                    ?kind,
+                    chunk_hash=?witness.inner.chunk_header.chunk_hash(),
+                    ?err,
+                    "Failed to save chunk state witness",
+                );
+            }
+        }
+    }
+}
+
 /// Checks that proposed `transactions` are valid for a chunk with `chunk_header`.
 /// Uses `storage_config` to possibly record reads or use recorded storage.
 pub(crate) fn validate_prepared_transactions(
@@ -181,7 +274,7 @@ pub(crate) fn validate_prepared_transactions(
 /// Pre-validates the chunk's receipts and transactions against the chain.
 /// We do this before handing off the computationally intensive part to a
 /// validation thread.
-pub(crate) fn pre_validate_chunk_state_witness(
+pub fn pre_validate_chunk_state_witness(
     state_witness: &ChunkStateWitnessInner,
     chain: &Chain,
     epoch_manager: &dyn EpochManagerAdapter,
@@ -463,21 +556,51 @@ impl MainTransition {
     }
 }
 
-pub(crate) struct PreValidationOutput {
+pub struct PreValidationOutput {
     main_transition_params: MainTransition,
     implicit_transition_params: Vec<ApplyChunkBlockContext>,
 }
 
-pub(crate) fn validate_chunk_state_witness(
+/// Post state root of a state transition of a chunk state witness, as claimed by the
+/// witness and as computed by applying the transition.
+#[derive(Debug)]
+pub struct TransitionPostStateRoot {
+    pub block_hash: CryptoHash,
+    pub expected: CryptoHash,
+    pub actual: CryptoHash,
+}
+
+/// Result of applying the state transitions of a chunk state witness.
+pub struct AppliedStateWitness {
+    /// Post state roots of the applied transitions, starting with the main transition.
+    /// Applying stops at the first transition with an unexpected post state root, as
+    /// the following transitions can't be applied on top of it.
+    pub post_state_roots: Vec<TransitionPostStateRoot>,
+    /// Chunk extra after applying all the transitions, to be compared with the chunk header.
+    pub chunk_extra: ChunkExtra,
+    /// Outgoing receipts of the main transition.
+    pub outgoing_receipts: Vec<Receipt>,
+    /// Merkle root of the outgoing receipts, to be compared with the chunk header.
+    pub outgoing_receipts_root: CryptoHash,
+}
+
+impl AppliedStateWitness {
+    /// Returns the first transition with a post state root not matching the witness,
+    /// if any, together with its index. Index 0 is the main transition.
+    pub fn first_post_state_root_mismatch(&self) -> Option<(usize, &TransitionPostStateRoot)> {
+        self.post_state_roots.iter().enumerate().find(|(_, root)| root.expected != root.actual)
+    }
+}
+
+/// Applies the state transitions of the chunk state witness on top of the partial
+/// state included in the witness.
+pub fn apply_chunk_state_witness(
     state_witness: ChunkStateWitnessInner,
     pre_validation_output: PreValidationOutput,
     epoch_manager: &dyn EpochManagerAdapter,
     runtime_adapter: &dyn RuntimeAdapter,
-) -> Result<(), Error> {
-    let _timer = metrics::CHUNK_STATE_WITNESS_VALIDATION_TIME
-        .with_label_values(&[&state_witness.chunk_header.shard_id().to_string()])
-        .start_timer();
-    let span = tracing::debug_span!(target: "chain", "validate_chunk_state_witness").entered();
+) -> Result<AppliedStateWitness, Error> {
+    let span = tracing::debug_span!(target: "chain", "apply_chunk_state_witness").entered();
     let block_hash = pre_validation_output.main_transition_params.block_hash();
     let epoch_id = epoch_manager.get_epoch_id(&block_hash)?;
     let shard_uid = epoch_manager
@@ -503,72 +626,112 @@ pub(crate) fn validate_chunk_state_witness(
             (apply_result_to_chunk_extra(main_apply_result, &chunk_header), outgoing_receipts)
         }
     };
-    if chunk_extra.state_root() != &state_witness.main_state_transition.post_state_root {
-        // This is an early check, it's not for correctness, only for better
-        // error reporting in case of an invalid state witness due to a bug.
-        // Only the final state root check against the chunk header is required.
-        return Err(Error::InvalidChunkStateWitness(format!(
-            "Post state root {:?} for main transition does not match expected post state root {:?}",
-            chunk_extra.state_root(),
-            state_witness.main_state_transition.post_state_root,
-        )));
-    }
-
-    for (block, transition) in pre_validation_output
-        .implicit_transition_params
-        .into_iter()
-        .zip(state_witness.implicit_transitions.into_iter())
-    {
-        let block_hash = block.block_hash;
-        let old_chunk_data = OldChunkData {
-            prev_chunk_extra: chunk_extra.clone(),
-            resharding_state_roots: None,
-            block,
-            storage_context: StorageContext {
-                storage_data_source: StorageDataSource::Recorded(PartialStorage {
-                    nodes: transition.base_state,
-                }),
-                state_patch: Default::default(),
-                record_storage: false,
-            },
-        };
-        let OldChunkResult { apply_result, .. } = apply_old_chunk(
-            &span,
-            old_chunk_data,
-            ShardContext {
-                // Consider other shard uid in case of resharding.
-                shard_uid,
-                cares_about_shard_this_epoch: true,
-                will_shard_layout_change: false,
-                should_apply_chunk: false,
-                need_to_reshard: false,
-            },
-            runtime_adapter,
-            epoch_manager,
-        )?;
-        *chunk_extra.state_root_mut() = apply_result.new_root;
-        if chunk_extra.state_root() != &transition.post_state_root {
-            // This is an early check, it's not for correctness, only for better
-            // error reporting in case of an invalid state witness due to a bug.
-            // Only the final state root check against the chunk header is required.
-            return Err(Error::InvalidChunkStateWitness(format!(
-                "Post state root {:?} for implicit transition at block {:?}, does not match expected state root {:?}",
-                chunk_extra.state_root(), block_hash, transition.post_state_root
-            )));
+    let mut post_state_roots = vec![TransitionPostStateRoot {
+        block_hash,
+        expected: state_witness.main_state_transition.post_state_root,
+        actual: *chunk_extra.state_root(),
+    }];
+
+    if chunk_extra.state_root() == &state_witness.main_state_transition.post_state_root {
+        for (block, transition) in pre_validation_output
+            .implicit_transition_params
+            .into_iter()
+            .zip(state_witness.implicit_transitions.into_iter())
+        {
+            let block_hash = block.block_hash;
+            let old_chunk_data = OldChunkData {
+                prev_chunk_extra: chunk_extra.clone(),
+                resharding_state_roots: None,
+                block,
+                storage_context: StorageContext {
+                    storage_data_source: StorageDataSource::Recorded(PartialStorage {
+                        nodes: transition.base_state,
+                    }),
+                    state_patch: Default::default(),
+                    record_storage: false,
+                },
+            };
+            let OldChunkResult { apply_result, .. } = apply_old_chunk(
+                &span,
+                old_chunk_data,
+                ShardContext {
+                    // Consider other shard uid in case of resharding.
+                    shard_uid,
+                    cares_about_shard_this_epoch: true,
+                    will_shard_layout_change: false,
+                    should_apply_chunk: false,
+                    need_to_reshard: false,
+                },
+                runtime_adapter,
+                epoch_manager,
+            )?;
+            *chunk_extra.state_root_mut() = apply_result.new_root;
+            post_state_roots.push(TransitionPostStateRoot {
+                block_hash,
+                expected: transition.post_state_root,
+                actual: apply_result.new_root,
+            });
+            if apply_result.new_root != transition.post_state_root {
+                break;
+            }
         }
     }
 
-    // Finally, verify that the newly proposed chunk matches everything we have computed.
     let outgoing_receipts_hashes = {
         let shard_layout = epoch_manager
             .get_shard_layout_from_prev_block(state_witness.chunk_header.prev_block_hash())?;
         Chain::build_receipts_hashes(&outgoing_receipts, &shard_layout)
     };
     let (outgoing_receipts_root, _) = merklize(&outgoing_receipts_hashes);
+    Ok(AppliedStateWitness {
+        post_state_roots,
+        chunk_extra,
+        outgoing_receipts,
+        outgoing_receipts_root,
+    })
+}
+
+pub fn validate_chunk_state_witness(
+    state_witness: ChunkStateWitnessInner,
+    pre_validation_output: PreValidationOutput,
+    epoch_manager: &dyn EpochManagerAdapter,
+    runtime_adapter: &dyn RuntimeAdapter,
+) -> Result<(), Error> {
+    let _timer = metrics::CHUNK_STATE_WITNESS_VALIDATION_TIME
+        .with_label_values(&[&state_witness.chunk_header.shard_id().to_string()])
+        .start_timer();
+    let chunk_header = state_witness.chunk_header.clone();
+    let applied = apply_chunk_state_witness(
+        state_witness,
+        pre_validation_output,
+        epoch_manager,
+        runtime_adapter,
+    )?;
+
+    // This is an early check, it's not for correctness, only for better
+    // error reporting in case of an invalid state witness due to a bug.
+    // Only the final state root check against the chunk header is required.
+    match applied.first_post_state_root_mismatch() {
+        Some((0, root)) => {
+            return Err(Error::InvalidChunkStateWitness(format!(
+                "Post state root {:?} for main transition does not match expected post state root {:?}",
+                root.actual, root.expected,
+            )));
+        }
+        Some((_, root)) => {
+            return Err(Error::InvalidChunkStateWitness(format!(
+                "Post state root {:?} for implicit transition at block {:?}, does not match expected state root {:?}",
+                root.actual, root.block_hash, root.expected
+            )));
+        }
+        None => {}
+    }
+
+    // Finally, verify that the newly proposed chunk matches everything we have computed.
     validate_chunk_with_chunk_extra_and_receipts_root(
-        &chunk_extra,
-        &state_witness.chunk_header,
-        &outgoing_receipts_root,
+        &applied.chunk_extra,
+        &chunk_header,
+        &applied.outgoing_receipts_root,
     )?;
 
     Ok(())
@@ -615,7 +778,13 @@ pub(crate) fn send_chunk_endorsement_to_block_producers(
         "Chunk validated successfully, sending endorsement",
     );
 
-    let endorsement = ChunkEndorsement::new(chunk_header.chunk_hash(), signer);
+    let endorsement = match ChunkEndorsement::new(chunk_header.chunk_hash(), signer) {
+        Ok(endorsement) => endorsement,
+        Err(err) => {
+            tracing::error!(target: "stateless_validation", ?chunk_hash, ?err, "Failed to sign chunk endorsement");
+            return;
+        }
+    };
     for block_producer in block_producers {
         if signer.validator_id() == &block_producer {
             // Unwrap here as we always expect our own endorsements to be valid
@@ -673,4 +842,134 @@ impl Client {
 
         self.chunk_validator.start_validating_chunk(witness, &self.chain, processing_done_tracker)
     }
+
+    /// Responds to a network request with a part of a `ChunkStateWitness`, sent by the chunk
+    /// producer to the chunk validator with the same index as the part. The part is forwarded
+    /// to the other chunk validators, who can't get it otherwise, and then stored like
+    /// a forwarded part.
+    pub fn process_partial_encoded_state_witness(
+        &mut self,
+        part: PartialEncodedStateWitness,
+        processing_done_tracker: Option<ProcessingDoneTracker>,
+    ) -> Result<(), Error> {
+        let my_signer = self.validator_signer.as_ref().ok_or(Error::NotAValidator)?.clone();
+        let chunk_validators = self
+            .epoch_manager
+            .get_chunk_validator_assignments(&part.epoch_id, part.shard_id, part.height_created)?
+            .ordered_chunk_validators();
+        // Only the part meant for us is forwarded, otherwise anyone could make us send
+        // arbitrary data to all the chunk validators.
+        if chunk_validators.get(part.part_ord as usize) != Some(my_signer.validator_id()) {
+            return Err(Error::InvalidChunkStateWitness(format!(
+                "Witness part {} is not meant for {}",
+                part.part_ord,
+                my_signer.validator_id()
+            )));
+        }
+        // The part is forwarded only once, so that resending it doesn't make us send it
+        // to all the chunk validators over and over again.
+        if self.chunk_validator.partial_witness_tracker.has_part(&part) {
+            return Ok(());
+        }
+        let (data_parts, total_parts) = witness_part_counts(chunk_validators.len());
+        PartialEncodedStateWitnessTracker::validate_part(&part, data_parts, total_parts)?;
+        self.verify_partial_encoded_state_witness_signature(&part)?;
+
+        // The chunk producer doesn't validate its own chunk, so it doesn't need the parts.
+        let chunk_producer = self.epoch_manager.get_chunk_producer(
+            &part.epoch_id,
+            part.height_created,
+            part.shard_id,
+        )?;
+        let targets = chunk_validators
+            .into_iter()
+            .filter(|validator| {
+                validator != my_signer.validator_id() && validator != &chunk_producer
+            })
+            .collect_vec();
+        if !targets.is_empty() {
+            self.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(
+                NetworkRequests::PartialEncodedStateWitnessForward(targets, part.clone()),
+            ));
+        }
+        self.store_partial_encoded_state_witness(
+            part,
+            data_parts,
+            total_parts,
+            processing_done_tracker,
+        )
+    }
+
+    /// Responds to a network request with a part of a `ChunkStateWitness`, forwarded by another
+    /// chunk validator. `sender_accounts` are the accounts of the node which has sent the part,
+    /// it has to be the chunk validator with the same index as the part. Once enough parts are
+    /// collected, the witness is reconstructed and processed like a `ChunkStateWitness`
+    /// received as a whole.
+    pub fn process_partial_encoded_state_witness_forward(
+        &mut self,
+        part: PartialEncodedStateWitness,
+        sender_accounts: &[AccountId],
+        processing_done_tracker: Option<ProcessingDoneTracker>,
+    ) -> Result<(), Error> {
+        let my_signer = self.validator_signer.as_ref().ok_or(Error::NotAValidator)?.clone();
+        let chunk_validator_assignments = self.epoch_manager.get_chunk_validator_assignments(
+            &part.epoch_id,
+            part.shard_id,
+            part.height_created,
+        )?;
+        if !chunk_validator_assignments.contains(my_signer.validator_id()) {
+            return Err(Error::NotAChunkValidator);
+        }
+        let chunk_validators = chunk_validator_assignments.ordered_chunk_validators();
+        match chunk_validators.get(part.part_ord as usize) {
+            Some(forwarder) if sender_accounts.contains(forwarder) => {}
+            forwarder => {
+                return Err(Error::InvalidChunkStateWitness(format!(
+                    "Witness part {} is expected to be forwarded by {:?}, but was sent by {:?}",
+                    part.part_ord, forwarder, sender_accounts
+                )));
+            }
+        }
+        if self.chunk_validator.partial_witness_tracker.has_part(&part) {
+            return Ok(());
+        }
+        let (data_parts, total_parts) = witness_part_counts(chunk_validators.len());
+        self.verify_partial_encoded_state_witness_signature(&part)?;
+        self.store_partial_encoded_state_witness(
+            part,
+            data_parts,
+            total_parts,
+            processing_done_tracker,
+        )
+    }
+
+    fn verify_partial_encoded_state_witness_signature(
+        &self,
+        part: &PartialEncodedStateWitness,
+    ) -> Result<(), Error> {
+        if !self.epoch_manager.verify_partial_encoded_state_witness_signature(part)? {
+            return Err(Error::InvalidChunkStateWitness(
+                "Invalid signature of a witness part".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    fn store_partial_encoded_state_witness(
+        &mut self,
+        part: PartialEncodedStateWitness,
+        data_parts: usize,
+        total_parts: usize,
+        processing_done_tracker: Option<ProcessingDoneTracker>,
+    ) -> Result<(), Error> {
+        let Some(witness) = self.chunk_validator.partial_witness_tracker.store_part(
+            part,
+            data_parts,
+            total_parts,
+        )?
+        else {
+            return Ok(());
+        };
+        self.process_chunk_state_witness(witness, processing_done_tracker)
+    }
 }
@@ -0,0 +1,288 @@
+use bytesize::ByteSize;
+use lru::LruCache;
+use near_chain_primitives::Error;
+use near_primitives::hash::CryptoHash;
+use near_primitives::stateless_validation::{ChunkStateWitness, PartialEncodedStateWitness};
+use near_primitives::types::{BlockHeight, ShardId};
+use std::collections::HashMap;
+
+/// Number of chunks for which the parts are kept at the same time.
+/// Parts of a witness normally arrive within a fraction of a second, so only
+/// the most recent witnesses have to be kept.
+const DEFAULT_PARTIAL_WITNESS_TRACKER_CAPACITY: usize = 32;
+
+/// Number of different witnesses for which the parts are collected per chunk.
+/// The parts are signed by the chunk producer, so only the chunk producer can make
+/// more than one witness for its chunk, e.g. by producing the chunk on different forks.
+const MAX_WITNESSES_PER_CHUNK: usize = 2;
+
+/// Witnesses longer than this are rejected before any memory is allocated for their parts.
+/// The same as the limit on the size of a network message, which a whole witness used to fit.
+pub const MAX_WITNESS_ENCODED_LENGTH: ByteSize = ByteSize::mib(512);
+
+/// Parts are grouped by the shard and height of the chunk, which determine its chunk producer.
+type CacheKey = (ShardId, BlockHeight);
+
+/// Parts of a chunk are grouped by the merkle root of all the parts and the encoded length
+/// of the witness, both signed by the chunk producer.
+type WitnessKey = (CryptoHash, u64);
+
+enum CacheEntry {
+    /// Parts received so far, indexed by their ordinals.
+    Collecting { parts: Vec<Option<Box<[u8]>>>, num_parts: usize },
+    /// The witness has already been reconstructed, the remaining parts are not needed.
+    Decoded,
+}
+
+/// `PartialEncodedStateWitnessTracker` collects the parts of chunk state witnesses distributed
+/// as `PartialEncodedStateWitness`es, until there are enough of them to reconstruct the witness.
+/// The signature of the chunk producer has to be checked before the parts are stored, see
+/// `EpochManagerAdapter::verify_partial_encoded_state_witness_signature`.
+pub struct PartialEncodedStateWitnessTracker {
+    parts_cache: LruCache<CacheKey, HashMap<WitnessKey, CacheEntry>>,
+}
+
+impl PartialEncodedStateWitnessTracker {
+    pub fn new(cache_capacity: usize) -> Self {
+        PartialEncodedStateWitnessTracker { parts_cache: LruCache::new(cache_capacity) }
+    }
+
+    /// Checks the part without storing it. `data_parts` and `total_parts` are the number
+    /// of parts needed to reconstruct the witness and the number of all the parts, as
+    /// returned by `witness_part_counts` for the chunk validators of the chunk.
+    pub fn validate_part(
+        part: &PartialEncodedStateWitness,
+        data_parts: usize,
+        total_parts: usize,
+    ) -> Result<(), Error> {
+        if part.part_ord as usize >= total_parts {
+            return Err(Error::InvalidChunkStateWitness(format!(
+                "Invalid part ordinal {} for a witness with {} parts",
+                part.part_ord, total_parts
+            )));
+        }
+        if part.encoded_length == 0 || part.encoded_length > MAX_WITNESS_ENCODED_LENGTH.as_u64() {
+            return Err(Error::InvalidChunkStateWitness(format!(
+                "Invalid encoded length of a witness {}",
+                part.encoded_length
+            )));
+        }
+        let part_length = PartialEncodedStateWitness::part_length(part.encoded_length, data_parts);
+        if part.part.len() as u64 != part_length {
+            return Err(Error::InvalidChunkStateWitness(format!(
+                "Invalid length of a witness part {}, expected {}",
+                part.part.len(),
+                part_length
+            )));
+        }
+        if !part.verify_merkle_proof() {
+            return Err(Error::InvalidChunkStateWitness(
+                "Invalid merkle proof of a witness part".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Returns true if the part has already been stored, or isn't needed anymore
+    /// because the witness has already been reconstructed.
+    pub fn has_part(&self, part: &PartialEncodedStateWitness) -> bool {
+        let Some(witnesses) = self.parts_cache.peek(&(part.shard_id, part.height_created)) else {
+            return false;
+        };
+        match witnesses.get(&(part.parts_root, part.encoded_length)) {
+            Some(CacheEntry::Collecting { parts, .. }) => {
+                parts.get(part.part_ord as usize).map_or(false, Option::is_some)
+            }
+            Some(CacheEntry::Decoded) => true,
+            None => false,
+        }
+    }
+
+    /// Adds the part to the tracker, see `validate_part` for the meaning of the arguments.
+    /// Returns the witness if this part made it possible to reconstruct it. The witness
+    /// is returned only once, the parts arriving after that are ignored.
+    pub fn store_part(
+        &mut self,
+        part: PartialEncodedStateWitness,
+        data_parts: usize,
+        total_parts: usize,
+    ) -> Result<Option<ChunkStateWitness>, Error> {
+        Self::validate_part(&part, data_parts, total_parts)?;
+
+        let part_ord = part.part_ord as usize;
+        let encoded_length = part.encoded_length;
+        let cache_key = (part.shard_id, part.height_created);
+        if !self.parts_cache.contains(&cache_key) {
+            self.parts_cache.put(cache_key, HashMap::new());
+        }
+        let witnesses = self.parts_cache.get_mut(&cache_key).unwrap();
+        let witness_key = (part.parts_root, encoded_length);
+        if !witnesses.contains_key(&witness_key) {
+            if witnesses.len() >= MAX_WITNESSES_PER_CHUNK {
+                return Err(Error::InvalidChunkStateWitness(format!(
+                    "Too many different witnesses for the chunk at height {} of shard {}",
+                    part.height_created, part.shard_id
+                )));
+            }
+            let entry = CacheEntry::Collecting { parts: vec![None; total_parts], num_parts: 0 };
+            witnesses.insert(witness_key, entry);
+        }
+        let entry = witnesses.get_mut(&witness_key).unwrap();
+        let CacheEntry::Collecting { parts, num_parts } = entry else {
+            return Ok(None);
+        };
+        if parts.len() != total_parts {
+            return Err(Error::InvalidChunkStateWitness(format!(
+                "Expected {} parts of the witness, but previous parts had {}",
+                total_parts,
+                parts.len()
+            )));
+        }
+        if parts[part_ord].is_some() {
+            return Ok(None);
+        }
+        parts[part_ord] = Some(part.part);
+        *num_parts += 1;
+        if *num_parts < data_parts {
+            return Ok(None);
+        }
+
+        let mut parts = std::mem::take(parts);
+        *entry = CacheEntry::Decoded;
+        let witness = PartialEncodedStateWitness::decode(&mut parts, data_parts, encoded_length)
+            .map_err(|err| {
+                Error::InvalidChunkStateWitness(format!("Failed to decode witness parts: {}", err))
+            })?;
+        Ok(Some(witness))
+    }
+
+    /// Removes the parts of all witnesses below the given height. They won't be needed anymore.
+    pub fn remove_parts_below_final_height(&mut self, final_height: BlockHeight) {
+        let to_remove: Vec<CacheKey> = self
+            .parts_cache
+            .iter()
+            .filter(|((_, height), _)| *height < final_height)
+            .map(|(cache_key, _)| *cache_key)
+            .collect();
+        for cache_key in to_remove {
+            self.parts_cache.pop(&cache_key);
+        }
+    }
+}
+
+impl Default for PartialEncodedStateWitnessTracker {
+    fn default() -> PartialEncodedStateWitnessTracker {
+        PartialEncodedStateWitnessTracker::new(DEFAULT_PARTIAL_WITNESS_TRACKER_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PartialEncodedStateWitnessTracker, MAX_WITNESS_ENCODED_LENGTH};
+    use near_primitives::hash::hash;
+    use near_primitives::stateless_validation::{
+        witness_part_counts, ChunkStateWitness, PartialEncodedStateWitness,
+    };
+    use near_primitives::test_utils::create_test_signer;
+    use near_primitives::types::EpochId;
+
+    fn encode(
+        witness: &ChunkStateWitness,
+        num_validators: usize,
+    ) -> Vec<PartialEncodedStateWitness> {
+        let signer = create_test_signer("test");
+        PartialEncodedStateWitness::encode(EpochId::default(), witness, num_validators, &signer)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_reconstruct_from_subset_of_parts() {
+        let witness = ChunkStateWitness::new_dummy(10, 0, hash(b"prev"));
+        let parts = encode(&witness, 9);
+        let (data_parts, total_parts) = witness_part_counts(9);
+        assert_eq!(data_parts, 3);
+
+        let mut tracker = PartialEncodedStateWitnessTracker::default();
+        // Duplicates don't count towards the parts needed.
+        assert_eq!(tracker.store_part(parts[8].clone(), data_parts, total_parts).unwrap(), None);
+        assert_eq!(tracker.store_part(parts[8].clone(), data_parts, total_parts).unwrap(), None);
+        assert_eq!(tracker.store_part(parts[2].clone(), data_parts, total_parts).unwrap(), None);
+        assert_eq!(
+            tracker.store_part(parts[5].clone(), data_parts, total_parts).unwrap(),
+            Some(witness)
+        );
+        // The witness is returned only once.
+        assert_eq!(tracker.store_part(parts[0].clone(), data_parts, total_parts).unwrap(), None);
+    }
+
+    #[test]
+    fn test_forged_parts() {
+        let witness = ChunkStateWitness::new_dummy(10, 0, hash(b"prev"));
+        let other_witness = ChunkStateWitness::new_dummy(10, 0, hash(b"other"));
+        let parts = encode(&witness, 6);
+        let other_parts = encode(&other_witness, 6);
+        let (data_parts, total_parts) = witness_part_counts(6);
+
+        let mut tracker = PartialEncodedStateWitnessTracker::default();
+        let mut forged = parts[0].clone();
+        forged.part[0] ^= 1;
+        assert!(tracker.store_part(forged, data_parts, total_parts).is_err());
+        let mut forged = parts[0].clone();
+        forged.part_ord = total_parts as u64;
+        assert!(tracker.store_part(forged, data_parts, total_parts).is_err());
+
+        // Parts of a different witness for the same chunk are collected separately.
+        assert_eq!(
+            tracker.store_part(other_parts[0].clone(), data_parts, total_parts).unwrap(),
+            None
+        );
+        assert_eq!(tracker.store_part(parts[1].clone(), data_parts, total_parts).unwrap(), None);
+        assert_eq!(
+            tracker.store_part(parts[4].clone(), data_parts, total_parts).unwrap(),
+            Some(witness)
+        );
+    }
+
+    #[test]
+    fn test_adversarial_parts() {
+        let witness = ChunkStateWitness::new_dummy(10, 0, hash(b"prev"));
+        let parts = encode(&witness, 6);
+        let (data_parts, total_parts) = witness_part_counts(6);
+        let mut tracker = PartialEncodedStateWitnessTracker::default();
+
+        // The encoded length has to match the length of the parts, and is checked
+        // before anything is allocated for the witness.
+        let mut forged = parts[0].clone();
+        forged.encoded_length = MAX_WITNESS_ENCODED_LENGTH.as_u64() + 1;
+        assert!(tracker.store_part(forged, data_parts, total_parts).is_err());
+        let mut forged = parts[0].clone();
+        forged.encoded_length += (data_parts * 100) as u64;
+        assert!(tracker.store_part(forged, data_parts, total_parts).is_err());
+        let mut forged = parts[0].clone();
+        forged.part = forged.part[1..].into();
+        assert!(tracker.store_part(forged, data_parts, total_parts).is_err());
+        assert!(!tracker.has_part(&parts[0]));
+
+        // Only a couple of witnesses are collected for the same chunk.
+        for i in 0..2 {
+            let other_witness = ChunkStateWitness::new_dummy(10, 0, hash(&[i]));
+            let other_part = encode(&other_witness, 6).swap_remove(0);
+            assert_eq!(tracker.store_part(other_part, data_parts, total_parts).unwrap(), None);
+        }
+        assert!(tracker.store_part(parts[0].clone(), data_parts, total_parts).is_err());
+        // Parts of other chunks are still collected.
+        let next_witness = ChunkStateWitness::new_dummy(11, 0, hash(b"prev"));
+        let next_parts = encode(&next_witness, 6);
+        assert_eq!(
+            tracker.store_part(next_parts[0].clone(), data_parts, total_parts).unwrap(),
+            None
+        );
+        assert!(tracker.has_part(&next_parts[0]));
+
+        // Once the height is final, the parts are dropped.
+        tracker.remove_parts_below_final_height(11);
+        assert_eq!(tracker.store_part(parts[0].clone(), data_parts, total_parts).unwrap(), None);
+        assert!(tracker.has_part(&parts[0]));
+        assert!(tracker.has_part(&next_parts[0]));
+    }
+}
@@ -10,9 +10,11 @@ use near_primitives::hash::{hash, CryptoHash};
 use near_primitives::receipt::Receipt;
 use near_primitives::sharding::{ChunkHash, ReceiptProof, ShardChunk, ShardChunkHeader};
 use near_primitives::stateless_validation::{
-    ChunkStateTransition, ChunkStateWitness, ChunkStateWitnessInner, StoredChunkStateTransitionData,
+    ChunkStateTransition, ChunkStateWitness, ChunkStateWitnessInner, PartialEncodedStateWitness,
+    StoredChunkStateTransitionData,
 };
-use near_primitives::types::EpochId;
+use near_primitives::types::{AccountId, EpochId};
+use near_primitives::validator_signer::{signer_for_epoch, ValidatorSigner};
 use std::collections::HashMap;
 
 use crate::stateless_validation::chunk_validator::send_chunk_endorsement_to_block_producers;
@@ -44,7 +46,8 @@ impl Client {
             )?
             .ordered_chunk_validators();
 
-        let my_signer = self.validator_signer.as_ref().ok_or(Error::NotAValidator)?.clone();
+        let my_signer =
+            signer_for_epoch(self.validator_signer.as_ref().ok_or(Error::NotAValidator)?, epoch_id);
         let witness = {
             let witness_inner = self.create_state_witness_inner(
                 prev_block_header,
@@ -52,7 +55,9 @@ impl Client {
                 chunk,
                 transactions_storage_proof,
             )?;
-            let (signature, witness_size) = my_signer.sign_chunk_state_witness(&witness_inner);
+            let (signature, witness_size) = my_signer
+                .sign_chunk_state_witness(&witness_inner)
+                .map_err(|err| Error::Other(err.to_string()))?;
             metrics::CHUNK_STATE_WITNESS_TOTAL_SIZE
                 .with_label_values(&[&chunk_header.shard_id().to_string()])
                 .observe(witness_size as f64);
@@ -68,7 +73,22 @@ impl Client {
                 &self.network_adapter.clone().into_sender(),
                 self.chunk_endorsement_tracker.as_ref(),
             );
+            self.validator_performance.record_endorsement(
+                chunk_header.height_created(),
+                chunk_header.shard_id(),
+                &chunk_header.chunk_hash(),
+                None,
+            );
+        }
+        if checked_feature!("stable", PartialEncodedStateWitness, protocol_version) {
+            return self.send_partial_encoded_state_witness(
+                epoch_id,
+                chunk_validators,
+                my_signer.as_ref(),
+                &witness,
+            );
         }
+
         // Remove ourselves from the list of chunk validators. Network can't send messages to ourselves.
         chunk_validators.retain(|validator| validator != my_signer.validator_id());
 
@@ -84,6 +104,54 @@ impl Client {
         Ok(())
     }
 
+    /// Encodes the witness into parts and sends the `i`-th part to the `i`-th chunk validator,
+    /// which forwards it to the other chunk validators.
+    fn send_partial_encoded_state_witness(
+        &self,
+        epoch_id: &EpochId,
+        chunk_validators: Vec<AccountId>,
+        my_signer: &dyn ValidatorSigner,
+        witness: &ChunkStateWitness,
+    ) -> Result<(), Error> {
+        let my_account_id = my_signer.validator_id();
+        let parts = PartialEncodedStateWitness::encode(
+            epoch_id.clone(),
+            witness,
+            chunk_validators.len(),
+            my_signer,
+        )?;
+        let mut requests = Vec::with_capacity(parts.len());
+        for (chunk_validator, part) in chunk_validators.iter().zip(parts) {
+            if chunk_validator != my_account_id {
+                requests.push((chunk_validator.clone(), part));
+                continue;
+            }
+            // Network can't send messages to ourselves, and we don't need the witness anyway.
+            // Our part is still needed by the other chunk validators, so we forward it ourselves.
+            let targets = chunk_validators
+                .iter()
+                .filter(|validator| *validator != my_account_id)
+                .cloned()
+                .collect::<Vec<_>>();
+            if !targets.is_empty() {
+                self.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(
+                    NetworkRequests::PartialEncodedStateWitnessForward(targets, part),
+                ));
+            }
+        }
+
+        tracing::debug!(
+            target: "stateless_validation",
+            "Sending parts of chunk state witness for chunk {:?} to chunk validators {:?}",
+            witness.inner.chunk_header.chunk_hash(),
+            chunk_validators,
+        );
+        self.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(
+            NetworkRequests::PartialEncodedStateWitness(requests),
+        ));
+        Ok(())
+    }
+
     pub(crate) fn create_state_witness_inner(
         &mut self,
         prev_block_header: &BlockHeader,
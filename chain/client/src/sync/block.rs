@@ -354,6 +354,7 @@ mod test {
                     id: PeerId::new(PublicKey::empty(KeyType::ED25519)),
                     addr: None,
                     account_id: None,
+                    extra_addrs: vec![],
                 },
                 genesis_id: Default::default(),
                 highest_block_height: 0,
@@ -1,52 +1,50 @@
+use near_async::messaging::CanSend;
 use near_async::time::{Clock, Duration, Utc};
-use near_network::types::PeerManagerAdapter;
-use near_primitives::hash::CryptoHash;
+use near_chain::Chain;
+#[cfg(feature = "new_epoch_sync")]
+use near_chain_primitives::error::epoch_sync::EpochSyncInfoError;
+use near_client_primitives::types::SyncStatus;
+#[cfg(feature = "new_epoch_sync")]
+use near_network::types::EpochSyncInfoResponse;
+#[cfg(feature = "new_epoch_sync")]
+use near_network::types::ReasonForBan;
+use near_network::types::{
+    HighestHeightPeerInfo, NetworkRequests, PeerManagerAdapter, PeerManagerMessageRequest,
+};
 use near_primitives::network::PeerId;
-use near_primitives::types::validator_stake::ValidatorStake;
-use near_primitives::types::EpochId;
-use std::collections::{HashMap, HashSet};
+use near_primitives::types::{BlockHeight, EpochId};
+use rand::seq::IteratorRandom;
+use std::collections::HashSet;
+use tracing::debug;
 
-/// Helper to keep track of the Epoch Sync
-// TODO #3488
-#[allow(dead_code)]
+/// Helper to keep track of the Epoch Sync.
+///
+/// A node starting from genesis requests `EpochSyncInfo`s of finalised epochs from its peers,
+/// a batch of consecutive epochs at a time. Each of them is verified against the validators
+/// known from the previous epoch and recorded, which moves the header head to the last block
+/// of the epoch. A peer without the requested epoch may simply be behind, so the epoch is
+/// requested from another peer, until the header head is within an epoch of the highest
+/// peers or none of the peers has the epoch. Then header sync continues from the header head.
 pub struct EpochSync {
     clock: Clock,
     network_adapter: PeerManagerAdapter,
-    /// Datastructure to keep track of when the last request to each peer was made.
-    /// Peers do not respond to Epoch Sync requests more frequently than once per a certain time
-    /// interval, thus there's no point in requesting more frequently.
-    peer_to_last_request_time: HashMap<PeerId, Utc>,
-    /// Tracks all the peers who have reported that we are already up to date
-    peers_reporting_up_to_date: HashSet<PeerId>,
-    /// The last epoch we are synced to
-    current_epoch_id: EpochId,
-    /// The next epoch id we need to sync
+    /// The next epoch id we need to sync.
     next_epoch_id: EpochId,
-    /// The block producers set to validate the light client block view for the next epoch
-    next_block_producers: Vec<ValidatorStake>,
-    /// The last epoch id that we have requested
-    requested_epoch_id: EpochId,
-    /// When and to whom was the last request made
+    /// When and to whom was the last request made.
+    /// `last_request_peer_id` is None if there is no request in flight.
     last_request_time: Utc,
     last_request_peer_id: Option<PeerId>,
-
-    /// How long to wait for a response before re-requesting the same light client block view
+    /// How long to wait for a response before re-requesting the same epoch from another peer.
     request_timeout: Duration,
-    /// How frequently to send request to the same peer
-    peer_timeout: Duration,
-
-    /// True, if all peers agreed that we're at the last Epoch.
-    /// Only finalization is needed.
-    have_all_epochs: bool,
+    /// Peers which responded without `next_epoch_id`. Reset once the epoch is synced.
+    peers_without_epoch: HashSet<PeerId>,
+    /// Peers which sent an invalid response. They are banned and never asked again.
+    bad_peers: HashSet<PeerId>,
+    /// Highest height of the peers, as of the last `run`.
+    highest_peer_height: BlockHeight,
     /// Whether the Epoch Sync was performed to completion previously.
     /// Current state machine allows for only one Epoch Sync.
     pub done: bool,
-
-    pub sync_hash: CryptoHash,
-
-    received_epoch: bool,
-
-    is_just_started: bool,
 }
 
 impl EpochSync {
@@ -54,29 +52,170 @@ impl EpochSync {
         clock: Clock,
         network_adapter: PeerManagerAdapter,
         genesis_epoch_id: EpochId,
-        genesis_next_epoch_id: EpochId,
-        first_epoch_block_producers: Vec<ValidatorStake>,
         request_timeout: Duration,
-        peer_timeout: Duration,
     ) -> Self {
         Self {
             clock: clock.clone(),
             network_adapter,
-            peer_to_last_request_time: HashMap::new(),
-            peers_reporting_up_to_date: HashSet::new(),
-            current_epoch_id: genesis_epoch_id.clone(),
-            next_epoch_id: genesis_next_epoch_id,
-            next_block_producers: first_epoch_block_producers,
-            requested_epoch_id: genesis_epoch_id,
+            next_epoch_id: genesis_epoch_id,
             last_request_time: clock.now_utc(),
             last_request_peer_id: None,
             request_timeout,
-            peer_timeout,
-            received_epoch: false,
-            have_all_epochs: false,
+            peers_without_epoch: HashSet::new(),
+            bad_peers: HashSet::new(),
+            highest_peer_height: 0,
             done: false,
-            sync_hash: CryptoHash::default(),
-            is_just_started: true,
         }
     }
+
+    /// Requests the next epoch from one of the peers, unless a request is already in flight.
+    /// Returns true while epoch sync is in progress, in which case other syncs should wait.
+    pub fn run(
+        &mut self,
+        sync_status: &mut SyncStatus,
+        chain: &Chain,
+        highest_height_peers: &[HighestHeightPeerInfo],
+    ) -> Result<bool, near_chain::Error> {
+        if self.done {
+            return Ok(false);
+        }
+        let header_head = chain.header_head()?;
+        if !matches!(sync_status, SyncStatus::EpochSync { .. })
+            && header_head.height != chain.genesis().height()
+        {
+            // Epoch sync can only start from genesis. A node which already has headers
+            // continues with header sync.
+            debug!(target: "sync", header_head_height = header_head.height, "Skipping epoch sync");
+            self.done = true;
+            return Ok(false);
+        }
+
+        // Without peers there is nothing to compare the header head with, keep waiting.
+        if let Some(height) =
+            highest_height_peers.iter().map(|peer| peer.highest_block_height).max()
+        {
+            self.highest_peer_height = height;
+            if self.is_header_head_current(chain)? {
+                self.finish();
+                return Ok(false);
+            }
+        }
+
+        let epoch_ord = chain.epoch_manager.get_epoch_info(&self.next_epoch_id)?.epoch_height();
+        sync_status.update(SyncStatus::EpochSync { epoch_ord });
+
+        let now = self.clock.now_utc();
+        if self.last_request_peer_id.is_some()
+            && now < self.last_request_time + self.request_timeout
+        {
+            return Ok(true);
+        }
+        let candidates: Vec<_> = highest_height_peers
+            .iter()
+            .filter(|peer| {
+                !self.bad_peers.contains(&peer.peer_info.id)
+                    && !self.peers_without_epoch.contains(&peer.peer_info.id)
+            })
+            .collect();
+        if candidates.is_empty() && !self.peers_without_epoch.is_empty() {
+            // None of the peers has the epoch, it is not finalised yet.
+            self.finish();
+            return Ok(false);
+        }
+        // Prefer a peer other than the one which didn't respond in time.
+        let peer = candidates
+            .iter()
+            .filter(|peer| Some(&peer.peer_info.id) != self.last_request_peer_id.as_ref())
+            .choose(&mut rand::thread_rng())
+            .or_else(|| candidates.iter().choose(&mut rand::thread_rng()));
+        if let Some(peer) = peer {
+            debug!(target: "sync", epoch_id = ?self.next_epoch_id, peer_id = ?peer.peer_info.id, "Requesting EpochSyncInfo");
+            self.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(
+                NetworkRequests::EpochSyncRequest {
+                    epoch_id: self.next_epoch_id.clone(),
+                    peer_id: peer.peer_info.id.clone(),
+                },
+            ));
+            self.last_request_time = now;
+            self.last_request_peer_id = Some(peer.peer_info.id.clone());
+        }
+        Ok(true)
+    }
+
+    /// Whether the header head is within an epoch of the highest peers, so there are no
+    /// more finalised epochs to sync.
+    fn is_header_head_current(&self, chain: &Chain) -> Result<bool, near_chain::Error> {
+        Ok(chain.header_head()?.height + chain.epoch_length >= self.highest_peer_height)
+    }
+
+    fn finish(&mut self) {
+        tracing::info!(target: "sync", epoch_id = ?self.next_epoch_id, "Epoch sync done");
+        self.done = true;
+        self.last_request_peer_id = None;
+    }
+
+    /// Verifies and records the epochs from the response of a peer.
+    /// A response without epochs means that the peer doesn't have the epoch, either because
+    /// it is not finalised yet or because the peer is behind. Unless the header head is
+    /// current, the epoch is requested from another peer by the next `run`.
+    /// A peer sending an invalid response is banned and the epoch is requested from another
+    /// peer by the next `run`, without waiting for the request timeout.
+    #[cfg(feature = "new_epoch_sync")]
+    pub fn on_response(
+        &mut self,
+        chain: &mut Chain,
+        response: EpochSyncInfoResponse,
+        peer_id: &PeerId,
+    ) -> Result<(), EpochSyncInfoError> {
+        if self.done || response.epoch_id != self.next_epoch_id {
+            debug!(target: "sync", epoch_id = ?response.epoch_id, ?peer_id, "Ignoring unexpected EpochSyncInfo response");
+            return Ok(());
+        }
+        // Whatever the response is, the next request shouldn't wait for the timeout.
+        self.last_request_peer_id = None;
+        if response.epoch_sync_infos.is_empty() {
+            if self.is_header_head_current(chain)? {
+                self.finish();
+            } else {
+                debug!(target: "sync", epoch_id = ?self.next_epoch_id, ?peer_id, "Peer doesn't have the epoch, trying another peer");
+                self.peers_without_epoch.insert(peer_id.clone());
+            }
+            return Ok(());
+        }
+        for epoch_sync_info in &response.epoch_sync_infos {
+            if let Err(err) = self.record_epoch_sync_info(chain, epoch_sync_info, peer_id) {
+                // IO errors are local, anything else is caused by the content of the response.
+                if !matches!(err, EpochSyncInfoError::IOErr(_)) {
+                    self.ban_peer(peer_id);
+                }
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "new_epoch_sync")]
+    fn record_epoch_sync_info(
+        &mut self,
+        chain: &mut Chain,
+        epoch_sync_info: &near_primitives::epoch_manager::epoch_sync::EpochSyncInfo,
+        peer_id: &PeerId,
+    ) -> Result<(), EpochSyncInfoError> {
+        chain.validate_and_record_epoch_sync_info(epoch_sync_info)?;
+        self.next_epoch_id = epoch_sync_info.get_next_epoch_id()?.clone();
+        self.peers_without_epoch.clear();
+        debug!(target: "sync", epoch_id = ?epoch_sync_info.get_epoch_id()?, ?peer_id, "Recorded EpochSyncInfo");
+        Ok(())
+    }
+
+    #[cfg(feature = "new_epoch_sync")]
+    fn ban_peer(&mut self, peer_id: &PeerId) {
+        self.bad_peers.insert(peer_id.clone());
+        self.network_adapter.send(PeerManagerMessageRequest::NetworkRequests(
+            NetworkRequests::BanPeer {
+                peer_id: peer_id.clone(),
+                ban_reason: ReasonForBan::BadEpochSyncInfo,
+            },
+        ));
+    }
 }
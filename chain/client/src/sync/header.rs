@@ -119,7 +119,6 @@ impl HeaderSync {
                 true
             }
             SyncStatus::NoSync | SyncStatus::AwaitingPeers | SyncStatus::EpochSync { .. } => {
-                // TODO: How can it get to EpochSync if it's hardcoded to go from NoSync to HeaderSync?
                 debug!(target: "sync", "Sync: initial transition to Header sync. Header head {} at {}",
                     header_head.last_block_hash, header_head.height,
                 );
@@ -196,11 +195,10 @@ impl HeaderSync {
 
         // Always enable header sync on initial state transition from
         // * NoSync
-        // * AwaitingPeers.
-        // TODO: Will this remain correct with the introduction of EpochSync?
-        // TODO: Shouldn't a node transition to EpochSync from these states?
+        // * AwaitingPeers
+        // * EpochSync.
         let force_sync = match sync_status {
-            SyncStatus::NoSync | SyncStatus::AwaitingPeers => true,
+            SyncStatus::NoSync | SyncStatus::AwaitingPeers | SyncStatus::EpochSync { .. } => true,
             _ => false,
         };
 
@@ -654,6 +652,7 @@ mod test {
                     id: PeerId::new(PublicKey::empty(KeyType::ED25519)),
                     addr: None,
                     account_id: None,
+                    extra_addrs: vec![],
                 },
                 genesis_id: Default::default(),
                 highest_block_height: 0,
@@ -787,6 +786,7 @@ mod test {
                                 this_height,
                                 signer.as_ref(),
                             )
+                            .unwrap()
                             .signature,
                         ))
                     })
@@ -801,7 +801,8 @@ mod test {
                 *last_block.header().next_bp_hash(),
                 block_merkle_tree.root(),
                 clock.now_utc(),
-            );
+            )
+            .unwrap();
             block_merkle_tree.insert(*block.hash());
             chain2.process_block_header(block.header(), &mut Vec::new()).unwrap(); // just to validate
             process_block_sync(
@@ -1495,7 +1495,12 @@ mod test {
         let public_key = secret_key.public_key();
         let peer_id = PeerId::new(public_key);
         let highest_height_peer_info = HighestHeightPeerInfo {
-            peer_info: PeerInfo { id: peer_id.clone(), addr: None, account_id: None },
+            peer_info: PeerInfo {
+                id: peer_id.clone(),
+                addr: None,
+                account_id: None,
+                extra_addrs: vec![],
+            },
             genesis_id: Default::default(),
             highest_block_height: chain.epoch_length + 10,
             highest_block_hash: Default::default(),
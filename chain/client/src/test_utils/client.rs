@@ -227,7 +227,8 @@ pub fn create_chunk(
     let mut block_merkle_tree = PartialMerkleTree::clone(&block_merkle_tree);
 
     let signer = client.validator_signer.as_ref().unwrap().clone();
-    let endorsement = ChunkEndorsement::new(chunk.cloned_header().chunk_hash(), signer.as_ref());
+    let endorsement =
+        ChunkEndorsement::new(chunk.cloned_header().chunk_hash(), signer.as_ref()).unwrap();
     block_merkle_tree.insert(*last_block.hash());
     let block = Block::produce(
         PROTOCOL_VERSION,
@@ -251,7 +252,8 @@ pub fn create_chunk(
         *last_block.header().next_bp_hash(),
         block_merkle_tree.root(),
         client.clock.now_utc(),
-    );
+    )
+    .unwrap();
     (
         ProduceChunkResult {
             chunk,
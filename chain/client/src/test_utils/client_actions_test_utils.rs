@@ -20,6 +20,11 @@ pub fn forward_client_messages_from_network_to_client_actions(
             ClientSenderForNetworkMessage::_block(msg) => {
                 (msg.callback)(Ok(client_actions.handle(msg.message)));
             }
+            ClientSenderForNetworkMessage::_block_request(msg) => {
+                // Served by the view client in production; answered from the chain directly here.
+                let block = client_actions.client.chain.get_block(&msg.message.0).ok();
+                (msg.callback)(Ok(block.map(Box::new)));
+            }
             ClientSenderForNetworkMessage::_block_headers(msg) => {
                 (msg.callback)(Ok(client_actions.handle(msg.message)));
             }
@@ -35,6 +40,15 @@ pub fn forward_client_messages_from_network_to_client_actions(
             ClientSenderForNetworkMessage::_chunk_endorsement(msg) => {
                 (msg.callback)(Ok(client_actions.handle(msg.message)));
             }
+            ClientSenderForNetworkMessage::_partial_encoded_state_witness(msg) => {
+                (msg.callback)(Ok(client_actions.handle(msg.message)));
+            }
+            ClientSenderForNetworkMessage::_partial_encoded_state_witness_forward(msg) => {
+                (msg.callback)(Ok(client_actions.handle(msg.message)));
+            }
+            ClientSenderForNetworkMessage::_epoch_sync_response(msg) => {
+                (msg.callback)(Ok(client_actions.handle(msg.message)));
+            }
             _ => {
                 return Err(msg);
             }
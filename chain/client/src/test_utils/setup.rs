@@ -551,6 +551,7 @@ pub fn setup_mock_all_validators(
                                 connection_established_time: near_async::time::Instant::now(),
                                 peer_type: PeerType::Outbound,
                                 nonce: 3,
+                                send_classes: vec![],
                             })
                             .collect();
                         let peers2 = peers
@@ -853,6 +854,7 @@ pub fn setup_mock_all_validators(
                         | NetworkRequests::BanPeer { .. }
                         | NetworkRequests::TxStatus(_, _, _)
                         | NetworkRequests::SnapshotHostInfo { .. }
+                        | NetworkRequests::EpochSyncRequest { .. }
                         | NetworkRequests::Challenge(_) => {}
                         NetworkRequests::ChunkStateWitness(_, _) => {
                             // TODO(#10265): Implement for integration tests.
@@ -860,6 +862,10 @@ pub fn setup_mock_all_validators(
                         NetworkRequests::ChunkEndorsement(_, _) => {
                             // TODO(#10265): Implement for integration tests.
                         },
+                        NetworkRequests::PartialEncodedStateWitness(_)
+                        | NetworkRequests::PartialEncodedStateWitnessForward(_, _) => {
+                            // TODO(#10265): Implement for integration tests.
+                        },
                     };
                 }
                 resp
@@ -946,7 +952,7 @@ pub fn setup_no_network_with_validity_period_and_no_epoch_sync(
 pub fn setup_client_with_runtime(
     clock: Clock,
     num_validator_seats: NumSeats,
-    account_id: Option<AccountId>,
+    validator_signer: Option<Arc<dyn ValidatorSigner>>,
     enable_doomslug: bool,
     network_adapter: PeerManagerAdapter,
     shards_manager_adapter: SynchronousShardsManagerAdapter,
@@ -959,8 +965,6 @@ pub fn setup_client_with_runtime(
     save_trie_changes: bool,
     snapshot_callbacks: Option<SnapshotCallbacks>,
 ) -> Client {
-    let validator_signer =
-        account_id.map(|x| Arc::new(create_test_signer(x.as_str())) as Arc<dyn ValidatorSigner>);
     let mut config = ClientConfig::test(
         true,
         10,
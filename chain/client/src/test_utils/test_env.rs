@@ -27,7 +27,9 @@ use near_primitives::epoch_manager::RngSeed;
 use near_primitives::errors::InvalidTxError;
 use near_primitives::hash::CryptoHash;
 use near_primitives::sharding::{ChunkHash, PartialEncodedChunk};
-use near_primitives::stateless_validation::{ChunkEndorsement, ChunkStateWitness};
+use near_primitives::stateless_validation::{
+    witness_part_counts, ChunkEndorsement, ChunkStateWitness, PartialEncodedStateWitness,
+};
 use near_primitives::test_utils::create_test_signer;
 use near_primitives::transaction::{Action, FunctionCallAction, SignedTransaction};
 use near_primitives::types::{AccountId, Balance, BlockHeight, EpochId, NumSeats, ShardId};
@@ -304,8 +306,38 @@ impl TestEnv {
         post_state_roots.len() >= 2
     }
 
-    /// Processes all state witnesses sent over the network. The function waits for the processing to finish,
-    /// so chunk endorsements are available immediately after this function returns.
+    /// Reconstructs the witness from its parts, as the chunk validators receiving them would.
+    fn decode_partial_encoded_state_witness(
+        &self,
+        parts: &[PartialEncodedStateWitness],
+    ) -> ChunkStateWitness {
+        let first_part = &parts[0];
+        let num_chunk_validators = self.clients[0]
+            .epoch_manager
+            .get_chunk_validator_assignments(
+                &first_part.epoch_id,
+                first_part.shard_id,
+                first_part.height_created,
+            )
+            .unwrap()
+            .assignments()
+            .len();
+        let (data_parts, total_parts) = witness_part_counts(num_chunk_validators);
+        let mut encoded_parts = vec![None; total_parts];
+        for part in parts {
+            encoded_parts[part.part_ord as usize] = Some(part.part.clone());
+        }
+        PartialEncodedStateWitness::decode(
+            &mut encoded_parts,
+            data_parts,
+            first_part.encoded_length,
+        )
+        .unwrap()
+    }
+
+    /// Processes all state witnesses sent over the network, either as a whole or as parts.
+    /// The function waits for the processing to finish, so chunk endorsements are available
+    /// immediately after this function returns.
     pub fn propagate_chunk_state_witnesses(
         &mut self,
         allow_errors: bool,
@@ -314,39 +346,102 @@ impl TestEnv {
             found_differing_post_state_root_due_to_state_transitions: false,
         };
         let mut witness_processing_done_waiters: Vec<ProcessingDoneWaiter> = Vec::new();
+        // Parts of the witnesses sent over the network, grouped by the merkle root of the parts.
+        let mut witness_parts: HashMap<CryptoHash, Vec<PartialEncodedStateWitness>> =
+            HashMap::new();
 
         let network_adapters = self.network_adapters.clone();
-        for network_adapter in network_adapters {
-            network_adapter.handle_filtered(|request| match request {
-                PeerManagerMessageRequest::NetworkRequests(NetworkRequests::ChunkStateWitness(
-                    account_ids,
-                    state_witness,
-                )) => {
-                    // Process chunk state witness for each client.
-                    for account_id in account_ids.iter() {
-                        let processing_done_tracker = ProcessingDoneTracker::new();
-                        witness_processing_done_waiters.push(processing_done_tracker.make_waiter());
-
-                        let processing_result =
-                            self.client(account_id).process_chunk_state_witness(
-                                state_witness.clone(),
-                                Some(processing_done_tracker),
+        // Chunk validators forward the parts they receive, so the parts have to be propagated
+        // until no more of them are sent.
+        loop {
+            let mut any_propagated = false;
+            for (client_idx, network_adapter) in network_adapters.iter().enumerate() {
+                let sender_account = self.get_client_id(client_idx).clone();
+                network_adapter.handle_filtered(|request| match request {
+                    PeerManagerMessageRequest::NetworkRequests(
+                        NetworkRequests::ChunkStateWitness(account_ids, state_witness),
+                    ) => {
+                        any_propagated = true;
+                        // Process chunk state witness for each client.
+                        for account_id in account_ids.iter() {
+                            let processing_done_tracker = ProcessingDoneTracker::new();
+                            witness_processing_done_waiters
+                                .push(processing_done_tracker.make_waiter());
+
+                            let processing_result =
+                                self.client(account_id).process_chunk_state_witness(
+                                    state_witness.clone(),
+                                    Some(processing_done_tracker),
+                                );
+                            if !allow_errors {
+                                processing_result.unwrap();
+                            }
+                        }
+
+                        // Update output.
+                        output.found_differing_post_state_root_due_to_state_transitions |=
+                            Self::found_differing_post_state_root_due_to_state_transitions(
+                                &state_witness,
                             );
-                        if !allow_errors {
-                            processing_result.unwrap();
+
+                        None
+                    }
+                    PeerManagerMessageRequest::NetworkRequests(
+                        NetworkRequests::PartialEncodedStateWitness(parts),
+                    ) => {
+                        any_propagated = true;
+                        for (account_id, part) in parts {
+                            let processing_done_tracker = ProcessingDoneTracker::new();
+                            witness_processing_done_waiters
+                                .push(processing_done_tracker.make_waiter());
+                            witness_parts.entry(part.parts_root).or_default().push(part.clone());
+
+                            let processing_result =
+                                self.client(&account_id).process_partial_encoded_state_witness(
+                                    part,
+                                    Some(processing_done_tracker),
+                                );
+                            if !allow_errors {
+                                processing_result.unwrap();
+                            }
                         }
+                        None
                     }
+                    PeerManagerMessageRequest::NetworkRequests(
+                        NetworkRequests::PartialEncodedStateWitnessForward(account_ids, part),
+                    ) => {
+                        any_propagated = true;
+                        witness_parts.entry(part.parts_root).or_default().push(part.clone());
+                        for account_id in account_ids.iter() {
+                            let processing_done_tracker = ProcessingDoneTracker::new();
+                            witness_processing_done_waiters
+                                .push(processing_done_tracker.make_waiter());
+
+                            let processing_result = self
+                                .client(account_id)
+                                .process_partial_encoded_state_witness_forward(
+                                    part.clone(),
+                                    std::slice::from_ref(&sender_account),
+                                    Some(processing_done_tracker),
+                                );
+                            if !allow_errors {
+                                processing_result.unwrap();
+                            }
+                        }
+                        None
+                    }
+                    _ => Some(request),
+                });
+            }
+            if !any_propagated {
+                break;
+            }
+        }
 
-                    // Update output.
-                    output.found_differing_post_state_root_due_to_state_transitions |=
-                        Self::found_differing_post_state_root_due_to_state_transitions(
-                            &state_witness,
-                        );
-
-                    None
-                }
-                _ => Some(request),
-            });
+        for parts in witness_parts.values() {
+            let state_witness = self.decode_partial_encoded_state_witness(parts);
+            output.found_differing_post_state_root_due_to_state_transitions |=
+                Self::found_differing_post_state_root_due_to_state_transitions(&state_witness);
         }
 
         // Wait for all state witnesses to be processed before returning.
@@ -572,7 +667,7 @@ impl TestEnv {
         self.clients[idx] = setup_client_with_runtime(
             self.clock.clone(),
             num_validator_seats,
-            Some(self.get_client_id(idx).clone()),
+            self.clients[idx].validator_signer.clone(),
             false,
             self.network_adapters[idx].clone().as_multi_sender(),
             self.shards_manager_adapters[idx].clone(),
@@ -16,7 +16,9 @@ use near_epoch_manager::{EpochManager, EpochManagerAdapter, EpochManagerHandle};
 use near_network::test_utils::MockPeerManagerAdapter;
 use near_parameters::RuntimeConfigStore;
 use near_primitives::epoch_manager::{AllEpochConfigTestOverrides, RngSeed};
+use near_primitives::test_utils::create_test_signer;
 use near_primitives::types::{AccountId, NumShards};
+use near_primitives::validator_signer::ValidatorSigner;
 use near_store::config::StateSnapshotType;
 use near_store::test_utils::create_test_store;
 use near_store::{NodeStorage, ShardUId, Store, StoreConfig, TrieConfig};
@@ -55,6 +57,7 @@ pub struct TestEnvBuilder {
     // random seed to be inject in each client according to AccountId
     // if not set, a default constant TEST_SEED will be injected
     seeds: HashMap<AccountId, RngSeed>,
+    validator_signers: HashMap<AccountId, Arc<dyn ValidatorSigner>>,
     archive: bool,
     save_trie_changes: bool,
     state_snapshot_enabled: bool,
@@ -83,6 +86,7 @@ impl TestEnvBuilder {
             network_adapters: None,
             num_shards: None,
             seeds,
+            validator_signers: HashMap::new(),
             archive: false,
             save_trie_changes: true,
             state_snapshot_enabled: false,
@@ -114,6 +118,14 @@ impl TestEnvBuilder {
         self
     }
 
+    /// Sets the validator signers of the clients with the same accounts. Other clients
+    /// sign with the test signers of their accounts.
+    pub fn validator_signers(mut self, signers: Vec<Arc<dyn ValidatorSigner>>) -> Self {
+        self.validator_signers =
+            signers.into_iter().map(|signer| (signer.validator_id().clone(), signer)).collect();
+        self
+    }
+
     /// Sets number of clients to given one.  To get [`AccountId`] used by the
     /// validator associated with the client the [`TestEnv::get_client_id`]
     /// method can be used.  Tests should not rely on any particular format of
@@ -463,6 +475,7 @@ impl TestEnvBuilder {
         let validators = self.validators;
         let num_validators = validators.len();
         let seeds = self.seeds;
+        let validator_signers = self.validator_signers;
         let epoch_managers = self.epoch_managers.unwrap();
         let shard_trackers = self.shard_trackers.unwrap();
         let runtimes = self.runtimes.unwrap();
@@ -502,6 +515,9 @@ impl TestEnvBuilder {
                         Some(seed) => *seed,
                         None => TEST_SEED,
                     };
+                    let validator_signer = validator_signers.get(&account_id).cloned().unwrap_or_else(|| {
+                        Arc::new(create_test_signer(account_id.as_str())) as Arc<dyn ValidatorSigner>
+                    });
                     let tries = runtime.get_tries();
                     let make_snapshot_callback = Arc::new(move |prev_block_hash, _epoch_height, shard_uids: Vec<ShardUId>, block| {
                         tracing::info!(target: "state_snapshot", ?prev_block_hash, "make_snapshot_callback");
@@ -520,7 +536,7 @@ impl TestEnvBuilder {
                     setup_client_with_runtime(
                         clock.clone(),
                         u64::try_from(num_validators).unwrap(),
-                        Some(account_id),
+                        Some(validator_signer),
                         false,
                         network_adapter.as_multi_sender(),
                         shards_manager_adapter,
@@ -77,7 +77,8 @@ fn test_bad_shard_id() {
         chunk.tx_root(),
         chunk.prev_validator_proposals().collect(),
         &validator_signer,
-    );
+    )
+    .unwrap();
     modified_chunk.height_included = 2;
     chunks[0] = ShardChunkHeader::V3(modified_chunk);
     block.mut_header().get_mut().inner_rest.chunk_headers_root =
@@ -0,0 +1,301 @@
+//! Node-local history of the duties of this node as a validator and their outcomes.
+//! Used for debug purposes only, the history is not persisted.
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Mutex;
+
+use near_async::time::Clock;
+use near_chain::Error;
+use near_primitives::block::Approval;
+use near_primitives::block_header::ApprovalInner;
+use near_primitives::hash::CryptoHash;
+use near_primitives::sharding::ChunkHash;
+use near_primitives::types::{BlockHeight, ShardId};
+use near_primitives::views::{
+    ApprovalOutcomeView, ValidatorDutiesView, ValidatorDutyOutcomeView, ValidatorPerformanceView,
+};
+
+use crate::Client;
+
+/// Number of the most recent heights for which the duties are kept.
+const VALIDATOR_PERFORMANCE_HISTORY_SIZE: usize = 1000;
+
+/// Keeps the duties of this node at the recent heights: producing blocks and chunks, endorsing
+/// chunks and sending approvals. Each duty is recorded with the time of the last attempt to
+/// perform it and the cause of the failure, if any.
+/// Chunks are endorsed asynchronously, so the tracker is shared with the chunk validator.
+pub struct ValidatorPerformanceTracker {
+    clock: Clock,
+    duties: Mutex<BTreeMap<BlockHeight, ValidatorDutiesView>>,
+    /// Heights at which this node is the block producer, but doesn't have enough approvals yet.
+    heights_waiting_for_approvals: Mutex<BTreeSet<BlockHeight>>,
+}
+
+impl ValidatorPerformanceTracker {
+    pub fn new(clock: Clock) -> Self {
+        Self {
+            clock,
+            duties: Mutex::new(BTreeMap::new()),
+            heights_waiting_for_approvals: Mutex::new(BTreeSet::new()),
+        }
+    }
+
+    fn update(&self, height: BlockHeight, f: impl FnOnce(&mut ValidatorDutiesView)) {
+        let mut duties = self.duties.lock().unwrap();
+        f(duties
+            .entry(height)
+            .or_insert_with(|| ValidatorDutiesView { height, ..Default::default() }));
+        while duties.len() > VALIDATOR_PERFORMANCE_HISTORY_SIZE {
+            duties.pop_first();
+        }
+    }
+
+    fn outcome(
+        &self,
+        shard_id: Option<ShardId>,
+        hash: Option<CryptoHash>,
+        failure_cause: Option<String>,
+    ) -> ValidatorDutyOutcomeView {
+        ValidatorDutyOutcomeView {
+            shard_id,
+            hash,
+            timestamp: self.clock.now_utc(),
+            included: None,
+            failure_cause,
+        }
+    }
+
+    pub fn record_block_produced(&self, height: BlockHeight, block_hash: CryptoHash) {
+        let outcome = self.outcome(None, Some(block_hash), None);
+        self.update(height, |duties| duties.block = Some(outcome));
+    }
+
+    /// Failures don't override the block which was already produced at this height.
+    pub fn record_block_failure(&self, height: BlockHeight, failure_cause: String) {
+        let outcome = self.outcome(None, None, Some(failure_cause));
+        self.update(height, |duties| {
+            if duties.block.as_ref().map_or(true, |block| block.hash.is_none()) {
+                duties.block = Some(outcome);
+            }
+        });
+    }
+
+    /// Sets whether this node, as the block producer at `height`, waits for more approvals to
+    /// produce the block.
+    pub fn set_waiting_for_approvals(&self, height: BlockHeight, waiting: bool) {
+        let mut heights = self.heights_waiting_for_approvals.lock().unwrap();
+        if waiting {
+            heights.insert(height);
+        } else {
+            heights.remove(&height);
+        }
+    }
+
+    /// Records the missing approvals as the failure for the heights which were still waiting for
+    /// them, once a block at `latest_known_height` is known and no block is produced below it.
+    pub fn record_abandoned_heights(&self, latest_known_height: BlockHeight) {
+        let abandoned = {
+            let mut heights = self.heights_waiting_for_approvals.lock().unwrap();
+            let remaining = heights.split_off(&(latest_known_height + 1));
+            std::mem::replace(&mut *heights, remaining)
+        };
+        for height in abandoned {
+            self.record_block_failure(height, "Not enough approvals".to_string());
+        }
+    }
+
+    pub fn record_chunk_produced(
+        &self,
+        height: BlockHeight,
+        shard_id: ShardId,
+        chunk_hash: &ChunkHash,
+    ) {
+        let outcome = self.outcome(Some(shard_id), Some(chunk_hash.0), None);
+        self.update(height, |duties| Self::set_shard_outcome(&mut duties.chunks, outcome));
+    }
+
+    pub fn record_chunk_failure(
+        &self,
+        height: BlockHeight,
+        shard_id: ShardId,
+        failure_cause: String,
+    ) {
+        let outcome = self.outcome(Some(shard_id), None, Some(failure_cause));
+        self.update(height, |duties| Self::set_shard_outcome(&mut duties.chunks, outcome));
+    }
+
+    /// Records the outcome of validating the chunk created at `height`. The endorsement is sent
+    /// only if `failure_cause` is None.
+    pub fn record_endorsement(
+        &self,
+        height: BlockHeight,
+        shard_id: ShardId,
+        chunk_hash: &ChunkHash,
+        failure_cause: Option<String>,
+    ) {
+        let outcome = self.outcome(Some(shard_id), Some(chunk_hash.0), failure_cause);
+        self.update(height, |duties| Self::set_shard_outcome(&mut duties.endorsements, outcome));
+    }
+
+    /// Records the approval for `approval.target_height`. `latency` is the pair of the actual
+    /// and the expected delays in milliseconds, if doomslug knows them.
+    pub fn record_approval(
+        &self,
+        approval: &Approval,
+        latency: Option<(u64, u64)>,
+        failure_cause: Option<String>,
+    ) {
+        let (parent_height, is_endorsement) = match approval.inner {
+            ApprovalInner::Endorsement(_) => (approval.target_height.saturating_sub(1), true),
+            ApprovalInner::Skip(parent_height) => (parent_height, false),
+        };
+        let outcome = ApprovalOutcomeView {
+            parent_height,
+            is_endorsement,
+            timestamp: self.clock.now_utc(),
+            latency_millis: latency.map(|(actual, _)| actual),
+            expected_latency_millis: latency.map(|(_, expected)| expected),
+            failure_cause,
+        };
+        self.update(approval.target_height, |duties| duties.approval = Some(outcome));
+    }
+
+    /// Replaces the outcome for the same shard, the latest attempt wins.
+    fn set_shard_outcome(
+        outcomes: &mut Vec<ValidatorDutyOutcomeView>,
+        outcome: ValidatorDutyOutcomeView,
+    ) {
+        outcomes.retain(|existing| existing.shard_id != outcome.shard_id);
+        outcomes.push(outcome);
+        outcomes.sort_by_key(|outcome| outcome.shard_id);
+    }
+
+    /// Returns the recorded duties, ordered by height from high to low.
+    fn get_duties(&self) -> Vec<ValidatorDutiesView> {
+        self.duties.lock().unwrap().values().rev().cloned().collect()
+    }
+}
+
+impl Client {
+    /// Records the approval together with the delays doomslug observed for it.
+    pub(crate) fn record_approval_performance(
+        &self,
+        approval: &Approval,
+        failure_cause: Option<String>,
+    ) {
+        let latency = self
+            .doomslug
+            .get_approval_history_entry(approval.target_height)
+            .map(|entry| (entry.timer_started_ago_millis, entry.expected_delay_millis));
+        self.validator_performance.record_approval(approval, latency, failure_cause);
+    }
+
+    /// Returns the recent duties of this node, with the inclusion of the produced blocks and
+    /// chunks checked against the canonical chain.
+    pub fn get_validator_performance(&self) -> Result<ValidatorPerformanceView, Error> {
+        let head_height = self.chain.head()?.height;
+        let tail_height = self.chain.tail()?;
+        let mut heights = self.validator_performance.get_duties();
+        for duties in &mut heights {
+            // The inclusion is unknown for the heights which are not processed yet or which are
+            // already garbage collected.
+            if duties.height > head_height || duties.height <= tail_height {
+                continue;
+            }
+            let block_hash = self.chain.get_block_hash_by_height(duties.height).ok();
+            if let Some(outcome) = &mut duties.block {
+                if let Some(produced_hash) = outcome.hash {
+                    outcome.included = Some(block_hash == Some(produced_hash));
+                }
+            }
+            if duties.chunks.iter().all(|outcome| outcome.hash.is_none()) {
+                continue;
+            }
+            // If the block at this height is skipped, the chunk can still be included by the
+            // next block, as long as it is built on the same previous block.
+            let next_block = (duties.height..=head_height)
+                .find_map(|height| self.chain.get_block_hash_by_height(height).ok())
+                .map(|block_hash| self.chain.get_block(&block_hash))
+                .transpose()?;
+            for outcome in &mut duties.chunks {
+                let Some(produced_hash) = outcome.hash else {
+                    continue;
+                };
+                let included = next_block.as_ref().map_or(false, |block| {
+                    block.chunks().iter().any(|chunk| chunk.chunk_hash().0 == produced_hash)
+                });
+                outcome.included = Some(included);
+            }
+        }
+        Ok(ValidatorPerformanceView {
+            validator_id: self
+                .validator_signer
+                .as_ref()
+                .map(|signer| signer.validator_id().clone()),
+            heights,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ValidatorPerformanceTracker;
+    use near_async::time::FakeClock;
+    use near_primitives::hash::CryptoHash;
+    use near_primitives::sharding::ChunkHash;
+
+    #[test]
+    fn test_failures_do_not_override_produced_block() {
+        let tracker = ValidatorPerformanceTracker::new(FakeClock::default().clock());
+        tracker.record_block_failure(10, "Not enough approvals".to_string());
+        tracker.record_block_produced(10, CryptoHash::hash_bytes(b"block"));
+        tracker.record_block_failure(10, "Height already known".to_string());
+
+        let duties = tracker.get_duties();
+        assert_eq!(duties.len(), 1);
+        let block = duties[0].block.as_ref().unwrap();
+        assert_eq!(block.hash, Some(CryptoHash::hash_bytes(b"block")));
+        assert_eq!(block.failure_cause, None);
+    }
+
+    #[test]
+    fn test_not_enough_approvals_recorded_once_abandoned() {
+        let tracker = ValidatorPerformanceTracker::new(FakeClock::default().clock());
+        tracker.set_waiting_for_approvals(10, true);
+        tracker.set_waiting_for_approvals(11, true);
+        tracker.set_waiting_for_approvals(12, true);
+        tracker.record_abandoned_heights(9);
+        assert!(tracker.get_duties().is_empty());
+
+        // The approvals for 12 arrived in the meantime.
+        tracker.set_waiting_for_approvals(12, false);
+        tracker.record_abandoned_heights(12);
+        let duties = tracker.get_duties();
+        assert_eq!(duties.iter().map(|duties| duties.height).collect::<Vec<_>>(), vec![11, 10]);
+        for duties in &duties {
+            let block = duties.block.as_ref().unwrap();
+            assert_eq!(block.failure_cause.as_deref(), Some("Not enough approvals"));
+        }
+
+        // Each height is recorded once.
+        tracker.record_abandoned_heights(13);
+        assert_eq!(tracker.get_duties().len(), 2);
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let tracker = ValidatorPerformanceTracker::new(FakeClock::default().clock());
+        let chunk_hash = ChunkHash(CryptoHash::hash_bytes(b"chunk"));
+        for height in 0..super::VALIDATOR_PERFORMANCE_HISTORY_SIZE as u64 + 10 {
+            tracker.record_endorsement(height, 0, &chunk_hash, None);
+            tracker.record_chunk_failure(height, 1, "Error".to_string());
+            tracker.record_chunk_failure(height, 1, "Error".to_string());
+        }
+
+        let duties = tracker.get_duties();
+        assert_eq!(duties.len(), super::VALIDATOR_PERFORMANCE_HISTORY_SIZE);
+        assert_eq!(duties[0].height, super::VALIDATOR_PERFORMANCE_HISTORY_SIZE as u64 + 9);
+        assert_eq!(duties.last().unwrap().height, 10);
+        assert_eq!(duties[0].chunks.len(), 1);
+        assert_eq!(duties[0].endorsements.len(), 1);
+    }
+}
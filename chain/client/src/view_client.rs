@@ -27,12 +27,13 @@ use near_client_primitives::types::{
 use near_epoch_manager::shard_tracker::ShardTracker;
 use near_epoch_manager::EpochManagerAdapter;
 use near_network::client::{
-    AnnounceAccountRequest, BlockHeadersRequest, BlockRequest, StateRequestHeader,
-    StateRequestPart, StateResponse, TxStatusRequest, TxStatusResponse,
+    AnnounceAccountRequest, BlockHeadersRequest, BlockRequest, EpochBlockProducersRequest,
+    EpochSyncRequest, LightClientBlockRequest, StateRequestHeader, StateRequestPart, StateResponse,
+    TxStatusRequest, TxStatusResponse,
 };
 use near_network::types::{
-    NetworkRequests, PeerManagerAdapter, PeerManagerMessageRequest, ReasonForBan,
-    StateResponseInfo, StateResponseInfoV2,
+    EpochBlockProducers, EpochSyncInfoResponse, NetworkRequests, PeerManagerAdapter,
+    PeerManagerMessageRequest, ReasonForBan, StateResponseInfo, StateResponseInfoV2,
 };
 use near_o11y::{handler_debug_span, WithSpanContext, WithSpanContextExt};
 use near_performance_metrics_macros::perf;
@@ -585,6 +586,46 @@ impl ViewClientActor {
         self.chain.retrieve_headers(hashes, sync::header::MAX_BLOCK_HEADERS, None)
     }
 
+    /// Returns the next light client block, given the hash of the last block known to the light client.
+    /// There are three cases:
+    ///  1. The last block known to the light client is in the same epoch as the tip:
+    ///     - Then return the last known final block, as long as it's more recent that the last known
+    ///  2. The last block known to the light client is in the epoch preceding that of the tip:
+    ///     - Same as above
+    ///  3. Otherwise, return the last final block in the epoch that follows that of the last block known
+    ///     to the light client
+    fn get_next_light_client_block(
+        &self,
+        last_block_hash: &CryptoHash,
+    ) -> Result<Option<Arc<LightClientBlockView>>, near_chain::Error> {
+        let last_block_header = self.chain.get_block_header(last_block_hash)?;
+        let last_epoch_id = last_block_header.epoch_id().clone();
+        let last_next_epoch_id = last_block_header.next_epoch_id().clone();
+        let last_height = last_block_header.height();
+        let head = self.chain.head()?;
+
+        if last_epoch_id == head.epoch_id || last_next_epoch_id == head.epoch_id {
+            let head_header = self.chain.get_block_header(&head.last_block_hash)?;
+            let ret = Chain::create_light_client_block(
+                &head_header,
+                self.epoch_manager.as_ref(),
+                self.chain.chain_store(),
+            )?;
+
+            if ret.inner_lite.height <= last_height {
+                Ok(None)
+            } else {
+                Ok(Some(Arc::new(ret)))
+            }
+        } else {
+            match self.chain.chain_store().get_epoch_light_client_block(&last_next_epoch_id.0) {
+                Ok(light_block) => Ok(Some(light_block)),
+                Err(near_chain::Error::DBNotFoundErr(_)) => Ok(None),
+                Err(e) => Err(e),
+            }
+        }
+    }
+
     fn check_signature_account_announce(
         &self,
         announce_account: &AnnounceAccount,
@@ -965,14 +1006,6 @@ impl Handler<WithSpanContext<GetStateChangesWithCauseInBlockForTrackedShards>> f
     }
 }
 
-/// Returns the next light client block, given the hash of the last block known to the light client.
-/// There are three cases:
-///  1. The last block known to the light client is in the same epoch as the tip:
-///     - Then return the last known final block, as long as it's more recent that the last known
-///  2. The last block known to the light client is in the epoch preceding that of the tip:
-///     - Same as above
-///  3. Otherwise, return the last final block in the epoch that follows that of the last block known
-///     to the light client
 impl Handler<WithSpanContext<GetNextLightClientBlock>> for ViewClientActor {
     type Result = Result<Option<Arc<LightClientBlockView>>, GetNextLightClientBlockError>;
 
@@ -987,40 +1020,93 @@ impl Handler<WithSpanContext<GetNextLightClientBlock>> for ViewClientActor {
         let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
             .with_label_values(&["GetNextLightClientBlock"])
             .start_timer();
-        let last_block_header = self.chain.get_block_header(&msg.last_block_hash)?;
-        let last_epoch_id = last_block_header.epoch_id().clone();
-        let last_next_epoch_id = last_block_header.next_epoch_id().clone();
-        let last_height = last_block_header.height();
-        let head = self.chain.head()?;
+        Ok(self.get_next_light_client_block(&msg.last_block_hash)?)
+    }
+}
 
-        if last_epoch_id == head.epoch_id || last_next_epoch_id == head.epoch_id {
-            let head_header = self.chain.get_block_header(&head.last_block_hash)?;
-            let ret = Chain::create_light_client_block(
-                &head_header,
-                self.epoch_manager.as_ref(),
-                self.chain.chain_store(),
-            )?;
+impl Handler<WithSpanContext<LightClientBlockRequest>> for ViewClientActor {
+    type Result = Option<Box<LightClientBlockView>>;
 
-            if ret.inner_lite.height <= last_height {
-                Ok(None)
-            } else {
-                Ok(Some(Arc::new(ret)))
-            }
-        } else {
-            match self.chain.chain_store().get_epoch_light_client_block(&last_next_epoch_id.0) {
-                Ok(light_block) => Ok(Some(light_block)),
-                Err(e) => {
-                    if let near_chain::Error::DBNotFoundErr(_) = e {
-                        Ok(None)
-                    } else {
-                        Err(e.into())
-                    }
-                }
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: WithSpanContext<LightClientBlockRequest>,
+        _: &mut Self::Context,
+    ) -> Self::Result {
+        let (_span, msg) = handler_debug_span!(target: "client", msg);
+        tracing::debug!(target: "client", ?msg);
+        let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
+            .with_label_values(&["LightClientBlockRequest"])
+            .start_timer();
+        match self.get_next_light_client_block(&msg.last_block_hash) {
+            Ok(block) => block.map(|block| Box::new(block.as_ref().clone())),
+            Err(err) => {
+                tracing::debug!(target: "client", ?err, "failed to serve a light client block");
+                None
             }
         }
     }
 }
 
+impl Handler<WithSpanContext<EpochBlockProducersRequest>> for ViewClientActor {
+    type Result = Option<EpochBlockProducers>;
+
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: WithSpanContext<EpochBlockProducersRequest>,
+        _: &mut Self::Context,
+    ) -> Self::Result {
+        let (_span, msg) = handler_debug_span!(target: "client", msg);
+        tracing::debug!(target: "client", ?msg);
+        let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
+            .with_label_values(&["EpochBlockProducersRequest"])
+            .start_timer();
+        let header = self.chain.get_block_header(&msg.block_hash).ok()?;
+        // `next_bp_hash` of a block commits to the block producers of the next epoch.
+        let next_bps = get_epoch_block_producers_view(
+            header.next_epoch_id(),
+            header.hash(),
+            self.epoch_manager.as_ref(),
+        )
+        .ok()?;
+        Some(EpochBlockProducers { header: header.into(), next_bps })
+    }
+}
+
+impl Handler<WithSpanContext<EpochSyncRequest>> for ViewClientActor {
+    type Result = Option<EpochSyncInfoResponse>;
+
+    #[perf]
+    fn handle(
+        &mut self,
+        msg: WithSpanContext<EpochSyncRequest>,
+        _: &mut Self::Context,
+    ) -> Self::Result {
+        let (_span, msg) = handler_debug_span!(target: "client", msg);
+        tracing::debug!(target: "client", ?msg);
+        let _timer = metrics::VIEW_CLIENT_MESSAGE_TIME
+            .with_label_values(&["EpochSyncRequest"])
+            .start_timer();
+        // `EpochSyncInfo`s are only recorded by nodes built with epoch sync support.
+        #[cfg(feature = "new_epoch_sync")]
+        return match self.chain.get_epoch_sync_infos(
+            &msg.epoch_id,
+            near_network::types::MAX_EPOCH_SYNC_INFOS_PER_RESPONSE,
+        ) {
+            Ok(epoch_sync_infos) => {
+                Some(EpochSyncInfoResponse { epoch_id: msg.epoch_id, epoch_sync_infos })
+            }
+            Err(err) => {
+                tracing::warn!(target: "client", ?err, epoch_id = ?msg.epoch_id, "Failed to get EpochSyncInfo");
+                None
+            }
+        };
+        #[cfg(not(feature = "new_epoch_sync"))]
+        return None;
+    }
+}
+
 impl Handler<WithSpanContext<GetExecutionOutcome>> for ViewClientActor {
     type Result = Result<GetExecutionOutcomeResponse, GetExecutionOutcomeError>;
 
@@ -15,7 +15,7 @@ use near_primitives::hash::CryptoHash;
 use near_primitives::shard_layout::{account_id_to_shard_id, ShardLayout, ShardLayoutError};
 use near_primitives::sharding::{ChunkHash, ShardChunkHeader};
 use near_primitives::stateless_validation::{
-    ChunkEndorsement, ChunkStateWitness, ChunkValidatorAssignments,
+    ChunkEndorsement, ChunkStateWitness, ChunkValidatorAssignments, PartialEncodedStateWitness,
 };
 use near_primitives::types::validator_stake::ValidatorStake;
 use near_primitives::types::{
@@ -422,6 +422,12 @@ pub trait EpochManagerAdapter: Send + Sync {
         epoch_id: &EpochId,
     ) -> Result<bool, Error>;
 
+    /// Verifies that the header of the witness part is signed by the chunk producer.
+    fn verify_partial_encoded_state_witness_signature(
+        &self,
+        part: &PartialEncodedStateWitness,
+    ) -> Result<bool, Error>;
+
     fn cares_about_shard_from_prev_block(
         &self,
         parent_hash: &CryptoHash,
@@ -1089,6 +1095,19 @@ impl EpochManagerAdapter for EpochManagerHandle {
             .verify(&borsh::to_vec(&state_witness.inner)?, chunk_producer.public_key()))
     }
 
+    fn verify_partial_encoded_state_witness_signature(
+        &self,
+        part: &PartialEncodedStateWitness,
+    ) -> Result<bool, Error> {
+        let epoch_manager = self.read();
+        let chunk_producer = epoch_manager.get_chunk_producer_info(
+            &part.epoch_id,
+            part.height_created,
+            part.shard_id,
+        )?;
+        Ok(part.verify_signature(chunk_producer.public_key()))
+    }
+
     fn cares_about_shard_from_prev_block(
         &self,
         parent_hash: &CryptoHash,
@@ -1,16 +1,134 @@
-use std::sync::Arc;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
 
 use crate::EpochManagerAdapter;
 use near_cache::SyncLruCache;
-use near_chain_configs::ClientConfig;
+use near_chain_configs::{ClientConfig, UpdateableClientConfig};
 use near_primitives::errors::EpochError;
 use near_primitives::hash::CryptoHash;
-use near_primitives::shard_layout::account_id_to_shard_id;
-use near_primitives::types::{AccountId, EpochId, ShardId};
+use near_primitives::shard_layout::{
+    account_id_to_shard_id, account_prefix_to_shard_ids, ShardLayout,
+};
+use near_primitives::types::{AccountId, EpochHeight, EpochId, ShardId};
+
+/// Prefix of account patterns which are regular expressions.
+const REGEX_PATTERN_PREFIX: &str = "regex:";
+
+/// Pattern of account ids whose shards are tracked.
+///
+/// - `aurora` matches the account itself.
+/// - `aurora*`, `sweat.*.near` or `*.sweat` match accounts with `*` standing for any sequence
+///   of characters.
+/// - `regex:aurora\..*` matches accounts by a regular expression.
+///
+/// A shard is tracked if its range of accounts may contain an account matching the pattern.
+/// All matching accounts start with the literal prefix of the pattern, so those are the shards
+/// covering the prefix. Shards are computed for the shard layout of each epoch, so they follow
+/// resharding. Patterns without a literal prefix, like `*.sweat`, may match accounts in any
+/// shard, so they track all shards.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountPattern {
+    pattern: String,
+    kind: AccountPatternKind,
+}
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum AccountPatternKind {
+    Account(AccountId),
+    Prefix(String),
+}
+
+impl AccountPattern {
+    /// Returns the shards which may contain accounts matching the pattern.
+    pub fn shard_ids(&self, shard_layout: &ShardLayout) -> Vec<ShardId> {
+        match &self.kind {
+            AccountPatternKind::Account(account_id) => {
+                vec![account_id_to_shard_id(account_id, shard_layout)]
+            }
+            AccountPatternKind::Prefix(prefix) => account_prefix_to_shard_ids(prefix, shard_layout),
+        }
+    }
+
+    /// Whether the pattern has no literal prefix, so it may match accounts in any shard.
+    pub fn matches_all_shards(&self) -> bool {
+        self.kind == AccountPatternKind::Prefix(String::new())
+    }
+}
+
+impl From<&AccountId> for AccountPattern {
+    fn from(account_id: &AccountId) -> Self {
+        Self {
+            pattern: account_id.to_string(),
+            kind: AccountPatternKind::Account(account_id.clone()),
+        }
+    }
+}
+
+impl FromStr for AccountPattern {
+    type Err = String;
+
+    fn from_str(pattern: &str) -> Result<Self, Self::Err> {
+        let kind = if let Some(regex) = pattern.strip_prefix(REGEX_PATTERN_PREFIX) {
+            regex::Regex::new(regex)
+                .map_err(|err| format!("invalid regex in account pattern {pattern:?}: {err}"))?;
+            AccountPatternKind::Prefix(regex_literal_prefix(regex))
+        } else if let Some((prefix, _)) = pattern.split_once('*') {
+            if let Some(c) = pattern.chars().find(|c| !is_account_id_char(*c) && *c != '*') {
+                return Err(format!("invalid character {c:?} in account pattern {pattern:?}"));
+            }
+            AccountPatternKind::Prefix(prefix.to_string())
+        } else {
+            let account_id = pattern.parse().map_err(|err| {
+                format!("invalid account id in account pattern {pattern:?}: {err}")
+            })?;
+            AccountPatternKind::Account(account_id)
+        };
+        Ok(Self { pattern: pattern.to_string(), kind })
+    }
+}
+
+fn is_account_id_char(c: char) -> bool {
+    matches!(c, 'a'..='z' | '0'..='9' | '.' | '_' | '-')
+}
+
+/// Returns the literal characters a regular expression starts with, which are the prefix of
+/// every account it matches. Stops at the first character which isn't a plain literal.
+fn regex_literal_prefix(regex: &str) -> String {
+    if regex.contains('|') {
+        // Alternatives may start with different characters.
+        return String::new();
+    }
+    let mut prefix = String::new();
+    let mut chars = regex.strip_prefix('^').unwrap_or(regex).chars().peekable();
+    while let Some(c) = chars.next() {
+        let literal = match c {
+            'a'..='z' | '0'..='9' | '_' | '-' => c,
+            // Escaped punctuation like `\.` is a literal, unlike classes like `\d`.
+            '\\' => match chars.next() {
+                Some(c) if c.is_ascii_punctuation() => c,
+                _ => break,
+            },
+            _ => break,
+        };
+        match chars.peek() {
+            // The character may be missing or repeated.
+            Some('*' | '?' | '{') => break,
+            // The character is there at least once, but may be repeated.
+            Some('+') => {
+                prefix.push(literal);
+                break;
+            }
+            _ => prefix.push(literal),
+        }
+    }
+    prefix
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum TrackedConfig {
     Accounts(Vec<AccountId>),
+    // Tracks the shards which may contain accounts matching any of the patterns.
+    AccountPatterns(Vec<AccountPattern>),
     AllShards,
     // Rotates between sets of shards to track.
     Schedule(Vec<Vec<ShardId>>),
@@ -22,12 +140,52 @@ impl TrackedConfig {
     }
 
     pub fn from_config(config: &ClientConfig) -> Self {
-        if !config.tracked_shards.is_empty() {
+        Self::new(
+            &config.tracked_shards,
+            &config.tracked_shard_schedule,
+            &config.tracked_accounts,
+            &config.tracked_account_patterns,
+        )
+    }
+
+    pub fn from_updateable_config(config: &UpdateableClientConfig) -> Self {
+        Self::new(
+            &config.tracked_shards,
+            &config.tracked_shard_schedule,
+            &config.tracked_accounts,
+            &config.tracked_account_patterns,
+        )
+    }
+
+    fn new(
+        tracked_shards: &[ShardId],
+        tracked_shard_schedule: &[Vec<ShardId>],
+        tracked_accounts: &[AccountId],
+        tracked_account_patterns: &[String],
+    ) -> Self {
+        if !tracked_shards.is_empty() {
             TrackedConfig::AllShards
-        } else if !config.tracked_shard_schedule.is_empty() {
-            TrackedConfig::Schedule(config.tracked_shard_schedule.clone())
+        } else if !tracked_shard_schedule.is_empty() {
+            TrackedConfig::Schedule(tracked_shard_schedule.to_vec())
+        } else if !tracked_account_patterns.is_empty() {
+            // Patterns are validated when the config is loaded.
+            let patterns = tracked_account_patterns
+                .iter()
+                .map(|pattern| pattern.parse().unwrap_or_else(|err: String| panic!("{}", err)))
+                .inspect(|pattern: &AccountPattern| {
+                    if pattern.matches_all_shards() {
+                        tracing::warn!(
+                            target: "epoch_manager",
+                            ?pattern,
+                            "Account pattern doesn't start with a literal prefix, so all shards are tracked"
+                        );
+                    }
+                })
+                .chain(tracked_accounts.iter().map(AccountPattern::from))
+                .collect();
+            TrackedConfig::AccountPatterns(patterns)
         } else {
-            TrackedConfig::Accounts(config.tracked_accounts.clone())
+            TrackedConfig::Accounts(tracked_accounts.to_vec())
         }
     }
 }
@@ -35,13 +193,19 @@ impl TrackedConfig {
 // bit mask for which shard to track
 type BitMask = Vec<bool>;
 
-/// Tracker that tracks shard ids and accounts. Right now, it only supports two modes
+/// Tracker that tracks shard ids and accounts. Right now, it supports the following modes
 /// TrackedConfig::Accounts(accounts): track the shards where `accounts` belong to
+/// TrackedConfig::AccountPatterns(patterns): track the shards which may contain accounts matching `patterns`
 /// TrackedConfig::AllShards: track all shards
+/// TrackedConfig::Schedule(schedule): track the sets of shards from `schedule` in turns
+///
+/// The config can be updated while the node is running, see `update_tracked_config`.
 #[derive(Clone)]
 pub struct ShardTracker {
-    tracked_config: TrackedConfig,
-    /// Stores shard tracking information by epoch, only useful if TrackedState == Accounts
+    /// Tracked configs together with the epoch heights they take effect from, in increasing order.
+    /// The first one takes effect from genesis.
+    tracked_configs: Arc<RwLock<Vec<(EpochHeight, TrackedConfig)>>>,
+    /// Stores shard tracking information by epoch, only useful if TrackedState == Accounts or AccountPatterns
     tracking_shards_cache: Arc<SyncLruCache<EpochId, BitMask>>,
     epoch_manager: Arc<dyn EpochManagerAdapter>,
 }
@@ -49,7 +213,7 @@ pub struct ShardTracker {
 impl ShardTracker {
     pub fn new(tracked_config: TrackedConfig, epoch_manager: Arc<dyn EpochManagerAdapter>) -> Self {
         ShardTracker {
-            tracked_config,
+            tracked_configs: Arc::new(RwLock::new(vec![(0, tracked_config)])),
             // 1024 epochs on mainnet is about 512 days which is more than enough,
             // and this is a cache anyway. The data size is pretty small as well,
             // only one bit per shard per epoch.
@@ -62,12 +226,75 @@ impl ShardTracker {
         Self::new(TrackedConfig::new_empty(), epoch_manager)
     }
 
+    /// Replaces the tracked config, e.g. when `config.json` is reloaded.
+    ///
+    /// The new config takes effect from the epoch after the next one of `head_epoch_id`.
+    /// Shards tracked in the next epoch were already decided at the start of the current
+    /// epoch, when the state of shards which are newly tracked in the next epoch started to
+    /// be caught up. The state of shards which are no longer tracked is garbage collected.
+    pub fn update_tracked_config(
+        &self,
+        tracked_config: TrackedConfig,
+        head_epoch_id: &EpochId,
+    ) -> Result<(), EpochError> {
+        let epoch_height = self.epoch_manager.get_epoch_info(head_epoch_id)?.epoch_height() + 2;
+        let mut tracked_configs = self.tracked_configs.write().unwrap();
+        // Replace a config which hasn't taken effect yet.
+        tracked_configs.retain(|(from_epoch_height, _)| *from_epoch_height < epoch_height);
+        if tracked_configs.last().map(|(_, config)| config) == Some(&tracked_config) {
+            return Ok(());
+        }
+        tracing::info!(target: "epoch_manager", ?tracked_config, epoch_height, "Updating tracked config");
+        tracked_configs.push((epoch_height, tracked_config));
+        self.tracking_shards_cache.lock().clear();
+        Ok(())
+    }
+
+    /// Whether the tracked config was updated to take effect after the given epoch.
+    pub fn is_tracked_config_updated_after(&self, epoch_id: &EpochId) -> Result<bool, EpochError> {
+        let tracked_configs = self.tracked_configs.read().unwrap();
+        if tracked_configs.len() == 1 {
+            return Ok(false);
+        }
+        let epoch_height = self.epoch_manager.get_epoch_info(epoch_id)?.epoch_height();
+        Ok(tracked_configs.iter().any(|(from_epoch_height, _)| *from_epoch_height > epoch_height))
+    }
+
+    /// Whether the tracked config in effect in the given epoch tracks the shard, regardless of
+    /// the validator duties of the node.
+    pub fn tracks_shard_by_config(
+        &self,
+        shard_id: ShardId,
+        epoch_id: &EpochId,
+    ) -> Result<bool, EpochError> {
+        self.tracks_shard_at_epoch(shard_id, epoch_id)
+    }
+
+    /// Whether all shards are tracked in all epochs, which allows skipping the epoch lookup.
+    fn tracks_all_shards(&self) -> bool {
+        let tracked_configs = self.tracked_configs.read().unwrap();
+        tracked_configs.iter().all(|(_, config)| matches!(config, TrackedConfig::AllShards))
+    }
+
     fn tracks_shard_at_epoch(
         &self,
         shard_id: ShardId,
         epoch_id: &EpochId,
     ) -> Result<bool, EpochError> {
-        match &self.tracked_config {
+        let tracked_configs = self.tracked_configs.read().unwrap();
+        let tracked_config = match tracked_configs.as_slice() {
+            [(_, tracked_config)] => tracked_config,
+            _ => {
+                let epoch_height = self.epoch_manager.get_epoch_info(epoch_id)?.epoch_height();
+                tracked_configs
+                    .iter()
+                    .rev()
+                    .find(|(from_epoch_height, _)| *from_epoch_height <= epoch_height)
+                    .map(|(_, tracked_config)| tracked_config)
+                    .unwrap_or(&tracked_configs[0].1)
+            }
+        };
+        match tracked_config {
             TrackedConfig::Accounts(tracked_accounts) => {
                 let shard_layout = self.epoch_manager.get_shard_layout(epoch_id)?;
                 let tracking_mask = self.tracking_shards_cache.get_or_put(epoch_id.clone(), |_| {
@@ -81,6 +308,20 @@ impl ShardTracker {
                 });
                 Ok(tracking_mask.get(shard_id as usize).copied().unwrap_or(false))
             }
+            TrackedConfig::AccountPatterns(patterns) => {
+                let shard_layout = self.epoch_manager.get_shard_layout(epoch_id)?;
+                let tracking_mask = self.tracking_shards_cache.get_or_put(epoch_id.clone(), |_| {
+                    let mut tracking_mask: Vec<_> =
+                        shard_layout.shard_ids().map(|_| false).collect();
+                    for pattern in patterns {
+                        for shard_id in pattern.shard_ids(&shard_layout) {
+                            tracking_mask[shard_id as usize] = true;
+                        }
+                    }
+                    tracking_mask
+                });
+                Ok(tracking_mask.get(shard_id as usize).copied().unwrap_or(false))
+            }
             TrackedConfig::AllShards => Ok(true),
             TrackedConfig::Schedule(schedule) => {
                 assert_ne!(schedule.len(), 0);
@@ -139,13 +380,11 @@ impl ShardTracker {
                 // We have access to the node config. Use the config to find a definite answer.
             }
         }
-        match self.tracked_config {
-            TrackedConfig::AllShards => {
-                // Avoid looking up EpochId as a performance optimization.
-                true
-            }
-            _ => self.tracks_shard(shard_id, parent_hash).unwrap_or(false),
+        if self.tracks_all_shards() {
+            // Avoid looking up EpochId as a performance optimization.
+            return true;
         }
+        self.tracks_shard(shard_id, parent_hash).unwrap_or(false)
     }
 
     /// Whether the client cares about some shard in the next epoch.
@@ -182,21 +421,17 @@ impl ShardTracker {
                 // We have access to the node config. Use the config to find a definite answer.
             }
         }
-        match self.tracked_config {
-            TrackedConfig::AllShards => {
-                // Avoid looking up EpochId as a performance optimization.
-                true
-            }
-            _ => {
-                self.tracks_shard_next_epoch_from_prev_block(shard_id, parent_hash).unwrap_or(false)
-            }
+        if self.tracks_all_shards() {
+            // Avoid looking up EpochId as a performance optimization.
+            return true;
         }
+        self.tracks_shard_next_epoch_from_prev_block(shard_id, parent_hash).unwrap_or(false)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{account_id_to_shard_id, ShardTracker};
+    use super::{account_id_to_shard_id, AccountPattern, ShardTracker};
     use crate::shard_tracker::TrackedConfig;
     use crate::test_utils::hash_range;
     use crate::{EpochManager, EpochManagerAdapter, EpochManagerHandle, RewardCalculator};
@@ -206,6 +441,7 @@ mod tests {
     use near_primitives::hash::CryptoHash;
     use near_primitives::shard_layout::ShardLayout;
     use near_primitives::types::validator_stake::ValidatorStake;
+    use near_primitives::types::AccountId;
     use near_primitives::types::{BlockHeight, EpochId, NumShards, ProtocolVersion, ShardId};
     use near_primitives::version::ProtocolFeature::SimpleNightshade;
     use near_primitives::version::PROTOCOL_VERSION;
@@ -342,6 +578,117 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_account_pattern_shard_ids() {
+        let boundary_accounts = ["aurora", "bar", "foo", "foo.baz", "paz"];
+        let shard_layout = ShardLayout::v1(
+            boundary_accounts.iter().map(|a| a.parse().unwrap()).collect(),
+            None,
+            1,
+        );
+        let shard_ids =
+            |pattern: &str| pattern.parse::<AccountPattern>().unwrap().shard_ids(&shard_layout);
+        assert_eq!(shard_ids("aurora"), vec![1]);
+        assert_eq!(shard_ids("foo*"), vec![3, 4]);
+        assert_eq!(shard_ids("foo.b*z"), vec![3, 4]);
+        assert_eq!(shard_ids("regex:^foo\\.baz.*"), vec![4]);
+        assert_eq!(shard_ids("regex:^bx?"), vec![1, 2]);
+        assert_eq!(shard_ids("regex:z+"), vec![5]);
+
+        // Patterns without a literal prefix track all shards.
+        for pattern in ["*.near", "regex:aurora|zoo", "regex:\\d+"] {
+            assert!(pattern.parse::<AccountPattern>().unwrap().matches_all_shards());
+            assert_eq!(shard_ids(pattern), vec![0, 1, 2, 3, 4, 5]);
+        }
+        assert!(!"foo*".parse::<AccountPattern>().unwrap().matches_all_shards());
+
+        assert!("regex:(".parse::<AccountPattern>().is_err());
+        assert!("Aurora*".parse::<AccountPattern>().is_err());
+        assert!("a".parse::<AccountPattern>().is_err());
+    }
+
+    #[test]
+    fn test_track_account_patterns() {
+        let shard_ids: Vec<_> = (0..4).collect();
+        let epoch_manager =
+            get_epoch_manager(PROTOCOL_VERSION, shard_ids.len() as NumShards, false);
+        let shard_layout = epoch_manager.read().get_shard_layout(&EpochId::default()).unwrap();
+        let account_id: AccountId = "test1".parse().unwrap();
+        let tracker = ShardTracker::new(
+            TrackedConfig::AccountPatterns(vec![AccountPattern::from(&account_id)]),
+            Arc::new(epoch_manager.clone()),
+        );
+        let total_tracked_shards =
+            HashSet::from([account_id_to_shard_id(&account_id, &shard_layout)]);
+        assert_eq!(
+            get_all_shards_care_about(&tracker, &shard_ids, &CryptoHash::default()),
+            total_tracked_shards
+        );
+
+        // Accounts are assigned to shards by hash, so a prefix may match accounts in any shard.
+        let tracker = ShardTracker::new(
+            TrackedConfig::AccountPatterns(vec!["test*".parse().unwrap()]),
+            Arc::new(epoch_manager),
+        );
+        let total_tracked_shards: HashSet<_> = shard_ids.iter().cloned().collect();
+        assert_eq!(
+            get_all_shards_care_about(&tracker, &shard_ids, &CryptoHash::default()),
+            total_tracked_shards
+        );
+        assert_eq!(
+            get_all_shards_will_care_about(&tracker, &shard_ids, &CryptoHash::default()),
+            total_tracked_shards
+        );
+    }
+
+    #[test]
+    fn test_update_tracked_config() {
+        let shard_ids: Vec<_> = (0..4).collect();
+        let epoch_manager =
+            Arc::new(get_epoch_manager(PROTOCOL_VERSION, shard_ids.len() as NumShards, false));
+        let tracker = ShardTracker::new(TrackedConfig::new_empty(), epoch_manager.clone());
+
+        let h = hash_range(8);
+        {
+            let mut epoch_manager = epoch_manager.write();
+            for i in 0..8 {
+                record_block(
+                    &mut epoch_manager,
+                    if i > 0 { h[i - 1] } else { CryptoHash::default() },
+                    h[i],
+                    i as u64,
+                    vec![],
+                    PROTOCOL_VERSION,
+                );
+            }
+        }
+        let all_shards: HashSet<_> = shard_ids.iter().cloned().collect();
+        let head_epoch_id = epoch_manager.get_epoch_id(&h[4]).unwrap();
+
+        // The new config takes effect from the epoch after the next one of the head.
+        tracker.update_tracked_config(TrackedConfig::AllShards, &head_epoch_id).unwrap();
+        assert_eq!(get_all_shards_care_about(&tracker, &shard_ids, &h[3]), HashSet::new());
+        assert_eq!(get_all_shards_care_about(&tracker, &shard_ids, &h[4]), HashSet::new());
+        assert_eq!(get_all_shards_will_care_about(&tracker, &shard_ids, &h[4]), all_shards);
+        assert_eq!(get_all_shards_care_about(&tracker, &shard_ids, &h[5]), all_shards);
+        assert_eq!(get_all_shards_care_about(&tracker, &shard_ids, &h[7]), all_shards);
+
+        // A config which hasn't taken effect yet is replaced.
+        let subset = HashSet::from([1, 2]);
+        let schedule = vec![subset.clone().into_iter().collect()];
+        tracker.update_tracked_config(TrackedConfig::Schedule(schedule), &head_epoch_id).unwrap();
+        assert_eq!(get_all_shards_will_care_about(&tracker, &shard_ids, &h[4]), subset);
+        assert_eq!(get_all_shards_care_about(&tracker, &shard_ids, &h[7]), subset);
+
+        // A config which has taken effect is kept for the epochs it applies to.
+        let head_epoch_id = epoch_manager.get_epoch_id(&h[6]).unwrap();
+        tracker.update_tracked_config(TrackedConfig::new_empty(), &head_epoch_id).unwrap();
+        assert_eq!(get_all_shards_care_about(&tracker, &shard_ids, &h[3]), HashSet::new());
+        assert_eq!(get_all_shards_care_about(&tracker, &shard_ids, &h[5]), subset);
+        assert_eq!(get_all_shards_care_about(&tracker, &shard_ids, &h[6]), subset);
+        assert_eq!(get_all_shards_will_care_about(&tracker, &shard_ids, &h[6]), HashSet::new());
+    }
+
     #[test]
     fn test_track_all_shards() {
         let shard_ids: Vec<_> = (0..4).collect();
@@ -2791,22 +2791,25 @@ fn test_max_kickout_stake_ratio() {
 }
 
 fn test_chunk_header(h: &[CryptoHash], signer: &dyn ValidatorSigner) -> ShardChunkHeader {
-    ShardChunkHeader::V3(ShardChunkHeaderV3::new(
-        h[0],
-        h[2],
-        h[2],
-        h[2],
-        0,
-        1,
-        0,
-        0,
-        0,
-        0,
-        h[2],
-        h[2],
-        vec![],
-        signer,
-    ))
+    ShardChunkHeader::V3(
+        ShardChunkHeaderV3::new(
+            h[0],
+            h[2],
+            h[2],
+            h[2],
+            0,
+            1,
+            0,
+            0,
+            0,
+            0,
+            h[2],
+            h[2],
+            vec![],
+            signer,
+        )
+        .unwrap(),
+    )
 }
 
 #[test]
@@ -2851,7 +2854,8 @@ fn test_verify_chunk_endorsements() {
     let chunk_header = test_chunk_header(&h, signer.as_ref());
 
     // check chunk endorsement validity
-    let mut chunk_endorsement = ChunkEndorsement::new(chunk_header.chunk_hash(), signer.as_ref());
+    let mut chunk_endorsement =
+        ChunkEndorsement::new(chunk_header.chunk_hash(), signer.as_ref()).unwrap();
     assert!(epoch_manager.verify_chunk_endorsement(&chunk_header, &chunk_endorsement).unwrap());
 
     // check invalid chunk endorsement signature
@@ -2859,7 +2863,7 @@ fn test_verify_chunk_endorsements() {
     assert!(!epoch_manager.verify_chunk_endorsement(&chunk_header, &chunk_endorsement).unwrap());
 
     // check chunk endorsement invalidity when chunk header and chunk endorsement don't match
-    let chunk_endorsement = ChunkEndorsement::new(h[3].into(), signer.as_ref());
+    let chunk_endorsement = ChunkEndorsement::new(h[3].into(), signer.as_ref()).unwrap();
     let err =
         epoch_manager.verify_chunk_endorsement(&chunk_header, &chunk_endorsement).unwrap_err();
     match err {
@@ -2869,7 +2873,8 @@ fn test_verify_chunk_endorsements() {
 
     // check chunk endorsement invalidity when signer is not chunk validator
     let bad_signer = Arc::new(create_test_signer("test2"));
-    let chunk_endorsement = ChunkEndorsement::new(chunk_header.chunk_hash(), bad_signer.as_ref());
+    let chunk_endorsement =
+        ChunkEndorsement::new(chunk_header.chunk_hash(), bad_signer.as_ref()).unwrap();
     let err =
         epoch_manager.verify_chunk_endorsement(&chunk_header, &chunk_endorsement).unwrap_err();
     match err {
@@ -2923,7 +2928,7 @@ fn test_verify_chunk_state_witness() {
         vec![],
         Default::default(),
     );
-    let signature = signer.sign_chunk_state_witness(&witness_inner).0;
+    let signature = signer.sign_chunk_state_witness(&witness_inner).unwrap().0;
 
     // Check chunk state witness validity.
     let mut chunk_state_witness = ChunkStateWitness { inner: witness_inner, signature };
@@ -2936,7 +2941,7 @@ fn test_verify_chunk_state_witness() {
     // Check chunk state witness invalidity when signer is not a chunk validator.
     let bad_signer = Arc::new(create_test_signer("test2"));
     chunk_state_witness.signature =
-        bad_signer.sign_chunk_state_witness(&chunk_state_witness.inner).0;
+        bad_signer.sign_chunk_state_witness(&chunk_state_witness.inner).unwrap().0;
     assert!(!epoch_manager.verify_chunk_state_witness_signature(&chunk_state_witness).unwrap());
 }
 
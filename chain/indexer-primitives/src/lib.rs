@@ -8,6 +8,26 @@ pub struct StreamerMessage {
     pub shards: Vec<IndexerShard>,
 }
 
+/// Message of the optimistic streamer, which streams blocks before they are final.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub enum OptimisticStreamerMessage {
+    /// Block which extends the previously streamed blocks.
+    Block(StreamerMessage),
+    /// Previously streamed blocks were reverted because the chain switched to another fork.
+    /// The blocks of that fork are streamed next.
+    Rollback(IndexerChainReorg),
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct IndexerChainReorg {
+    pub old_head: CryptoHash,
+    pub new_head: CryptoHash,
+    /// Streamed blocks which are no longer on the canonical chain, from the latest one.
+    pub reverted_blocks: Vec<CryptoHash>,
+    /// Blocks which replaced the reverted blocks on the canonical chain, from the oldest one.
+    pub applied_blocks: Vec<CryptoHash>,
+}
+
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct IndexerChunkView {
     pub author: types::AccountId,
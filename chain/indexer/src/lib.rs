@@ -9,9 +9,9 @@ use near_primitives::types::Gas;
 pub use nearcore::{get_default_home, init_configs, NearConfig};
 
 pub use near_indexer_primitives::{
-    IndexerChunkView, IndexerExecutionOutcomeWithOptionalReceipt,
+    IndexerChainReorg, IndexerChunkView, IndexerExecutionOutcomeWithOptionalReceipt,
     IndexerExecutionOutcomeWithReceipt, IndexerShard, IndexerTransactionWithOutcome,
-    StreamerMessage,
+    OptimisticStreamerMessage, StreamerMessage,
 };
 
 mod streamer;
@@ -137,6 +137,21 @@ impl Indexer {
         receiver
     }
 
+    /// Boots up the optimistic streamer, which streams blocks as soon as they become the head of
+    /// the chain, without waiting for them to be final. Non-final blocks can be replaced by
+    /// another fork, which is streamed as a `Rollback` of the reverted blocks followed by the
+    /// blocks of the fork. Streaming starts from the current head, `sync_mode` isn't used.
+    pub fn optimistic_streamer(&self) -> mpsc::Receiver<OptimisticStreamerMessage> {
+        let (sender, receiver) = mpsc::channel(100);
+        actix::spawn(streamer::start_optimistic(
+            self.view_client.clone(),
+            self.client.clone(),
+            self.indexer_config.clone(),
+            sender,
+        ));
+        receiver
+    }
+
     /// Expose neard config
     pub fn near_config(&self) -> &nearcore::NearConfig {
         &self.near_config
@@ -27,13 +27,12 @@ pub(crate) async fn fetch_status(
 /// entire block or we already fetched this block.
 pub(crate) async fn fetch_latest_block(
     client: &Addr<near_client::ViewClientActor>,
+    finality: types::Finality,
 ) -> Result<views::BlockView, FailedToFetchData> {
     client
         .send(
-            near_client::GetBlock(near_primitives::types::BlockReference::Finality(
-                near_primitives::types::Finality::Final,
-            ))
-            .with_span_context(),
+            near_client::GetBlock(near_primitives::types::BlockReference::Finality(finality))
+                .with_span_context(),
         )
         .await?
         .map_err(|err| FailedToFetchData::String(err.to_string()))
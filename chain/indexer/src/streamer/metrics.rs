@@ -36,6 +36,14 @@ pub(crate) static NUM_STREAMER_MESSAGES_SENT: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+pub(crate) static NUM_STREAMER_ROLLBACKS_SENT: Lazy<IntCounter> = Lazy::new(|| {
+    try_create_int_counter(
+        "near_indexer_num_streamer_rollbacks_sent",
+        "Number of rollbacks of streamed blocks sent by the optimistic streamer",
+    )
+    .unwrap()
+});
+
 pub(crate) static BUILD_STREAMER_MESSAGE_TIME: Lazy<Histogram> = Lazy::new(|| {
     try_create_histogram(
         "near_indexer_build_streamer_message_time",
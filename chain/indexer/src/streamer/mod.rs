@@ -3,6 +3,7 @@ use self::fetchers::{
     fetch_block, fetch_block_by_height, fetch_block_chunks, fetch_latest_block, fetch_outcomes,
     fetch_state_changes, fetch_status,
 };
+use self::streamed_blocks::StreamedBlocks;
 use self::utils::convert_transactions_sir_into_local_receipts;
 use crate::streamer::fetchers::fetch_protocol_config;
 use crate::INDEXER;
@@ -10,22 +11,25 @@ use crate::{AwaitForNodeSyncedEnum, IndexerConfig};
 use actix::Addr;
 use async_recursion::async_recursion;
 use near_indexer_primitives::{
-    IndexerChunkView, IndexerExecutionOutcomeWithOptionalReceipt,
+    IndexerChainReorg, IndexerChunkView, IndexerExecutionOutcomeWithOptionalReceipt,
     IndexerExecutionOutcomeWithReceipt, IndexerShard, IndexerTransactionWithOutcome,
-    StreamerMessage,
+    OptimisticStreamerMessage, StreamerMessage,
 };
+use near_o11y::WithSpanContextExt;
 use near_parameters::RuntimeConfig;
 use near_primitives::hash::CryptoHash;
+use near_primitives::types::Finality;
 use near_primitives::views;
 use rocksdb::DB;
 use std::time::Duration;
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
 use tokio::time;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 mod errors;
 mod fetchers;
 mod metrics;
+mod streamed_blocks;
 mod utils;
 
 const INTERVAL: Duration = Duration::from_millis(500);
@@ -314,7 +318,7 @@ pub(crate) async fn start(
             AwaitForNodeSyncedEnum::StreamWhileSyncing => {}
         };
 
-        let block = if let Ok(block) = fetch_latest_block(&view_client).await {
+        let block = if let Ok(block) = fetch_latest_block(&view_client, Finality::Final).await {
             block
         } else {
             continue;
@@ -378,3 +382,159 @@ pub(crate) async fn start(
         }
     }
 }
+
+/// Function that starts the optimistic Streamer's busy loop. Every half a second it fetches the
+/// head of the chain and streams the blocks since the last streamed one. Before that, it streams
+/// rollbacks of the streamed blocks which were reverted by the chain reorgs the client reported,
+/// or which don't lead to the head, in case the reorg wasn't reported.
+pub(crate) async fn start_optimistic(
+    view_client: Addr<near_client::ViewClientActor>,
+    client: Addr<near_client::ClientActor>,
+    indexer_config: IndexerConfig,
+    blocks_sink: mpsc::Sender<OptimisticStreamerMessage>,
+) {
+    info!(target: INDEXER, "Starting optimistic Streamer...");
+    let mut reorgs = match client.send(near_client::SubscribeChainReorgs.with_span_context()).await
+    {
+        Ok(near_client::ChainReorgReceiver(reorgs)) => reorgs,
+        Err(err) => {
+            warn!(target: INDEXER, ?err, "Unable to subscribe to chain reorgs. terminating...");
+            return;
+        }
+    };
+    let mut streamed_blocks = StreamedBlocks::default();
+
+    'main: loop {
+        time::sleep(INTERVAL).await;
+        match indexer_config.await_for_node_synced {
+            AwaitForNodeSyncedEnum::WaitForFullSync => {
+                let status = fetch_status(&client).await;
+                if let Ok(status) = status {
+                    if status.sync_info.syncing {
+                        continue;
+                    }
+                }
+            }
+            AwaitForNodeSyncedEnum::StreamWhileSyncing => {}
+        };
+
+        loop {
+            let reorg = match reorgs.try_recv() {
+                Ok(reorg) => reorg,
+                Err(broadcast::error::TryRecvError::Empty) => break,
+                Err(broadcast::error::TryRecvError::Lagged(num_missed)) => {
+                    // The reverted blocks are found below, when looking for the streamed
+                    // ancestor of the head.
+                    warn!(target: INDEXER, num_missed, "Missed chain reorgs");
+                    continue;
+                }
+                Err(broadcast::error::TryRecvError::Closed) => break 'main,
+            };
+            let rollback = IndexerChainReorg {
+                old_head: reorg.old_head.last_block_hash,
+                new_head: reorg.new_head.last_block_hash,
+                reverted_blocks: streamed_blocks.revert(&reorg.reverted_blocks),
+                applied_blocks: reorg.applied_blocks,
+            };
+            if !send_rollback(&blocks_sink, rollback).await {
+                break 'main;
+            }
+        }
+
+        let head = match fetch_latest_block(&view_client, Finality::None).await {
+            Ok(head) => head,
+            Err(_) => continue,
+        };
+        // Collect the blocks since the last streamed one, from the head down.
+        let mut new_blocks = vec![];
+        let mut block = head.clone();
+        let ancestor_index = loop {
+            if let Some(index) = streamed_blocks.position(&block.header.hash) {
+                break Some(index);
+            }
+            if streamed_blocks.is_older(block.header.height) {
+                break None;
+            }
+            let prev_hash = block.header.prev_hash;
+            new_blocks.push(block);
+            block = match fetch_block(&view_client, prev_hash).await {
+                Ok(block) => block,
+                Err(_) => continue 'main,
+            };
+        };
+        match ancestor_index {
+            // Start streaming from the head.
+            None if streamed_blocks.is_empty() => new_blocks = vec![head.clone()],
+            None => {
+                // This happens when the node resets its head, e.g. after state sync, so the
+                // streamed blocks can't be rolled back to a common ancestor.
+                warn!(target: INDEXER, head = ?head.header.hash, "Head doesn't descend from the streamed blocks, restarting from the head");
+                streamed_blocks.clear();
+                new_blocks = vec![head.clone()];
+            }
+            // The head is on another fork, whose reorg wasn't reported by the client.
+            Some(index) if !streamed_blocks.is_last(index) => {
+                let rollback = IndexerChainReorg {
+                    old_head: streamed_blocks.last_hash().unwrap(),
+                    new_head: head.header.hash,
+                    reverted_blocks: streamed_blocks.revert_after(index),
+                    applied_blocks: new_blocks
+                        .iter()
+                        .rev()
+                        .map(|block| block.header.hash)
+                        .collect(),
+                };
+                if !send_rollback(&blocks_sink, rollback).await {
+                    break 'main;
+                }
+            }
+            Some(_) => {}
+        }
+
+        for block in new_blocks.into_iter().rev() {
+            let (height, hash) = (block.header.height, block.header.hash);
+            metrics::CURRENT_BLOCK_HEIGHT.set(height as i64);
+            let streamer_message = match build_streamer_message(&view_client, block).await {
+                Ok(streamer_message) => streamer_message,
+                Err(err) => {
+                    // The rest of the blocks are retried in the next iteration.
+                    debug!(target: INDEXER, "Missing data, retrying block #{}...", height);
+                    debug!(target: INDEXER, "{:#?}", err);
+                    continue 'main;
+                }
+            };
+            if blocks_sink.send(OptimisticStreamerMessage::Block(streamer_message)).await.is_err() {
+                info!(
+                    target: INDEXER,
+                    "Unable to send OptimisticStreamerMessage to listener, listener doesn't listen. terminating..."
+                );
+                break 'main;
+            }
+            metrics::NUM_STREAMER_MESSAGES_SENT.inc();
+            streamed_blocks.push(height, hash);
+        }
+
+        streamed_blocks.finalize(&head.header.last_final_block);
+    }
+}
+
+/// Sends the rollback of the streamed blocks, if any of them were reverted.
+/// Returns false if the listener doesn't listen anymore.
+async fn send_rollback(
+    blocks_sink: &mpsc::Sender<OptimisticStreamerMessage>,
+    rollback: IndexerChainReorg,
+) -> bool {
+    if rollback.reverted_blocks.is_empty() {
+        return true;
+    }
+    debug!(target: INDEXER, ?rollback, "Rolling back streamed blocks");
+    if blocks_sink.send(OptimisticStreamerMessage::Rollback(rollback)).await.is_err() {
+        info!(
+            target: INDEXER,
+            "Unable to send OptimisticStreamerMessage to listener, listener doesn't listen. terminating..."
+        );
+        return false;
+    }
+    metrics::NUM_STREAMER_ROLLBACKS_SENT.inc();
+    true
+}
@@ -0,0 +1,132 @@
+use near_primitives::hash::CryptoHash;
+use near_primitives::types::BlockHeight;
+
+/// Blocks streamed by the optimistic streamer, starting from the last final one, which can't be
+/// reverted.
+#[derive(Default)]
+pub(crate) struct StreamedBlocks {
+    blocks: Vec<(BlockHeight, CryptoHash)>,
+}
+
+impl StreamedBlocks {
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    pub fn last_hash(&self) -> Option<CryptoHash> {
+        self.blocks.last().map(|(_, hash)| *hash)
+    }
+
+    /// Returns the index of the streamed block with the given hash.
+    pub fn position(&self, hash: &CryptoHash) -> Option<usize> {
+        self.blocks.iter().position(|(_, block_hash)| block_hash == hash)
+    }
+
+    /// Whether a block at `height` is older than all streamed blocks, so none of them can be
+    /// its ancestor.
+    pub fn is_older(&self, height: BlockHeight) -> bool {
+        self.blocks.first().map_or(true, |(first_height, _)| height < *first_height)
+    }
+
+    /// Whether the streamed blocks end with the block at `index`.
+    pub fn is_last(&self, index: usize) -> bool {
+        index + 1 == self.blocks.len()
+    }
+
+    pub fn push(&mut self, height: BlockHeight, hash: CryptoHash) {
+        self.blocks.push((height, hash));
+    }
+
+    /// Forgets the streamed blocks which are in `reverted_blocks`.
+    /// Returns their hashes, from the latest one.
+    pub fn revert(&mut self, reverted_blocks: &[CryptoHash]) -> Vec<CryptoHash> {
+        let reverted: Vec<_> = self
+            .blocks
+            .iter()
+            .rev()
+            .map(|(_, hash)| *hash)
+            .filter(|hash| reverted_blocks.contains(hash))
+            .collect();
+        self.blocks.retain(|(_, hash)| !reverted.contains(hash));
+        reverted
+    }
+
+    /// Forgets the streamed blocks after the one at `index`, which is the last common ancestor
+    /// with the new head. Returns their hashes, from the latest one.
+    pub fn revert_after(&mut self, index: usize) -> Vec<CryptoHash> {
+        self.blocks.drain(index + 1..).rev().map(|(_, hash)| hash).collect()
+    }
+
+    /// Forgets all streamed blocks, so that streaming restarts from the head.
+    pub fn clear(&mut self) {
+        self.blocks.clear();
+    }
+
+    /// Forgets the streamed blocks before the last final block, which can't be reverted anymore.
+    pub fn finalize(&mut self, last_final_block: &CryptoHash) {
+        if let Some(index) = self.position(last_final_block) {
+            self.blocks.drain(..index);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StreamedBlocks;
+    use near_primitives::hash::{hash, CryptoHash};
+
+    fn block(name: &str) -> CryptoHash {
+        hash(name.as_bytes())
+    }
+
+    fn streamed(names: &[(u64, &str)]) -> StreamedBlocks {
+        let mut blocks = StreamedBlocks::default();
+        for (height, name) in names {
+            blocks.push(*height, block(name));
+        }
+        blocks
+    }
+
+    #[test]
+    fn test_revert_after_fork() {
+        // a1 <- a2 <- a3 <- a4 were streamed, while the head moved to a2 <- b3.
+        let mut blocks = streamed(&[(1, "a1"), (2, "a2"), (3, "a3"), (4, "a4")]);
+        let index = blocks.position(&block("a2")).unwrap();
+        assert!(!blocks.is_last(index));
+        assert_eq!(blocks.revert_after(index), vec![block("a4"), block("a3")]);
+        assert!(blocks.is_last(index));
+        assert_eq!(blocks.last_hash(), Some(block("a2")));
+
+        blocks.push(3, block("b3"));
+        assert_eq!(blocks.revert_after(blocks.position(&block("b3")).unwrap()), vec![]);
+        assert_eq!(blocks.last_hash(), Some(block("b3")));
+    }
+
+    #[test]
+    fn test_revert_reported_reorg() {
+        let mut blocks = streamed(&[(1, "a1"), (2, "a2"), (3, "a3")]);
+        // The reorg may revert blocks which were never streamed.
+        let reverted = blocks.revert(&[block("a4"), block("a3"), block("a2")]);
+        assert_eq!(reverted, vec![block("a3"), block("a2")]);
+        assert_eq!(blocks.last_hash(), Some(block("a1")));
+        assert_eq!(blocks.revert(&[block("b2")]), vec![]);
+    }
+
+    #[test]
+    fn test_finalize_and_clear() {
+        let mut blocks = streamed(&[(1, "a1"), (2, "a2"), (3, "a3")]);
+        assert!(blocks.is_older(0));
+        assert!(!blocks.is_older(1));
+
+        blocks.finalize(&block("a2"));
+        assert!(blocks.is_older(1));
+        assert_eq!(blocks.position(&block("a2")), Some(0));
+        // Unknown final blocks don't change anything.
+        blocks.finalize(&block("b5"));
+        assert_eq!(blocks.position(&block("a3")), Some(1));
+
+        blocks.clear();
+        assert!(blocks.is_empty());
+        assert!(blocks.is_older(100));
+    }
+}
@@ -0,0 +1,69 @@
+use near_primitives::network::PeerId;
+use serde_json::Value;
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcAddPeerRequest {
+    /// Peer in the `<peer_id>@<addr>[@<addr>...]` format, as used by `boot_nodes`.
+    pub peer: String,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcPeerIdRequest {
+    pub peer_id: PeerId,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcSetBlacklistRequest {
+    /// Entries in the `<ip>` or `<ip>:<port>` format, as used by `blacklist`.
+    pub blacklist: Vec<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcBannedPeer {
+    pub peer_id: PeerId,
+    pub reason: String,
+    /// UNIX timestamp in seconds. Missing for the peers banned via `ban_peer`
+    /// which the node doesn't know anything else about.
+    pub banned_at: Option<i64>,
+    /// Whether the peer has been banned via `ban_peer`, in which case the ban
+    /// lasts until `unban_peer` is called.
+    pub permanent: bool,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct RpcListBannedResponse {
+    pub banned_peers: Vec<RpcBannedPeer>,
+    /// Blacklist entries, both from the config and set via `set_blacklist`.
+    pub blacklist: Vec<String>,
+}
+
+#[derive(thiserror::Error, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "name", content = "info", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RpcAdminError {
+    #[error("The request is not authorized")]
+    Unauthorized,
+    #[error("Invalid request: {error_message}")]
+    InvalidRequest { error_message: String },
+    #[error("Peer {peer_id} is unknown")]
+    UnknownPeer { peer_id: PeerId },
+    #[error("Peer {peer_id} is not banned")]
+    NotBanned { peer_id: PeerId },
+    #[error("Internal error: {error_message}")]
+    InternalError { error_message: String },
+}
+
+impl From<RpcAdminError> for crate::errors::RpcError {
+    fn from(error: RpcAdminError) -> Self {
+        let error_data = Some(Value::String(error.to_string()));
+        let error_data_value = match serde_json::to_value(error) {
+            Ok(value) => value,
+            Err(err) => {
+                return Self::new_internal_error(
+                    None,
+                    format!("Failed to serialize RpcAdminError: {:?}", err),
+                )
+            }
+        };
+        Self::new_internal_or_handler_error(error_data, error_data_value)
+    }
+}
@@ -58,6 +58,7 @@ pub enum EntityQuery {
     BlockHashByHeight { block_height: BlockHeight },
     BlockHeaderByHash { block_hash: CryptoHash },
     ChunkByHash { chunk_hash: CryptoHash },
+    ChunkExecutionReportByBlockHash { block_hash: CryptoHash },
     EpochInfoByEpochId { epoch_id: EpochId },
     FlatStateByTrieKey { trie_key: String, shard_uid: ShardUId },
     FlatStateChangesByBlockHash { block_hash: CryptoHash, shard_uid: ShardUId },
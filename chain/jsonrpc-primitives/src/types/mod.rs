@@ -1,3 +1,4 @@
+pub mod admin;
 pub mod blocks;
 pub mod changes;
 pub mod chunks;
@@ -15,3 +16,4 @@ pub mod split_storage;
 pub mod status;
 pub mod transactions;
 pub mod validator;
+pub mod validator_performance;
@@ -6,7 +6,7 @@ use near_client_primitives::debug::{
 use near_primitives::views::{
     CatchupStatusView, ChainProcessingInfo, NetworkGraphView, NetworkRoutesView, PeerStoreView,
     RecentOutboundConnectionsView, RequestedStatePartsView, SnapshotHostsView,
-    SplitStorageInfoView, SyncStatusView,
+    SplitStorageInfoView, SyncStatusView, ValidatorPerformanceView,
 };
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -36,6 +36,8 @@ pub enum DebugStatusResponse {
     Routes(NetworkRoutesView),
     SnapshotHosts(SnapshotHostsView),
     SplitStoreStatus(SplitStorageInfoView),
+    // Duties of the validator at the recent heights and their outcomes.
+    ValidatorPerformance(ValidatorPerformanceView),
 }
 
 #[cfg(feature = "debug_types")]
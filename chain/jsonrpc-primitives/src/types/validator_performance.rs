@@ -0,0 +1,54 @@
+use near_primitives::views::ValidatorPerformanceView;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::types::status::RpcStatusError;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RpcValidatorPerformanceRequest {}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RpcValidatorPerformanceResponse {
+    #[serde(flatten)]
+    pub result: ValidatorPerformanceView,
+}
+
+#[derive(thiserror::Error, Debug, Serialize, Deserialize)]
+#[serde(tag = "name", content = "info", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum RpcValidatorPerformanceError {
+    #[error("The node reached its limits. Try again later. More details: {error_message}")]
+    InternalError { error_message: String },
+}
+
+impl From<RpcValidatorPerformanceError> for crate::errors::RpcError {
+    fn from(error: RpcValidatorPerformanceError) -> Self {
+        let error_data = match &error {
+            RpcValidatorPerformanceError::InternalError { .. } => {
+                Some(Value::String(error.to_string()))
+            }
+        };
+
+        let error_data_value = match serde_json::to_value(error) {
+            Ok(value) => value,
+            Err(err) => {
+                return Self::new_internal_error(
+                    None,
+                    format!("Failed to serialize RpcValidatorPerformanceError: {:?}", err),
+                )
+            }
+        };
+
+        Self::new_internal_or_handler_error(error_data, error_data_value)
+    }
+}
+
+impl RpcValidatorPerformanceError {
+    // Same as `RpcSplitStorageInfoError::into_rpc_status_error`, used by the debug page.
+    pub fn into_rpc_status_error(self) -> RpcStatusError {
+        match self {
+            RpcValidatorPerformanceError::InternalError { error_message } => {
+                RpcStatusError::InternalError { error_message }
+            }
+        }
+    }
+}
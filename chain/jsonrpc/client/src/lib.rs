@@ -261,6 +261,16 @@ impl JsonRpcClient {
         call_method(&self.client, &self.server_addr, "EXPERIMENTAL_split_storage_info", request)
     }
 
+    #[allow(non_snake_case)]
+    pub fn EXPERIMENTAL_validator_performance(
+        &self,
+        request: near_jsonrpc_primitives::types::validator_performance::RpcValidatorPerformanceRequest,
+    ) -> RpcRequest<
+        near_jsonrpc_primitives::types::validator_performance::RpcValidatorPerformanceResponse,
+    > {
+        call_method(&self.client, &self.server_addr, "EXPERIMENTAL_validator_performance", request)
+    }
+
     pub fn validators(
         &self,
         epoch_id_or_block_id: Option<EpochReference>,
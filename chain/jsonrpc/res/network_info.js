@@ -38,6 +38,26 @@ function computeTraffic(bytes_received, bytes_sent) {
     return "⬇ " + convertBps(bytes_received) + "<br>⬆ " + convertBps(bytes_sent);
 }
 
+function convertBytes(bytes) {
+    if (bytes < 3000) {
+        return `${bytes} B`
+    }
+    let kilobytes = bytes / 1024;
+    if (kilobytes < 3000) {
+        return `${kilobytes.toFixed(1)} KiB`
+    }
+    let megabytes = kilobytes / 1024;
+    if (megabytes < 3000) {
+        return `${megabytes.toFixed(1)} MiB`
+    }
+    return `${(megabytes / 1024).toFixed(1)} GiB`
+}
+
+// Outbound traffic of the rate limited message classes: bytes sent, messages delayed and dropped.
+function computeSendClasses(send_classes) {
+    return send_classes.map(c => c.class + ": ⬆ " + convertBytes(c.sent_bytes) + " ⏸ " + c.delayed_messages + " ✗ " + c.dropped_messages).join("<br>");
+}
+
 function add_debug_port_link(peer_network_addr) {
     // Each node running in a machine is assigned ports 24567 + peer_num and 3030 + peer_num, whereby peer_num is a whole number
     // peer_rpc_address is not shared between peer nodes. Hence, it cannot be programmatically fetched.
@@ -0,0 +1,66 @@
+use super::{Params, RpcFrom, RpcRequest};
+use near_async::messaging::AsyncSendError;
+use near_jsonrpc_primitives::errors::RpcParseError;
+use near_jsonrpc_primitives::types::admin::{
+    RpcAddPeerRequest, RpcAdminError, RpcBannedPeer, RpcListBannedResponse, RpcPeerIdRequest,
+    RpcSetBlacklistRequest,
+};
+use near_network::admin::{AdminPeerError, BannedPeer, BannedPeers};
+use serde_json::Value;
+
+impl RpcRequest for RpcAddPeerRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Params::parse(value)
+    }
+}
+
+impl RpcRequest for RpcPeerIdRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Params::parse(value)
+    }
+}
+
+impl RpcRequest for RpcSetBlacklistRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Params::parse(value)
+    }
+}
+
+impl RpcFrom<AsyncSendError> for RpcAdminError {
+    fn rpc_from(error: AsyncSendError) -> Self {
+        Self::InternalError { error_message: error.to_string() }
+    }
+}
+
+impl RpcFrom<AdminPeerError> for RpcAdminError {
+    fn rpc_from(error: AdminPeerError) -> Self {
+        match error {
+            AdminPeerError::UnknownPeer(peer_id) => Self::UnknownPeer { peer_id },
+            AdminPeerError::NotBanned(peer_id) => Self::NotBanned { peer_id },
+            AdminPeerError::MissingAddr(_) | AdminPeerError::Blacklisted(_) => {
+                Self::InvalidRequest { error_message: error.to_string() }
+            }
+            AdminPeerError::Store(error_message) => Self::InternalError { error_message },
+        }
+    }
+}
+
+impl RpcFrom<BannedPeer> for RpcBannedPeer {
+    fn rpc_from(peer: BannedPeer) -> Self {
+        Self {
+            peer_id: peer.peer_id,
+            reason: format!("{:?}", peer.reason),
+            banned_at: peer.banned_at.map(|t| t.unix_timestamp()),
+            permanent: peer.permanent,
+        }
+    }
+}
+
+impl RpcFrom<BannedPeers> for RpcListBannedResponse {
+    fn rpc_from(banned: BannedPeers) -> Self {
+        Self {
+            banned_peers: banned.peers.into_iter().map(RpcFrom::rpc_from).collect(),
+            blacklist: banned.blacklist.iter().map(|e| e.to_string()).collect(),
+        }
+    }
+}
@@ -4,6 +4,7 @@ use serde_json::Value;
 use near_jsonrpc_primitives::errors::RpcParseError;
 use near_jsonrpc_primitives::errors::{RpcError, ServerError};
 
+mod admin;
 mod blocks;
 mod changes;
 mod chunks;
@@ -20,6 +21,7 @@ mod split_storage;
 mod status;
 mod transactions;
 mod validator;
+mod validator_performance;
 
 pub(crate) trait RpcRequest: Sized {
     fn parse(value: Value) -> Result<Self, RpcParseError>;
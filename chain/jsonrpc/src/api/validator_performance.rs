@@ -0,0 +1,38 @@
+use near_async::messaging::AsyncSendError;
+use near_client_primitives::types::GetValidatorPerformanceError;
+use near_jsonrpc_primitives::{
+    errors::RpcParseError,
+    types::validator_performance::{RpcValidatorPerformanceError, RpcValidatorPerformanceRequest},
+};
+use serde_json::Value;
+
+use super::{Params, RpcFrom, RpcRequest};
+
+impl RpcRequest for RpcValidatorPerformanceRequest {
+    fn parse(value: Value) -> Result<Self, RpcParseError> {
+        Params::parse(value)
+    }
+}
+
+impl RpcFrom<AsyncSendError> for RpcValidatorPerformanceError {
+    fn rpc_from(error: AsyncSendError) -> Self {
+        Self::InternalError { error_message: error.to_string() }
+    }
+}
+
+impl RpcFrom<GetValidatorPerformanceError> for RpcValidatorPerformanceError {
+    fn rpc_from(error: GetValidatorPerformanceError) -> Self {
+        match error {
+            GetValidatorPerformanceError::IOError(error_message) => {
+                Self::InternalError { error_message }
+            }
+            GetValidatorPerformanceError::Unreachable(ref error_message) => {
+                tracing::warn!(target: "jsonrpc", "Unreachable error occurred: {}", error_message);
+                crate::metrics::RPC_UNREACHABLE_ERROR_COUNT
+                    .with_label_values(&["RpcValidatorPerformanceError"])
+                    .inc();
+                Self::InternalError { error_message: error.to_string() }
+            }
+        }
+    }
+}
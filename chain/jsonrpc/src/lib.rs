@@ -17,12 +17,16 @@ use near_client::{
     DebugStatus, GetBlock, GetBlockProof, GetChunk, GetClientConfig, GetExecutionOutcome,
     GetGasPrice, GetMaintenanceWindows, GetNetworkInfo, GetNextLightClientBlock, GetProtocolConfig,
     GetReceipt, GetStateChanges, GetStateChangesInBlock, GetValidatorInfo, GetValidatorOrdered,
-    ProcessTxRequest, ProcessTxResponse, Query, Status, TxStatus,
+    GetValidatorPerformance, ProcessTxRequest, ProcessTxResponse, Query, Status, TxStatus,
 };
 use near_client_primitives::types::GetSplitStorageInfo;
 pub use near_jsonrpc_client as client;
 use near_jsonrpc_primitives::errors::RpcError;
 use near_jsonrpc_primitives::message::{Message, Request};
+use near_jsonrpc_primitives::types::admin::{
+    RpcAddPeerRequest, RpcAdminError, RpcListBannedResponse, RpcPeerIdRequest,
+    RpcSetBlacklistRequest,
+};
 use near_jsonrpc_primitives::types::config::RpcProtocolConfigResponse;
 use near_jsonrpc_primitives::types::entity_debug::{EntityDebugHandler, EntityQuery};
 use near_jsonrpc_primitives::types::query::RpcQueryRequest;
@@ -32,6 +36,10 @@ use near_jsonrpc_primitives::types::split_storage::{
 use near_jsonrpc_primitives::types::transactions::{
     RpcSendTransactionRequest, RpcTransactionResponse,
 };
+use near_jsonrpc_primitives::types::validator_performance::{
+    RpcValidatorPerformanceRequest, RpcValidatorPerformanceResponse,
+};
+use near_network::admin::{AdminPeerRequest, AdminPeerResponse};
 use near_network::debug::GetDebugStatus;
 use near_network::tcp;
 use near_o11y::metrics::{prometheus, Encoder, TextEncoder};
@@ -97,6 +105,11 @@ pub struct RpcConfig {
     // be read from this directory, instead of the contents compiled into the binary. This allows
     // for quick iterative development.
     pub experimental_debug_pages_src_path: Option<String>,
+    // If provided, enables the admin methods (like `ban_peer`), which require the requests
+    // to carry an `Authorization: Bearer <admin_token>` header.
+    // Admin methods are disabled by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub admin_token: Option<String>,
 }
 
 impl Default for RpcConfig {
@@ -109,6 +122,7 @@ impl Default for RpcConfig {
             limits_config: Default::default(),
             enable_debug_rpc: false,
             experimental_debug_pages_src_path: None,
+            admin_token: None,
         }
     }
 }
@@ -225,6 +239,7 @@ pub struct ClientSenderForRpc(
     AsyncSender<DebugStatus, ActixResult<DebugStatus>>,
     AsyncSender<GetClientConfig, ActixResult<GetClientConfig>>,
     AsyncSender<GetNetworkInfo, ActixResult<GetNetworkInfo>>,
+    AsyncSender<GetValidatorPerformance, ActixResult<GetValidatorPerformance>>,
     AsyncSender<ProcessTxRequest, ActixResult<ProcessTxRequest>>,
     AsyncSender<Status, ActixResult<Status>>,
     Sender<ProcessTxRequest>,
@@ -263,7 +278,10 @@ pub struct ViewClientSenderForRpc(
 );
 
 #[derive(Clone, near_async::MultiSend, near_async::MultiSenderFrom)]
-pub struct PeerManagerSenderForRpc(AsyncSender<GetDebugStatus, ActixResult<GetDebugStatus>>);
+pub struct PeerManagerSenderForRpc(
+    AsyncSender<AdminPeerRequest, ActixResult<AdminPeerRequest>>,
+    AsyncSender<GetDebugStatus, ActixResult<GetDebugStatus>>,
+);
 
 struct JsonRpcHandler {
     client_sender: ClientSenderForRpc,
@@ -274,14 +292,27 @@ struct JsonRpcHandler {
     enable_debug_rpc: bool,
     debug_pages_src_path: Option<PathBuf>,
     entity_debug_handler: Arc<dyn EntityDebugHandler>,
+    admin_token: Option<String>,
+}
+
+/// Names of the methods which require the admin token.
+const ADMIN_METHODS: &[&str] =
+    &["add_peer", "remove_peer", "ban_peer", "unban_peer", "set_blacklist", "list_banned"];
+
+/// Compares the tokens in constant time, so that the admin token doesn't leak via timing.
+fn is_same_token(expected: &str, provided: &str) -> bool {
+    expected.len() == provided.len()
+        && expected.bytes().zip(provided.bytes()).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
 }
 
 impl JsonRpcHandler {
-    pub async fn process(&self, message: Message) -> Result<Message, HttpError> {
+    /// Processes the message. `is_admin` tells whether the message has been
+    /// authorized to call the admin methods.
+    pub async fn process(&self, message: Message, is_admin: bool) -> Result<Message, HttpError> {
         let id = message.id();
         match message {
             Message::Request(request) => {
-                Ok(Message::response(id, self.process_request(request).await))
+                Ok(Message::response(id, self.process_request(request, is_admin).await))
             }
             _ => Ok(Message::error(RpcError::parse_error(
                 "JSON RPC Request format was expected".to_owned(),
@@ -291,9 +322,9 @@ impl JsonRpcHandler {
 
     // `process_request` increments affected metrics but the request processing is done by
     // `process_request_internal`.
-    async fn process_request(&self, request: Request) -> Result<Value, RpcError> {
+    async fn process_request(&self, request: Request, is_admin: bool) -> Result<Value, RpcError> {
         let timer = Instant::now();
-        let (metrics_name, response) = self.process_request_internal(request).await;
+        let (metrics_name, response) = self.process_request_internal(request, is_admin).await;
 
         metrics::HTTP_RPC_REQUEST_COUNT.with_label_values(&[&metrics_name]).inc();
         metrics::RPC_PROCESSING_TIME
@@ -315,8 +346,14 @@ impl JsonRpcHandler {
     async fn process_request_internal(
         &self,
         request: Request,
+        is_admin: bool,
     ) -> (String, Result<Value, RpcError>) {
         let method_name = request.method.to_string();
+        let request = match self.process_admin_request_internal(request, is_admin).await {
+            Ok(response) => return (method_name, response),
+            Err(request) => request,
+        };
+
         let request = match self.process_adversarial_request_internal(request).await {
             Ok(response) => return (method_name, response),
             Err(request) => request,
@@ -430,6 +467,11 @@ impl JsonRpcHandler {
             "EXPERIMENTAL_split_storage_info" => {
                 process_method_call(request, |params| self.split_storage_info(params)).await
             }
+            // Computed by the client actor, so it's only served with the debug RPC enabled, like
+            // `/debug/api/validator_performance`.
+            "EXPERIMENTAL_validator_performance" if self.enable_debug_rpc => {
+                process_method_call(request, |params| self.validator_performance(params)).await
+            }
             #[cfg(feature = "sandbox")]
             "sandbox_patch_state" => {
                 process_method_call(request, |params| self.sandbox_patch_state(params)).await
@@ -442,6 +484,57 @@ impl JsonRpcHandler {
         })
     }
 
+    /// Handles the admin methods, if the admin token is configured. Requests which
+    /// are not authorized with the token are rejected.
+    async fn process_admin_request_internal(
+        &self,
+        request: Request,
+        is_admin: bool,
+    ) -> Result<Result<Value, RpcError>, Request> {
+        if self.admin_token.is_none() || !ADMIN_METHODS.contains(&request.method.as_str()) {
+            return Err(request);
+        }
+        if !is_admin {
+            return Ok(Err(RpcAdminError::Unauthorized.into()));
+        }
+        Ok(match request.method.as_ref() {
+            "add_peer" => process_method_call(request, |params| self.add_peer(params)).await,
+            "remove_peer" => {
+                process_method_call(request, |params: RpcPeerIdRequest| {
+                    self.admin_command(AdminPeerRequest::RemovePeer(params.peer_id))
+                })
+                .await
+            }
+            "ban_peer" => {
+                process_method_call(request, |params: RpcPeerIdRequest| {
+                    self.admin_command(AdminPeerRequest::BanPeer(params.peer_id))
+                })
+                .await
+            }
+            "unban_peer" => {
+                process_method_call(request, |params: RpcPeerIdRequest| {
+                    self.admin_command(AdminPeerRequest::UnbanPeer(params.peer_id))
+                })
+                .await
+            }
+            "set_blacklist" => {
+                process_method_call(request, |params| self.set_blacklist(params)).await
+            }
+            "list_banned" => process_method_call(request, |_params: ()| self.list_banned()).await,
+            _ => return Err(request),
+        })
+    }
+
+    /// Checks whether the HTTP request carries the admin token.
+    fn is_admin_request(&self, req: &HttpRequest) -> bool {
+        let Some(admin_token) = &self.admin_token else { return false };
+        req.headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| is_same_token(admin_token, token.trim()))
+    }
+
     /// Handles adversarial requests if they are enabled.
     ///
     /// Adversarial requests are only enabled when `test_features` Cargo feature
@@ -815,6 +908,13 @@ impl JsonRpcHandler {
                             .map_err(|e| e.into_rpc_status_error())?;
                         near_jsonrpc_primitives::types::status::DebugStatusResponse::SplitStoreStatus(split_storage_info.result)
                     }
+                    "/debug/api/validator_performance" => {
+                        let validator_performance: RpcValidatorPerformanceResponse = self
+                            .validator_performance(RpcValidatorPerformanceRequest {})
+                            .await
+                            .map_err(|e| e.into_rpc_status_error())?;
+                        near_jsonrpc_primitives::types::status::DebugStatusResponse::ValidatorPerformance(validator_performance.result)
+                    }
                     _ => return Ok(None),
                 };
             Ok(Some(near_jsonrpc_primitives::types::status::RpcDebugStatusResponse {
@@ -1023,6 +1123,51 @@ impl JsonRpcHandler {
         Ok(network_info.rpc_into())
     }
 
+    async fn admin_peer_request(
+        &self,
+        request: AdminPeerRequest,
+    ) -> Result<AdminPeerResponse, RpcAdminError> {
+        self.peer_manager_send::<_, _, RpcAdminError>(request).await?.map_err(RpcFrom::rpc_from)
+    }
+
+    async fn add_peer(&self, request_data: RpcAddPeerRequest) -> Result<(), RpcAdminError> {
+        let peer_info = request_data.peer.parse().map_err(|err| RpcAdminError::InvalidRequest {
+            error_message: format!("invalid peer {:?}: {err}", request_data.peer),
+        })?;
+        self.admin_command(AdminPeerRequest::AddPeer(peer_info)).await
+    }
+
+    /// Sends an admin request which doesn't return any data.
+    async fn admin_command(&self, request: AdminPeerRequest) -> Result<(), RpcAdminError> {
+        self.admin_peer_request(request).await?;
+        Ok(())
+    }
+
+    async fn set_blacklist(
+        &self,
+        request_data: RpcSetBlacklistRequest,
+    ) -> Result<(), RpcAdminError> {
+        let entries = request_data
+            .blacklist
+            .iter()
+            .map(|entry| {
+                entry.parse().map_err(|err| RpcAdminError::InvalidRequest {
+                    error_message: format!("invalid blacklist entry {entry:?}: {err}"),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        self.admin_command(AdminPeerRequest::SetBlacklist(entries)).await
+    }
+
+    async fn list_banned(&self) -> Result<RpcListBannedResponse, RpcAdminError> {
+        match self.admin_peer_request(AdminPeerRequest::ListBanned).await? {
+            AdminPeerResponse::Banned(banned) => Ok(banned.rpc_into()),
+            AdminPeerResponse::Ok => Err(RpcAdminError::InternalError {
+                error_message: "unexpected response to ListBanned".to_string(),
+            }),
+        }
+    }
+
     async fn gas_price(
         &self,
         request_data: near_jsonrpc_primitives::types::gas_price::RpcGasPriceRequest,
@@ -1112,6 +1257,17 @@ impl JsonRpcHandler {
         let split_storage = self.view_client_send(GetSplitStorageInfo {}).await?;
         Ok(RpcSplitStorageInfoResponse { result: split_storage })
     }
+
+    pub async fn validator_performance(
+        &self,
+        _request_data: RpcValidatorPerformanceRequest,
+    ) -> Result<
+        RpcValidatorPerformanceResponse,
+        near_jsonrpc_primitives::types::validator_performance::RpcValidatorPerformanceError,
+    > {
+        let validator_performance = self.client_send(GetValidatorPerformance {}).await?;
+        Ok(RpcValidatorPerformanceResponse { result: validator_performance })
+    }
 }
 
 #[cfg(feature = "sandbox")]
@@ -1266,11 +1422,13 @@ impl JsonRpcHandler {
 }
 
 fn rpc_handler(
+    req: HttpRequest,
     message: web::Json<Message>,
     handler: web::Data<JsonRpcHandler>,
 ) -> impl Future<Output = Result<HttpResponse, HttpError>> {
+    let is_admin = handler.is_admin_request(&req);
     let response = async move {
-        let message = handler.process(message.0).await?;
+        let message = handler.process(message.0, is_admin).await?;
         Ok(HttpResponse::Ok().json(&message))
     };
     response.boxed()
@@ -1469,6 +1627,7 @@ pub fn start_http(
         limits_config,
         enable_debug_rpc,
         experimental_debug_pages_src_path: debug_pages_src_path,
+        admin_token,
     } = config;
     let prometheus_addr = prometheus_addr.filter(|it| it != &addr.to_string());
     let cors_allowed_origins_clone = cors_allowed_origins.clone();
@@ -1486,6 +1645,7 @@ pub fn start_http(
                 enable_debug_rpc,
                 debug_pages_src_path: debug_pages_src_path.clone().map(Into::into),
                 entity_debug_handler: entity_debug_handler.clone(),
+                admin_token: admin_token.clone(),
             }))
             .app_data(web::JsonConfig::default().limit(limits_config.json_payload_max_size))
             .wrap(middleware::Logger::default())
@@ -1583,3 +1743,104 @@ fn tx_execution_status_meets_expectations(
         TxExecutionStatus::Final => actual == &TxExecutionStatus::Final,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+    use near_async::messaging::{noop, IntoMultiSender, IntoSender, Sender};
+    use near_jsonrpc_primitives::types::entity_debug::DummyEntityDebugHandler;
+    use near_network::admin::BannedPeers;
+    use near_primitives::network::PeerId;
+
+    const ADMIN_TOKEN: &str = "secret";
+
+    fn make_handler(admin_token: Option<&str>) -> JsonRpcHandler {
+        JsonRpcHandler {
+            client_sender: noop().into_multi_sender(),
+            view_client_sender: noop().into_multi_sender(),
+            peer_manager_sender: PeerManagerSenderForRpc(
+                Sender::from_async_fn(|request| match request {
+                    AdminPeerRequest::ListBanned => Ok(AdminPeerResponse::Banned(BannedPeers {
+                        peers: vec![],
+                        blacklist: vec![],
+                    })),
+                    _ => Ok(AdminPeerResponse::Ok),
+                }),
+                noop().into_sender(),
+            ),
+            polling_config: Default::default(),
+            genesis_config: Default::default(),
+            enable_debug_rpc: false,
+            debug_pages_src_path: None,
+            entity_debug_handler: Arc::new(DummyEntityDebugHandler {}),
+            admin_token: admin_token.map(str::to_string),
+        }
+    }
+
+    fn request(method: &str, params: Value) -> Request {
+        serde_json::from_value(
+            json!({"jsonrpc": "2.0", "method": method, "params": params, "id": 1}),
+        )
+        .unwrap()
+    }
+
+    fn authorization(value: &str) -> HttpRequest {
+        TestRequest::default().insert_header((header::AUTHORIZATION, value)).to_http_request()
+    }
+
+    #[test]
+    fn is_admin_request() {
+        let handler = make_handler(Some(ADMIN_TOKEN));
+        assert!(handler.is_admin_request(&authorization("Bearer secret")));
+        assert!(!handler.is_admin_request(&authorization("Bearer secreT")));
+        assert!(!handler.is_admin_request(&authorization("Bearer secret2")));
+        assert!(!handler.is_admin_request(&authorization("Bearer ")));
+        assert!(!handler.is_admin_request(&authorization("Basic secret")));
+        assert!(!handler.is_admin_request(&authorization("secret")));
+        assert!(!handler.is_admin_request(&TestRequest::default().to_http_request()));
+
+        // Without the token configured, no request is an admin request.
+        let handler = make_handler(None);
+        assert!(!handler.is_admin_request(&authorization("Bearer secret")));
+        assert!(!handler.is_admin_request(&authorization("Bearer ")));
+    }
+
+    #[tokio::test]
+    async fn process_admin_request() {
+        let handler = make_handler(Some(ADMIN_TOKEN));
+        for method in ADMIN_METHODS {
+            let got =
+                handler.process_admin_request_internal(request(method, Value::Null), false).await;
+            assert_eq!(Some(Err(RpcAdminError::Unauthorized.into())), got.ok(), "{method}");
+        }
+        let got =
+            handler.process_admin_request_internal(request("list_banned", Value::Null), true).await;
+        let want = json!({"banned_peers": [], "blacklist": []});
+        assert_eq!(Some(Ok(want)), got.ok());
+        let params = json!({"peer_id": PeerId::random()});
+        let got = handler.process_admin_request_internal(request("ban_peer", params), true).await;
+        assert_eq!(Some(Ok(Value::Null)), got.ok());
+
+        // Other methods are not handled, regardless of the authorization.
+        for is_admin in [false, true] {
+            let got = handler
+                .process_admin_request_internal(request("status", Value::Null), is_admin)
+                .await;
+            assert_eq!("status", got.unwrap_err().method);
+        }
+    }
+
+    #[tokio::test]
+    async fn admin_methods_disabled_without_token() {
+        let handler = make_handler(None);
+        for method in ADMIN_METHODS {
+            for is_admin in [false, true] {
+                let got = handler
+                    .process_admin_request_internal(request(method, Value::Null), is_admin)
+                    .await;
+                assert_eq!(*method, got.unwrap_err().method);
+            }
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use crate::blacklist;
+use crate::network_protocol::PeerInfo;
+use crate::types::ReasonForBan;
+use near_async::time;
+use near_primitives::network::PeerId;
+use std::net::SocketAddr;
+
+/// Requests of the node operator, changing the set of peers of a running node.
+/// They are sent by the admin JSON RPC methods. The changes are persisted,
+/// so that they survive node restarts.
+#[derive(Debug)]
+pub enum AdminPeerRequest {
+    /// Whitelists the peer and connects to it. The node will keep reconnecting
+    /// to the peer, until it is removed or banned.
+    AddPeer(PeerInfo),
+    /// Disconnects from the peer and removes it from the peer store.
+    RemovePeer(PeerId),
+    /// Disconnects from the peer and bans it, until it is explicitly unbanned.
+    BanPeer(PeerId),
+    /// Lifts the ban of the peer, regardless of whether it has been banned
+    /// by the operator or for misbehavior.
+    UnbanPeer(PeerId),
+    /// Replaces the blacklist entries set by the operator and disconnects from
+    /// the blacklisted peers. The `blacklist` from the config stays in force.
+    SetBlacklist(Vec<blacklist::Entry>),
+    /// Lists the banned peers and the blacklist.
+    ListBanned,
+}
+
+#[derive(Debug)]
+pub enum AdminPeerResponse {
+    Ok,
+    Banned(BannedPeers),
+}
+
+#[derive(Debug)]
+pub struct BannedPeer {
+    pub peer_id: PeerId,
+    pub reason: ReasonForBan,
+    /// Unknown for the peers banned by the operator, which are not present in the peer store.
+    pub banned_at: Option<time::Utc>,
+    /// Whether the peer has been banned by the operator, in which case the ban doesn't expire.
+    pub permanent: bool,
+}
+
+#[derive(Debug)]
+pub struct BannedPeers {
+    pub peers: Vec<BannedPeer>,
+    /// Blacklist entries, both from the config and set by the operator.
+    pub blacklist: Vec<blacklist::Entry>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum AdminPeerError {
+    #[error("peer {0} is unknown")]
+    UnknownPeer(PeerId),
+    #[error("peer {0} is not banned")]
+    NotBanned(PeerId),
+    #[error("address of peer {0} is missing")]
+    MissingAddr(PeerId),
+    #[error("address {0} is blacklisted")]
+    Blacklisted(SocketAddr),
+    #[error("failed to persist the change: {0}")]
+    Store(String),
+}
+
+impl actix::Message for AdminPeerRequest {
+    type Result = Result<AdminPeerResponse, AdminPeerError>;
+}
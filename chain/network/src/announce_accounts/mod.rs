@@ -91,6 +91,17 @@ impl AnnounceAccountCache {
         self.0.lock().get_announce(account_id).map(|announce_account| announce_account.peer_id)
     }
 
+    /// Find the AccountIds owned by the peer, among the cached ones.
+    pub(crate) fn get_peer_accounts(&self, peer_id: &PeerId) -> Vec<AccountId> {
+        self.0
+            .lock()
+            .account_peers
+            .iter()
+            .filter(|(_, announce_account)| &announce_account.peer_id == peer_id)
+            .map(|(account_id, _)| account_id.clone())
+            .collect()
+    }
+
     /// Public interface for `account_peers`.
     /// Get keys currently on cache.
     pub(crate) fn get_accounts_keys(&self) -> Vec<AccountId> {
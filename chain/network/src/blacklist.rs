@@ -43,6 +43,23 @@ impl std::str::FromStr for Entry {
     }
 }
 
+/// Formats the entry in the format accepted by `FromStr`.
+/// IPv4-mapped addresses are displayed as IPv4 addresses.
+impl std::fmt::Display for Entry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn canonical(ip: &net::Ipv6Addr) -> net::IpAddr {
+            match ip.to_ipv4_mapped() {
+                Some(ip) => net::IpAddr::V4(ip),
+                None => net::IpAddr::V6(*ip),
+            }
+        }
+        match self {
+            Entry::Ip(ip) => write!(f, "{}", canonical(ip)),
+            Entry::IpPort(ip, port) => write!(f, "{}", net::SocketAddr::new(canonical(ip), *port)),
+        }
+    }
+}
+
 /// A blacklist for socket addresses.  Supports adding individual IP:port tuples
 /// to the blacklist or entire IPs.
 #[derive(Debug, Default, Clone)]
@@ -61,6 +78,11 @@ impl Blacklist {
     pub fn contains(&self, addr: net::SocketAddr) -> bool {
         self.0.contains(&Entry::from_ip(addr.ip())) || self.0.contains(&Entry::from_addr(addr))
     }
+
+    /// Iterates over the entries of the blacklist, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = &Entry> {
+        self.0.iter()
+    }
 }
 
 #[cfg(test)]
@@ -96,6 +118,15 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_display_entry() {
+        for s in ["192.0.2.4", "192.0.2.4:42", "::1", "[::1]:42"] {
+            let entry: Entry = s.parse().unwrap();
+            assert_eq!(s, entry.to_string());
+        }
+        assert_eq!("192.0.2.4", "::ffff:192.0.2.4".parse::<Entry>().unwrap().to_string());
+    }
+
     #[test]
     fn test_blacklist() {
         use std::net::*;
@@ -1,4 +1,4 @@
-use crate::network_protocol::StateResponseInfo;
+use crate::network_protocol::{EpochBlockProducers, EpochSyncInfoResponse, StateResponseInfo};
 use crate::types::{NetworkInfo, ReasonForBan};
 use near_async::messaging::AsyncSender;
 use near_primitives::block::{Approval, Block, BlockHeader};
@@ -6,10 +6,12 @@ use near_primitives::challenge::Challenge;
 use near_primitives::errors::InvalidTxError;
 use near_primitives::hash::CryptoHash;
 use near_primitives::network::{AnnounceAccount, PeerId};
-use near_primitives::stateless_validation::{ChunkEndorsement, ChunkStateWitness};
+use near_primitives::stateless_validation::{
+    ChunkEndorsement, ChunkStateWitness, PartialEncodedStateWitness,
+};
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::{AccountId, EpochId, ShardId};
-use near_primitives::views::FinalExecutionOutcomeView;
+use near_primitives::views::{FinalExecutionOutcomeView, LightClientBlockView};
 
 /// Transaction status query
 #[derive(actix::Message, Debug, Clone, PartialEq, Eq)]
@@ -69,6 +71,32 @@ pub struct StateRequestPart {
     pub part_id: u64,
 }
 
+/// Request for the next light client block after `last_block_hash`.
+#[derive(actix::Message, Debug, Clone, PartialEq, Eq)]
+#[rtype(result = "Option<Box<LightClientBlockView>>")]
+pub struct LightClientBlockRequest {
+    pub last_block_hash: CryptoHash,
+}
+
+/// Request for the block producers of the epoch following the epoch of `block_hash`.
+#[derive(actix::Message, Debug, Clone, PartialEq, Eq)]
+#[rtype(result = "Option<EpochBlockProducers>")]
+pub struct EpochBlockProducersRequest {
+    pub block_hash: CryptoHash,
+}
+
+/// Request for the `EpochSyncInfo`s of finalised epochs, starting with `epoch_id`.
+#[derive(actix::Message, Debug, Clone, PartialEq, Eq)]
+#[rtype(result = "Option<EpochSyncInfoResponse>")]
+pub struct EpochSyncRequest {
+    pub epoch_id: EpochId,
+}
+
+/// Response to `EpochSyncRequest` received from a peer.
+#[derive(actix::Message, Debug, Clone, PartialEq, Eq)]
+#[rtype(result = "()")]
+pub struct EpochSyncResponse(pub EpochSyncInfoResponse, pub PeerId);
+
 /// Response to state request.
 #[derive(actix::Message, Debug, Clone, PartialEq, Eq)]
 #[rtype(result = "()")]
@@ -120,6 +148,18 @@ pub struct ChunkStateWitnessMessage(pub ChunkStateWitness);
 #[rtype(result = "()")]
 pub struct ChunkEndorsementMessage(pub ChunkEndorsement);
 
+#[derive(actix::Message, Debug, Clone, PartialEq, Eq)]
+#[rtype(result = "()")]
+pub struct PartialEncodedStateWitnessMessage(pub PartialEncodedStateWitness);
+
+#[derive(actix::Message, Debug, Clone, PartialEq, Eq)]
+#[rtype(result = "()")]
+pub struct PartialEncodedStateWitnessForwardMessage {
+    pub part: PartialEncodedStateWitness,
+    /// Accounts announced by the node which has forwarded the part.
+    pub sender_accounts: Vec<AccountId>,
+}
+
 #[derive(
     Clone, near_async::MultiSend, near_async::MultiSenderFrom, near_async::MultiSendMessage,
 )]
@@ -143,4 +183,13 @@ pub struct ClientSenderForNetwork {
         AsyncSender<AnnounceAccountRequest, Result<Vec<AnnounceAccount>, ReasonForBan>>,
     pub chunk_state_witness: AsyncSender<ChunkStateWitnessMessage, ()>,
     pub chunk_endorsement: AsyncSender<ChunkEndorsementMessage, ()>,
+    pub partial_encoded_state_witness: AsyncSender<PartialEncodedStateWitnessMessage, ()>,
+    pub partial_encoded_state_witness_forward:
+        AsyncSender<PartialEncodedStateWitnessForwardMessage, ()>,
+    pub light_client_block_request:
+        AsyncSender<LightClientBlockRequest, Option<Box<LightClientBlockView>>>,
+    pub epoch_block_producers_request:
+        AsyncSender<EpochBlockProducersRequest, Option<EpochBlockProducers>>,
+    pub epoch_sync_request: AsyncSender<EpochSyncRequest, Option<EpochSyncInfoResponse>>,
+    pub epoch_sync_response: AsyncSender<EpochSyncResponse, ()>,
 }
@@ -1,3 +1,5 @@
+use near_async::time;
+
 /// Config of a rate limiter algorithm, which behaves like a semaphore
 /// - with maximal capacity `burst`
 /// - with a new ticket added automatically every 1/qps seconds (qps stands for "queries per
@@ -25,3 +27,54 @@ impl Limit {
         Ok(())
     }
 }
+
+/// Token bucket implementing the rate limiting algorithm described by `Limit`.
+/// Tokens are added continuously, so the state of the bucket is updated lazily,
+/// whenever tokens are acquired.
+pub struct TokenBucket {
+    limit: Limit,
+    /// Can be negative (down to `-limit.burst`), see `acquire`.
+    tokens: f64,
+    updated: time::Instant,
+}
+
+impl TokenBucket {
+    /// Constructs a full bucket.
+    pub fn new(clock: &time::Clock, limit: Limit) -> Self {
+        Self { limit, tokens: limit.burst as f64, updated: clock.now() }
+    }
+
+    /// Acquires `n` tokens and returns how long the caller has to wait before using them,
+    /// i.e. until the debt of the bucket is paid off. The bucket is allowed to go into debt,
+    /// so that requests for more than `burst` tokens can be satisfied as well, while the
+    /// long-term average rate is still `qps`. The debt is capped at `burst` tokens, so that
+    /// a single large request cannot block the bucket for longer than `burst/qps`.
+    /// Returns None if the debt has already reached the cap.
+    pub fn acquire(&mut self, clock: &time::Clock, n: u64) -> Option<time::Duration> {
+        self.refill(clock);
+        let max_debt = self.limit.burst as f64;
+        if self.tokens <= -max_debt {
+            return None;
+        }
+        let wait = time::Duration::seconds_f64(self.tokens.min(0.).abs() / self.limit.qps);
+        self.tokens = (self.tokens - n as f64).max(-max_debt);
+        Some(wait)
+    }
+
+    /// Acquires `n` tokens if they are available right away, without going into debt.
+    pub fn try_acquire(&mut self, clock: &time::Clock, n: u64) -> bool {
+        self.refill(clock);
+        if self.tokens < n as f64 {
+            return false;
+        }
+        self.tokens -= n as f64;
+        true
+    }
+
+    fn refill(&mut self, clock: &time::Clock) {
+        let now = clock.now();
+        let elapsed = (now - self.updated).as_seconds_f64().max(0.);
+        self.tokens = (self.tokens + elapsed * self.limit.qps).min(self.limit.burst as f64);
+        self.updated = now;
+    }
+}
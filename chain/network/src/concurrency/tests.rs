@@ -1,6 +1,7 @@
 use crate::concurrency::arc_mutex::ArcMutex;
 use crate::concurrency::demux;
 use crate::concurrency::rate;
+use near_async::time;
 
 #[tokio::test]
 async fn test_demux() {
@@ -97,3 +98,41 @@ fn arc_mutex_update() {
     );
     assert_eq!(v3, *m.load());
 }
+
+#[test]
+fn token_bucket() {
+    let clock = time::FakeClock::default();
+    let mut bucket = rate::TokenBucket::new(&clock.clock(), rate::Limit { qps: 10., burst: 20 });
+    let now = time::Duration::ZERO;
+    // Full bucket lets through up to burst tokens at once.
+    assert_eq!(Some(now), bucket.acquire(&clock.clock(), 15));
+    assert_eq!(Some(now), bucket.acquire(&clock.clock(), 5));
+    // Then requests have to wait until the tokens are refilled at qps rate.
+    assert_eq!(Some(now), bucket.acquire(&clock.clock(), 10));
+    assert_eq!(Some(time::Duration::seconds(1)), bucket.acquire(&clock.clock(), 5));
+    assert_eq!(Some(time::Duration::milliseconds(1500)), bucket.acquire(&clock.clock(), 10));
+    // The debt is capped at burst tokens, beyond that requests are rejected.
+    assert_eq!(None, bucket.acquire(&clock.clock(), 1));
+    clock.advance(time::Duration::seconds(1));
+    assert_eq!(Some(time::Duration::seconds(1)), bucket.acquire(&clock.clock(), 1));
+    // Requests larger than burst are allowed, but cost at most 2*burst tokens.
+    clock.advance(time::Duration::seconds(10));
+    assert_eq!(Some(now), bucket.acquire(&clock.clock(), 1000));
+    assert_eq!(None, bucket.acquire(&clock.clock(), 1));
+    clock.advance(time::Duration::seconds(2));
+    assert_eq!(Some(now), bucket.acquire(&clock.clock(), 1));
+}
+
+#[test]
+fn token_bucket_try_acquire() {
+    let clock = time::FakeClock::default();
+    let mut bucket = rate::TokenBucket::new(&clock.clock(), rate::Limit { qps: 1., burst: 2 });
+    assert!(bucket.try_acquire(&clock.clock(), 1));
+    assert!(bucket.try_acquire(&clock.clock(), 1));
+    // Rejected requests don't put the bucket into debt.
+    assert!(!bucket.try_acquire(&clock.clock(), 1));
+    assert!(!bucket.try_acquire(&clock.clock(), 1));
+    clock.advance(time::Duration::seconds(1));
+    assert!(bucket.try_acquire(&clock.clock(), 1));
+    assert!(!bucket.try_acquire(&clock.clock(), 1));
+}
@@ -2,7 +2,9 @@ use crate::blacklist;
 use crate::concurrency::rate;
 use crate::network_protocol::PeerAddr;
 use crate::network_protocol::PeerInfo;
+use crate::peer::capture;
 use crate::peer_manager::peer_store;
+use crate::peer_manager::reputation;
 use crate::snapshot_hosts;
 use crate::stun;
 use crate::tcp;
@@ -26,6 +28,36 @@ pub const MAX_ROUTES_TO_STORE: usize = 5;
 /// Maximum number of PeerAddrs in the ValidatorConfig::endpoints field.
 pub const MAX_PEER_ADDRS: usize = 10;
 
+/// Maximum number of addresses (primary and extra) of a single peer that this node advertises.
+pub const MAX_ADDRS_PER_PEER: usize = 4;
+
+/// Checks whether `ip` may be reachable from the public internet.
+/// Loopback, unspecified, private and link-local addresses are not.
+// TODO(gprusak): use ip.is_global() instead, once it is stable.
+pub(crate) fn is_public_ip(ip: std::net::IpAddr) -> bool {
+    match ip {
+        std::net::IpAddr::V4(ip) => {
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast())
+        }
+        std::net::IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ip(ip.into()),
+            None => {
+                let first = ip.segments()[0];
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    // Unique local addresses (fc00::/7).
+                    || first & 0xfe00 == 0xfc00
+                    // Link-local unicast addresses (fe80::/10).
+                    || first & 0xffc0 == 0xfe80)
+            }
+        },
+    }
+}
+
 /// Maximum number of peers to include in a PeersResponse message.
 pub const PEERS_RESPONSE_MAX_PEERS: u32 = 512;
 
@@ -85,16 +117,50 @@ pub struct Tier1 {
     /// - a node will try to start outbound TIER1 connections iff `enable_outbound` is true.
     pub enable_inbound: bool,
     pub enable_outbound: bool,
+    /// Whether to use QUIC for TIER1 connections (in addition to TCP). If enabled, the node
+    /// listens for inbound QUIC connections on the UDP port equal to the TCP port of `node_addr`
+    /// and prefers QUIC for outbound connections to the nodes advertising QUIC support
+    /// (see `AccountData::quic`), falling back to TCP.
+    /// A validator with static proxies should enable it only if all its proxies have it enabled.
+    pub enable_quic: bool,
+}
+
+/// Limits of the outbound bandwidth of a single TIER2 connection, per class of responses
+/// (see `peer::rate_limit::SendClass`). Tokens are bytes: a response of a limited class is
+/// delayed while the corresponding bucket is in debt, and dropped if the debt exceeds
+/// the burst. None means unlimited.
+#[derive(Clone, Default)]
+pub struct SendRateLimits {
+    /// Block, BlockHeaders, light client and epoch sync responses.
+    pub sync_responses: Option<rate::Limit>,
+    /// State sync responses.
+    pub state_parts: Option<rate::Limit>,
+    /// Routed chunk part responses.
+    pub routed_chunks: Option<rate::Limit>,
+}
+
+/// Limits of the rate of the expensive requests accepted from a single connection, per
+/// class of requests (see `peer::rate_limit::RequestClass`). Tokens are requests: requests
+/// over the limit are dropped. None means unlimited.
+#[derive(Clone, Default)]
+pub struct RequestRateLimits {
+    /// LightClientBlockRequest and EpochBlockProducersRequest.
+    pub light_client: Option<rate::Limit>,
+    /// EpochSyncRequest.
+    pub epoch_sync: Option<rate::Limit>,
 }
 
 /// Validated configuration for the peer-to-peer manager.
 #[derive(Clone)]
 pub struct NetworkConfig {
     pub node_addr: Option<tcp::ListenerAddr>,
+    /// Additional public addresses of this node, advertised to the peers in the Handshake.
+    pub extra_addrs: Vec<std::net::SocketAddr>,
     pub node_key: SecretKey,
     pub validator: Option<ValidatorConfig>,
 
     pub peer_store: peer_store::Config,
+    pub reputation: reputation::Config,
     pub snapshot_hosts: snapshot_hosts::Config,
     pub whitelist_nodes: Vec<PeerInfo>,
     pub handshake_timeout: time::Duration,
@@ -153,6 +219,10 @@ pub struct NetworkConfig {
     pub snapshot_hosts_broadcast_rate_limit: rate::Limit,
     /// Maximal rate at which RoutingTable can be recomputed.
     pub routing_table_update_rate_limit: rate::Limit,
+    /// Per-connection limits of the outbound bandwidth, by message class.
+    pub peer_send_rate_limits: SendRateLimits,
+    /// Per-connection limits of the expensive inbound requests, by request class.
+    pub peer_request_rate_limits: RequestRateLimits,
     /// Config of the TIER1 network.
     pub tier1: Option<Tier1>,
 
@@ -163,6 +233,15 @@ pub struct NetworkConfig {
     //   * ignoring received deleted edges as well
     pub skip_tombstones: Option<time::Duration>,
 
+    /// Messages of the types which carry large payloads (blocks, chunks, state witnesses,
+    /// state responses) are compressed before sending, if their serialized size is at least
+    /// this many bytes and the peer supports compression. None disables compression.
+    pub message_compression_threshold: Option<usize>,
+
+    /// If set, all the messages received from peers are recorded in the given directory.
+    /// See `crate::capture` for details.
+    pub traffic_capture: Option<capture::Config>,
+
     #[cfg(test)]
     pub(crate) event_sink:
         near_async::messaging::Sender<crate::peer_manager::peer_manager_actor::Event>,
@@ -208,6 +287,35 @@ impl NetworkConfig {
         ) {
             self.routing_table_update_rate_limit = rate::Limit { qps, burst }
         }
+        if let (Some(qps), Some(burst)) = (
+            overrides.sync_responses_send_rate_limit_qps,
+            overrides.sync_responses_send_rate_limit_burst,
+        ) {
+            self.peer_send_rate_limits.sync_responses = Some(rate::Limit { qps, burst })
+        }
+        if let (Some(qps), Some(burst)) =
+            (overrides.state_parts_send_rate_limit_qps, overrides.state_parts_send_rate_limit_burst)
+        {
+            self.peer_send_rate_limits.state_parts = Some(rate::Limit { qps, burst })
+        }
+        if let (Some(qps), Some(burst)) = (
+            overrides.routed_chunks_send_rate_limit_qps,
+            overrides.routed_chunks_send_rate_limit_burst,
+        ) {
+            self.peer_send_rate_limits.routed_chunks = Some(rate::Limit { qps, burst })
+        }
+        if let (Some(qps), Some(burst)) = (
+            overrides.light_client_requests_rate_limit_qps,
+            overrides.light_client_requests_rate_limit_burst,
+        ) {
+            self.peer_request_rate_limits.light_client = Some(rate::Limit { qps, burst })
+        }
+        if let (Some(qps), Some(burst)) = (
+            overrides.epoch_sync_requests_rate_limit_qps,
+            overrides.epoch_sync_requests_rate_limit_burst,
+        ) {
+            self.peer_request_rate_limits.epoch_sync = Some(rate::Limit { qps, burst })
+        }
     }
 
     pub fn new(
@@ -222,27 +330,36 @@ impl NetworkConfig {
                 cfg.public_addrs.len()
             );
         }
+        if cfg.extra_addrs.len() >= MAX_ADDRS_PER_PEER {
+            anyhow::bail!(
+                "extra_addrs has {} entries, limit is {}",
+                cfg.extra_addrs.len(),
+                MAX_ADDRS_PER_PEER - 1
+            );
+        }
+        for addr in &cfg.extra_addrs {
+            if addr.ip().is_unspecified() || addr.port() == 0 {
+                anyhow::bail!("extra_addrs: {addr} is not a valid public address.");
+            }
+        }
         let mut proxies = HashSet::new();
         for proxy in &cfg.public_addrs {
             if proxies.contains(&proxy.peer_id) {
                 anyhow::bail!("public_addrs: found multiple entries with peer_id {}. Only 1 entry per peer_id is supported.",proxy.peer_id);
             }
             proxies.insert(proxy.peer_id.clone());
-            let ip = proxy.addr.ip();
-            if cfg.allow_private_ip_in_public_addrs {
-                if ip.is_unspecified() {
-                    anyhow::bail!("public_addrs: {ip} is not a valid IP. If you wanted to specify a loopback IP, use 127.0.0.1 instead.");
-                }
-            } else {
-                // TODO(gprusak): use !ip.is_global() instead, once it is stable.
-                if ip.is_loopback()
-                    || ip.is_unspecified()
-                    || match ip {
-                        std::net::IpAddr::V4(ip) => ip.is_private(),
-                        // TODO(gprusak): use ip.is_unique_local() once stable.
-                        std::net::IpAddr::V6(_) => false,
+            if proxy.addrs().count() > MAX_ADDRS_PER_PEER {
+                anyhow::bail!(
+                    "public_addrs: {proxy} has more than {MAX_ADDRS_PER_PEER} addresses."
+                );
+            }
+            for addr in proxy.addrs() {
+                let ip = addr.ip();
+                if cfg.allow_private_ip_in_public_addrs {
+                    if ip.is_unspecified() {
+                        anyhow::bail!("public_addrs: {ip} is not a valid IP. If you wanted to specify a loopback IP, use 127.0.0.1 instead.");
                     }
-                {
+                } else if !is_public_ip(ip) {
                     anyhow::bail!("public_addrs: {ip} is not a public IP.");
                 }
             }
@@ -263,6 +380,7 @@ impl NetworkConfig {
                     addr.parse().context("Failed to parse SocketAddr")?,
                 )),
             },
+            extra_addrs: cfg.extra_addrs,
             peer_store: peer_store::Config {
                 boot_nodes: if cfg.boot_nodes.is_empty() {
                     vec![]
@@ -284,6 +402,11 @@ impl NetworkConfig {
                 ban_window: cfg.ban_window.try_into()?,
                 peer_expiration_duration: cfg.peer_expiration_duration.try_into()?,
             },
+            reputation: reputation::Config {
+                half_life: cfg.experimental.peer_reputation_half_life.try_into()?,
+                disconnect_threshold: cfg.experimental.peer_reputation_disconnect_threshold,
+                ban_threshold: cfg.experimental.peer_reputation_ban_threshold,
+            },
             snapshot_hosts: snapshot_hosts::Config {
                 snapshot_hosts_cache_size: cfg.snapshot_hosts_cache_size,
                 part_selection_cache_batch_size: 10,
@@ -325,12 +448,34 @@ impl NetworkConfig {
             accounts_data_broadcast_rate_limit: rate::Limit { qps: 0.1, burst: 1 },
             snapshot_hosts_broadcast_rate_limit: rate::Limit { qps: 0.1, burst: 1 },
             routing_table_update_rate_limit: rate::Limit { qps: 1., burst: 1 },
+            peer_send_rate_limits: SendRateLimits {
+                sync_responses: Some(rate::Limit {
+                    qps: (10 * bytesize::MIB) as f64,
+                    burst: 50 * bytesize::MIB,
+                }),
+                state_parts: Some(rate::Limit {
+                    qps: (10 * bytesize::MIB) as f64,
+                    burst: 100 * bytesize::MIB,
+                }),
+                routed_chunks: Some(rate::Limit {
+                    qps: (50 * bytesize::MIB) as f64,
+                    burst: 100 * bytesize::MIB,
+                }),
+            },
+            // A light client needs a single block per epoch to follow the chain.
+            peer_request_rate_limits: RequestRateLimits {
+                light_client: Some(rate::Limit { qps: 1., burst: 10 }),
+                // Every response reads and serializes up to MAX_EPOCH_SYNC_INFOS_PER_RESPONSE
+                // epochs, while a syncing node needs only a few of them.
+                epoch_sync: Some(rate::Limit { qps: 0.2, burst: 5 }),
+            },
             tier1: Some(Tier1 {
                 connect_interval: cfg.experimental.tier1_connect_interval.try_into()?,
                 new_connections_per_attempt: cfg.experimental.tier1_new_connections_per_attempt,
                 advertise_proxies_interval: time::Duration::minutes(15),
                 enable_inbound: cfg.experimental.tier1_enable_inbound,
                 enable_outbound: cfg.experimental.tier1_enable_outbound,
+                enable_quic: cfg.experimental.tier1_enable_quic,
             }),
             inbound_disabled: cfg.experimental.inbound_disabled,
             skip_tombstones: if cfg.experimental.skip_sending_tombstones_seconds > 0 {
@@ -338,6 +483,17 @@ impl NetworkConfig {
             } else {
                 None
             },
+            message_compression_threshold: cfg
+                .experimental
+                .message_compression_threshold_bytes
+                .map(usize::try_from)
+                .transpose()
+                .context("message_compression_threshold_bytes")?,
+            traffic_capture: cfg.experimental.traffic_capture_dir.map(|dir| capture::Config {
+                dir,
+                max_file_bytes: cfg.experimental.traffic_capture_max_file_bytes,
+                max_files: cfg.experimental.traffic_capture_max_files,
+            }),
             #[cfg(test)]
             event_sink: near_async::messaging::IntoSender::into_sender(
                 near_async::messaging::noop(),
@@ -356,13 +512,14 @@ impl NetworkConfig {
         let node_key = SecretKey::from_seed(KeyType::ED25519, seed);
         let validator = ValidatorConfig {
             signer: Arc::new(create_test_signer(seed)),
-            proxies: ValidatorProxies::Static(vec![PeerAddr {
-                addr: *node_addr,
-                peer_id: PeerId::new(node_key.public_key()),
-            }]),
+            proxies: ValidatorProxies::Static(vec![PeerAddr::new(
+                PeerId::new(node_key.public_key()),
+                *node_addr,
+            )]),
         };
         NetworkConfig {
             node_addr: Some(node_addr),
+            extra_addrs: vec![],
             node_key,
             validator: Some(validator),
             peer_store: peer_store::Config {
@@ -373,6 +530,11 @@ impl NetworkConfig {
                 peer_expiration_duration: time::Duration::seconds(60 * 60),
                 connect_only_to_boot_nodes: false,
             },
+            reputation: reputation::Config {
+                half_life: time::Duration::hours(1),
+                disconnect_threshold: -20.,
+                ban_threshold: -50.,
+            },
             snapshot_hosts: snapshot_hosts::Config {
                 snapshot_hosts_cache_size: 1000,
                 part_selection_cache_batch_size: 10,
@@ -401,6 +563,8 @@ impl NetworkConfig {
             accounts_data_broadcast_rate_limit: rate::Limit { qps: 100., burst: 1000000 },
             snapshot_hosts_broadcast_rate_limit: rate::Limit { qps: 100., burst: 1000000 },
             routing_table_update_rate_limit: rate::Limit { qps: 10., burst: 1 },
+            peer_send_rate_limits: SendRateLimits::default(),
+            peer_request_rate_limits: RequestRateLimits::default(),
             tier1: Some(Tier1 {
                 // Interval is very large, so that it doesn't happen spontaneously in tests.
                 // It should rather be triggered manually in tests.
@@ -409,8 +573,11 @@ impl NetworkConfig {
                 advertise_proxies_interval: time::Duration::hours(1000),
                 enable_inbound: true,
                 enable_outbound: true,
+                enable_quic: false,
             }),
             skip_tombstones: None,
+            message_compression_threshold: Some(1024),
+            traffic_capture: None,
             #[cfg(test)]
             event_sink: near_async::messaging::IntoSender::into_sender(
                 near_async::messaging::noop(),
@@ -463,6 +630,27 @@ impl NetworkConfig {
         self.routing_table_update_rate_limit
             .validate()
             .context("routing_table_update_rate_limit")?;
+        self.reputation.validate().context("reputation")?;
+        if let Some(traffic_capture) = &self.traffic_capture {
+            traffic_capture.validate().context("traffic_capture")?;
+        }
+        for (name, limit) in [
+            ("sync_responses", &self.peer_send_rate_limits.sync_responses),
+            ("state_parts", &self.peer_send_rate_limits.state_parts),
+            ("routed_chunks", &self.peer_send_rate_limits.routed_chunks),
+        ] {
+            if let Some(limit) = limit {
+                limit.validate().with_context(|| format!("peer_send_rate_limits.{name}"))?;
+            }
+        }
+        for (name, limit) in [
+            ("light_client", &self.peer_request_rate_limits.light_client),
+            ("epoch_sync", &self.peer_request_rate_limits.epoch_sync),
+        ] {
+            if let Some(limit) = limit {
+                limit.validate().with_context(|| format!("peer_request_rate_limits.{name}"))?;
+            }
+        }
         Ok(VerifiedConfig { node_id: self.node_id(), inner: self })
     }
 }
@@ -527,6 +715,28 @@ mod test {
         assert!(nc.verify().is_err());
     }
 
+    #[test]
+    fn test_is_public_ip() {
+        for ip in ["1.2.3.4", "2001:db8::1", "::ffff:1.2.3.4"] {
+            assert!(config::is_public_ip(ip.parse().unwrap()), "{ip}");
+        }
+        for ip in [
+            "127.0.0.1",
+            "0.0.0.0",
+            "10.0.0.1",
+            "192.168.1.1",
+            "169.254.0.1",
+            "255.255.255.255",
+            "::1",
+            "::",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:10.0.0.1",
+        ] {
+            assert!(!config::is_public_ip(ip.parse().unwrap()), "{ip}");
+        }
+    }
+
     #[test]
     fn test_network_config_override() {
         fn check_override_field<T: std::cmp::PartialEq>(
@@ -627,10 +837,15 @@ mod test {
                     .map(|_| {
                         // Using IPv6 gives maximal size of the resulting config.
                         let ip = data::make_ipv6(&mut rng);
-                        data::make_peer_addr(&mut rng, ip)
+                        let mut peer_addr = data::make_peer_addr(&mut rng, ip);
+                        peer_addr.extra_addrs = (1..config::MAX_ADDRS_PER_PEER)
+                            .map(|_| data::make_peer_addr(&mut rng, ip).addr)
+                            .collect();
+                        peer_addr
                     })
                     .collect(),
                 peer_id: data::make_peer_id(&mut rng),
+                quic: true,
             },
             account_key: signer.public_key(),
             version: 0,
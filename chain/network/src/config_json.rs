@@ -1,6 +1,7 @@
 use crate::network_protocol::PeerAddr;
 use crate::stun;
 use near_async::time::Duration;
+use std::path::PathBuf;
 
 /// Time to persist Accounts Id in the router without removing them in seconds.
 pub const TTL_ACCOUNT_ID_ROUTER: i64 = 60 * 60;
@@ -79,6 +80,14 @@ fn default_trusted_stun_servers() -> Vec<stun::ServerAddr> {
 pub struct Config {
     /// Local address to listen for incoming connections.
     pub addr: String,
+    /// Additional public addresses of this node (for example the IPv6 address of a dual-stack
+    /// node, listening on "[::]:24567"), in the order of preference. They are advertised to
+    /// the peers, which try them if they fail to connect to the address of this node they have
+    /// observed.
+    /// Example:
+    ///   ["[2001:db8::1]:24567"]
+    #[serde(default)]
+    pub extra_addrs: Vec<std::net::SocketAddr>,
     /// Comma separated list of nodes to connect to.
     /// Examples:
     ///   ed25519:86EtEy7epneKyrcJwSWP7zsisTkfDRH5CFVszt4qiQYw@31.192.22.209:24567
@@ -160,6 +169,10 @@ pub struct Config {
     /// Example:
     ///   ["ed25519:86EtEy7epneKyrcJwSWP7zsisTkfDRH5CFVszt4qiQYw@31.192.22.209:24567"]
     ///
+    /// A dual-stack node can be reachable under multiple addresses, which are then listed
+    /// from the most preferred one:
+    ///   ["ed25519:86EtEy7epneKyrcJwSWP7zsisTkfDRH5CFVszt4qiQYw@31.192.22.209:24567@[2001:db8::1]:24567"]
+    ///
     /// Recommended setup (requires public static IP):
     /// In the simplest case this list should contains just 1 public address (with the node public
     /// key) of this validator.
@@ -172,9 +185,10 @@ pub struct Config {
     ///
     /// Less recommended setup (requires exactly one public dynamic/ephemeral or static IP):
     /// If the list is empty, the validator node will query trusted_stun_servers to determine its own IP.
-    /// Only if the answer from the STUN servers is unambiguous (at least 1 server responds and
-    /// all received responses provide the same IP), the IP (together with the port deduced from
-    /// the addr field in this config) will be signed and broadcasted.
+    /// The servers are queried over both IPv4 and IPv6. For each of them, only if the answer from
+    /// the STUN servers is unambiguous (at least 1 server responds and all received responses
+    /// provide the same IP), the IP (together with the port deduced from the addr field in this
+    /// config) will be signed and broadcasted.
     ///
     /// Discouraged setup (might be removed in the future)
     /// If the list is empty and STUN servers' response is ambiguous, the peers which connect to
@@ -215,6 +229,30 @@ fn default_tier1_connect_interval() -> Duration {
     Duration::seconds(60)
 }
 
+fn default_message_compression_threshold_bytes() -> Option<u64> {
+    Some(16 * 1024)
+}
+
+fn default_traffic_capture_max_file_bytes() -> u64 {
+    100 * 1024 * 1024
+}
+
+fn default_traffic_capture_max_files() -> usize {
+    10
+}
+
+fn default_peer_reputation_half_life() -> Duration {
+    Duration::hours(1)
+}
+
+fn default_peer_reputation_disconnect_threshold() -> f64 {
+    -20.
+}
+
+fn default_peer_reputation_ban_threshold() -> f64 {
+    -50.
+}
+
 fn default_tier1_new_connections_per_attempt() -> u64 {
     50
 }
@@ -253,6 +291,34 @@ pub struct ExperimentalConfig {
     #[serde(default = "default_tier1_new_connections_per_attempt")]
     pub tier1_new_connections_per_attempt: u64,
 
+    /// See `near_network::config::Tier1::enable_quic`.
+    #[serde(default)]
+    pub tier1_enable_quic: bool,
+
+    /// See `near_network::config::NetworkConfig::message_compression_threshold`.
+    /// Set to null to disable compression of the sent messages.
+    #[serde(default = "default_message_compression_threshold_bytes")]
+    pub message_compression_threshold_bytes: Option<u64>,
+
+    /// See `near_network::config::NetworkConfig::reputation`.
+    #[serde(default = "default_peer_reputation_half_life")]
+    #[serde(with = "near_async::time::serde_duration_as_std")]
+    pub peer_reputation_half_life: Duration,
+    #[serde(default = "default_peer_reputation_disconnect_threshold")]
+    pub peer_reputation_disconnect_threshold: f64,
+    #[serde(default = "default_peer_reputation_ban_threshold")]
+    pub peer_reputation_ban_threshold: f64,
+
+    /// See `near_network::config::NetworkConfig::traffic_capture`.
+    /// Relative paths are resolved against the node's home directory.
+    /// Unset by default, which disables the capture.
+    #[serde(default)]
+    pub traffic_capture_dir: Option<PathBuf>,
+    #[serde(default = "default_traffic_capture_max_file_bytes")]
+    pub traffic_capture_max_file_bytes: u64,
+    #[serde(default = "default_traffic_capture_max_files")]
+    pub traffic_capture_max_files: usize,
+
     /// See `NetworkConfig`.
     /// Fields set here will override the NetworkConfig fields.
     #[serde(default)]
@@ -274,6 +340,16 @@ pub struct NetworkConfigOverrides {
     pub accounts_data_broadcast_rate_limit_qps: Option<f64>,
     pub routing_table_update_rate_limit_burst: Option<u64>,
     pub routing_table_update_rate_limit_qps: Option<f64>,
+    pub sync_responses_send_rate_limit_burst: Option<u64>,
+    pub sync_responses_send_rate_limit_qps: Option<f64>,
+    pub state_parts_send_rate_limit_burst: Option<u64>,
+    pub state_parts_send_rate_limit_qps: Option<f64>,
+    pub routed_chunks_send_rate_limit_burst: Option<u64>,
+    pub routed_chunks_send_rate_limit_qps: Option<f64>,
+    pub light_client_requests_rate_limit_burst: Option<u64>,
+    pub light_client_requests_rate_limit_qps: Option<f64>,
+    pub epoch_sync_requests_rate_limit_burst: Option<u64>,
+    pub epoch_sync_requests_rate_limit_qps: Option<f64>,
 }
 
 impl Default for ExperimentalConfig {
@@ -286,6 +362,14 @@ impl Default for ExperimentalConfig {
             tier1_enable_outbound: default_tier1_enable_outbound(),
             tier1_connect_interval: default_tier1_connect_interval(),
             tier1_new_connections_per_attempt: default_tier1_new_connections_per_attempt(),
+            tier1_enable_quic: false,
+            message_compression_threshold_bytes: default_message_compression_threshold_bytes(),
+            peer_reputation_half_life: default_peer_reputation_half_life(),
+            peer_reputation_disconnect_threshold: default_peer_reputation_disconnect_threshold(),
+            peer_reputation_ban_threshold: default_peer_reputation_ban_threshold(),
+            traffic_capture_dir: None,
+            traffic_capture_max_file_bytes: default_traffic_capture_max_file_bytes(),
+            traffic_capture_max_files: default_traffic_capture_max_files(),
             network_config_overrides: Default::default(),
         }
     }
@@ -295,6 +379,7 @@ impl Default for Config {
     fn default() -> Self {
         Config {
             addr: "0.0.0.0:24567".to_string(),
+            extra_addrs: vec![],
             boot_nodes: "".to_string(),
             whitelist_nodes: "".to_string(),
             max_num_peers: default_max_num_peers(),
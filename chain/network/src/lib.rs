@@ -1,3 +1,4 @@
+pub use crate::peer::capture;
 pub use crate::peer_manager::peer_manager_actor::{Event, PeerManagerActor};
 
 mod accounts_data;
@@ -6,12 +7,14 @@ mod network_protocol;
 mod peer;
 mod peer_manager;
 mod private_actix;
+mod quic;
 mod snapshot_hosts;
 mod stats;
 mod store;
 mod stun;
 
 pub mod actix;
+pub mod admin;
 pub mod blacklist;
 pub mod client;
 pub mod concurrency;
@@ -14,6 +14,9 @@ impl From<&net::Handshake> for mem::Handshake {
             sender_chain_info: x.sender_chain_info.clone(),
             partial_edge_info: x.partial_edge_info.clone(),
             owned_account: None,
+            session_key: None,
+            supported_compression: vec![],
+            sender_extra_addrs: vec![],
         }
     }
 }
@@ -253,6 +256,14 @@ impl From<&mem::PeerMessage> for net::PeerMessage {
                 net::PeerMessage::VersionedStateResponse(sri)
             }
             mem::PeerMessage::SyncSnapshotHosts(ssh) => net::PeerMessage::SyncSnapshotHosts(ssh),
+            mem::PeerMessage::LightClientBlockRequest(_)
+            | mem::PeerMessage::LightClientBlockResponse(_)
+            | mem::PeerMessage::EpochBlockProducersRequest(_)
+            | mem::PeerMessage::EpochBlockProducersResponse(_)
+            | mem::PeerMessage::EpochSyncRequest(_)
+            | mem::PeerMessage::EpochSyncResponse(_) => {
+                panic!("{} is not supported in Borsh encoding", x.msg_variant())
+            }
         }
     }
 }
@@ -0,0 +1,110 @@
+/// Compression of proto-encoded PeerMessages.
+/// See CompressedPeerMessage in network_protocol/network.proto.
+use super::proto;
+use crate::network_protocol::proto::peer_message::Message_type as ProtoMT;
+use crate::peer::stream::NETWORK_MESSAGE_MAX_SIZE_BYTES;
+use crate::stats::metrics;
+use protobuf::Message as _;
+use std::io::Read as _;
+
+/// zstd compression level. Low levels are much faster than high ones
+/// and the messages we compress (mostly borsh-encoded) don't compress
+/// much better with higher levels anyway.
+const ZSTD_LEVEL: i32 = 3;
+
+/// Upper bound on the size of a decompressed message.
+/// A compressed message cannot expand beyond what an uncompressed frame could carry.
+const MAX_UNCOMPRESSED_SIZE: u64 = NETWORK_MESSAGE_MAX_SIZE_BYTES as u64;
+
+/// Algorithm used to compress a PeerMessage.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, strum::IntoStaticStr)]
+pub enum Compression {
+    Zstd,
+}
+
+impl Compression {
+    /// Algorithms that this node is able to decompress, in the order of preference.
+    pub(crate) const SUPPORTED: &'static [Compression] = &[Compression::Zstd];
+
+    /// Picks the algorithm to use for messages sent to a peer supporting `peer_supported`.
+    pub(crate) fn negotiate(peer_supported: &[Compression]) -> Option<Compression> {
+        Self::SUPPORTED.iter().find(|c| peer_supported.contains(c)).copied()
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum DecompressError {
+    #[error("unknown compression algorithm")]
+    UnknownAlgorithm,
+    #[error("uncompressed size too large: got {got_bytes}B, want <={want_max_bytes}B")]
+    TooLarge { got_bytes: u64, want_max_bytes: u64 },
+    #[error("zstd: {0}")]
+    Zstd(#[source] std::io::Error),
+    #[error("uncompressed size mismatch: got {got_bytes}B, want {want_bytes}B")]
+    SizeMismatch { got_bytes: usize, want_bytes: u64 },
+    #[error("proto: {0}")]
+    ProtoDecode(#[source] protobuf::Error),
+}
+
+/// Compresses a proto-encoded PeerMessage and wraps it into a CompressedPeerMessage.
+/// `msg_type` is used only for metrics.
+pub(crate) fn compress(data: &[u8], compression: Compression, msg_type: &'static str) -> Vec<u8> {
+    let timer =
+        metrics::PEER_MESSAGE_COMPRESSION_TIME.with_label_values(&["compress"]).start_timer();
+    let compressed = match compression {
+        Compression::Zstd => {
+            zstd::bulk::compress(data, ZSTD_LEVEL).expect("zstd compression failed")
+        }
+    };
+    timer.observe_duration();
+    metrics::PEER_MESSAGE_COMPRESSION_RATIO
+        .with_label_values(&[msg_type])
+        .observe(compressed.len() as f64 / data.len().max(1) as f64);
+    proto::PeerMessage {
+        message_type: Some(ProtoMT::Compressed(proto::CompressedPeerMessage {
+            algorithm: proto::compressed_peer_message::Algorithm::from(compression).into(),
+            uncompressed_size: data.len() as u64,
+            data: compressed,
+            ..Default::default()
+        })),
+        ..Default::default()
+    }
+    .write_to_bytes()
+    .unwrap()
+}
+
+/// Decompresses the PeerMessage wrapped in a CompressedPeerMessage.
+pub(crate) fn decompress(
+    msg: &proto::CompressedPeerMessage,
+) -> Result<proto::PeerMessage, DecompressError> {
+    if msg.uncompressed_size > MAX_UNCOMPRESSED_SIZE {
+        return Err(DecompressError::TooLarge {
+            got_bytes: msg.uncompressed_size,
+            want_max_bytes: MAX_UNCOMPRESSED_SIZE,
+        });
+    }
+    let _timer =
+        metrics::PEER_MESSAGE_COMPRESSION_TIME.with_label_values(&["decompress"]).start_timer();
+    // uncompressed_size is declared by the sender, so it is not used to preallocate the
+    // output: the buffer grows only as data is actually decompressed. The decoder output is
+    // cut one byte past the declared size, which is enough to detect a mismatch without
+    // ever holding more than MAX_UNCOMPRESSED_SIZE + 1 bytes.
+    let mut data = vec![];
+    match msg.algorithm.enum_value() {
+        Ok(proto::compressed_peer_message::Algorithm::ZSTD) => {
+            zstd::stream::read::Decoder::new(&msg.data[..])
+                .map_err(DecompressError::Zstd)?
+                .take(msg.uncompressed_size + 1)
+                .read_to_end(&mut data)
+                .map_err(DecompressError::Zstd)?;
+        }
+        _ => return Err(DecompressError::UnknownAlgorithm),
+    }
+    if data.len() as u64 != msg.uncompressed_size {
+        return Err(DecompressError::SizeMismatch {
+            got_bytes: data.len(),
+            want_bytes: msg.uncompressed_size,
+        });
+    }
+    proto::PeerMessage::parse_from_bytes(&data).map_err(DecompressError::ProtoDecode)
+}
@@ -0,0 +1,18 @@
+use near_primitives::epoch_manager::epoch_sync::EpochSyncInfo;
+use near_primitives::types::EpochId;
+
+/// Maximum number of `EpochSyncInfo`s sent in a single `EpochSyncInfoResponse`.
+/// Each of them contains hashes of all blocks of the epoch, so responses are large.
+pub const MAX_EPOCH_SYNC_INFOS_PER_RESPONSE: usize = 4;
+
+/// Response to `PeerMessage::EpochSyncRequest`.
+///
+/// Contains `EpochSyncInfo`s of consecutive finalised epochs starting with the requested one,
+/// which allow a new node to verify the chain of validator sets without downloading all headers.
+#[derive(Clone, Debug, PartialEq, Eq, borsh::BorshSerialize, borsh::BorshDeserialize)]
+pub struct EpochSyncInfoResponse {
+    /// Epoch from the request.
+    pub epoch_id: EpochId,
+    /// Empty if the requested epoch is not finalised yet, or the responding node doesn't know it.
+    pub epoch_sync_infos: Vec<EpochSyncInfo>,
+}
@@ -0,0 +1,37 @@
+use near_primitives::hash::CryptoHash;
+use near_primitives::views::validator_stake_view::ValidatorStakeView;
+use near_primitives::views::{LightClientBlockLiteView, LightClientBlockView};
+
+/// Response to `PeerMessage::LightClientBlockRequest`.
+///
+/// Contains the next block a light client which knows `last_block_hash` should
+/// validate, as returned by the `next_light_client_block` JSON-RPC method.
+#[derive(Clone, Debug, PartialEq, Eq, borsh::BorshSerialize, borsh::BorshDeserialize)]
+pub struct LightClientBlockResponse {
+    /// Hash from the request.
+    pub last_block_hash: CryptoHash,
+    /// None if there is no newer block, or the responding node doesn't know `last_block_hash`.
+    pub block: Option<Box<LightClientBlockView>>,
+}
+
+/// Block producers of an epoch, together with a proof that they were committed to
+/// by a block of the previous epoch.
+#[derive(Clone, Debug, PartialEq, Eq, borsh::BorshSerialize, borsh::BorshDeserialize)]
+pub struct EpochBlockProducers {
+    /// Header of the requested block. Its `inner_lite.next_bp_hash` is the hash of `next_bps`.
+    pub header: LightClientBlockLiteView,
+    /// Ordered block producers of the epoch following the epoch of `header`.
+    pub next_bps: Vec<ValidatorStakeView>,
+}
+
+/// Response to `PeerMessage::EpochBlockProducersRequest`.
+///
+/// Allows a light client to bootstrap from a trusted block hash: the client verifies
+/// that `header` hashes to the trusted hash, and that `next_bps` match the hash in the header.
+#[derive(Clone, Debug, PartialEq, Eq, borsh::BorshSerialize, borsh::BorshDeserialize)]
+pub struct EpochBlockProducersResponse {
+    /// Hash from the request.
+    pub block_hash: CryptoHash,
+    /// None if the responding node doesn't know `block_hash`.
+    pub block_producers: Option<EpochBlockProducers>,
+}
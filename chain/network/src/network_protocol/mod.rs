@@ -2,13 +2,19 @@
 #[path = "borsh.rs"]
 mod borsh_;
 mod borsh_conv;
+pub(crate) mod compression;
 mod edge;
+mod epoch_sync;
+mod light_client;
 mod peer;
 mod proto_conv;
 mod state_sync;
+pub use compression::Compression;
 pub use edge::*;
+pub use epoch_sync::*;
+pub use light_client::*;
 use near_primitives::stateless_validation::ChunkEndorsement;
-use near_primitives::stateless_validation::ChunkStateWitness;
+use near_primitives::stateless_validation::{ChunkStateWitness, PartialEncodedStateWitness};
 pub use peer::*;
 pub use state_sync::*;
 
@@ -29,6 +35,7 @@ use crate::network_protocol::proto_conv::trace_context::{
 use borsh::BorshDeserialize as _;
 use near_async::time;
 use near_crypto::PublicKey;
+use near_crypto::SecretKey;
 use near_crypto::Signature;
 use near_o11y::OpenTelemetrySpanExt;
 use near_primitives::block::{Approval, Block, BlockHeader, GenesisId};
@@ -43,7 +50,7 @@ use near_primitives::state_sync::{ShardStateSyncResponse, ShardStateSyncResponse
 use near_primitives::transaction::SignedTransaction;
 use near_primitives::types::AccountId;
 use near_primitives::types::{BlockHeight, ShardId};
-use near_primitives::validator_signer::ValidatorSigner;
+use near_primitives::validator_signer::{ValidatorSigner, ValidatorSignerError};
 use near_primitives::views::FinalExecutionOutcomeView;
 use protobuf::Message as _;
 use std::collections::HashSet;
@@ -54,13 +61,50 @@ use tracing::Span;
 
 #[derive(PartialEq, Eq, Clone, Debug, Hash)]
 pub struct PeerAddr {
+    /// The most preferred address of the peer.
     pub addr: std::net::SocketAddr,
     pub peer_id: PeerId,
+    /// Alternative addresses of the peer (for example the IPv6 address of a dual-stack node),
+    /// in the order of preference.
+    pub extra_addrs: Vec<std::net::SocketAddr>,
+}
+
+impl PeerAddr {
+    pub fn new(peer_id: PeerId, addr: std::net::SocketAddr) -> Self {
+        Self { addr, peer_id, extra_addrs: vec![] }
+    }
+
+    /// All the addresses of the peer, from the most preferred one.
+    pub fn addrs(&self) -> impl Iterator<Item = std::net::SocketAddr> + '_ {
+        std::iter::once(self.addr)
+            .chain(self.extra_addrs.iter().filter(|a| **a != self.addr).copied())
+    }
+}
+
+impl From<&PeerAddr> for PeerInfo {
+    fn from(x: &PeerAddr) -> Self {
+        Self {
+            id: x.peer_id.clone(),
+            addr: Some(x.addr),
+            account_id: None,
+            extra_addrs: x.extra_addrs.clone(),
+        }
+    }
+}
+
+impl fmt::Display for PeerAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.peer_id, self.addr)?;
+        for addr in &self.extra_addrs {
+            write!(f, "@{}", addr)?;
+        }
+        Ok(())
+    }
 }
 
 impl serde::Serialize for PeerAddr {
     fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
-        s.serialize_str(&format!("{}@{}", self.peer_id, self.addr))
+        s.serialize_str(&self.to_string())
     }
 }
 
@@ -72,7 +116,7 @@ impl<'a> serde::Deserialize<'a> for PeerAddr {
 
 #[derive(thiserror::Error, Debug)]
 pub enum ParsePeerAddrError {
-    #[error("expected <PeerId>@<IP>:<port>, got \'{0}\'")]
+    #[error("expected <PeerId>@<IP>:<port>[@<IP>:<port>...], got \'{0}\'")]
     Format(String),
     #[error("PeerId: {0}")]
     PeerId(#[source] near_crypto::ParseKeyError),
@@ -84,12 +128,17 @@ impl std::str::FromStr for PeerAddr {
     type Err = ParsePeerAddrError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let parts: Vec<_> = s.split('@').collect();
-        if parts.len() != 2 {
+        if parts.len() < 2 {
             return Err(Self::Err::Format(s.to_string()));
         }
+        let addrs: Vec<std::net::SocketAddr> = parts[1..]
+            .iter()
+            .map(|a| a.parse().map_err(Self::Err::SocketAddr))
+            .collect::<Result<_, _>>()?;
         Ok(PeerAddr {
             peer_id: PeerId::new(parts[0].parse().map_err(Self::Err::PeerId)?),
-            addr: parts[1].parse().map_err(Self::Err::SocketAddr)?,
+            addr: addrs[0],
+            extra_addrs: addrs[1..].to_vec(),
         })
     }
 }
@@ -112,6 +161,9 @@ pub struct AccountData {
     /// TIER1 nodes should connect to one of the proxies to sent TIER1
     /// messages to the validator.
     pub proxies: Vec<PeerAddr>,
+    /// Whether the proxies accept TIER1 connections over QUIC,
+    /// on the UDP port equal to their TCP port.
+    pub quic: bool,
 }
 
 /// Wrapper of the AccountData which adds metadata to it.
@@ -179,7 +231,7 @@ impl VersionedAccountData {
                 MAX_ACCOUNT_DATA_SIZE_BYTES
             );
         }
-        let signature = signer.sign_account_key_payload(&payload);
+        let signature = signer.sign_account_key_payload(&payload)?;
         Ok(SignedAccountData {
             account_data: self,
             payload: AccountKeySignedPayload { payload, signature },
@@ -251,18 +303,60 @@ impl OwnedAccount {
     /// Serializes OwnedAccount to proto and signs it using `signer`.
     /// Panics if OwnedAccount.account_key doesn't match signer.public_key(),
     /// as this would likely be a bug.
-    pub fn sign(self, signer: &dyn ValidatorSigner) -> SignedOwnedAccount {
+    pub fn sign(
+        self,
+        signer: &dyn ValidatorSigner,
+    ) -> Result<SignedOwnedAccount, ValidatorSignerError> {
         assert_eq!(
             self.account_key,
             signer.public_key(),
             "OwnedAccount.account_key doesn't match the signer's account_key"
         );
         let payload = proto::AccountKeyPayload::from(&self).write_to_bytes().unwrap();
-        let signature = signer.sign_account_key_payload(&payload);
-        SignedOwnedAccount {
+        let signature = signer.sign_account_key_payload(&payload)?;
+        Ok(SignedOwnedAccount {
             owned_account: self,
             payload: AccountKeySignedPayload { payload, signature },
-        }
+        })
+    }
+}
+
+/// Ephemeral X25519 public key of the sender, used to establish an encrypted session.
+/// It is signed with the node key of the sender, which binds the session to the sender's
+/// PeerId and to the edge nonce proposed in the same Handshake.
+/// See SessionKey in network_protocol/network.proto.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct SessionKey {
+    pub(crate) ephemeral_key: [u8; 32],
+    pub(crate) signature: Signature,
+}
+
+impl SessionKey {
+    pub fn new(
+        ephemeral_key: [u8; 32],
+        sender: &PeerId,
+        target: &PeerId,
+        nonce: u64,
+        node_key: &SecretKey,
+    ) -> Self {
+        let data = Self::build_hash(&ephemeral_key, sender, target, nonce);
+        let signature = node_key.sign(data.as_ref());
+        Self { ephemeral_key, signature }
+    }
+
+    /// Verifies that the key has been signed by `sender` for a connection to `target`.
+    pub fn verify(&self, sender: &PeerId, target: &PeerId, nonce: u64) -> bool {
+        let data = Self::build_hash(&self.ephemeral_key, sender, target, nonce);
+        self.signature.verify(data.as_ref(), sender.public_key())
+    }
+
+    fn build_hash(
+        ephemeral_key: &[u8; 32],
+        sender: &PeerId,
+        target: &PeerId,
+        nonce: u64,
+    ) -> CryptoHash {
+        CryptoHash::hash_borsh(("near-p2p-session-key", ephemeral_key, sender, target, nonce))
     }
 }
 
@@ -350,6 +444,12 @@ pub struct Handshake {
     pub(crate) partial_edge_info: PartialEdgeInfo,
     /// Account owned by the sender.
     pub(crate) owned_account: Option<SignedOwnedAccount>,
+    /// Key offered by the sender to encrypt the connection.
+    pub(crate) session_key: Option<SessionKey>,
+    /// Compression algorithms that the sender is able to decompress.
+    pub(crate) supported_compression: Vec<Compression>,
+    /// Public addresses of the sender, other than the one observed by the receiver.
+    pub(crate) sender_extra_addrs: Vec<std::net::SocketAddr>,
 }
 
 #[derive(PartialEq, Eq, Clone, Debug, strum::IntoStaticStr)]
@@ -429,6 +529,19 @@ pub enum PeerMessage {
     StateRequestHeader(ShardId, CryptoHash),
     StateRequestPart(ShardId, CryptoHash, u64),
     VersionedStateResponse(StateResponseInfo),
+
+    /// Requests the next light client block after the given block,
+    /// so that light clients can follow the chain over P2P.
+    LightClientBlockRequest(CryptoHash),
+    LightClientBlockResponse(LightClientBlockResponse),
+    /// Requests the block producers of the epoch following the epoch of the given block.
+    EpochBlockProducersRequest(CryptoHash),
+    EpochBlockProducersResponse(EpochBlockProducersResponse),
+
+    /// Requests `EpochSyncInfo`s starting with the given epoch, so that a new node can
+    /// bootstrap the chain of epochs without header sync.
+    EpochSyncRequest(EpochId),
+    EpochSyncResponse(EpochSyncInfoResponse),
 }
 
 impl fmt::Display for PeerMessage {
@@ -437,7 +550,17 @@ impl fmt::Display for PeerMessage {
     }
 }
 
-#[derive(Copy, Clone, PartialEq, Eq, Debug, Hash, strum::IntoStaticStr)]
+#[derive(
+    Copy,
+    Clone,
+    PartialEq,
+    Eq,
+    Debug,
+    Hash,
+    strum::IntoStaticStr,
+    borsh::BorshSerialize,
+    borsh::BorshDeserialize,
+)]
 pub enum Encoding {
     Borsh,
     Proto,
@@ -453,6 +576,8 @@ pub enum ParsePeerMessageError {
     ProtoDecode(#[source] protobuf::Error),
     #[error("ProtoConv")]
     ProtoConv(#[source] proto_conv::ParsePeerMessageError),
+    #[error("Decompress")]
+    Decompress(#[source] compression::DecompressError),
 }
 
 impl PeerMessage {
@@ -481,8 +606,14 @@ impl PeerMessage {
                 .try_into()
                 .map_err(ParsePeerMessageError::BorshConv)?,
             Encoding::Proto => {
-                let proto_msg: proto::PeerMessage = proto::PeerMessage::parse_from_bytes(data)
+                let mut proto_msg: proto::PeerMessage = proto::PeerMessage::parse_from_bytes(data)
                     .map_err(ParsePeerMessageError::ProtoDecode)?;
+                if let Some(proto::peer_message::Message_type::Compressed(msg)) =
+                    &proto_msg.message_type
+                {
+                    proto_msg =
+                        compression::decompress(msg).map_err(ParsePeerMessageError::Decompress)?;
+                }
                 if let Ok(extracted_span_context) = extract_span_context(&proto_msg.trace_context) {
                     span.clone().or_current().add_link(extracted_span_context);
                 }
@@ -491,6 +622,22 @@ impl PeerMessage {
         })
     }
 
+    /// Whether the message type may carry a payload large enough to be worth compressing.
+    pub(crate) fn is_compressible(&self) -> bool {
+        match self {
+            PeerMessage::Block(_) | PeerMessage::VersionedStateResponse(_) => true,
+            PeerMessage::Routed(msg) => matches!(
+                msg.body,
+                RoutedMessageBody::VersionedPartialEncodedChunk(_)
+                    | RoutedMessageBody::PartialEncodedChunkResponse(_)
+                    | RoutedMessageBody::ChunkStateWitness(_)
+                    | RoutedMessageBody::PartialEncodedStateWitness(_)
+                    | RoutedMessageBody::PartialEncodedStateWitnessForward(_)
+            ),
+            _ => false,
+        }
+    }
+
     pub(crate) fn msg_variant(&self) -> &'static str {
         match self {
             PeerMessage::Routed(routed_msg) => routed_msg.body_variant(),
@@ -531,6 +678,11 @@ pub enum RoutedMessageBody {
     PartialEncodedChunkForward(PartialEncodedChunkForwardMsg),
     ChunkStateWitness(ChunkStateWitness),
     ChunkEndorsement(ChunkEndorsement),
+    /// Part of a chunk state witness, sent by the chunk producer to the chunk validator
+    /// which is supposed to forward it to the other chunk validators.
+    PartialEncodedStateWitness(PartialEncodedStateWitness),
+    /// Part of a chunk state witness, forwarded by the chunk validator it was sent to.
+    PartialEncodedStateWitnessForward(PartialEncodedStateWitness),
 }
 
 impl RoutedMessageBody {
@@ -544,6 +696,8 @@ impl RoutedMessageBody {
             RoutedMessageBody::BlockApproval(_)
             | RoutedMessageBody::ChunkEndorsement(_)
             | RoutedMessageBody::ChunkStateWitness(_)
+            | RoutedMessageBody::PartialEncodedStateWitness(_)
+            | RoutedMessageBody::PartialEncodedStateWitnessForward(_)
             | RoutedMessageBody::VersionedPartialEncodedChunk(_) => true,
             _ => false,
         }
@@ -598,6 +752,16 @@ impl fmt::Debug for RoutedMessageBody {
             RoutedMessageBody::_UnusedVersionedStateResponse => write!(f, "VersionedStateResponse"),
             RoutedMessageBody::ChunkStateWitness(_) => write!(f, "ChunkStateWitness"),
             RoutedMessageBody::ChunkEndorsement(_) => write!(f, "ChunkEndorsement"),
+            RoutedMessageBody::PartialEncodedStateWitness(part) => write!(
+                f,
+                "PartialEncodedStateWitness({}, {}, {})",
+                part.shard_id, part.height_created, part.part_ord
+            ),
+            RoutedMessageBody::PartialEncodedStateWitnessForward(part) => write!(
+                f,
+                "PartialEncodedStateWitnessForward({}, {}, {})",
+                part.shard_id, part.height_created, part.part_ord
+            ),
         }
     }
 }
@@ -14,23 +14,37 @@ use std::str::FromStr;
 #[derive(borsh::BorshSerialize, borsh::BorshDeserialize, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct PeerInfo {
     pub id: PeerId,
+    /// The most preferred address of the peer.
     pub addr: Option<SocketAddr>,
     pub account_id: Option<AccountId>,
+    /// Alternative addresses of the peer (for example the IPv6 address of a dual-stack node),
+    /// in the order of preference. They are tried when connecting to `addr` fails.
+    ///
+    /// Not a part of the Borsh encoding, so that the encoding stays compatible with
+    /// the older nodes and with the PeerInfo persisted in the DB. Protobuf encoding
+    /// carries them in a separate field.
+    #[borsh(skip)]
+    pub extra_addrs: Vec<SocketAddr>,
 }
 
 impl PeerInfo {
     /// Creates random peer info.
     pub fn new(id: PeerId, addr: SocketAddr) -> Self {
-        PeerInfo { id, addr: Some(addr), account_id: None }
+        PeerInfo { id, addr: Some(addr), account_id: None, extra_addrs: vec![] }
     }
 
     pub fn random() -> Self {
-        PeerInfo { id: PeerId::random(), addr: None, account_id: None }
+        PeerInfo { id: PeerId::random(), addr: None, account_id: None, extra_addrs: vec![] }
     }
 
     pub fn addr_port(&self) -> Option<u16> {
         self.addr.map(|addr| addr.port())
     }
+
+    /// All the known addresses of the peer, from the most preferred one.
+    pub fn addrs(&self) -> impl Iterator<Item = SocketAddr> + '_ {
+        self.addr.iter().chain(self.extra_addrs.iter().filter(|a| Some(**a) != self.addr)).copied()
+    }
 }
 
 // Note, `Display` automatically implements `ToString` which must be reciprocal to `FromStr`.
@@ -39,6 +53,9 @@ impl fmt::Display for PeerInfo {
         write!(f, "{}", self.id)?;
         if let Some(addr) = &self.addr {
             write!(f, "@{}", addr)?;
+            for addr in &self.extra_addrs {
+                write!(f, "@{}", addr)?;
+            }
         }
         if let Some(account_id) = &self.account_id {
             write!(f, "@{}", account_id)?;
@@ -65,6 +82,9 @@ impl FromStr for PeerInfo {
     ///     ed25519:C6HLP37VJN1Wj2irxxZPsVsSya92Rnx12tqK3us5erKV@test.near
     ///     ed25519:C6HLP37VJN1Wj2irxxZPsVsSya92Rnx12tqK3us5erKV@127.0.0.1:24567@test.near
     ///
+    /// Multiple addresses can be specified, from the most preferred one:
+    ///     ed25519:C6HLP37VJN1Wj2irxxZPsVsSya92Rnx12tqK3us5erKV@127.0.0.1:24567@[::1]:24567@test.near
+    ///
     /// Hostname can be used instead of IP address, if node trusts DNS server it connects to, for example:
     ///     ed25519:C6HLP37VJN1Wj2irxxZPsVsSya92Rnx12tqK3us5erKV@localhost:24567@test.near
     ///     ed25519:C6HLP37VJN1Wj2irxxZPsVsSya92Rnx12tqK3us5erKV@my.own.node.test:24567@test.near
@@ -76,13 +96,13 @@ impl FromStr for PeerInfo {
             None => return Err(Self::Err::InvalidFormat(s.to_string())),
         };
         let mut i = 1;
-        let addr = match chunks.get(i).map(|s| s.to_socket_addrs()) {
-            Some(Ok(mut x)) => {
-                i += 1;
-                x.next()
-            }
-            _ => None,
-        };
+        let mut addrs = vec![];
+        while let Some(Ok(mut x)) = chunks.get(i).map(|s| s.to_socket_addrs()) {
+            i += 1;
+            addrs.extend(x.next());
+        }
+        let mut addrs = addrs.into_iter();
+        let addr = addrs.next();
         let account_id = match chunks.get(i).map(|c| c.parse()) {
             Some(Ok(it)) => {
                 i += 1;
@@ -93,7 +113,7 @@ impl FromStr for PeerInfo {
         if i < chunks.len() {
             return Err(Self::Err::InvalidFormat(s.to_string()));
         }
-        Ok(PeerInfo { id, addr, account_id })
+        Ok(PeerInfo { id, addr, account_id, extra_addrs: addrs.collect() })
     }
 }
 
@@ -149,4 +169,25 @@ mod test {
         .unwrap();
         assert!(peer_test.addr.unwrap() == socket_v4 || peer_test.addr.unwrap() == socket_v6);
     }
+
+    #[test]
+    fn test_multiple_addrs() {
+        use crate::network_protocol::PeerInfo;
+        use std::str::FromStr;
+
+        let socket_v4 = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 1337);
+        let socket_v6 = SocketAddr::new(IpAddr::V6(Ipv6Addr::LOCALHOST), 1338);
+
+        let s = "ed25519:C6HLP37VJN1Wj2irxxZPsVsSya92Rnx12tqK3us5erKV@127.0.0.1:1337@[::1]:1338@account.near";
+        let peer_test = PeerInfo::from_str(s).unwrap();
+        assert_eq!(Some(socket_v4), peer_test.addr);
+        assert_eq!(vec![socket_v6], peer_test.extra_addrs);
+        assert_eq!(vec![socket_v4, socket_v6], peer_test.addrs().collect::<Vec<_>>());
+        assert_eq!(Some("account.near".parse().unwrap()), peer_test.account_id);
+        assert_eq!(s, peer_test.to_string());
+
+        // Extra addresses are not a part of the Borsh encoding.
+        let got = borsh::from_slice::<PeerInfo>(&borsh::to_vec(&peer_test).unwrap()).unwrap();
+        assert_eq!(PeerInfo { extra_addrs: vec![], ..peer_test }, got);
+    }
 }
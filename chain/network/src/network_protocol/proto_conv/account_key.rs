@@ -31,6 +31,7 @@ impl From<&VersionedAccountData> for proto::AccountKeyPayload {
                 peer_id: MF::some((&x.peer_id).into()),
                 account_key: MF::some((&x.account_key).into()),
                 proxies: x.proxies.iter().map(Into::into).collect(),
+                quic: x.quic,
                 version: x.version,
                 timestamp: MF::some(utc_to_proto(&x.timestamp)),
                 ..Default::default()
@@ -51,6 +52,7 @@ impl TryFrom<&proto::AccountKeyPayload> for VersionedAccountData {
             data: AccountData {
                 peer_id: try_from_required(&x.peer_id).map_err(Self::Error::PeerId)?,
                 proxies: try_from_slice(&x.proxies).map_err(Self::Error::Peers)?,
+                quic: x.quic,
             },
             account_key: try_from_required(&x.account_key).map_err(Self::Error::AccountKey)?,
             version: x.version,
@@ -2,7 +2,7 @@
 use super::*;
 
 use crate::network_protocol::proto;
-use crate::network_protocol::{Handshake, HandshakeFailureReason};
+use crate::network_protocol::{Compression, Handshake, HandshakeFailureReason, SessionKey};
 use crate::network_protocol::{PeerChainInfoV2, PeerInfo};
 use near_primitives::block::GenesisId;
 use protobuf::MessageField as MF;
@@ -63,6 +63,60 @@ impl TryFrom<&proto::PeerChainInfo> for PeerChainInfoV2 {
 
 //////////////////////////////////////////
 
+impl From<Compression> for proto::compressed_peer_message::Algorithm {
+    fn from(x: Compression) -> Self {
+        match x {
+            Compression::Zstd => Self::ZSTD,
+        }
+    }
+}
+
+/// Returns None for algorithms unknown to this node.
+fn compression_from_proto(
+    x: &protobuf::EnumOrUnknown<proto::compressed_peer_message::Algorithm>,
+) -> Option<Compression> {
+    match x.enum_value() {
+        Ok(proto::compressed_peer_message::Algorithm::ZSTD) => Some(Compression::Zstd),
+        Ok(proto::compressed_peer_message::Algorithm::UNKNOWN) | Err(_) => None,
+    }
+}
+
+//////////////////////////////////////////
+
+#[derive(thiserror::Error, Debug)]
+pub enum ParseSessionKeyError {
+    #[error("ephemeral_key: got {0} bytes, want 32")]
+    EphemeralKeyLength(usize),
+    #[error("signature {0}")]
+    Signature(ParseRequiredError<ParseSignatureError>),
+}
+
+impl From<&SessionKey> for proto::SessionKey {
+    fn from(x: &SessionKey) -> Self {
+        Self {
+            ephemeral_key: x.ephemeral_key.to_vec(),
+            signature: MF::some((&x.signature).into()),
+            ..Self::default()
+        }
+    }
+}
+
+impl TryFrom<&proto::SessionKey> for SessionKey {
+    type Error = ParseSessionKeyError;
+    fn try_from(p: &proto::SessionKey) -> Result<Self, Self::Error> {
+        Ok(Self {
+            ephemeral_key: p
+                .ephemeral_key
+                .as_slice()
+                .try_into()
+                .map_err(|_| Self::Error::EphemeralKeyLength(p.ephemeral_key.len()))?,
+            signature: try_from_required(&p.signature).map_err(Self::Error::Signature)?,
+        })
+    }
+}
+
+//////////////////////////////////////////
+
 #[derive(thiserror::Error, Debug)]
 pub enum ParseHandshakeError {
     #[error("sender_peer_id {0}")]
@@ -77,6 +131,10 @@ pub enum ParseHandshakeError {
     PartialEdgeInfo(ParseRequiredError<ParsePartialEdgeInfoError>),
     #[error("owned_account {0}")]
     OwnedAccount(ParseSignedOwnedAccountError),
+    #[error("session_key {0}")]
+    SessionKey(ParseSessionKeyError),
+    #[error("sender_extra_addrs {0}")]
+    SenderExtraAddrs(ParseVecError<ParseSocketAddrError>),
 }
 
 impl From<&Handshake> for proto::Handshake {
@@ -90,6 +148,13 @@ impl From<&Handshake> for proto::Handshake {
             sender_chain_info: MF::some((&x.sender_chain_info).into()),
             partial_edge_info: MF::some((&x.partial_edge_info).into()),
             owned_account: x.owned_account.as_ref().map(Into::into).into(),
+            session_key: x.session_key.as_ref().map(Into::into).into(),
+            supported_compression: x
+                .supported_compression
+                .iter()
+                .map(|c| proto::compressed_peer_message::Algorithm::from(*c).into())
+                .collect(),
+            sender_extra_addrs: x.sender_extra_addrs.iter().map(Into::into).collect(),
             ..Self::default()
         }
     }
@@ -120,6 +185,16 @@ impl TryFrom<&proto::Handshake> for Handshake {
                 .map_err(Self::Error::PartialEdgeInfo)?,
             owned_account: try_from_optional(&p.owned_account)
                 .map_err(Self::Error::OwnedAccount)?,
+            session_key: try_from_optional(&p.session_key).map_err(Self::Error::SessionKey)?,
+            // Algorithms unknown to this node are skipped, so that new algorithms
+            // can be added without breaking compatibility.
+            supported_compression: p
+                .supported_compression
+                .iter()
+                .filter_map(compression_from_proto)
+                .collect(),
+            sender_extra_addrs: try_from_slice(&p.sender_extra_addrs)
+                .map_err(Self::Error::SenderExtraAddrs)?,
         })
     }
 }
@@ -51,6 +51,8 @@ pub enum ParsePeerAddrError {
     Addr(ParseRequiredError<ParseSocketAddrError>),
     #[error("peer_id: {0}")]
     PeerId(ParseRequiredError<ParsePublicKeyError>),
+    #[error("extra_addrs: {0}")]
+    ExtraAddrs(ParseVecError<ParseSocketAddrError>),
 }
 
 impl From<&PeerAddr> for proto::PeerAddr {
@@ -58,6 +60,7 @@ impl From<&PeerAddr> for proto::PeerAddr {
         Self {
             addr: MF::some((&x.addr).into()),
             peer_id: MF::some((&x.peer_id).into()),
+            extra_addrs: x.extra_addrs.iter().map(Into::into).collect(),
             ..Self::default()
         }
     }
@@ -69,24 +72,38 @@ impl TryFrom<&proto::PeerAddr> for PeerAddr {
         Ok(Self {
             addr: try_from_required(&x.addr).map_err(Self::Error::Addr)?,
             peer_id: try_from_required(&x.peer_id).map_err(Self::Error::PeerId)?,
+            extra_addrs: try_from_slice(&x.extra_addrs).map_err(Self::Error::ExtraAddrs)?,
         })
     }
 }
 
 ////////////////////////////////////////
 
+#[derive(thiserror::Error, Debug)]
+pub enum ParsePeerInfoError {
+    #[error("borsh: {0}")]
+    Borsh(std::io::Error),
+    #[error("extra_addrs: {0}")]
+    ExtraAddrs(ParseVecError<ParseSocketAddrError>),
+}
+
 impl From<&PeerInfo> for proto::PeerInfo {
     fn from(x: &PeerInfo) -> Self {
-        Self { borsh: borsh::to_vec(&x).unwrap(), ..Self::default() }
+        Self {
+            borsh: borsh::to_vec(&x).unwrap(),
+            extra_addrs: x.extra_addrs.iter().map(Into::into).collect(),
+            ..Self::default()
+        }
     }
 }
 
-pub type ParsePeerInfoError = std::io::Error;
-
 impl TryFrom<&proto::PeerInfo> for PeerInfo {
     type Error = ParsePeerInfoError;
     fn try_from(x: &proto::PeerInfo) -> Result<Self, Self::Error> {
-        Self::try_from_slice(&x.borsh)
+        Ok(Self {
+            extra_addrs: try_from_slice(&x.extra_addrs).map_err(Self::Error::ExtraAddrs)?,
+            ..Self::try_from_slice(&x.borsh).map_err(Self::Error::Borsh)?
+        })
     }
 }
 
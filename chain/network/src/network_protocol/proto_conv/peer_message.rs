@@ -5,7 +5,8 @@ use crate::network_protocol::proto::peer_message::Message_type as ProtoMT;
 use crate::network_protocol::proto::{self};
 use crate::network_protocol::state_sync::{SnapshotHostInfo, SyncSnapshotHosts};
 use crate::network_protocol::{
-    AdvertisedPeerDistance, Disconnect, DistanceVector, PeerMessage, PeersRequest, PeersResponse,
+    AdvertisedPeerDistance, Disconnect, DistanceVector, EpochBlockProducersResponse,
+    EpochSyncInfoResponse, LightClientBlockResponse, PeerMessage, PeersRequest, PeersResponse,
     RoutingTableUpdate, SyncAccountsData,
 };
 use crate::network_protocol::{RoutedMessage, RoutedMessageV2};
@@ -15,6 +16,7 @@ use near_async::time::error::ComponentRange;
 use near_primitives::block::{Block, BlockHeader};
 use near_primitives::challenge::Challenge;
 use near_primitives::transaction::SignedTransaction;
+use near_primitives::types::EpochId;
 use protobuf::MessageField as MF;
 use std::sync::Arc;
 
@@ -331,6 +333,42 @@ impl From<&PeerMessage> for proto::PeerMessage {
                         ..Default::default()
                     })
                 }
+                PeerMessage::LightClientBlockRequest(h) => {
+                    ProtoMT::LightClientBlockRequest(proto::LightClientBlockRequest {
+                        last_block_hash: MF::some(h.into()),
+                        ..Default::default()
+                    })
+                }
+                PeerMessage::LightClientBlockResponse(r) => {
+                    ProtoMT::LightClientBlockResponse(proto::LightClientBlockResponse {
+                        borsh: borsh::to_vec(&r).unwrap(),
+                        ..Default::default()
+                    })
+                }
+                PeerMessage::EpochBlockProducersRequest(h) => {
+                    ProtoMT::EpochBlockProducersRequest(proto::EpochBlockProducersRequest {
+                        block_hash: MF::some(h.into()),
+                        ..Default::default()
+                    })
+                }
+                PeerMessage::EpochBlockProducersResponse(r) => {
+                    ProtoMT::EpochBlockProducersResponse(proto::EpochBlockProducersResponse {
+                        borsh: borsh::to_vec(&r).unwrap(),
+                        ..Default::default()
+                    })
+                }
+                PeerMessage::EpochSyncRequest(epoch_id) => {
+                    ProtoMT::EpochSyncRequest(proto::EpochSyncRequest {
+                        epoch_id: MF::some((&epoch_id.0).into()),
+                        ..Default::default()
+                    })
+                }
+                PeerMessage::EpochSyncResponse(r) => {
+                    ProtoMT::EpochSyncResponse(proto::EpochSyncResponse {
+                        borsh: borsh::to_vec(&r).unwrap(),
+                        ..Default::default()
+                    })
+                }
             }),
             ..Default::default()
         }
@@ -341,6 +379,9 @@ pub type ParsePeersRequestError = std::io::Error;
 pub type ParseTransactionError = std::io::Error;
 pub type ParseRoutedError = std::io::Error;
 pub type ParseChallengeError = std::io::Error;
+pub type ParseLightClientBlockResponseError = std::io::Error;
+pub type ParseEpochBlockProducersResponseError = std::io::Error;
+pub type ParseEpochSyncResponseError = std::io::Error;
 
 #[derive(thiserror::Error, Debug)]
 pub enum ParsePeerMessageError {
@@ -386,6 +427,20 @@ pub enum ParsePeerMessageError {
     StateResponse(ParseRequiredError<ParseStateInfoError>),
     #[error("sync_snapshot_hosts: {0}")]
     SyncSnapshotHosts(ParseSyncSnapshotHostsError),
+    #[error("light_client_block_request: {0}")]
+    LightClientBlockRequest(ParseRequiredError<ParseCryptoHashError>),
+    #[error("light_client_block_response: {0}")]
+    LightClientBlockResponse(ParseLightClientBlockResponseError),
+    #[error("epoch_block_producers_request: {0}")]
+    EpochBlockProducersRequest(ParseRequiredError<ParseCryptoHashError>),
+    #[error("epoch_block_producers_response: {0}")]
+    EpochBlockProducersResponse(ParseEpochBlockProducersResponseError),
+    #[error("epoch_sync_request: {0}")]
+    EpochSyncRequest(ParseRequiredError<ParseCryptoHashError>),
+    #[error("epoch_sync_response: {0}")]
+    EpochSyncResponse(ParseEpochSyncResponseError),
+    #[error("compressed: nested compression is not allowed")]
+    NestedCompression,
 }
 
 impl TryFrom<&proto::PeerMessage> for PeerMessage {
@@ -487,6 +542,32 @@ impl TryFrom<&proto::PeerMessage> for PeerMessage {
             ProtoMT::SyncSnapshotHosts(srh) => PeerMessage::SyncSnapshotHosts(
                 srh.try_into().map_err(Self::Error::SyncSnapshotHosts)?,
             ),
+            ProtoMT::LightClientBlockRequest(r) => PeerMessage::LightClientBlockRequest(
+                try_from_required(&r.last_block_hash)
+                    .map_err(Self::Error::LightClientBlockRequest)?,
+            ),
+            ProtoMT::LightClientBlockResponse(r) => PeerMessage::LightClientBlockResponse(
+                LightClientBlockResponse::try_from_slice(&r.borsh)
+                    .map_err(Self::Error::LightClientBlockResponse)?,
+            ),
+            ProtoMT::EpochBlockProducersRequest(r) => PeerMessage::EpochBlockProducersRequest(
+                try_from_required(&r.block_hash)
+                    .map_err(Self::Error::EpochBlockProducersRequest)?,
+            ),
+            ProtoMT::EpochBlockProducersResponse(r) => PeerMessage::EpochBlockProducersResponse(
+                EpochBlockProducersResponse::try_from_slice(&r.borsh)
+                    .map_err(Self::Error::EpochBlockProducersResponse)?,
+            ),
+            ProtoMT::EpochSyncRequest(r) => PeerMessage::EpochSyncRequest(EpochId(
+                try_from_required(&r.epoch_id).map_err(Self::Error::EpochSyncRequest)?,
+            )),
+            ProtoMT::EpochSyncResponse(r) => PeerMessage::EpochSyncResponse(
+                EpochSyncInfoResponse::try_from_slice(&r.borsh)
+                    .map_err(Self::Error::EpochSyncResponse)?,
+            ),
+            // Compressed messages are unwrapped before the conversion,
+            // so here we can only encounter a compressed message nested in another one.
+            ProtoMT::Compressed(_) => return Err(Self::Error::NestedCompression),
         })
     }
 }
@@ -68,6 +68,7 @@ pub fn make_block(
         CryptoHash::default(),
         clock.now_utc(),
     )
+    .unwrap()
 }
 
 pub fn make_account_id<R: Rng>(rng: &mut R) -> AccountId {
@@ -98,17 +99,16 @@ pub fn make_peer_info<R: Rng>(rng: &mut R) -> PeerInfo {
         id: PeerId::new(signer.public_key),
         addr: Some(make_addr(rng)),
         account_id: Some(signer.account_id),
+        extra_addrs: vec![],
     }
 }
 
 pub fn make_announce_account<R: Rng>(rng: &mut R) -> AnnounceAccount {
     let peer_id = make_peer_id(rng);
     let validator_signer = make_validator_signer(rng);
-    let signature = validator_signer.sign_account_announce(
-        validator_signer.validator_id(),
-        &peer_id,
-        &EpochId::default(),
-    );
+    let signature = validator_signer
+        .sign_account_announce(validator_signer.validator_id(), &peer_id, &EpochId::default())
+        .unwrap();
     AnnounceAccount {
         account_id: validator_signer.validator_id().clone(),
         peer_id: peer_id,
@@ -177,6 +177,7 @@ pub fn make_challenge<R: Rng>(rng: &mut R) -> Challenge {
         }),
         &make_validator_signer(rng),
     )
+    .unwrap()
 }
 
 // Based on ShardsManager::prepare_partial_encoded_chunk_response_from_chunk.
@@ -340,6 +341,9 @@ pub fn make_handshake<R: Rng>(rng: &mut R, chain: &Chain) -> Handshake {
         sender_chain_info: chain.get_peer_chain_info(),
         partial_edge_info: make_partial_edge(rng),
         owned_account: None,
+        session_key: None,
+        supported_compression: vec![],
+        sender_extra_addrs: vec![],
     }
 }
 
@@ -365,7 +369,11 @@ pub fn make_addr<R: Rng>(rng: &mut R) -> net::SocketAddr {
 }
 
 pub fn make_peer_addr(rng: &mut impl Rng, ip: net::IpAddr) -> PeerAddr {
-    PeerAddr { addr: net::SocketAddr::new(ip, rng.gen()), peer_id: make_peer_id(rng) }
+    PeerAddr {
+        addr: net::SocketAddr::new(ip, rng.gen()),
+        peer_id: make_peer_id(rng),
+        extra_addrs: vec![],
+    }
 }
 
 pub fn make_account_data(
@@ -394,6 +402,7 @@ pub fn make_account_data(
                 },
             ],
             peer_id,
+            quic: rng.gen(),
         },
         account_key,
         version,
@@ -7,6 +7,12 @@ use crate::types::{PartialEncodedChunkRequestMsg, PartialEncodedChunkResponseMsg
 use anyhow::{bail, Context as _};
 use itertools::Itertools as _;
 use near_async::time;
+use near_primitives::epoch_manager::epoch_info::EpochInfo;
+use near_primitives::epoch_manager::epoch_sync::EpochSyncInfo;
+use near_primitives::types::validator_stake::ValidatorStake;
+use near_primitives::types::EpochId;
+use near_primitives::views::validator_stake_view::ValidatorStakeView;
+use near_primitives::views::{LightClientBlockLiteView, LightClientBlockView};
 use rand::Rng as _;
 
 #[test]
@@ -48,6 +54,7 @@ fn bad_account_data_size() {
                 })
                 .collect(),
             peer_id: data::make_peer_id(&mut rng),
+            quic: true,
         },
         account_key: signer.public_key(),
         version: rng.gen(),
@@ -56,13 +63,80 @@ fn bad_account_data_size() {
     assert!(ad.sign(&signer).is_err());
 }
 
+#[test]
+fn peer_addr_from_str() {
+    let s = "ed25519:C6HLP37VJN1Wj2irxxZPsVsSya92Rnx12tqK3us5erKV@31.192.22.209:24567";
+    let peer_addr: PeerAddr = s.parse().unwrap();
+    assert_eq!(vec![peer_addr.addr], peer_addr.addrs().collect::<Vec<_>>());
+    assert_eq!(s, peer_addr.to_string());
+
+    let s = "ed25519:C6HLP37VJN1Wj2irxxZPsVsSya92Rnx12tqK3us5erKV@31.192.22.209:24567@[2001:db8::1]:24567";
+    let peer_addr: PeerAddr = s.parse().unwrap();
+    assert_eq!(
+        vec!["31.192.22.209:24567".parse().unwrap(), "[2001:db8::1]:24567".parse().unwrap()],
+        peer_addr.addrs().collect::<Vec<std::net::SocketAddr>>()
+    );
+    assert_eq!(s, peer_addr.to_string());
+
+    assert!("ed25519:C6HLP37VJN1Wj2irxxZPsVsSya92Rnx12tqK3us5erKV".parse::<PeerAddr>().is_err());
+    assert!("ed25519:C6HLP37VJN1Wj2irxxZPsVsSya92Rnx12tqK3us5erKV@31.192.22.209:24567@test.near"
+        .parse::<PeerAddr>()
+        .is_err());
+}
+
 #[test]
 fn serialize_deserialize_protobuf_only() {
     let mut rng = make_rng(39521947542);
     let mut clock = time::FakeClock::default();
     let chain = data::Chain::make(&mut clock, &mut rng, 12);
+    let mut handshake = data::make_handshake(&mut rng, &chain);
+    handshake.session_key = Some(SessionKey::new(
+        rng.gen(),
+        &handshake.sender_peer_id,
+        &handshake.target_peer_id,
+        handshake.partial_edge_info.nonce,
+        &data::make_secret_key(&mut rng),
+    ));
+    handshake.supported_compression = vec![Compression::Zstd];
+    handshake.sender_extra_addrs = vec![data::make_addr(&mut rng)];
+    // Extra addresses are encoded only in proto.
+    let mut peer_info = data::make_peer_info(&mut rng);
+    peer_info.extra_addrs = vec![
+        std::net::SocketAddr::new(data::make_ipv6(&mut rng), rng.gen()),
+        data::make_addr(&mut rng),
+    ];
+    let header = LightClientBlockLiteView::from(chain.blocks[5].header().clone());
+    let next_bps: Vec<ValidatorStakeView> = (0..3)
+        .map(|_| {
+            let signer = data::make_validator_signer(&mut rng);
+            ValidatorStake::new_v1(signer.validator_id().clone(), signer.public_key(), rng.gen())
+                .into()
+        })
+        .collect();
+    let light_client_block = LightClientBlockView {
+        prev_block_hash: header.prev_block_hash,
+        next_block_inner_hash: data::make_hash(&mut rng),
+        inner_lite: header.inner_lite.clone(),
+        inner_rest_hash: header.inner_rest_hash,
+        next_bps: Some(next_bps.clone()),
+        approvals_after_next: vec![
+            None,
+            Some(Box::new(data::make_secret_key(&mut rng).sign(b"approval"))),
+        ],
+    };
+    let epoch_headers = chain.get_block_headers();
+    let epoch_sync_info = EpochSyncInfo {
+        all_block_hashes: epoch_headers.iter().map(|h| *h.hash()).collect(),
+        headers: epoch_headers.iter().map(|h| (*h.hash(), h.clone())).collect(),
+        headers_to_save: epoch_headers.iter().take(3).map(|h| *h.hash()).collect(),
+        next_epoch_first_hash: *epoch_headers.last().unwrap().hash(),
+        epoch_info: EpochInfo::v1_test(),
+        next_epoch_info: EpochInfo::default(),
+        next_next_epoch_info: EpochInfo::v1_test(),
+    };
     let msgs = [
         PeerMessage::Tier1Handshake(data::make_handshake(&mut rng, &chain)),
+        PeerMessage::Tier2Handshake(handshake),
         PeerMessage::SyncAccountsData(SyncAccountsData {
             accounts_data: (0..4)
                 .map(|_| Arc::new(data::make_signed_account_data(&mut rng, &clock.clock())))
@@ -70,6 +144,33 @@ fn serialize_deserialize_protobuf_only() {
             incremental: true,
             requesting_full_sync: true,
         }),
+        PeerMessage::PeersResponse(PeersResponse {
+            peers: vec![peer_info.clone(), data::make_peer_info(&mut rng)],
+            direct_peers: vec![peer_info],
+        }),
+        PeerMessage::LightClientBlockRequest(data::make_hash(&mut rng)),
+        PeerMessage::LightClientBlockResponse(LightClientBlockResponse {
+            last_block_hash: data::make_hash(&mut rng),
+            block: Some(Box::new(light_client_block)),
+        }),
+        PeerMessage::LightClientBlockResponse(LightClientBlockResponse {
+            last_block_hash: data::make_hash(&mut rng),
+            block: None,
+        }),
+        PeerMessage::EpochBlockProducersRequest(data::make_hash(&mut rng)),
+        PeerMessage::EpochBlockProducersResponse(EpochBlockProducersResponse {
+            block_hash: header.hash(),
+            block_producers: Some(EpochBlockProducers { header, next_bps }),
+        }),
+        PeerMessage::EpochSyncRequest(EpochId(data::make_hash(&mut rng))),
+        PeerMessage::EpochSyncResponse(EpochSyncInfoResponse {
+            epoch_id: EpochId(data::make_hash(&mut rng)),
+            epoch_sync_infos: vec![epoch_sync_info],
+        }),
+        PeerMessage::EpochSyncResponse(EpochSyncInfoResponse {
+            epoch_id: EpochId(data::make_hash(&mut rng)),
+            epoch_sync_infos: vec![],
+        }),
     ];
     for m in msgs {
         let m2 = PeerMessage::deserialize(Encoding::Proto, &m.serialize(Encoding::Proto))
@@ -79,6 +180,54 @@ fn serialize_deserialize_protobuf_only() {
     }
 }
 
+#[test]
+fn compress_decompress() {
+    let mut rng = make_rng(19385203948);
+    let mut clock = time::FakeClock::default();
+    let chain = data::Chain::make(&mut clock, &mut rng, 12);
+    let msgs = [
+        PeerMessage::Block(chain.blocks[5].clone()),
+        PeerMessage::BlockHeaders(chain.get_block_headers()),
+    ];
+    for m in msgs {
+        let compressed =
+            compression::compress(&m.serialize(Encoding::Proto), Compression::Zstd, "test");
+        let m2 = PeerMessage::deserialize(Encoding::Proto, &compressed)
+            .with_context(|| m.to_string())
+            .unwrap();
+        assert_eq!(m, m2);
+
+        // Compressed messages cannot be nested.
+        let nested = compression::compress(&compressed, Compression::Zstd, "test");
+        assert!(PeerMessage::deserialize(Encoding::Proto, &nested).is_err());
+    }
+}
+
+#[test]
+fn decompress_checks_uncompressed_size() {
+    let data = vec![0u8; 1 << 20];
+    let make = |uncompressed_size: u64| proto::CompressedPeerMessage {
+        algorithm: proto::compressed_peer_message::Algorithm::ZSTD.into(),
+        uncompressed_size,
+        data: zstd::bulk::compress(&data, 3).unwrap(),
+        ..Default::default()
+    };
+    // Declared size smaller than the actual one: the output is cut short and rejected.
+    assert!(matches!(
+        compression::decompress(&make(1000)),
+        Err(compression::DecompressError::SizeMismatch { got_bytes: 1001, want_bytes: 1000 })
+    ));
+    // Declared size larger than the actual one: the buffer is not preallocated.
+    assert!(matches!(
+        compression::decompress(&make(100 << 20)),
+        Err(compression::DecompressError::SizeMismatch { .. })
+    ));
+    assert!(matches!(
+        compression::decompress(&make(u64::MAX)),
+        Err(compression::DecompressError::TooLarge { .. })
+    ));
+}
+
 #[test]
 fn serialize_deserialize() -> anyhow::Result<()> {
     let mut rng = make_rng(89028037453);
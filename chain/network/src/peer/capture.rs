@@ -0,0 +1,305 @@
+//! Capture of the network traffic received by the node.
+//!
+//! When enabled (see `NetworkConfig::traffic_capture`), every PeerMessage received and
+//! successfully decoded by a PeerActor is appended to a binary log, together with the time
+//! of receipt, the sender and the TIER of the connection. Messages are stored as they were
+//! received (after decompression and decryption), together with their encoding, so that
+//! capturing doesn't re-encode them on the PeerActor thread.
+//! The log is split into files of bounded size and only the most recent files are kept.
+//!
+//! Each file is a sequence of records: a u32 (little endian) length followed by
+//! a borsh-encoded `CapturedMessage` of that length.
+//!
+//! Capture files can be read with `Reader`, and replayed with `neard network replay`.
+use crate::network_protocol::{Encoding, ParsePeerMessageError, PeerMessage};
+use crate::stats::metrics;
+use crate::tcp;
+use near_async::time;
+use near_primitives::network::PeerId;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+
+/// Maximal number of messages waiting to be written.
+/// Messages received when the queue is full are not captured.
+const QUEUE_CAPACITY: usize = 10_000;
+const FILE_PREFIX: &str = "capture-";
+const FILE_SUFFIX: &str = ".bin";
+
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// Directory in which the capture files are stored.
+    pub dir: PathBuf,
+    /// A new file is started once the current one reaches this size.
+    pub max_file_bytes: u64,
+    /// Maximal number of files kept in `dir`. The oldest files are deleted.
+    pub max_files: usize,
+}
+
+impl Config {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.max_file_bytes == 0 {
+            anyhow::bail!("max_file_bytes has to be >0");
+        }
+        if self.max_files == 0 {
+            anyhow::bail!("max_files has to be >0");
+        }
+        Ok(())
+    }
+}
+
+/// A single message received by the node.
+#[derive(Clone, Debug, PartialEq, Eq, borsh::BorshSerialize, borsh::BorshDeserialize)]
+pub struct CapturedMessage {
+    /// Time of receipt, in nanoseconds since the unix epoch.
+    timestamp_unix_nanos: i128,
+    /// Peer which sent the message.
+    pub peer_id: PeerId,
+    /// TIER of the connection the message was received on.
+    pub tier: tcp::Tier,
+    /// Encoding of `message`.
+    encoding: Encoding,
+    /// Encoded PeerMessage.
+    message: Vec<u8>,
+}
+
+impl CapturedMessage {
+    pub(crate) fn new(
+        clock: &time::Clock,
+        peer_id: PeerId,
+        tier: tcp::Tier,
+        encoding: Encoding,
+        message: &[u8],
+    ) -> Self {
+        Self {
+            timestamp_unix_nanos: clock.now_utc().unix_timestamp_nanos(),
+            peer_id,
+            tier,
+            encoding,
+            message: message.to_vec(),
+        }
+    }
+
+    /// Time at which the message was received.
+    pub fn timestamp(&self) -> time::Utc {
+        time::Utc::from_unix_timestamp_nanos(self.timestamp_unix_nanos)
+            .unwrap_or(time::Utc::UNIX_EPOCH)
+    }
+
+    pub(crate) fn message(&self) -> Result<PeerMessage, ParsePeerMessageError> {
+        PeerMessage::deserialize(self.encoding, &self.message)
+    }
+
+    /// Type of the message, as reported in the metrics (for routed messages it is the type
+    /// of the body). None if the message cannot be decoded.
+    pub fn message_type(&self) -> Option<&'static str> {
+        self.message().ok().map(|msg| msg.msg_variant())
+    }
+}
+
+/// Records the received messages in the background.
+pub(crate) struct Recorder {
+    queue: mpsc::SyncSender<CapturedMessage>,
+}
+
+impl Recorder {
+    /// Spawns a thread writing the captured messages to `config.dir`.
+    /// The thread stops once the Recorder is dropped.
+    pub fn new(config: &Config) -> anyhow::Result<Self> {
+        let mut writer = Writer::new(config.clone())?;
+        let (send, recv) = mpsc::sync_channel(QUEUE_CAPACITY);
+        std::thread::Builder::new().name("traffic_capture".to_string()).spawn(move || {
+            if let Err(err) = writer.run(recv) {
+                tracing::error!(target: "network", ?err, "Traffic capture failed");
+            }
+        })?;
+        Ok(Self { queue: send })
+    }
+
+    pub fn record(&self, msg: CapturedMessage) {
+        if let Err(mpsc::TrySendError::Full(_)) = self.queue.try_send(msg) {
+            metrics::TRAFFIC_CAPTURE_DROPPED_MESSAGES.inc();
+        }
+    }
+}
+
+/// Returns the capture files in `dir`, from the oldest to the newest.
+pub fn list_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut files = vec![];
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if file_index(&path).is_some() {
+            files.push(path);
+        }
+    }
+    files.sort_by_key(|path| file_index(path));
+    Ok(files)
+}
+
+fn file_index(path: &Path) -> Option<u64> {
+    let name = path.file_name()?.to_str()?;
+    name.strip_prefix(FILE_PREFIX)?.strip_suffix(FILE_SUFFIX)?.parse().ok()
+}
+
+struct Writer {
+    config: Config,
+    /// Capture files in `config.dir`, from the oldest to the newest.
+    files: VecDeque<PathBuf>,
+    next_index: u64,
+    /// File currently being written and the number of bytes written to it.
+    current: Option<(BufWriter<File>, u64)>,
+}
+
+impl Writer {
+    fn new(config: Config) -> anyhow::Result<Self> {
+        std::fs::create_dir_all(&config.dir)?;
+        let files = list_files(&config.dir)?;
+        let next_index = files.last().and_then(|path| file_index(path)).map_or(0, |i| i + 1);
+        Ok(Self { config, files: files.into(), next_index, current: None })
+    }
+
+    fn write(&mut self, msg: &CapturedMessage) -> io::Result<()> {
+        let data = borsh::to_vec(msg)?;
+        let full = match &self.current {
+            Some((_, n)) => *n >= self.config.max_file_bytes,
+            None => true,
+        };
+        if full {
+            self.rotate()?;
+        }
+        let (file, n) = self.current.as_mut().unwrap();
+        file.write_all(&(data.len() as u32).to_le_bytes())?;
+        file.write_all(&data)?;
+        *n += 4 + data.len() as u64;
+        Ok(())
+    }
+
+    /// Starts a new file, deleting the oldest files if there are too many.
+    fn rotate(&mut self) -> io::Result<()> {
+        if let Some((mut file, _)) = self.current.take() {
+            file.flush()?;
+        }
+        let path =
+            self.config.dir.join(format!("{FILE_PREFIX}{:010}{FILE_SUFFIX}", self.next_index));
+        self.next_index += 1;
+        let file = File::create(&path)?;
+        self.files.push_back(path);
+        while self.files.len() > self.config.max_files {
+            let path = self.files.pop_front().unwrap();
+            match std::fs::remove_file(&path) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+                _ => {}
+            }
+        }
+        self.current = Some((BufWriter::new(file), 0));
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match &mut self.current {
+            Some((file, _)) => file.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// Writes the messages from the queue until all the senders are dropped.
+    /// The file is flushed whenever the queue becomes empty.
+    fn run(&mut self, queue: mpsc::Receiver<CapturedMessage>) -> io::Result<()> {
+        while let Ok(msg) = queue.recv() {
+            self.write(&msg)?;
+            while let Ok(msg) = queue.try_recv() {
+                self.write(&msg)?;
+            }
+            self.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Iterator over the messages stored in a capture file.
+pub struct Reader(BufReader<File>);
+
+impl Reader {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        Ok(Self(BufReader::new(File::open(path)?)))
+    }
+}
+
+impl Iterator for Reader {
+    type Item = io::Result<CapturedMessage>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut len = [0u8; 4];
+        match self.0.read_exact(&mut len) {
+            Ok(()) => {}
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => return None,
+            Err(err) => return Some(Err(err)),
+        }
+        let mut data = vec![0; u32::from_le_bytes(len) as usize];
+        Some(self.0.read_exact(&mut data).and_then(|()| borsh::from_slice(&data)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{list_files, CapturedMessage, Config, Reader, Writer};
+    use crate::network_protocol::testonly as data;
+    use crate::network_protocol::{Encoding, PeerMessage};
+    use crate::tcp;
+    use crate::testonly::make_rng;
+    use near_async::time;
+
+    #[test]
+    fn write_rotate_and_read() {
+        let mut rng = make_rng(921853233);
+        let rng = &mut rng;
+        let clock = time::FakeClock::default();
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config { dir: dir.path().to_path_buf(), max_file_bytes: 1, max_files: 2 };
+
+        let msgs: Vec<_> = (0..3)
+            .map(|i| {
+                clock.advance(time::Duration::seconds(1));
+                let msg = PeerMessage::BlockRequest(data::make_hash(rng));
+                let (tier, encoding) = if i % 2 == 0 {
+                    (tcp::Tier::T1, Encoding::Proto)
+                } else {
+                    (tcp::Tier::T2, Encoding::Borsh)
+                };
+                let peer_id = data::make_peer_id(rng);
+                CapturedMessage::new(
+                    &clock.clock(),
+                    peer_id,
+                    tier,
+                    encoding,
+                    &msg.serialize(encoding),
+                )
+            })
+            .collect();
+        let mut writer = Writer::new(config.clone()).unwrap();
+        for msg in &msgs {
+            writer.write(msg).unwrap();
+        }
+        writer.flush().unwrap();
+
+        // Every message went to a separate file, and only the last 2 files were kept.
+        let files = list_files(&config.dir).unwrap();
+        assert_eq!(files.len(), 2);
+        let got: Vec<_> =
+            files.iter().flat_map(|path| Reader::open(path).unwrap()).map(Result::unwrap).collect();
+        assert_eq!(got, msgs[1..]);
+        assert_eq!(got[0].timestamp(), clock.now_utc() - time::Duration::seconds(1));
+        assert_eq!(got[0].message_type(), Some("BlockRequest"));
+        assert_eq!(got[1].message_type(), Some("BlockRequest"));
+
+        // A new writer continues the numbering of the existing files.
+        let mut writer = Writer::new(config.clone()).unwrap();
+        writer.write(&msgs[0]).unwrap();
+        writer.flush().unwrap();
+        let files = list_files(&config.dir).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!(Reader::open(&files[1]).unwrap().next().unwrap().unwrap(), msgs[0]);
+    }
+}
@@ -0,0 +1,133 @@
+//! Encryption of the peer connections.
+//!
+//! Each side of the connection generates an ephemeral X25519 key and sends it,
+//! signed with its node key, in its Handshake (see `SessionKey`). Once both
+//! Handshakes have been exchanged, both sides derive a pair of symmetric keys
+//! (one per direction) from the X25519 shared secret and encrypt every
+//! subsequent frame with ChaCha20-Poly1305. Frames are encrypted with
+//! consecutive nonces, so any reordered, replayed, dropped or injected frame
+//! fails to decrypt and the connection is closed.
+use crate::network_protocol::SessionKey;
+use crate::types::PeerType;
+use chacha20poly1305::aead::{Aead as _, KeyInit as _};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use near_crypto::SecretKey;
+use near_primitives::network::PeerId;
+use near_primitives::version::{ProtocolFeature, ProtocolVersion};
+
+/// Whether connections using the given protocol version should be encrypted.
+pub(crate) fn is_supported(protocol_version: ProtocolVersion) -> bool {
+    ProtocolFeature::EncryptedPeerConnections.protocol_version() <= protocol_version
+}
+
+#[derive(thiserror::Error, Debug)]
+#[error("failed to decrypt a frame")]
+pub(crate) struct DecryptError;
+
+/// Ephemeral key of this node for a single connection.
+/// It is reusable, because the outbound side may need to resend its Handshake.
+pub(crate) struct EphemeralKey {
+    secret: x25519_dalek::ReusableSecret,
+    public: x25519_dalek::PublicKey,
+}
+
+impl EphemeralKey {
+    pub fn new() -> Self {
+        let secret = x25519_dalek::ReusableSecret::random_from_rng(rand::rngs::OsRng);
+        let public = x25519_dalek::PublicKey::from(&secret);
+        Self { secret, public }
+    }
+
+    /// Signs the public part of the key, so that it can be sent in a Handshake.
+    pub fn sign(
+        &self,
+        sender: &PeerId,
+        target: &PeerId,
+        nonce: u64,
+        node_key: &SecretKey,
+    ) -> SessionKey {
+        SessionKey::new(self.public.to_bytes(), sender, target, nonce, node_key)
+    }
+
+    /// Derives the session keys from this key and the key received from the peer.
+    /// `peer_type` determines which key is used for which direction:
+    /// both sides have to agree on it to be able to communicate.
+    pub fn agree(&self, their_key: &SessionKey, peer_type: PeerType) -> SessionCiphers {
+        let their_public = x25519_dalek::PublicKey::from(their_key.ephemeral_key);
+        let (outbound_key, inbound_key) = match peer_type {
+            PeerType::Outbound => (self.public, their_public),
+            PeerType::Inbound => (their_public, self.public),
+        };
+        let shared_secret = self.secret.diffie_hellman(&their_public);
+        let hkdf = hkdf::Hkdf::<sha2::Sha256>::new(None, shared_secret.as_bytes());
+        let mut info = b"near-p2p-session".to_vec();
+        info.extend_from_slice(outbound_key.as_bytes());
+        info.extend_from_slice(inbound_key.as_bytes());
+        let mut keys = [0u8; 64];
+        hkdf.expand(&info, &mut keys).expect("64 bytes is a valid HKDF-SHA256 output length");
+        // The first key is used for frames sent by the outbound side,
+        // the second one for frames sent by the inbound side.
+        let (outbound_cipher, inbound_cipher) =
+            (FrameCipher::new(&keys[..32]), FrameCipher::new(&keys[32..]));
+        match peer_type {
+            PeerType::Outbound => SessionCiphers { send: outbound_cipher, recv: inbound_cipher },
+            PeerType::Inbound => SessionCiphers { send: inbound_cipher, recv: outbound_cipher },
+        }
+    }
+}
+
+/// Ciphers for both directions of an encrypted connection.
+pub(crate) struct SessionCiphers {
+    pub send: FrameCipher,
+    pub recv: FrameCipher,
+}
+
+/// Cipher for a single direction of an encrypted connection.
+pub(crate) struct FrameCipher {
+    cipher: ChaCha20Poly1305,
+    /// Index of the stream within the connection, see `for_stream`.
+    stream: u32,
+    /// Number of frames processed so far, used as the AEAD nonce.
+    counter: u64,
+}
+
+impl FrameCipher {
+    fn new(key: &[u8]) -> Self {
+        Self { cipher: ChaCha20Poly1305::new(Key::from_slice(key)), stream: 0, counter: 0 }
+    }
+
+    /// Cipher for the given stream of a connection multiplexing independently
+    /// ordered streams (QUIC). Streams use disjoint nonce spaces, so they can share the key.
+    /// A TCP connection consists of a single stream 0.
+    pub fn for_stream(&self, stream: u32) -> Self {
+        Self { cipher: self.cipher.clone(), stream, counter: 0 }
+    }
+
+    fn nonce(&self) -> Nonce {
+        let mut nonce = [0u8; 12];
+        nonce[..4].copy_from_slice(&self.stream.to_le_bytes());
+        nonce[4..].copy_from_slice(&self.counter.to_le_bytes());
+        *Nonce::from_slice(&nonce)
+    }
+
+    fn advance(&mut self) {
+        // 2^64 frames is way more than a connection is able to send in its lifetime.
+        self.counter = self.counter.checked_add(1).expect("frame counter overflow");
+    }
+
+    pub fn encrypt(&mut self, frame: &[u8]) -> Vec<u8> {
+        let frame = self
+            .cipher
+            .encrypt(&self.nonce(), frame)
+            .expect("ChaCha20Poly1305 encryption cannot fail");
+        self.advance();
+        frame
+    }
+
+    /// Decrypts the next frame. On failure the state is not changed.
+    pub fn decrypt(&mut self, frame: &[u8]) -> Result<Vec<u8>, DecryptError> {
+        let frame = self.cipher.decrypt(&self.nonce(), frame).map_err(|_| DecryptError)?;
+        self.advance();
+        Ok(frame)
+    }
+}
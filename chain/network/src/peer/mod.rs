@@ -1,5 +1,8 @@
+pub mod capture;
+pub(crate) mod encryption;
 pub(crate) mod peer_actor;
-mod stream;
+pub(crate) mod rate_limit;
+pub(crate) mod stream;
 mod tracker;
 mod transfer_stats;
 
@@ -1,20 +1,27 @@
 use crate::accounts_data::AccountDataError;
 use crate::client::{
     AnnounceAccountRequest, BlockApproval, BlockHeadersRequest, BlockHeadersResponse, BlockRequest,
-    BlockResponse, ChunkEndorsementMessage, ChunkStateWitnessMessage, ProcessTxRequest,
+    BlockResponse, ChunkEndorsementMessage, ChunkStateWitnessMessage, EpochBlockProducersRequest,
+    EpochSyncRequest, EpochSyncResponse, LightClientBlockRequest,
+    PartialEncodedStateWitnessForwardMessage, PartialEncodedStateWitnessMessage, ProcessTxRequest,
     RecvChallenge, StateRequestHeader, StateRequestPart, StateResponse, TxStatusRequest,
     TxStatusResponse,
 };
 use crate::concurrency::atomic_cell::AtomicCell;
 use crate::concurrency::demux;
-use crate::config::PEERS_RESPONSE_MAX_PEERS;
+use crate::config::{self, MAX_ADDRS_PER_PEER, PEERS_RESPONSE_MAX_PEERS};
+use crate::network_protocol::compression;
 use crate::network_protocol::SnapshotHostInfoVerificationError;
 use crate::network_protocol::{
-    DistanceVector, Edge, EdgeState, Encoding, OwnedAccount, ParsePeerMessageError,
+    Compression, DistanceVector, Edge, EdgeState, Encoding, EpochBlockProducersResponse,
+    EpochSyncInfoResponse, LightClientBlockResponse, OwnedAccount, ParsePeerMessageError,
     PartialEdgeInfo, PeerChainInfoV2, PeerIdOrHash, PeerInfo, PeersRequest, PeersResponse,
     RawRoutedMessage, RoutedMessageBody, RoutingTableUpdate, StateResponseInfo, SyncAccountsData,
     SyncSnapshotHosts,
 };
+use crate::peer::capture;
+use crate::peer::encryption;
+use crate::peer::rate_limit;
 use crate::peer::stream;
 use crate::peer::tracker::Tracker;
 use crate::peer_manager::connection;
@@ -22,7 +29,9 @@ use crate::peer_manager::network_state::{NetworkState, PRUNE_EDGES_AFTER};
 #[cfg(test)]
 use crate::peer_manager::peer_manager_actor::Event;
 use crate::peer_manager::peer_manager_actor::MAX_TIER2_PEERS;
+use crate::peer_manager::reputation;
 use crate::private_actix::{RegisterPeerError, SendMessage};
+use crate::quic;
 use crate::routing::edge::verify_nonce;
 use crate::routing::NetworkTopologyChange;
 use crate::shards_manager::ShardsManagerRequestFromNetwork;
@@ -72,6 +81,11 @@ const MAX_TRANSACTIONS_PER_BLOCK_MESSAGE: usize = 1000;
 const ROUTED_MESSAGE_CACHE_SIZE: usize = 1000;
 /// Duplicated messages will be dropped if routed through the same peer multiple times.
 pub(crate) const DROP_DUPLICATED_MESSAGES_PERIOD: time::Duration = time::Duration::milliseconds(50);
+/// Responses to our requests arriving later than that decrease the reputation of the peer.
+const SLOW_RESPONSE_THRESHOLD: time::Duration = time::Duration::seconds(2);
+/// Requests not responded to within that time are considered timed out. They are forgotten,
+/// so that a late response doesn't count as a useful one.
+const REQUEST_TIMEOUT: time::Duration = time::Duration::seconds(10);
 /// How often to send the latest block to peers.
 const SYNC_LATEST_BLOCK_INTERVAL: time::Duration = time::Duration::seconds(60);
 /// How often to perform a full sync of AccountsData with the peer.
@@ -165,11 +179,21 @@ pub(crate) struct PeerActor {
 
     /// Framed wrapper to send messages through the TCP connection.
     framed: stream::FramedStream<PeerActor>,
+    /// Whether the connection uses QUIC, which is supported only for TIER1.
+    quic: bool,
+    /// Key offered to the peer in the Handshake to encrypt the connection.
+    ephemeral_key: encryption::EphemeralKey,
+    /// Compression supported by the peer, negotiated during handshake.
+    compression: Option<Compression>,
 
     /// Tracker for requests and responses.
     tracker: Arc<Mutex<Tracker>>,
     /// Network bandwidth stats.
     stats: Arc<connection::Stats>,
+    /// Outbound bandwidth limits, per message class.
+    send_limiter: Mutex<rate_limit::SendLimiter>,
+    /// Limits of the expensive inbound requests, per request class.
+    request_limiter: Mutex<rate_limit::RequestLimiter>,
     /// Cache of recently routed messages, this allows us to drop duplicates
     routed_message_cache: LruCache<(PeerId, PeerIdOrHash, Signature), time::Instant>,
     /// Whether we detected support for protocol buffers during handshake.
@@ -298,12 +322,19 @@ impl PeerActor {
             tcp::StreamType::Outbound { tier, .. } if tier == &tcp::Tier::T1 => {
                 Some(Encoding::Proto)
             }
+            // Similarly, SessionKey is supported only with proto encoding.
+            tcp::StreamType::Outbound { .. }
+                if force_encoding.is_none() && encryption::is_supported(PROTOCOL_VERSION) =>
+            {
+                Some(Encoding::Proto)
+            }
             _ => force_encoding,
         };
         let my_node_info = PeerInfo {
             id: network_state.config.node_id(),
             addr: network_state.config.node_addr.as_ref().map(|a| **a),
             account_id: network_state.config.validator.as_ref().map(|v| v.account_id()),
+            extra_addrs: network_state.config.extra_addrs.clone(),
         };
         // recv is the HandshakeSignal returned by this spawn_inner() call.
         let (send, recv): (HandshakeSignalSender, HandshakeSignal) =
@@ -314,8 +345,17 @@ impl PeerActor {
                 let stream_id = stream.id();
                 let peer_addr = stream.peer_addr;
                 let stream_type = stream.type_.clone();
+                let quic = stream.is_quic();
                 let stats = Arc::new(connection::Stats::default());
                 let framed = stream::FramedStream::spawn(ctx, stream, stats.clone());
+                let send_limiter = Mutex::new(rate_limit::SendLimiter::new(
+                    &clock,
+                    &network_state.config.peer_send_rate_limits,
+                ));
+                let request_limiter = Mutex::new(rate_limit::RequestLimiter::new(
+                    &clock,
+                    &network_state.config.peer_request_rate_limits,
+                ));
                 Self {
                     closing_reason: None,
                     clock,
@@ -328,18 +368,21 @@ impl PeerActor {
                     },
                     peer_status: PeerStatus::Connecting(send, connecting_status),
                     framed,
+                    quic,
+                    ephemeral_key: encryption::EphemeralKey::new(),
+                    compression: None,
                     tracker: Default::default(),
                     stats,
+                    send_limiter,
+                    request_limiter,
                     routed_message_cache: LruCache::new(ROUTED_MESSAGE_CACHE_SIZE),
                     protocol_buffers_supported: false,
                     force_encoding,
                     peer_info: match &stream_type {
                         tcp::StreamType::Inbound => None,
-                        tcp::StreamType::Outbound { peer_id, .. } => Some(PeerInfo {
-                            id: peer_id.clone(),
-                            addr: Some(peer_addr),
-                            account_id: None,
-                        }),
+                        tcp::StreamType::Outbound { peer_id, .. } => {
+                            Some(PeerInfo::new(peer_id.clone(), peer_addr))
+                        }
                     }
                     .into(),
                     network_state,
@@ -377,19 +420,52 @@ impl PeerActor {
         return PeerMessage::deserialize(Encoding::Borsh, msg);
     }
 
+    /// Records the received message, if traffic capture is enabled.
+    /// `raw` is the message as received, which `msg` was parsed from.
+    fn capture_message(&self, msg: &PeerMessage, raw: &[u8]) {
+        let Some(recorder) = &self.network_state.traffic_capture else { return };
+        let (peer_id, tier) = match (&self.peer_status, msg) {
+            (PeerStatus::Ready(conn), _) => (conn.peer_info.id.clone(), conn.tier),
+            (PeerStatus::Connecting { .. }, PeerMessage::Tier1Handshake(h)) => {
+                (h.sender_peer_id.clone(), tcp::Tier::T1)
+            }
+            (PeerStatus::Connecting { .. }, PeerMessage::Tier2Handshake(h)) => {
+                (h.sender_peer_id.clone(), tcp::Tier::T2)
+            }
+            (PeerStatus::Connecting(_, ConnectingStatus::Outbound { handshake_spec, .. }), _) => {
+                (handshake_spec.peer_id.clone(), handshake_spec.tier)
+            }
+            // Other messages received on an inbound connection before the handshake
+            // cannot be attributed to any peer.
+            (PeerStatus::Connecting(_, ConnectingStatus::Inbound(_)), _) => return,
+        };
+        // parse_message() falls back to borsh if the encoding is not known yet.
+        let encoding = self.encoding().unwrap_or(Encoding::Borsh);
+        recorder.record(capture::CapturedMessage::new(&self.clock, peer_id, tier, encoding, raw));
+    }
+
     fn send_message_or_log(&self, msg: &PeerMessage) {
         self.send_message(msg);
     }
 
     fn send_message(&self, msg: &PeerMessage) {
+        self.send_message_of_class(msg, rate_limit::SendClass::of(msg));
+    }
+
+    /// Sends a response to the peer's request. Responses are rate limited, see `rate_limit`.
+    fn send_response(&self, msg: &PeerMessage) {
+        self.send_message_of_class(msg, rate_limit::SendClass::of_response(msg));
+    }
+
+    fn send_message_of_class(&self, msg: &PeerMessage, class: Option<rate_limit::SendClass>) {
         if let (PeerStatus::Ready(conn), PeerMessage::PeersRequest(_)) = (&self.peer_status, msg) {
             conn.last_time_peer_requested.store(Some(self.clock.now()));
         }
         if let Some(enc) = self.encoding() {
-            return self.send_message_with_encoding(msg, enc);
+            return self.send_message_with_encoding(msg, enc, class);
         }
-        self.send_message_with_encoding(msg, Encoding::Proto);
-        self.send_message_with_encoding(msg, Encoding::Borsh);
+        self.send_message_with_encoding(msg, Encoding::Proto, class);
+        self.send_message_with_encoding(msg, Encoding::Borsh, class);
     }
 
     #[tracing::instrument(
@@ -399,7 +475,12 @@ impl PeerActor {
         skip_all,
         fields(msg_type = msg.msg_variant())
     )]
-    fn send_message_with_encoding(&self, msg: &PeerMessage, enc: Encoding) {
+    fn send_message_with_encoding(
+        &self,
+        msg: &PeerMessage,
+        enc: Encoding,
+        class: Option<rate_limit::SendClass>,
+    ) {
         // Skip sending block and headers if we received it or header from this peer.
         // Record block requests in tracker.
         match msg {
@@ -407,7 +488,7 @@ impl PeerActor {
             // peers to update its height at the peer. In the future we will introduce a new
             // peer message type for that and then we can enable this check again.
             //PeerMessage::Block(b) if self.tracker.lock().has_received(b.hash()) => return,
-            PeerMessage::BlockRequest(h) => self.tracker.lock().push_request(*h),
+            PeerMessage::BlockRequest(h) => self.tracker.lock().push_request(&self.clock, *h),
             PeerMessage::SyncAccountsData(d) => metrics::SYNC_ACCOUNTS_DATA
                 .with_label_values(&[
                     "sent",
@@ -421,11 +502,43 @@ impl PeerActor {
             _ => (),
         };
 
-        let bytes = msg.serialize(enc);
-        self.tracker.lock().increment_sent(&self.clock, bytes.len() as u64);
+        let mut bytes = msg.serialize(enc);
+        if let Some(compression) = self.compression_for(msg, enc, bytes.len()) {
+            bytes = compression::compress(&bytes, compression, msg.msg_variant());
+        }
         let bytes_len = bytes.len();
+        let mut delay = time::Duration::ZERO;
+        // Only the responses sent over TIER2 are rate limited.
+        let tier = match &self.peer_status {
+            PeerStatus::Ready(conn) => Some(conn.tier),
+            PeerStatus::Connecting(..) => None,
+        };
+        if let (Some(class), Some(tcp::Tier::T2)) = (class, tier) {
+            let stats = &self.stats.sent_by_class[class as usize];
+            delay = match self.send_limiter.lock().acquire(&self.clock, class, bytes_len) {
+                Some(delay) => delay,
+                None => {
+                    metrics::MessageDropped::RateLimited.inc_msg_type(msg.msg_variant());
+                    stats.dropped_messages.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+            };
+            if delay > time::Duration::ZERO {
+                stats.delayed_messages.fetch_add(1, Ordering::Relaxed);
+            }
+            stats.sent_bytes.fetch_add(bytes_len as u64, Ordering::Relaxed);
+            metrics::PEER_MESSAGE_SENT_BY_CLASS_BYTES
+                .with_label_values(&[class.as_ref()])
+                .inc_by(bytes_len as u64);
+        }
+        self.tracker.lock().increment_sent(&self.clock, bytes_len as u64);
         tracing::trace!(target: "network", msg_len = bytes_len);
-        self.framed.send(stream::Frame(bytes));
+        let class = quic::StreamClass::of(msg);
+        if delay > time::Duration::ZERO {
+            self.framed.send_after(&self.clock, delay, class, stream::Frame(bytes));
+        } else {
+            self.framed.send(class, stream::Frame(bytes));
+        }
         metrics::PEER_DATA_SENT_BYTES.inc_by(bytes_len as u64);
         let msg_type = msg.msg_variant();
         metrics::PEER_MESSAGE_SENT_BY_TYPE_TOTAL.with_label_values(&[msg_type]).inc();
@@ -434,7 +547,31 @@ impl PeerActor {
             .inc_by(bytes_len as u64);
     }
 
+    /// Compression to apply to the message of the given encoding and size, if any.
+    fn compression_for(
+        &self,
+        msg: &PeerMessage,
+        enc: Encoding,
+        size: usize,
+    ) -> Option<Compression> {
+        let threshold = self.network_state.config.message_compression_threshold?;
+        if enc != Encoding::Proto || size < threshold || !msg.is_compressible() {
+            return None;
+        }
+        self.compression
+    }
+
     fn send_handshake(&self, spec: HandshakeSpec) {
+        let session_key = if encryption::is_supported(spec.protocol_version) {
+            Some(self.ephemeral_key.sign(
+                &self.network_state.config.node_id(),
+                &spec.peer_id,
+                spec.partial_edge_info.nonce,
+                &self.network_state.config.node_key,
+            ))
+        } else {
+            None
+        };
         let (height, tracked_shards) =
             if let Some(chain_info) = self.network_state.chain_info.load().as_ref() {
                 (chain_info.block.header().height(), chain_info.tracked_shards.clone())
@@ -455,14 +592,21 @@ impl PeerActor {
                 archival: self.network_state.config.archive,
             },
             partial_edge_info: spec.partial_edge_info,
-            owned_account: self.network_state.config.validator.as_ref().map(|vc| {
+            owned_account: self.network_state.config.validator.as_ref().and_then(|vc| {
                 OwnedAccount {
                     account_key: vc.signer.public_key(),
                     peer_id: self.network_state.config.node_id(),
                     timestamp: self.clock.now_utc(),
                 }
                 .sign(vc.signer.as_ref())
+                .inspect_err(|err| {
+                    tracing::warn!(target: "network", ?err, "Failed to sign the owned account")
+                })
+                .ok()
             }),
+            session_key,
+            supported_compression: Compression::SUPPORTED.to_vec(),
+            sender_extra_addrs: self.network_state.config.extra_addrs.clone(),
         };
         let msg = match spec.tier {
             tcp::Tier::T1 => PeerMessage::Tier1Handshake(handshake),
@@ -500,6 +644,11 @@ impl PeerActor {
             PeerStatus::Connecting(_, it) => it,
             _ => panic!("process_handshake called in non-connecting state"),
         };
+        if self.quic && tier != tcp::Tier::T1 {
+            tracing::debug!(target: "network", "Received {tier:?} handshake over QUIC. Disconnecting peer {}", handshake.sender_peer_id);
+            self.stop(ctx, ClosingReason::DisallowedMessage);
+            return;
+        }
         match cs {
             ConnectingStatus::Outbound { handshake_spec: spec, .. } => {
                 if handshake.protocol_version != spec.protocol_version {
@@ -602,8 +751,42 @@ impl PeerActor {
             }
         }
 
-        // Merge partial edges.
+        // Establish encryption. Peers offer a session key whenever the protocol version of the
+        // connection supports it, so a proto Handshake without one is rejected rather than
+        // silently downgraded to plaintext. Borsh Handshakes can't carry a session key.
         let nonce = handshake.partial_edge_info.nonce;
+        let ciphers = match &handshake.session_key {
+            _ if !encryption::is_supported(handshake.protocol_version) => None,
+            Some(session_key) => {
+                if !session_key.verify(&handshake.sender_peer_id, self.my_node_id(), nonce) {
+                    self.stop(ctx, ClosingReason::Ban(ReasonForBan::InvalidSignature));
+                    return;
+                }
+                Some(self.ephemeral_key.agree(session_key, self.peer_type))
+            }
+            None if self.encoding() == Some(Encoding::Proto) => {
+                tracing::debug!(target: "network", "Handshake from {} is missing a session key. Disconnecting.", handshake.sender_peer_id);
+                self.stop(ctx, ClosingReason::HandshakeFailed);
+                return;
+            }
+            None => None,
+        };
+        let encrypted = ciphers.is_some();
+        self.compression = Compression::negotiate(&handshake.supported_compression);
+        let ciphers = match ciphers {
+            // Outbound peer has already sent its Handshake, so it enables encryption right away,
+            // before the next frame (already encrypted by the inbound peer) is received.
+            Some(ciphers) if self.peer_type == PeerType::Outbound => {
+                self.framed.enable_encryption(ciphers);
+                None
+            }
+            ciphers => ciphers,
+        };
+        if self.peer_type == PeerType::Outbound {
+            self.framed.start_data_streams();
+        }
+
+        // Merge partial edges.
         let partial_edge_info = match cs {
             ConnectingStatus::Outbound { handshake_spec, .. } => {
                 handshake_spec.partial_edge_info.clone()
@@ -625,12 +808,33 @@ impl PeerActor {
         // we still should do the check against the PeerStore::blacklist.
         // Currently PeerManager is rejecting connections with peer_info.addr == None
         // preemptively.
+        let addr =
+            handshake.sender_listen_port.map(|port| SocketAddr::new(self.peer_addr.ip(), port));
+        // The extra addresses are not verified in any way, so only the ones which might be
+        // reachable from the public internet are accepted. Addresses from the private ranges
+        // are accepted only from peers connected over a private network (i.e. localnet).
+        let allow_private = !config::is_public_ip(self.peer_addr.ip());
+        let mut extra_addrs: Vec<SocketAddr> = vec![];
+        for extra_addr in &handshake.sender_extra_addrs {
+            if extra_addrs.len() == MAX_ADDRS_PER_PEER - 1 {
+                break;
+            }
+            if extra_addr.port() == 0
+                || extra_addr.ip().is_unspecified()
+                || !(allow_private || config::is_public_ip(extra_addr.ip()))
+                || Some(*extra_addr) == addr
+                || extra_addrs.contains(extra_addr)
+            {
+                continue;
+            }
+            extra_addrs.push(*extra_addr);
+        }
         let peer_info = PeerInfo {
             id: handshake.sender_peer_id.clone(),
-            addr: handshake
-                .sender_listen_port
-                .map(|port| SocketAddr::new(self.peer_addr.ip(), port)),
+            // The observed address is preferred, since it is known to be reachable.
+            addr,
             account_id: None,
+            extra_addrs,
         };
 
         let now = self.clock.now();
@@ -642,6 +846,7 @@ impl PeerActor {
             genesis_id: handshake.sender_chain_info.genesis_id.clone(),
             tracked_shards: handshake.sender_chain_info.tracked_shards.clone(),
             archival: handshake.sender_chain_info.archival,
+            protocol_version: handshake.protocol_version,
             last_block: Default::default(),
             peer_type: self.peer_type,
             stats: self.stats.clone(),
@@ -667,9 +872,18 @@ impl PeerActor {
             time::Interval::new(clock.now(), self.network_state.config.peer_stats_period);
         ctx.spawn({
             let conn = conn.clone();
+            let network_state = self.network_state.clone();
             wrap_future(async move {
                 loop {
                     interval.tick(&clock).await;
+                    let timed_out = tracker.lock().take_timed_out_requests(&clock, REQUEST_TIMEOUT);
+                    for _ in 0..timed_out {
+                        network_state.report_peer(
+                            &clock,
+                            &conn.peer_info.id,
+                            reputation::Event::Timeout,
+                        );
+                    }
                     let sent = tracker.lock().sent_bytes.minute_stats(&clock);
                     let received = tracker.lock().received_bytes.minute_stats(&clock);
                     conn.stats
@@ -711,6 +925,13 @@ impl PeerActor {
                                 partial_edge_info: partial_edge_info,
                             });
                         }
+                        // Inbound peer enables encryption once its Handshake is queued:
+                        // the outbound peer won't send any encrypted frames before receiving it.
+                        if let Some(ciphers) = ciphers {
+                            act.framed.enable_encryption(ciphers);
+                        }
+                        act.framed.start_data_streams();
+                        tracing::debug!(target: "network", peer_id = ?conn.peer_info.id, encrypted, "Connection established");
                         // TIER1 is strictly reserved for BFT consensensus messages,
                         // so all kinds of periodical syncs happen only on TIER2 connections.
                         if tier==tcp::Tier::T2 {
@@ -943,6 +1164,7 @@ impl PeerActor {
         clock: &time::Clock,
         network_state: &NetworkState,
         peer_id: PeerId,
+        author: PeerId,
         msg_hash: CryptoHash,
         body: RoutedMessageBody,
     ) -> Result<Option<RoutedMessageBody>, ReasonForBan> {
@@ -1020,6 +1242,23 @@ impl PeerActor {
                 network_state.client.send_async(ChunkEndorsementMessage(endorsement)).await.ok();
                 None
             }
+            RoutedMessageBody::PartialEncodedStateWitness(part) => {
+                network_state.client.send_async(PartialEncodedStateWitnessMessage(part)).await.ok();
+                None
+            }
+            RoutedMessageBody::PartialEncodedStateWitnessForward(part) => {
+                network_state
+                    .client
+                    .send_async(PartialEncodedStateWitnessForwardMessage {
+                        part,
+                        sender_accounts: network_state
+                            .account_announcements
+                            .get_peer_accounts(&author),
+                    })
+                    .await
+                    .ok();
+                None
+            }
             body => {
                 tracing::error!(target: "network", "Peer received unexpected message type: {:?}", body);
                 None
@@ -1043,6 +1282,22 @@ impl PeerActor {
                 sink.send(Event::MessageProcessed(tier, msg));
             }
         };
+        if let Some(class) = rate_limit::RequestClass::of(&msg) {
+            let reason = if !class.is_supported(conn.protocol_version) {
+                Some("unsupported protocol version")
+            } else if !self.request_limiter.lock().allow(&self.clock, class) {
+                metrics::MessageDropped::RateLimited.inc_msg_type(msg.msg_variant());
+                Some("rate limited")
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                tracing::debug!(target: "network", peer_id = ?conn.peer_info.id, msg = msg.msg_variant(), reason, "Dropping request");
+                #[cfg(test)]
+                message_processed_event();
+                return;
+            }
+        }
         let was_requested = match &msg {
             PeerMessage::Block(block) => {
                 self.network_state.txns_since_last_block.store(0, Ordering::Release);
@@ -1055,9 +1310,20 @@ impl PeerActor {
                         last_block.clone()
                     }
                 });
-                let mut tracker = self.tracker.lock();
-                tracker.push_received(hash);
-                tracker.has_request(&hash)
+                let (was_requested, latency) = {
+                    let mut tracker = self.tracker.lock();
+                    tracker.push_received(hash);
+                    (tracker.has_request(&hash), tracker.take_response_latency(&self.clock, &hash))
+                };
+                if let Some(latency) = latency {
+                    let event = if latency > SLOW_RESPONSE_THRESHOLD {
+                        reputation::Event::SlowResponse
+                    } else {
+                        reputation::Event::UsefulResponse
+                    };
+                    self.network_state.report_peer(&self.clock, &conn.peer_info.id, event);
+                }
+                was_requested
             }
             _ => false,
         };
@@ -1072,6 +1338,7 @@ impl PeerActor {
                         &clock,
                         &network_state,
                         peer_id,
+                        msg.msg.author,
                         msg_hash,
                         msg.msg.body,
                     )
@@ -1150,6 +1417,53 @@ impl PeerActor {
                     network_state.client.send_async(StateResponse(info.into())).await.ok();
                     None
                 }
+                // Light client requests which pass the checks in receive_message() are always
+                // answered, so that the light client doesn't have to wait for a timeout to learn
+                // that it is up to date.
+                PeerMessage::LightClientBlockRequest(last_block_hash) => {
+                    let block = network_state
+                        .client
+                        .send_async(LightClientBlockRequest { last_block_hash })
+                        .await
+                        .ok()
+                        .flatten();
+                    Some(PeerMessage::LightClientBlockResponse(LightClientBlockResponse {
+                        last_block_hash,
+                        block,
+                    }))
+                }
+                PeerMessage::EpochBlockProducersRequest(block_hash) => {
+                    let block_producers = network_state
+                        .client
+                        .send_async(EpochBlockProducersRequest { block_hash })
+                        .await
+                        .ok()
+                        .flatten();
+                    Some(PeerMessage::EpochBlockProducersResponse(EpochBlockProducersResponse {
+                        block_hash,
+                        block_producers,
+                    }))
+                }
+                // An empty response tells the requesting node that it has synced all
+                // finalised epochs known to this node.
+                PeerMessage::EpochSyncRequest(epoch_id) => {
+                    let response = network_state
+                        .client
+                        .send_async(EpochSyncRequest { epoch_id: epoch_id.clone() })
+                        .await
+                        .ok()
+                        .flatten()
+                        .unwrap_or(EpochSyncInfoResponse { epoch_id, epoch_sync_infos: vec![] });
+                    Some(PeerMessage::EpochSyncResponse(response))
+                }
+                PeerMessage::EpochSyncResponse(response) => {
+                    network_state
+                        .client
+                        .send_async(EpochSyncResponse(response, peer_id))
+                        .await
+                        .ok();
+                    None
+                }
                 msg => {
                     tracing::error!(target: "network", "Peer received unexpected type: {:?}", msg);
                     None
@@ -1160,7 +1474,7 @@ impl PeerActor {
             |res, act: &mut PeerActor, ctx| {
                 match res {
                     // TODO(gprusak): make sure that for routed messages we drop routeback info correctly.
-                    Ok(Some(resp)) => act.send_message_or_log(&resp),
+                    Ok(Some(resp)) => act.send_response(&resp),
                     Ok(None) => {}
                     Err(ban_reason) => act.stop(ctx, ClosingReason::Ban(ban_reason)),
                 }
@@ -1651,6 +1965,9 @@ impl actix::Handler<stream::Error> for PeerActor {
             }
             // It is expected in a sense that the peer might be just slow.
             stream::Error::Send(stream::SendError::QueueOverflow { .. }) => true,
+            // The frame might have been tampered with on the way, so we cannot
+            // blame the peer for it.
+            stream::Error::Recv(stream::RecvError::Decrypt(_)) => true,
             stream::Error::Recv(stream::RecvError::IO(err))
             | stream::Error::Send(stream::SendError::IO(err)) => match err.kind() {
                 // Connection has been closed.
@@ -1708,6 +2025,7 @@ impl actix::Handler<stream::Frame> for PeerActor {
         };
 
         tracing::trace!(target: "network", "Received message: {}", peer_msg);
+        self.capture_message(&peer_msg, &msg);
 
         {
             let labels = [peer_msg.msg_variant()];
@@ -0,0 +1,161 @@
+//! Per-connection limits of the outbound bandwidth and of the expensive inbound requests.
+//!
+//! Responses to the peer's requests (blocks, headers, state parts, chunk parts) can be the
+//! bulk of the data sent over a TIER2 connection. A single peer requesting them aggressively
+//! could saturate the node's upload bandwidth, starving other connections. Therefore
+//! responses of these classes are passed through a token bucket (tokens are bytes) per
+//! connection per class, and are delayed until the bucket is refilled. Only when the peer
+//! has requested so much that the bucket reached its maximal debt are the responses dropped:
+//! the peer is expected to retry requests which haven't been answered.
+//!
+//! Broadcasts of new blocks, chunk parts sent to the validators and other broadcasts are
+//! never limited, as the node itself depends on them being delivered, and neither is TIER1
+//! traffic. A `Block` message is limited only when it is sent in response to a
+//! `BlockRequest`, which is known only at the send site, see `SendClass::of_response`.
+//!
+//! Independently, requests which are served only to the nodes outside of the validator set
+//! and are expensive to serve (see `RequestClass`) are passed through a token bucket
+//! (tokens are requests) per connection per class, and dropped while the bucket is empty.
+use crate::concurrency::rate;
+use crate::config;
+use crate::network_protocol::{PeerMessage, RoutedMessageBody};
+use near_async::time;
+use near_primitives::version::{ProtocolFeature, ProtocolVersion};
+use std::sync::atomic::AtomicU64;
+
+/// Class of outbound messages subject to rate limiting.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::AsRefStr, strum::IntoStaticStr)]
+pub(crate) enum SendClass {
+    SyncResponses = 0,
+    StateParts = 1,
+    RoutedChunks = 2,
+}
+
+impl SendClass {
+    pub const COUNT: usize = 3;
+    pub const ALL: [SendClass; Self::COUNT] =
+        [SendClass::SyncResponses, SendClass::StateParts, SendClass::RoutedChunks];
+
+    /// Returns the class of the message, or None if the message is not rate limited.
+    /// Only responses to the peer's requests are limited. In particular `Block` is not,
+    /// because it is also used to broadcast new blocks.
+    pub fn of(msg: &PeerMessage) -> Option<Self> {
+        match msg {
+            PeerMessage::BlockHeaders(_)
+            | PeerMessage::LightClientBlockResponse(_)
+            | PeerMessage::EpochBlockProducersResponse(_)
+            | PeerMessage::EpochSyncResponse(_) => Some(Self::SyncResponses),
+            PeerMessage::VersionedStateResponse(_) => Some(Self::StateParts),
+            PeerMessage::Routed(msg) => match msg.body {
+                RoutedMessageBody::StateResponse(_) => Some(Self::StateParts),
+                RoutedMessageBody::PartialEncodedChunkResponse(_) => Some(Self::RoutedChunks),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the class of a message sent in response to the peer's request, or None if the
+    /// message is not rate limited. Unlike `of`, it limits a `Block`, which can only be the
+    /// response to a `BlockRequest`.
+    pub fn of_response(msg: &PeerMessage) -> Option<Self> {
+        match msg {
+            PeerMessage::Block(_) => Some(Self::SyncResponses),
+            _ => Self::of(msg),
+        }
+    }
+}
+
+/// Outbound traffic of a single class on a single connection.
+#[derive(Default)]
+pub(crate) struct SendClassStats {
+    /// Number of bytes sent since the connection was established.
+    pub sent_bytes: AtomicU64,
+    /// Number of messages delayed because of the rate limit.
+    pub delayed_messages: AtomicU64,
+    /// Number of messages dropped because the rate limit was exceeded
+    /// by more than the burst.
+    pub dropped_messages: AtomicU64,
+}
+
+/// Token buckets of a single connection, one per SendClass.
+pub(crate) struct SendLimiter {
+    buckets: [Option<rate::TokenBucket>; SendClass::COUNT],
+}
+
+impl SendLimiter {
+    pub fn new(clock: &time::Clock, limits: &config::SendRateLimits) -> Self {
+        let bucket = |limit: Option<rate::Limit>| limit.map(|l| rate::TokenBucket::new(clock, l));
+        Self {
+            buckets: [
+                bucket(limits.sync_responses),
+                bucket(limits.state_parts),
+                bucket(limits.routed_chunks),
+            ],
+        }
+    }
+
+    /// Returns how long a message of the given class and size has to wait before it is sent,
+    /// or None if it should be dropped.
+    pub fn acquire(
+        &mut self,
+        clock: &time::Clock,
+        class: SendClass,
+        bytes: usize,
+    ) -> Option<time::Duration> {
+        match &mut self.buckets[class as usize] {
+            Some(bucket) => bucket.acquire(clock, bytes as u64),
+            None => Some(time::Duration::ZERO),
+        }
+    }
+}
+
+/// Class of inbound requests subject to rate limiting.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::AsRefStr)]
+pub(crate) enum RequestClass {
+    LightClient = 0,
+    EpochSync = 1,
+}
+
+impl RequestClass {
+    pub const COUNT: usize = 2;
+
+    /// Returns the class of the message, or None if the message is not a limited request.
+    pub fn of(msg: &PeerMessage) -> Option<Self> {
+        match msg {
+            PeerMessage::LightClientBlockRequest(_)
+            | PeerMessage::EpochBlockProducersRequest(_) => Some(Self::LightClient),
+            PeerMessage::EpochSyncRequest(_) => Some(Self::EpochSync),
+            _ => None,
+        }
+    }
+
+    /// Whether the peer using the given protocol version may send requests of this class.
+    pub fn is_supported(self, protocol_version: ProtocolVersion) -> bool {
+        let feature = match self {
+            Self::LightClient => ProtocolFeature::LightClientP2PRequests,
+            Self::EpochSync => ProtocolFeature::EpochSyncP2PRequests,
+        };
+        feature.protocol_version() <= protocol_version
+    }
+}
+
+/// Token buckets of a single connection, one per RequestClass.
+pub(crate) struct RequestLimiter {
+    buckets: [Option<rate::TokenBucket>; RequestClass::COUNT],
+}
+
+impl RequestLimiter {
+    pub fn new(clock: &time::Clock, limits: &config::RequestRateLimits) -> Self {
+        let bucket = |limit: Option<rate::Limit>| limit.map(|l| rate::TokenBucket::new(clock, l));
+        Self { buckets: [bucket(limits.light_client), bucket(limits.epoch_sync)] }
+    }
+
+    /// Whether a request of the given class should be served.
+    pub fn allow(&mut self, clock: &time::Clock, class: RequestClass) -> bool {
+        match &mut self.buckets[class as usize] {
+            Some(bucket) => bucket.try_acquire(clock, 1),
+            None => true,
+        }
+    }
+}
@@ -1,25 +1,27 @@
+use crate::peer::encryption;
 use crate::peer_manager::connection;
+use crate::quic;
 use crate::stats::metrics;
 use crate::tcp;
 use actix::fut::future::wrap_future;
 use actix::AsyncContext as _;
 use bytesize::{GIB, MIB};
+use near_async::time;
+use parking_lot::Mutex;
 use std::io;
 use std::net::SocketAddr;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 use tokio::io::AsyncReadExt as _;
 use tokio::io::AsyncWriteExt as _;
+use tokio::io::{AsyncRead, AsyncWrite};
 
 /// Maximum size of network message in encoded format.
 /// We encode length as `u32`, and therefore maximum size can't be larger than `u32::MAX`.
-const NETWORK_MESSAGE_MAX_SIZE_BYTES: usize = 512 * MIB as usize;
+pub(crate) const NETWORK_MESSAGE_MAX_SIZE_BYTES: usize = 512 * MIB as usize;
 /// Maximum capacity of write buffer in bytes.
 const MAX_WRITE_BUFFER_CAPACITY_BYTES: usize = GIB as usize;
 
-type ReadHalf = tokio::io::ReadHalf<tokio::net::TcpStream>;
-type WriteHalf = tokio::io::WriteHalf<tokio::net::TcpStream>;
-
 #[derive(thiserror::Error, Debug)]
 pub(crate) enum SendError {
     #[error("IO error: {0}")]
@@ -34,12 +36,20 @@ pub(crate) enum RecvError {
     IO(#[source] io::Error),
     #[error("message too large: got {got_bytes}B, want <={want_max_bytes}B")]
     MessageTooLarge { got_bytes: usize, want_max_bytes: usize },
+    #[error("decrypt: {0}")]
+    Decrypt(#[source] encryption::DecryptError),
 }
 
 #[derive(actix::Message, PartialEq, Eq, Clone, Debug)]
 #[rtype(result = "()")]
 pub(crate) struct Frame(pub Vec<u8>);
 
+enum SendQueueItem {
+    Frame(Frame),
+    /// All frames queued after this item are encrypted with the given cipher.
+    EnableEncryption(encryption::FrameCipher),
+}
+
 /// Stream critical error.
 /// Actor is responsible for calling ctx.stop() after receiving stream::Error.
 /// Actor might receive more than 1 stream::Error, but should call ctx.stop() just after the
@@ -57,7 +67,13 @@ pub(crate) enum Error {
 }
 
 pub(crate) struct FramedStream<Actor: actix::Actor> {
-    queue_send: tokio::sync::mpsc::UnboundedSender<Frame>,
+    /// Send queue per stream: a single one for TCP, one per quic::StreamClass for QUIC.
+    queues_send: Vec<tokio::sync::mpsc::UnboundedSender<SendQueueItem>>,
+    /// Cipher to decrypt the received frames with, picked up by the recv loops.
+    recv_cipher: Arc<Mutex<Option<encryption::FrameCipher>>>,
+    /// Set once the frames received on QUIC streams other than
+    /// quic::StreamClass::Control can be delivered to the Actor.
+    data_streams_started: tokio::sync::watch::Sender<bool>,
     stats: Arc<connection::Stats>,
     send_buf_size_metric: Arc<metrics::IntGaugeGuard>,
     addr: actix::Addr<Actor>,
@@ -74,41 +90,166 @@ where
         stream: tcp::Stream,
         stats: Arc<connection::Stats>,
     ) -> Self {
-        let (tcp_recv, tcp_send) = tokio::io::split(stream.stream);
-        let (queue_send, queue_recv) = tokio::sync::mpsc::unbounded_channel();
+        let recv_cipher = Arc::new(Mutex::new(None));
+        let (data_streams_started, _) = tokio::sync::watch::channel(false);
         let send_buf_size_metric = Arc::new(metrics::MetricGuard::new(
             &*metrics::PEER_DATA_WRITE_BUFFER_SIZE,
             vec![stream.peer_addr.to_string()],
         ));
-        ctx.spawn(wrap_future({
-            let addr = ctx.address();
-            let stats = stats.clone();
-            let m = send_buf_size_metric.clone();
-            async move {
-                if let Err(err) = Self::run_send_loop(tcp_send, queue_recv, stats, m).await {
-                    addr.do_send(Error::Send(SendError::IO(err)));
-                }
+        let peer_addr = stream.peer_addr;
+        let queues_send = match stream.stream {
+            tcp::Transport::Tcp(stream) => {
+                let (tcp_recv, tcp_send) = tokio::io::split(stream);
+                let (queue_send, queue_recv) = tokio::sync::mpsc::unbounded_channel();
+                ctx.spawn(wrap_future({
+                    let addr = ctx.address();
+                    let stats = stats.clone();
+                    let m = send_buf_size_metric.clone();
+                    async move {
+                        if let Err(err) = Self::run_send_loop(tcp_send, queue_recv, stats, m).await
+                        {
+                            addr.do_send(Error::Send(SendError::IO(err)));
+                        }
+                    }
+                }));
+                ctx.spawn(wrap_future({
+                    let addr = ctx.address();
+                    let stats = stats.clone();
+                    let recv_cipher = recv_cipher.clone();
+                    async move {
+                        if let Err(err) = Self::run_recv_loop(
+                            peer_addr,
+                            tcp_recv,
+                            // TCP connection consists of a single stream.
+                            0,
+                            recv_cipher,
+                            addr.clone(),
+                            stats,
+                        )
+                        .await
+                        {
+                            addr.do_send(Error::Recv(err));
+                        }
+                    }
+                }));
+                vec![queue_send]
             }
-        }));
-        ctx.spawn(wrap_future({
-            let addr = ctx.address();
-            let stats = stats.clone();
-            async move {
-                if let Err(err) =
-                    Self::run_recv_loop(stream.peer_addr, tcp_recv, addr.clone(), stats).await
-                {
-                    addr.do_send(Error::Recv(err));
+            tcp::Transport::Quic(conn) => {
+                let mut queues_send = vec![];
+                for class in quic::StreamClass::ALL {
+                    let (queue_send, queue_recv) = tokio::sync::mpsc::unbounded_channel();
+                    queues_send.push(queue_send);
+                    ctx.spawn(wrap_future({
+                        let addr = ctx.address();
+                        let conn = conn.clone();
+                        let stats = stats.clone();
+                        let m = send_buf_size_metric.clone();
+                        async move {
+                            let res = async {
+                                let send = quic::open_stream(&conn, class).await?;
+                                Self::run_send_loop(send, queue_recv, stats, m).await
+                            };
+                            if let Err(err) = res.await {
+                                addr.do_send(Error::Send(SendError::IO(err)));
+                            }
+                        }
+                    }));
                 }
+                ctx.spawn(wrap_future({
+                    let addr = ctx.address();
+                    let stats = stats.clone();
+                    let recv_cipher = recv_cipher.clone();
+                    let started = data_streams_started.subscribe();
+                    async move {
+                        if let Err(err) = Self::run_quic_recv_loops(
+                            peer_addr,
+                            conn,
+                            started,
+                            recv_cipher,
+                            addr.clone(),
+                            stats,
+                        )
+                        .await
+                        {
+                            addr.do_send(Error::Recv(err));
+                        }
+                    }
+                }));
+                queues_send
             }
-        }));
-        Self { queue_send, stats, send_buf_size_metric, addr: ctx.address() }
+        };
+        Self {
+            queues_send,
+            recv_cipher,
+            data_streams_started,
+            stats,
+            send_buf_size_metric,
+            addr: ctx.address(),
+        }
     }
 
-    /// Pushes `msg` to the send queue.
+    /// Starts delivering to the Actor the frames received on QUIC streams other than
+    /// quic::StreamClass::Control. These streams are not ordered wrt. the Handshake,
+    /// so this method should be called once the Handshake has been processed and
+    /// encryption (if any) has been enabled. No-op for TCP streams.
+    pub fn start_data_streams(&self) {
+        self.data_streams_started.send_replace(true);
+    }
+
+    /// Enables encryption of the stream.
+    /// All frames passed to `send` after this call are encrypted with `ciphers.send`.
+    /// All frames received after the frame currently processed by the Actor
+    /// are decrypted with `ciphers.recv`. Therefore this method should be called
+    /// from the Actor's Handler<Frame>, or when the peer is known not to send
+    /// any frames until it receives a frame which is yet to be sent.
+    pub fn enable_encryption(&self, ciphers: encryption::SessionCiphers) {
+        *self.recv_cipher.lock() = Some(ciphers.recv);
+        for (stream, queue_send) in self.queues_send.iter().enumerate() {
+            let cipher = ciphers.send.for_stream(stream as u32);
+            let _ = queue_send.send(SendQueueItem::EnableEncryption(cipher));
+        }
+    }
+
+    /// Pushes `msg` to the send queue of the stream of the given class.
+    /// For TCP streams `class` is ignored: all frames are sent in order.
     /// Silently drops message if the connection has been closed.
     /// If the message is too large, it will be silently dropped inside run_send_loop.
     /// Emits a critical error to Actor if send queue is full.
-    pub fn send(&self, frame: Frame) {
+    pub fn send(&self, class: quic::StreamClass, frame: Frame) {
+        self.account(&frame);
+        let _ = self.queue_send(class).send(SendQueueItem::Frame(frame));
+    }
+
+    /// Like `send`, but pushes the frame to the send queue only after `delay`.
+    /// The frame counts towards the send buffer capacity right away.
+    pub fn send_after(
+        &self,
+        clock: &time::Clock,
+        delay: time::Duration,
+        class: quic::StreamClass,
+        frame: Frame,
+    ) {
+        self.account(&frame);
+        let queue_send = self.queue_send(class).clone();
+        let clock = clock.clone();
+        actix::spawn(async move {
+            clock.sleep(delay).await;
+            let _ = queue_send.send(SendQueueItem::Frame(frame));
+        });
+    }
+
+    fn queue_send(
+        &self,
+        class: quic::StreamClass,
+    ) -> &tokio::sync::mpsc::UnboundedSender<SendQueueItem> {
+        match &self.queues_send[..] {
+            [queue_send] => queue_send,
+            queues_send => &queues_send[class as usize],
+        }
+    }
+
+    /// Accounts the frame in the send buffer size.
+    fn account(&self, frame: &Frame) {
         let msg = &frame.0;
         let mut buf_size =
             self.stats.bytes_to_send.fetch_add(msg.len() as u64, Ordering::Acquire) as usize;
@@ -125,7 +266,39 @@ where
                 want_max_bytes: MAX_WRITE_BUFFER_CAPACITY_BYTES,
             }));
         }
-        let _ = self.queue_send.send(frame);
+    }
+
+    /// Accepts the QUIC streams opened by the peer and runs a recv loop for each of them.
+    /// Frames received on the streams other than quic::StreamClass::Control are not read
+    /// until `start_data_streams` is called.
+    async fn run_quic_recv_loops(
+        peer_addr: SocketAddr,
+        conn: quinn::Connection,
+        started: tokio::sync::watch::Receiver<bool>,
+        recv_cipher: Arc<Mutex<Option<encryption::FrameCipher>>>,
+        addr: actix::Addr<Actor>,
+        stats: Arc<connection::Stats>,
+    ) -> Result<(), RecvError> {
+        let streams = quic::accept_streams(&conn).await.map_err(RecvError::IO)?;
+        futures_util::future::try_join_all(streams.into_iter().map(|(class, read)| {
+            let mut started = started.clone();
+            let recv_cipher = recv_cipher.clone();
+            let addr = addr.clone();
+            let stats = stats.clone();
+            async move {
+                if class != quic::StreamClass::Control {
+                    while !*started.borrow_and_update() {
+                        if started.changed().await.is_err() {
+                            // FramedStream has been dropped.
+                            return Ok(());
+                        }
+                    }
+                }
+                Self::run_recv_loop(peer_addr, read, class as u32, recv_cipher, addr, stats).await
+            }
+        }))
+        .await?;
+        Ok(())
     }
 
     /// Event loop receiving and processing messages.
@@ -138,7 +311,9 @@ where
     // directly from the stream.
     async fn run_recv_loop(
         peer_addr: SocketAddr,
-        read: ReadHalf,
+        read: impl AsyncRead + Unpin,
+        stream: u32,
+        recv_cipher: Arc<Mutex<Option<encryption::FrameCipher>>>,
         addr: actix::Addr<Actor>,
         stats: Arc<connection::Stats>,
     ) -> Result<(), RecvError> {
@@ -151,6 +326,7 @@ where
             &metrics::PEER_DATA_READ_BUFFER_SIZE,
             vec![peer_addr.to_string()],
         );
+        let mut cipher = None;
         loop {
             let n = read.read_u32_le().await.map_err(RecvError::IO)? as usize;
             if n > NETWORK_MESSAGE_MAX_SIZE_BYTES {
@@ -168,6 +344,14 @@ where
             buf_size_metric.set(0);
             stats.received_messages.fetch_add(1, Ordering::Relaxed);
             stats.received_bytes.fetch_add(n as u64, Ordering::Relaxed);
+            // Encryption is enabled by the Actor while processing a frame, i.e. before
+            // the next frame is read, so it is enough to check it once per frame.
+            if cipher.is_none() {
+                cipher = recv_cipher.lock().as_ref().map(|c| c.for_stream(stream));
+            }
+            if let Some(cipher) = &mut cipher {
+                buf = cipher.decrypt(&buf).map_err(RecvError::Decrypt)?;
+            }
             if let Err(_) = addr.send(Frame(buf)).await {
                 // We got mailbox error, which means that Actor has stopped,
                 // so we should just close the stream.
@@ -176,29 +360,43 @@ where
         }
     }
     async fn run_send_loop(
-        tcp_send: WriteHalf,
-        mut queue_recv: tokio::sync::mpsc::UnboundedReceiver<Frame>,
+        send: impl AsyncWrite + Unpin,
+        mut queue_recv: tokio::sync::mpsc::UnboundedReceiver<SendQueueItem>,
         stats: Arc<connection::Stats>,
         buf_size_metric: Arc<metrics::IntGaugeGuard>,
     ) -> io::Result<()> {
         const WRITE_BUFFER_CAPACITY: usize = 8 * 1024;
-        let mut writer = tokio::io::BufWriter::with_capacity(WRITE_BUFFER_CAPACITY, tcp_send);
-        while let Some(Frame(mut msg)) = queue_recv.recv().await {
+        let mut writer = tokio::io::BufWriter::with_capacity(WRITE_BUFFER_CAPACITY, send);
+        let mut cipher: Option<encryption::FrameCipher> = None;
+        while let Some(mut item) = queue_recv.recv().await {
             // Try writing a batch of messages and flush once at the end.
             loop {
-                // TODO(gprusak): sending a too large message should probably be treated as a bug,
-                // since dropping messages may lead to hard-to-debug high-level issues.
-                if msg.len() > NETWORK_MESSAGE_MAX_SIZE_BYTES {
-                    metrics::MessageDropped::InputTooLong.inc_unknown_msg();
-                } else {
-                    writer.write_u32_le(msg.len() as u32).await?;
-                    writer.write_all(&msg[..]).await?;
+                match item {
+                    SendQueueItem::EnableEncryption(c) => cipher = Some(c),
+                    SendQueueItem::Frame(Frame(msg)) => {
+                        let encrypted;
+                        let data = match &mut cipher {
+                            Some(cipher) => {
+                                encrypted = cipher.encrypt(&msg);
+                                &encrypted[..]
+                            }
+                            None => &msg[..],
+                        };
+                        // TODO(gprusak): sending a too large message should probably be treated as a bug,
+                        // since dropping messages may lead to hard-to-debug high-level issues.
+                        if data.len() > NETWORK_MESSAGE_MAX_SIZE_BYTES {
+                            metrics::MessageDropped::InputTooLong.inc_unknown_msg();
+                        } else {
+                            writer.write_u32_le(data.len() as u32).await?;
+                            writer.write_all(data).await?;
+                        }
+                        stats.messages_to_send.fetch_sub(1, Ordering::Release);
+                        stats.bytes_to_send.fetch_sub(msg.len() as u64, Ordering::Release);
+                        buf_size_metric.sub(msg.len() as i64);
+                    }
                 }
-                stats.messages_to_send.fetch_sub(1, Ordering::Release);
-                stats.bytes_to_send.fetch_sub(msg.len() as u64, Ordering::Release);
-                buf_size_metric.sub(msg.len() as i64);
-                msg = match queue_recv.try_recv() {
-                    Ok(Frame(it)) => it,
+                item = match queue_recv.try_recv() {
+                    Ok(it) => it,
                     Err(_) => break,
                 };
             }
@@ -10,6 +10,7 @@ use crate::peer::peer_actor::PeerActor;
 use crate::peer_manager::network_state::NetworkState;
 use crate::peer_manager::peer_manager_actor;
 use crate::peer_manager::peer_store;
+use crate::peer_manager::reputation;
 use crate::private_actix::SendMessage;
 use crate::shards_manager::ShardsManagerRequestFromNetwork;
 use crate::store;
@@ -122,6 +123,7 @@ impl PeerHandle {
             &clock,
             store.clone(),
             peer_store::PeerStore::new(&clock, network_cfg.peer_store.clone()).unwrap(),
+            reputation::Reputation::new(network_cfg.reputation.clone(), store.clone()).unwrap(),
             network_cfg.verify().unwrap(),
             cfg.chain.genesis_id.clone(),
             client_sender.break_apart().into_multi_sender(),
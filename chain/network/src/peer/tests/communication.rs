@@ -3,6 +3,7 @@ use crate::network_protocol::{
     Encoding, Handshake, HandshakeFailureReason, PartialEdgeInfo, PeerMessage, PeersRequest,
     PeersResponse, RoutedMessageBody,
 };
+use crate::peer::encryption;
 use crate::peer::testonly::{Event, PeerConfig, PeerHandle};
 use crate::peer_manager::peer_manager_actor::Event as PME;
 use crate::tcp;
@@ -210,6 +211,9 @@ async fn test_handshake(outbound_encoding: Option<Encoding>, inbound_encoding: O
         sender_chain_info: outbound_cfg.chain.get_peer_chain_info(),
         partial_edge_info: outbound_cfg.partial_edge_info(&inbound.cfg.id(), 1),
         owned_account: None,
+        session_key: None,
+        supported_compression: vec![],
+        sender_extra_addrs: vec![],
     };
     // We will also introduce chain_id mismatch, but ProtocolVersionMismatch is expected to take priority.
     handshake.sender_chain_info.genesis_id.chain_id = "unknown_chain".to_string();
@@ -243,11 +247,65 @@ async fn test_handshake(outbound_encoding: Option<Encoding>, inbound_encoding: O
 
     // Send a correct Handshake, expect a matching Handshake response.
     handshake.sender_chain_info = chain.get_peer_chain_info();
+    handshake.session_key = encryption::is_supported(PROTOCOL_VERSION).then(|| {
+        encryption::EphemeralKey::new().sign(
+            &outbound_cfg.id(),
+            &inbound.cfg.id(),
+            1,
+            &outbound_cfg.network.node_key,
+        )
+    });
     outbound.write(&PeerMessage::Tier2Handshake(handshake.clone())).await;
     let resp = outbound.read().await.unwrap();
     assert_matches!(resp, PeerMessage::Tier2Handshake(_));
 }
 
+#[tokio::test]
+// Verifies that a proto Handshake without a session key is rejected if the protocol version
+// supports encryption, instead of falling back to a plaintext connection.
+async fn handshake_without_session_key() {
+    init_test_logger();
+    if !encryption::is_supported(PROTOCOL_VERSION) {
+        return;
+    }
+    let mut rng = make_rng(89028037453);
+    let mut clock = time::FakeClock::default();
+
+    let chain = Arc::new(data::Chain::make(&mut clock, &mut rng, 12));
+    let inbound_cfg = PeerConfig {
+        network: chain.make_config(&mut rng),
+        chain: chain.clone(),
+        force_encoding: Some(Encoding::Proto),
+    };
+    let outbound_cfg = PeerConfig {
+        network: chain.make_config(&mut rng),
+        chain: chain.clone(),
+        force_encoding: Some(Encoding::Proto),
+    };
+    let (outbound_stream, inbound_stream) =
+        tcp::Stream::loopback(inbound_cfg.id(), tcp::Tier::T2).await;
+    let inbound = PeerHandle::start_endpoint(clock.clock(), inbound_cfg, inbound_stream).await;
+    let outbound_port = outbound_stream.local_addr.port();
+    let mut outbound = Stream::new(Some(Encoding::Proto), outbound_stream);
+
+    let handshake = Handshake {
+        protocol_version: PROTOCOL_VERSION,
+        oldest_supported_version: PROTOCOL_VERSION,
+        sender_peer_id: outbound_cfg.id(),
+        target_peer_id: inbound.cfg.id(),
+        sender_listen_port: Some(outbound_port),
+        sender_chain_info: chain.get_peer_chain_info(),
+        partial_edge_info: outbound_cfg.partial_edge_info(&inbound.cfg.id(), 1),
+        owned_account: None,
+        session_key: None,
+        supported_compression: vec![],
+        sender_extra_addrs: vec![],
+    };
+    outbound.write(&PeerMessage::Tier2Handshake(handshake)).await;
+    // The connection is closed without a Handshake response.
+    assert!(outbound.read().await.is_err());
+}
+
 #[tokio::test]
 // Verifies that HandshakeFailures are served correctly.
 async fn handshake() -> anyhow::Result<()> {
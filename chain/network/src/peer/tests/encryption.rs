@@ -0,0 +1,44 @@
+use crate::network_protocol::testonly as data;
+use crate::peer::encryption;
+use crate::testonly::make_rng;
+use crate::types::PeerType;
+
+#[test]
+fn session_key_signature() {
+    let mut rng = make_rng(2390482374);
+    let node_key = data::make_secret_key(&mut rng);
+    let sender = near_primitives::network::PeerId::new(node_key.public_key());
+    let target = data::make_peer_id(&mut rng);
+    let key = encryption::EphemeralKey::new().sign(&sender, &target, 7, &node_key);
+    assert!(key.verify(&sender, &target, 7));
+    // Signature is bound to the connection it was made for.
+    assert!(!key.verify(&sender, &target, 8));
+    assert!(!key.verify(&sender, &data::make_peer_id(&mut rng), 7));
+    assert!(!key.verify(&target, &sender, 7));
+}
+
+#[test]
+fn frame_encryption() {
+    let mut rng = make_rng(89723409234);
+    let node_key = data::make_secret_key(&mut rng);
+    let (id1, id2) = (data::make_peer_id(&mut rng), data::make_peer_id(&mut rng));
+    let k1 = encryption::EphemeralKey::new();
+    let k2 = encryption::EphemeralKey::new();
+    let mut c1 = k1.agree(&k2.sign(&id2, &id1, 1, &node_key), PeerType::Outbound);
+    let mut c2 = k2.agree(&k1.sign(&id1, &id2, 1, &node_key), PeerType::Inbound);
+
+    let msg = b"hello".to_vec();
+    let frame = c1.send.encrypt(&msg);
+    assert_ne!(frame, msg);
+    assert_eq!(c2.recv.decrypt(&frame).unwrap(), msg);
+    assert_eq!(c1.recv.decrypt(&c2.send.encrypt(&msg)).unwrap(), msg);
+
+    // Replayed frame is rejected.
+    assert!(c2.recv.decrypt(&frame).is_err());
+    // Modified frame is rejected.
+    let frame = c1.send.encrypt(&msg);
+    let mut modified = frame.clone();
+    modified[0] ^= 1;
+    assert!(c2.recv.decrypt(&modified).is_err());
+    assert_eq!(c2.recv.decrypt(&frame).unwrap(), msg);
+}
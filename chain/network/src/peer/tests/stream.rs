@@ -1,11 +1,15 @@
 use crate::actix::ActixSystem;
 use crate::network_protocol::testonly as data;
+use crate::peer::encryption;
 use crate::peer::stream;
+use crate::quic;
 use crate::tcp;
 use crate::testonly::make_rng;
+use crate::types::PeerType;
 use actix::Actor as _;
 use actix::ActorContext as _;
 use rand::Rng as _;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 
@@ -20,12 +24,12 @@ impl actix::Actor for Actor {
 
 #[derive(actix::Message)]
 #[rtype("()")]
-struct SendFrame(stream::Frame);
+struct SendFrame(quic::StreamClass, stream::Frame);
 
 impl actix::Handler<SendFrame> for Actor {
     type Result = ();
-    fn handle(&mut self, SendFrame(frame): SendFrame, _ctx: &mut Self::Context) {
-        self.stream.send(frame);
+    fn handle(&mut self, SendFrame(class, frame): SendFrame, _ctx: &mut Self::Context) {
+        self.stream.send(class, frame);
     }
 }
 
@@ -49,13 +53,17 @@ struct Handler {
 }
 
 impl Actor {
-    async fn spawn(s: tcp::Stream) -> Handler {
+    async fn spawn(s: tcp::Stream, ciphers: Option<encryption::SessionCiphers>) -> Handler {
         let (queue_send, queue_recv) = mpsc::unbounded_channel();
         Handler {
             queue_recv,
             system: ActixSystem::spawn(|| {
                 Actor::create(|ctx| {
                     let stream = stream::FramedStream::spawn(ctx, s, Arc::default());
+                    if let Some(ciphers) = ciphers {
+                        stream.enable_encryption(ciphers);
+                    }
+                    stream.start_data_streams();
                     Self { stream, queue_send }
                 })
             })
@@ -64,13 +72,7 @@ impl Actor {
     }
 }
 
-#[tokio::test]
-async fn send_recv() {
-    let mut rng = make_rng(98324532);
-    let (s1, s2) = tcp::Stream::loopback(data::make_peer_id(&mut rng), tcp::Tier::T2).await;
-    let a1 = Actor::spawn(s1).await;
-    let mut a2 = Actor::spawn(s2).await;
-
+async fn check_send_recv(rng: &mut impl rand::Rng, a1: &Handler, a2: &mut Handler) {
     for _ in 0..5 {
         let n = rng.gen_range(1..10);
         let msgs: Vec<_> = (0..n)
@@ -82,7 +84,7 @@ async fn send_recv() {
             })
             .collect();
         for msg in &msgs {
-            a1.system.addr.send(SendFrame(msg.clone())).await.unwrap();
+            a1.system.addr.send(SendFrame(quic::StreamClass::Control, msg.clone())).await.unwrap();
         }
         for want in &msgs {
             let got = a2.queue_recv.recv().await.unwrap();
@@ -90,3 +92,69 @@ async fn send_recv() {
         }
     }
 }
+
+#[tokio::test]
+async fn send_recv() {
+    let mut rng = make_rng(98324532);
+    let (s1, s2) = tcp::Stream::loopback(data::make_peer_id(&mut rng), tcp::Tier::T2).await;
+    let a1 = Actor::spawn(s1, None).await;
+    let mut a2 = Actor::spawn(s2, None).await;
+    check_send_recv(&mut rng, &a1, &mut a2).await;
+}
+
+#[tokio::test]
+async fn send_recv_encrypted() {
+    let mut rng = make_rng(98324532);
+    let (s1, s2) = tcp::Stream::loopback(data::make_peer_id(&mut rng), tcp::Tier::T2).await;
+    let k1 = encryption::EphemeralKey::new();
+    let k2 = encryption::EphemeralKey::new();
+    let (id1, id2) = (data::make_peer_id(&mut rng), data::make_peer_id(&mut rng));
+    let node_key = data::make_secret_key(&mut rng);
+    let c1 = k1.agree(&k2.sign(&id2, &id1, 1, &node_key), PeerType::Outbound);
+    let c2 = k2.agree(&k1.sign(&id1, &id2, 1, &node_key), PeerType::Inbound);
+    let mut a1 = Actor::spawn(s1, Some(c1)).await;
+    let mut a2 = Actor::spawn(s2, Some(c2)).await;
+    check_send_recv(&mut rng, &a1, &mut a2).await;
+    check_send_recv(&mut rng, &a2, &mut a1).await;
+}
+
+#[tokio::test]
+async fn send_recv_quic() {
+    let mut rng = make_rng(98324532);
+    let (s1, s2) = quic::testonly::loopback(&data::make_secret_key(&mut rng)).await;
+    let k1 = encryption::EphemeralKey::new();
+    let k2 = encryption::EphemeralKey::new();
+    let (id1, id2) = (data::make_peer_id(&mut rng), data::make_peer_id(&mut rng));
+    let node_key = data::make_secret_key(&mut rng);
+    let c1 = k1.agree(&k2.sign(&id2, &id1, 1, &node_key), PeerType::Outbound);
+    let c2 = k2.agree(&k1.sign(&id1, &id2, 1, &node_key), PeerType::Inbound);
+    let a1 = Actor::spawn(s1, Some(c1)).await;
+    let mut a2 = Actor::spawn(s2, Some(c2)).await;
+
+    // Frames of each class are delivered in order, but there is no order between the classes.
+    // The first byte of each frame identifies its class.
+    let mut want = HashMap::<u8, Vec<stream::Frame>>::new();
+    for _ in 0..20 {
+        let class = quic::StreamClass::ALL[rng.gen_range(0..quic::StreamClass::ALL.len())];
+        let mut msg = vec![0; rng.gen_range(1..10000)];
+        rng.fill(&mut msg[..]);
+        msg[0] = class as u8;
+        let msg = stream::Frame(msg);
+        a1.system.addr.send(SendFrame(class, msg.clone())).await.unwrap();
+        want.entry(class as u8).or_default().push(msg);
+    }
+    let mut got = HashMap::<u8, Vec<stream::Frame>>::new();
+    for _ in 0..20 {
+        let msg = a2.queue_recv.recv().await.unwrap();
+        got.entry(msg.0[0]).or_default().push(msg);
+    }
+    assert_eq!(want, got);
+}
+
+#[tokio::test]
+async fn quic_rejects_certificate_of_another_node() {
+    let mut rng = make_rng(98324533);
+    let node_key = data::make_secret_key(&mut rng);
+    let (outbound, _) = quic::testonly::connect(&node_key, data::make_peer_id(&mut rng)).await;
+    assert!(outbound.is_err());
+}
@@ -47,6 +47,8 @@ pub(crate) struct Tracker {
     pub(crate) received_bytes: TransferStats,
     /// Sent requests.
     requested: CircularUniqueQueue,
+    /// Sent requests which haven't been responded to yet, with the time they were sent.
+    pending: Vec<(CryptoHash, time::Instant)>,
     /// Received elements.
     received: CircularUniqueQueue,
 }
@@ -57,6 +59,7 @@ impl Default for Tracker {
             sent_bytes: TransferStats::default(),
             received_bytes: TransferStats::default(),
             requested: CircularUniqueQueue::new(MAX_TRACK_SIZE),
+            pending: Vec::with_capacity(MAX_TRACK_SIZE),
             received: CircularUniqueQueue::new(MAX_TRACK_SIZE),
         }
     }
@@ -86,8 +89,37 @@ impl Tracker {
         self.requested.contains(hash)
     }
 
-    pub(crate) fn push_request(&mut self, hash: CryptoHash) {
+    pub(crate) fn push_request(&mut self, clock: &time::Clock, hash: CryptoHash) {
         self.requested.push(hash);
+        if !self.pending.iter().any(|(h, _)| h == &hash) {
+            if self.pending.len() == MAX_TRACK_SIZE {
+                self.pending.remove(0);
+            }
+            self.pending.push((hash, clock.now()));
+        }
+    }
+
+    /// Marks the request as responded to.
+    /// Returns the time it took to respond, or None if the request was not pending.
+    pub(crate) fn take_response_latency(
+        &mut self,
+        clock: &time::Clock,
+        hash: &CryptoHash,
+    ) -> Option<time::Duration> {
+        let i = self.pending.iter().position(|(h, _)| h == hash)?;
+        Some(clock.now() - self.pending.remove(i).1)
+    }
+
+    /// Forgets the requests pending for longer than `timeout`. Returns their number.
+    pub(crate) fn take_timed_out_requests(
+        &mut self,
+        clock: &time::Clock,
+        timeout: time::Duration,
+    ) -> usize {
+        let now = clock.now();
+        let len = self.pending.len();
+        self.pending.retain(|(_, sent)| now - *sent < timeout);
+        len - self.pending.len()
     }
 }
 
@@ -165,4 +197,30 @@ mod tests {
         }
         assert!(q.contains(&hash(&[5])));
     }
+
+    #[test]
+    fn test_pending_requests() {
+        let clock = time::FakeClock::default();
+        let mut t = Tracker::default();
+        t.push_request(&clock.clock(), hash(&[1]));
+        clock.advance(time::Duration::seconds(1));
+        t.push_request(&clock.clock(), hash(&[2]));
+        clock.advance(time::Duration::seconds(2));
+        assert_eq!(
+            t.take_response_latency(&clock.clock(), &hash(&[1])),
+            Some(time::Duration::seconds(3))
+        );
+        // Response can be matched with the request only once.
+        assert_eq!(t.take_response_latency(&clock.clock(), &hash(&[1])), None);
+        assert!(t.has_request(&hash(&[1])));
+
+        t.push_request(&clock.clock(), hash(&[3]));
+        clock.advance(time::Duration::seconds(5));
+        assert_eq!(t.take_timed_out_requests(&clock.clock(), time::Duration::seconds(6)), 1);
+        assert_eq!(t.take_response_latency(&clock.clock(), &hash(&[2])), None);
+        assert_eq!(
+            t.take_response_latency(&clock.clock(), &hash(&[3])),
+            Some(time::Duration::seconds(5))
+        );
+    }
 }
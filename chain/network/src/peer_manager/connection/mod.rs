@@ -7,6 +7,7 @@ use crate::network_protocol::{
 };
 use crate::peer::peer_actor;
 use crate::peer::peer_actor::PeerActor;
+use crate::peer::rate_limit;
 use crate::private_actix::SendMessage;
 use crate::stats::metrics;
 use crate::tcp;
@@ -18,6 +19,7 @@ use near_o11y::WithSpanContextExt;
 use near_primitives::block::GenesisId;
 use near_primitives::network::PeerId;
 use near_primitives::types::ShardId;
+use near_primitives::version::ProtocolVersion;
 use std::collections::{hash_map::Entry, HashMap};
 use std::fmt;
 use std::future::Future;
@@ -67,6 +69,9 @@ pub(crate) struct Stats {
     pub messages_to_send: AtomicU64,
     /// Number of bytes (sum of message sizes) in the buffer to send.
     pub bytes_to_send: AtomicU64,
+
+    /// Outbound traffic of the rate limited message classes, indexed by `SendClass`.
+    pub sent_by_class: [rate_limit::SendClassStats; rate_limit::SendClass::COUNT],
 }
 
 /// Contains information relevant to a connected peer.
@@ -86,6 +91,8 @@ pub(crate) struct Connection {
     pub tracked_shards: Vec<ShardId>,
     /// Denote if a node is running in archival mode or not.
     pub archival: bool,
+    /// Protocol version declared by the peer in its Handshake.
+    pub protocol_version: ProtocolVersion,
     pub last_block: ArcSwap<Option<BlockInfo>>,
 
     /// Who started connection. Inbound (other) or Outbound (us).
@@ -117,7 +117,14 @@ impl ConnectionStore {
     }
 
     /// Given a snapshot of the TIER2 connection pool, updates the connections in storage.
-    pub fn update(&self, clock: &time::Clock, tier2: &connection::PoolSnapshot) {
+    /// Active connections are ordered by the reputation of the peers (as returned by `score`),
+    /// so that connections to the peers with the lowest reputation are evicted first.
+    pub fn update(
+        &self,
+        clock: &time::Clock,
+        tier2: &connection::PoolSnapshot,
+        score: impl Fn(&PeerId) -> f64,
+    ) {
         let now = clock.now();
         let now_utc = clock.now_utc();
 
@@ -140,6 +147,8 @@ impl ConnectionStore {
             });
         }
 
+        outbound.sort_by(|a, b| score(&b.peer_info.id).total_cmp(&score(&a.peer_info.id)));
+
         // Push the information about the active connections to the front of the store
         self.0.update(|mut inner| {
             inner.push_front_outbound(outbound);
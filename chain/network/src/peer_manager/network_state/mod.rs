@@ -8,12 +8,15 @@ use crate::network_protocol::{
     Edge, EdgeState, PartialEdgeInfo, PeerIdOrHash, PeerInfo, PeerMessage, RawRoutedMessage,
     RoutedMessageBody, RoutedMessageV2, SignedAccountData, SnapshotHostInfo,
 };
+use crate::peer::capture;
 use crate::peer::peer_actor::ClosingReason;
 use crate::peer::peer_actor::PeerActor;
 use crate::peer_manager::connection;
 use crate::peer_manager::connection_store;
 use crate::peer_manager::peer_store;
+use crate::peer_manager::reputation;
 use crate::private_actix::RegisterPeerError;
+use crate::quic;
 use crate::routing::route_back_cache::RouteBackCache;
 use crate::routing::NetworkTopologyChange;
 use crate::shards_manager::ShardsManagerRequestFromNetwork;
@@ -109,6 +112,8 @@ pub(crate) struct NetworkState {
     /// Connected peers (inbound and outbound) with their full peer information.
     pub tier2: connection::Pool,
     pub tier1: connection::Pool,
+    /// QUIC endpoint for TIER1 connections, if enabled (see `config::Tier1::enable_quic`).
+    pub quic: Option<quic::Endpoint>,
     /// Semaphore limiting inflight inbound handshakes.
     pub inbound_handshake_permits: Arc<tokio::sync::Semaphore>,
     /// Peer store that provides read/write access to peers.
@@ -117,6 +122,10 @@ pub(crate) struct NetworkState {
     pub snapshot_hosts: Arc<SnapshotHostsCache>,
     /// Connection store that provides read/write access to stored connections.
     pub connection_store: connection_store::ConnectionStore,
+    /// Reputation scores of the peers.
+    pub reputation: reputation::Reputation,
+    /// Recorder of the received messages, if traffic capture is enabled.
+    pub traffic_capture: Option<capture::Recorder>,
     /// List of peers to which we should re-establish a connection
     pub pending_reconnect: Mutex<Vec<PeerInfo>>,
     /// A graph of the whole NEAR network.
@@ -163,14 +172,17 @@ impl NetworkState {
         clock: &time::Clock,
         store: store::Store,
         peer_store: peer_store::PeerStore,
+        reputation: reputation::Reputation,
         config: config::VerifiedConfig,
         genesis_id: GenesisId,
         client: ClientSenderForNetwork,
         shards_manager_adapter: Sender<ShardsManagerRequestFromNetwork>,
         whitelist_nodes: Vec<WhitelistNode>,
     ) -> Self {
+        let runtime = Runtime::new();
         Self {
-            runtime: Runtime::new(),
+            quic: Self::bind_quic(&runtime, &config),
+            runtime,
             graph: Arc::new(crate::routing::Graph::new(
                 crate::routing::GraphConfig {
                     node_id: config.node_id(),
@@ -193,6 +205,8 @@ impl NetworkState {
             peer_store,
             snapshot_hosts: Arc::new(SnapshotHostsCache::new(config.snapshot_hosts.clone())),
             connection_store: connection_store::ConnectionStore::new(store.clone()).unwrap(),
+            reputation,
+            traffic_capture: Self::start_traffic_capture(&config),
             pending_reconnect: Mutex::new(Vec::<PeerInfo>::new()),
             accounts_data: Arc::new(AccountDataCache::new()),
             account_announcements: Arc::new(AnnounceAccountCache::new(store)),
@@ -212,6 +226,39 @@ impl NetworkState {
         }
     }
 
+    /// Binds the QUIC endpoint for TIER1 connections, if enabled.
+    /// The endpoint is driven by the `runtime`, so it lives as long as the NetworkState.
+    fn bind_quic(runtime: &Runtime, config: &config::VerifiedConfig) -> Option<quic::Endpoint> {
+        let tier1 = config.tier1.as_ref().filter(|cfg| cfg.enable_quic)?;
+        let (addr, accept_inbound) = match &config.node_addr {
+            Some(addr) => (**addr, tier1.enable_inbound),
+            // Nodes without a public address can still use QUIC for outbound connections.
+            None => ("[::]:0".parse().unwrap(), false),
+        };
+        let _guard = runtime.handle.enter();
+        match quic::Endpoint::new(addr, accept_inbound, &config.node_key) {
+            Ok(endpoint) => Some(endpoint),
+            Err(err) => {
+                tracing::error!(target: "network", ?err, "failed to start QUIC endpoint, TIER1 will use TCP only");
+                None
+            }
+        }
+    }
+
+    fn start_traffic_capture(config: &config::VerifiedConfig) -> Option<capture::Recorder> {
+        let cfg = config.traffic_capture.as_ref()?;
+        match capture::Recorder::new(cfg) {
+            Ok(recorder) => {
+                tracing::info!(target: "network", dir = ?cfg.dir, "capturing received messages");
+                Some(recorder)
+            }
+            Err(err) => {
+                tracing::error!(target: "network", ?err, "failed to start traffic capture");
+                None
+            }
+        }
+    }
+
     /// Spawn a future on the runtime which has the same lifetime as the NetworkState instance.
     /// In particular if the future contains the NetworkState handler, it will be run until
     /// completion. It is safe to self.spawn(...).await.unwrap(), since runtime will be kept alive
@@ -245,15 +292,37 @@ impl NetworkState {
         }
     }
 
+    /// Records an event affecting the reputation of the peer.
+    /// Disconnects the peer if its reputation dropped below the disconnect threshold,
+    /// and bans it if its reputation dropped below the ban threshold.
+    pub fn report_peer(&self, clock: &time::Clock, peer_id: &PeerId, event: reputation::Event) {
+        metrics::PEER_REPUTATION_EVENTS.with_label_values(&[event.as_ref()]).inc();
+        match self.reputation.report(clock, peer_id, event) {
+            reputation::Verdict::Ok => {}
+            reputation::Verdict::Disconnect => {
+                if let Some(conn) = self.tier2.load().ready.get(peer_id) {
+                    tracing::info!(target: "network", ?peer_id, "Disconnecting peer with low reputation");
+                    metrics::PEER_REPUTATION_DISCONNECTS.inc();
+                    conn.stop(None);
+                }
+            }
+            reputation::Verdict::Ban => {
+                self.disconnect_and_ban(clock, peer_id, ReasonForBan::LowReputation)
+            }
+        }
+    }
+
     /// is_peer_whitelisted checks whether a peer is a whitelisted node.
     /// whitelisted nodes are allowed to connect, even if the inbound connections limit has
     /// been reached. This predicate should be evaluated AFTER the Handshake.
+    /// Peers added by the operator at runtime are whitelisted regardless of their address.
     pub fn is_peer_whitelisted(&self, peer_info: &PeerInfo) -> bool {
         self.whitelist_nodes
             .iter()
             .filter(|wn| wn.id == peer_info.id)
             .filter(|wn| Some(wn.addr) == peer_info.addr)
             .any(|wn| wn.account_id.is_none() || wn.account_id == peer_info.account_id)
+            || self.peer_store.is_admin_added(&peer_info.id)
     }
 
     /// predicate checking whether we should allow an inbound connection from peer_info.
@@ -300,6 +369,16 @@ impl NetworkState {
                 return Err(RegisterPeerError::Banned);
             }
 
+            // Peers disconnected because of their low reputation are not allowed to reconnect
+            // until their reputation recovers. Peers with reputation below the ban threshold
+            // have been banned already.
+            if conn.tier == tcp::Tier::T2
+                && this.reputation.verdict(&clock, &peer_info.id) != reputation::Verdict::Ok
+            {
+                tracing::debug!(target: "network", id = ?peer_info.id, "Dropping connection from peer with low reputation");
+                return Err(RegisterPeerError::LowReputation);
+            }
+
             match conn.tier {
                 tcp::Tier::T1 => {
                     if conn.peer_type == PeerType::Inbound {
@@ -391,6 +470,14 @@ impl NetworkState {
             // Save the fact that we are disconnecting to the PeerStore.
             let res = match reason {
                 ClosingReason::Ban(ban_reason) => {
+                    if !matches!(ban_reason, ReasonForBan::LowReputation | ReasonForBan::ManualBan)
+                    {
+                        this.reputation.report(
+                            &clock,
+                            &peer_id,
+                            reputation::Event::Misbehavior(ban_reason),
+                        );
+                    }
                     this.peer_store.peer_ban(&clock, &conn.peer_info.id, ban_reason)
                 }
                 _ => this.peer_store.peer_disconnected(&clock, &conn.peer_info.id),
@@ -788,7 +875,8 @@ impl NetworkState {
     }
 
     pub fn update_connection_store(self: &Arc<Self>, clock: &time::Clock) {
-        self.connection_store.update(clock, &self.tier2.load());
+        self.connection_store
+            .update(clock, &self.tier2.load(), |peer_id| self.reputation.score(clock, peer_id));
     }
 
     /// Clears pending_reconnect and returns the cleared values
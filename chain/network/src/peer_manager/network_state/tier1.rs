@@ -46,18 +46,8 @@ impl super::NetworkState {
                 continue;
             }
             handles.push(async move {
-                let res = async {
-                    let stream = tcp::Stream::connect(
-                        &PeerInfo {
-                            id: proxy.peer_id.clone(),
-                            addr: Some(proxy.addr),
-                            account_id: None,
-                        },
-                        tcp::Tier::T1,
-                    )
-                    .await?;
-                    anyhow::Ok(PeerActor::spawn_and_handshake(clock.clone(), stream, None, self.clone()).await?)
-                }.await;
+                // Proxies of this node are expected to support QUIC iff this node does.
+                let res = self.tier1_connect_to_proxy(clock, proxy, self.quic.is_some()).await;
                 if let Err(err) = res {
                     tracing::warn!(target:"network", ?err, "failed to establish connection to TIER1 proxy {:?}",proxy);
                 }
@@ -66,6 +56,35 @@ impl super::NetworkState {
         futures_util::future::join_all(handles).await;
     }
 
+    /// Establishes a TIER1 connection to the given proxy.
+    /// Uses QUIC if `quic` is set and QUIC is enabled on this node, falling back to TCP.
+    async fn tier1_connect_to_proxy(
+        self: &Arc<Self>,
+        clock: &time::Clock,
+        proxy: &PeerAddr,
+        quic: bool,
+    ) -> anyhow::Result<actix::Addr<PeerActor>> {
+        let peer_info = PeerInfo::from(proxy);
+        if let (true, Some(endpoint)) = (quic, &self.quic) {
+            match endpoint.connect(&peer_info).await {
+                Ok(stream) => {
+                    return PeerActor::spawn_and_handshake(
+                        clock.clone(),
+                        stream,
+                        None,
+                        self.clone(),
+                    )
+                    .await
+                }
+                Err(err) => {
+                    tracing::debug!(target:"network", ?err, "failed to establish QUIC connection to TIER1 proxy {proxy:?}, falling back to TCP")
+                }
+            }
+        }
+        let stream = tcp::Stream::connect(&peer_info, tcp::Tier::T1).await?;
+        PeerActor::spawn_and_handshake(clock.clone(), stream, None, self.clone()).await
+    }
+
     /// Requests direct peers for accounts data full sync.
     /// Should be called whenever the accounts_data.keys changes, and
     /// periodically just in case.
@@ -99,39 +118,57 @@ impl super::NetworkState {
             (_, config::ValidatorProxies::Static(peer_addrs)) => peer_addrs.clone(),
             // If Dynamic are specified,
             // it means that this node is its own proxy.
-            // Discover the public IP of this node using those STUN servers.
+            // Discover the public IPs of this node using those STUN servers.
+            // A dual-stack node has a separate public IP for IPv4 and IPv6,
+            // so the servers are queried over both.
             // We do not require all stun servers to be available, but
             // we require the received responses to be consistent.
             (Some(node_addr), config::ValidatorProxies::Dynamic(stun_servers)) => {
                 // Query all the STUN servers in parallel.
-                let queries = stun_servers.iter().map(|addr| {
-                    let clock = clock.clone();
-                    let addr = addr.clone();
-                    self.spawn(async move {
-                        match stun::query(&clock, &addr).await {
-                            Ok(ip) => Some(ip),
-                            Err(err) => {
-                                tracing::warn!(target:"network", "STUN lookup failed for {addr}: {err}");
-                                None
+                let families = [stun::IpFamily::V4, stun::IpFamily::V6];
+                let queries: Vec<_> = families
+                    .iter()
+                    .flat_map(|family| stun_servers.iter().map(move |addr| (*family, addr)))
+                    .map(|(family, addr)| {
+                        let clock = clock.clone();
+                        let addr = addr.clone();
+                        let query = self.spawn(async move {
+                            match stun::query(&clock, &addr, family).await {
+                                Ok(ip) => Some(ip),
+                                Err(err) => {
+                                    tracing::warn!(target:"network", "STUN {family:?} lookup failed for {addr}: {err}");
+                                    None
+                                }
                             }
-                        }
+                        });
+                        (family, query)
                     })
-                });
-                let mut node_ips = vec![];
-                for q in queries {
-                    node_ips.extend(q.await.unwrap());
+                    .collect();
+                let mut responses = vec![];
+                for (family, q) in queries {
+                    responses.extend(q.await.unwrap().map(|ip| (family, ip)));
                 }
-                // Check that we have received non-zero responses and that they are consistent.
-                if node_ips.is_empty() {
-                    vec![]
-                } else if !node_ips.iter().all(|ip| ip == &node_ips[0]) {
-                    tracing::warn!(target:"network", "received inconsistent responses from the STUN servers");
-                    vec![]
-                } else {
-                    vec![PeerAddr {
+                let mut node_addrs = vec![];
+                for family in families {
+                    let node_ips: Vec<_> =
+                        responses.iter().filter(|(f, _)| f == &family).map(|(_, ip)| ip).collect();
+                    // Check that we have received non-zero responses and that they are consistent.
+                    if node_ips.is_empty() {
+                        continue;
+                    }
+                    if !node_ips.iter().all(|ip| ip == &node_ips[0]) {
+                        tracing::warn!(target:"network", "received inconsistent {family:?} responses from the STUN servers");
+                        continue;
+                    }
+                    node_addrs.push(std::net::SocketAddr::new(*node_ips[0], node_addr.port()));
+                }
+                match node_addrs.split_first() {
+                    None => vec![],
+                    Some((addr, extra_addrs)) => vec![PeerAddr {
                         peer_id: self.config.node_id(),
-                        addr: std::net::SocketAddr::new(node_ips[0], node_addr.port()),
-                    }]
+                        addr: *addr,
+                        extra_addrs: extra_addrs.to_vec(),
+                    }],
                 }
             }
         };
@@ -143,12 +180,21 @@ impl super::NetworkState {
         let my_proxies = match &vc.proxies {
             // In case of dynamic configuration, only the node itself can be its proxy,
             // so we look for a loop connection which would prove our node's address.
+            // The other discovered addresses are advertised as the fallback ones.
             config::ValidatorProxies::Dynamic(_) => match tier1.ready.get(&self.config.node_id()) {
                 Some(conn) => {
                     log_assert!(PeerType::Outbound == conn.peer_type);
                     log_assert!(conn.peer_info.addr.is_some());
                     match conn.peer_info.addr {
-                        Some(addr) => vec![PeerAddr { peer_id: self.config.node_id(), addr }],
+                        Some(addr) => vec![PeerAddr {
+                            peer_id: self.config.node_id(),
+                            addr,
+                            extra_addrs: proxies
+                                .iter()
+                                .flat_map(|p| p.addrs())
+                                .filter(|a| a != &addr)
+                                .collect(),
+                        }],
                         None => vec![],
                     }
                 }
@@ -172,11 +218,14 @@ impl super::NetworkState {
                         // pools, so that both endpoints can keep a connection
                         // to the IP that they prefer. This is a corner case which can happen
                         // only if 2 TIER1 validators are proxies for some other validator.
-                        Some(conn) if conn.peer_info.addr == Some(proxy.addr) => {
+                        //
+                        // Connecting to any of the addresses of the proxy confirms that
+                        // the proxy is reachable, so all of them are broadcasted.
+                        Some(conn) if proxy.addrs().any(|a| conn.peer_info.addr == Some(a)) => {
                             connected_proxies.push(proxy.clone());
                         }
                         Some(conn) => {
-                            tracing::info!(target:"network", "connected to {}, but got addr {:?}, while want {}",conn.peer_info.id,conn.peer_info.addr,proxy.addr)
+                            tracing::info!(target:"network", "connected to {}, but got addr {:?}, while want {}",conn.peer_info.id,conn.peer_info.addr,proxy)
                         }
                         _ => {}
                     }
@@ -189,7 +238,11 @@ impl super::NetworkState {
             clock,
             LocalAccountData {
                 signer: vc.signer.clone(),
-                data: Arc::new(AccountData { peer_id: self.config.node_id(), proxies: my_proxies }),
+                data: Arc::new(AccountData {
+                    peer_id: self.config.node_id(),
+                    proxies: my_proxies,
+                    quic: self.quic.is_some(),
+                }),
             },
         );
         // Early exit in case this node is not a TIER1 node any more.
@@ -219,8 +272,12 @@ impl super::NetworkState {
         // Construct indices on accounts_data.
         let mut accounts_by_proxy = HashMap::<_, Vec<_>>::new();
         let mut proxies_by_account = HashMap::<_, Vec<_>>::new();
+        let mut quic_accounts = HashSet::new();
         for d in accounts_data.data.values() {
             proxies_by_account.entry(&d.account_key).or_default().extend(d.proxies.iter());
+            if d.quic {
+                quic_accounts.insert(&d.account_key);
+            }
             for p in &d.proxies {
                 accounts_by_proxy.entry(&p.peer_id).or_default().push(&d.account_key);
             }
@@ -319,19 +376,10 @@ impl super::NetworkState {
                 let proxy = proxies.iter().choose(&mut rand::thread_rng());
                 if let Some(proxy) = proxy {
                     let proxy = (*proxy).clone();
-                    handles.push(async move {
-                        let stream = tcp::Stream::connect(
-                            &PeerInfo {
-                                id: proxy.peer_id,
-                                addr: Some(proxy.addr),
-                                account_id: None,
-                            },
-                            tcp::Tier::T1,
-                        )
-                        .await?;
-                        PeerActor::spawn_and_handshake(clock.clone(), stream, None, self.clone())
-                            .await
-                    });
+                    let quic = quic_accounts.contains(account_key);
+                    handles.push(
+                        async move { self.tier1_connect_to_proxy(clock, &proxy, quic).await },
+                    );
                 }
             }
             tracing::debug!(target:"network","{}: establishing {} new connections",self.config.node_id(),handles.len());
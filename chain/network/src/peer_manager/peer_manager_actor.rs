@@ -1,3 +1,4 @@
+use crate::admin::{AdminPeerError, AdminPeerRequest, AdminPeerResponse, BannedPeer, BannedPeers};
 use crate::client::{ClientSenderForNetwork, SetNetworkInfo};
 use crate::config;
 use crate::debug::{DebugStatus, GetDebugStatus};
@@ -7,9 +8,11 @@ use crate::network_protocol::{
     Disconnect, Edge, PeerIdOrHash, PeerMessage, Ping, Pong, RawRoutedMessage, RoutedMessageBody,
 };
 use crate::peer::peer_actor::PeerActor;
+use crate::peer::rate_limit::SendClass;
 use crate::peer_manager::connection;
 use crate::peer_manager::network_state::{NetworkState, WhitelistNode};
 use crate::peer_manager::peer_store;
+use crate::peer_manager::reputation;
 use crate::shards_manager::ShardsManagerRequestFromNetwork;
 use crate::stats::metrics;
 use crate::store;
@@ -17,7 +20,7 @@ use crate::tcp;
 use crate::types::{
     ConnectedPeerInfo, HighestHeightPeerInfo, KnownProducer, NetworkInfo, NetworkRequests,
     NetworkResponses, PeerInfo, PeerManagerMessageRequest, PeerManagerMessageResponse, PeerType,
-    SetChainInfo, SnapshotHostInfo,
+    ReasonForBan, SendClassInfo, SetChainInfo, SnapshotHostInfo,
 };
 use actix::fut::future::wrap_future;
 use actix::{Actor as _, AsyncContext as _};
@@ -81,6 +84,8 @@ const PREFER_PREVIOUSLY_CONNECTED_PEER: f64 = 0.6;
 
 /// How often to update the connections in storage.
 pub(crate) const UPDATE_CONNECTION_STORE_INTERVAL: time::Duration = time::Duration::minutes(1);
+/// How often to persist the reputation of the peers.
+pub(crate) const FLUSH_PEER_REPUTATION_INTERVAL: time::Duration = time::Duration::minutes(1);
 /// How often to poll the NetworkState for closed connections we'd like to re-establish.
 pub(crate) const POLL_CONNECTION_STORE_INTERVAL: time::Duration = time::Duration::minutes(1);
 
@@ -94,6 +99,8 @@ pub struct PeerManagerActor {
 
     /// State that is shared between multiple threads (including PeerActors).
     pub(crate) state: Arc<NetworkState>,
+    /// Used to persist the changes made via AdminPeerRequest.
+    store: store::Store,
 }
 
 /// TEST-ONLY
@@ -179,6 +186,17 @@ impl actix::Actor for PeerManagerActor {
             }
         }));
 
+        // Periodically persist the reputation of the peers.
+        let clock = self.clock.clone();
+        let state = self.state.clone();
+        ctx.spawn(wrap_future(async move {
+            let mut interval = time::Interval::new(clock.now(), FLUSH_PEER_REPUTATION_INTERVAL);
+            loop {
+                interval.tick(&clock).await;
+                state.reputation.flush(&clock);
+            }
+        }));
+
         // Periodically prints bandwidth stats for each peer.
         self.report_bandwidth_stats_trigger(ctx, REPORT_BANDWIDTH_STATS_TRIGGER_INTERVAL);
 
@@ -213,6 +231,12 @@ impl PeerManagerActor {
         let store = store::Store::from(store);
         let peer_store = peer_store::PeerStore::new(&clock, config.peer_store.clone())
             .context("PeerStore::new")?;
+        peer_store.restore_admin_overrides(
+            &clock,
+            store.get_peer_admin_overrides().context("get_peer_admin_overrides")?,
+        );
+        let reputation = reputation::Reputation::new(config.reputation.clone(), store.clone())
+            .context("Reputation::new")?;
         tracing::debug!(target: "network",
                len = peer_store.len(),
                boot_nodes = config.peer_store.boot_nodes.len(),
@@ -231,8 +255,9 @@ impl PeerManagerActor {
         let clock = clock;
         let state = Arc::new(NetworkState::new(
             &clock,
-            store,
+            store.clone(),
             peer_store,
+            reputation,
             config,
             genesis_id,
             client,
@@ -277,6 +302,40 @@ impl PeerManagerActor {
                         }
                     });
                 }
+                if let (Some(endpoint), Some(_)) = (&state.quic, &state.config.node_addr) {
+                    tracing::debug!(target: "network", "accepting TIER1 QUIC connections");
+                    arbiter.spawn({
+                        let clock = clock.clone();
+                        let state = state.clone();
+                        let endpoint = endpoint.clone();
+                        let arbiter = arbiter.clone();
+                        async move {
+                            while let Some(connecting) = endpoint.accept().await {
+                                // Complete the QUIC handshake in the background,
+                                // so that it doesn't block accepting other connections.
+                                arbiter.spawn({
+                                    let clock = clock.clone();
+                                    let state = state.clone();
+                                    async move {
+                                        let stream = match connecting.finish().await {
+                                            Ok(stream) => stream,
+                                            Err(err) => {
+                                                tracing::debug!(target: "network", ?err, "failed to accept QUIC connection");
+                                                return;
+                                            }
+                                        };
+                                        tracing::debug!(target: "network", from = ?stream.peer_addr, "got new QUIC connection");
+                                        if let Err(err) =
+                                            PeerActor::spawn(clock, stream, None, state)
+                                        {
+                                            tracing::info!(target:"network", ?err, "PeerActor::spawn()");
+                                        }
+                                    }
+                                });
+                            }
+                        }
+                    });
+                }
                 if let Some(cfg) = state.config.tier1.clone() {
                     // Connect to TIER1 proxies and broadcast the list those connections periodically.
                     arbiter.spawn({
@@ -340,6 +399,7 @@ impl PeerManagerActor {
             started_connect_attempts: false,
             state,
             clock,
+            store,
         }))
     }
 
@@ -462,8 +522,8 @@ impl PeerManagerActor {
     }
 
     /// Check if the number of connections (excluding whitelisted ones) exceeds ideal_connections_hi.
-    /// If so, constructs a safe set of peers and selects one random peer outside of that set
-    /// and sends signal to stop connection to it gracefully.
+    /// If so, constructs a safe set of peers and selects the peer with the lowest reputation
+    /// outside of that set and sends signal to stop connection to it gracefully.
     ///
     /// Safe set construction process:
     /// 1. Add all whitelisted peers to the safe set.
@@ -537,8 +597,14 @@ impl PeerManagerActor {
         }
 
         // Build valid candidate list to choose the peer to be removed. All peers outside the safe set.
-        let candidates = tier2.ready.values().filter(|p| !safe_set.contains(&p.peer_info.id));
-        if let Some(p) = candidates.choose(&mut rand::thread_rng()) {
+        // Out of those, the peer with the lowest reputation is removed (ties are broken randomly).
+        let mut candidates: Vec<_> =
+            tier2.ready.values().filter(|p| !safe_set.contains(&p.peer_info.id)).collect();
+        candidates.shuffle(&mut rand::thread_rng());
+        let score = |p: &&Arc<connection::Connection>| {
+            self.state.reputation.score(&self.clock, &p.peer_info.id)
+        };
+        if let Some(p) = candidates.into_iter().min_by(|a, b| score(a).total_cmp(&score(b))) {
             tracing::debug!(target: "network", id = ?p.peer_info.id,
                 tier2_len = tier2.ready.len(),
                 ideal_connections_hi = self.state.config.ideal_connections_hi,
@@ -584,8 +650,11 @@ impl PeerManagerActor {
                     || self.state.config.node_addr.as_ref().map(|a|**a) == peer_state.peer_info.addr
                     // Or to peers we are currently trying to connect to
                     || tier2.outbound_handshakes.contains(&peer_state.peer_info.id)
+                    // Or to peers with low reputation
+                    || self.state.reputation.verdict(&self.clock, &peer_state.peer_info.id) != reputation::Verdict::Ok
                 },
                 prefer_previously_connected_peer,
+                |peer_id| self.state.reputation.score(&self.clock, peer_id),
             ) {
                 // Start monitor_peers_attempts from start after we discover the first healthy peer
                 if !self.started_connect_attempts {
@@ -613,6 +682,8 @@ impl PeerManagerActor {
             }
         }
 
+        self.connect_to_admin_added_peers(ctx);
+
         // If there are too many active connections try to remove some connections
         self.maybe_stop_active_connection();
 
@@ -652,6 +723,101 @@ impl PeerManagerActor {
         }
     }
 
+    /// Spawns a single connection attempt to each peer added by the operator,
+    /// which we are neither connected nor connecting to.
+    fn connect_to_admin_added_peers(&self, ctx: &mut actix::Context<Self>) {
+        let tier2 = self.state.tier2.load();
+        for peer_info in self.state.peer_store.admin_overrides().added_peers {
+            if tier2.ready.contains_key(&peer_info.id)
+                || tier2.outbound_handshakes.contains(&peer_info.id)
+            {
+                continue;
+            }
+            ctx.spawn(wrap_future({
+                let state = self.state.clone();
+                let clock = self.clock.clone();
+                async move {
+                    state.reconnect(clock, peer_info, 1).await;
+                }
+            }));
+        }
+    }
+
+    fn handle_admin_peer_request(
+        &mut self,
+        msg: AdminPeerRequest,
+        ctx: &mut actix::Context<Self>,
+    ) -> Result<AdminPeerResponse, AdminPeerError> {
+        let peer_store = &self.state.peer_store;
+        let tier1 = self.state.tier1.load();
+        let tier2 = self.state.tier2.load();
+        match msg {
+            AdminPeerRequest::AddPeer(peer_info) => {
+                if peer_info.addr.is_none() {
+                    return Err(AdminPeerError::MissingAddr(peer_info.id));
+                }
+                if let Some(addr) = peer_info.addrs().find(|addr| peer_store.is_blacklisted(addr)) {
+                    return Err(AdminPeerError::Blacklisted(addr));
+                }
+                peer_store.admin_add_peer(&self.clock, peer_info);
+                self.connect_to_admin_added_peers(ctx);
+            }
+            AdminPeerRequest::RemovePeer(peer_id) => {
+                let conn = tier2.ready.get(&peer_id);
+                if let Some(conn) = conn {
+                    conn.stop(None);
+                }
+                if !peer_store.admin_remove_peer(&peer_id) && conn.is_none() {
+                    return Err(AdminPeerError::UnknownPeer(peer_id));
+                }
+            }
+            AdminPeerRequest::BanPeer(peer_id) => {
+                peer_store.admin_ban_peer(&self.clock, &peer_id);
+                // Banned peers are rejected on both tiers (see NetworkState::register),
+                // so the existing connections are closed on both tiers as well.
+                for conn in
+                    [tier1.ready.get(&peer_id), tier2.ready.get(&peer_id)].into_iter().flatten()
+                {
+                    conn.stop(Some(ReasonForBan::ManualBan));
+                }
+            }
+            AdminPeerRequest::UnbanPeer(peer_id) => {
+                if !peer_store.admin_unban_peer(&peer_id) {
+                    return Err(AdminPeerError::NotBanned(peer_id));
+                }
+            }
+            AdminPeerRequest::SetBlacklist(entries) => {
+                peer_store.admin_set_blacklist(entries);
+                for conn in tier1.ready.values().chain(tier2.ready.values()) {
+                    if conn.peer_info.addr.is_some_and(|addr| peer_store.is_blacklisted(&addr)) {
+                        conn.stop(Some(ReasonForBan::Blacklisted));
+                    }
+                }
+            }
+            AdminPeerRequest::ListBanned => {
+                let admin = peer_store.admin_overrides();
+                let peers = peer_store
+                    .banned_peers()
+                    .into_iter()
+                    .map(|(peer_id, ban)| BannedPeer {
+                        permanent: admin.banned_peers.contains(&peer_id),
+                        peer_id,
+                        reason: ban.map_or(ReasonForBan::ManualBan, |(reason, _)| reason),
+                        banned_at: ban.map(|(_, time)| time),
+                    })
+                    .collect();
+                return Ok(AdminPeerResponse::Banned(BannedPeers {
+                    peers,
+                    blacklist: peer_store.blacklist(),
+                }));
+            }
+        }
+        self.store
+            .set_peer_admin_overrides(&peer_store.admin_overrides())
+            .map_err(|err| AdminPeerError::Store(err.to_string()))?;
+        Ok(AdminPeerResponse::Ok)
+    }
+
     pub(crate) fn get_network_info(&self) -> NetworkInfo {
         let tier1 = self.state.tier1.load();
         let tier2 = self.state.tier2.load();
@@ -669,6 +835,18 @@ impl PeerManagerActor {
                 Some(e) => e.nonce(),
                 None => 0,
             },
+            send_classes: SendClass::ALL
+                .into_iter()
+                .map(|class| {
+                    let stats = &cp.stats.sent_by_class[class as usize];
+                    SendClassInfo {
+                        class: class.into(),
+                        sent_bytes: stats.sent_bytes.load(Ordering::Relaxed),
+                        delayed_messages: stats.delayed_messages.load(Ordering::Relaxed),
+                        dropped_messages: stats.dropped_messages.load(Ordering::Relaxed),
+                    }
+                })
+                .collect(),
         };
         NetworkInfo {
             connected_peers: tier2.ready.values().map(connected_peer).collect(),
@@ -773,6 +951,17 @@ impl PeerManagerActor {
                     NetworkResponses::RouteNotFound
                 }
             }
+            NetworkRequests::EpochSyncRequest { epoch_id, peer_id } => {
+                if self
+                    .state
+                    .tier2
+                    .send_message(peer_id, Arc::new(PeerMessage::EpochSyncRequest(epoch_id)))
+                {
+                    NetworkResponses::NoResponse
+                } else {
+                    NetworkResponses::RouteNotFound
+                }
+            }
             NetworkRequests::StateRequestHeader { shard_id, sync_hash, peer_id } => {
                 if self.state.tier2.send_message(
                     peer_id,
@@ -984,6 +1173,26 @@ impl PeerManagerActor {
                 );
                 NetworkResponses::NoResponse
             }
+            NetworkRequests::PartialEncodedStateWitness(parts) => {
+                for (chunk_validator, part) in parts {
+                    self.state.send_message_to_account(
+                        &self.clock,
+                        &chunk_validator,
+                        RoutedMessageBody::PartialEncodedStateWitness(part),
+                    );
+                }
+                NetworkResponses::NoResponse
+            }
+            NetworkRequests::PartialEncodedStateWitnessForward(chunk_validators, part) => {
+                for chunk_validator in chunk_validators {
+                    self.state.send_message_to_account(
+                        &self.clock,
+                        &chunk_validator,
+                        RoutedMessageBody::PartialEncodedStateWitnessForward(part.clone()),
+                    );
+                }
+                NetworkResponses::NoResponse
+            }
         }
     }
 
@@ -1065,6 +1274,15 @@ impl actix::Handler<WithSpanContext<PeerManagerMessageRequest>> for PeerManagerA
     }
 }
 
+impl actix::Handler<AdminPeerRequest> for PeerManagerActor {
+    type Result = Result<AdminPeerResponse, AdminPeerError>;
+    #[perf]
+    fn handle(&mut self, msg: AdminPeerRequest, ctx: &mut actix::Context<Self>) -> Self::Result {
+        tracing::info!(target: "network", ?msg, "Admin peer request");
+        self.handle_admin_peer_request(msg, ctx)
+    }
+}
+
 impl actix::Handler<GetDebugStatus> for PeerManagerActor {
     type Result = DebugStatus;
     #[perf]
@@ -1100,7 +1318,10 @@ impl actix::Handler<GetDebugStatus> for PeerManagerActor {
                         -a.last_seen,
                     )
                 });
-                DebugStatus::PeerStore(PeerStoreView { peer_states: peer_states_view })
+                let mut blacklist: Vec<_> =
+                    self.state.peer_store.blacklist().iter().map(|e| e.to_string()).collect();
+                blacklist.sort();
+                DebugStatus::PeerStore(PeerStoreView { peer_states: peer_states_view, blacklist })
             }
             GetDebugStatus::Graph => DebugStatus::Graph(NetworkGraphView {
                 edges: self
@@ -1,4 +1,5 @@
 use crate::blacklist;
+use crate::config::MAX_ADDRS_PER_PEER;
 use crate::network_protocol::PeerInfo;
 use crate::types::{KnownPeerState, KnownPeerStatus, ReasonForBan};
 use anyhow::bail;
@@ -8,11 +9,15 @@ use lru::LruCache;
 use near_async::time;
 use near_primitives::network::PeerId;
 use parking_lot::Mutex;
-use rand::seq::IteratorRandom;
+use rand::seq::{IteratorRandom, SliceRandom};
 use rand::thread_rng;
 use std::net::SocketAddr;
 use std::ops::Not;
 
+/// Number of randomly chosen candidates for an outbound connection,
+/// out of which the one with the best reputation is selected.
+const OUTBOUND_CANDIDATES: usize = 3;
+
 #[cfg(test)]
 mod testonly;
 #[cfg(test)]
@@ -26,6 +31,26 @@ mod tests;
 ///
 /// Contents of the PeerStore are not persisted to the database. Upon starting a node,
 /// the PeerStore is initialized from the boot nodes in its config.
+/// The only exception are the changes made by the node operator (see AdminOverrides).
+///
+/// A peer may have multiple addresses (see PeerInfo::extra_addrs). Only the most preferred one
+/// is verified and indexed; the other ones are kept as fallbacks for the outbound connections
+/// and are not advertised to other peers.
+
+/// Changes to the peer management policy, made by the node operator at runtime
+/// via the admin RPC. Unlike the rest of the PeerStore, they are persisted to the
+/// database by the PeerManagerActor, so that they survive node restarts.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct AdminOverrides {
+    /// Peers added by the operator. They are treated as whitelisted nodes,
+    /// and the node will keep trying to connect to them.
+    pub added_peers: Vec<PeerInfo>,
+    /// Peers banned by the operator. Unlike the regular bans, these bans
+    /// don't expire after `Config::ban_window`.
+    pub banned_peers: Vec<PeerId>,
+    /// Blacklist entries applied in addition to `Config::blacklist`.
+    pub blacklist: Vec<blacklist::Entry>,
+}
 
 /// Level of trust we have about a new (PeerId, Addr) pair.
 #[derive(Eq, PartialEq, Debug, Clone, Copy)]
@@ -84,6 +109,9 @@ struct Inner {
     // It can happens that some peers don't have known address, so
     // they will not be present in this list, otherwise they will be present.
     addr_peers: HashMap<SocketAddr, VerifiedPeer>,
+    admin: AdminOverrides,
+    // Blacklist built from admin.blacklist.
+    admin_blacklist: blacklist::Blacklist,
 }
 
 impl Inner {
@@ -99,6 +127,28 @@ impl Inner {
 
     /// Adds a peer into the store with given trust level.
     fn add_peer(&mut self, clock: &time::Clock, peer_info: PeerInfo, trust_level: TrustLevel) {
+        let peer_id = peer_info.id.clone();
+        self.add_peer_inner(clock, peer_info, trust_level);
+        // Peers banned by the operator stay banned, even if they have been evicted
+        // from the store and we learn about them again.
+        if self.admin.banned_peers.contains(&peer_id) {
+            if let Some(peer_state) = self.peer_states.peek_mut(&peer_id) {
+                if !peer_state.status.is_banned() {
+                    peer_state.status =
+                        KnownPeerStatus::Banned(ReasonForBan::ManualBan, clock.now_utc());
+                }
+            }
+        }
+    }
+
+    fn add_peer_inner(
+        &mut self,
+        clock: &time::Clock,
+        mut peer_info: PeerInfo,
+        trust_level: TrustLevel,
+    ) {
+        peer_info.extra_addrs.retain(|addr| !self.is_blacklisted(*addr));
+        peer_info.extra_addrs.truncate(MAX_ADDRS_PER_PEER - 1);
         if let Some(peer_addr) = peer_info.addr {
             match trust_level {
                 TrustLevel::Signed => {
@@ -147,6 +197,15 @@ impl Inner {
         }
     }
 
+    fn is_blacklisted(&self, addr: SocketAddr) -> bool {
+        self.config.blacklist.contains(addr) || self.admin_blacklist.contains(addr)
+    }
+
+    fn is_banned(&self, peer_id: &PeerId) -> bool {
+        self.admin.banned_peers.contains(peer_id)
+            || self.peer_states.peek(peer_id).is_some_and(|s| s.status.is_banned())
+    }
+
     fn peer_unban(&mut self, peer_id: &PeerId) -> anyhow::Result<()> {
         if let Some(peer_state) = self.peer_states.get_mut(peer_id) {
             peer_state.status = KnownPeerStatus::NotConnected;
@@ -208,11 +267,14 @@ impl Inner {
         // Update or insert peer_id addr
         if let Some(peer_state) = self.peer_states.peek_mut(&peer_info.id) {
             peer_state.peer_info.addr = Some(peer_addr);
+            // Keep the previously known fallback addresses, unless new ones are provided.
+            if !peer_info.extra_addrs.is_empty() {
+                peer_state.peer_info.extra_addrs = peer_info.extra_addrs;
+            }
         } else {
             let now = clock.now_utc();
-            if let Some((_, popped_peer_state)) = self
-                .peer_states
-                .push(peer_info.id.clone(), KnownPeerState::new(peer_info.clone(), now))
+            if let Some((_, popped_peer_state)) =
+                self.peer_states.push(peer_info.id.clone(), KnownPeerState::new(peer_info, now))
             {
                 // If a peer was evicted from peer_states due to the bounded cache size
                 // and it has an address, remove the corresponding entry from addr_peers
@@ -241,7 +303,9 @@ impl Inner {
         let mut to_unban = vec![];
         for (peer_id, peer_state) in &self.peer_states {
             if let KnownPeerStatus::Banned(_, ban_time) = peer_state.status {
-                if now < ban_time + self.config.ban_window {
+                if now < ban_time + self.config.ban_window
+                    || self.admin.banned_peers.contains(peer_id)
+                {
                     continue;
                 }
                 tracing::info!(target: "network", unbanned = ?peer_id, ?ban_time, "unbanning a peer");
@@ -284,6 +348,15 @@ impl Inner {
     }
 }
 
+/// Returns the candidate with the highest score. Ties are broken randomly.
+fn best_candidate(
+    mut candidates: Vec<PeerInfo>,
+    score: &impl Fn(&PeerId) -> f64,
+) -> Option<PeerInfo> {
+    candidates.shuffle(&mut thread_rng());
+    candidates.into_iter().max_by(|a, b| score(&a.id).total_cmp(&score(&b.id)))
+}
+
 pub(crate) struct PeerStore(Mutex<Inner>);
 
 impl PeerStore {
@@ -334,13 +407,19 @@ impl PeerStore {
             }
         }
 
-        let inner =
-            Inner { config, boot_nodes, peer_states: peerid_2_state, addr_peers: addr_2_peer };
+        let inner = Inner {
+            config,
+            boot_nodes,
+            peer_states: peerid_2_state,
+            addr_peers: addr_2_peer,
+            admin: AdminOverrides::default(),
+            admin_blacklist: blacklist::Blacklist::default(),
+        };
         Ok(PeerStore(Mutex::new(inner)))
     }
 
     pub fn is_blacklisted(&self, addr: &SocketAddr) -> bool {
-        self.0.lock().config.blacklist.contains(*addr)
+        self.0.lock().is_blacklisted(*addr)
     }
 
     pub fn len(&self) -> usize {
@@ -348,11 +427,14 @@ impl PeerStore {
     }
 
     pub fn is_banned(&self, peer_id: &PeerId) -> bool {
-        self.0.lock().peer_states.get(peer_id).is_some_and(|s| s.status.is_banned())
+        self.0.lock().is_banned(peer_id)
     }
 
     pub fn count_banned(&self) -> usize {
-        self.0.lock().peer_states.iter().filter(|(_, st)| st.status.is_banned()).count()
+        let inner = self.0.lock();
+        let not_stored =
+            inner.admin.banned_peers.iter().filter(|id| !inner.peer_states.contains(*id)).count();
+        inner.peer_states.iter().filter(|(_, st)| st.status.is_banned()).count() + not_stored
     }
 
     pub fn update(&self, clock: &time::Clock) {
@@ -428,50 +510,63 @@ impl PeerStore {
 
     /// Return unconnected or peers with unknown status that we can try to connect to.
     /// Peers with unknown addresses are filtered out.
+    /// Out of a few randomly chosen candidates, the one with the highest `score`
+    /// (reputation) is returned.
     pub fn unconnected_peer(
         &self,
         ignore_fn: impl Fn(&KnownPeerState) -> bool,
         prefer_previously_connected_peer: bool,
+        score: impl Fn(&PeerId) -> f64,
     ) -> Option<PeerInfo> {
         let inner = self.0.lock();
         if prefer_previously_connected_peer {
-            let preferred_peer = inner.find_peers(
-                |p| {
-                    (p.status == KnownPeerStatus::NotConnected)
-                        && !ignore_fn(p)
-                        && p.peer_info.addr.is_some()
-                        // if we're connecting only to the boot nodes - filter out the nodes that are not bootnodes.
-                        && (!inner.config.connect_only_to_boot_nodes || inner.boot_nodes.contains(&p.peer_info.id))
-                },
-                1,
-            )
-            .get(0)
-            .cloned();
+            let preferred_peer = best_candidate(
+                inner.find_peers(
+                    |p| {
+                        (p.status == KnownPeerStatus::NotConnected)
+                            && !ignore_fn(p)
+                            && p.peer_info.addr.is_some()
+                            // if we're connecting only to the boot nodes - filter out the nodes that are not bootnodes.
+                            && (!inner.config.connect_only_to_boot_nodes || inner.boot_nodes.contains(&p.peer_info.id))
+                    },
+                    OUTBOUND_CANDIDATES,
+                ),
+                &score,
+            );
             // If we found a preferred peer - return it.
             if preferred_peer.is_some() {
                 return preferred_peer;
             };
             // otherwise, pick a peer from the wider pool below.
         }
-        inner.find_peers(
-            |p| {
-                (p.status == KnownPeerStatus::NotConnected || p.status == KnownPeerStatus::Unknown)
-                    && !ignore_fn(p)
-                    && p.peer_info.addr.is_some()
-                    // If we're connecting only to the boot nodes - filter out the nodes that are not boot nodes.
-                    && (!inner.config.connect_only_to_boot_nodes || inner.boot_nodes.contains(&p.peer_info.id))
-            },
-            1,
+        best_candidate(
+            inner.find_peers(
+                |p| {
+                    (p.status == KnownPeerStatus::NotConnected
+                        || p.status == KnownPeerStatus::Unknown)
+                        && !ignore_fn(p)
+                        && p.peer_info.addr.is_some()
+                        // If we're connecting only to the boot nodes - filter out the nodes that are not boot nodes.
+                        && (!inner.config.connect_only_to_boot_nodes || inner.boot_nodes.contains(&p.peer_info.id))
+                },
+                OUTBOUND_CANDIDATES,
+            ),
+            &score,
         )
-        .get(0)
-        .cloned()
     }
 
     /// Return healthy known peers up to given amount.
+    ///
+    /// The fallback addresses are not verified, so they are used only locally
+    /// and are not advertised: once this node connects to a peer via a fallback
+    /// address, that address becomes the primary one and gets advertised.
     pub fn healthy_peers(&self, max_count: usize) -> Vec<PeerInfo> {
         self.0
             .lock()
             .find_peers(|p| matches!(p.status, KnownPeerStatus::Banned(_, _)).not(), max_count)
+            .into_iter()
+            .map(|peer_info| PeerInfo { extra_addrs: vec![], ..peer_info })
+            .collect()
     }
 
     /// Adds peers we’ve learned about from other peers.
@@ -487,8 +582,7 @@ impl PeerStore {
         let mut blacklisted: usize = 0;
         for peer_info in peers {
             total += 1;
-            let is_blacklisted =
-                peer_info.addr.is_some_and(|addr| inner.config.blacklist.contains(addr));
+            let is_blacklisted = peer_info.addr.is_some_and(|addr| inner.is_blacklisted(addr));
             if is_blacklisted {
                 blacklisted += 1;
             } else {
@@ -515,4 +609,109 @@ impl PeerStore {
     pub fn load(&self) -> HashMap<PeerId, KnownPeerState> {
         self.0.lock().peer_states.iter().map(|(k, v)| (k.clone(), v.clone())).collect()
     }
+
+    /// Returns the blacklist entries, both from the config and the admin overrides.
+    pub fn blacklist(&self) -> Vec<blacklist::Entry> {
+        let inner = self.0.lock();
+        inner.config.blacklist.iter().chain(inner.admin_blacklist.iter()).copied().collect()
+    }
+
+    /// Returns the banned peers, together with the reason and time of the ban, if known.
+    /// Peers banned by the operator are reported even if they are missing in the store.
+    pub fn banned_peers(&self) -> Vec<(PeerId, Option<(ReasonForBan, time::Utc)>)> {
+        let inner = self.0.lock();
+        let mut banned: Vec<_> = inner
+            .peer_states
+            .iter()
+            .filter_map(|(peer_id, state)| match state.status {
+                KnownPeerStatus::Banned(reason, time) => {
+                    Some((peer_id.clone(), Some((reason, time))))
+                }
+                _ => None,
+            })
+            .collect();
+        for peer_id in &inner.admin.banned_peers {
+            if !inner.peer_states.contains(peer_id) {
+                banned.push((peer_id.clone(), None));
+            }
+        }
+        banned
+    }
+
+    pub fn admin_overrides(&self) -> AdminOverrides {
+        self.0.lock().admin.clone()
+    }
+
+    /// Applies the admin overrides loaded from the database.
+    pub fn restore_admin_overrides(&self, clock: &time::Clock, admin: AdminOverrides) {
+        for peer_info in &admin.added_peers {
+            self.admin_add_peer(clock, peer_info.clone());
+        }
+        for peer_id in &admin.banned_peers {
+            self.admin_ban_peer(clock, peer_id);
+        }
+        self.admin_set_blacklist(admin.blacklist);
+    }
+
+    /// Adds a peer on behalf of the operator. The peer is trusted as if we
+    /// connected to it directly, and is lifted from the operator's ban, if any.
+    pub fn admin_add_peer(&self, clock: &time::Clock, peer_info: PeerInfo) {
+        let mut inner = self.0.lock();
+        if inner.admin.banned_peers.contains(&peer_info.id) {
+            inner.admin.banned_peers.retain(|id| id != &peer_info.id);
+            if inner.peer_states.peek(&peer_info.id).is_some_and(|s| s.status.is_banned()) {
+                inner.peer_unban(&peer_info.id).unwrap();
+            }
+        }
+        inner.admin.added_peers.retain(|p| p.id != peer_info.id);
+        inner.admin.added_peers.push(peer_info.clone());
+        inner.add_peer(clock, peer_info, TrustLevel::Direct);
+    }
+
+    /// Removes the peer from the store and from the peers added by the operator.
+    /// Returns false if the peer was unknown.
+    pub fn admin_remove_peer(&self, peer_id: &PeerId) -> bool {
+        let mut inner = self.0.lock();
+        let added = inner.admin.added_peers.len();
+        inner.admin.added_peers.retain(|p| &p.id != peer_id);
+        let known = inner.peer_states.contains(peer_id);
+        inner.delete_peers(std::slice::from_ref(peer_id));
+        known || added != inner.admin.added_peers.len()
+    }
+
+    /// Bans the peer until it is explicitly unbanned by the operator.
+    pub fn admin_ban_peer(&self, clock: &time::Clock, peer_id: &PeerId) {
+        let mut inner = self.0.lock();
+        inner.admin.added_peers.retain(|p| &p.id != peer_id);
+        if !inner.admin.banned_peers.contains(peer_id) {
+            inner.admin.banned_peers.push(peer_id.clone());
+        }
+        if let Some(peer_state) = inner.peer_states.get_mut(peer_id) {
+            peer_state.status = KnownPeerStatus::Banned(ReasonForBan::ManualBan, clock.now_utc());
+        }
+    }
+
+    /// Lifts both the operator's ban and the regular ban of the peer.
+    /// Returns false if the peer was not banned.
+    pub fn admin_unban_peer(&self, peer_id: &PeerId) -> bool {
+        let mut inner = self.0.lock();
+        let was_banned = inner.is_banned(peer_id);
+        inner.admin.banned_peers.retain(|id| id != peer_id);
+        if inner.peer_states.peek(peer_id).is_some_and(|s| s.status.is_banned()) {
+            inner.peer_unban(peer_id).unwrap();
+        }
+        was_banned
+    }
+
+    /// Replaces the blacklist entries set by the operator.
+    pub fn admin_set_blacklist(&self, entries: Vec<blacklist::Entry>) {
+        let mut inner = self.0.lock();
+        inner.admin_blacklist = entries.iter().copied().collect();
+        inner.admin.blacklist = entries;
+    }
+
+    /// Checks whether the peer has been added by the operator.
+    pub fn is_admin_added(&self, peer_id: &PeerId) -> bool {
+        self.0.lock().admin.added_peers.iter().any(|p| &p.id == peer_id)
+    }
 }
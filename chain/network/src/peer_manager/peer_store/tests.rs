@@ -14,7 +14,7 @@ fn get_addr(port: u16) -> SocketAddr {
 }
 
 fn get_peer_info(peer_id: PeerId, addr: Option<SocketAddr>) -> PeerInfo {
-    PeerInfo { id: peer_id, addr, account_id: None }
+    PeerInfo { id: peer_id, addr, account_id: None, extra_addrs: vec![] }
 }
 
 fn gen_peer_info(port: u16) -> PeerInfo {
@@ -22,6 +22,7 @@ fn gen_peer_info(port: u16) -> PeerInfo {
         id: PeerId::new(SecretKey::from_random(KeyType::ED25519).public_key()),
         addr: Some(get_addr(port)),
         account_id: None,
+        extra_addrs: vec![],
     }
 }
 
@@ -66,8 +67,23 @@ fn test_unconnected_peer() {
         PeerStore::new(&clock.clock(), make_config(&boot_nodes, Blacklist::default(), false))
             .unwrap();
 
-    assert!(peer_store.unconnected_peer(|_| false, false).is_some());
-    assert!(peer_store.unconnected_peer(|_| true, false).is_none());
+    assert!(peer_store.unconnected_peer(|_| false, false, |_| 0.).is_some());
+    assert!(peer_store.unconnected_peer(|_| true, false, |_| 0.).is_none());
+}
+
+#[test]
+fn test_unconnected_peer_prefers_reputation() {
+    let clock = time::FakeClock::default();
+    let boot_nodes: Vec<_> = (0..OUTBOUND_CANDIDATES as u16).map(gen_peer_info).collect();
+    let best = boot_nodes[1].id.clone();
+
+    let peer_store =
+        PeerStore::new(&clock.clock(), make_config(&boot_nodes, Blacklist::default(), false))
+            .unwrap();
+    let score = |peer_id: &PeerId| if peer_id == &best { 1. } else { -1. };
+    for _ in 0..10 {
+        assert_eq!(peer_store.unconnected_peer(|_| false, false, score).unwrap().id, best);
+    }
 }
 
 #[test]
@@ -120,7 +136,7 @@ fn test_unknown_vs_not_connected() {
     // if we prefer 'previously connected' peers - we should keep picking 'b'.
     assert_eq!(
         (0..10)
-            .map(|_| peer_store.unconnected_peer(|_| false, true).unwrap().id)
+            .map(|_| peer_store.unconnected_peer(|_| false, true, |_| 0.).unwrap().id)
             .collect::<HashSet<PeerId>>(),
         [peer_info_b.id.clone()].into_iter().collect::<HashSet<_>>()
     );
@@ -128,7 +144,7 @@ fn test_unknown_vs_not_connected() {
     // if we don't care, we should pick either 'b' or 'boot'.
     assert_eq!(
         (0..100)
-            .map(|_| peer_store.unconnected_peer(|_| false, false).unwrap().id)
+            .map(|_| peer_store.unconnected_peer(|_| false, false, |_| 0.).unwrap().id)
             .collect::<HashSet<PeerId>>(),
         [peer_info_b.id.clone(), peer_info_boot_node.id.clone()]
             .into_iter()
@@ -164,7 +180,10 @@ fn test_unconnected_peer_only_boot_nodes() {
                 .unwrap();
         peer_store.add_direct_peer(&clock.clock(), peer_in_store.clone());
         peer_store.peer_connected(&clock.clock(), &peer_info_a);
-        assert_eq!(peer_store.unconnected_peer(|_| false, false), Some(peer_in_store.clone()));
+        assert_eq!(
+            peer_store.unconnected_peer(|_| false, false, |_| 0.),
+            Some(peer_in_store.clone())
+        );
     }
 
     // 1 boot node (peer_info_a) that we're already connected to.
@@ -176,7 +195,7 @@ fn test_unconnected_peer_only_boot_nodes() {
                 .unwrap();
         peer_store.add_direct_peer(&clock.clock(), peer_in_store);
         peer_store.peer_connected(&clock.clock(), &peer_info_a);
-        assert_eq!(peer_store.unconnected_peer(|_| false, false), None);
+        assert_eq!(peer_store.unconnected_peer(|_| false, false, |_| 0.), None);
     }
 
     // 1 boot node (peer_info_a) is in the store.
@@ -188,7 +207,10 @@ fn test_unconnected_peer_only_boot_nodes() {
         )
         .unwrap();
         peer_store.add_direct_peer(&clock.clock(), peer_info_a.clone());
-        assert_eq!(peer_store.unconnected_peer(|_| false, false), Some(peer_info_a.clone()));
+        assert_eq!(
+            peer_store.unconnected_peer(|_| false, false, |_| 0.),
+            Some(peer_info_a.clone())
+        );
     }
 }
 
@@ -385,6 +407,90 @@ fn check_ignore_blacklisted_peers() {
     assert_peers(&peer_store, &[&ids[0], &ids[2]]);
 }
 
+#[test]
+fn keep_extra_addrs() {
+    let clock = time::FakeClock::default();
+    let blacklist: blacklist::Blacklist =
+        ["127.0.0.1:3"].iter().map(|e| e.parse().unwrap()).collect();
+    let peer_store = PeerStore::new(&clock.clock(), make_config(&[], blacklist, false)).unwrap();
+
+    let id = get_peer_id("node".to_string());
+    let v6_addr = SocketAddr::new(std::net::Ipv6Addr::LOCALHOST.into(), 2);
+    let mut peer_info = get_peer_info(id.clone(), Some(get_addr(1)));
+    peer_info.extra_addrs = vec![v6_addr, get_addr(3)];
+    peer_store.add_indirect_peers(&clock.clock(), [peer_info].into_iter());
+
+    // Blacklisted fallback address is dropped.
+    let state = peer_store.get_peer_state(&id).unwrap();
+    assert_eq!(vec![get_addr(1), v6_addr], state.peer_info.addrs().collect::<Vec<_>>());
+    // Fallback addresses are not verified, so they are not advertised.
+    assert_eq!(vec![get_peer_info(id.clone(), Some(get_addr(1)))], peer_store.healthy_peers(1));
+
+    // Connection without the fallback addresses known (for example an inbound
+    // connection from a node which doesn't advertise them) doesn't remove them.
+    peer_store.peer_connected(&clock.clock(), &get_peer_info(id.clone(), Some(get_addr(4))));
+    let state = peer_store.get_peer_state(&id).unwrap();
+    assert_eq!(vec![get_addr(4), v6_addr], state.peer_info.addrs().collect::<Vec<_>>());
+    assert!(check_integrity(&peer_store));
+
+    // Fallback addresses are included in the unconnected peer to try.
+    peer_store.peer_disconnected(&clock.clock(), &id).unwrap();
+    let peer_info = peer_store.unconnected_peer(|_| false, false, |_| 0.).unwrap();
+    assert_eq!(vec![v6_addr], peer_info.extra_addrs);
+}
+
+#[test]
+fn admin_overrides() {
+    let clock = time::FakeClock::default();
+    let peer_store =
+        PeerStore::new(&clock.clock(), make_config(&[], Blacklist::default(), false)).unwrap();
+    let known = gen_peer_info(1);
+    let unknown = get_peer_id("unknown".to_string());
+    peer_store.add_indirect_peers(&clock.clock(), [known.clone()].into_iter());
+
+    // Bans by the operator don't expire and cover peers missing in the store.
+    peer_store.admin_ban_peer(&clock.clock(), &known.id);
+    peer_store.admin_ban_peer(&clock.clock(), &unknown);
+    clock.advance(time::Duration::seconds(10));
+    peer_store.update(&clock.clock());
+    assert!(peer_store.is_banned(&known.id));
+    assert!(peer_store.is_banned(&unknown));
+    assert_eq!(2, peer_store.count_banned());
+
+    // A banned peer learned about again stays banned.
+    peer_store
+        .add_indirect_peers(&clock.clock(), [get_peer_info(unknown.clone(), None)].into_iter());
+    assert!(peer_store.is_banned(&unknown));
+    assert!(peer_store.admin_unban_peer(&unknown));
+    assert!(!peer_store.admin_unban_peer(&unknown));
+    assert!(!peer_store.is_banned(&unknown));
+
+    // Blacklist entries set by the operator are applied on top of the config.
+    assert!(!peer_store.is_blacklisted(&get_addr(2)));
+    peer_store.admin_set_blacklist(vec!["127.0.0.1:2".parse().unwrap()]);
+    assert!(peer_store.is_blacklisted(&get_addr(2)));
+
+    // Added peers are lifted from the ban.
+    peer_store.admin_add_peer(&clock.clock(), known.clone());
+    assert!(!peer_store.is_banned(&known.id));
+    assert!(peer_store.is_admin_added(&known.id));
+
+    // Overrides survive a restart.
+    let admin = peer_store.admin_overrides();
+    let mut store = crate::store::Store::from(near_store::db::TestDB::new());
+    store.set_peer_admin_overrides(&admin).unwrap();
+    let restarted =
+        PeerStore::new(&clock.clock(), make_config(&[], Blacklist::default(), false)).unwrap();
+    restarted.restore_admin_overrides(&clock.clock(), store.get_peer_admin_overrides().unwrap());
+    assert_eq!(admin, restarted.admin_overrides());
+    assert!(restarted.is_admin_added(&known.id));
+    assert!(restarted.is_blacklisted(&get_addr(2)));
+
+    assert!(restarted.admin_remove_peer(&known.id));
+    assert!(!restarted.is_admin_added(&known.id));
+    assert!(restarted.get_peer_state(&known.id).is_none());
+}
+
 #[track_caller]
 fn assert_peers_in_cache(
     peer_store: &PeerStore,
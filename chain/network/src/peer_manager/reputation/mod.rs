@@ -0,0 +1,194 @@
+//! Reputation of the network peers.
+//!
+//! Every peer has a score, which is increased by useful behavior (timely responses to our
+//! requests) and decreased by harmful behavior (slow or missing responses, invalid data).
+//! The score, including the penalties, decays exponentially towards 0, so that old events
+//! matter less than the recent ones and a peer can always recover its reputation.
+//! Requests which are not responded to at all are penalized less than slow responses: the
+//! peer might simply not have the requested data, so only a peer which keeps ignoring our
+//! requests gets disconnected, and a transient network issue is forgotten soon.
+//! The score is used to:
+//! * prefer peers with higher reputation when establishing outbound connections and when
+//!   choosing which connections to keep,
+//! * disconnect peers whose reputation dropped below `Config::disconnect_threshold`,
+//! * ban peers whose reputation dropped below `Config::ban_threshold`.
+//!
+//! Scores are persisted in the DB periodically, so that they survive node restarts.
+use crate::store;
+use crate::types::ReasonForBan;
+use near_async::time;
+use near_primitives::network::PeerId;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+
+#[cfg(test)]
+mod tests;
+
+/// Scores are clamped to [-MAX_SCORE,MAX_SCORE], so that a peer cannot accumulate
+/// an arbitrarily large credit (or debt).
+const MAX_SCORE: f64 = 100.;
+/// Scores with smaller absolute value are forgotten when persisting.
+const NEGLIGIBLE_SCORE: f64 = 0.1;
+/// Maximal number of scores persisted in the DB. The scores with the largest absolute
+/// values are kept.
+pub(crate) const MAX_STORED_SCORES: usize = 1000;
+
+/// Events affecting the reputation of a peer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::AsRefStr)]
+pub(crate) enum Event {
+    /// Peer responded to our request in time.
+    UsefulResponse,
+    /// Peer responded to our request, but it took longer than expected.
+    SlowResponse,
+    /// Peer didn't respond to our request in time.
+    Timeout,
+    /// Peer was banned for sending invalid data (for example invalid block headers).
+    Misbehavior(ReasonForBan),
+}
+
+impl Event {
+    fn score_delta(&self) -> f64 {
+        match self {
+            Event::UsefulResponse => 1.,
+            Event::SlowResponse => -1.,
+            Event::Timeout => -0.5,
+            Event::Misbehavior(_) => -MAX_SCORE,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Config {
+    /// Time after which the score of a peer decays to half of its value.
+    pub half_life: time::Duration,
+    /// Peers with a score below this threshold are disconnected.
+    pub disconnect_threshold: f64,
+    /// Peers with a score below this threshold are banned.
+    pub ban_threshold: f64,
+}
+
+impl Config {
+    pub fn validate(&self) -> anyhow::Result<()> {
+        if self.half_life <= time::Duration::ZERO {
+            anyhow::bail!("half_life has to be >0");
+        }
+        if !(self.ban_threshold <= self.disconnect_threshold && self.disconnect_threshold < 0.) {
+            anyhow::bail!("expected ban_threshold <= disconnect_threshold < 0");
+        }
+        Ok(())
+    }
+}
+
+/// Action to take on a peer, based on its reputation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Verdict {
+    Ok,
+    Disconnect,
+    Ban,
+}
+
+/// Score of a peer as of `updated` time.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Score {
+    pub value: f64,
+    pub updated: time::Utc,
+}
+
+impl Score {
+    /// Value of the score at `now`, after applying the decay.
+    fn at(&self, half_life: time::Duration, now: time::Utc) -> f64 {
+        let elapsed = (now - self.updated).max(time::Duration::ZERO);
+        self.value * 0.5f64.powf(elapsed / half_life)
+    }
+}
+
+struct Inner {
+    config: Config,
+    store: store::Store,
+    scores: HashMap<PeerId, Score>,
+}
+
+impl Inner {
+    fn score(&self, now: time::Utc, peer_id: &PeerId) -> f64 {
+        self.scores.get(peer_id).map_or(0., |s| s.at(self.config.half_life, now))
+    }
+
+    fn verdict(&self, score: f64) -> Verdict {
+        if score < self.config.ban_threshold {
+            Verdict::Ban
+        } else if score < self.config.disconnect_threshold {
+            Verdict::Disconnect
+        } else {
+            Verdict::Ok
+        }
+    }
+}
+
+pub(crate) struct Reputation(Mutex<Inner>);
+
+impl Reputation {
+    /// Loads the scores persisted in the store.
+    pub fn new(config: Config, store: store::Store) -> anyhow::Result<Self> {
+        let scores = store.get_peer_reputation()?.into_iter().collect();
+        Ok(Self(Mutex::new(Inner { config, store, scores })))
+    }
+
+    /// Current score of the peer. Unknown peers have score 0.
+    pub fn score(&self, clock: &time::Clock, peer_id: &PeerId) -> f64 {
+        self.0.lock().score(clock.now_utc(), peer_id)
+    }
+
+    /// Action to take on the peer, given its current score.
+    pub fn verdict(&self, clock: &time::Clock, peer_id: &PeerId) -> Verdict {
+        let inner = self.0.lock();
+        inner.verdict(inner.score(clock.now_utc(), peer_id))
+    }
+
+    /// Updates the score of the peer and returns the action to take on it.
+    /// Only events decreasing the score may result in a verdict other than `Verdict::Ok`.
+    pub fn report(&self, clock: &time::Clock, peer_id: &PeerId, event: Event) -> Verdict {
+        let now = clock.now_utc();
+        let delta = event.score_delta();
+        let mut inner = self.0.lock();
+        let value = (inner.score(now, peer_id) + delta).clamp(-MAX_SCORE, MAX_SCORE);
+        tracing::trace!(target: "network", ?peer_id, ?event, value, "peer reputation updated");
+        inner.scores.insert(peer_id.clone(), Score { value, updated: now });
+        if delta < 0. {
+            inner.verdict(value)
+        } else {
+            Verdict::Ok
+        }
+    }
+
+    /// Current scores of all the peers with a non-negligible reputation.
+    pub fn load(&self, clock: &time::Clock) -> HashMap<PeerId, f64> {
+        let now = clock.now_utc();
+        let inner = self.0.lock();
+        inner
+            .scores
+            .iter()
+            .map(|(peer_id, s)| (peer_id.clone(), s.at(inner.config.half_life, now)))
+            .filter(|(_, v)| v.abs() >= NEGLIGIBLE_SCORE)
+            .collect()
+    }
+
+    /// Forgets the negligible scores and persists the remaining ones
+    /// (at most MAX_STORED_SCORES of them) in the store.
+    /// This function should be called periodically.
+    pub fn flush(&self, clock: &time::Clock) {
+        let now = clock.now_utc();
+        let mut inner = self.0.lock();
+        let half_life = inner.config.half_life;
+        let mut scores: Vec<_> = std::mem::take(&mut inner.scores)
+            .into_iter()
+            .map(|(peer_id, s)| (peer_id, Score { value: s.at(half_life, now), updated: now }))
+            .filter(|(_, s)| s.value.abs() >= NEGLIGIBLE_SCORE)
+            .collect();
+        scores.sort_by(|(_, a), (_, b)| b.value.abs().total_cmp(&a.value.abs()));
+        scores.truncate(MAX_STORED_SCORES);
+        if let Err(err) = inner.store.set_peer_reputation(&scores) {
+            tracing::error!(target: "network", ?err, "Failed to save peer reputation");
+        }
+        inner.scores = scores.into_iter().collect();
+    }
+}
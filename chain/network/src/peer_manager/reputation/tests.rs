@@ -0,0 +1,122 @@
+use crate::network_protocol::testonly as data;
+use crate::peer_manager::reputation::{Config, Event, Reputation, Verdict, MAX_SCORE};
+use crate::store;
+use crate::testonly::make_rng;
+use crate::types::ReasonForBan;
+use near_async::time;
+use near_primitives::network::PeerId;
+use std::collections::HashMap;
+
+fn make_config() -> Config {
+    Config { half_life: time::Duration::hours(1), disconnect_threshold: -20., ban_threshold: -50. }
+}
+
+fn assert_close(a: f64, b: f64) {
+    assert!((a - b).abs() < 1e-9, "{a} != {b}");
+}
+
+#[test]
+fn score_decays() {
+    let mut rng = make_rng(87927345);
+    let rng = &mut rng;
+    let clock = time::FakeClock::default();
+    let reputation =
+        Reputation::new(make_config(), store::Store::from(near_store::db::TestDB::new())).unwrap();
+    let peer_id = data::make_peer_id(rng);
+
+    assert_eq!(reputation.score(&clock.clock(), &peer_id), 0.);
+    for _ in 0..4 {
+        reputation.report(&clock.clock(), &peer_id, Event::SlowResponse);
+    }
+    assert_close(reputation.score(&clock.clock(), &peer_id), -4.);
+    clock.advance(time::Duration::hours(1));
+    assert_close(reputation.score(&clock.clock(), &peer_id), -2.);
+    reputation.report(&clock.clock(), &peer_id, Event::UsefulResponse);
+    assert_close(reputation.score(&clock.clock(), &peer_id), -1.);
+    clock.advance(time::Duration::hours(2));
+    assert_close(reputation.score(&clock.clock(), &peer_id), -0.25);
+}
+
+#[test]
+fn timeouts_are_penalized_less_than_slow_responses() {
+    let mut rng = make_rng(87927345);
+    let rng = &mut rng;
+    let clock = time::FakeClock::default();
+    let reputation =
+        Reputation::new(make_config(), store::Store::from(near_store::db::TestDB::new())).unwrap();
+    let peer_id = data::make_peer_id(rng);
+
+    for _ in 0..40 {
+        assert_eq!(reputation.report(&clock.clock(), &peer_id, Event::Timeout), Verdict::Ok);
+    }
+    assert_close(reputation.score(&clock.clock(), &peer_id), -20.);
+    assert_eq!(reputation.report(&clock.clock(), &peer_id, Event::Timeout), Verdict::Disconnect);
+    // The penalty decays like any other score.
+    clock.advance(time::Duration::hours(1));
+    assert_close(reputation.score(&clock.clock(), &peer_id), -10.25);
+    assert_eq!(reputation.verdict(&clock.clock(), &peer_id), Verdict::Ok);
+}
+
+#[test]
+fn verdicts() {
+    let mut rng = make_rng(87927345);
+    let rng = &mut rng;
+    let clock = time::FakeClock::default();
+    let reputation =
+        Reputation::new(make_config(), store::Store::from(near_store::db::TestDB::new())).unwrap();
+    let peer_id = data::make_peer_id(rng);
+
+    for _ in 0..20 {
+        assert_eq!(reputation.report(&clock.clock(), &peer_id, Event::SlowResponse), Verdict::Ok);
+    }
+    assert_eq!(
+        reputation.report(&clock.clock(), &peer_id, Event::SlowResponse),
+        Verdict::Disconnect
+    );
+    assert_eq!(
+        reputation.report(&clock.clock(), &peer_id, Event::SlowResponse),
+        Verdict::Disconnect
+    );
+    // Events increasing the score never trigger any action.
+    assert_eq!(reputation.report(&clock.clock(), &peer_id, Event::UsefulResponse), Verdict::Ok);
+    assert_eq!(reputation.verdict(&clock.clock(), &peer_id), Verdict::Disconnect);
+    assert_eq!(
+        reputation.report(&clock.clock(), &peer_id, Event::Misbehavior(ReasonForBan::Abusive)),
+        Verdict::Ban
+    );
+    // Score is clamped.
+    assert_close(reputation.score(&clock.clock(), &peer_id), -MAX_SCORE);
+    // Reputation recovers with time.
+    clock.advance(time::Duration::hours(2));
+    assert_eq!(reputation.verdict(&clock.clock(), &peer_id), Verdict::Disconnect);
+    clock.advance(time::Duration::hours(1));
+    assert_eq!(reputation.verdict(&clock.clock(), &peer_id), Verdict::Ok);
+}
+
+#[test]
+fn reload_from_storage() {
+    let mut rng = make_rng(87927345);
+    let rng = &mut rng;
+    let clock = time::FakeClock::default();
+    let store = store::Store::from(near_store::db::TestDB::new());
+    let good = data::make_peer_id(rng);
+    let bad = data::make_peer_id(rng);
+    let forgotten = data::make_peer_id(rng);
+    {
+        let reputation = Reputation::new(make_config(), store.clone()).unwrap();
+        reputation.report(&clock.clock(), &good, Event::UsefulResponse);
+        reputation.report(&clock.clock(), &bad, Event::SlowResponse);
+        reputation.report(&clock.clock(), &forgotten, Event::UsefulResponse);
+        reputation.report(&clock.clock(), &forgotten, Event::SlowResponse);
+        reputation.flush(&clock.clock());
+    }
+    clock.advance(time::Duration::hours(1));
+    let reputation = Reputation::new(make_config(), store).unwrap();
+    let want: HashMap<PeerId, f64> = [(good, 0.5), (bad, -0.5)].into_iter().collect();
+    let got = reputation.load(&clock.clock());
+    assert_eq!(got.len(), 2);
+    for (peer_id, score) in &want {
+        assert_close(got[peer_id], *score);
+    }
+    assert_eq!(reputation.score(&clock.clock(), &forgotten), 0.);
+}
@@ -171,6 +171,7 @@ impl ActorHandler {
             id: PeerId::new(self.cfg.node_key.public_key()),
             addr: self.cfg.node_addr.as_ref().map(|a| **a),
             account_id: None,
+            extra_addrs: vec![],
         }
     }
 
@@ -1,6 +1,7 @@
 use crate::network_protocol::testonly as data;
 use crate::network_protocol::PeerMessage;
 use crate::network_protocol::{Encoding, Handshake, OwnedAccount, PartialEdgeInfo};
+use crate::peer::encryption;
 use crate::peer::peer_actor::ClosingReason;
 use crate::peer_manager;
 use crate::peer_manager::connection;
@@ -103,6 +104,16 @@ async fn loop_connection() {
                 &pm.cfg.node_key,
             ),
             owned_account: None,
+            session_key: encryption::is_supported(PROTOCOL_VERSION).then(|| {
+                encryption::EphemeralKey::new().sign(
+                    &pm.cfg.node_id(),
+                    &pm.cfg.node_id(),
+                    1,
+                    &pm.cfg.node_key,
+                )
+            }),
+            supported_compression: vec![],
+            sender_extra_addrs: vec![],
         }))
         .await;
     let reason = events
@@ -169,8 +180,12 @@ async fn owned_account_mismatch() {
                     peer_id: data::make_peer_id(rng),
                     timestamp: clock.now_utc(),
                 }
-                .sign(vc.signer.as_ref()),
+                .sign(vc.signer.as_ref())
+                .unwrap(),
             ),
+            session_key: None,
+            supported_compression: vec![],
+            sender_extra_addrs: vec![],
         }))
         .await;
     let reason = events
@@ -282,8 +297,19 @@ async fn invalid_edge() {
                         peer_id: cfg.node_id(),
                         timestamp: clock.now_utc(),
                     }
-                    .sign(vc.signer.as_ref()),
+                    .sign(vc.signer.as_ref())
+                    .unwrap(),
                 ),
+                session_key: encryption::is_supported(PROTOCOL_VERSION).then(|| {
+                    encryption::EphemeralKey::new().sign(
+                        &cfg.node_id(),
+                        &pm.cfg.node_id(),
+                        edge.nonce,
+                        &cfg.node_key,
+                    )
+                }),
+                supported_compression: vec![],
+                sender_extra_addrs: vec![],
             };
             let handshake = match tier {
                 tcp::Tier::T1 => PeerMessage::Tier1Handshake(handshake),
@@ -28,7 +28,7 @@ async fn random_handshake_connect(input: &[u8]) {
     .await;
     let fuzzer_peer_config = chain.make_config(&mut rng);
     let mut fuzzer_peer = pm.start_inbound(chain.clone(), fuzzer_peer_config.clone()).await;
-    let _ = fuzzer_peer.stream.stream.write_all(input).await; // ignore failures, eg. connection closed
+    let _ = fuzzer_peer.stream.tcp_mut().unwrap().write_all(input).await; // ignore failures, eg. connection closed
     pm.events
         .recv_until(|ev| match ev {
             Event::PeerManager(PME::HandshakeCompleted(_)) => {
@@ -2,6 +2,7 @@ use crate::network_protocol::testonly as data;
 use crate::network_protocol::{
     Encoding, Handshake, PartialEdgeInfo, PeerMessage, EDGE_MIN_TIMESTAMP_NONCE,
 };
+use crate::peer::encryption;
 use crate::peer_manager::testonly::{ActorHandler, Event};
 use crate::peer_manager::{self, peer_manager_actor};
 use crate::tcp;
@@ -75,6 +76,11 @@ async fn test_nonces() {
             sender_chain_info: chain.get_peer_chain_info(),
             partial_edge_info: PartialEdgeInfo::new(&peer_id, &pm.cfg.node_id(), test.0, &peer_key),
             owned_account: None,
+            session_key: encryption::is_supported(version::PROTOCOL_VERSION).then(|| {
+                encryption::EphemeralKey::new().sign(&peer_id, &pm.cfg.node_id(), test.0, &peer_key)
+            }),
+            supported_compression: vec![],
+            sender_extra_addrs: vec![],
         });
         stream.write(&handshake).await;
         if test.1 {
@@ -9,6 +9,7 @@ use crate::peer::peer_actor::{
 };
 use crate::peer_manager;
 use crate::peer_manager::peer_manager_actor::Event as PME;
+use crate::peer_manager::reputation;
 use crate::peer_manager::testonly::start as start_pm;
 use crate::peer_manager::testonly::Event;
 use crate::private_actix::RegisterPeerError;
@@ -645,6 +646,7 @@ fn make_configs(
             id: c.node_id(),
             addr: c.node_addr.as_ref().map(|a| **a),
             account_id: None,
+            extra_addrs: vec![],
         })
         .collect();
     for config in cfgs.iter_mut() {
@@ -1385,6 +1387,48 @@ async fn connect_to_unbanned_peer() {
     drop(pm1);
 }
 
+/// Check that a peer with low reputation cannot reconnect until its reputation recovers.
+#[tokio::test]
+async fn reject_peer_with_low_reputation() {
+    abort_on_panic();
+    let mut rng = make_rng(921853233);
+    let rng = &mut rng;
+    let mut clock = time::FakeClock::default();
+    let chain = Arc::new(data::Chain::make(&mut clock, rng, 10));
+
+    let pm0 = start_pm(clock.clock(), TestDB::new(), chain.make_config(rng), chain.clone()).await;
+    let pm1 = start_pm(clock.clock(), TestDB::new(), chain.make_config(rng), chain.clone()).await;
+
+    tracing::info!(target:"test", "pm0 gets a reputation below the disconnect threshold at pm1");
+    let pm0_id = pm0.cfg.node_id();
+    let verdict = pm1
+        .with_state({
+            let clock = clock.clock();
+            move |s| async move {
+                let event = reputation::Event::SlowResponse;
+                let threshold = -s.config.reputation.disconnect_threshold;
+                (0..=threshold as usize)
+                    .map(|_| s.reputation.report(&clock, &pm0_id, event))
+                    .last()
+                    .unwrap()
+            }
+        })
+        .await;
+    assert_eq!(reputation::Verdict::Disconnect, verdict);
+
+    tracing::info!(target:"test", "pm0 fails to connect to pm1");
+    let got_reason = pm1
+        .start_inbound(chain.clone(), pm0.cfg.clone())
+        .await
+        .manager_fail_handshake(&clock.clock())
+        .await;
+    assert_eq!(ClosingReason::RejectedByPeerManager(RegisterPeerError::LowReputation), got_reason);
+
+    tracing::info!(target:"test", "pm0 connects to pm1 once its reputation recovers");
+    clock.advance(pm1.cfg.reputation.half_life);
+    pm0.connect_to(&pm1.peer_info(), tcp::Tier::T2).await;
+}
+
 /// Awaits a DistanceVector message from a given `peer_id`
 async fn wait_for_distance_vector(events: &mut broadcast::Receiver<Event>, peer_id: PeerId) {
     events
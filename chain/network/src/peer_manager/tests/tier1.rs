@@ -1,3 +1,4 @@
+use crate::admin::AdminPeerRequest;
 use crate::config;
 use crate::network_protocol::testonly as data;
 use crate::network_protocol::{PeerAddr, PeerMessage, RoutedMessageBody};
@@ -22,7 +23,7 @@ fn make_block_approval(rng: &mut Rng, signer: &dyn ValidatorSigner) -> Approval
     let inner = ApprovalInner::Endorsement(data::make_hash(rng));
     let target_height = rng.gen_range(0..100000);
     Approval {
-        signature: signer.sign_approval(&inner, target_height),
+        signature: signer.sign_approval(&inner, target_height).unwrap(),
         account_id: signer.validator_id().clone(),
         target_height,
         inner,
@@ -136,7 +137,7 @@ async fn first_proxy_advertisement() {
     let got = pm.tier1_advertise_proxies(&clock.clock()).await;
     assert_eq!(
         got.unwrap().proxies,
-        vec![PeerAddr { peer_id: pm.cfg.node_id(), addr: **pm.cfg.node_addr.as_ref().unwrap() }]
+        vec![PeerAddr::new(pm.cfg.node_id(), **pm.cfg.node_addr.as_ref().unwrap())]
     );
 }
 
@@ -212,10 +213,10 @@ async fn proxy_connections() {
     for i in 0..N {
         let mut cfg = chain.make_config(rng);
         cfg.validator.as_mut().unwrap().proxies =
-            config::ValidatorProxies::Static(vec![PeerAddr {
-                peer_id: proxies[i].cfg.node_id(),
-                addr: **proxies[i].cfg.node_addr.as_ref().unwrap(),
-            }]);
+            config::ValidatorProxies::Static(vec![PeerAddr::new(
+                proxies[i].cfg.node_id(),
+                **proxies[i].cfg.node_addr.as_ref().unwrap(),
+            )]);
         validators
             .push(start_pm(clock.clock(), near_store::db::TestDB::new(), cfg, chain.clone()).await);
     }
@@ -308,8 +309,8 @@ async fn proxy_change() {
     let p1cfg = chain.make_config(rng);
     let mut v0cfg = chain.make_config(rng);
     v0cfg.validator.as_mut().unwrap().proxies = config::ValidatorProxies::Static(vec![
-        PeerAddr { peer_id: p0cfg.node_id(), addr: **p0cfg.node_addr.as_ref().unwrap() },
-        PeerAddr { peer_id: p1cfg.node_id(), addr: **p1cfg.node_addr.as_ref().unwrap() },
+        PeerAddr::new(p0cfg.node_id(), **p0cfg.node_addr.as_ref().unwrap()),
+        PeerAddr::new(p1cfg.node_id(), **p1cfg.node_addr.as_ref().unwrap()),
     ]);
     let mut v1cfg = chain.make_config(rng);
     v1cfg.validator.as_mut().unwrap().proxies = config::ValidatorProxies::Static(vec![]);
@@ -409,7 +410,7 @@ async fn stun_self_discovery() {
     let chain_info = peer_manager::testonly::make_chain_info(&chain, &[&pm.cfg]);
     pm.set_chain_info(chain_info).await;
     let got = pm.tier1_advertise_proxies(&clock.clock()).await.unwrap();
-    let want = vec![PeerAddr { peer_id: pm.cfg.node_id(), addr: *pm.cfg.node_addr.unwrap() }];
+    let want = vec![PeerAddr::new(pm.cfg.node_id(), *pm.cfg.node_addr.unwrap())];
     assert_eq!(want, got.proxies);
 
     tracing::info!(target:"test", "close the stun servers");
@@ -417,3 +418,54 @@ async fn stun_self_discovery() {
     stun_server1.close().await;
     stun_server2.close().await;
 }
+
+/// Banning a peer by the node operator closes both the TIER1 and TIER2 connections to it.
+#[tokio::test]
+async fn admin_ban_closes_tier1_connections() {
+    init_test_logger();
+    let mut rng = make_rng(921853233);
+    let rng = &mut rng;
+    let mut clock = time::FakeClock::default();
+    let chain = Arc::new(data::Chain::make(&mut clock, rng, 10));
+
+    let mut pms = vec![];
+    for _ in 0..2 {
+        pms.push(
+            start_pm(
+                clock.clock(),
+                near_store::db::TestDB::new(),
+                chain.make_config(rng),
+                chain.clone(),
+            )
+            .await,
+        );
+    }
+    let pms: Vec<_> = pms.iter().collect();
+    pms[0].connect_to(&pms[1].peer_info(), tcp::Tier::T2).await;
+    let chain_info = peer_manager::testonly::make_chain_info(
+        &chain,
+        &pms.iter().map(|pm| &pm.cfg).collect::<Vec<_>>()[..],
+    );
+    for pm in &pms {
+        pm.set_chain_info(chain_info.clone()).await;
+    }
+    establish_connections(&clock.clock(), &pms[..]).await;
+    test_clique(rng, &clock.clock(), &pms[..]).await;
+
+    tracing::info!(target:"test", "Ban the peer.");
+    let peer_id = pms[1].cfg.node_id();
+    let mut events = pms[0].events.from_now();
+    pms[0].actix.addr.send(AdminPeerRequest::BanPeer(peer_id.clone())).await.unwrap().unwrap();
+    loop {
+        let id = peer_id.clone();
+        let connected = pms[0]
+            .with_state(move |s| async move {
+                s.tier1.load().ready.contains_key(&id) || s.tier2.load().ready.contains_key(&id)
+            })
+            .await;
+        if !connected {
+            break;
+        }
+        events.recv().await;
+    }
+}
@@ -9,6 +9,7 @@ use std::sync::Arc;
 pub(crate) enum RegisterPeerError {
     Blacklisted,
     Banned,
+    LowReputation,
     PoolError(connection::PoolError),
     ConnectionLimitExceeded,
     NotTier1Peer,
@@ -0,0 +1,302 @@
+//! QUIC transport for TIER1 connections.
+//!
+//! TCP delivers the frames of a connection strictly in order, so a large message
+//! (for example a chunk state witness) delays all the messages queued after it,
+//! including the small latency-critical ones (approvals, endorsements).
+//! A QUIC connection instead carries a separate unidirectional stream per `StreamClass`
+//! in each direction, and the streams are ordered (and flow controlled) independently.
+//!
+//! QUIC requires TLS. Every node presents a self-signed certificate made with its node key,
+//! and the outbound side accepts only the certificate of the node key of the peer it
+//! connects to (see `trust_anchor`), so no certificate authorities are involved.
+//! The identity of the peer is additionally verified by the Handshake, exactly as it is
+//! done for TCP connections, and the frames are encrypted with the session keys exchanged
+//! in the Handshake (see `peer::encryption`), independently of the TLS encryption.
+use crate::network_protocol::{PeerInfo, PeerMessage, RoutedMessageBody};
+use crate::tcp;
+use anyhow::{anyhow, Context as _};
+use bytesize::MIB;
+use near_crypto::{PublicKey, SecretKey};
+use std::io;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+
+/// ALPN protocol identifier of the TIER1 QUIC connections.
+const ALPN: &[u8] = b"near-tier1";
+/// TLS server name. Peers are identified by the key of the certificate, not by the name,
+/// so all the nodes use the same one.
+const SERVER_NAME: &str = "near";
+/// Same as for the TCP connections, see `tcp::Stream::connect`.
+const CONNECT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(1);
+const KEEP_ALIVE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+const MAX_IDLE_TIMEOUT_MS: u32 = 60_000;
+/// Flow control windows are large enough to transfer state witnesses
+/// between validators on different continents at a reasonable speed.
+const STREAM_RECEIVE_WINDOW_BYTES: u32 = 16 * MIB as u32;
+const CONNECTION_RECEIVE_WINDOW_BYTES: u32 = 64 * MIB as u32;
+
+/// Class of messages sent over a single QUIC stream.
+/// Discriminants are sent over the network to identify the streams.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, strum::AsRefStr)]
+#[repr(u8)]
+pub(crate) enum StreamClass {
+    /// Handshake and all the messages not covered by the other classes.
+    Control = 0,
+    /// Small, latency-critical BFT consensus messages.
+    Consensus = 1,
+    /// Large messages: blocks, chunks and chunk state witnesses.
+    Bulk = 2,
+}
+
+impl StreamClass {
+    pub const ALL: [StreamClass; 3] =
+        [StreamClass::Control, StreamClass::Consensus, StreamClass::Bulk];
+
+    pub fn of(msg: &PeerMessage) -> Self {
+        match msg {
+            PeerMessage::Block(_) => Self::Bulk,
+            PeerMessage::Routed(msg) => match msg.body {
+                RoutedMessageBody::BlockApproval(_) | RoutedMessageBody::ChunkEndorsement(_) => {
+                    Self::Consensus
+                }
+                RoutedMessageBody::VersionedPartialEncodedChunk(_)
+                | RoutedMessageBody::PartialEncodedChunkForward(_)
+                | RoutedMessageBody::PartialEncodedChunkResponse(_)
+                | RoutedMessageBody::ChunkStateWitness(_)
+                | RoutedMessageBody::PartialEncodedStateWitness(_)
+                | RoutedMessageBody::PartialEncodedStateWitnessForward(_) => Self::Bulk,
+                _ => Self::Control,
+            },
+            _ => Self::Control,
+        }
+    }
+
+    fn from_u8(x: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| *c as u8 == x)
+    }
+
+    /// Data of the streams with higher priority is sent first.
+    fn priority(&self) -> i32 {
+        match self {
+            Self::Consensus => 1,
+            Self::Control => 0,
+            Self::Bulk => -1,
+        }
+    }
+}
+
+/// Opens the stream for sending messages of the given class.
+/// The first byte of the stream identifies the class.
+pub(crate) async fn open_stream(
+    conn: &quinn::Connection,
+    class: StreamClass,
+) -> io::Result<quinn::SendStream> {
+    let mut stream = conn.open_uni().await?;
+    stream
+        .set_priority(class.priority())
+        .map_err(|err| io::Error::new(io::ErrorKind::NotConnected, err))?;
+    stream.write_u8(class as u8).await?;
+    Ok(stream)
+}
+
+/// Accepts the streams of all the classes, opened by the peer with `open_stream`.
+pub(crate) async fn accept_streams(
+    conn: &quinn::Connection,
+) -> io::Result<Vec<(StreamClass, quinn::RecvStream)>> {
+    let mut streams: Vec<(StreamClass, quinn::RecvStream)> = vec![];
+    while streams.len() < StreamClass::ALL.len() {
+        let mut stream = conn.accept_uni().await?;
+        let class = stream.read_u8().await?;
+        match StreamClass::from_u8(class) {
+            Some(class) if streams.iter().all(|(c, _)| c != &class) => {
+                streams.push((class, stream))
+            }
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unexpected stream class {class}"),
+                ))
+            }
+        }
+    }
+    Ok(streams)
+}
+
+/// QUIC endpoint of this node, used both to accept and to establish TIER1 connections.
+#[derive(Clone)]
+pub(crate) struct Endpoint {
+    endpoint: quinn::Endpoint,
+    transport: Arc<quinn::TransportConfig>,
+}
+
+impl Endpoint {
+    /// Binds the endpoint to the given UDP address.
+    /// Inbound connections are accepted only if `accept_inbound` is set, and are presented
+    /// a certificate made with `node_key`.
+    /// Has to be called from within a tokio runtime.
+    pub fn new(
+        addr: std::net::SocketAddr,
+        accept_inbound: bool,
+        node_key: &SecretKey,
+    ) -> anyhow::Result<Self> {
+        let transport = Arc::new(transport_config());
+        let endpoint = if accept_inbound {
+            quinn::Endpoint::server(server_config(transport.clone(), node_key)?, addr)
+        } else {
+            quinn::Endpoint::client(addr)
+        }
+        .with_context(|| format!("failed to bind QUIC endpoint to {addr}"))?;
+        Ok(Self { endpoint, transport })
+    }
+
+    /// Establishes an outbound TIER1 connection to the given peer.
+    /// The connection fails unless the peer presents a certificate made with its node key.
+    pub async fn connect(&self, peer_info: &PeerInfo) -> anyhow::Result<tcp::Stream> {
+        let addr = peer_info
+            .addr
+            .ok_or_else(|| anyhow!("Trying to connect to peer with no public address"))?;
+        let client_config = client_config(self.transport.clone(), peer_info.id.public_key())?;
+        let conn = tokio::time::timeout(CONNECT_TIMEOUT, async {
+            anyhow::Ok(self.endpoint.connect_with(client_config, addr, SERVER_NAME)?.await?)
+        })
+        .await?
+        .context("quinn::Endpoint::connect()")?;
+        Ok(tcp::Stream::new_quic(
+            conn,
+            self.endpoint.local_addr()?,
+            tcp::StreamType::Outbound { peer_id: peer_info.id.clone(), tier: tcp::Tier::T1 },
+        ))
+    }
+
+    /// Waits for the next inbound connection.
+    /// Returns None if the endpoint has been closed.
+    pub async fn accept(&self) -> Option<Connecting> {
+        Some(Connecting {
+            connecting: self.endpoint.accept().await?,
+            endpoint: self.endpoint.clone(),
+        })
+    }
+}
+
+/// Inbound connection in the middle of the QUIC handshake.
+pub(crate) struct Connecting {
+    connecting: quinn::Connecting,
+    endpoint: quinn::Endpoint,
+}
+
+impl Connecting {
+    pub async fn finish(self) -> anyhow::Result<tcp::Stream> {
+        let conn = self.connecting.await?;
+        Ok(tcp::Stream::new_quic(conn, self.endpoint.local_addr()?, tcp::StreamType::Inbound))
+    }
+}
+
+fn transport_config() -> quinn::TransportConfig {
+    let mut cfg = quinn::TransportConfig::default();
+    cfg.keep_alive_interval(Some(KEEP_ALIVE_INTERVAL));
+    cfg.max_idle_timeout(Some(quinn::VarInt::from_u32(MAX_IDLE_TIMEOUT_MS).into()));
+    // Each side opens exactly one unidirectional stream per StreamClass.
+    cfg.max_concurrent_uni_streams((StreamClass::ALL.len() as u32).into());
+    cfg.max_concurrent_bidi_streams(0u32.into());
+    cfg.stream_receive_window(STREAM_RECEIVE_WINDOW_BYTES.into());
+    cfg.receive_window(CONNECTION_RECEIVE_WINDOW_BYTES.into());
+    cfg.send_window(CONNECTION_RECEIVE_WINDOW_BYTES as u64);
+    cfg
+}
+
+/// PKCS#8 (RFC 8410) encoding of an Ed25519 private key is this prefix followed by the seed.
+const ED25519_PKCS8_PREFIX: [u8; 16] = [
+    0x30, 0x2e, 0x02, 0x01, 0x00, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x04, 0x22, 0x04, 0x20,
+];
+/// Contents of the SubjectPublicKeyInfo of an Ed25519 public key (RFC 8410), without
+/// the outer SEQUENCE header, is this prefix followed by the public key.
+const ED25519_SPKI_PREFIX: [u8; 10] = [0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00];
+
+fn server_config(
+    transport: Arc<quinn::TransportConfig>,
+    node_key: &SecretKey,
+) -> anyhow::Result<quinn::ServerConfig> {
+    let SecretKey::ED25519(node_key) = node_key else {
+        anyhow::bail!("QUIC requires an ED25519 node key");
+    };
+    let mut pkcs8 = ED25519_PKCS8_PREFIX.to_vec();
+    pkcs8.extend_from_slice(&node_key.0[..32]);
+    // The certificate is self-signed with the node key and has an empty subject,
+    // so that it is accepted by the `trust_anchor` of the node key.
+    let mut params = rcgen::CertificateParams::new(vec![SERVER_NAME.to_string()]);
+    params.alg = &rcgen::PKCS_ED25519;
+    params.distinguished_name = rcgen::DistinguishedName::new();
+    params.key_pair = Some(rcgen::KeyPair::from_der(&pkcs8)?);
+    let cert = rcgen::Certificate::from_params(params)?;
+    let mut crypto = rustls::ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(
+            vec![rustls::Certificate(cert.serialize_der()?)],
+            rustls::PrivateKey(pkcs8),
+        )?;
+    crypto.alpn_protocols = vec![ALPN.to_vec()];
+    let mut cfg = quinn::ServerConfig::with_crypto(Arc::new(crypto));
+    cfg.transport_config(transport);
+    Ok(cfg)
+}
+
+/// Config of a connection to the peer with the given node key.
+fn client_config(
+    transport: Arc<quinn::TransportConfig>,
+    peer_key: &PublicKey,
+) -> anyhow::Result<quinn::ClientConfig> {
+    let mut roots = rustls::RootCertStore::empty();
+    roots.add_trust_anchors(std::iter::once(trust_anchor(peer_key)?));
+    let mut crypto = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    crypto.alpn_protocols = vec![ALPN.to_vec()];
+    let mut cfg = quinn::ClientConfig::new(Arc::new(crypto));
+    cfg.transport_config(transport);
+    Ok(cfg)
+}
+
+/// The only certificate authority trusted when connecting to the peer with the given node key:
+/// the node key itself, with an empty subject. Therefore the only accepted certificates are
+/// the ones signed with the node key, i.e. the certificates made by the peer in `server_config`.
+fn trust_anchor(peer_key: &PublicKey) -> anyhow::Result<rustls::OwnedTrustAnchor> {
+    let PublicKey::ED25519(peer_key) = peer_key else {
+        anyhow::bail!("QUIC requires an ED25519 node key");
+    };
+    let mut spki = ED25519_SPKI_PREFIX.to_vec();
+    spki.extend_from_slice(&peer_key.0);
+    Ok(rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(
+        Vec::<u8>::new(),
+        spki,
+        None::<Vec<u8>>,
+    ))
+}
+
+#[cfg(test)]
+pub(crate) mod testonly {
+    use super::*;
+    use near_primitives::network::PeerId;
+
+    /// Establishes a loopback QUIC connection to localhost with random ports,
+    /// to a server with the given node key.
+    /// Returns a pair of streams: (outbound,inbound).
+    pub async fn loopback(node_key: &SecretKey) -> (tcp::Stream, tcp::Stream) {
+        let (outbound, inbound) = connect(node_key, PeerId::new(node_key.public_key())).await;
+        (outbound.unwrap(), inbound.unwrap())
+    }
+
+    /// Starts a server with `node_key` and connects to it, expecting it to be `peer_id`.
+    pub async fn connect(
+        node_key: &SecretKey,
+        peer_id: PeerId,
+    ) -> (anyhow::Result<tcp::Stream>, anyhow::Result<tcp::Stream>) {
+        let server = Endpoint::new("[::1]:0".parse().unwrap(), true, node_key).unwrap();
+        let client = Endpoint::new("[::1]:0".parse().unwrap(), false, node_key).unwrap();
+        let peer_info = PeerInfo::new(peer_id, server.endpoint.local_addr().unwrap());
+        tokio::join!(client.connect(&peer_info), async {
+            server.accept().await.unwrap().finish().await
+        })
+    }
+}
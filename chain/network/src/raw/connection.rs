@@ -1,10 +1,15 @@
+use crate::capture;
 use crate::network_protocol::{
-    Encoding, Handshake, HandshakeFailureReason, PartialEdgeInfo, PeerChainInfoV2, PeerIdOrHash,
-    PeerMessage, Ping, Pong, RawRoutedMessage, RoutedMessageBody, RoutingTableUpdate,
+    Encoding, EpochBlockProducersResponse, EpochSyncInfoResponse, Handshake,
+    HandshakeFailureReason, LightClientBlockResponse, PartialEdgeInfo, PeerChainInfoV2,
+    PeerIdOrHash, PeerMessage, Ping, Pong, RawRoutedMessage, RoutedMessageBody, RoutingTableUpdate,
+    SessionKey,
 };
+use crate::peer::encryption;
 use crate::tcp;
 use crate::types::{
-    PartialEncodedChunkRequestMsg, PartialEncodedChunkResponseMsg, PeerInfo, StateResponseInfo,
+    PartialEncodedChunkRequestMsg, PartialEncodedChunkResponseMsg, PeerInfo, PeerType,
+    StateResponseInfo,
 };
 use bytes::buf::{Buf, BufMut};
 use bytes::BytesMut;
@@ -13,7 +18,7 @@ use near_crypto::{KeyType, SecretKey};
 use near_primitives::block::{Block, BlockHeader, GenesisId};
 use near_primitives::hash::CryptoHash;
 use near_primitives::network::{AnnounceAccount, PeerId};
-use near_primitives::types::{BlockHeight, ShardId};
+use near_primitives::types::{BlockHeight, EpochId, ShardId};
 use near_primitives::version::{ProtocolVersion, PROTOCOL_VERSION};
 use std::fmt;
 use std::io;
@@ -85,6 +90,12 @@ pub enum DirectMessage {
     StateRequestHeader(ShardId, CryptoHash),
     StateRequestPart(ShardId, CryptoHash, u64),
     VersionedStateResponse(StateResponseInfo),
+    LightClientBlockRequest(CryptoHash),
+    LightClientBlockResponse(LightClientBlockResponse),
+    EpochBlockProducersRequest(CryptoHash),
+    EpochBlockProducersResponse(EpochBlockProducersResponse),
+    EpochSyncRequest(EpochId),
+    EpochSyncResponse(EpochSyncInfoResponse),
 }
 
 impl fmt::Display for DirectMessage {
@@ -119,6 +130,27 @@ impl fmt::Debug for DirectMessage {
                 r.shard_id(),
                 r.sync_hash()
             ),
+            Self::LightClientBlockRequest(h) => write!(f, "LightClientBlockRequest({})", h),
+            Self::LightClientBlockResponse(r) => write!(
+                f,
+                "LightClientBlockResponse({}, {:?})",
+                r.last_block_hash,
+                r.block.as_ref().map(|b| b.inner_lite.height)
+            ),
+            Self::EpochBlockProducersRequest(h) => write!(f, "EpochBlockProducersRequest({})", h),
+            Self::EpochBlockProducersResponse(r) => write!(
+                f,
+                "EpochBlockProducersResponse({}, {:?})",
+                r.block_hash,
+                r.block_producers.as_ref().map(|bps| bps.next_bps.len())
+            ),
+            Self::EpochSyncRequest(epoch_id) => write!(f, "EpochSyncRequest({:?})", epoch_id),
+            Self::EpochSyncResponse(r) => write!(
+                f,
+                "EpochSyncResponse({:?}, {} epochs)",
+                r.epoch_id,
+                r.epoch_sync_infos.len()
+            ),
         }
     }
 }
@@ -172,6 +204,8 @@ pub enum ConnectError {
     HandshakeFailure(HandshakeFailureReason),
     #[error("received unexpected message before the handshake: {0:?}")]
     UnexpectedFirstMessage(PeerMessage),
+    #[error("handshake is missing a valid session key")]
+    InvalidSessionKey,
     #[error(transparent)]
     TcpConnect(anyhow::Error),
 }
@@ -192,6 +226,7 @@ fn new_handshake(
     listen_port: u16,
     nonce: u64,
     protocol_version: ProtocolVersion,
+    session_key: Option<SessionKey>,
     chain_id: &str,
     genesis_hash: CryptoHash,
     head_height: BlockHeight,
@@ -214,6 +249,9 @@ fn new_handshake(
         },
         partial_edge_info: PartialEdgeInfo::new(my_peer_id, target_peer_id, nonce, secret_key),
         owned_account: None,
+        session_key,
+        supported_compression: vec![],
+        sender_extra_addrs: vec![],
     })
 }
 
@@ -283,9 +321,11 @@ impl Connection {
             Err(RecvError::IO(e)) => return Err(ConnectError::IO(e)),
         };
 
-        let (peer_id, nonce) = match message {
+        let (peer_id, nonce, their_session_key) = match message {
             // TODO: maybe check the handshake for sanity
-            PeerMessage::Tier2Handshake(h) => (h.sender_peer_id, h.partial_edge_info.nonce),
+            PeerMessage::Tier2Handshake(h) => {
+                (h.sender_peer_id, h.partial_edge_info.nonce, h.session_key)
+            }
             PeerMessage::HandshakeFailure(_peer_info, reason) => {
                 return Err(ConnectError::HandshakeFailure(reason))
             }
@@ -293,6 +333,20 @@ impl Connection {
         };
 
         let my_peer_id = PeerId::new(secret_key.public_key());
+        // Encrypt the connection if the peer offered a session key, just like a node would.
+        let ephemeral_key = encryption::EphemeralKey::new();
+        let ciphers = match their_session_key {
+            Some(key) if encryption::is_supported(PROTOCOL_VERSION) => {
+                if !key.verify(&peer_id, &my_peer_id, nonce) {
+                    return Err(ConnectError::InvalidSessionKey);
+                }
+                Some(ephemeral_key.agree(&key, PeerType::Inbound))
+            }
+            _ => None,
+        };
+        let session_key = ciphers
+            .is_some()
+            .then(|| ephemeral_key.sign(&my_peer_id, &peer_id, nonce, &secret_key));
         let handshake = new_handshake(
             &secret_key,
             &my_peer_id,
@@ -300,6 +354,7 @@ impl Connection {
             stream.stream.local_addr.port(),
             nonce,
             PROTOCOL_VERSION,
+            session_key,
             chain_id,
             genesis_hash,
             head_height,
@@ -308,6 +363,12 @@ impl Connection {
         );
 
         stream.write_message(&handshake).await.map_err(ConnectError::IO)?;
+        // Frames following our Handshake are encrypted in both directions.
+        // Peers offering encryption only send the proto Handshake.
+        if ciphers.is_some() {
+            borsh_message_expected = false;
+        }
+        stream.ciphers = ciphers;
 
         Ok(Self {
             secret_key,
@@ -327,13 +388,19 @@ impl Connection {
         head_height: BlockHeight,
         tracked_shards: Vec<ShardId>,
     ) -> Result<(), ConnectError> {
+        let nonce = 1;
+        let ephemeral_key = encryption::EphemeralKey::new();
+        let encrypted = encryption::is_supported(protocol_version);
+        let session_key = encrypted
+            .then(|| ephemeral_key.sign(&self.my_peer_id, &self.peer_id, nonce, &self.secret_key));
         let handshake = new_handshake(
             &self.secret_key,
             &self.my_peer_id,
             &self.peer_id,
             self.stream.stream.local_addr.port(),
-            1,
+            nonce,
             protocol_version,
+            session_key,
             chain_id,
             genesis_hash,
             head_height,
@@ -349,8 +416,15 @@ impl Connection {
 
         match message {
             // TODO: maybe check the handshake for sanity
-            PeerMessage::Tier2Handshake(_) => {
+            PeerMessage::Tier2Handshake(h) => {
                 tracing::info!(target: "network", "handshake latency: {}", timestamp - start);
+                if encrypted {
+                    let key = h.session_key.ok_or(ConnectError::InvalidSessionKey)?;
+                    if !key.verify(&self.peer_id, &self.my_peer_id, nonce) {
+                        return Err(ConnectError::InvalidSessionKey);
+                    }
+                    self.stream.ciphers = Some(ephemeral_key.agree(&key, PeerType::Outbound));
+                }
             }
             PeerMessage::HandshakeFailure(_peer_info, reason) => {
                 return Err(ConnectError::HandshakeFailure(reason))
@@ -380,6 +454,16 @@ impl Connection {
             DirectMessage::VersionedStateResponse(request) => {
                 PeerMessage::VersionedStateResponse(request)
             }
+            DirectMessage::LightClientBlockRequest(h) => PeerMessage::LightClientBlockRequest(h),
+            DirectMessage::LightClientBlockResponse(r) => PeerMessage::LightClientBlockResponse(r),
+            DirectMessage::EpochBlockProducersRequest(h) => {
+                PeerMessage::EpochBlockProducersRequest(h)
+            }
+            DirectMessage::EpochBlockProducersResponse(r) => {
+                PeerMessage::EpochBlockProducersResponse(r)
+            }
+            DirectMessage::EpochSyncRequest(epoch_id) => PeerMessage::EpochSyncRequest(epoch_id),
+            DirectMessage::EpochSyncResponse(r) => PeerMessage::EpochSyncResponse(r),
         };
 
         self.stream.write_message(&peer_msg).await
@@ -415,6 +499,15 @@ impl Connection {
         self.stream.write_message(&PeerMessage::Routed(Box::new(msg))).await
     }
 
+    // Send a message recorded by the traffic capture (see crate::capture) as is.
+    pub async fn send_captured_message(
+        &mut self,
+        msg: &capture::CapturedMessage,
+    ) -> io::Result<()> {
+        let msg = msg.message().map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        self.stream.write_message(&msg).await
+    }
+
     fn target_is_for_me(&mut self, target: &PeerIdOrHash) -> bool {
         match target {
             PeerIdOrHash::PeerId(peer_id) => peer_id == &self.my_peer_id,
@@ -501,6 +594,39 @@ impl Connection {
                         timestamp,
                     ));
                 }
+                PeerMessage::LightClientBlockRequest(h) => {
+                    return Ok((
+                        Message::Direct(DirectMessage::LightClientBlockRequest(h)),
+                        timestamp,
+                    ));
+                }
+                PeerMessage::LightClientBlockResponse(r) => {
+                    return Ok((
+                        Message::Direct(DirectMessage::LightClientBlockResponse(r)),
+                        timestamp,
+                    ));
+                }
+                PeerMessage::EpochBlockProducersRequest(h) => {
+                    return Ok((
+                        Message::Direct(DirectMessage::EpochBlockProducersRequest(h)),
+                        timestamp,
+                    ));
+                }
+                PeerMessage::EpochBlockProducersResponse(r) => {
+                    return Ok((
+                        Message::Direct(DirectMessage::EpochBlockProducersResponse(r)),
+                        timestamp,
+                    ));
+                }
+                PeerMessage::EpochSyncRequest(epoch_id) => {
+                    return Ok((
+                        Message::Direct(DirectMessage::EpochSyncRequest(epoch_id)),
+                        timestamp,
+                    ));
+                }
+                PeerMessage::EpochSyncResponse(r) => {
+                    return Ok((Message::Direct(DirectMessage::EpochSyncResponse(r)), timestamp));
+                }
                 _ => {}
             }
         }
@@ -523,6 +649,8 @@ struct PeerStream {
     stream: tcp::Stream,
     buf: BytesMut,
     recv_timeout: Duration,
+    /// Set once the handshake has established an encrypted session.
+    ciphers: Option<encryption::SessionCiphers>,
 }
 
 impl std::fmt::Debug for PeerStream {
@@ -533,20 +661,23 @@ impl std::fmt::Debug for PeerStream {
 
 impl PeerStream {
     fn new(stream: tcp::Stream, recv_timeout: Duration) -> Self {
-        Self { stream, buf: BytesMut::with_capacity(1024), recv_timeout }
+        Self { stream, buf: BytesMut::with_capacity(1024), recv_timeout, ciphers: None }
     }
 
     async fn write_message(&mut self, msg: &PeerMessage) -> io::Result<()> {
         let mut msg = msg.serialize(Encoding::Proto);
+        if let Some(ciphers) = &mut self.ciphers {
+            msg = ciphers.send.encrypt(&msg);
+        }
         let mut buf = (msg.len() as u32).to_le_bytes().to_vec();
         buf.append(&mut msg);
-        self.stream.stream.write_all(&buf).await
+        self.stream.tcp_mut()?.write_all(&buf).await
     }
 
     async fn do_read(&mut self) -> io::Result<()> {
         let n = tokio::time::timeout(
             self.recv_timeout.try_into().unwrap(),
-            self.stream.stream.read_buf(&mut self.buf),
+            self.stream.tcp_mut()?.read_buf(&mut self.buf),
         )
         .await??;
         tracing::trace!(target: "network", "Read {} bytes from {:?}", n, self.stream.peer_addr);
@@ -585,8 +716,17 @@ impl PeerStream {
         }
 
         self.buf.advance(4);
-        let msg = PeerMessage::deserialize(Encoding::Proto, &self.buf[..msg_length]);
-        self.buf.advance(msg_length);
+        let frame = self.buf.split_to(msg_length);
+        let msg = match &mut self.ciphers {
+            Some(ciphers) => {
+                let frame = ciphers
+                    .recv
+                    .decrypt(&frame)
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+                PeerMessage::deserialize(Encoding::Proto, &frame)
+            }
+            None => PeerMessage::deserialize(Encoding::Proto, &frame),
+        };
 
         // make sure we can probably read the next message in one syscall next time
         let max_len_after_next_read = self.buf.chunk_mut().len() + self.buf.remaining();
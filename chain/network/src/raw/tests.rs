@@ -1,3 +1,4 @@
+use crate::capture;
 use crate::network_protocol::testonly as data;
 use crate::raw;
 use crate::tcp;
@@ -184,3 +185,107 @@ async fn test_listener() {
     // at least the handshake logic has gotten exercised somewhat
     let _ = conn.recv().await.unwrap();
 }
+
+/// Captures the messages received by a node and replays them to a mock node.
+#[tokio::test]
+async fn test_capture_and_replay() {
+    init_test_logger();
+    let mut rng = testonly::make_rng(33955575545);
+    let rng = &mut rng;
+    let mut clock = time::FakeClock::default();
+    let chain = Arc::new(data::Chain::make(&mut clock, rng, 10));
+    let genesis_id = chain.genesis_id.clone();
+
+    let capture_dir = tempfile::tempdir().unwrap();
+    let mut cfg = chain.make_config(rng);
+    cfg.traffic_capture = Some(capture::Config {
+        dir: capture_dir.path().to_path_buf(),
+        max_file_bytes: 1 << 20,
+        max_files: 10,
+    });
+    let peer_id = cfg.node_id();
+    let addr = **cfg.node_addr.as_ref().unwrap();
+    let _pm = crate::peer_manager::testonly::start(
+        clock.clock(),
+        near_store::db::TestDB::new(),
+        cfg,
+        chain,
+    )
+    .await;
+
+    tracing::info!(target:"test", "send messages to the capturing node");
+    let mut conn = raw::Connection::connect(
+        addr,
+        peer_id,
+        None,
+        &genesis_id.chain_id,
+        genesis_id.hash,
+        0,
+        vec![0],
+        time::Duration::SECOND,
+    )
+    .await
+    .unwrap();
+    let hashes: Vec<_> = (0..3).map(|_| data::make_hash(rng)).collect();
+    for hash in &hashes {
+        conn.send_message(raw::DirectMessage::BlockRequest(*hash)).await.unwrap();
+    }
+
+    tracing::info!(target:"test", "wait for the messages to be captured");
+    let captured = loop {
+        let mut captured = vec![];
+        for path in capture::list_files(capture_dir.path()).unwrap() {
+            // The last record might still be partially written.
+            captured.extend(capture::Reader::open(&path).unwrap().map_while(Result::ok));
+        }
+        captured.retain(|msg| msg.message_type() == Some("BlockRequest"));
+        if captured.len() == hashes.len() {
+            break captured;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    };
+
+    tracing::info!(target:"test", "replay the captured messages to a mock node");
+    let mock_addr = tcp::ListenerAddr::reserve_for_test();
+    let mock_socket_addr = *mock_addr;
+    let mock_key = SecretKey::from_random(KeyType::ED25519);
+    let mock_peer_id = PeerId::new(mock_key.public_key());
+    let mut listener = raw::Listener::bind(
+        mock_addr,
+        mock_key,
+        &genesis_id.chain_id,
+        genesis_id.hash,
+        0,
+        vec![0],
+        false,
+        time::Duration::SECOND,
+    )
+    .await
+    .unwrap();
+    let (mock_conn, replay_conn) = tokio::join!(
+        listener.accept(),
+        raw::Connection::connect(
+            mock_socket_addr,
+            mock_peer_id,
+            None,
+            &genesis_id.chain_id,
+            genesis_id.hash,
+            0,
+            vec![0],
+            time::Duration::SECOND,
+        )
+    );
+    let (mut mock_conn, mut replay_conn) = (mock_conn.unwrap(), replay_conn.unwrap());
+    for msg in &captured {
+        replay_conn.send_captured_message(msg).await.unwrap();
+    }
+    let mut got = vec![];
+    while got.len() < hashes.len() {
+        if let (raw::Message::Direct(raw::DirectMessage::BlockRequest(hash)), _) =
+            mock_conn.recv().await.unwrap()
+        {
+            got.push(hash);
+        }
+    }
+    assert_eq!(hashes, got);
+}
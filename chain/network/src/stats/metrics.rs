@@ -144,6 +144,23 @@ pub(crate) static PEER_DATA_SENT_BYTES: Lazy<IntCounter> = Lazy::new(|| {
     try_create_int_counter("near_peer_data_sent_bytes", "Total data sent to peers").unwrap()
 });
 
+pub(crate) static PEER_REPUTATION_EVENTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_peer_reputation_events_total",
+        "Number of events affecting the reputation of the peers, by event type",
+        &["event"],
+    )
+    .unwrap()
+});
+
+pub(crate) static PEER_REPUTATION_DISCONNECTS: Lazy<IntCounter> = Lazy::new(|| {
+    try_create_int_counter(
+        "near_peer_reputation_disconnects_total",
+        "Number of peers disconnected because of low reputation",
+    )
+    .unwrap()
+});
+
 pub(crate) static PEER_DATA_READ_BUFFER_SIZE: Lazy<IntGaugeVec> = Lazy::new(|| {
     try_create_int_gauge_vec(
         "near_peer_read_buffer_size",
@@ -184,6 +201,14 @@ pub(crate) static PEER_MESSAGE_SENT_BY_TYPE_BYTES: Lazy<IntCounterVec> = Lazy::n
     )
     .unwrap()
 });
+pub(crate) static PEER_MESSAGE_SENT_BY_CLASS_BYTES: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_peer_message_sent_by_class_bytes",
+        "Data sent to peers by rate limited message classes (see SendRateLimits)",
+        &["class"],
+    )
+    .unwrap()
+});
 pub(crate) static PEER_MESSAGE_SENT_BY_TYPE_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
     try_create_int_counter_vec(
         "near_peer_message_sent_by_type_total",
@@ -192,6 +217,24 @@ pub(crate) static PEER_MESSAGE_SENT_BY_TYPE_TOTAL: Lazy<IntCounterVec> = Lazy::n
     )
     .unwrap()
 });
+pub(crate) static PEER_MESSAGE_COMPRESSION_RATIO: Lazy<HistogramVec> = Lazy::new(|| {
+    try_create_histogram_vec(
+        "near_peer_message_compression_ratio",
+        "Ratio of compressed to uncompressed size of messages sent to peers, by message types",
+        &["type"],
+        Some(prometheus::linear_buckets(0.05, 0.05, 20).unwrap()),
+    )
+    .unwrap()
+});
+pub(crate) static PEER_MESSAGE_COMPRESSION_TIME: Lazy<HistogramVec> = Lazy::new(|| {
+    try_create_histogram_vec(
+        "near_peer_message_compression_time",
+        "CPU time spent on compressing and decompressing peer messages",
+        &["operation"],
+        Some(exponential_buckets(0.00001, 2., 20).unwrap()),
+    )
+    .unwrap()
+});
 pub(crate) static SYNC_ACCOUNTS_DATA: Lazy<IntCounterVec> = Lazy::new(|| {
     try_create_int_counter_vec(
         "near_sync_accounts_data",
@@ -342,6 +385,14 @@ static NETWORK_ROUTED_MSG_NUM_HOPS: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+pub(crate) static TRAFFIC_CAPTURE_DROPPED_MESSAGES: Lazy<IntCounter> = Lazy::new(|| {
+    try_create_int_counter(
+        "near_traffic_capture_dropped_messages",
+        "Number of received messages which were not captured, because the capture queue was full",
+    )
+    .unwrap()
+});
+
 pub(crate) static CONNECTED_TO_MYSELF: Lazy<IntCounter> = Lazy::new(|| {
     try_create_int_counter(
         "near_connected_to_myself",
@@ -446,6 +497,7 @@ pub(crate) enum MessageDropped {
     MaxCapacityExceeded,
     TransactionsPerBlockExceeded,
     Duplicate,
+    RateLimited,
 }
 
 impl MessageDropped {
@@ -457,7 +509,7 @@ impl MessageDropped {
         self.inc_msg_type("unknown")
     }
 
-    fn inc_msg_type(self, msg_type: &str) {
+    pub fn inc_msg_type(self, msg_type: &str) {
         let reason = self.as_ref();
         DROPPED_MESSAGE_COUNT.with_label_values(&[msg_type, reason]).inc();
     }
@@ -2,6 +2,8 @@
 /// All transactions should be implemented within this module,
 /// in particular schema::StoreUpdate is not exported.
 use crate::network_protocol::Edge;
+use crate::peer_manager::peer_store::AdminOverrides;
+use crate::peer_manager::reputation;
 use crate::types::ConnectionInfo;
 use near_primitives::network::{AnnounceAccount, PeerId};
 use near_primitives::types::AccountId;
@@ -137,6 +139,35 @@ impl Store {
     }
 }
 
+// Reputation storage.
+impl Store {
+    pub fn set_peer_reputation(
+        &mut self,
+        scores: &Vec<(PeerId, reputation::Score)>,
+    ) -> Result<(), Error> {
+        let mut update = self.0.new_update();
+        update.set::<schema::PeerReputation>(&(), scores);
+        self.0.commit(update).map_err(Error)
+    }
+
+    pub fn get_peer_reputation(&self) -> Result<Vec<(PeerId, reputation::Score)>, Error> {
+        Ok(self.0.get::<schema::PeerReputation>(&()).map_err(Error)?.unwrap_or(vec![]))
+    }
+}
+
+// Admin overrides storage.
+impl Store {
+    pub fn set_peer_admin_overrides(&mut self, admin: &AdminOverrides) -> Result<(), Error> {
+        let mut update = self.0.new_update();
+        update.set::<schema::PeerAdminOverrides>(&(), admin);
+        self.0.commit(update).map_err(Error)
+    }
+
+    pub fn get_peer_admin_overrides(&self) -> Result<AdminOverrides, Error> {
+        Ok(self.0.get::<schema::PeerAdminOverrides>(&()).map_err(Error)?.unwrap_or_default())
+    }
+}
+
 impl From<Arc<dyn near_store::db::Database>> for Store {
     fn from(store: Arc<dyn near_store::db::Database>) -> Self {
         Self(schema::Store::from(store))
@@ -1,3 +1,6 @@
+use crate::blacklist;
+use crate::peer_manager::peer_store::AdminOverrides;
+use crate::peer_manager::reputation;
 use crate::types as primitives;
 /// Schema module defines a type-safe access to the DB.
 /// It is a concise definition of key and value types
@@ -9,6 +12,7 @@ use near_primitives::account::id::AccountId;
 use near_primitives::network::{AnnounceAccount, PeerId};
 use near_store::DBCol;
 use std::io;
+use std::net::SocketAddr;
 use std::sync::Arc;
 
 #[cfg(test)]
@@ -28,6 +32,9 @@ impl Format for AccountIdFormat {
 }
 
 /// A Borsh representation of the primitives::ConnectionInfo.
+/// PeerInfo::extra_addrs are not stored: peer_info.addr is the address
+/// the outbound connection was established to, which is enough to reconnect,
+/// and the peer advertises its other addresses again in the handshake.
 #[derive(BorshSerialize, BorshDeserialize)]
 pub(super) struct ConnectionInfoRepr {
     peer_info: primitives::PeerInfo,
@@ -59,6 +66,89 @@ impl BorshRepr for ConnectionInfoRepr {
     }
 }
 
+/// A Borsh representation of the reputation score of a peer.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(super) struct PeerReputationRepr {
+    peer_id: PeerId,
+    value: f64,
+    /// UNIX timestamp in nanos.
+    updated: u64,
+}
+
+impl BorshRepr for PeerReputationRepr {
+    type T = (PeerId, reputation::Score);
+    fn to_repr((peer_id, s): &Self::T) -> Self {
+        Self {
+            peer_id: peer_id.clone(),
+            value: s.value,
+            updated: s.updated.unix_timestamp_nanos() as u64,
+        }
+    }
+
+    fn from_repr(s: Self) -> Result<Self::T, Error> {
+        Ok((
+            s.peer_id,
+            reputation::Score {
+                value: s.value,
+                updated: time::Utc::from_unix_timestamp_nanos(s.updated as i128)
+                    .map_err(invalid_data)?,
+            },
+        ))
+    }
+}
+
+/// A Borsh representation of the primitives::PeerInfo, which keeps the
+/// PeerInfo::extra_addrs skipped by the Borsh encoding of PeerInfo.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(super) struct PeerInfoRepr {
+    peer_info: primitives::PeerInfo,
+    extra_addrs: Vec<SocketAddr>,
+}
+
+impl BorshRepr for PeerInfoRepr {
+    type T = primitives::PeerInfo;
+    fn to_repr(s: &primitives::PeerInfo) -> Self {
+        Self { peer_info: s.clone(), extra_addrs: s.extra_addrs.clone() }
+    }
+
+    fn from_repr(s: Self) -> Result<primitives::PeerInfo, Error> {
+        Ok(primitives::PeerInfo { extra_addrs: s.extra_addrs, ..s.peer_info })
+    }
+}
+
+/// A Borsh representation of the peer_store::AdminOverrides.
+/// Blacklist entries are stored in their text format.
+#[derive(BorshSerialize, BorshDeserialize)]
+pub(super) struct AdminOverridesRepr {
+    added_peers: Vec<PeerInfoRepr>,
+    banned_peers: Vec<PeerId>,
+    blacklist: Vec<String>,
+}
+
+impl BorshRepr for AdminOverridesRepr {
+    type T = AdminOverrides;
+    fn to_repr(s: &AdminOverrides) -> Self {
+        Self {
+            added_peers: Vec::<PeerInfoRepr>::to_repr(&s.added_peers),
+            banned_peers: s.banned_peers.clone(),
+            blacklist: s.blacklist.iter().map(|e| e.to_string()).collect(),
+        }
+    }
+
+    fn from_repr(s: Self) -> Result<AdminOverrides, Error> {
+        Ok(AdminOverrides {
+            added_peers: Vec::<PeerInfoRepr>::from_repr(s.added_peers)?,
+            banned_peers: s.banned_peers,
+            blacklist: s
+                .blacklist
+                .iter()
+                .map(|e| e.parse::<blacklist::Entry>())
+                .collect::<Result<_, _>>()
+                .map_err(invalid_data)?,
+        })
+    }
+}
+
 #[derive(BorshSerialize, BorshDeserialize)]
 pub(super) struct EdgeRepr {
     key: (PeerId, PeerId),
@@ -103,6 +193,20 @@ impl Column for RecentOutboundConnections {
     type Value = Vec<ConnectionInfoRepr>;
 }
 
+pub(super) struct PeerReputation;
+impl Column for PeerReputation {
+    const COL: DBCol = DBCol::PeerReputation;
+    type Key = Borsh<()>;
+    type Value = Vec<PeerReputationRepr>;
+}
+
+pub(super) struct PeerAdminOverrides;
+impl Column for PeerAdminOverrides {
+    const COL: DBCol = DBCol::PeerAdminOverrides;
+    type Key = Borsh<()>;
+    type Value = AdminOverridesRepr;
+}
+
 pub(super) struct PeerComponent;
 impl Column for PeerComponent {
     const COL: DBCol = DBCol::PeerComponent;
@@ -11,3 +11,18 @@ fn borsh_wrapper_is_transparent() {
     let e = data::make_edge(&s1, &s2, 1);
     assert_eq!(borsh::to_vec(&Borsh(e.clone())).unwrap(), borsh::to_vec(&e).unwrap());
 }
+
+#[test]
+fn admin_overrides_keep_extra_addrs() {
+    let mut rng = make_rng(423423);
+    let rng = &mut rng;
+    let mut peer_info = data::make_peer_info(rng);
+    peer_info.extra_addrs = vec!["[::1]:3030".parse().unwrap()];
+    let admin = AdminOverrides {
+        added_peers: vec![peer_info],
+        banned_peers: vec![data::make_peer_info(rng).id],
+        blacklist: vec!["10.0.0.1".parse().unwrap()],
+    };
+    let got = AdminOverridesRepr::decode(&to_vec::<AdminOverridesRepr>(&admin)).unwrap();
+    assert_eq!(admin, got);
+}
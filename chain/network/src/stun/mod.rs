@@ -16,15 +16,49 @@ pub(crate) type Error = stun::Error;
 
 const QUERY_TIMEOUT: time::Duration = time::Duration::seconds(5);
 
-/// Sends a STUN BINDING request to `addr`.
+/// IP protocol version used to query a STUN server.
+/// A dual-stack machine has a separate public IP for each version.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum IpFamily {
+    V4,
+    V6,
+}
+
+impl IpFamily {
+    fn contains(&self, ip: &std::net::IpAddr) -> bool {
+        match self {
+            Self::V4 => ip.is_ipv4(),
+            Self::V6 => ip.is_ipv6(),
+        }
+    }
+
+    fn unspecified(&self) -> std::net::SocketAddr {
+        match self {
+            Self::V4 => (std::net::Ipv4Addr::UNSPECIFIED, 0).into(),
+            Self::V6 => (std::net::Ipv6Addr::UNSPECIFIED, 0).into(),
+        }
+    }
+}
+
+/// Sends a STUN BINDING request to `addr`, using the IP protocol version `family`.
 /// Returns the result of the query: the IP of this machine as perceived by the STUN server.
 /// It should be used to determine the public IP of this machine.
 pub(crate) async fn query(
     clock: &time::Clock,
     addr: &ServerAddr,
+    family: IpFamily,
 ) -> Result<std::net::IpAddr, Error> {
-    let socket = tokio::net::UdpSocket::bind("[::]:0").await?;
-    socket.connect(addr).await?;
+    let server_addr = tokio::net::lookup_host(addr)
+        .await?
+        .find(|a| family.contains(&a.ip()))
+        .ok_or_else(|| {
+            std::io::Error::new(
+                std::io::ErrorKind::AddrNotAvailable,
+                format!("{addr} has no {family:?} address"),
+            )
+        })?;
+    let socket = tokio::net::UdpSocket::bind(family.unspecified()).await?;
+    socket.connect(server_addr).await?;
     let mut client = stun::client::ClientBuilder::new().with_conn(Arc::new(socket)).build()?;
     let mut msg = stun::message::Message::new();
     msg.new_transaction_id()?;
@@ -22,11 +22,21 @@ pub(crate) struct Server {
 }
 
 impl Server {
-    /// Spawns a new STUN server on localhost interface.
+    /// Spawns a new STUN server on the IPv6 localhost interface.
+    pub async fn new() -> Self {
+        Self::bind("[::1]:0").await
+    }
+
+    /// Spawns a new STUN server on the IPv4 localhost interface.
+    pub async fn new_v4() -> Self {
+        Self::bind("127.0.0.1:0").await
+    }
+
+    /// Spawns a new STUN server on `addr`.
     /// In fact a TURN server is spawned, which implements a superset
     /// of STUN functionality.
-    pub async fn new() -> Self {
-        let server_conn = Arc::new(tokio::net::UdpSocket::bind("[::1]:0").await.unwrap());
+    async fn bind(addr: &str) -> Self {
+        let server_conn = Arc::new(tokio::net::UdpSocket::bind(addr).await.unwrap());
         let server_addr = server_conn.local_addr().unwrap();
 
         Self {
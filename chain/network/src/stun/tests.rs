@@ -7,7 +7,19 @@ async fn test_query() {
     init_test_logger();
     let clock = time::FakeClock::default();
     let server = stun::testonly::Server::new().await;
-    let ip = stun::query(&clock.clock(), &server.addr()).await.unwrap();
+    let ip = stun::query(&clock.clock(), &server.addr(), stun::IpFamily::V6).await.unwrap();
     assert_eq!(std::net::Ipv6Addr::LOCALHOST, ip);
+    // The server has no IPv4 address.
+    assert!(stun::query(&clock.clock(), &server.addr(), stun::IpFamily::V4).await.is_err());
+    server.close().await;
+}
+
+#[tokio::test]
+async fn test_query_v4() {
+    init_test_logger();
+    let clock = time::FakeClock::default();
+    let server = stun::testonly::Server::new_v4().await;
+    let ip = stun::query(&clock.clock(), &server.addr(), stun::IpFamily::V4).await.unwrap();
+    assert_eq!(std::net::Ipv4Addr::LOCALHOST, ip);
     server.close().await;
 }
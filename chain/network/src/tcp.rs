@@ -16,7 +16,16 @@ static RESERVED_LISTENER_ADDRS: Lazy<Mutex<HashMap<std::net::SocketAddr, tokio::
 /// TCP connections established by a node belong to different logical networks (aka tiers),
 /// which serve different purpose.
 // TODO(gprusak): add a link to the design on github docs (but first write those docs).
-#[derive(Clone, Copy, Debug, PartialEq, Eq, strum::AsRefStr)]
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    strum::AsRefStr,
+    borsh::BorshSerialize,
+    borsh::BorshDeserialize,
+)]
 pub enum Tier {
     /// Tier1 connections are established between the BFT consensus participants (or their proxies)
     /// and are reserved exclusively for exchanging BFT consensus messages.
@@ -33,9 +42,17 @@ pub(crate) enum StreamType {
     Outbound { peer_id: PeerId, tier: Tier },
 }
 
+/// Transport of a connection.
+#[derive(Debug)]
+pub(crate) enum Transport {
+    Tcp(tokio::net::TcpStream),
+    /// Used only for TIER1 connections, see `crate::quic`.
+    Quic(quinn::Connection),
+}
+
 #[derive(Debug)]
 pub struct Stream {
-    pub(crate) stream: tokio::net::TcpStream,
+    pub(crate) stream: Transport,
     pub(crate) type_: StreamType,
     /// cached stream.local_addr()
     pub(crate) local_addr: std::net::SocketAddr,
@@ -79,28 +96,70 @@ impl Socket {
 
 impl Stream {
     fn new(stream: tokio::net::TcpStream, type_: StreamType) -> std::io::Result<Self> {
-        Ok(Self { peer_addr: stream.peer_addr()?, local_addr: stream.local_addr()?, stream, type_ })
+        Ok(Self {
+            peer_addr: stream.peer_addr()?,
+            local_addr: stream.local_addr()?,
+            stream: Transport::Tcp(stream),
+            type_,
+        })
+    }
+
+    pub(crate) fn new_quic(
+        conn: quinn::Connection,
+        local_addr: std::net::SocketAddr,
+        type_: StreamType,
+    ) -> Self {
+        Self { peer_addr: conn.remote_address(), local_addr, stream: Transport::Quic(conn), type_ }
+    }
+
+    pub(crate) fn is_quic(&self) -> bool {
+        matches!(self.stream, Transport::Quic(_))
+    }
+
+    /// The underlying TCP stream. Fails for QUIC connections.
+    /// Used by the tools operating on the raw network protocol.
+    pub(crate) fn tcp_mut(&mut self) -> std::io::Result<&mut tokio::net::TcpStream> {
+        match &mut self.stream {
+            Transport::Tcp(stream) => Ok(stream),
+            Transport::Quic(_) => {
+                Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "not a TCP stream"))
+            }
+        }
     }
 
+    /// Connects to the peer. The addresses of the peer are tried one by one,
+    /// from the most preferred one, until a connection is established.
     pub async fn connect(peer_info: &PeerInfo, tier: Tier) -> anyhow::Result<Stream> {
-        let addr = peer_info
-            .addr
-            .ok_or_else(|| anyhow!("Trying to connect to peer with no public address"))?;
-        // The `connect` may take several minutes. This happens when the
-        // `SYN` packet for establishing a TCP connection gets silently
-        // dropped, in which case the default TCP timeout is applied. That's
-        // too long for us, so we shorten it to one second.
-        //
-        // Why exactly a second? It was hard-coded in a library we used
-        // before, so we keep it to preserve behavior. Removing the timeout
-        // completely was observed to break stuff for real on the testnet.
-        let stream = tokio::time::timeout(
-            std::time::Duration::from_secs(1),
-            tokio::net::TcpStream::connect(addr),
-        )
-        .await?
-        .context("TcpStream::connect()")?;
-        Ok(Stream::new(stream, StreamType::Outbound { peer_id: peer_info.id.clone(), tier })?)
+        let mut res = Err(anyhow!("Trying to connect to peer with no public address"));
+        for addr in peer_info.addrs() {
+            // The `connect` may take several minutes. This happens when the
+            // `SYN` packet for establishing a TCP connection gets silently
+            // dropped, in which case the default TCP timeout is applied. That's
+            // too long for us, so we shorten it to one second.
+            //
+            // Why exactly a second? It was hard-coded in a library we used
+            // before, so we keep it to preserve behavior. Removing the timeout
+            // completely was observed to break stuff for real on the testnet.
+            res = async {
+                anyhow::Ok(
+                    tokio::time::timeout(
+                        std::time::Duration::from_secs(1),
+                        tokio::net::TcpStream::connect(addr),
+                    )
+                    .await?
+                    .context("TcpStream::connect()")?,
+                )
+            }
+            .await
+            .with_context(|| format!("connecting to {addr}"));
+            match &res {
+                Ok(_) => break,
+                Err(err) => {
+                    tracing::debug!(target: "network", peer_id = %peer_info.id, %addr, ?err, "failed to connect to an address of the peer")
+                }
+            }
+        }
+        Ok(Stream::new(res?, StreamType::Outbound { peer_id: peer_info.id.clone(), tier })?)
     }
 
     /// Establishes a loopback TCP connection to localhost with random ports.
@@ -108,7 +167,7 @@ impl Stream {
     #[cfg(test)]
     pub async fn loopback(peer_id: PeerId, tier: Tier) -> (Stream, Stream) {
         let listener_addr = ListenerAddr::reserve_for_test();
-        let peer_info = PeerInfo { id: peer_id, addr: Some(*listener_addr), account_id: None };
+        let peer_info = PeerInfo::new(peer_id, *listener_addr);
         let mut listener = listener_addr.listener().unwrap();
         let (outbound, inbound) =
             tokio::join!(Stream::connect(&peer_info, tier), listener.accept());
@@ -218,3 +277,25 @@ impl Listener {
         Stream::new(stream, StreamType::Inbound)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn connect_to_fallback_addr() {
+        let listener_addr = ListenerAddr::reserve_for_test();
+        let mut listener = listener_addr.listener().unwrap();
+        // Nothing listens on the reserved address, so connecting to it fails.
+        let unreachable_addr = ListenerAddr::reserve_for_test();
+        let mut peer_info = PeerInfo::new(PeerId::random(), *unreachable_addr);
+        peer_info.extra_addrs = vec![*listener_addr];
+        let (outbound, inbound) =
+            tokio::join!(Stream::connect(&peer_info, Tier::T2), listener.accept());
+        assert_eq!(*listener_addr, outbound.unwrap().peer_addr);
+        inbound.unwrap();
+
+        peer_info.extra_addrs = vec![];
+        assert!(Stream::connect(&peer_info, Tier::T2).await.is_err());
+    }
+}
@@ -1,10 +1,30 @@
+use crate::client::{
+    BlockApproval, BlockRequest, BlockResponse, ChunkEndorsementMessage, ChunkStateWitnessMessage,
+    ClientSenderForNetwork, ClientSenderForNetworkMessage,
+    PartialEncodedStateWitnessForwardMessage, PartialEncodedStateWitnessMessage, ProcessTxRequest,
+};
+use crate::types::{NetworkRequests, PeerManagerMessageRequest};
+use near_async::messaging::{AsyncSendError, CanSend, MessageWithCallback, SendAsync};
+use near_async::test_loop::delay_sender::DelaySender;
+use near_async::test_loop::event_handler::{LoopEventHandler, LoopHandlerContext, TryIntoOrSelf};
+use near_async::time;
+use near_crypto::{KeyType, SecretKey};
+use near_primitives::block::Block;
+use near_primitives::network::PeerId;
 use near_primitives::types::AccountId;
+use parking_lot::Mutex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::num::NonZeroU64;
+use std::sync::Arc;
 
 /// A multi-instance test using the TestLoop framework can support routing
 /// lookup for network messages, as long as the Data type contains AccountId.
 /// This trait is just a helper for looking up the index.
 pub trait SupportsRoutingLookup {
     fn index_for_account(&self, account: &AccountId) -> usize;
+    fn account_for_index(&self, idx: usize) -> AccountId;
     fn num_accounts(&self) -> usize;
 }
 
@@ -15,7 +35,477 @@ impl<InnerData: AsRef<AccountId>> SupportsRoutingLookup for Vec<InnerData> {
             .unwrap_or_else(|| panic!("Account not found: {}", account))
     }
 
+    fn account_for_index(&self, idx: usize) -> AccountId {
+        self[idx].as_ref().clone()
+    }
+
     fn num_accounts(&self) -> usize {
         self.len()
     }
 }
+
+/// Deterministic PeerId of the node with the given index in a multi-instance test.
+pub fn peer_id_for_index(idx: usize) -> PeerId {
+    PeerId::new(SecretKey::from_seed(KeyType::ED25519, &format!("node{idx}")).public_key())
+}
+
+/// Properties of a directed link between two nodes of a SimulatedNetwork.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinkConfig {
+    /// Time it takes for a message to travel through the link.
+    pub latency: time::Duration,
+    /// Additional random delay, uniformly distributed in [0,jitter].
+    pub jitter: time::Duration,
+    /// Bytes per second that the link can transmit. Messages sent over the link are
+    /// queued, so that a large message delays the messages sent after it.
+    /// None means unlimited.
+    pub bandwidth: Option<NonZeroU64>,
+    /// Probability that a message sent over the link is lost, in [0,1].
+    pub drop_probability: f64,
+}
+
+impl LinkConfig {
+    /// A reliable link with a fixed latency.
+    pub fn with_latency(latency: time::Duration) -> Self {
+        Self { latency, jitter: time::Duration::ZERO, bandwidth: None, drop_probability: 0. }
+    }
+
+    fn validate(&self) {
+        assert!(self.latency >= time::Duration::ZERO, "negative latency in {self:?}");
+        assert!(self.jitter >= time::Duration::ZERO, "negative jitter in {self:?}");
+        assert!(
+            (0. ..=1.).contains(&self.drop_probability),
+            "drop_probability out of [0,1] in {self:?}"
+        );
+    }
+}
+
+struct SimulatedNetworkInner {
+    rng: StdRng,
+    default_link: LinkConfig,
+    links: HashMap<(usize, usize), LinkConfig>,
+    /// Time at which each link will finish transmitting the messages queued so far.
+    busy_until: HashMap<(usize, usize), time::Instant>,
+    /// Partition of each node. Nodes can only communicate within the same partition.
+    /// Nodes not present in the map belong to a common default partition.
+    partitions: HashMap<usize, usize>,
+    dropped_messages: u64,
+}
+
+/// Simulated network layer of a multi-instance TestLoop test.
+///
+/// Decides for each message sent between two nodes whether it is delivered and with
+/// what delay, based on the configuration of the link between them and the current
+/// partitioning of the network. All the randomness comes from the seed, so that
+/// the test is deterministic.
+///
+/// The network is shared between all the routing handlers registered in the test,
+/// and the test can keep a clone to change the network conditions while running,
+/// for example to partition the network and heal it later.
+#[derive(Clone)]
+pub struct SimulatedNetwork(Arc<Mutex<SimulatedNetworkInner>>);
+
+impl SimulatedNetwork {
+    pub fn new(seed: u64, default_link: LinkConfig) -> Self {
+        default_link.validate();
+        Self(Arc::new(Mutex::new(SimulatedNetworkInner {
+            rng: StdRng::seed_from_u64(seed),
+            default_link,
+            links: HashMap::new(),
+            busy_until: HashMap::new(),
+            partitions: HashMap::new(),
+            dropped_messages: 0,
+        })))
+    }
+
+    /// Overrides the configuration of the link from `from` to `to`.
+    pub fn set_link(&self, from: usize, to: usize, config: LinkConfig) {
+        config.validate();
+        self.0.lock().links.insert((from, to), config);
+    }
+
+    /// Splits the network into the given groups of nodes. Messages between nodes
+    /// of different groups are lost. Nodes not listed form one more group.
+    pub fn partition(&self, groups: &[&[usize]]) {
+        let mut inner = self.0.lock();
+        inner.partitions.clear();
+        for (i, group) in groups.iter().enumerate() {
+            for idx in *group {
+                inner.partitions.insert(*idx, i + 1);
+            }
+        }
+    }
+
+    /// Removes the partitioning of the network.
+    pub fn heal(&self) {
+        self.0.lock().partitions.clear();
+    }
+
+    /// Number of messages lost so far, either because of a partition or a random drop.
+    pub fn dropped_messages(&self) -> u64 {
+        self.0.lock().dropped_messages
+    }
+
+    /// Simulates sending a message of `bytes` bytes from `from` to `to` at `now`.
+    /// Returns the delay after which the message is delivered, or None if it is lost.
+    pub fn transmit(
+        &self,
+        now: time::Instant,
+        from: usize,
+        to: usize,
+        bytes: usize,
+    ) -> Option<time::Duration> {
+        let mut inner = self.0.lock();
+        let inner = &mut *inner;
+        let partition = |idx| inner.partitions.get(&idx).copied().unwrap_or(0);
+        if partition(from) != partition(to) {
+            inner.dropped_messages += 1;
+            return None;
+        }
+        let link = inner.links.get(&(from, to)).unwrap_or(&inner.default_link);
+        if link.drop_probability > 0. && inner.rng.gen_bool(link.drop_probability) {
+            inner.dropped_messages += 1;
+            return None;
+        }
+        let mut delay = link.latency;
+        if link.jitter > time::Duration::ZERO {
+            delay += time::Duration::nanoseconds(
+                inner.rng.gen_range(0..=link.jitter.whole_nanoseconds() as i64),
+            );
+        }
+        if let Some(bandwidth) = link.bandwidth {
+            let busy_until = inner.busy_until.entry((from, to)).or_insert(now);
+            let start = (*busy_until).max(now);
+            *busy_until =
+                start + time::Duration::seconds_f64(bytes as f64 / bandwidth.get() as f64);
+            delay += *busy_until - now;
+        }
+        Some(delay)
+    }
+
+    /// Sends the event made of `payload` to the node `to` through the simulated link
+    /// from `from`, unless the message is lost. The size of the message is the borsh
+    /// size of `payload`.
+    pub fn send<Event: 'static, Payload: borsh::BorshSerialize>(
+        &self,
+        context: &LoopHandlerContext<(usize, Event)>,
+        from: usize,
+        to: usize,
+        payload: Payload,
+        make_event: impl FnOnce(Payload) -> Event,
+    ) {
+        let bytes = borsh::object_length(&payload).unwrap();
+        if let Some(delay) = self.transmit(context.clock.now(), from, to, bytes) {
+            context.sender.send_with_delay((to, make_event(payload)), delay);
+        } else {
+            tracing::debug!(target: "test", from, to, bytes, "simulated network dropped a message");
+        }
+    }
+
+    /// Like send(), but returns a sender of messages to the client of node `to`,
+    /// delivering them with the simulated delay. Returns None if the message is lost.
+    fn client_sender<Event: From<ClientSenderForNetworkMessage> + 'static>(
+        &self,
+        clock: &time::Clock,
+        sender: &DelaySender<(usize, Event)>,
+        from: usize,
+        to: usize,
+        payload: &impl borsh::BorshSerialize,
+    ) -> Option<ClientSenderForNetwork> {
+        let bytes = borsh::object_length(payload).unwrap();
+        let Some(delay) = self.transmit(clock.now(), from, to, bytes) else {
+            tracing::debug!(target: "test", from, to, bytes, "simulated network dropped a message");
+            return None;
+        };
+        Some(
+            sender
+                .with_additional_delay(delay)
+                .for_index(to)
+                .into_wrapped_multi_sender::<ClientSenderForNetworkMessage, ClientSenderForNetwork>(
+                ),
+        )
+    }
+}
+
+/// Handles outgoing network messages, and turns them into incoming client messages
+/// delivered over the simulated network.
+pub fn route_network_messages_to_client<
+    Data: SupportsRoutingLookup,
+    Event: TryIntoOrSelf<PeerManagerMessageRequest>
+        + From<PeerManagerMessageRequest>
+        + From<ClientSenderForNetworkMessage>,
+>(
+    network: SimulatedNetwork,
+) -> LoopEventHandler<Data, (usize, Event)> {
+    LoopEventHandler::new(
+        move |event: (usize, Event),
+              data: &mut Data,
+              context: &LoopHandlerContext<(usize, Event)>| {
+            let (idx, event) = event;
+            let message = event.try_into_or_self().map_err(|event| (idx, event.into()))?;
+            let PeerManagerMessageRequest::NetworkRequests(request) = message else {
+                return Err((idx, message.into()));
+            };
+
+            match request {
+                NetworkRequests::Block { block } => {
+                    for other_idx in 0..data.num_accounts() {
+                        if other_idx != idx {
+                            if let Some(sender) = network.client_sender(
+                                &context.clock,
+                                &context.sender,
+                                idx,
+                                other_idx,
+                                &block,
+                            ) {
+                                drop(sender.send_async(BlockResponse {
+                                    block: block.clone(),
+                                    peer_id: peer_id_for_index(idx),
+                                    was_requested: false,
+                                }));
+                            }
+                        }
+                    }
+                }
+                NetworkRequests::Approval { approval_message } => {
+                    let other_idx = data.index_for_account(&approval_message.target);
+                    if other_idx != idx {
+                        if let Some(sender) = network.client_sender(
+                            &context.clock,
+                            &context.sender,
+                            idx,
+                            other_idx,
+                            &approval_message.approval,
+                        ) {
+                            drop(sender.send_async(BlockApproval(
+                                approval_message.approval.clone(),
+                                peer_id_for_index(idx),
+                            )));
+                        }
+                    } else {
+                        tracing::warn!("Dropping message to self");
+                    }
+                }
+                NetworkRequests::ForwardTx(account, transaction) => {
+                    let other_idx = data.index_for_account(&account);
+                    if other_idx != idx {
+                        if let Some(sender) = network.client_sender(
+                            &context.clock,
+                            &context.sender,
+                            idx,
+                            other_idx,
+                            &transaction,
+                        ) {
+                            drop(sender.send_async(ProcessTxRequest {
+                                transaction: transaction.clone(),
+                                is_forwarded: true,
+                                check_only: false,
+                            }));
+                        }
+                    } else {
+                        tracing::warn!("Dropping message to self");
+                    }
+                }
+                NetworkRequests::ChunkEndorsement(target, endorsement) => {
+                    let other_idx = data.index_for_account(&target);
+                    if other_idx != idx {
+                        if let Some(sender) = network.client_sender(
+                            &context.clock,
+                            &context.sender,
+                            idx,
+                            other_idx,
+                            &endorsement,
+                        ) {
+                            drop(sender.send_async(ChunkEndorsementMessage(endorsement.clone())));
+                        }
+                    } else {
+                        tracing::warn!("Dropping message to self");
+                    }
+                }
+                NetworkRequests::ChunkStateWitness(targets, witness) => {
+                    let other_idxes = targets
+                        .iter()
+                        .map(|account| data.index_for_account(account))
+                        .collect::<Vec<_>>();
+                    for other_idx in &other_idxes {
+                        if *other_idx != idx {
+                            if let Some(sender) = network.client_sender(
+                                &context.clock,
+                                &context.sender,
+                                idx,
+                                *other_idx,
+                                &witness,
+                            ) {
+                                drop(sender.send_async(ChunkStateWitnessMessage(witness.clone())));
+                            }
+                        } else {
+                            tracing::warn!(
+                                "ChunkStateWitness asked to send to nodes {:?}, but {} is ourselves, so skipping that",
+                                other_idxes, idx);
+                        }
+                    }
+                }
+                NetworkRequests::PartialEncodedStateWitness(parts) => {
+                    for (target, part) in parts {
+                        let other_idx = data.index_for_account(&target);
+                        if other_idx != idx {
+                            if let Some(sender) = network.client_sender(
+                                &context.clock,
+                                &context.sender,
+                                idx,
+                                other_idx,
+                                &part,
+                            ) {
+                                drop(sender.send_async(PartialEncodedStateWitnessMessage(part)));
+                            }
+                        } else {
+                            tracing::warn!("Dropping message to self");
+                        }
+                    }
+                }
+                NetworkRequests::PartialEncodedStateWitnessForward(targets, part) => {
+                    for target in targets {
+                        let other_idx = data.index_for_account(&target);
+                        if other_idx != idx {
+                            if let Some(sender) = network.client_sender(
+                                &context.clock,
+                                &context.sender,
+                                idx,
+                                other_idx,
+                                &part,
+                            ) {
+                                drop(sender.send_async(PartialEncodedStateWitnessForwardMessage {
+                                    part: part.clone(),
+                                    sender_accounts: vec![data.account_for_index(idx)],
+                                }));
+                            }
+                        } else {
+                            tracing::warn!("Dropping message to self");
+                        }
+                    }
+                }
+                NetworkRequests::BlockRequest { hash, peer_id } => {
+                    let other_idx = (0..data.num_accounts())
+                        .find(|other_idx| peer_id_for_index(*other_idx) == peer_id)
+                        .expect("BlockRequest to an unknown peer");
+                    let Some(delay) = network.transmit(
+                        context.clock.now(),
+                        idx,
+                        other_idx,
+                        borsh::object_length(&hash).unwrap(),
+                    ) else {
+                        tracing::debug!(target: "test", from = idx, to = other_idx, "simulated network dropped a BlockRequest");
+                        return Ok(());
+                    };
+                    // The response travels back over the simulated network as well.
+                    let network = network.clone();
+                    let clock = context.clock.clone();
+                    let sender = context.sender.clone();
+                    let callback = move |block: Result<Option<Box<Block>>, AsyncSendError>| {
+                        let Ok(Some(block)) = block else {
+                            return;
+                        };
+                        if let Some(client) =
+                            network.client_sender(&clock, &sender, other_idx, idx, &block)
+                        {
+                            drop(client.send_async(BlockResponse {
+                                block: *block,
+                                peer_id: peer_id_for_index(other_idx),
+                                was_requested: true,
+                            }));
+                        }
+                    };
+                    context.sender.with_additional_delay(delay).for_index(other_idx).send(
+                        ClientSenderForNetworkMessage::_block_request(MessageWithCallback {
+                            message: BlockRequest(hash),
+                            callback: Box::new(callback),
+                        }),
+                    );
+                }
+                // TODO: Support more network message types as we expand the tests.
+                _ => return Err((idx, PeerManagerMessageRequest::NetworkRequests(request).into())),
+            }
+
+            Ok(())
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LinkConfig, SimulatedNetwork};
+    use near_async::time;
+    use std::num::NonZeroU64;
+
+    fn latency() -> time::Duration {
+        time::Duration::milliseconds(10)
+    }
+
+    #[test]
+    fn bandwidth_queues_messages() {
+        let clock = time::FakeClock::default();
+        let net = SimulatedNetwork::new(
+            0,
+            LinkConfig { bandwidth: NonZeroU64::new(1000), ..LinkConfig::with_latency(latency()) },
+        );
+        let now = clock.now();
+        assert_eq!(
+            net.transmit(now, 0, 1, 500),
+            Some(latency() + time::Duration::milliseconds(500))
+        );
+        // The second message waits for the first one to be transmitted.
+        assert_eq!(net.transmit(now, 0, 1, 500), Some(latency() + time::Duration::seconds(1)));
+        // Links are independent.
+        assert_eq!(
+            net.transmit(now, 1, 0, 500),
+            Some(latency() + time::Duration::milliseconds(500))
+        );
+        clock.advance(time::Duration::seconds(5));
+        assert_eq!(
+            net.transmit(clock.now(), 0, 1, 500),
+            Some(latency() + time::Duration::milliseconds(500))
+        );
+    }
+
+    #[test]
+    fn partitions() {
+        let now = time::FakeClock::default().now();
+        let net = SimulatedNetwork::new(0, LinkConfig::with_latency(latency()));
+        net.partition(&[&[0, 1], &[2]]);
+        assert_eq!(net.transmit(now, 0, 1, 1), Some(latency()));
+        assert_eq!(net.transmit(now, 1, 2, 1), None);
+        assert_eq!(net.transmit(now, 2, 3, 1), None);
+        assert_eq!(net.transmit(now, 3, 4, 1), Some(latency()));
+        assert_eq!(net.dropped_messages(), 2);
+        net.heal();
+        assert_eq!(net.transmit(now, 1, 2, 1), Some(latency()));
+    }
+
+    #[test]
+    fn drops_and_jitter_are_deterministic() {
+        let now = time::FakeClock::default().now();
+        let link = LinkConfig {
+            jitter: time::Duration::milliseconds(5),
+            drop_probability: 0.3,
+            ..LinkConfig::with_latency(latency())
+        };
+        let run = |seed| {
+            let net = SimulatedNetwork::new(seed, link.clone());
+            (0..100).map(|_| net.transmit(now, 0, 1, 1)).collect::<Vec<_>>()
+        };
+        let got = run(42);
+        assert_eq!(got, run(42));
+        let delivered: Vec<_> = got.iter().flatten().collect();
+        assert!(!delivered.is_empty() && delivered.len() < got.len());
+        for delay in delivered {
+            assert!(latency() <= *delay && *delay <= latency() + link.jitter);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "drop_probability")]
+    fn rejects_invalid_drop_probability() {
+        let link = LinkConfig { drop_probability: 1.5, ..LinkConfig::with_latency(latency()) };
+        SimulatedNetwork::new(0, link);
+    }
+}
@@ -29,10 +29,11 @@ impl Stream {
 
     pub async fn read(&mut self) -> Result<PeerMessage, std::io::Error> {
         'read: loop {
-            let n = self.stream.stream.read_u32_le().await? as usize;
+            let stream = self.stream.tcp_mut()?;
+            let n = stream.read_u32_le().await? as usize;
             let mut buf = BytesMut::new();
             buf.resize(n, 0);
-            self.stream.stream.read_exact(&mut buf[..]).await?;
+            stream.read_exact(&mut buf[..]).await?;
             for enc in [Encoding::Proto, Encoding::Borsh] {
                 if let Ok(msg) = PeerMessage::deserialize(enc, &buf[..]) {
                     // If deserialize() succeeded but we expected different encoding, ignore the
@@ -61,8 +62,9 @@ impl Stream {
     }
 
     async fn write_encoded(&mut self, msg: &[u8]) {
-        self.stream.stream.write_u32_le(msg.len() as u32).await.unwrap();
-        self.stream.stream.write_all(msg).await.unwrap();
-        self.stream.stream.flush().await.unwrap();
+        let stream = self.stream.tcp_mut().unwrap();
+        stream.write_u32_le(msg.len() as u32).await.unwrap();
+        stream.write_all(msg).await.unwrap();
+        stream.flush().await.unwrap();
     }
 }
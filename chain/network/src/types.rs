@@ -5,9 +5,11 @@ pub use crate::network_protocol::{
 };
 /// Exported types, which are part of network protocol.
 pub use crate::network_protocol::{
-    Edge, PartialEdgeInfo, PartialEncodedChunkForwardMsg, PartialEncodedChunkRequestMsg,
-    PartialEncodedChunkResponseMsg, PeerChainInfoV2, PeerInfo, SnapshotHostInfo, StateResponseInfo,
-    StateResponseInfoV1, StateResponseInfoV2,
+    Edge, EpochBlockProducers, EpochBlockProducersResponse, EpochSyncInfoResponse,
+    LightClientBlockResponse, PartialEdgeInfo, PartialEncodedChunkForwardMsg,
+    PartialEncodedChunkRequestMsg, PartialEncodedChunkResponseMsg, PeerChainInfoV2, PeerInfo,
+    SnapshotHostInfo, StateResponseInfo, StateResponseInfoV1, StateResponseInfoV2,
+    MAX_EPOCH_SYNC_INFOS_PER_RESPONSE,
 };
 use crate::routing::routing_table_view::RoutingTableInfo;
 pub use crate::state_sync::{StateSync, StateSyncResponse};
@@ -19,9 +21,11 @@ use near_primitives::challenge::Challenge;
 use near_primitives::hash::CryptoHash;
 use near_primitives::network::{AnnounceAccount, PeerId};
 use near_primitives::sharding::PartialEncodedChunkWithArcReceipts;
-use near_primitives::stateless_validation::{ChunkEndorsement, ChunkStateWitness};
+use near_primitives::stateless_validation::{
+    ChunkEndorsement, ChunkStateWitness, PartialEncodedStateWitness,
+};
 use near_primitives::transaction::SignedTransaction;
-use near_primitives::types::{AccountId, BlockHeight, EpochHeight, ShardId};
+use near_primitives::types::{AccountId, BlockHeight, EpochHeight, EpochId, ShardId};
 use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::net::SocketAddr;
@@ -68,6 +72,11 @@ pub enum ReasonForBan {
     Blacklisted = 14,
     ProvidedNotEnoughHeaders = 15,
     BadChunkStateWitness = 16,
+    LowReputation = 17,
+    /// Banned by the node operator, via the admin RPC.
+    ManualBan = 18,
+    /// Sent an EpochSyncInfo which doesn't pass verification.
+    BadEpochSyncInfo = 19,
 }
 
 /// Banning signal sent from Peer instance to PeerManager
@@ -224,6 +233,8 @@ pub enum NetworkRequests {
     BlockRequest { hash: CryptoHash, peer_id: PeerId },
     /// Request given block headers.
     BlockHeadersRequest { hashes: Vec<CryptoHash>, peer_id: PeerId },
+    /// Request `EpochSyncInfo`s starting with the given epoch.
+    EpochSyncRequest { epoch_id: EpochId, peer_id: PeerId },
     /// Request state header for given shard at given state root.
     StateRequestHeader { shard_id: ShardId, sync_hash: CryptoHash, peer_id: PeerId },
     /// Request state part for given shard at given state root.
@@ -260,6 +271,10 @@ pub enum NetworkRequests {
     ChunkStateWitness(Vec<AccountId>, ChunkStateWitness),
     /// Message for a chunk endorsement, sent by a chunk validator to the block producer.
     ChunkEndorsement(AccountId, ChunkEndorsement),
+    /// Parts of a chunk's state witness, sent by the chunk producer to each chunk validator.
+    PartialEncodedStateWitness(Vec<(AccountId, PartialEncodedStateWitness)>),
+    /// A part of a chunk's state witness, forwarded by a chunk validator to the other chunk validators.
+    PartialEncodedStateWitnessForward(Vec<AccountId>, PartialEncodedStateWitness),
 }
 
 /// Combines peer address info, chain.
@@ -342,6 +357,20 @@ pub struct ConnectedPeerInfo {
     pub peer_type: PeerType,
     /// Nonce used for the connection with the peer.
     pub nonce: u64,
+    /// Outbound traffic of the rate limited message classes.
+    pub send_classes: Vec<SendClassInfo>,
+}
+
+/// Outbound traffic of a rate limited message class on a single connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SendClassInfo {
+    pub class: &'static str,
+    /// Number of bytes sent since the connection was established.
+    pub sent_bytes: u64,
+    /// Number of messages delayed because of the rate limit.
+    pub delayed_messages: u64,
+    /// Number of messages dropped because of the rate limit.
+    pub dropped_messages: u64,
 }
 
 #[derive(Debug, Clone, actix::MessageResponse, PartialEq, Eq)]
@@ -1,8 +1,10 @@
 use std::collections::btree_map::Entry;
-use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
+use crate::selection::{new_selector, TransactionSelector};
 use crate::types::{PoolKey, TransactionGroup, TransactionGroupIterator};
 
+use near_chain_configs::TransactionSelectionStrategy;
 use near_crypto::PublicKey;
 use near_o11y::metrics::prometheus::core::{AtomicI64, GenericGauge};
 use near_primitives::epoch_manager::RngSeed;
@@ -12,6 +14,7 @@ use near_primitives::types::AccountId;
 use std::ops::Bound;
 
 mod metrics;
+pub mod selection;
 pub mod types;
 
 #[derive(Debug, PartialEq)]
@@ -122,6 +125,15 @@ impl TransactionPool {
         PoolIteratorWrapper::new(self)
     }
 
+    /// Same as `pool_iterator`, but the transaction groups are iterated in the order defined by
+    /// the given strategy.
+    pub fn pool_iterator_with_strategy(
+        &mut self,
+        strategy: TransactionSelectionStrategy,
+    ) -> PoolIteratorWrapper<'_> {
+        PoolIteratorWrapper::with_selector(self, new_selector(strategy))
+    }
+
     /// Removes given transactions from the pool.
     ///
     /// In practice, used to evict transactions that have already been included into the block or
@@ -184,90 +196,81 @@ pub struct PoolIteratorWrapper<'a> {
     /// Mutable reference to the pool, to avoid exposing it while the iterator exists.
     pool: &'a mut TransactionPool,
 
-    /// Queue of transaction groups. Each group there is sorted by nonce.
-    sorted_groups: VecDeque<TransactionGroup>,
+    /// Transaction groups pulled from the pool. Each group there is sorted by nonce.
+    sorted_groups: Vec<TransactionGroup>,
+
+    /// Decides which of the sorted groups is returned next.
+    selector: Box<dyn TransactionSelector>,
 }
 
 impl<'a> PoolIteratorWrapper<'a> {
     pub fn new(pool: &'a mut TransactionPool) -> Self {
-        Self { pool, sorted_groups: Default::default() }
+        Self::with_selector(pool, new_selector(TransactionSelectionStrategy::default()))
+    }
+
+    /// Iterates over transaction groups in the order decided by `selector`.
+    pub fn with_selector(
+        pool: &'a mut TransactionPool,
+        selector: Box<dyn TransactionSelector>,
+    ) -> Self {
+        Self { pool, sorted_groups: Default::default(), selector }
+    }
+
+    /// Pulls the transaction group following the last used key from the pool, sorts
+    /// transactions in it and adds it to the sorted groups.
+    fn pull_group(&mut self) {
+        let key = *self
+            .pool
+            .transactions
+            .range((Bound::Excluded(self.pool.last_used_key), Bound::Unbounded))
+            .next()
+            .map(|(k, _v)| k)
+            .unwrap_or_else(|| {
+                self.pool.transactions.keys().next().expect("the pool is checked to be not empty")
+            });
+        self.pool.last_used_key = key;
+        let mut transactions = self.pool.transactions.remove(&key).expect("just checked existence");
+        transactions.sort_by_key(|st| std::cmp::Reverse(st.transaction.nonce));
+        let group = TransactionGroup {
+            key,
+            transactions,
+            removed_transaction_hashes: vec![],
+            removed_transaction_size: 0,
+        };
+        self.selector.add_group(self.sorted_groups.len(), &group);
+        self.sorted_groups.push(group);
     }
 }
 
 /// The iterator works with the following algorithm:
-/// On next(), the iterator tries to get a transaction group from the pool, sorts transactions in
-/// it, and add it to the back of the sorted groups queue.
+/// On next(), the iterator asks the selector for the group to return. The selector may ask for
+/// more groups first, in which case the iterator pulls the group following the last used key
+/// from the pool, sorts transactions in it and adds it to the sorted groups.
 /// Remembers the last used key, so it can continue from the next key.
 ///
-/// If the pool is empty, the iterator gets the group from the front of the sorted groups queue.
-///
-/// If this group is empty (no transactions left inside), then the iterator discards it and
-/// updates `unique_transactions` in the pool. Then gets the next one.
-///
-/// Once a non-empty group is found, this group is pushed to the back of the sorted groups queue
-/// and the iterator returns a mutable reference to this group.
-///
-/// If the sorted groups queue is empty, the iterator returns None.
+/// Once the pool is empty and the selector doesn't return a group, the iterator returns None.
+/// The default selector returns the groups in the order they are pulled, and then in turns
+/// skipping the groups without transactions left.
 ///
 /// When the iterator is dropped, `unique_transactions` in the pool is updated for every group.
-/// And all non-empty group from the sorted groups queue are inserted back into the pool.
+/// And all non-empty group from the sorted groups are inserted back into the pool.
 impl<'a> TransactionGroupIterator for PoolIteratorWrapper<'a> {
     fn next(&mut self) -> Option<&mut TransactionGroup> {
-        if !self.pool.transactions.is_empty() {
-            let key = *self
-                .pool
-                .transactions
-                .range((Bound::Excluded(self.pool.last_used_key), Bound::Unbounded))
-                .next()
-                .map(|(k, _v)| k)
-                .unwrap_or_else(|| {
-                    self.pool
-                        .transactions
-                        .keys()
-                        .next()
-                        .expect("we've just checked that the map is not empty")
-                });
-            self.pool.last_used_key = key;
-            let mut transactions =
-                self.pool.transactions.remove(&key).expect("just checked existence");
-            transactions.sort_by_key(|st| std::cmp::Reverse(st.transaction.nonce));
-            self.sorted_groups.push_back(TransactionGroup {
-                key,
-                transactions,
-                removed_transaction_hashes: vec![],
-                removed_transaction_size: 0,
-            });
-            Some(self.sorted_groups.back_mut().expect("just pushed"))
-        } else {
-            while let Some(sorted_group) = self.sorted_groups.pop_front() {
-                if sorted_group.transactions.is_empty() {
-                    for hash in sorted_group.removed_transaction_hashes {
-                        self.pool.unique_transactions.remove(&hash);
-                    }
-                    // See the comment in `insert_transaction` where we increase the size for reasoning
-                    // why panicing here catches a logic error.
-                    self.pool.total_transaction_size = self
-                        .pool
-                        .total_transaction_size
-                        .checked_sub(sorted_group.removed_transaction_size)
-                        .expect("Total transaction size dropped below zero");
-
-                    self.pool
-                        .transaction_pool_count_metric
-                        .set(self.pool.unique_transactions.len() as i64);
-                    self.pool.transaction_pool_size_metric.set(self.pool.transaction_size() as i64);
-                } else {
-                    self.sorted_groups.push_back(sorted_group);
-                    return Some(self.sorted_groups.back_mut().expect("just pushed"));
-                }
+        loop {
+            let pool_drained = self.pool.transactions.is_empty();
+            if let Some(index) = self.selector.select(&self.sorted_groups, pool_drained) {
+                return Some(&mut self.sorted_groups[index]);
             }
-            None
+            if pool_drained {
+                return None;
+            }
+            self.pull_group();
         }
     }
 }
 
 /// When a pool iterator is dropped, all remaining non empty transaction groups from the sorted
-/// groups queue are inserted back into the pool. And removed transactions hashes from groups are
+/// groups are inserted back into the pool. And removed transactions hashes from groups are
 /// removed from the pool's unique_transactions.
 impl<'a> Drop for PoolIteratorWrapper<'a> {
     fn drop(&mut self) {
@@ -595,6 +598,129 @@ mod tests {
         assert_ne!(nonces, new_nonces);
     }
 
+    fn prepare_transactions_with_strategy(
+        pool: &mut TransactionPool,
+        strategy: TransactionSelectionStrategy,
+        max_number_of_transactions: usize,
+    ) -> Vec<SignedTransaction> {
+        let mut res = vec![];
+        let mut pool_iter = pool.pool_iterator_with_strategy(strategy);
+        while res.len() < max_number_of_transactions {
+            if let Some(iter) = pool_iter.next() {
+                if let Some(tx) = iter.next() {
+                    res.push(tx);
+                }
+            } else {
+                break;
+            }
+        }
+        res
+    }
+
+    /// Transactions which prepay more gas are taken first, but always in nonce order within
+    /// a group.
+    #[test]
+    fn test_prepaid_gas_descending() {
+        let signer_id: AccountId = "alice.near".parse().unwrap();
+        let signer =
+            Arc::new(InMemorySigner::from_seed(signer_id.clone(), KeyType::ED25519, "alice.near"));
+        let calls = [(1, 10), (2, 40), (3, 30)].into_iter().map(|(nonce, gas)| {
+            SignedTransaction::call(
+                nonce,
+                signer_id.clone(),
+                "bob.near".parse().unwrap(),
+                &*signer,
+                0,
+                "method".to_string(),
+                vec![],
+                gas,
+                CryptoHash::default(),
+            )
+        });
+        let mut transactions = generate_transactions("carol.near", "carol.near", 1, 2);
+        transactions.extend(calls);
+
+        let mut pool = TransactionPool::new(TEST_SEED, None, "");
+        for tx in transactions {
+            assert_eq!(pool.insert_transaction(tx), InsertTransactionResult::Success);
+        }
+        let txs = prepare_transactions_with_strategy(
+            &mut pool,
+            TransactionSelectionStrategy::PrepaidGasDescending,
+            10,
+        );
+        let txs: Vec<_> = txs
+            .iter()
+            .map(|tx| (tx.transaction.signer_id.as_str(), tx.transaction.nonce))
+            .collect();
+        assert_eq!(
+            txs,
+            vec![
+                ("alice.near", 1),
+                ("alice.near", 2),
+                ("alice.near", 3),
+                ("carol.near", 1),
+                ("carol.near", 2)
+            ]
+        );
+        assert_eq!(pool.len(), 0);
+        assert_eq!(pool.transaction_size(), 0);
+    }
+
+    /// A signer with two access keys gets the same share as a signer with one access key.
+    #[test]
+    fn test_fair_share_per_signer() {
+        let mut transactions = generate_transactions("alice.near", "alice.near", 1, 10);
+        transactions.extend(generate_transactions("alice.near", "alice2.near", 21, 30));
+        transactions.extend(generate_transactions("bob.near", "bob.near", 41, 50));
+
+        let mut pool = TransactionPool::new(TEST_SEED, None, "");
+        for tx in transactions {
+            assert_eq!(pool.insert_transaction(tx), InsertTransactionResult::Success);
+        }
+        let txs = prepare_transactions_with_strategy(
+            &mut pool,
+            TransactionSelectionStrategy::FairSharePerSigner,
+            10,
+        );
+        let mut nonces: Vec<_> = txs.iter().map(|tx| tx.transaction.nonce).collect();
+        nonces.sort();
+        assert_eq!(nonces[5..], [41, 42, 43, 44, 45]);
+        // The access keys of alice.near take turns, starting with either of them.
+        assert!(nonces[..5] == [1, 2, 3, 21, 22] || nonces[..5] == [1, 2, 21, 22, 23]);
+        assert_eq!(pool.len(), 20);
+    }
+
+    /// Selectors don't pull the whole pool before returning the first group, so that chunk
+    /// producers can stop adding transactions when they hit the time limit.
+    #[test]
+    fn test_selection_is_incremental() {
+        let num_groups = 2 * selection::INITIAL_LOOKAHEAD_GROUPS;
+        for (strategy, first_pulled) in [
+            (TransactionSelectionStrategy::FifoPerGroup, 1),
+            (
+                TransactionSelectionStrategy::PrepaidGasDescending,
+                selection::INITIAL_LOOKAHEAD_GROUPS,
+            ),
+            (TransactionSelectionStrategy::FairSharePerSigner, selection::INITIAL_LOOKAHEAD_GROUPS),
+        ] {
+            let mut pool = TransactionPool::new(TEST_SEED, None, "");
+            for i in 0..num_groups {
+                let signer_id = format!("user{i}.near");
+                for tx in generate_transactions(&signer_id, &signer_id, 1, 1) {
+                    assert_eq!(pool.insert_transaction(tx), InsertTransactionResult::Success);
+                }
+            }
+            let mut pool_iter = pool.pool_iterator_with_strategy(strategy);
+            assert!(pool_iter.next().unwrap().next().is_some());
+            assert_eq!(pool_iter.pool.transactions.len(), num_groups - first_pulled);
+            assert!(pool_iter.next().unwrap().next().is_some());
+            assert_eq!(pool_iter.pool.transactions.len(), num_groups - first_pulled - 1);
+            drop(pool_iter);
+            assert_eq!(pool.len(), num_groups - 2);
+        }
+    }
+
     #[test]
     fn test_transaction_pool_size() {
         let mut pool = TransactionPool::new(TEST_SEED, None, "");
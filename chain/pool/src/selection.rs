@@ -0,0 +1,246 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+use crate::types::TransactionGroup;
+
+use near_chain_configs::TransactionSelectionStrategy;
+use near_primitives::action::Action;
+use near_primitives::transaction::SignedTransaction;
+use near_primitives::types::{AccountId, Gas};
+
+/// Number of groups pulled from the pool before a selector which compares groups returns the
+/// first one. After that, such a selector pulls one more group each time it returns a group.
+///
+/// Chunk producers check `produce_chunk_add_transactions_time_limit` between the groups returned
+/// by the pool iterator, so the selectors must never pull the whole pool at once.
+pub const INITIAL_LOOKAHEAD_GROUPS: usize = 64;
+
+/// Transactions of the same signer access key, sorted by nonce, as seen by a selector.
+///
+/// The pool selects between `TransactionGroup`s. The trait lets tools, like the congestion
+/// model, run the same selectors over their own transactions.
+pub trait SelectionGroup {
+    /// Identifies the signer account of the group.
+    type Signer: Clone + Eq + Hash;
+
+    /// Returns the signer account of the group, or `None` if the group has no transactions left.
+    fn signer(&self) -> Option<Self::Signer>;
+
+    /// Returns the gas prepaid by the transaction with the smallest nonce, or `None` if the group
+    /// has no transactions left.
+    fn next_prepaid_gas(&self) -> Option<Gas>;
+
+    fn is_empty(&self) -> bool;
+}
+
+impl SelectionGroup for TransactionGroup {
+    type Signer = AccountId;
+
+    fn signer(&self) -> Option<AccountId> {
+        self.transactions.last().map(|tx| tx.transaction.signer_id.clone())
+    }
+
+    fn next_prepaid_gas(&self) -> Option<Gas> {
+        self.transactions.last().map(prepaid_gas)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+}
+
+/// Decides which transaction group the pool iterator returns next.
+///
+/// Groups are pulled from the pool one by one, in the random order of the pool keys starting
+/// after the last used key. The caller takes transactions from the returned group in nonce
+/// order, so a selector only decides between groups.
+pub trait TransactionSelector<G: SelectionGroup = TransactionGroup> {
+    /// Called when a group is pulled from the pool. `index` is the position of the group in
+    /// `groups` passed to `select`. Transactions of the group are already sorted by nonce.
+    fn add_group(&mut self, index: usize, group: &G);
+
+    /// Returns the index of the group to take the next transaction from. Groups returned before
+    /// may have been drained since.
+    /// `pool_drained` tells whether all groups were pulled from the pool. Returning `None` before
+    /// that pulls one more group from the pool, after that it ends the iteration. A selector
+    /// should only ask for a bounded number of groups before returning one, see
+    /// `INITIAL_LOOKAHEAD_GROUPS`.
+    fn select(&mut self, groups: &[G], pool_drained: bool) -> Option<usize>;
+}
+
+/// Creates the built-in selector for the given strategy.
+pub fn new_selector<G: SelectionGroup + 'static>(
+    strategy: TransactionSelectionStrategy,
+) -> Box<dyn TransactionSelector<G>> {
+    match strategy {
+        TransactionSelectionStrategy::FifoPerGroup => Box::<FifoPerGroup>::default(),
+        TransactionSelectionStrategy::PrepaidGasDescending => {
+            Box::<PrepaidGasDescending>::default()
+        }
+        TransactionSelectionStrategy::FairSharePerSigner => {
+            Box::<FairSharePerSigner<G::Signer>>::default()
+        }
+    }
+}
+
+/// Takes one transaction from each group in turns, in the order the groups are pulled from the
+/// pool. Groups are pulled lazily, so the iteration continues from the last used key next time.
+#[derive(Default)]
+pub struct FifoPerGroup {
+    /// Groups which were pulled from the pool but not returned yet.
+    new_groups: VecDeque<usize>,
+    /// Groups which were returned, in the order they are returned again.
+    returned_groups: VecDeque<usize>,
+}
+
+impl<G: SelectionGroup> TransactionSelector<G> for FifoPerGroup {
+    fn add_group(&mut self, index: usize, _group: &G) {
+        self.new_groups.push_back(index);
+    }
+
+    fn select(&mut self, groups: &[G], pool_drained: bool) -> Option<usize> {
+        if let Some(index) = self.new_groups.pop_front() {
+            self.returned_groups.push_back(index);
+            return Some(index);
+        }
+        if !pool_drained {
+            return None;
+        }
+        while let Some(index) = self.returned_groups.pop_front() {
+            if !groups[index].is_empty() {
+                self.returned_groups.push_back(index);
+                return Some(index);
+            }
+        }
+        None
+    }
+}
+
+/// Counts the groups pulled since the last returned group, so that selectors comparing groups
+/// return one after `INITIAL_LOOKAHEAD_GROUPS` groups first and after one group later on.
+#[derive(Default)]
+struct Lookahead {
+    num_pulled: usize,
+    returned_any: bool,
+}
+
+impl Lookahead {
+    fn on_pull(&mut self) {
+        self.num_pulled += 1;
+    }
+
+    fn on_return(&mut self) {
+        self.num_pulled = 0;
+        self.returned_any = true;
+    }
+
+    /// Whether one more group should be pulled from the pool before returning a group.
+    fn wants_more(&self, pool_drained: bool) -> bool {
+        let limit = if self.returned_any { 1 } else { INITIAL_LOOKAHEAD_GROUPS };
+        !pool_drained && self.num_pulled < limit
+    }
+}
+
+/// Takes the transaction which prepays the most gas first, among the transactions with the
+/// smallest nonce of each pulled group. Ties are broken by the order the groups are pulled in.
+///
+/// Transactions don't set their own gas price, every transaction in a chunk pays the gas price
+/// of the block. So this doesn't rank by the fee the signer pays per unit of gas, but by the gas
+/// the signer reserves for function calls, which is only burnt if the calls use it.
+#[derive(Default)]
+pub struct PrepaidGasDescending {
+    /// Groups by the prepaid gas of their next transaction, except the last returned group.
+    groups_by_gas: BinaryHeap<(Gas, Reverse<usize>)>,
+    /// The last returned group, whose next transaction is only known on the following call.
+    returned_group: Option<usize>,
+    lookahead: Lookahead,
+}
+
+impl PrepaidGasDescending {
+    fn push_group<G: SelectionGroup>(&mut self, index: usize, group: &G) {
+        if let Some(gas) = group.next_prepaid_gas() {
+            self.groups_by_gas.push((gas, Reverse(index)));
+        }
+    }
+}
+
+impl<G: SelectionGroup> TransactionSelector<G> for PrepaidGasDescending {
+    fn add_group(&mut self, index: usize, group: &G) {
+        self.lookahead.on_pull();
+        self.push_group(index, group);
+    }
+
+    fn select(&mut self, groups: &[G], pool_drained: bool) -> Option<usize> {
+        if let Some(index) = self.returned_group.take() {
+            self.push_group(index, &groups[index]);
+        }
+        if self.lookahead.wants_more(pool_drained) {
+            return None;
+        }
+        let (_, Reverse(index)) = self.groups_by_gas.pop()?;
+        self.returned_group = Some(index);
+        self.lookahead.on_return();
+        Some(index)
+    }
+}
+
+/// Takes one transaction from each signer account in turns, in the order the signers are first
+/// pulled from the pool. Groups of the same signer, which differ by access key, take turns within
+/// the signer's share. Signers pulled later join the turns as they are pulled.
+pub struct FairSharePerSigner<S = AccountId> {
+    /// Signers in the order of their turns.
+    signers: VecDeque<S>,
+    /// Groups of each signer in the order of their turns.
+    signer_groups: HashMap<S, VecDeque<usize>>,
+    lookahead: Lookahead,
+}
+
+impl<S> Default for FairSharePerSigner<S> {
+    fn default() -> Self {
+        Self {
+            signers: VecDeque::new(),
+            signer_groups: HashMap::new(),
+            lookahead: Lookahead::default(),
+        }
+    }
+}
+
+impl<G: SelectionGroup> TransactionSelector<G> for FairSharePerSigner<G::Signer> {
+    fn add_group(&mut self, index: usize, group: &G) {
+        self.lookahead.on_pull();
+        let Some(signer) = group.signer() else {
+            return;
+        };
+        if !self.signer_groups.contains_key(&signer) {
+            self.signers.push_back(signer.clone());
+        }
+        self.signer_groups.entry(signer).or_default().push_back(index);
+    }
+
+    fn select(&mut self, groups: &[G], pool_drained: bool) -> Option<usize> {
+        if self.lookahead.wants_more(pool_drained) {
+            return None;
+        }
+        while let Some(signer) = self.signers.pop_front() {
+            let signer_groups =
+                self.signer_groups.get_mut(&signer).expect("every signer has groups");
+            while let Some(index) = signer_groups.pop_front() {
+                if !groups[index].is_empty() {
+                    signer_groups.push_back(index);
+                    self.signers.push_back(signer);
+                    self.lookahead.on_return();
+                    return Some(index);
+                }
+            }
+            self.signer_groups.remove(&signer);
+        }
+        // All pulled groups are drained, which pulls one more group if the pool has any.
+        None
+    }
+}
+
+/// Total gas the transaction prepays for function calls.
+fn prepaid_gas(tx: &SignedTransaction) -> Gas {
+    tx.transaction.actions.iter().map(Action::get_prepaid_gas).fold(0, Gas::saturating_add)
+}
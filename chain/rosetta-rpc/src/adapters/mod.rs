@@ -407,6 +407,7 @@ impl From<NearActions> for Vec<crate::models::Operation> {
                         validated_operations::DeployContractOperation {
                             account: receiver_account_identifier.clone(),
                             code: action.code,
+                            expected_code_hash: None,
                         }
                         .into_related_operation(
                             crate::models::OperationIdentifier::new(&operations),
@@ -415,6 +416,56 @@ impl From<NearActions> for Vec<crate::models::Operation> {
                     );
                 }
 
+                near_primitives::transaction::Action::DeployContractWithCodeHashCheck(action) => {
+                    let initiate_deploy_contract_operation_id =
+                        crate::models::OperationIdentifier::new(&operations);
+                    operations.push(
+                        validated_operations::InitiateDeployContractOperation {
+                            sender_account: sender_account_identifier.clone(),
+                        }
+                        .into_operation(initiate_deploy_contract_operation_id.clone()),
+                    );
+
+                    operations.push(
+                        validated_operations::DeployContractOperation {
+                            account: receiver_account_identifier.clone(),
+                            code: action.code,
+                            expected_code_hash: Some(action.expected_code_hash),
+                        }
+                        .into_related_operation(
+                            crate::models::OperationIdentifier::new(&operations),
+                            vec![initiate_deploy_contract_operation_id],
+                        ),
+                    );
+                }
+
+                near_primitives::transaction::Action::DeployGlobalContract(action) => {
+                    let initiate_deploy_contract_operation_id =
+                        crate::models::OperationIdentifier::new(&operations);
+                    operations.push(
+                        validated_operations::InitiateDeployContractOperation {
+                            sender_account: sender_account_identifier.clone(),
+                        }
+                        .into_operation(initiate_deploy_contract_operation_id.clone()),
+                    );
+
+                    operations.push(
+                        validated_operations::DeployContractOperation {
+                            account: receiver_account_identifier.clone(),
+                            code: action.code,
+                            expected_code_hash: None,
+                        }
+                        .into_related_operation(
+                            crate::models::OperationIdentifier::new(&operations),
+                            vec![initiate_deploy_contract_operation_id],
+                        ),
+                    );
+                }
+
+                // Switching to a global contract neither moves balance nor uploads code, so
+                // there is nothing to report.
+                near_primitives::transaction::Action::UseGlobalContract(_) => {}
+
                 near_primitives::transaction::Action::FunctionCall(action) => {
                     let attached_amount = crate::models::Amount::from_yoctonear(action.deposit);
 
@@ -700,12 +751,20 @@ impl TryFrom<Vec<crate::models::Operation>> for NearActions {
                     sender_account_id
                         .try_set(&initiate_deploy_contract_operation.sender_account)?;
 
-                    actions.push(
-                        near_primitives::transaction::DeployContractAction {
+                    let action = match deploy_contract_operation.expected_code_hash {
+                        None => near_primitives::transaction::DeployContractAction {
                             code: deploy_contract_operation.code,
                         }
                         .into(),
-                    )
+                        Some(expected_code_hash) => {
+                            near_primitives::transaction::DeployContractWithCodeHashCheckAction {
+                                code: deploy_contract_operation.code,
+                                expected_code_hash,
+                            }
+                            .into()
+                        }
+                    };
+                    actions.push(action)
                 }
                 crate::models::OperationType::FunctionCall => {
                     let function_call_operation =
@@ -3,6 +3,9 @@ use super::ValidatedOperation;
 pub(crate) struct DeployContractOperation {
     pub(crate) account: crate::models::AccountIdentifier,
     pub(crate) code: Vec<u8>,
+    /// Hash of the code which must currently be deployed on the account, if the deployment is
+    /// conditional.
+    pub(crate) expected_code_hash: Option<near_primitives::hash::CryptoHash>,
 }
 
 impl ValidatedOperation for DeployContractOperation {
@@ -20,6 +23,7 @@ impl ValidatedOperation for DeployContractOperation {
             amount: None,
             metadata: Some(crate::models::OperationMetadata {
                 code: Some(self.code.into()),
+                expected_code_hash: self.expected_code_hash.map(|hash| hash.to_string()),
                 ..Default::default()
             }),
 
@@ -43,7 +47,11 @@ impl TryFrom<crate::models::Operation> for DeployContractOperation {
         Self::validate_operation_type(operation.type_)?;
         let metadata = operation.metadata.ok_or_else(required_fields_error)?;
         let code = metadata.code.ok_or_else(required_fields_error)?.into_inner();
+        let expected_code_hash =
+            metadata.expected_code_hash.map(|hash| hash.parse()).transpose().map_err(|_| {
+                crate::errors::ErrorKind::InvalidInput("Invalid expected_code_hash".to_string())
+            })?;
 
-        Ok(Self { account: operation.account, code })
+        Ok(Self { account: operation.account, code, expected_code_hash })
     }
 }
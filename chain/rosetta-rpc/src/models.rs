@@ -797,6 +797,10 @@ pub(crate) struct OperationMetadata {
     /// Has to be specified for DEPLOY_CONTRACT operation
     #[serde(skip_serializing_if = "Option::is_none")]
     pub code: Option<BlobInHexString<Vec<u8>>>,
+    /// Can be specified for DEPLOY_CONTRACT operation to only deploy the code if the code
+    /// currently deployed on the account has the given hash
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expected_code_hash: Option<String>,
     /// Has to be specified for FUNCTION_CALL operation
     #[serde(skip_serializing_if = "Option::is_none")]
     pub method_name: Option<String>,
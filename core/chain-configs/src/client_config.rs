@@ -21,6 +21,25 @@ pub enum LogSummaryStyle {
     Colored,
 }
 
+/// Order in which a chunk producer takes transactions from the transaction pool.
+///
+/// Transactions are grouped by signer account and access key, and within a group they are always
+/// taken in nonce order. The strategy decides between the groups.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionSelectionStrategy {
+    /// Takes one transaction from each group in turns, in a random order of groups.
+    #[default]
+    FifoPerGroup,
+    /// Takes the transaction which prepays the most gas for function calls first. Transactions
+    /// don't set their own gas price, so this doesn't rank by the fee per unit of gas, which is
+    /// the same for all transactions in a chunk.
+    PrepaidGasDescending,
+    /// Takes one transaction from each signer account in turns, so signers with many access
+    /// keys don't get a larger share of the chunk.
+    FairSharePerSigner,
+}
+
 /// Minimum number of epochs for which we keep store data
 pub const MIN_GC_NUM_EPOCHS_TO_KEEP: u64 = 3;
 
@@ -401,6 +420,12 @@ pub struct ClientConfig {
     /// Used to simulate the behavior of chunk only producers without staking tokens.
     /// This field is only used if `tracked_shards` is empty.
     pub tracked_shard_schedule: Vec<Vec<ShardId>>,
+    /// Track the shards which may contain accounts matching these patterns, in addition to
+    /// `tracked_accounts`. A pattern is an account id, a glob like `sweat.*.near` or a regular
+    /// expression prefixed with `regex:`. The literal prefix of a pattern decides the shards it
+    /// may match, so patterns without one, like `*.sweat`, track all shards.
+    /// This field is only used if `tracked_shards` and `tracked_shard_schedule` are empty.
+    pub tracked_account_patterns: Vec<String>,
     /// Not clear old data, set `true` for archive nodes.
     pub archive: bool,
     /// save_trie_changes should be set to true iff
@@ -408,6 +433,15 @@ pub struct ClientConfig {
     /// - archive is true, cold_store is configured and migration to split_storage is finished - node
     /// working in split storage mode needs trie changes in order to do garbage collection on hot.
     pub save_trie_changes: bool,
+    /// Whether to save per-receipt execution reports of applied chunks in the
+    /// `ChunkExecutionReport` column. Only meant for debugging slow chunks.
+    pub save_chunk_execution_reports: bool,
+    /// Whether to save chunk state witnesses which failed validation in the
+    /// `InvalidChunkStateWitnesses` column. Only meant for debugging.
+    pub save_invalid_witnesses: bool,
+    /// Number of the most recently validated chunk state witnesses to keep in the
+    /// `LatestChunkStateWitnesses` column. Only meant for debugging.
+    pub save_latest_witnesses: usize,
     /// Number of threads for ViewClientActor pool.
     pub view_client_threads: usize,
     /// Run Epoch Sync on the start.
@@ -436,6 +470,8 @@ pub struct ClientConfig {
     /// Limit of the size of per-shard transaction pool measured in bytes. If not set, the size
     /// will be unbounded.
     pub transaction_pool_size_limit: Option<u64>,
+    /// Order in which transactions are taken from the pool when producing a chunk.
+    pub transaction_selection_strategy: TransactionSelectionStrategy,
     // Allows more detailed logging, for example a list of orphaned blocks.
     pub enable_multiline_logging: bool,
     // Configuration for resharding.
@@ -515,8 +551,12 @@ impl ClientConfig {
             tracked_accounts: vec![],
             tracked_shards: vec![],
             tracked_shard_schedule: vec![],
+            tracked_account_patterns: vec![],
             archive,
             save_trie_changes,
+            save_chunk_execution_reports: false,
+            save_invalid_witnesses: false,
+            save_latest_witnesses: 0,
             log_summary_style: LogSummaryStyle::Colored,
             view_client_threads: 1,
             epoch_sync_enabled,
@@ -530,6 +570,7 @@ impl ClientConfig {
             state_sync_enabled,
             state_sync: StateSyncConfig::default(),
             transaction_pool_size_limit: None,
+            transaction_selection_strategy: TransactionSelectionStrategy::default(),
             enable_multiline_logging: false,
             resharding_config: MutableConfigValue::new(
                 ReshardingConfig::default(),
@@ -1,5 +1,6 @@
 use near_async::time::Clock;
-use near_primitives::types::BlockHeight;
+use near_primitives::types::{AccountId, BlockHeight, ShardId};
+use near_primitives::validator_signer::ValidatorSigner;
 use serde::{Deserialize, Serialize, Serializer};
 use std::fmt::Debug;
 use std::sync::{Arc, Mutex};
@@ -100,4 +101,16 @@ pub struct UpdateableClientConfig {
     /// Time limit for adding transactions in produce_chunk()
     #[serde(with = "near_async::time::serde_opt_duration_as_std")]
     pub produce_chunk_add_transactions_time_limit: Option<Duration>,
+
+    /// Tracked accounts, shards and account patterns, see `ClientConfig`.
+    /// Changes take effect from the epoch after the next one.
+    pub tracked_accounts: Vec<AccountId>,
+    pub tracked_shards: Vec<ShardId>,
+    pub tracked_shard_schedule: Vec<Vec<ShardId>>,
+    pub tracked_account_patterns: Vec<String>,
+
+    /// Validator keys the node can switch between, read from `validator_key_file` and
+    /// `additional_validator_key_files`. Not set if the node isn't a validator.
+    #[serde(skip)]
+    pub validator_signers: Option<Vec<Arc<dyn ValidatorSigner>>>,
 }
@@ -42,6 +42,11 @@ static CONFIG_DIFFS: &[(ProtocolVersion, &str)] = &[
     // Introduce ETH-implicit accounts.
     (138, include_config!("138.yaml")),
     (139, include_config!("139.yaml")),
+    // Enable the `storage_range` host function, see `ProtocolFeature::StorageRange`.
+    (142, include_config!("142.yaml")),
+    // Enable the Wasm bulk memory, multi-value and non-trapping float-to-int conversion
+    // proposals, see `ProtocolFeature::PreparationV3`.
+    (148, include_config!("148.yaml")),
 ];
 
 /// Testnet parameters for versions <= 29, which (incorrectly) differed from mainnet parameters
@@ -156,8 +161,10 @@ impl RuntimeConfigStore {
 mod tests {
     use super::*;
     use crate::cost::{ActionCosts, ExtCosts};
+    use crate::vm::ContractPrepareVersion;
     use near_primitives_core::version::ProtocolFeature::{
         LowerDataReceiptAndEcrecoverBaseCost, LowerStorageCost, LowerStorageKeyLimit,
+        PreparationV3, StorageRange,
     };
     use std::collections::HashSet;
 
@@ -312,6 +319,30 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_storage_range_enabled_by_protocol_feature() {
+        let store = RuntimeConfigStore::new(None);
+        let base_cfg = store.get_config(StorageRange.protocol_version() - 1);
+        let new_cfg = store.get_config(StorageRange.protocol_version());
+        assert!(!base_cfg.wasm_config.storage_range_host_functions);
+        assert!(new_cfg.wasm_config.storage_range_host_functions);
+    }
+
+    #[test]
+    fn test_preparation_v3_enabled_by_protocol_feature() {
+        let store = RuntimeConfigStore::new(None);
+        let base_cfg = store.get_config(PreparationV3.protocol_version() - 1);
+        let new_cfg = store.get_config(PreparationV3.protocol_version());
+        assert_eq!(
+            base_cfg.wasm_config.limit_config.contract_prepare_version,
+            ContractPrepareVersion::V2
+        );
+        assert_eq!(
+            new_cfg.wasm_config.limit_config.contract_prepare_version,
+            ContractPrepareVersion::V3
+        );
+    }
+
     /// Use snapshot testing to check that the JSON representation of the
     /// configurations of each version is unchanged.
     /// If tests fail after an intended change, run `cargo insta review` accept
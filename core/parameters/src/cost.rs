@@ -135,6 +135,10 @@ impl ExtCostsConfig {
             ExtCosts::yield_create_byte => 300_000_000_000_000,
             ExtCosts::yield_resume_base => 300_000_000_000_000,
             ExtCosts::yield_resume_byte => 300_000_000_000_000,
+            ExtCosts::storage_range_base => SAFETY_MULTIPLIER * 18785615250,
+            ExtCosts::storage_range_item => SAFETY_MULTIPLIER * 18785615250,
+            ExtCosts::storage_range_key_byte => SAFETY_MULTIPLIER * 10317511,
+            ExtCosts::storage_range_value_byte => SAFETY_MULTIPLIER * 1870335,
         }
         .map(|_, value| ParameterCost { gas: value, compute: value * factor });
         ExtCostsConfig { costs }
@@ -230,6 +234,10 @@ pub enum ExtCosts {
     yield_create_byte = 62,
     yield_resume_base = 63,
     yield_resume_byte = 64,
+    storage_range_base = 65,
+    storage_range_item = 66,
+    storage_range_key_byte = 67,
+    storage_range_value_byte = 68,
 }
 
 // Type of an action, used in fees logic.
@@ -264,6 +272,9 @@ pub enum ActionCosts {
     new_data_receipt_base = 13,
     new_data_receipt_byte = 14,
     delegate = 15,
+    deploy_global_contract_base = 16,
+    deploy_global_contract_byte = 17,
+    use_global_contract = 18,
 }
 
 impl ExtCosts {
@@ -342,6 +353,10 @@ impl ExtCosts {
             ExtCosts::yield_create_byte => Parameter::WasmYieldCreateByte,
             ExtCosts::yield_resume_base => Parameter::WasmYieldResumeBase,
             ExtCosts::yield_resume_byte => Parameter::WasmYieldResumeBase,
+            ExtCosts::storage_range_base => Parameter::WasmStorageRangeBase,
+            ExtCosts::storage_range_item => Parameter::WasmStorageRangeItem,
+            ExtCosts::storage_range_key_byte => Parameter::WasmStorageRangeKeyByte,
+            ExtCosts::storage_range_value_byte => Parameter::WasmStorageRangeValueByte,
         }
     }
 }
@@ -465,6 +480,21 @@ impl RuntimeFeesConfig {
                     send_not_sir: 200_000_000_000,
                     execution: 200_000_000_000,
                 },
+                ActionCosts::deploy_global_contract_base => Fee {
+                    send_sir: 184765750000,
+                    send_not_sir: 184765750000,
+                    execution: 184765750000,
+                },
+                ActionCosts::deploy_global_contract_byte => Fee {
+                    send_sir: 6812999,
+                    send_not_sir: 6812999,
+                    execution: 6812999,
+                },
+                ActionCosts::use_global_contract => Fee {
+                    send_sir: 184765750000,
+                    send_not_sir: 184765750000,
+                    execution: 184765750000,
+                },
             },
         }
     }
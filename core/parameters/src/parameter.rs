@@ -122,6 +122,10 @@ pub enum Parameter {
     WasmYieldCreateByte,
     WasmYieldResumeBase,
     WasmYieldResumeByte,
+    WasmStorageRangeBase,
+    WasmStorageRangeItem,
+    WasmStorageRangeKeyByte,
+    WasmStorageRangeValueByte,
 
     // Smart contract limits
     MaxGasBurnt,
@@ -153,6 +157,7 @@ pub enum Parameter {
     AccountIdValidityRulesVersion,
     YieldTimeoutLengthInBlocks,
     MaxYieldPayloadSize,
+    MaxStorageRangeItems,
 
     // Contract runtime features
     #[strum(serialize = "disable_9393_fix")]
@@ -167,6 +172,7 @@ pub enum Parameter {
     VmKind,
     EthImplicitAccounts,
     YieldResume,
+    StorageRange,
 }
 
 #[derive(
@@ -199,6 +205,9 @@ pub enum FeeParameter {
     ActionAddFunctionCallKeyPerByte,
     ActionDeleteKey,
     ActionDelegate,
+    ActionDeployGlobalContract,
+    ActionDeployGlobalContractPerByte,
+    ActionUseGlobalContract,
 }
 
 impl Parameter {
@@ -234,6 +243,7 @@ impl Parameter {
             Parameter::AccountIdValidityRulesVersion,
             Parameter::YieldTimeoutLengthInBlocks,
             Parameter::MaxYieldPayloadSize,
+            Parameter::MaxStorageRangeItems,
         ]
         .iter()
     }
@@ -260,6 +270,9 @@ impl From<ActionCosts> for FeeParameter {
             ActionCosts::new_action_receipt => Self::ActionReceiptCreation,
             ActionCosts::new_data_receipt_base => Self::DataReceiptCreationBase,
             ActionCosts::new_data_receipt_byte => Self::DataReceiptCreationPerByte,
+            ActionCosts::deploy_global_contract_base => Self::ActionDeployGlobalContract,
+            ActionCosts::deploy_global_contract_byte => Self::ActionDeployGlobalContractPerByte,
+            ActionCosts::use_global_contract => Self::ActionUseGlobalContract,
         }
     }
 }
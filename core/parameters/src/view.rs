@@ -227,6 +227,8 @@ pub struct VMConfigView {
     pub eth_implicit_accounts: bool,
     /// See [`VMConfig::yield_resume_host_functions`].
     pub yield_resume_host_functions: bool,
+    /// See [`VMConfig::storage_range_host_functions`].
+    pub storage_range_host_functions: bool,
 
     /// Describes limits for VM and Runtime.
     ///
@@ -253,6 +255,7 @@ impl From<crate::vm::Config> for VMConfigView {
             vm_kind: config.vm_kind,
             eth_implicit_accounts: config.eth_implicit_accounts,
             yield_resume_host_functions: config.yield_resume_host_functions,
+            storage_range_host_functions: config.storage_range_host_functions,
         }
     }
 }
@@ -275,6 +278,7 @@ impl From<VMConfigView> for crate::vm::Config {
             vm_kind: view.vm_kind,
             eth_implicit_accounts: view.eth_implicit_accounts,
             yield_resume_host_functions: view.yield_resume_host_functions,
+            storage_range_host_functions: view.storage_range_host_functions,
         }
     }
 }
@@ -455,6 +459,15 @@ pub struct ExtCostsConfigView {
     pub yield_resume_base: Gas,
     /// Per byte cost of resume payload.
     pub yield_resume_byte: Gas,
+
+    /// Base cost for a storage range scan.
+    pub storage_range_base: Gas,
+    /// Cost per entry returned by a storage range scan.
+    pub storage_range_item: Gas,
+    /// Per byte cost of keys returned by a storage range scan.
+    pub storage_range_key_byte: Gas,
+    /// Per byte cost of values returned by a storage range scan.
+    pub storage_range_value_byte: Gas,
 }
 
 impl From<crate::ExtCostsConfig> for ExtCostsConfigView {
@@ -529,6 +542,10 @@ impl From<crate::ExtCostsConfig> for ExtCostsConfigView {
             yield_create_byte: config.gas_cost(ExtCosts::yield_create_byte),
             yield_resume_base: config.gas_cost(ExtCosts::yield_resume_base),
             yield_resume_byte: config.gas_cost(ExtCosts::yield_resume_byte),
+            storage_range_base: config.gas_cost(ExtCosts::storage_range_base),
+            storage_range_item: config.gas_cost(ExtCosts::storage_range_item),
+            storage_range_key_byte: config.gas_cost(ExtCosts::storage_range_key_byte),
+            storage_range_value_byte: config.gas_cost(ExtCosts::storage_range_value_byte),
             // removed parameters
             contract_compile_base: 0,
             contract_compile_bytes: 0,
@@ -604,6 +621,10 @@ impl From<ExtCostsConfigView> for crate::ExtCostsConfig {
                 ExtCosts::yield_create_byte => view.yield_create_byte,
                 ExtCosts::yield_resume_base => view.yield_resume_base,
                 ExtCosts::yield_resume_byte => view.yield_resume_byte,
+                ExtCosts::storage_range_base => view.storage_range_base,
+                ExtCosts::storage_range_item => view.storage_range_item,
+                ExtCosts::storage_range_key_byte => view.storage_range_key_byte,
+                ExtCosts::storage_range_value_byte => view.storage_range_value_byte,
         }
         .map(|_, value| ParameterCost { gas: value, compute: value });
         Self { costs }
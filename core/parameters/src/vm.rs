@@ -136,6 +136,8 @@ pub struct LimitConfig {
     pub yield_timeout_length_in_blocks: u64,
     /// Maximum number of bytes for payload passed over a yield resume.
     pub max_yield_payload_size: u64,
+    /// Maximum number of entries returned by a single `storage_range` call.
+    pub max_storage_range_items: u64,
 }
 
 /// Dynamic configuration parameters required for the WASM runtime to
@@ -190,6 +192,9 @@ pub struct Config {
     /// Enable the `promise_yield_create` and `promise_yield_resume` host functions.
     pub yield_resume_host_functions: bool,
 
+    /// Enable the `storage_range` host function.
+    pub storage_range_host_functions: bool,
+
     /// Describes limits for VM and Runtime.
     pub limit_config: LimitConfig,
 }
@@ -216,6 +221,7 @@ impl Config {
 
     pub fn enable_all_features(&mut self) {
         self.yield_resume_host_functions = true;
+        self.storage_range_host_functions = true;
         self.eth_implicit_accounts = true;
         self.function_call_weight = true;
         self.alt_bn128 = true;
@@ -258,6 +264,15 @@ pub enum ContractPrepareVersion {
     V1,
     /// finite-wasm 0.3.0 based contract preparation code.
     V2,
+    /// Same as V2, but additionally accepts the bulk memory, multi-value and non-trapping
+    /// float-to-int conversion proposals.
+    ///
+    /// Multi-value is limited to block types: functions with multiple results are rejected.
+    ///
+    /// `memory.copy`, `memory.fill` and `memory.init` are charged proportionally to the number of
+    /// bytes they process, `table.copy`, `table.fill` and `table.init` to the number of elements.
+    /// Only supported by NearVM and Wasmtime.
+    V3,
 }
 
 impl ContractPrepareVersion {
@@ -129,6 +129,13 @@ pub enum ProtocolFeature {
     /// NEP: https://github.com/near/NEPs/pull/491
     #[cfg(feature = "protocol_feature_nonrefundable_transfer_nep491")]
     NonRefundableBalance,
+    /// Allows deploying a contract only if the code currently deployed on the account has the
+    /// expected hash, which makes contract upgrades safe against concurrent upgrades.
+    DeployContractCodeHashCheck,
+    /// Allows deploying a contract code once under its hash and using it from many accounts
+    /// without storing a copy of the code for each of them. The code is stored per shard, so
+    /// only accounts on the shard where it was deployed can use it.
+    GlobalContracts,
     RestrictTla,
     /// Increases the number of chunk producers.
     TestnetFewerBlockProducers,
@@ -142,6 +149,24 @@ pub enum ProtocolFeature {
     SingleShardTracking,
     // Stateless validation: state witness size limits.
     StateWitnessSizeLimit,
+    /// Enables the `storage_range` host function, which reads a bounded range of the contract
+    /// state in one call. The host function is enabled by the runtime config of this version.
+    StorageRange,
+    /// Encrypts peer connections with keys exchanged during the handshake.
+    EncryptedPeerConnections,
+    /// Distributes chunk state witnesses as Reed-Solomon encoded parts, which
+    /// chunk validators forward to each other, instead of sending the whole
+    /// witness to every chunk validator.
+    PartialEncodedStateWitness,
+    /// Serves light client blocks and epoch block producers over P2P
+    /// (`LightClientBlockRequest` and `EpochBlockProducersRequest`).
+    LightClientP2PRequests,
+    /// Serves `EpochSyncInfo`s over P2P (`EpochSyncRequest`), rate limited per connection.
+    EpochSyncP2PRequests,
+    /// Enables preparation V3, which accepts the Wasm bulk memory, multi-value and non-trapping
+    /// float-to-int conversion proposals. The preparation version is set by the runtime config of
+    /// this version.
+    PreparationV3,
 }
 
 impl ProtocolFeature {
@@ -211,6 +236,14 @@ impl ProtocolFeature {
             ProtocolFeature::NonRefundableBalance => 140,
             #[cfg(feature = "statelessnet_protocol")]
             ProtocolFeature::SimpleNightshadeV3 => 141,
+            ProtocolFeature::DeployContractCodeHashCheck => 142,
+            ProtocolFeature::StorageRange => 142,
+            ProtocolFeature::GlobalContracts => 143,
+            ProtocolFeature::EncryptedPeerConnections => 144,
+            ProtocolFeature::PartialEncodedStateWitness => 145,
+            ProtocolFeature::LightClientP2PRequests => 146,
+            ProtocolFeature::EpochSyncP2PRequests => 147,
+            ProtocolFeature::PreparationV3 => 148,
         }
     }
 }
@@ -226,7 +259,7 @@ pub const PROTOCOL_VERSION: ProtocolVersion = if cfg!(feature = "statelessnet_pr
     83
 } else if cfg!(feature = "nightly_protocol") {
     // On nightly, pick big enough version to support all features.
-    140
+    148
 } else {
     // Enable all stable features.
     STABLE_PROTOCOL_VERSION
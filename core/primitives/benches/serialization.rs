@@ -71,6 +71,7 @@ fn create_block() -> Block {
         CryptoHash::default(),
         Clock::real().now_utc(),
     )
+    .unwrap()
 }
 
 fn create_account() -> Account {
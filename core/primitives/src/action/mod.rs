@@ -2,6 +2,7 @@ pub mod delegate;
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_crypto::PublicKey;
+use near_primitives_core::hash::CryptoHash;
 use near_primitives_core::{
     account::AccessKey,
     serialize::dec_format,
@@ -94,6 +95,76 @@ impl fmt::Debug for DeployContractAction {
     }
 }
 
+/// Deploy contract action which only succeeds if the code currently deployed on the receiver
+/// account has the expected hash.
+///
+/// Combined with a `FunctionCall` action in the same receipt, this allows to atomically upgrade
+/// a contract and migrate its state without racing against concurrent upgrades.
+#[serde_as]
+#[derive(
+    BorshSerialize, BorshDeserialize, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone,
+)]
+pub struct DeployContractWithCodeHashCheckAction {
+    /// WebAssembly binary
+    #[serde_as(as = "Base64")]
+    pub code: Vec<u8>,
+    /// Hash of the code that must currently be deployed on the account, `CryptoHash::default()`
+    /// if the account must not have a contract yet.
+    pub expected_code_hash: CryptoHash,
+}
+
+impl fmt::Debug for DeployContractWithCodeHashCheckAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeployContractWithCodeHashCheckAction")
+            .field("code", &format_args!("{}", base64(&self.code)))
+            .field("expected_code_hash", &self.expected_code_hash)
+            .finish()
+    }
+}
+
+/// Deploys a contract code once under its hash, so that any account can use it with
+/// a `UseGlobalContract` action without storing its own copy of the code.
+///
+/// The code is only stored in the state of the shard of the receiver account, and the storage cost
+/// of the code is burnt from the balance of the receiver account the first time it is deployed.
+#[serde_as]
+#[derive(
+    BorshSerialize, BorshDeserialize, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone,
+)]
+pub struct DeployGlobalContractAction {
+    /// WebAssembly binary
+    #[serde_as(as = "Base64")]
+    pub code: Vec<u8>,
+}
+
+impl fmt::Debug for DeployGlobalContractAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DeployGlobalContractAction")
+            .field("code", &format_args!("{}", base64(&self.code)))
+            .finish()
+    }
+}
+
+/// Makes the receiver account use the global contract code with the given hash instead of
+/// a code deployed on the account itself.
+///
+/// The code must have been deployed on the shard of the receiver account, global contract code is
+/// not distributed across shards.
+#[derive(
+    BorshSerialize,
+    BorshDeserialize,
+    serde::Serialize,
+    serde::Deserialize,
+    PartialEq,
+    Eq,
+    Clone,
+    Debug,
+)]
+pub struct UseGlobalContractAction {
+    /// Hash of the code previously deployed with a `DeployGlobalContract` action.
+    pub code_hash: CryptoHash,
+}
+
 #[serde_as]
 #[derive(
     BorshSerialize, BorshDeserialize, serde::Serialize, serde::Deserialize, PartialEq, Eq, Clone,
@@ -193,6 +264,15 @@ pub enum Action {
     DeleteKey(Box<DeleteKeyAction>),
     DeleteAccount(DeleteAccountAction),
     Delegate(Box<delegate::SignedDelegateAction>),
+    /// Sets a Wasm code to a receiver_id, provided the currently deployed code has the expected
+    /// hash.
+    DeployContractWithCodeHashCheck(Box<DeployContractWithCodeHashCheckAction>),
+    /// Stores a Wasm code under its hash so that it can be used by any account on the same shard.
+    DeployGlobalContract(DeployGlobalContractAction),
+    /// Makes the receiver_id use a previously deployed global contract code.
+    UseGlobalContract(Box<UseGlobalContractAction>),
+    // Variants behind cargo features go last, so that the borsh tags of the other variants
+    // don't depend on the enabled features.
     #[cfg(feature = "protocol_feature_nonrefundable_transfer_nep491")]
     /// Makes a non-refundable transfer for storage allowance.
     /// Only possible during new account creation.
@@ -238,6 +318,24 @@ impl From<DeployContractAction> for Action {
     }
 }
 
+impl From<DeployContractWithCodeHashCheckAction> for Action {
+    fn from(deploy_contract_action: DeployContractWithCodeHashCheckAction) -> Self {
+        Self::DeployContractWithCodeHashCheck(Box::new(deploy_contract_action))
+    }
+}
+
+impl From<DeployGlobalContractAction> for Action {
+    fn from(deploy_global_contract_action: DeployGlobalContractAction) -> Self {
+        Self::DeployGlobalContract(deploy_global_contract_action)
+    }
+}
+
+impl From<UseGlobalContractAction> for Action {
+    fn from(use_global_contract_action: UseGlobalContractAction) -> Self {
+        Self::UseGlobalContract(Box::new(use_global_contract_action))
+    }
+}
+
 impl From<FunctionCallAction> for Action {
     fn from(function_call_action: FunctionCallAction) -> Self {
         Self::FunctionCall(Box::new(function_call_action))
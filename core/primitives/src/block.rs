@@ -14,7 +14,7 @@ use crate::sharding::{
     ShardChunkHeaderV1,
 };
 use crate::types::{Balance, BlockHeight, EpochId, Gas, NumBlocks, StateRoot};
-use crate::validator_signer::{EmptyValidatorSigner, ValidatorSigner};
+use crate::validator_signer::{EmptyValidatorSigner, ValidatorSigner, ValidatorSignerError};
 use crate::version::{ProtocolVersion, SHARD_CHUNK_HEADER_UPGRADE_VERSION};
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_async::time::Utc;
@@ -263,7 +263,7 @@ impl Block {
         next_bp_hash: CryptoHash,
         block_merkle_root: CryptoHash,
         timestamp: Utc,
-    ) -> Self {
+    ) -> Result<Self, ValidatorSignerError> {
         // Collect aggregate of validators and gas usage/limits from chunks.
         let mut prev_validator_proposals = vec![];
         let mut gas_used = 0;
@@ -295,7 +295,7 @@ impl Block {
         let now = timestamp.unix_timestamp_nanos() as u64;
         let time = if now <= prev.raw_timestamp() { prev.raw_timestamp() + 1 } else { now };
 
-        let (vrf_value, vrf_proof) = signer.compute_vrf_with_proof(prev.random_value().as_ref());
+        let (vrf_value, vrf_proof) = signer.compute_vrf_with_proof(prev.random_value().as_ref())?;
         let random_value = hash(vrf_value.0.as_ref());
 
         let last_ds_final_block =
@@ -357,14 +357,14 @@ impl Block {
             next_bp_hash,
             block_merkle_root,
             prev.height(),
-        );
+        )?;
 
-        Self::block_from_protocol_version(
+        Ok(Self::block_from_protocol_version(
             this_epoch_protocol_version,
             next_epoch_protocol_version,
             header,
             body,
-        )
+        ))
     }
 
     pub fn verify_total_supply(
@@ -4,7 +4,7 @@ use crate::merkle::combine_hash;
 use crate::network::PeerId;
 use crate::types::validator_stake::{ValidatorStake, ValidatorStakeIter, ValidatorStakeV1};
 use crate::types::{AccountId, Balance, BlockHeight, EpochId, MerkleHash, NumBlocks};
-use crate::validator_signer::ValidatorSigner;
+use crate::validator_signer::{ValidatorSigner, ValidatorSignerError};
 use crate::version::{get_protocol_version, ProtocolVersion, PROTOCOL_VERSION};
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_async::time::Utc;
@@ -249,10 +249,10 @@ impl Approval {
         parent_height: BlockHeight,
         target_height: BlockHeight,
         signer: &dyn ValidatorSigner,
-    ) -> Self {
+    ) -> Result<Self, ValidatorSignerError> {
         let inner = ApprovalInner::new(&parent_hash, parent_height, target_height);
-        let signature = signer.sign_approval(&inner, target_height);
-        Approval { inner, target_height, signature, account_id: signer.validator_id().clone() }
+        let signature = signer.sign_approval(&inner, target_height)?;
+        Ok(Approval { inner, target_height, signature, account_id: signer.validator_id().clone() })
     }
 
     pub fn get_data_for_sig(inner: &ApprovalInner, target_height: BlockHeight) -> Vec<u8> {
@@ -437,7 +437,7 @@ impl BlockHeader {
         next_bp_hash: CryptoHash,
         block_merkle_root: CryptoHash,
         prev_height: BlockHeight,
-    ) -> Self {
+    ) -> Result<Self, ValidatorSignerError> {
         let inner_lite = BlockHeaderInnerLite {
             height,
             epoch_id,
@@ -455,7 +455,7 @@ impl BlockHeader {
             crate::version::ProtocolFeature::BlockHeaderV3.protocol_version() - 1;
         // Previously we passed next_epoch_protocol_version here, which is incorrect, but we need
         // to preserve this for archival nodes
-        if next_epoch_protocol_version <= 29 {
+        let header = if next_epoch_protocol_version <= 29 {
             let chunks_included = chunk_mask.iter().map(|val| *val as u64).sum::<u64>();
             let inner_rest = BlockHeaderInnerRest {
                 prev_chunk_outgoing_receipts_root,
@@ -481,7 +481,7 @@ impl BlockHeader {
                 prev_hash,
                 &borsh::to_vec(&inner_lite).expect("Failed to serialize"),
                 &borsh::to_vec(&inner_rest).expect("Failed to serialize"),
-            );
+            )?;
             Self::BlockHeaderV1(Arc::new(BlockHeaderV1 {
                 prev_hash,
                 inner_lite,
@@ -513,7 +513,7 @@ impl BlockHeader {
                 prev_hash,
                 &borsh::to_vec(&inner_lite).expect("Failed to serialize"),
                 &borsh::to_vec(&inner_rest).expect("Failed to serialize"),
-            );
+            )?;
             Self::BlockHeaderV2(Arc::new(BlockHeaderV2 {
                 prev_hash,
                 inner_lite,
@@ -545,7 +545,7 @@ impl BlockHeader {
                 prev_hash,
                 &borsh::to_vec(&inner_lite).expect("Failed to serialize"),
                 &borsh::to_vec(&inner_rest).expect("Failed to serialize"),
-            );
+            )?;
             Self::BlockHeaderV3(Arc::new(BlockHeaderV3 {
                 prev_hash,
                 inner_lite,
@@ -578,7 +578,7 @@ impl BlockHeader {
                 prev_hash,
                 &borsh::to_vec(&inner_lite).expect("Failed to serialize"),
                 &borsh::to_vec(&inner_rest).expect("Failed to serialize"),
-            );
+            )?;
             Self::BlockHeaderV4(Arc::new(BlockHeaderV4 {
                 prev_hash,
                 inner_lite,
@@ -586,7 +586,8 @@ impl BlockHeader {
                 signature,
                 hash,
             }))
-        }
+        };
+        Ok(header)
     }
 
     pub fn genesis(
@@ -2,7 +2,7 @@ use crate::hash::CryptoHash;
 use crate::merkle::MerklePath;
 use crate::sharding::{EncodedShardChunk, ShardChunk, ShardChunkHeader};
 use crate::types::AccountId;
-use crate::validator_signer::ValidatorSigner;
+use crate::validator_signer::{ValidatorSigner, ValidatorSignerError};
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_crypto::Signature;
 
@@ -109,9 +109,12 @@ impl Challenge {
         self.hash = CryptoHash::hash_borsh(&self.body);
     }
 
-    pub fn produce(body: ChallengeBody, signer: &dyn ValidatorSigner) -> Self {
-        let (hash, signature) = signer.sign_challenge(&body);
-        Self { body, account_id: signer.validator_id().clone(), signature, hash }
+    pub fn produce(
+        body: ChallengeBody,
+        signer: &dyn ValidatorSigner,
+    ) -> Result<Self, ValidatorSignerError> {
+        let (hash, signature) = signer.sign_challenge(&body)?;
+        Ok(Self { body, account_id: signer.validator_id().clone(), signature, hash })
     }
 }
 
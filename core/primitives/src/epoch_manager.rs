@@ -1253,7 +1253,6 @@ pub enum SlashState {
     Other,
 }
 
-#[cfg(feature = "new_epoch_sync")]
 pub mod epoch_sync {
     use crate::block_header::BlockHeader;
     use crate::epoch_manager::block_info::BlockInfo;
@@ -1265,7 +1264,7 @@ pub mod epoch_sync {
     use std::collections::{HashMap, HashSet};
 
     /// Struct to keep all the info that is transferred for one epoch during Epoch Sync.
-    #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug)]
+    #[derive(BorshSerialize, BorshDeserialize, Clone, PartialEq, Eq, Debug)]
     pub struct EpochSyncInfo {
         /// All block hashes of this epoch. In order of production.
         pub all_block_hashes: Vec<CryptoHash>,
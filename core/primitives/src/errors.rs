@@ -510,6 +510,17 @@ pub enum ActionErrorKind {
     DelegateActionNonceTooLarge { delegate_nonce: Nonce, upper_bound: Nonce },
     /// Sending non-refundable balance to an existing account is not allowed according to NEP-491.
     NonRefundableBalanceToExistingAccount { account_id: AccountId },
+    /// The code hash of the contract currently deployed on the account doesn't match the one
+    /// expected by a `DeployContractWithCodeHashCheck` action.
+    DeployContractCodeHashMismatch {
+        account_id: AccountId,
+        expected_code_hash: CryptoHash,
+        actual_code_hash: CryptoHash,
+    },
+    /// There is no global contract code with the hash referenced by a `UseGlobalContract` action
+    /// on the shard of the account. Global contract code is not distributed across shards, so it
+    /// can only be used by accounts on the shard where it was deployed.
+    GlobalContractDoesNotExist { account_id: AccountId, code_hash: CryptoHash },
 }
 
 impl From<ActionErrorKind> for ActionError {
@@ -837,6 +848,8 @@ impl Display for ActionErrorKind {
             ActionErrorKind::NonRefundableBalanceToExistingAccount { account_id} => {
                 write!(f, "Can't send non-refundable balance to {} because it already exists", account_id)
             }
+            ActionErrorKind::DeployContractCodeHashMismatch { account_id, expected_code_hash, actual_code_hash } => write!(f, "Account {} has contract code with hash {} deployed, but {} was expected", account_id, actual_code_hash, expected_code_hash),
+            ActionErrorKind::GlobalContractDoesNotExist { account_id, code_hash } => write!(f, "Global contract code with hash {} is not deployed on the shard of account {}", code_hash, account_id),
         }
     }
 }
@@ -971,6 +984,8 @@ pub enum PrepareError {
     TooManyFunctions,
     /// Contract contains too many locals.
     TooManyLocals,
+    /// Contract contains a function type with more than one result, which is not supported yet.
+    MultiValueResult,
 }
 
 /// A kind of a trap happened during execution of a binary
@@ -1171,7 +1186,6 @@ pub enum FunctionCallError {
     ExecutionError(String),
 }
 
-#[cfg(feature = "new_epoch_sync")]
 pub mod epoch_sync {
     use near_primitives_core::hash::CryptoHash;
     use near_primitives_core::types::EpochHeight;
@@ -0,0 +1,48 @@
+//! Per-chunk reports on how receipts were executed.
+//!
+//! The reports are opt-in and only meant for debugging: they record wall-clock
+//! time and trie access statistics which are not part of consensus, so that it
+//! is possible to find out which contracts make chunk application slow.
+use crate::hash::CryptoHash;
+use crate::types::{AccountId, Compute, Gas};
+use borsh::{BorshDeserialize, BorshSerialize};
+
+/// Execution statistics for all receipts processed while applying a chunk,
+/// in the order in which they were processed.
+#[derive(
+    BorshSerialize, BorshDeserialize, serde::Serialize, Debug, Clone, PartialEq, Eq, Default,
+)]
+pub struct ChunkExecutionReport {
+    pub receipts: Vec<ReceiptExecutionReport>,
+}
+
+/// Execution statistics for a single receipt.
+#[derive(BorshSerialize, BorshDeserialize, serde::Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ReceiptExecutionReport {
+    pub receipt_id: CryptoHash,
+    pub predecessor_id: AccountId,
+    pub receiver_id: AccountId,
+    /// Names of the methods called by the function call actions of the
+    /// receipt. Empty for data receipts and receipts without function calls.
+    pub method_names: Vec<String>,
+    /// Gas burnt by the execution. Zero if the receipt was postponed.
+    pub gas_burnt: Gas,
+    /// Compute usage of the execution. Zero if the receipt was postponed.
+    pub compute_usage: Compute,
+    /// Wall-clock time spent processing the receipt, in nanoseconds.
+    pub wall_time_ns: u64,
+    /// Part of `wall_time_ns` spent compiling contracts missing from the
+    /// compiled contract cache.
+    pub compilation_time_ns: u64,
+    /// Part of `wall_time_ns` spent on everything but compilation, including
+    /// loading compiled contracts and executing them.
+    pub execution_time_ns: u64,
+    /// Whether the compiled contracts were found in the compiled contract
+    /// cache. `None` if no compiled contract was looked up, `Some(false)` if
+    /// any of the lookups missed.
+    pub contract_cache_hit: Option<bool>,
+    /// Trie node reads which may have been served from disk.
+    pub trie_db_reads: u64,
+    /// Trie node reads served from the chunk cache.
+    pub trie_mem_reads: u64,
+}
@@ -13,6 +13,7 @@ pub mod challenge;
 pub mod epoch_manager;
 pub mod epoch_sync;
 pub mod errors;
+pub mod execution_report;
 pub mod merkle;
 pub mod network;
 pub mod profile_data_v2;
@@ -277,6 +277,37 @@ pub fn account_id_to_shard_id(account_id: &AccountId, shard_layout: &ShardLayout
     }
 }
 
+/// Returns the shards that may contain accounts starting with `prefix`, given a shard_layout.
+/// For V0, accounts are mapped by hash, so any shard may contain them.
+/// For V1, those are the shards whose account ranges intersect the range of accounts with the prefix.
+pub fn account_prefix_to_shard_ids(prefix: &str, shard_layout: &ShardLayout) -> Vec<ShardId> {
+    match shard_layout {
+        ShardLayout::V0(_) => shard_layout.shard_ids().collect(),
+        ShardLayout::V1(ShardLayoutV1 { boundary_accounts, .. }) => {
+            // Accounts starting with `prefix` are in the range [prefix, prefix_end), where
+            // `prefix_end` is the prefix with its last character incremented.
+            let prefix_end = prefix.char_indices().last().map(|(i, c)| {
+                let next = char::from_u32(c as u32 + 1).unwrap_or(char::MAX);
+                format!("{}{}", &prefix[..i], next)
+            });
+            shard_layout
+                .shard_ids()
+                .filter(|&shard_id| {
+                    let start =
+                        shard_id.checked_sub(1).map(|i| boundary_accounts[i as usize].as_str());
+                    let end = boundary_accounts.get(shard_id as usize).map(|a| a.as_str());
+                    let starts_before_prefix_end = match (start, &prefix_end) {
+                        (Some(start), Some(prefix_end)) => start < prefix_end.as_str(),
+                        _ => true,
+                    };
+                    let ends_after_prefix = end.map_or(true, |end| prefix < end);
+                    starts_before_prefix_end && ends_after_prefix
+                })
+                .collect()
+        }
+    }
+}
+
 /// Maps an account to the shard that it belongs to given a shard_layout
 pub fn account_id_to_shard_uid(account_id: &AccountId, shard_layout: &ShardLayout) -> ShardUId {
     ShardUId::from_shard_id_and_layout(
@@ -468,7 +499,9 @@ impl<'de> serde::de::Visitor<'de> for ShardUIdVisitor {
 
 #[cfg(test)]
 mod tests {
-    use crate::shard_layout::{account_id_to_shard_id, ShardLayout, ShardLayoutV1, ShardUId};
+    use crate::shard_layout::{
+        account_id_to_shard_id, account_prefix_to_shard_ids, ShardLayout, ShardLayoutV1, ShardUId,
+    };
     use near_primitives_core::types::{AccountId, ShardId};
     use rand::distributions::Alphanumeric;
     use rand::rngs::StdRng;
@@ -554,6 +587,30 @@ mod tests {
         assert_eq!(account_id_to_shard_id(&"zoo".parse().unwrap(), &shard_layout), 5);
     }
 
+    #[test]
+    fn test_account_prefix_to_shard_ids() {
+        let shard_layout = ShardLayout::v1(
+            parse_account_ids(&["aurora", "bar", "foo", "foo.baz", "paz"]),
+            Some(vec![vec![0, 1, 2], vec![3, 4, 5]]),
+            1,
+        );
+        assert_eq!(account_prefix_to_shard_ids("", &shard_layout), vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(account_prefix_to_shard_ids("a", &shard_layout), vec![0, 1]);
+        assert_eq!(account_prefix_to_shard_ids("aurora", &shard_layout), vec![1]);
+        assert_eq!(account_prefix_to_shard_ids("ba", &shard_layout), vec![1, 2]);
+        assert_eq!(account_prefix_to_shard_ids("foo", &shard_layout), vec![3, 4]);
+        assert_eq!(account_prefix_to_shard_ids("foo.", &shard_layout), vec![3, 4]);
+        assert_eq!(account_prefix_to_shard_ids("foo.bazz", &shard_layout), vec![4]);
+        assert_eq!(account_prefix_to_shard_ids("zoo", &shard_layout), vec![5]);
+        for account_id in ["aurora", "bar.foo.aurora", "foo.baz", "goo", "zoo"] {
+            let shard_id = account_id_to_shard_id(&account_id.parse().unwrap(), &shard_layout);
+            assert_eq!(account_prefix_to_shard_ids(account_id, &shard_layout), vec![shard_id]);
+        }
+
+        let shard_layout = ShardLayout::v0(4, 0);
+        assert_eq!(account_prefix_to_shard_ids("aurora", &shard_layout), vec![0, 1, 2, 3]);
+    }
+
     // check that after removing the fixed shards from the shard layout v1
     // the fixed shards are skipped in deserialization
     // this should be the default as long as serde(deny_unknown_fields) is not set
@@ -4,7 +4,7 @@ use crate::receipt::Receipt;
 use crate::transaction::SignedTransaction;
 use crate::types::validator_stake::{ValidatorStake, ValidatorStakeIter, ValidatorStakeV1};
 use crate::types::{Balance, BlockHeight, Gas, MerkleHash, ShardId, StateRoot};
-use crate::validator_signer::ValidatorSigner;
+use crate::validator_signer::{ValidatorSigner, ValidatorSignerError};
 use crate::version::{ProtocolFeature, ProtocolVersion, SHARD_CHUNK_HEADER_UPGRADE_VERSION};
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_crypto::Signature;
@@ -127,7 +127,7 @@ impl ShardChunkHeaderV2 {
         tx_root: CryptoHash,
         prev_validator_proposals: Vec<ValidatorStakeV1>,
         signer: &dyn ValidatorSigner,
-    ) -> Self {
+    ) -> Result<Self, ValidatorSignerError> {
         let inner = ShardChunkHeaderInnerV1 {
             prev_block_hash,
             prev_state_root,
@@ -144,8 +144,8 @@ impl ShardChunkHeaderV2 {
             prev_validator_proposals,
         };
         let hash = Self::compute_hash(&inner);
-        let signature = signer.sign_chunk_hash(&hash);
-        Self { inner, height_included: 0, signature, hash }
+        let signature = signer.sign_chunk_hash(&hash)?;
+        Ok(Self { inner, height_included: 0, signature, hash })
     }
 }
 
@@ -191,7 +191,7 @@ impl ShardChunkHeaderV3 {
         tx_root: CryptoHash,
         prev_validator_proposals: Vec<ValidatorStake>,
         signer: &dyn ValidatorSigner,
-    ) -> Self {
+    ) -> Result<Self, ValidatorSignerError> {
         let inner = ShardChunkHeaderInner::V2(ShardChunkHeaderInnerV2 {
             prev_block_hash,
             prev_state_root,
@@ -210,10 +210,13 @@ impl ShardChunkHeaderV3 {
         Self::from_inner(inner, signer)
     }
 
-    pub fn from_inner(inner: ShardChunkHeaderInner, signer: &dyn ValidatorSigner) -> Self {
+    pub fn from_inner(
+        inner: ShardChunkHeaderInner,
+        signer: &dyn ValidatorSigner,
+    ) -> Result<Self, ValidatorSignerError> {
         let hash = Self::compute_hash(&inner);
-        let signature = signer.sign_chunk_hash(&hash);
-        Self { inner, height_included: 0, signature, hash }
+        let signature = signer.sign_chunk_hash(&hash)?;
+        Ok(Self { inner, height_included: 0, signature, hash })
     }
 }
 
@@ -461,7 +464,7 @@ impl ShardChunkHeaderV1 {
         tx_root: CryptoHash,
         prev_validator_proposals: Vec<ValidatorStakeV1>,
         signer: &dyn ValidatorSigner,
-    ) -> Self {
+    ) -> Result<Self, ValidatorSignerError> {
         let inner = ShardChunkHeaderInnerV1 {
             prev_block_hash,
             prev_state_root,
@@ -478,8 +481,8 @@ impl ShardChunkHeaderV1 {
             prev_validator_proposals,
         };
         let hash = Self::compute_hash(&inner);
-        let signature = signer.sign_chunk_hash(&hash);
-        Self { inner, height_included: 0, signature, hash }
+        let signature = signer.sign_chunk_hash(&hash)?;
+        Ok(Self { inner, height_included: 0, signature, hash })
     }
 }
 
@@ -1079,7 +1082,8 @@ impl EncodedShardChunk {
                 tx_root,
                 prev_validator_proposals,
                 signer,
-            );
+            )
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
             let chunk = EncodedShardChunkV1 { header, content };
             Ok((Self::V1(chunk), merkle_paths))
         } else if block_header_v3_version.is_none()
@@ -1102,7 +1106,8 @@ impl EncodedShardChunk {
                 tx_root,
                 validator_proposals,
                 signer,
-            );
+            )
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
             let chunk = EncodedShardChunkV2 { header: ShardChunkHeader::V2(header), content };
             Ok((Self::V2(chunk), merkle_paths))
         } else {
@@ -1121,7 +1126,8 @@ impl EncodedShardChunk {
                 tx_root,
                 prev_validator_proposals,
                 signer,
-            );
+            )
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
             let chunk = EncodedShardChunkV2 { header: ShardChunkHeader::V3(header), content };
             Ok((Self::V2(chunk), merkle_paths))
         }
@@ -1,9 +1,13 @@
 use std::collections::{HashMap, HashSet};
 
 use crate::challenge::PartialState;
-use crate::sharding::{ChunkHash, ReceiptProof, ShardChunkHeader, ShardChunkHeaderV3};
+use crate::merkle::{merklize, verify_path, MerklePath};
+use crate::sharding::{
+    ChunkHash, ReceiptProof, ReedSolomonWrapper, ShardChunkHeader, ShardChunkHeaderV3,
+};
 use crate::transaction::SignedTransaction;
-use crate::validator_signer::{EmptyValidatorSigner, ValidatorSigner};
+use crate::types::EpochId;
+use crate::validator_signer::{EmptyValidatorSigner, ValidatorSigner, ValidatorSignerError};
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_crypto::{PublicKey, Signature};
 use near_primitives_core::hash::CryptoHash;
@@ -125,22 +129,25 @@ impl ChunkStateWitness {
         shard_id: ShardId,
         prev_block_hash: CryptoHash,
     ) -> ChunkStateWitness {
-        let header = ShardChunkHeader::V3(ShardChunkHeaderV3::new(
-            prev_block_hash,
-            Default::default(),
-            Default::default(),
-            Default::default(),
-            Default::default(),
-            height,
-            shard_id,
-            Default::default(),
-            Default::default(),
-            Default::default(),
-            Default::default(),
-            Default::default(),
-            Default::default(),
-            &EmptyValidatorSigner::default(),
-        ));
+        let header = ShardChunkHeader::V3(
+            ShardChunkHeaderV3::new(
+                prev_block_hash,
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                height,
+                shard_id,
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+                &EmptyValidatorSigner::default(),
+            )
+            .unwrap(),
+        );
         let inner = ChunkStateWitnessInner::new(
             header,
             Default::default(),
@@ -155,6 +162,174 @@ impl ChunkStateWitness {
     }
 }
 
+/// A part of a `ChunkStateWitness` encoded with Reed-Solomon erasure code.
+/// Instead of sending the whole witness to every chunk validator, the chunk
+/// producer sends part `i` to the `i`-th chunk validator, which forwards it to
+/// the other chunk validators. Any `data_parts` of the parts are enough to
+/// reconstruct the witness (see `witness_part_counts`).
+///
+/// The chunk producer signs the header of the parts (see
+/// `PartialEncodedStateWitnessHeader`), and each part carries a merkle proof of
+/// belonging to the set of parts with root `parts_root`, so only the chunk
+/// producer can make parts which pass the checks.
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct PartialEncodedStateWitness {
+    pub epoch_id: EpochId,
+    pub shard_id: ShardId,
+    pub height_created: BlockHeight,
+    /// Merkle root of all the parts of the witness.
+    pub parts_root: CryptoHash,
+    pub part_ord: u64,
+    pub part: Box<[u8]>,
+    pub merkle_proof: MerklePath,
+    /// Length of the borsh-encoded witness. The encoded witness is padded
+    /// with zeroes to a multiple of the number of data parts.
+    pub encoded_length: u64,
+    /// Signature of the chunk producer over the header of the parts.
+    pub signature: Signature,
+}
+
+/// The part of `PartialEncodedStateWitness` signed by the chunk producer,
+/// the same for all the parts of a witness.
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct PartialEncodedStateWitnessHeader {
+    pub epoch_id: EpochId,
+    pub shard_id: ShardId,
+    pub height_created: BlockHeight,
+    pub parts_root: CryptoHash,
+    pub encoded_length: u64,
+    signature_differentiator: SignatureDifferentiator,
+}
+
+impl PartialEncodedStateWitnessHeader {
+    pub fn new(
+        epoch_id: EpochId,
+        shard_id: ShardId,
+        height_created: BlockHeight,
+        parts_root: CryptoHash,
+        encoded_length: u64,
+    ) -> Self {
+        Self {
+            epoch_id,
+            shard_id,
+            height_created,
+            parts_root,
+            encoded_length,
+            signature_differentiator: "PartialEncodedStateWitness".to_owned(),
+        }
+    }
+}
+
+/// Returns the number of data parts and the total number of parts a witness
+/// is encoded into when it's distributed to `num_chunk_validators` validators.
+/// A third of the parts is enough to reconstruct the witness, the same as
+/// for chunks. There is always at least one parity part, because Reed-Solomon
+/// doesn't work without them; the extra parts are simply not sent.
+pub fn witness_part_counts(num_chunk_validators: usize) -> (usize, usize) {
+    let data_parts = std::cmp::max(num_chunk_validators / 3, 1);
+    let total_parts = std::cmp::max(num_chunk_validators, data_parts + 1);
+    (data_parts, total_parts)
+}
+
+impl PartialEncodedStateWitness {
+    /// Encodes the witness into parts, one for each of `num_chunk_validators`
+    /// chunk validators in the order of `ordered_chunk_validators`.
+    /// `signer` is the chunk producer.
+    pub fn encode(
+        epoch_id: EpochId,
+        witness: &ChunkStateWitness,
+        num_chunk_validators: usize,
+        signer: &dyn ValidatorSigner,
+    ) -> Result<Vec<PartialEncodedStateWitness>, std::io::Error> {
+        let (data_parts, total_parts) = witness_part_counts(num_chunk_validators);
+        let mut bytes = borsh::to_vec(witness)?;
+        let encoded_length = bytes.len();
+        let part_length = Self::part_length(encoded_length as u64, data_parts) as usize;
+        bytes.resize(part_length * data_parts, 0);
+
+        let mut parts: Vec<Option<Box<[u8]>>> =
+            bytes.chunks(part_length).map(|part| Some(part.to_vec().into_boxed_slice())).collect();
+        parts.resize(total_parts, None);
+        ReedSolomonWrapper::new(data_parts, total_parts - data_parts)
+            .reconstruct(parts.as_mut_slice())
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+
+        let parts: Vec<Box<[u8]>> = parts.into_iter().map(Option::unwrap).collect();
+        let (parts_root, merkle_proofs) = merklize(&parts);
+        let chunk_header = &witness.inner.chunk_header;
+        let header = PartialEncodedStateWitnessHeader::new(
+            epoch_id,
+            chunk_header.shard_id(),
+            chunk_header.height_created(),
+            parts_root,
+            encoded_length as u64,
+        );
+        let signature = signer
+            .sign_partial_encoded_state_witness_header(&header)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+        Ok(parts
+            .into_iter()
+            .zip(merkle_proofs)
+            .enumerate()
+            .take(num_chunk_validators)
+            .map(|(part_ord, (part, merkle_proof))| PartialEncodedStateWitness {
+                epoch_id: header.epoch_id.clone(),
+                shard_id: header.shard_id,
+                height_created: header.height_created,
+                parts_root,
+                part_ord: part_ord as u64,
+                part,
+                merkle_proof,
+                encoded_length: header.encoded_length,
+                signature: signature.clone(),
+            })
+            .collect())
+    }
+
+    pub fn header(&self) -> PartialEncodedStateWitnessHeader {
+        PartialEncodedStateWitnessHeader::new(
+            self.epoch_id.clone(),
+            self.shard_id,
+            self.height_created,
+            self.parts_root,
+            self.encoded_length,
+        )
+    }
+
+    /// Checks that the header of the part is signed by the chunk producer with `public_key`.
+    pub fn verify_signature(&self, public_key: &PublicKey) -> bool {
+        self.signature.verify(&borsh::to_vec(&self.header()).unwrap(), public_key)
+    }
+
+    /// Length of each of the parts of a witness with the given encoded length.
+    pub fn part_length(encoded_length: u64, data_parts: usize) -> u64 {
+        (encoded_length + data_parts as u64 - 1) / data_parts as u64
+    }
+
+    /// Checks that the part belongs to the set of parts with root `parts_root`.
+    pub fn verify_merkle_proof(&self) -> bool {
+        verify_path(self.parts_root, &self.merkle_proof, &self.part)
+    }
+
+    /// Reconstructs the witness from the parts, indexed by their ordinals.
+    /// At least `data_parts` of the `parts` have to be present.
+    pub fn decode(
+        parts: &mut [Option<Box<[u8]>>],
+        data_parts: usize,
+        encoded_length: u64,
+    ) -> Result<ChunkStateWitness, std::io::Error> {
+        ReedSolomonWrapper::new(data_parts, parts.len() - data_parts)
+            .reconstruct(parts)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))?;
+        let bytes: Vec<u8> = parts[..data_parts]
+            .iter()
+            .flat_map(|part| part.as_deref().unwrap().iter().copied())
+            .take(encoded_length as usize)
+            .collect();
+        ChunkStateWitness::try_from_slice(&bytes)
+    }
+}
+
 /// Represents the base state and the expected post-state-root of a chunk's state
 /// transition. The actual state transition itself is not included here.
 #[derive(Debug, Default, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
@@ -183,11 +358,14 @@ pub struct ChunkEndorsement {
 }
 
 impl ChunkEndorsement {
-    pub fn new(chunk_hash: ChunkHash, signer: &dyn ValidatorSigner) -> ChunkEndorsement {
+    pub fn new(
+        chunk_hash: ChunkHash,
+        signer: &dyn ValidatorSigner,
+    ) -> Result<ChunkEndorsement, ValidatorSignerError> {
         let inner = ChunkEndorsementInner::new(chunk_hash);
         let account_id = signer.validator_id().clone();
-        let signature = signer.sign_chunk_endorsement(&inner);
-        Self { inner, account_id, signature }
+        let signature = signer.sign_chunk_endorsement(&inner)?;
+        Ok(Self { inner, account_id, signature })
     }
 
     pub fn verify(&self, public_key: &PublicKey) -> bool {
@@ -238,6 +416,16 @@ pub struct StoredChunkStateTransitionData {
     pub receipts_hash: CryptoHash,
 }
 
+/// Chunk state witness saved on disk for debugging, together with the result
+/// of its validation. Only stored when enabled in the node config.
+#[derive(Debug, Clone, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct SavedChunkStateWitness {
+    pub witness: ChunkStateWitness,
+    /// The error returned by the validation of the witness, or None if the witness
+    /// was validated successfully.
+    pub validation_error: Option<String>,
+}
+
 #[derive(Debug)]
 pub struct EndorsementStats {
     pub total_stake: Balance,
@@ -302,3 +490,60 @@ impl ChunkValidatorAssignments {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{witness_part_counts, ChunkStateWitness, PartialEncodedStateWitness};
+    use crate::hash::hash;
+    use crate::test_utils::create_test_signer;
+    use crate::types::EpochId;
+    use crate::validator_signer::ValidatorSigner;
+
+    #[test]
+    fn test_partial_encoded_state_witness_roundtrip() {
+        let witness = ChunkStateWitness::new_dummy(10, 1, hash(b"prev"));
+        for num_validators in [1, 2, 3, 10] {
+            let parts = PartialEncodedStateWitness::encode(
+                EpochId::default(),
+                &witness,
+                num_validators,
+                &create_test_signer("test"),
+            )
+            .unwrap();
+            assert_eq!(parts.len(), num_validators);
+            assert!(parts.iter().all(|part| part.verify_merkle_proof()));
+
+            // Reconstruct from the last `data_parts` parts.
+            let (data_parts, total_parts) = witness_part_counts(num_validators);
+            let mut received = vec![None; total_parts];
+            for part in &parts[num_validators - data_parts..] {
+                received[part.part_ord as usize] = Some(part.part.clone());
+            }
+            let decoded = PartialEncodedStateWitness::decode(
+                &mut received,
+                data_parts,
+                parts[0].encoded_length,
+            )
+            .unwrap();
+            assert_eq!(decoded, witness);
+        }
+    }
+
+    #[test]
+    fn test_partial_encoded_state_witness_forged_part() {
+        let witness = ChunkStateWitness::new_dummy(10, 1, hash(b"prev"));
+        let signer = create_test_signer("test");
+        let mut parts =
+            PartialEncodedStateWitness::encode(EpochId::default(), &witness, 4, &signer).unwrap();
+        parts[0].part[0] ^= 1;
+        assert!(!parts[0].verify_merkle_proof());
+        assert!(parts[1].verify_merkle_proof());
+
+        // The header is signed by the chunk producer.
+        assert!(parts[1].verify_signature(&signer.public_key()));
+        assert!(!parts[1].verify_signature(&create_test_signer("other").public_key()));
+        let mut forged = parts[1].clone();
+        forged.encoded_length += 1;
+        assert!(!forged.verify_signature(&signer.public_key()));
+    }
+}
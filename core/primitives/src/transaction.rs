@@ -18,7 +18,8 @@ use std::hash::{Hash, Hasher};
 pub use crate::action::NonrefundableStorageTransferAction;
 pub use crate::action::{
     Action, AddKeyAction, CreateAccountAction, DeleteAccountAction, DeleteKeyAction,
-    DeployContractAction, FunctionCallAction, StakeAction, TransferAction,
+    DeployContractAction, DeployContractWithCodeHashCheckAction, DeployGlobalContractAction,
+    FunctionCallAction, StakeAction, TransferAction, UseGlobalContractAction,
 };
 
 pub type LogEntry = String;
@@ -50,8 +50,11 @@ pub mod col {
     /// This column id is used when storing the postponed promise yield receipts
     /// (`primitives::receipt::Receipt`).
     pub const PROMISE_YIELD_RECEIPT: u8 = 12;
-    /// All columns except those used for the delayed receipts queue and the yielded promises
-    /// queue, which are both global state for the shard.
+    /// This column id is used when storing contract code deployed globally, keyed by the hash of
+    /// the code rather than by an account id.
+    pub const GLOBAL_CONTRACT_CODE: u8 = 13;
+    /// All columns except those used for the delayed receipts queue, the yielded promises
+    /// queue and the global contract code, which are all global state for the shard.
     pub const COLUMNS_WITH_ACCOUNT_ID_IN_KEY: [(u8, &str); 9] = [
         (ACCOUNT, "Account"),
         (CONTRACT_CODE, "ContractCode"),
@@ -109,6 +112,10 @@ pub enum TrieKey {
     /// Used to store the postponed promise yield receipt `primitives::receipt::Receipt`
     /// for a given receiver's `AccountId` and a given `data_id`.
     PromiseYieldReceipt { receiver_id: AccountId, data_id: CryptoHash },
+    /// Used to store `Vec<u8>` contract code deployed globally for a given code hash.
+    /// NOTE: Accounts refer to such code through their `code_hash`.
+    /// NOTE: The code is stored per shard and copied to all children shards on resharding.
+    GlobalContractCode { code_hash: CryptoHash },
 }
 
 /// Provides `len` function.
@@ -178,6 +185,9 @@ impl TrieKey {
                     + ACCOUNT_DATA_SEPARATOR.len()
                     + key.len()
             }
+            TrieKey::GlobalContractCode { code_hash } => {
+                col::GLOBAL_CONTRACT_CODE.len() + code_hash.as_ref().len()
+            }
         }
     }
 
@@ -250,6 +260,10 @@ impl TrieKey {
                 buf.push(ACCOUNT_DATA_SEPARATOR);
                 buf.extend(data_id.as_ref());
             }
+            TrieKey::GlobalContractCode { code_hash } => {
+                buf.push(col::GLOBAL_CONTRACT_CODE);
+                buf.extend(code_hash.as_ref());
+            }
         };
         debug_assert_eq!(expected_len, buf.len() - start_len);
     }
@@ -276,6 +290,7 @@ impl TrieKey {
             TrieKey::YieldedPromiseQueueIndices => None,
             TrieKey::YieldedPromiseQueueEntry { .. } => None,
             TrieKey::PromiseYieldReceipt { receiver_id, .. } => Some(receiver_id.clone()),
+            TrieKey::GlobalContractCode { .. } => None,
         }
     }
 }
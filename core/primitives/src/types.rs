@@ -373,6 +373,7 @@ impl StateChanges {
                 TrieKey::YieldedPromiseQueueIndices => {}
                 TrieKey::YieldedPromiseQueueEntry { .. } => {}
                 TrieKey::PromiseYieldReceipt { .. } => {}
+                TrieKey::GlobalContractCode { .. } => {}
             }
         }
 
@@ -1,5 +1,6 @@
+use std::collections::HashMap;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use near_crypto::{InMemorySigner, KeyType, PublicKey, Signature, Signer};
 
@@ -8,11 +9,21 @@ use crate::challenge::ChallengeBody;
 use crate::hash::CryptoHash;
 use crate::network::{AnnounceAccount, PeerId};
 use crate::sharding::ChunkHash;
-use crate::stateless_validation::{ChunkEndorsementInner, ChunkStateWitnessInner};
+use crate::stateless_validation::{
+    ChunkEndorsementInner, ChunkStateWitnessInner, PartialEncodedStateWitnessHeader,
+};
 use crate::telemetry::TelemetryInfo;
 use crate::types::{AccountId, BlockHeight, EpochId};
 
+/// Error of a `ValidatorSigner` which doesn't hold the validator key itself, e.g. because
+/// signing is delegated to a remote signer, which may be unreachable or refuse to sign.
+#[derive(thiserror::Error, Debug)]
+#[error("failed to sign with the validator key: {0}")]
+pub struct ValidatorSignerError(pub Box<dyn std::error::Error + Send + Sync>);
+
 /// Validator signer that is used to sign blocks and approvals.
+/// Signing fails only for signers which don't hold the key in memory, in which case nothing
+/// should be published instead of the signed message.
 pub trait ValidatorSigner: Sync + Send {
     /// Account id of the given validator.
     fn validator_id(&self) -> &AccountId;
@@ -21,7 +32,10 @@ pub trait ValidatorSigner: Sync + Send {
     fn public_key(&self) -> PublicKey;
 
     /// Serializes telemetry info to JSON and signs it, returning JSON with "signature" field.
-    fn sign_telemetry(&self, info: &TelemetryInfo) -> serde_json::Value;
+    fn sign_telemetry(
+        &self,
+        info: &TelemetryInfo,
+    ) -> Result<serde_json::Value, ValidatorSignerError>;
 
     /// Signs given parts of the header.
     fn sign_block_header_parts(
@@ -29,23 +43,42 @@ pub trait ValidatorSigner: Sync + Send {
         prev_hash: CryptoHash,
         inner_lite: &[u8],
         inner_rest: &[u8],
-    ) -> (CryptoHash, Signature);
+    ) -> Result<(CryptoHash, Signature), ValidatorSignerError>;
 
     /// Signs given inner of the chunk header.
-    fn sign_chunk_hash(&self, chunk_hash: &ChunkHash) -> Signature;
+    fn sign_chunk_hash(&self, chunk_hash: &ChunkHash) -> Result<Signature, ValidatorSignerError>;
 
     /// Signs approval of given parent hash and reference hash.
-    fn sign_approval(&self, inner: &ApprovalInner, target_height: BlockHeight) -> Signature;
+    fn sign_approval(
+        &self,
+        inner: &ApprovalInner,
+        target_height: BlockHeight,
+    ) -> Result<Signature, ValidatorSignerError>;
 
     /// Signs approval of the given chunk.
-    fn sign_chunk_endorsement(&self, inner: &ChunkEndorsementInner) -> Signature;
+    fn sign_chunk_endorsement(
+        &self,
+        inner: &ChunkEndorsementInner,
+    ) -> Result<Signature, ValidatorSignerError>;
 
     /// Signs approval of the given chunk.
     /// Returns signature and a signed payload size in bytes
-    fn sign_chunk_state_witness(&self, inner: &ChunkStateWitnessInner) -> (Signature, usize);
+    fn sign_chunk_state_witness(
+        &self,
+        inner: &ChunkStateWitnessInner,
+    ) -> Result<(Signature, usize), ValidatorSignerError>;
+
+    /// Signs the header of the parts of a chunk state witness.
+    fn sign_partial_encoded_state_witness_header(
+        &self,
+        header: &PartialEncodedStateWitnessHeader,
+    ) -> Result<Signature, ValidatorSignerError>;
 
     /// Signs challenge body.
-    fn sign_challenge(&self, challenge_body: &ChallengeBody) -> (CryptoHash, Signature);
+    fn sign_challenge(
+        &self,
+        challenge_body: &ChallengeBody,
+    ) -> Result<(CryptoHash, Signature), ValidatorSignerError>;
 
     /// Signs account announce.
     fn sign_account_announce(
@@ -53,7 +86,7 @@ pub trait ValidatorSigner: Sync + Send {
         account_id: &AccountId,
         peer_id: &PeerId,
         epoch_id: &EpochId,
-    ) -> Signature;
+    ) -> Result<Signature, ValidatorSignerError>;
 
     /// Signs a proto-serialized AccountKeyPayload (see
     /// chain/network/src/network_protocol/network.proto).
@@ -66,15 +99,23 @@ pub trait ValidatorSigner: Sync + Send {
     /// used only for networking purposes and are not persisted on chain.
     /// Moving to proto serialization for stuff stored on chain would be way
     /// harder.
-    fn sign_account_key_payload(&self, proto_bytes: &[u8]) -> Signature;
+    fn sign_account_key_payload(
+        &self,
+        proto_bytes: &[u8],
+    ) -> Result<Signature, ValidatorSignerError>;
 
     fn compute_vrf_with_proof(
         &self,
         data: &[u8],
-    ) -> (near_crypto::vrf::Value, near_crypto::vrf::Proof);
+    ) -> Result<(near_crypto::vrf::Value, near_crypto::vrf::Proof), ValidatorSignerError>;
 
     /// Used by test infrastructure, only implement if make sense for testing otherwise raise `unimplemented`.
     fn write_to_file(&self, path: &Path) -> std::io::Result<()>;
+
+    /// Returns the signer if it can switch between multiple keys.
+    fn as_rotating(&self) -> Option<&RotatingValidatorSigner> {
+        None
+    }
 }
 
 /// Test-only signer that "signs" everything with 0s.
@@ -94,8 +135,11 @@ impl ValidatorSigner for EmptyValidatorSigner {
         PublicKey::empty(KeyType::ED25519)
     }
 
-    fn sign_telemetry(&self, _info: &TelemetryInfo) -> serde_json::Value {
-        serde_json::Value::default()
+    fn sign_telemetry(
+        &self,
+        _info: &TelemetryInfo,
+    ) -> Result<serde_json::Value, ValidatorSignerError> {
+        Ok(serde_json::Value::default())
     }
 
     fn sign_block_header_parts(
@@ -103,29 +147,49 @@ impl ValidatorSigner for EmptyValidatorSigner {
         prev_hash: CryptoHash,
         inner_lite: &[u8],
         inner_rest: &[u8],
-    ) -> (CryptoHash, Signature) {
+    ) -> Result<(CryptoHash, Signature), ValidatorSignerError> {
         let hash = BlockHeader::compute_hash(prev_hash, inner_lite, inner_rest);
-        (hash, Signature::default())
+        Ok((hash, Signature::default()))
     }
 
-    fn sign_chunk_hash(&self, _chunk_hash: &ChunkHash) -> Signature {
-        Signature::default()
+    fn sign_chunk_hash(&self, _chunk_hash: &ChunkHash) -> Result<Signature, ValidatorSignerError> {
+        Ok(Signature::default())
     }
 
-    fn sign_approval(&self, _inner: &ApprovalInner, _target_height: BlockHeight) -> Signature {
-        Signature::default()
+    fn sign_approval(
+        &self,
+        _inner: &ApprovalInner,
+        _target_height: BlockHeight,
+    ) -> Result<Signature, ValidatorSignerError> {
+        Ok(Signature::default())
     }
 
-    fn sign_chunk_endorsement(&self, _inner: &ChunkEndorsementInner) -> Signature {
-        Signature::default()
+    fn sign_chunk_endorsement(
+        &self,
+        _inner: &ChunkEndorsementInner,
+    ) -> Result<Signature, ValidatorSignerError> {
+        Ok(Signature::default())
     }
 
-    fn sign_chunk_state_witness(&self, _inner: &ChunkStateWitnessInner) -> (Signature, usize) {
-        (Signature::default(), 0)
+    fn sign_chunk_state_witness(
+        &self,
+        _inner: &ChunkStateWitnessInner,
+    ) -> Result<(Signature, usize), ValidatorSignerError> {
+        Ok((Signature::default(), 0))
     }
 
-    fn sign_challenge(&self, challenge_body: &ChallengeBody) -> (CryptoHash, Signature) {
-        (CryptoHash::hash_borsh(challenge_body), Signature::default())
+    fn sign_partial_encoded_state_witness_header(
+        &self,
+        _header: &PartialEncodedStateWitnessHeader,
+    ) -> Result<Signature, ValidatorSignerError> {
+        Ok(Signature::default())
+    }
+
+    fn sign_challenge(
+        &self,
+        challenge_body: &ChallengeBody,
+    ) -> Result<(CryptoHash, Signature), ValidatorSignerError> {
+        Ok((CryptoHash::hash_borsh(challenge_body), Signature::default()))
     }
 
     fn sign_account_announce(
@@ -133,18 +197,21 @@ impl ValidatorSigner for EmptyValidatorSigner {
         _account_id: &AccountId,
         _peer_id: &PeerId,
         _epoch_id: &EpochId,
-    ) -> Signature {
-        Signature::default()
+    ) -> Result<Signature, ValidatorSignerError> {
+        Ok(Signature::default())
     }
 
-    fn sign_account_key_payload(&self, _proto_bytes: &[u8]) -> Signature {
-        Signature::default()
+    fn sign_account_key_payload(
+        &self,
+        _proto_bytes: &[u8],
+    ) -> Result<Signature, ValidatorSignerError> {
+        Ok(Signature::default())
     }
 
     fn compute_vrf_with_proof(
         &self,
         _data: &[u8],
-    ) -> (near_crypto::vrf::Value, near_crypto::vrf::Proof) {
+    ) -> Result<(near_crypto::vrf::Value, near_crypto::vrf::Proof), ValidatorSignerError> {
         unimplemented!()
     }
 
@@ -190,11 +257,14 @@ impl ValidatorSigner for InMemoryValidatorSigner {
         self.signer.public_key()
     }
 
-    fn sign_telemetry(&self, info: &TelemetryInfo) -> serde_json::Value {
+    fn sign_telemetry(
+        &self,
+        info: &TelemetryInfo,
+    ) -> Result<serde_json::Value, ValidatorSignerError> {
         let mut value = serde_json::to_value(info).expect("Telemetry must serialize to JSON");
         let content = serde_json::to_string(&value).expect("Telemetry must serialize to JSON");
         value["signature"] = self.signer.sign(content.as_bytes()).to_string().into();
-        value
+        Ok(value)
     }
 
     fn sign_block_header_parts(
@@ -202,32 +272,52 @@ impl ValidatorSigner for InMemoryValidatorSigner {
         prev_hash: CryptoHash,
         inner_lite: &[u8],
         inner_rest: &[u8],
-    ) -> (CryptoHash, Signature) {
+    ) -> Result<(CryptoHash, Signature), ValidatorSignerError> {
         let hash = BlockHeader::compute_hash(prev_hash, inner_lite, inner_rest);
-        (hash, self.signer.sign(hash.as_ref()))
+        Ok((hash, self.signer.sign(hash.as_ref())))
     }
 
-    fn sign_chunk_hash(&self, chunk_hash: &ChunkHash) -> Signature {
-        self.signer.sign(chunk_hash.as_ref())
+    fn sign_chunk_hash(&self, chunk_hash: &ChunkHash) -> Result<Signature, ValidatorSignerError> {
+        Ok(self.signer.sign(chunk_hash.as_ref()))
     }
 
-    fn sign_approval(&self, inner: &ApprovalInner, target_height: BlockHeight) -> Signature {
-        self.signer.sign(&Approval::get_data_for_sig(inner, target_height))
+    fn sign_approval(
+        &self,
+        inner: &ApprovalInner,
+        target_height: BlockHeight,
+    ) -> Result<Signature, ValidatorSignerError> {
+        Ok(self.signer.sign(&Approval::get_data_for_sig(inner, target_height)))
     }
 
-    fn sign_chunk_endorsement(&self, inner: &ChunkEndorsementInner) -> Signature {
-        self.signer.sign(&borsh::to_vec(inner).unwrap())
+    fn sign_chunk_endorsement(
+        &self,
+        inner: &ChunkEndorsementInner,
+    ) -> Result<Signature, ValidatorSignerError> {
+        Ok(self.signer.sign(&borsh::to_vec(inner).unwrap()))
     }
 
-    fn sign_chunk_state_witness(&self, inner: &ChunkStateWitnessInner) -> (Signature, usize) {
+    fn sign_chunk_state_witness(
+        &self,
+        inner: &ChunkStateWitnessInner,
+    ) -> Result<(Signature, usize), ValidatorSignerError> {
         let data = borsh::to_vec(inner).unwrap();
-        (self.signer.sign(&data), data.len())
+        Ok((self.signer.sign(&data), data.len()))
     }
 
-    fn sign_challenge(&self, challenge_body: &ChallengeBody) -> (CryptoHash, Signature) {
+    fn sign_partial_encoded_state_witness_header(
+        &self,
+        header: &PartialEncodedStateWitnessHeader,
+    ) -> Result<Signature, ValidatorSignerError> {
+        Ok(self.signer.sign(&borsh::to_vec(header).unwrap()))
+    }
+
+    fn sign_challenge(
+        &self,
+        challenge_body: &ChallengeBody,
+    ) -> Result<(CryptoHash, Signature), ValidatorSignerError> {
         let hash = CryptoHash::hash_borsh(challenge_body);
         let signature = self.signer.sign(hash.as_ref());
-        (hash, signature)
+        Ok((hash, signature))
     }
 
     fn sign_account_announce(
@@ -235,23 +325,343 @@ impl ValidatorSigner for InMemoryValidatorSigner {
         account_id: &AccountId,
         peer_id: &PeerId,
         epoch_id: &EpochId,
-    ) -> Signature {
+    ) -> Result<Signature, ValidatorSignerError> {
         let hash = AnnounceAccount::build_header_hash(account_id, peer_id, epoch_id);
-        self.signer.sign(hash.as_ref())
+        Ok(self.signer.sign(hash.as_ref()))
     }
 
-    fn sign_account_key_payload(&self, proto_bytes: &[u8]) -> Signature {
-        self.signer.sign(proto_bytes)
+    fn sign_account_key_payload(
+        &self,
+        proto_bytes: &[u8],
+    ) -> Result<Signature, ValidatorSignerError> {
+        Ok(self.signer.sign(proto_bytes))
     }
 
     fn compute_vrf_with_proof(
         &self,
         data: &[u8],
-    ) -> (near_crypto::vrf::Value, near_crypto::vrf::Proof) {
-        self.signer.compute_vrf_with_proof(data)
+    ) -> Result<(near_crypto::vrf::Value, near_crypto::vrf::Proof), ValidatorSignerError> {
+        Ok(self.signer.compute_vrf_with_proof(data))
     }
 
     fn write_to_file(&self, path: &Path) -> std::io::Result<()> {
         self.signer.write_to_file(path)
     }
 }
+
+/// Returns the signer to sign the messages of the epoch `epoch_id` with: for a
+/// `RotatingValidatorSigner` it is the validator's key in that epoch, if known and held.
+pub fn signer_for_epoch(
+    signer: &Arc<dyn ValidatorSigner>,
+    epoch_id: &EpochId,
+) -> Arc<dyn ValidatorSigner> {
+    signer
+        .as_rotating()
+        .and_then(|rotating| rotating.signer_for_epoch(epoch_id))
+        .unwrap_or_else(|| signer.clone())
+}
+
+/// Signer holding multiple keys of the same validator, which signs with the active one.
+/// Allows rotating the validator key without restarting the node: the new key is added
+/// to the signer before the stake transaction with it gets into the validator set, and
+/// the client makes it active once the epoch in which it is the validator's key starts.
+///
+/// Around the epoch boundary messages of both epochs are signed, so the client also
+/// registers the validator's key in each of the recent epochs, see `signer_for_epoch`.
+pub struct RotatingValidatorSigner {
+    account_id: AccountId,
+    keys: RwLock<RotatingKeys>,
+}
+
+struct RotatingKeys {
+    signers: Vec<Arc<dyn ValidatorSigner>>,
+    active: usize,
+    /// Key which has been requested to be active, but isn't held by the signer.
+    missing: Option<PublicKey>,
+    /// Validator's keys in the recent epochs.
+    epoch_keys: HashMap<EpochId, PublicKey>,
+}
+
+impl RotatingValidatorSigner {
+    /// `signers` should hold keys of the same account, the first one is active initially.
+    pub fn new(signers: Vec<Arc<dyn ValidatorSigner>>) -> Self {
+        assert!(!signers.is_empty(), "at least one validator key is required");
+        let account_id = signers[0].validator_id().clone();
+        let this = Self {
+            account_id,
+            keys: RwLock::new(RotatingKeys {
+                signers: vec![],
+                active: 0,
+                missing: None,
+                epoch_keys: HashMap::new(),
+            }),
+        };
+        this.set_signers(signers);
+        this
+    }
+
+    fn active(&self) -> Arc<dyn ValidatorSigner> {
+        let keys = self.keys.read().unwrap();
+        keys.signers[keys.active].clone()
+    }
+
+    /// Replaces the keys held by the signer. The key which has been requested to be
+    /// active becomes active if it is held now, the active key stays active if it is
+    /// still held, otherwise the first key becomes active. Keys of other accounts
+    /// are ignored.
+    pub fn set_signers(&self, signers: Vec<Arc<dyn ValidatorSigner>>) {
+        let (signers, ignored): (Vec<_>, Vec<_>) =
+            signers.into_iter().partition(|signer| signer.validator_id() == &self.account_id);
+        for signer in ignored {
+            tracing::error!(
+                target: "validator_signer",
+                account_id = %signer.validator_id(),
+                public_key = %signer.public_key(),
+                "ignoring the key of another account, expected {}",
+                self.account_id
+            );
+        }
+        if signers.is_empty() {
+            tracing::error!(target: "validator_signer", "no validator keys to switch to, keeping the current ones");
+            return;
+        }
+        let mut keys = self.keys.write().unwrap();
+        let active_key = keys
+            .missing
+            .clone()
+            .filter(|missing| signers.iter().any(|signer| &signer.public_key() == missing))
+            .or_else(|| keys.signers.get(keys.active).map(|signer| signer.public_key()));
+        keys.active =
+            signers.iter().position(|signer| Some(signer.public_key()) == active_key).unwrap_or(0);
+        keys.signers = signers;
+        keys.missing = None;
+        tracing::info!(
+            target: "validator_signer",
+            keys = ?keys.signers.iter().map(|signer| signer.public_key()).collect::<Vec<_>>(),
+            active = %keys.signers[keys.active].public_key(),
+            "updated validator keys"
+        );
+    }
+
+    /// Makes the key `public_key` active. Returns false if the signer doesn't hold it.
+    pub fn activate(&self, public_key: &PublicKey) -> bool {
+        if &self.active().public_key() == public_key {
+            return true;
+        }
+        let mut keys = self.keys.write().unwrap();
+        match keys.signers.iter().position(|signer| &signer.public_key() == public_key) {
+            Some(index) => {
+                tracing::info!(
+                    target: "validator_signer",
+                    from = %keys.signers[keys.active].public_key(),
+                    to = %public_key,
+                    "switching the validator key"
+                );
+                keys.active = index;
+                keys.missing = None;
+                true
+            }
+            None => {
+                if keys.missing.as_ref() != Some(public_key) {
+                    tracing::warn!(
+                        target: "validator_signer",
+                        %public_key,
+                        "the validator key is not held by the node, add its key file to `additional_validator_key_files`"
+                    );
+                    keys.missing = Some(public_key.clone());
+                }
+                false
+            }
+        }
+    }
+
+    /// Sets the validator's keys in the recent epochs, replacing the previous ones.
+    pub fn set_epoch_keys(&self, epoch_keys: HashMap<EpochId, PublicKey>) {
+        self.keys.write().unwrap().epoch_keys = epoch_keys;
+    }
+
+    /// Returns the signer holding the validator's key in the epoch `epoch_id`, or None
+    /// if the key in the epoch isn't known or isn't held by the signer.
+    pub fn signer_for_epoch(&self, epoch_id: &EpochId) -> Option<Arc<dyn ValidatorSigner>> {
+        let keys = self.keys.read().unwrap();
+        let public_key = keys.epoch_keys.get(epoch_id)?;
+        keys.signers.iter().find(|signer| &signer.public_key() == public_key).cloned()
+    }
+
+    /// Public keys of all the keys held by the signer.
+    pub fn public_keys(&self) -> Vec<PublicKey> {
+        self.keys.read().unwrap().signers.iter().map(|signer| signer.public_key()).collect()
+    }
+}
+
+impl ValidatorSigner for RotatingValidatorSigner {
+    fn validator_id(&self) -> &AccountId {
+        &self.account_id
+    }
+
+    fn public_key(&self) -> PublicKey {
+        self.active().public_key()
+    }
+
+    fn sign_telemetry(
+        &self,
+        info: &TelemetryInfo,
+    ) -> Result<serde_json::Value, ValidatorSignerError> {
+        self.active().sign_telemetry(info)
+    }
+
+    fn sign_block_header_parts(
+        &self,
+        prev_hash: CryptoHash,
+        inner_lite: &[u8],
+        inner_rest: &[u8],
+    ) -> Result<(CryptoHash, Signature), ValidatorSignerError> {
+        self.active().sign_block_header_parts(prev_hash, inner_lite, inner_rest)
+    }
+
+    fn sign_chunk_hash(&self, chunk_hash: &ChunkHash) -> Result<Signature, ValidatorSignerError> {
+        self.active().sign_chunk_hash(chunk_hash)
+    }
+
+    fn sign_approval(
+        &self,
+        inner: &ApprovalInner,
+        target_height: BlockHeight,
+    ) -> Result<Signature, ValidatorSignerError> {
+        self.active().sign_approval(inner, target_height)
+    }
+
+    fn sign_chunk_endorsement(
+        &self,
+        inner: &ChunkEndorsementInner,
+    ) -> Result<Signature, ValidatorSignerError> {
+        self.active().sign_chunk_endorsement(inner)
+    }
+
+    fn sign_chunk_state_witness(
+        &self,
+        inner: &ChunkStateWitnessInner,
+    ) -> Result<(Signature, usize), ValidatorSignerError> {
+        self.active().sign_chunk_state_witness(inner)
+    }
+
+    fn sign_partial_encoded_state_witness_header(
+        &self,
+        header: &PartialEncodedStateWitnessHeader,
+    ) -> Result<Signature, ValidatorSignerError> {
+        self.active().sign_partial_encoded_state_witness_header(header)
+    }
+
+    fn sign_challenge(
+        &self,
+        challenge_body: &ChallengeBody,
+    ) -> Result<(CryptoHash, Signature), ValidatorSignerError> {
+        self.active().sign_challenge(challenge_body)
+    }
+
+    fn sign_account_announce(
+        &self,
+        account_id: &AccountId,
+        peer_id: &PeerId,
+        epoch_id: &EpochId,
+    ) -> Result<Signature, ValidatorSignerError> {
+        self.active().sign_account_announce(account_id, peer_id, epoch_id)
+    }
+
+    fn sign_account_key_payload(
+        &self,
+        proto_bytes: &[u8],
+    ) -> Result<Signature, ValidatorSignerError> {
+        self.active().sign_account_key_payload(proto_bytes)
+    }
+
+    fn compute_vrf_with_proof(
+        &self,
+        data: &[u8],
+    ) -> Result<(near_crypto::vrf::Value, near_crypto::vrf::Proof), ValidatorSignerError> {
+        self.active().compute_vrf_with_proof(data)
+    }
+
+    fn write_to_file(&self, path: &Path) -> std::io::Result<()> {
+        self.active().write_to_file(path)
+    }
+
+    fn as_rotating(&self) -> Option<&RotatingValidatorSigner> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        signer_for_epoch, InMemoryValidatorSigner, RotatingValidatorSigner, ValidatorSigner,
+    };
+    use crate::hash::CryptoHash;
+    use crate::types::EpochId;
+    use near_crypto::KeyType;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    fn signer(account_id: &str, seed: &str) -> Arc<dyn ValidatorSigner> {
+        Arc::new(InMemoryValidatorSigner::from_seed(
+            account_id.parse().unwrap(),
+            KeyType::ED25519,
+            seed,
+        ))
+    }
+
+    #[test]
+    fn test_rotating_signer() {
+        let old = signer("test", "old");
+        let new = signer("test", "new");
+        let rotating = RotatingValidatorSigner::new(vec![old.clone()]);
+        assert_eq!(rotating.public_key(), old.public_key());
+        assert!(!rotating.activate(&new.public_key()));
+
+        // Adding a key doesn't change the active one.
+        rotating.set_signers(vec![new.clone(), old.clone(), signer("other", "other")]);
+        assert_eq!(rotating.public_keys(), vec![new.public_key(), old.public_key()]);
+        assert_eq!(rotating.public_key(), old.public_key());
+
+        assert!(rotating.activate(&new.public_key()));
+        assert_eq!(rotating.public_key(), new.public_key());
+        let data = b"data";
+        assert!(rotating.sign_account_key_payload(data).unwrap().verify(data, &new.public_key()));
+
+        // Removing the active key makes the first one active.
+        rotating.set_signers(vec![old.clone()]);
+        assert_eq!(rotating.public_key(), old.public_key());
+        // Keys of other accounts are never used.
+        rotating.set_signers(vec![signer("other", "other")]);
+        assert_eq!(rotating.public_key(), old.public_key());
+
+        // The requested key becomes active once it is added.
+        assert!(!rotating.activate(&new.public_key()));
+        rotating.set_signers(vec![old.clone(), new.clone()]);
+        assert_eq!(rotating.public_key(), new.public_key());
+    }
+
+    #[test]
+    fn test_signer_for_epoch() {
+        let old = signer("test", "old");
+        let new = signer("test", "new");
+        let rotating: Arc<dyn ValidatorSigner> =
+            Arc::new(RotatingValidatorSigner::new(vec![old.clone(), new.clone()]));
+        let old_epoch = EpochId(CryptoHash::hash_bytes(b"old"));
+        let new_epoch = EpochId(CryptoHash::hash_bytes(b"new"));
+        let unknown_epoch = EpochId(CryptoHash::hash_bytes(b"unknown"));
+        rotating.as_rotating().unwrap().set_epoch_keys(HashMap::from([
+            (old_epoch.clone(), old.public_key()),
+            (new_epoch.clone(), new.public_key()),
+        ]));
+        assert!(rotating.as_rotating().unwrap().activate(&new.public_key()));
+
+        // Messages of the previous epoch are still signed with its key.
+        assert_eq!(signer_for_epoch(&rotating, &old_epoch).public_key(), old.public_key());
+        assert_eq!(signer_for_epoch(&rotating, &new_epoch).public_key(), new.public_key());
+        assert_eq!(signer_for_epoch(&rotating, &unknown_epoch).public_key(), new.public_key());
+
+        // Not a rotating signer, the signer itself is used.
+        assert_eq!(signer_for_epoch(&old, &new_epoch).public_key(), old.public_key());
+    }
+}
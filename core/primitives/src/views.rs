@@ -27,9 +27,10 @@ use crate::sharding::{
 use crate::transaction::NonrefundableStorageTransferAction;
 use crate::transaction::{
     Action, AddKeyAction, CreateAccountAction, DeleteAccountAction, DeleteKeyAction,
-    DeployContractAction, ExecutionMetadata, ExecutionOutcome, ExecutionOutcomeWithIdAndProof,
-    ExecutionStatus, FunctionCallAction, PartialExecutionOutcome, PartialExecutionStatus,
-    SignedTransaction, StakeAction, TransferAction,
+    DeployContractAction, DeployContractWithCodeHashCheckAction, DeployGlobalContractAction,
+    ExecutionMetadata, ExecutionOutcome, ExecutionOutcomeWithIdAndProof, ExecutionStatus,
+    FunctionCallAction, PartialExecutionOutcome, PartialExecutionStatus, SignedTransaction,
+    StakeAction, TransferAction, UseGlobalContractAction,
 };
 use crate::types::{
     AccountId, AccountWithPublicKey, Balance, BlockHeight, EpochHeight, EpochId, FunctionArgs, Gas,
@@ -387,6 +388,16 @@ pub struct PeerInfoView {
     pub is_outbound_peer: bool,
     /// Connection nonce.
     pub nonce: u64,
+    /// Outbound traffic of the rate limited message classes.
+    pub send_classes: Vec<PeerSendClassView>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq)]
+pub struct PeerSendClassView {
+    pub class: String,
+    pub sent_bytes: u64,
+    pub delayed_messages: u64,
+    pub dropped_messages: u64,
 }
 
 /// Information about a Producer: its account name, peer_id and a list of connected peers that
@@ -455,6 +466,9 @@ pub enum SyncStatusView {
 #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq)]
 pub struct PeerStoreView {
     pub peer_states: Vec<KnownPeerStateView>,
+    /// Blacklist entries, both from the config and set via the admin RPC.
+    #[serde(default)]
+    pub blacklist: Vec<String>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Debug, PartialEq, Eq)]
@@ -1219,6 +1233,18 @@ pub enum ActionView {
         delegate_action: DelegateAction,
         signature: Signature,
     },
+    DeployContractWithCodeHashCheck {
+        #[serde_as(as = "Base64")]
+        code: Vec<u8>,
+        expected_code_hash: CryptoHash,
+    },
+    DeployGlobalContract {
+        #[serde_as(as = "Base64")]
+        code: Vec<u8>,
+    },
+    UseGlobalContract {
+        code_hash: CryptoHash,
+    },
 }
 
 impl From<Action> for ActionView {
@@ -1255,6 +1281,20 @@ impl From<Action> for ActionView {
                 delegate_action: action.delegate_action,
                 signature: action.signature,
             },
+            Action::DeployContractWithCodeHashCheck(action) => {
+                let code = hash(&action.code).as_ref().to_vec();
+                ActionView::DeployContractWithCodeHashCheck {
+                    code,
+                    expected_code_hash: action.expected_code_hash,
+                }
+            }
+            Action::DeployGlobalContract(action) => {
+                let code = hash(&action.code).as_ref().to_vec();
+                ActionView::DeployGlobalContract { code }
+            }
+            Action::UseGlobalContract(action) => {
+                ActionView::UseGlobalContract { code_hash: action.code_hash }
+            }
         }
     }
 }
@@ -1296,6 +1336,17 @@ impl TryFrom<ActionView> for Action {
             ActionView::Delegate { delegate_action, signature } => {
                 Action::Delegate(Box::new(SignedDelegateAction { delegate_action, signature }))
             }
+            ActionView::DeployContractWithCodeHashCheck { code, expected_code_hash } => {
+                Action::DeployContractWithCodeHashCheck(Box::new(
+                    DeployContractWithCodeHashCheckAction { code, expected_code_hash },
+                ))
+            }
+            ActionView::DeployGlobalContract { code } => {
+                Action::DeployGlobalContract(DeployGlobalContractAction { code })
+            }
+            ActionView::UseGlobalContract { code_hash } => {
+                Action::UseGlobalContract(Box::new(UseGlobalContractAction { code_hash }))
+            }
         })
     }
 }
@@ -2137,7 +2188,16 @@ pub struct LightClientBlockView {
     pub approvals_after_next: Vec<Option<Box<Signature>>>,
 }
 
-#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, BorshDeserialize, BorshSerialize)]
+#[derive(
+    serde::Serialize,
+    serde::Deserialize,
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshDeserialize,
+    BorshSerialize,
+)]
 pub struct LightClientBlockLiteView {
     pub prev_block_hash: CryptoHash,
     pub inner_rest_hash: CryptoHash,
@@ -2399,6 +2459,62 @@ pub struct SplitStorageInfoView {
     pub hot_db_kind: Option<String>,
 }
 
+/// Duties of this node as a validator at the recent heights and their outcomes.
+#[derive(serde::Serialize, serde::Deserialize, Debug)]
+pub struct ValidatorPerformanceView {
+    /// Account id of this node, None if it isn't a validator.
+    pub validator_id: Option<AccountId>,
+    /// Heights ordered from high to low.
+    pub heights: Vec<ValidatorDutiesView>,
+}
+
+/// Duties of the validator at a single height. Only duties which the validator attempted
+/// or failed to perform are present.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, Default)]
+pub struct ValidatorDutiesView {
+    pub height: BlockHeight,
+    pub block: Option<ValidatorDutyOutcomeView>,
+    /// Chunks produced for the block at this height.
+    pub chunks: Vec<ValidatorDutyOutcomeView>,
+    /// Endorsements of the chunks created at this height.
+    pub endorsements: Vec<ValidatorDutyOutcomeView>,
+    /// Approval sent to the block producer of this height.
+    pub approval: Option<ApprovalOutcomeView>,
+}
+
+/// Outcome of producing a block or a chunk, or of endorsing a chunk.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct ValidatorDutyOutcomeView {
+    /// None for blocks.
+    pub shard_id: Option<ShardId>,
+    /// Hash of the produced block or chunk, or of the endorsed chunk.
+    pub hash: Option<CryptoHash>,
+    /// Time of the last attempt to perform the duty.
+    #[serde(with = "near_async::time::serde_utc_as_iso")]
+    pub timestamp: Utc,
+    /// Whether the produced block or chunk is on the canonical chain. None if it is not known
+    /// yet, or not applicable.
+    pub included: Option<bool>,
+    /// Why the duty wasn't performed, None if it was.
+    pub failure_cause: Option<String>,
+}
+
+/// Outcome of sending an approval (an endorsement or a skip).
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct ApprovalOutcomeView {
+    /// Height of the block which is endorsed or skipped from.
+    pub parent_height: BlockHeight,
+    pub is_endorsement: bool,
+    #[serde(with = "near_async::time::serde_utc_as_iso")]
+    pub timestamp: Utc,
+    /// Time between starting the doomslug timer and sending the approval.
+    pub latency_millis: Option<u64>,
+    /// Delay required by doomslug before sending the approval.
+    pub expected_latency_millis: Option<u64>,
+    /// Why the approval wasn't sent, None if it was.
+    pub failure_cause: Option<String>,
+}
+
 #[cfg(test)]
 #[cfg(not(feature = "nightly"))]
 #[cfg(not(feature = "statelessnet_protocol"))]
@@ -284,6 +284,31 @@ pub enum DBCol {
     /// - *Rows*: BlockShardId (BlockHash || ShardId) - 40 bytes
     /// - *Column type*: `StoredChunkStateTransitionData`
     StateTransitionData,
+    /// Per-receipt execution statistics of an applied chunk. Only written when
+    /// `save_chunk_execution_reports` is enabled in the node config.
+    /// - *Rows*: BlockShardId (BlockHash || ShardId) - 40 bytes
+    /// - *Column type*: `ChunkExecutionReport`
+    ChunkExecutionReport,
+    /// Chunk state witnesses which failed validation on this node. Only written when
+    /// `save_invalid_witnesses` is enabled in the node config, and bounded to the most
+    /// recent ones.
+    /// - *Rows*: BlockHeight || ShardId || ChunkHash - 48 bytes
+    /// - *Column type*: `SavedChunkStateWitness`
+    InvalidChunkStateWitnesses,
+    /// The most recent chunk state witnesses validated by this node, whether valid or not.
+    /// Only written when `save_latest_witnesses` is non-zero in the node config.
+    /// - *Rows*: BlockHeight || ShardId || ChunkHash - 48 bytes
+    /// - *Column type*: `SavedChunkStateWitness`
+    LatestChunkStateWitnesses,
+    /// Reputation scores of the network peers, so that they survive node restarts.
+    /// - *Rows*: single row (empty row name)
+    /// - *Content type*: Vec of (PeerId, score, time of the last update)
+    PeerReputation,
+    /// Changes to the set of peers made by the node operator via the admin RPC:
+    /// added peers, banned peers and blacklist entries.
+    /// - *Rows*: single row (empty row name)
+    /// - *Content type*: (Vec of PeerInfo, Vec of PeerId, Vec of blacklist entries)
+    PeerAdminOverrides,
     /// Column to store data for Epoch Sync.
     /// Does not contain data for genesis epoch.
     /// - *Rows*: `epoch_id`
@@ -299,7 +324,8 @@ pub enum DBCol {
 /// Currently only used in cold storage continuous migration.
 #[derive(PartialEq, Copy, Clone, Debug, Hash, Eq, strum::EnumIter)]
 pub enum DBKeyType {
-    /// Empty row name. Used in DBCol::LastComponentNonce and DBCol::RecentOutboundConnections
+    /// Empty row name. Used in DBCol::LastComponentNonce, DBCol::RecentOutboundConnections,
+    /// DBCol::PeerReputation and DBCol::PeerAdminOverrides
     Empty,
     /// Set of predetermined strings. Used, for example, in DBCol::BlockMisc
     StringLiteral,
@@ -458,6 +484,11 @@ impl DBCol {
             DBCol::HeaderHashesByHeight => false,
             // StateTransitionData is only needed to produce ChunkStateWitness
             DBCol::StateTransitionData => false,
+            // ChunkExecutionReport is only used for debugging recent blocks.
+            DBCol::ChunkExecutionReport => false,
+            // Saved witnesses are only used for debugging.
+            DBCol::InvalidChunkStateWitnesses => false,
+            DBCol::LatestChunkStateWitnesses => false,
 
             // Columns that are not GC-ed need not be copied to the cold storage.
             DBCol::BlockHeader
@@ -465,6 +496,8 @@ impl DBCol {
             | DBCol::BlockHeight
             | DBCol::_Peers
             | DBCol::RecentOutboundConnections
+            | DBCol::PeerReputation
+            | DBCol::PeerAdminOverrides
             | DBCol::BlockMerkleTree
             | DBCol::AccountAnnouncements
             | DBCol::EpochLightClientBlocks
@@ -512,6 +545,8 @@ impl DBCol {
             DBCol::IncomingReceipts => &[DBKeyType::BlockHash, DBKeyType::ShardId],
             DBCol::_Peers => &[DBKeyType::PeerId],
             DBCol::RecentOutboundConnections => &[DBKeyType::Empty],
+            DBCol::PeerReputation => &[DBKeyType::Empty],
+            DBCol::PeerAdminOverrides => &[DBKeyType::Empty],
             DBCol::EpochInfo => &[DBKeyType::EpochId],
             DBCol::BlockInfo => &[DBKeyType::BlockHash],
             DBCol::Chunks => &[DBKeyType::ChunkHash],
@@ -557,6 +592,13 @@ impl DBCol {
             DBCol::FlatStateDeltaMetadata => &[DBKeyType::ShardUId, DBKeyType::BlockHash],
             DBCol::FlatStorageStatus => &[DBKeyType::ShardUId],
             DBCol::StateTransitionData => &[DBKeyType::BlockHash, DBKeyType::ShardId],
+            DBCol::ChunkExecutionReport => &[DBKeyType::BlockHash, DBKeyType::ShardId],
+            DBCol::InvalidChunkStateWitnesses => {
+                &[DBKeyType::BlockHeight, DBKeyType::ShardId, DBKeyType::ChunkHash]
+            }
+            DBCol::LatestChunkStateWitnesses => {
+                &[DBKeyType::BlockHeight, DBKeyType::ShardId, DBKeyType::ChunkHash]
+            }
             #[cfg(feature = "new_epoch_sync")]
             DBCol::EpochSyncInfo => &[DBKeyType::EpochId],
         }
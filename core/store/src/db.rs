@@ -39,6 +39,10 @@ pub const STATE_SNAPSHOT_KEY: &[u8; 18] = b"STATE_SNAPSHOT_KEY";
 pub const FLAT_STATE_VALUES_INLINING_MIGRATION_STATUS_KEY: &[u8] =
     b"FLAT_STATE_VALUES_INLINING_MIGRATION_STATUS";
 pub const STATE_TRANSITION_START_HEIGHTS: &[u8] = b"STATE_TRANSITION_START_HEIGHTS";
+/// Keys of the rows in `DBCol::InvalidChunkStateWitnesses`, from the oldest to the newest.
+pub const INVALID_CHUNK_STATE_WITNESSES_KEY: &[u8] = b"INVALID_CHUNK_STATE_WITNESSES";
+/// Keys of the rows in `DBCol::LatestChunkStateWitnesses`, from the oldest to the newest.
+pub const LATEST_CHUNK_STATE_WITNESSES_KEY: &[u8] = b"LATEST_CHUNK_STATE_WITNESSES";
 
 #[derive(Default, Debug)]
 pub struct DBTransaction {
@@ -49,6 +49,17 @@ impl FlatStorageChunkView {
         self.flat_storage.contains_key(&self.block_hash, key)
     }
 
+    /// Returns an iterator over the entries with keys in `[from, to)` range, taken from the
+    /// state corresponding to `FlatStorageChunkView::block_hash`. Unlike
+    /// `iter_flat_state_entries`, it takes into account the deltas after the flat head.
+    pub fn iter_range<'a>(
+        &'a self,
+        from: Option<&[u8]>,
+        to: Option<&[u8]>,
+    ) -> Result<FlatStateIterator<'a>, crate::StorageError> {
+        self.flat_storage.iter_range(&self.store, &self.block_hash, from, to)
+    }
+
     pub fn iter_flat_state_entries<'a>(
         &'a self,
         from: Option<&[u8]>,
@@ -1,4 +1,5 @@
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::{Arc, RwLock};
 
 use near_primitives::errors::StorageError;
@@ -16,7 +17,7 @@ use crate::{Store, StoreUpdate};
 use super::delta::{CachedFlatStateDelta, FlatStateDelta};
 use super::metrics::FlatStorageMetrics;
 use super::store_helper;
-use super::types::FlatStorageError;
+use super::types::{FlatStateIterator, FlatStorageError};
 
 /// FlatStorage stores information on which blocks flat storage current supports key lookups on.
 /// Note that this struct is shared by multiple threads, the chain thread, threads that apply chunks,
@@ -342,6 +343,65 @@ impl FlatStorage {
         })?)
     }
 
+    /// Returns an iterator over the entries with keys in `[from, to)` range of the state
+    /// after `block_hash` is applied, in ascending key order. `None` bounds are unbounded.
+    ///
+    /// The flat state on disk is merged with the deltas of the blocks after the flat head.
+    /// Cached deltas store only hashes of the keys, so the deltas are read from `store` when
+    /// the iterator is created. There are only a few blocks after the flat head, so the
+    /// creation cost is bounded, and every step of the iterator after that is O(1).
+    pub fn iter_range<'a>(
+        &self,
+        store: &'a Store,
+        block_hash: &CryptoHash,
+        from: Option<&[u8]>,
+        to: Option<&[u8]>,
+    ) -> Result<FlatStateIterator<'a>, StorageError> {
+        let guard = self.0.read().expect(super::POISONED_LOCK_ERR);
+        let blocks_to_head =
+            guard.get_blocks_to_head(block_hash).map_err(|e| StorageError::from(e))?;
+        let in_range =
+            |key: &[u8]| from.map_or(true, |from| from <= key) && to.map_or(true, |to| key < to);
+        let mut delta_entries = BTreeMap::new();
+        // The blocks go from the most recent one, so the first change of a key wins.
+        for block_hash in blocks_to_head.iter() {
+            let Some(changes) =
+                store_helper::get_delta_changes(store, guard.shard_uid, *block_hash)?
+            else {
+                continue;
+            };
+            for (key, value) in changes.0 {
+                if in_range(&key) {
+                    delta_entries.entry(key).or_insert(value);
+                }
+            }
+        }
+        // The iterator is created while holding the lock, so that the flat head can't move
+        // in between: the entries on disk are read from a snapshot taken at creation.
+        let mut disk_entries =
+            store_helper::iter_flat_state_entries(guard.shard_uid, store, from, to).peekable();
+        let mut delta_entries = delta_entries.into_iter().peekable();
+        Ok(Box::new(std::iter::from_fn(move || loop {
+            let ordering = match (disk_entries.peek(), delta_entries.peek()) {
+                (Some(Err(_)), _) => return disk_entries.next(),
+                (Some(Ok((disk_key, _))), Some((delta_key, _))) => disk_key.cmp(delta_key),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => return None,
+            };
+            if ordering != Ordering::Greater {
+                let disk_entry = disk_entries.next();
+                if ordering == Ordering::Less {
+                    return disk_entry;
+                }
+            }
+            // The delta overrides the value on disk, `None` means that the key was removed.
+            if let Some((key, Some(value))) = delta_entries.next() {
+                return Some(Ok((key, value)));
+            }
+        })))
+    }
+
     /// Update the head of the flat storage, including updating the flat state
     /// in memory and on disk and updating the flat state to reflect the state
     /// at the new head. If updating to given head is not possible, returns an
@@ -811,6 +871,67 @@ mod tests {
         );
     }
 
+    #[test]
+    fn flat_storage_iter_range() {
+        // Keys [1], [2] and [4] are on disk at block 0. Block 1 removes [2] and adds [3],
+        // block 2 updates [3] and adds [5].
+        let chain = MockChain::linear_chain(3);
+        let shard_uid = ShardUId::single_shard();
+        let store = create_test_store();
+        let mut store_update = store.store_update();
+        store_helper::set_flat_storage_status(
+            &mut store_update,
+            shard_uid,
+            FlatStorageStatus::Ready(FlatStorageReadyStatus { flat_head: chain.get_block(0) }),
+        );
+        for key in [1, 2, 4] {
+            store_helper::set_flat_state_value(
+                &mut store_update,
+                shard_uid,
+                vec![key],
+                Some(FlatStateValue::Inlined(vec![key, 0])),
+            );
+        }
+        let changes = [
+            vec![(vec![2], None), (vec![3], Some(FlatStateValue::Inlined(vec![3, 1])))],
+            vec![
+                (vec![3], Some(FlatStateValue::Inlined(vec![3, 2]))),
+                (vec![5], Some(FlatStateValue::Inlined(vec![5, 2]))),
+            ],
+        ];
+        for (i, changes) in changes.into_iter().enumerate() {
+            let delta = FlatStateDelta {
+                changes: FlatStateChanges::from(changes),
+                metadata: FlatStateDeltaMetadata {
+                    block: chain.get_block(i as BlockHeight + 1),
+                    prev_block_with_changes: None,
+                },
+            };
+            store_helper::set_delta(&mut store_update, shard_uid, &delta);
+        }
+        store_update.commit().unwrap();
+
+        let flat_storage_manager = FlatStorageManager::new(store.clone());
+        flat_storage_manager.create_flat_storage_for_shard(shard_uid).unwrap();
+        let iter_range = |height: BlockHeight, from: Option<&[u8]>, to: Option<&[u8]>| {
+            let chunk_view =
+                flat_storage_manager.chunk_view(shard_uid, chain.get_block_hash(height)).unwrap();
+            let entries = chunk_view.iter_range(from, to).unwrap().map(|entry| {
+                let (key, value) = entry.unwrap();
+                let FlatStateValue::Inlined(value) = value else { panic!("value is not inlined") };
+                (key[0], value[1])
+            });
+            entries.collect::<Vec<_>>()
+        };
+
+        assert_eq!(iter_range(0, None, None), vec![(1, 0), (2, 0), (4, 0)]);
+        assert_eq!(iter_range(1, None, None), vec![(1, 0), (3, 1), (4, 0)]);
+        assert_eq!(iter_range(2, None, None), vec![(1, 0), (3, 2), (4, 0), (5, 2)]);
+        assert_eq!(iter_range(2, Some(&[2]), Some(&[5])), vec![(3, 2), (4, 0)]);
+        assert_eq!(iter_range(2, Some(&[4]), None), vec![(4, 0), (5, 2)]);
+        assert_eq!(iter_range(2, Some(&[6]), None), vec![]);
+    }
+
     #[test]
     fn flat_storage_with_hops() {
         init_test_logger();
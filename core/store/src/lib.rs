@@ -34,7 +34,9 @@ use near_vm_runner::ContractCode;
 
 use crate::db::{refcount, DBIterator, DBOp, DBSlice, DBTransaction, Database, StoreStatistics};
 pub use crate::trie::iterator::{TrieIterator, TrieTraversalItem};
-pub use crate::trie::update::{TrieUpdate, TrieUpdateIterator, TrieUpdateValuePtr};
+pub use crate::trie::update::{
+    TrieUpdate, TrieUpdateIterator, TrieUpdateRangeIterator, TrieUpdateValuePtr,
+};
 pub use crate::trie::{
     estimator, resharding, ApplyStatePartResult, KeyForStateChanges, KeyLookupMode, NibbleSlice,
     PartialStorage, PrefetchApi, PrefetchError, RawTrieNode, RawTrieNodeWithSize, ShardTries,
@@ -880,6 +882,43 @@ pub fn get_code(
     trie.get(&key).map(|opt| opt.map(|code| ContractCode::new(code, code_hash)))
 }
 
+pub fn set_global_contract_code(state_update: &mut TrieUpdate, code: &ContractCode) {
+    state_update.set(TrieKey::GlobalContractCode { code_hash: *code.hash() }, code.code().to_vec());
+}
+
+pub fn get_global_contract_code(
+    trie: &dyn TrieAccess,
+    code_hash: CryptoHash,
+) -> Result<Option<ContractCode>, StorageError> {
+    let key = TrieKey::GlobalContractCode { code_hash };
+    trie.get(&key).map(|opt| opt.map(|code| ContractCode::new(code, Some(code_hash))))
+}
+
+pub fn has_global_contract_code(
+    trie: &dyn TrieAccess,
+    code_hash: CryptoHash,
+) -> Result<bool, StorageError> {
+    trie.contains_key(&TrieKey::GlobalContractCode { code_hash })
+}
+
+/// Returns the code the account with the given `code_hash` executes.
+///
+/// Code deployed directly on the account takes precedence. Otherwise the account may refer to
+/// globally deployed code by its hash, see `TrieKey::GlobalContractCode`.
+pub fn get_account_code(
+    trie: &dyn TrieAccess,
+    account_id: &AccountId,
+    code_hash: CryptoHash,
+) -> Result<Option<ContractCode>, StorageError> {
+    if let Some(code) = get_code(trie, account_id, Some(code_hash))? {
+        return Ok(Some(code));
+    }
+    if code_hash == CryptoHash::default() {
+        return Ok(None);
+    }
+    get_global_contract_code(trie, code_hash)
+}
+
 /// Removes account, code and all access keys associated to it.
 pub fn remove_account(
     state_update: &mut TrieUpdate,
@@ -105,6 +105,12 @@ impl<'a> TrieIterator<'a> {
         self.seek_nibble_slice(NibbleSlice::new(key.as_ref()), true).map(drop)
     }
 
+    /// Position the iterator on the first element with key >= `key`, without
+    /// restricting further iteration to keys having `key` as a prefix.
+    pub fn seek<K: AsRef<[u8]>>(&mut self, key: K) -> Result<(), StorageError> {
+        self.seek_nibble_slice(NibbleSlice::new(key.as_ref()), false).map(drop)
+    }
+
     /// Configures whether the iterator should remember all the nodes its
     /// visiting.
     ///
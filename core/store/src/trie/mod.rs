@@ -11,7 +11,7 @@ pub(crate) use crate::trie::config::{
     DEFAULT_SHARD_CACHE_DELETIONS_QUEUE_CAPACITY, DEFAULT_SHARD_CACHE_TOTAL_SIZE_LIMIT,
 };
 use crate::trie::insert_delete::NodesStorage;
-use crate::trie::iterator::TrieIterator;
+use crate::trie::iterator::{TrieItem, TrieIterator};
 pub use crate::trie::nibble_slice::NibbleSlice;
 pub use crate::trie::prefetching_trie_storage::{PrefetchApi, PrefetchError};
 pub use crate::trie::shard_tries::{KeyForStateChanges, ShardTries, WrappedTrieChanges};
@@ -1539,6 +1539,51 @@ impl Trie {
         TrieIterator::new(self, prune_condition)
     }
 
+    /// Returns an iterator over key-value pairs with keys in `[start, end)` range, in ascending
+    /// key order. `None` for `end` means the range is unbounded from above.
+    ///
+    /// The entries are read from flat storage if the trie has it, so that every step of the
+    /// iterator is O(1). If recording, the trie is iterated alongside to record the nodes
+    /// visited by the same iteration on chunk validators, which don't have flat storage.
+    pub fn iter_range<'a>(
+        &'a self,
+        start: &[u8],
+        end: Option<&[u8]>,
+    ) -> Result<Box<dyn Iterator<Item = Result<TrieItem, StorageError>> + 'a>, StorageError> {
+        let trie_iter = || -> Result<_, StorageError> {
+            let mut trie_iter = self.iter()?;
+            trie_iter.seek(start)?;
+            let end = end.map(|end| end.to_vec());
+            Ok(trie_iter.take_while(move |item| match (item, &end) {
+                (Ok((key, _)), Some(end)) => key < end,
+                _ => true,
+            }))
+        };
+        let Some(flat_storage_chunk_view) = &self.flat_storage_chunk_view else {
+            return Ok(Box::new(trie_iter()?));
+        };
+        let mut flat_iter = flat_storage_chunk_view.iter_range(Some(start), end)?;
+        let mut recording_iter = if self.recorder.is_some() { Some(trie_iter()?) } else { None };
+        Ok(Box::new(std::iter::from_fn(move || {
+            let entry = flat_iter.next();
+            if let Some(recording_iter) = &mut recording_iter {
+                // The trie is advanced also when flat storage has no more entries, as the
+                // nodes proving the end of the range are needed too. The value is already
+                // read and recorded by the trie iterator.
+                let item = recording_iter.next();
+                debug_assert_eq!(
+                    entry.as_ref().and_then(|entry| entry.as_ref().ok()).map(|(key, _)| key),
+                    item.as_ref().and_then(|item| item.as_ref().ok()).map(|(key, _)| key),
+                );
+                return item;
+            }
+            Some(entry?.map_err(StorageError::from).and_then(|(key, value)| {
+                let value = self.deref_optimized(&OptimizedValueRef::from_flat_value(value))?;
+                Ok((key, value))
+            }))
+        })))
+    }
+
     pub fn get_trie_nodes_count(&self) -> TrieNodesCount {
         self.accounting_cache.borrow().get_trie_nodes_count()
     }
@@ -1816,6 +1861,43 @@ mod tests {
         assert!(!trie.contains_key_mode(&[99, 44, 100, 58, 58, 49], KeyLookupMode::Trie).unwrap());
     }
 
+    #[test]
+    fn test_iter_range() {
+        let sid = ShardUId::single_shard();
+        let bid = CryptoHash::default();
+        let tries = TestTriesBuilder::new().with_flat_storage().build();
+        let initial = vec![
+            (vec![1], Some(vec![1])),
+            (vec![2, 1], Some(vec![2; 100])),
+            (vec![2, 2], Some(vec![3])),
+            (vec![3], Some(vec![4])),
+        ];
+        test_populate_flat_storage(&tries, sid, &bid, &bid, &initial);
+        let root = test_populate_trie(&tries, &Trie::EMPTY_ROOT, sid, initial.clone());
+        let collect = |trie: &Trie, end: Option<&[u8]>| {
+            trie.iter_range(&[2], end).unwrap().map(Result::unwrap).collect::<Vec<_>>()
+        };
+        let want: Vec<_> = initial[1..3]
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone().unwrap()))
+            .collect();
+
+        let trie = tries.get_trie_for_shard(sid, root);
+        assert!(!trie.has_flat_storage_chunk_view());
+        assert_eq!(collect(&trie, Some(&[3][..])), want);
+
+        // The trie recorded while reading from flat storage is enough to iterate the same
+        // range on chunk validators.
+        let trie = tries.get_trie_with_block_hash_for_shard(sid, root, &bid, false);
+        assert!(trie.has_flat_storage_chunk_view());
+        assert_eq!(collect(&trie, Some(&[3][..])), want);
+        let trie = trie.recording_reads();
+        assert_eq!(collect(&trie, Some(&[3][..])), want);
+        let partial_storage = trie.recorded_storage().unwrap();
+        let trie = Trie::from_recorded_storage(partial_storage, root, true);
+        assert_eq!(collect(&trie, Some(&[3][..])), want);
+    }
+
     #[test]
     fn test_equal_leafs() {
         let initial = vec![
@@ -8,7 +8,7 @@ use near_primitives::receipt::Receipt;
 use near_primitives::shard_layout::ShardUId;
 use near_primitives::state_part::PartId;
 use near_primitives::trie_key::trie_key_parsers::parse_account_id_from_raw_key;
-use near_primitives::trie_key::TrieKey;
+use near_primitives::trie_key::{col, TrieKey};
 use near_primitives::types::{
     ConsolidatedStateChange, StateChangeCause, StateChangesForResharding, StateRoot,
 };
@@ -58,6 +58,16 @@ impl ShardTries {
                 TrieKey::YieldedPromiseQueueIndices => {}
                 TrieKey::YieldedPromiseQueueEntry { .. } => todo!(),
                 TrieKey::PromiseYieldReceipt { .. } => todo!(),
+                // Global contract code is not tied to an account, so every child shard keeps
+                // its own copy of it.
+                TrieKey::GlobalContractCode { .. } => {
+                    for trie_update in trie_updates.values_mut() {
+                        match &value {
+                            Some(value) => trie_update.set(trie_key.clone(), value.clone()),
+                            None => trie_update.remove(trie_key.clone()),
+                        }
+                    }
+                }
                 TrieKey::Account { account_id }
                 | TrieKey::ContractCode { account_id }
                 | TrieKey::AccessKey { account_id, .. }
@@ -104,6 +114,7 @@ impl ShardTries {
     /// The caller must guarantee that `state_roots` contains all shard_ids
     /// that `key_to_shard_id` that may return
     /// Ignore changes on DelayedReceipts or DelayedReceiptsIndices
+    /// Global contract code is copied to all children shards
     /// Returns `store_update` and the new state_roots for children shards
     pub fn add_values_to_children_states(
         &self,
@@ -136,6 +147,17 @@ impl ShardTries {
     ) -> Result<(StoreUpdate, HashMap<ShardUId, StateRoot>), StorageError> {
         let mut changes_by_shard: HashMap<_, Vec<_>> = HashMap::new();
         for (raw_key, value) in values.into_iter() {
+            if raw_key.first() == Some(&col::GLOBAL_CONTRACT_CODE) {
+                // Global contract code is shared by all accounts in the parent shard, so it is
+                // copied to every child shard.
+                for shard_uid in state_roots.keys() {
+                    changes_by_shard
+                        .entry(*shard_uid)
+                        .or_default()
+                        .push((raw_key.clone(), value.clone()));
+                }
+                continue;
+            }
             if let Some(new_shard_uid) = key_to_shard_id(&raw_key)? {
                 changes_by_shard.entry(new_shard_uid).or_default().push((raw_key, value));
             }
@@ -330,7 +352,9 @@ mod tests {
     use near_primitives::hash::hash;
     use near_primitives::receipt::{DelayedReceiptIndices, Receipt};
     use near_primitives::trie_key::TrieKey;
-    use near_primitives::types::{NumShards, StateChangeCause, StateRoot};
+    use near_primitives::types::{
+        ConsolidatedStateChange, NumShards, StateChangeCause, StateChangesForResharding, StateRoot,
+    };
     use rand::Rng;
     use std::collections::HashMap;
 
@@ -379,6 +403,49 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_global_contract_code_copied_to_children_states() {
+        let tries = TestTriesBuilder::new().build();
+        let state_roots: HashMap<_, _> =
+            (0..2).map(|shard_id| (ShardUId { version: 1, shard_id }, Trie::EMPTY_ROOT)).collect();
+        let account_id_to_shard_uid = |account_id: &AccountId| ShardUId {
+            version: 1,
+            shard_id: if account_id.as_str() < "m" { 0 } else { 1 },
+        };
+        let code = b"code".to_vec();
+        let code_key = TrieKey::GlobalContractCode { code_hash: hash(&code) };
+        let contract_key = TrieKey::ContractCode { account_id: "alice".parse().unwrap() };
+
+        // Splitting the parent state copies the global code to every child shard.
+        let values = vec![
+            (code_key.to_vec(), Some(code.clone())),
+            (contract_key.to_vec(), Some(code.clone())),
+        ];
+        let (store_update, state_roots) = tries
+            .add_values_to_children_states(&state_roots, values, &account_id_to_shard_uid)
+            .unwrap();
+        store_update.commit().unwrap();
+        for (shard_uid, state_root) in &state_roots {
+            let trie = tries.get_trie_for_shard(*shard_uid, *state_root);
+            assert_eq!(trie.get(&code_key.to_vec()).unwrap(), Some(code.clone()));
+            let has_contract = trie.get(&contract_key.to_vec()).unwrap().is_some();
+            assert_eq!(has_contract, shard_uid.shard_id == 0);
+        }
+
+        // Changes of the global code in the parent shard apply to every child shard.
+        let changes = StateChangesForResharding {
+            changes: vec![ConsolidatedStateChange { trie_key: code_key.clone(), value: None }],
+            processed_delayed_receipts: vec![],
+        };
+        let trie_updates = tries
+            .apply_state_changes_to_children_states(&state_roots, changes, &account_id_to_shard_uid)
+            .unwrap();
+        assert_eq!(trie_updates.len(), 2);
+        for trie_update in trie_updates.values() {
+            assert_eq!(trie_update.get(&code_key).unwrap(), None);
+        }
+    }
+
     #[test]
     fn test_get_delayed_receipts() {
         let mut rng = rand::thread_rng();
@@ -420,6 +420,11 @@ impl ShardTries {
         guard.get(&shard_uid).cloned()
     }
 
+    /// Unloads the in-memory tries for the shard, e.g. when the state of the shard is removed.
+    pub fn unload_mem_trie(&self, shard_uid: &ShardUId) {
+        self.0.mem_tries.write().unwrap().remove(shard_uid);
+    }
+
     /// Garbage collects the in-memory tries for the shard up to (and including) the given
     /// height.
     pub fn delete_memtrie_roots_up_to_height(&self, shard_uid: ShardUId, height: BlockHeight) {
@@ -1,4 +1,4 @@
-pub use self::iterator::TrieUpdateIterator;
+pub use self::iterator::{TrieUpdateIterator, TrieUpdateRangeIterator};
 use super::{OptimizedValueRef, Trie};
 use crate::trie::{KeyLookupMode, TrieChanges};
 use crate::StorageError;
@@ -162,6 +162,16 @@ impl TrieUpdate {
         TrieUpdateIterator::new(self, key_prefix)
     }
 
+    /// Returns an iterator over key-value pairs with keys in `[start, end)`
+    /// range.  `None` for `end` means the range is unbounded from above.
+    pub fn iter_range(
+        &self,
+        start: &[u8],
+        end: Option<&[u8]>,
+    ) -> Result<TrieUpdateRangeIterator<'_>, StorageError> {
+        TrieUpdateRangeIterator::new(self, start, end)
+    }
+
     pub fn get_root(&self) -> &StateRoot {
         self.trie.get_root()
     }
@@ -332,4 +342,47 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn trie_iter_range() {
+        let tries = TestTriesBuilder::new().build();
+        let mut trie_update = tries.new_trie_update(ShardUId::single_shard(), Trie::EMPTY_ROOT);
+        for key in [b"a".as_slice(), b"b", b"bb", b"c", b"d"] {
+            trie_update.set(test_key(key.to_vec()), key.to_vec());
+        }
+        trie_update
+            .commit(StateChangeCause::TransactionProcessing { tx_hash: CryptoHash::default() });
+        let trie_changes = trie_update.finalize().unwrap().1;
+        let mut store_update = tries.store_update();
+        let new_root = tries.apply_all(&trie_changes, ShardUId::single_shard(), &mut store_update);
+        store_update.commit().unwrap();
+
+        let mut trie_update = tries.new_trie_update(ShardUId::single_shard(), new_root);
+        trie_update.remove(test_key(b"c".to_vec()));
+        trie_update.set(test_key(b"ba".to_vec()), b"new".to_vec());
+        trie_update.set(test_key(b"bb".to_vec()), b"updated".to_vec());
+
+        let collect = |start: &[u8], end: Option<&[u8]>| {
+            trie_update
+                .iter_range(
+                    &test_key(start.to_vec()).to_vec(),
+                    end.map(|end| test_key(end.to_vec()).to_vec()).as_deref(),
+                )
+                .unwrap()
+                .map(|item| item.map(|(_, value)| value))
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        };
+        assert_eq!(
+            collect(b"b", Some(b"d")),
+            vec![b"b".to_vec(), b"new".to_vec(), b"updated".to_vec()]
+        );
+        assert_eq!(collect(b"bc", None), vec![b"d".to_vec()]);
+        assert_eq!(collect(b"", Some(b"b")), vec![b"a".to_vec()]);
+        assert_eq!(collect(b"c", Some(b"d")), Vec::<Vec<u8>>::new());
+        // Equal and inverted bounds give empty ranges.
+        assert_eq!(collect(b"b", Some(b"b")), Vec::<Vec<u8>>::new());
+        assert_eq!(collect(b"d", Some(b"a")), Vec::<Vec<u8>>::new());
+        assert_eq!(collect(b"bb", Some(b"ba")), Vec::<Vec<u8>>::new());
+    }
 }
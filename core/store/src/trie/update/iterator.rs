@@ -4,7 +4,7 @@ use std::ops::Bound;
 use crate::trie::update::*;
 use crate::StorageError;
 
-use crate::trie::TrieIterator;
+use crate::trie::iterator::TrieItem;
 
 struct MergeIter<'a> {
     left: Peekable<Box<dyn Iterator<Item = (&'a [u8], Option<&'a [u8]>)> + 'a>>,
@@ -35,7 +35,11 @@ impl<'a> Iterator for MergeIter<'a> {
     }
 }
 
-pub struct TrieUpdateIterator<'a>(Option<(Peekable<TrieIterator<'a>>, Peekable<MergeIter<'a>>)>);
+type TrieItemIter<'a> = Box<dyn Iterator<Item = Result<TrieItem, StorageError>> + 'a>;
+
+/// Iterator over keys with a given prefix, which takes committed and
+/// prospective changes of a [`TrieUpdate`] into account.
+pub struct TrieUpdateIterator<'a>(TrieUpdateRangeIterator<'a>);
 
 impl<'a> TrieUpdateIterator<'a> {
     #![allow(clippy::new_ret_no_self)]
@@ -44,12 +48,69 @@ impl<'a> TrieUpdateIterator<'a> {
         trie_iter.seek_prefix(prefix)?;
 
         let end_bound = make_prefix_range_end_bound(prefix);
-        let end_bound = if let Some(end_bound) = &end_bound {
-            Bound::Excluded(end_bound.as_slice())
-        } else {
-            Bound::Unbounded
+        Ok(TrieUpdateIterator(TrieUpdateRangeIterator::with_trie_iter(
+            state_update,
+            Box::new(trie_iter),
+            prefix,
+            end_bound.as_deref(),
+        )))
+    }
+}
+
+impl<'a> Iterator for TrieUpdateIterator<'a> {
+    type Item = Result<Vec<u8>, StorageError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|item| item.map(|(key, _)| key))
+    }
+}
+
+impl<'a> std::iter::FusedIterator for TrieUpdateIterator<'a> {}
+
+/// Iterator over key-value pairs with keys in `[start, end)` range, in
+/// ascending key order, which takes committed and prospective changes of
+/// a [`TrieUpdate`] into account.
+///
+/// Items are read lazily, so the cost of fetching the next item doesn’t depend
+/// on the size of the range.  The items come from flat storage if the trie has
+/// it, see [`Trie::iter_range`].
+pub struct TrieUpdateRangeIterator<'a>(
+    Option<(Peekable<TrieItemIter<'a>>, Peekable<MergeIter<'a>>)>,
+);
+
+impl<'a> TrieUpdateRangeIterator<'a> {
+    #![allow(clippy::new_ret_no_self)]
+    /// Creates an iterator over keys `start <= key < end`.  If `end` is `None`,
+    /// the range is unbounded from above.  If `end` is not greater than
+    /// `start`, the range is empty.
+    pub fn new(
+        state_update: &'a TrieUpdate,
+        start: &[u8],
+        end: Option<&[u8]>,
+    ) -> Result<Self, StorageError> {
+        if is_empty_range(start, end) {
+            return Ok(TrieUpdateRangeIterator(None));
+        }
+        let trie_iter = state_update.trie.iter_range(start, end)?;
+        Ok(Self::with_trie_iter(state_update, trie_iter, start, end))
+    }
+
+    fn with_trie_iter(
+        state_update: &'a TrieUpdate,
+        trie_iter: TrieItemIter<'a>,
+        start: &[u8],
+        end: Option<&[u8]>,
+    ) -> Self {
+        // `BTreeMap::range` panics if the start of the range is greater than
+        // its end.
+        if is_empty_range(start, end) {
+            return TrieUpdateRangeIterator(None);
+        }
+        let end_bound = match end {
+            Some(end) => Bound::Excluded(end),
+            None => Bound::Unbounded,
         };
-        let range = (Bound::Included(prefix), end_bound);
+        let range = (Bound::Included(start), end_bound);
 
         let committed_iter = state_update.committed.range::<[u8], _>(range).map(
             |(raw_key, changes_with_trie_key)| {
@@ -73,12 +134,12 @@ impl<'a> TrieUpdateIterator<'a> {
             right: (Box::new(prospective_iter) as Box<dyn Iterator<Item = _>>).peekable(),
         }
         .peekable();
-        Ok(TrieUpdateIterator(Some((trie_iter.peekable(), overlay_iter))))
+        TrieUpdateRangeIterator(Some((trie_iter.peekable(), overlay_iter)))
     }
 }
 
-impl<'a> Iterator for TrieUpdateIterator<'a> {
-    type Item = Result<Vec<u8>, StorageError>;
+impl<'a> Iterator for TrieUpdateRangeIterator<'a> {
+    type Item = Result<TrieItem, StorageError>;
 
     fn next(&mut self) -> Option<Self::Item> {
         #[derive(Eq, PartialEq)]
@@ -117,22 +178,28 @@ impl<'a> Iterator for TrieUpdateIterator<'a> {
             // delete the value.
             let trie_item = if res != Ordering::Overlay { iterators.0.next() } else { None };
             if res == Ordering::Trie {
-                if let Some(Ok((key, _))) = trie_item {
-                    return Some(Ok(key));
+                if let Some(Ok(item)) = trie_item {
+                    return Some(Ok(item));
                 }
-            } else if let Some((overlay_key, Some(_))) = iterators.1.next() {
-                return Some(Ok(if let Some(Ok((trie_key, _))) = trie_item {
+            } else if let Some((overlay_key, Some(overlay_value))) = iterators.1.next() {
+                let key = if let Some(Ok((trie_key, _))) = trie_item {
                     debug_assert_eq!(trie_key.as_slice(), overlay_key);
                     trie_key
                 } else {
                     overlay_key.to_vec()
-                }));
+                };
+                return Some(Ok((key, overlay_value.to_vec())));
             }
         }
     }
 }
 
-impl<'a> std::iter::FusedIterator for TrieUpdateIterator<'a> {}
+impl<'a> std::iter::FusedIterator for TrieUpdateRangeIterator<'a> {}
+
+/// Returns whether there are no keys `start <= key < end`.
+fn is_empty_range(start: &[u8], end: Option<&[u8]>) -> bool {
+    end.map_or(false, |end| end <= start)
+}
 
 /// Returns an end bound for a range which corresponds to all values with
 /// a given prefix.
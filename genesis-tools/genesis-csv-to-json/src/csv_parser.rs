@@ -322,6 +322,7 @@ mod tests {
                     id: PeerId::new(PublicKey::empty(KeyType::ED25519)),
                     addr: Some("127.0.0.1:8080".parse().unwrap()),
                     account_id: None,
+                    extra_addrs: vec![],
                 }),
                 is_treasury: false,
                 smart_contract: None,
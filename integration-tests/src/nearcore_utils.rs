@@ -72,6 +72,7 @@ pub fn add_blocks(
                     prev.header().height() + 1,
                     signer,
                 )
+                .unwrap()
                 .signature,
             ))],
             Ratio::from_integer(0),
@@ -84,7 +85,8 @@ pub fn add_blocks(
             next_bp_hash,
             block_merkle_tree.root(),
             clock.now_utc(),
-        );
+        )
+        .unwrap();
         block_merkle_tree.insert(*block.hash());
         let _ = client.do_send(
             BlockResponse {
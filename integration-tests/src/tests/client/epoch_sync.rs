@@ -10,13 +10,19 @@ use near_chain::{BlockProcessingArtifact, ChainStoreAccess};
 use near_chain_configs::Genesis;
 use near_client::test_utils::TestEnv;
 use near_client::ProcessTxResponse;
-use near_client_primitives::types::GetBlock;
+use near_client_primitives::types::{GetBlock, SyncStatus};
 use near_crypto::{InMemorySigner, KeyType};
 use near_network::test_utils::WaitOrTimeoutActor;
+use near_network::types::{
+    EpochSyncInfoResponse, HighestHeightPeerInfo, MAX_EPOCH_SYNC_INFOS_PER_RESPONSE,
+};
 use near_o11y::testonly::{init_integration_logger, init_test_logger};
 use near_o11y::WithSpanContextExt;
+use near_primitives::block::Block;
 use near_primitives::epoch_manager::block_info::BlockInfo;
+use near_primitives::epoch_manager::epoch_info::EpochInfo;
 use near_primitives::epoch_manager::epoch_sync::EpochSyncInfo;
+use near_primitives::network::{PeerId, PeerInfo};
 use near_primitives::state_part::PartId;
 use near_primitives::state_sync::get_num_state_parts;
 use near_primitives::test_utils::create_test_signer;
@@ -304,11 +310,162 @@ fn test_epoch_sync_data_hash_from_epoch_sync_info() {
     }
 }
 
+/// Produce blocks until a number of epochs is finalised on the first client.
+/// Returns all produced blocks.
+fn produce_epochs(env: &mut TestEnv, epoch_length: BlockHeight, num_epochs: u64) -> Vec<Block> {
+    let mut blocks = vec![];
+    for h in 1..epoch_length * num_epochs + 3 {
+        let block = env.clients[0].produce_block(h).unwrap().unwrap();
+        env.process_block(0, block.clone(), Provenance::PRODUCED);
+        blocks.push(block);
+    }
+    blocks
+}
+
+/// Produce 5 epochs on the first client and epoch sync the second client from genesis,
+/// passing `EpochSyncInfoResponse`s between them as the network would.
+/// Check that the header head of the second client moves to the last block of the last
+/// finalised epoch without header sync, and that header sync continues from there.
+/// An empty response of a peer which is behind doesn't finish epoch sync.
+#[test]
+fn test_epoch_sync_from_genesis() {
+    init_test_logger();
+
+    let epoch_length = 10;
+    let num_epochs = 5;
+
+    let mut genesis = Genesis::test(vec!["test0".parse().unwrap(), "test1".parse().unwrap()], 1);
+    genesis.config.epoch_length = epoch_length;
+    let mut env =
+        TestEnv::builder(&genesis.config).clients_count(2).nightshade_runtimes(&genesis).build();
+
+    let blocks = produce_epochs(&mut env, epoch_length, num_epochs);
+
+    let peer_id = PeerId::random();
+    let highest_height_peers = vec![HighestHeightPeerInfo {
+        peer_info: PeerInfo {
+            id: peer_id.clone(),
+            addr: None,
+            account_id: None,
+            extra_addrs: vec![],
+        },
+        genesis_id: Default::default(),
+        highest_block_height: env.clients[0].chain.head().unwrap().height,
+        highest_block_hash: env.clients[0].chain.head().unwrap().last_block_hash,
+        tracked_shards: vec![],
+        archival: false,
+    }];
+    let mut sync_status = SyncStatus::AwaitingPeers;
+    let client = &mut env.clients[1];
+    assert!(client.epoch_sync.run(&mut sync_status, &client.chain, &highest_height_peers).unwrap());
+
+    let mut epoch_id = client.chain.genesis().epoch_id().clone();
+    let response = EpochSyncInfoResponse { epoch_id: epoch_id.clone(), epoch_sync_infos: vec![] };
+    client.epoch_sync.on_response(&mut client.chain, response, &PeerId::random()).unwrap();
+    assert!(!client.epoch_sync.done);
+
+    let mut last_synced_hash = *client.chain.genesis().hash();
+    let mut num_synced_epochs = 0;
+    while !env.clients[1].epoch_sync.done {
+        let epoch_sync_infos = env.clients[0]
+            .chain
+            .get_epoch_sync_infos(&epoch_id, MAX_EPOCH_SYNC_INFOS_PER_RESPONSE)
+            .unwrap();
+        assert!(epoch_sync_infos.len() <= MAX_EPOCH_SYNC_INFOS_PER_RESPONSE);
+        let response = EpochSyncInfoResponse {
+            epoch_id: epoch_id.clone(),
+            epoch_sync_infos: epoch_sync_infos.clone(),
+        };
+        if let Some(epoch_sync_info) = epoch_sync_infos.last() {
+            last_synced_hash = *epoch_sync_info.get_epoch_last_header().unwrap().hash();
+            epoch_id = epoch_sync_info.get_next_epoch_id().unwrap().clone();
+        }
+        num_synced_epochs += epoch_sync_infos.len();
+
+        let client = &mut env.clients[1];
+        client.epoch_sync.on_response(&mut client.chain, response, &peer_id).unwrap();
+        assert_eq!(client.chain.header_head().unwrap().last_block_hash, last_synced_hash);
+        // Epoch sync is done once the only peer has no more epochs.
+        client.epoch_sync.run(&mut sync_status, &client.chain, &highest_height_peers).unwrap();
+    }
+    assert!(num_synced_epochs >= num_epochs as usize - 1, "{}", num_synced_epochs);
+
+    // Only the headers of the synced epochs needed for further syncing are saved.
+    let header_head = env.clients[1].chain.header_head().unwrap();
+    let middle_block = &blocks[(epoch_length / 2) as usize];
+    assert!(env.clients[1].chain.get_block_header(middle_block.hash()).is_err());
+    for epoch_id in env.clients[0]
+        .chain
+        .get_epoch_sync_infos(env.clients[0].chain.genesis().epoch_id(), num_synced_epochs)
+        .unwrap()
+        .iter()
+        .map(|epoch_sync_info| epoch_sync_info.get_epoch_id().unwrap())
+    {
+        assert!(env.clients[1].epoch_manager.get_epoch_info(epoch_id).is_ok());
+    }
+
+    // Header sync the rest of the chain.
+    let headers = blocks
+        .iter()
+        .filter(|block| block.header().height() > header_head.height)
+        .map(|block| block.header().clone())
+        .collect();
+    env.clients[1].chain.sync_block_headers(headers, &mut vec![]).unwrap();
+    assert_eq!(
+        env.clients[1].chain.header_head().unwrap().last_block_hash,
+        env.clients[0].chain.header_head().unwrap().last_block_hash
+    );
+}
+
+/// Check that `EpochSyncInfo` with tampered `EpochInfo` or which doesn't follow the header head
+/// is not recorded.
+#[test]
+fn test_epoch_sync_invalid_epoch_sync_info() {
+    init_test_logger();
+
+    let epoch_length = 10;
+
+    let mut genesis = Genesis::test(vec!["test0".parse().unwrap(), "test1".parse().unwrap()], 1);
+    genesis.config.epoch_length = epoch_length;
+    let mut env =
+        TestEnv::builder(&genesis.config).clients_count(2).nightshade_runtimes(&genesis).build();
+
+    produce_epochs(&mut env, epoch_length, 3);
+
+    let genesis_epoch_id = env.clients[0].chain.genesis().epoch_id().clone();
+    let epoch_sync_infos = env.clients[0].chain.get_epoch_sync_infos(&genesis_epoch_id, 2).unwrap();
+    assert_eq!(epoch_sync_infos.len(), 2);
+    let genesis_head = env.clients[1].chain.header_head().unwrap();
+
+    // Second epoch doesn't follow the genesis.
+    assert!(env.clients[1]
+        .chain
+        .validate_and_record_epoch_sync_info(&epoch_sync_infos[1])
+        .is_err());
+
+    // `EpochInfo` of the epoch after the next isn't committed to by the chain.
+    let mut tampered = epoch_sync_infos[0].clone();
+    tampered.next_next_epoch_info = EpochInfo::default();
+    assert!(env.clients[1].chain.validate_and_record_epoch_sync_info(&tampered).is_err());
+
+    // Header of the epoch isn't signed by its block producer.
+    let mut tampered = epoch_sync_infos[0].clone();
+    let last_hash = *tampered.get_epoch_last_header().unwrap().hash();
+    let mut header = tampered.headers[&last_hash].clone();
+    header.resign(&create_test_signer("test1"));
+    tampered.headers.insert(last_hash, header);
+    assert!(env.clients[1].chain.validate_and_record_epoch_sync_info(&tampered).is_err());
+
+    assert_eq!(env.clients[1].chain.header_head().unwrap(), genesis_head);
+
+    env.clients[1].chain.validate_and_record_epoch_sync_info(&epoch_sync_infos[0]).unwrap();
+    env.clients[1].chain.validate_and_record_epoch_sync_info(&epoch_sync_infos[1]).unwrap();
+}
+
 /// This is an unreliable test that mocks/reimplements sync logic.
-/// After epoch sync is integrated into sync process we can write a better test.
 ///
 /// The test simulates two clients, one of which is
-/// - stopped after one epoch
+/// - started from genesis
 /// - synced through epoch sync, header sync, state sync, and block sync
 /// - in sync with other client for two more epochs
 #[test]
@@ -320,8 +477,6 @@ fn test_node_after_simulated_sync() {
     let num_epochs = 5;
     // Max height for clients[0] before sync.
     let max_height_0 = epoch_length * num_epochs - 1;
-    // Max height for clients[1] before sync.
-    let max_height_1 = epoch_length;
 
     // TestEnv setup
     let mut genesis = Genesis::test(vec!["test0".parse().unwrap(), "test1".parse().unwrap()], 1);
@@ -347,10 +502,6 @@ fn test_node_after_simulated_sync() {
         env.process_block(0, block.clone(), Provenance::PRODUCED);
         last_hash = *block.hash();
         blocks.push(block.clone());
-
-        if h < max_height_1 {
-            env.process_block(1, block.clone(), Provenance::NONE);
-        }
     }
 
     // Do "epoch sync" up to last epoch
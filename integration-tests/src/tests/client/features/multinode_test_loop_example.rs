@@ -1,14 +1,12 @@
 use derive_enum_from_into::{EnumFrom, EnumTryInto};
 use near_async::messaging::{noop, IntoMultiSender, IntoSender, MessageWithCallback, SendAsync};
 use near_async::test_loop::adhoc::{handle_adhoc_events, AdhocEvent, AdhocEventSender};
-use near_async::test_loop::event_handler::{
-    ignore_events, LoopEventHandler, LoopHandlerContext, TryIntoOrSelf,
-};
+use near_async::test_loop::event_handler::ignore_events;
 use near_async::test_loop::futures::{
     drive_async_computations, drive_delayed_action_runners, drive_futures,
     TestLoopAsyncComputationEvent, TestLoopDelayedActionEvent, TestLoopTask,
 };
-use near_async::test_loop::TestLoopBuilder;
+use near_async::test_loop::{TestLoop, TestLoopBuilder};
 use near_async::time::Duration;
 use near_chain::chunks_store::ReadOnlyChunksStore;
 use near_chain::ChainGenesis;
@@ -37,15 +35,13 @@ use near_client::{Client, SyncAdapter, SyncMessage};
 use near_epoch_manager::shard_tracker::{ShardTracker, TrackedConfig};
 use near_epoch_manager::EpochManager;
 use near_network::client::{
-    BlockApproval, BlockResponse, ChunkEndorsementMessage, ChunkStateWitnessMessage,
     ClientSenderForNetwork, ClientSenderForNetworkMessage, ProcessTxRequest,
 };
 use near_network::shards_manager::ShardsManagerRequestFromNetwork;
-use near_network::test_loop::SupportsRoutingLookup;
-use near_network::types::{
-    NetworkRequests, PeerManagerMessageRequest, PeerManagerMessageResponse, SetChainInfo,
+use near_network::test_loop::{
+    peer_id_for_index, route_network_messages_to_client, LinkConfig, SimulatedNetwork,
 };
-use near_primitives::network::PeerId;
+use near_network::types::{PeerManagerMessageRequest, PeerManagerMessageResponse, SetChainInfo};
 use near_primitives::shard_layout::ShardLayout;
 use near_primitives::state_record::StateRecord;
 use near_primitives::test_utils::{create_test_signer, create_user_test_signer};
@@ -134,17 +130,24 @@ enum TestEvent {
 }
 
 const ONE_NEAR: u128 = 1_000_000_000_000_000_000_000_000;
+const NUM_CLIENTS: usize = 4;
+const NETWORK_DELAY: Duration = Duration::milliseconds(10);
+const INITIAL_BALANCE: Balance = 10000 * ONE_NEAR;
 
-#[test]
-fn test_client_with_multi_test_loop() {
-    const NUM_CLIENTS: usize = 4;
-    const NETWORK_DELAY: Duration = Duration::milliseconds(10);
+fn test_accounts() -> Vec<AccountId> {
+    (0..100).map(|i| format!("account{}", i).parse().unwrap()).collect()
+}
+
+/// Sets up NUM_CLIENTS validator nodes connected by the given simulated network,
+/// and schedules the start of their components.
+fn setup(
+    accounts: &[AccountId],
+    network: SimulatedNetwork,
+) -> TestLoop<Vec<TestData>, (usize, TestEvent)> {
     let builder = TestLoopBuilder::<(usize, TestEvent)>::new();
 
     let validator_stake = 1000000 * ONE_NEAR;
-    let initial_balance = 10000 * ONE_NEAR;
-    let accounts =
-        (0..100).map(|i| format!("account{}", i).parse().unwrap()).collect::<Vec<AccountId>>();
+    let initial_balance = INITIAL_BALANCE;
 
     // TODO: Make some builder for genesis.
     let mut genesis_config = GenesisConfig {
@@ -278,7 +281,7 @@ fn test_client_with_multi_test_loop() {
                 .for_index(idx)
                 .into_wrapped_multi_sender::<ClientSenderForClientMessage, _>(),
             client_config,
-            PeerId::random(),
+            peer_id_for_index(idx),
             builder.sender().for_index(idx).into_multi_sender(),
             None,
             noop().into_sender(),
@@ -350,8 +353,8 @@ fn test_client_with_multi_test_loop() {
     }
     // Handles network routing. Outgoing messages are handled by emitting incoming messages to the
     // appropriate component of the appropriate node index.
-    test.register_handler(route_network_messages_to_client(NETWORK_DELAY));
-    test.register_handler(route_shards_manager_network_messages(NETWORK_DELAY));
+    test.register_handler(route_network_messages_to_client(network.clone()));
+    test.register_handler(route_shards_manager_network_messages(network));
 
     // Bootstrap the test by starting the components.
     // We use adhoc events for these, just so that the visualizer can see these as events rather
@@ -371,6 +374,15 @@ fn test_client_with_multi_test_loop() {
             );
         })
     }
+    test
+}
+
+#[test]
+fn test_client_with_multi_test_loop() {
+    let accounts = test_accounts();
+    let network = SimulatedNetwork::new(0, LinkConfig::with_latency(NETWORK_DELAY));
+    let mut test = setup(&accounts, network);
+    let initial_balance = INITIAL_BALANCE;
 
     // Give it some condition to stop running at. Here we run the test until the first client
     // reaches height 10003, with a timeout of 5sec (failing if it doesn't reach 10003 in time).
@@ -438,108 +450,55 @@ fn test_client_with_multi_test_loop() {
     test.finish_remaining_events(Duration::seconds(1));
 }
 
-/// Handles outgoing network messages, and turns them into incoming client messages.
-pub fn route_network_messages_to_client<
-    Data: SupportsRoutingLookup,
-    Event: TryIntoOrSelf<PeerManagerMessageRequest>
-        + From<PeerManagerMessageRequest>
-        + From<ClientSenderForNetworkMessage>,
->(
-    network_delay: Duration,
-) -> LoopEventHandler<Data, (usize, Event)> {
-    // let mut route_back_lookup: HashMap<CryptoHash, usize> = HashMap::new();
-    // let mut next_hash: u64 = 0;
-    LoopEventHandler::new(
-        move |event: (usize, Event),
-              data: &mut Data,
-              context: &LoopHandlerContext<(usize, Event)>| {
-            let (idx, event) = event;
-            let message = event.try_into_or_self().map_err(|event| (idx, event.into()))?;
-            let PeerManagerMessageRequest::NetworkRequests(request) = message else {
-                return Err((idx, message.into()));
-            };
-
-            let client_senders = (0..data.num_accounts())
-                .map(|idx| {
-                    context
-                        .sender
-                        .with_additional_delay(network_delay)
-                        .for_index(idx)
-                        .into_wrapped_multi_sender::<ClientSenderForNetworkMessage, ClientSenderForNetwork>()
-                })
-                .collect::<Vec<_>>();
-
-            match request {
-                NetworkRequests::Block { block } => {
-                    for other_idx in 0..data.num_accounts() {
-                        if other_idx != idx {
-                            drop(client_senders[other_idx].send_async(BlockResponse {
-                                block: block.clone(),
-                                peer_id: PeerId::random(),
-                                was_requested: false,
-                            }));
-                        }
-                    }
-                }
-                NetworkRequests::Approval { approval_message } => {
-                    let other_idx = data.index_for_account(&approval_message.target);
-                    if other_idx != idx {
-                        drop(client_senders[other_idx].send_async(BlockApproval(
-                            approval_message.approval,
-                            PeerId::random(),
-                        )));
-                    } else {
-                        tracing::warn!("Dropping message to self");
-                    }
-                }
-                NetworkRequests::ForwardTx(account, transaction) => {
-                    let other_idx = data.index_for_account(&account);
-                    if other_idx != idx {
-                        drop(client_senders[other_idx].send_async(ProcessTxRequest {
-                            transaction,
-                            is_forwarded: true,
-                            check_only: false,
-                        }))
-                    } else {
-                        tracing::warn!("Dropping message to self");
-                    }
-                }
-                NetworkRequests::ChunkEndorsement(target, endorsement) => {
-                    let other_idx = data.index_for_account(&target);
-                    if other_idx != idx {
-                        drop(
-                            client_senders[other_idx]
-                                .send_async(ChunkEndorsementMessage(endorsement)),
-                        );
-                    } else {
-                        tracing::warn!("Dropping message to self");
-                    }
-                }
-                NetworkRequests::ChunkStateWitness(targets, witness) => {
-                    let other_idxes = targets
-                        .iter()
-                        .map(|account| data.index_for_account(account))
-                        .collect::<Vec<_>>();
-                    for other_idx in &other_idxes {
-                        if *other_idx != idx {
-                            drop(
-                                client_senders[*other_idx]
-                                    .send_async(ChunkStateWitnessMessage(witness.clone())),
-                            );
-                        } else {
-                            tracing::warn!(
-                                "ChunkStateWitness asked to send to nodes {:?}, but {} is ourselves, so skipping that",
-                                other_idxes, idx);
-                        }
-                    }
-                }
-                // TODO: Support more network message types as we expand the test.
-                _ => return Err((idx, PeerManagerMessageRequest::NetworkRequests(request).into())),
-            }
+/// Checks that the chain keeps going while a validator is cut off from the rest of the
+/// network, and that the validator catches up once the partition is healed.
+#[test]
+fn test_client_with_multi_test_loop_partition() {
+    const ISOLATED: usize = NUM_CLIENTS - 1;
+    let accounts = test_accounts();
+    // Lossy links with jitter; chunk parts are re-requested and blocks are re-fetched
+    // by the nodes, so the chain makes progress anyway.
+    let network = SimulatedNetwork::new(
+        42,
+        LinkConfig {
+            jitter: Duration::milliseconds(20),
+            drop_probability: 0.05,
+            ..LinkConfig::with_latency(NETWORK_DELAY)
+        },
+    );
+    let mut test = setup(&accounts, network.clone());
+    let height = |test: &TestLoop<Vec<TestData>, _>, idx: usize| {
+        test.data[idx].client.client.chain.head().unwrap().height
+    };
+
+    test.run_until(
+        |data| data[0].client.client.chain.head().unwrap().height >= 10003,
+        Duration::seconds(10),
+    );
 
-            Ok(())
+    // The remaining validators hold 3/4 of the stake, which is enough to produce blocks.
+    let majority: Vec<usize> = (0..ISOLATED).collect();
+    network.partition(&[&majority, &[ISOLATED]]);
+    let partitioned_at = height(&test, 0);
+    test.run_until(
+        |data| data[0].client.client.chain.head().unwrap().height >= partitioned_at + 5,
+        Duration::seconds(30),
+    );
+    assert!(height(&test, ISOLATED) < height(&test, 0));
+    assert!(network.dropped_messages() > 0);
+
+    network.heal();
+    let healed_at = height(&test, 0);
+    test.run_until(
+        |data| {
+            let head = data[0].client.client.chain.head().unwrap();
+            head.height > healed_at
+                && data.iter().all(|d| d.client.client.chain.head().unwrap() == head)
         },
-    )
+        Duration::seconds(30),
+    );
+
+    test.finish_remaining_events(Duration::seconds(1));
 }
 
 // TODO: This would be a good starting point for turning this into a test util.
@@ -17,7 +17,7 @@ use near_primitives::stateless_validation::ChunkStateWitness;
 use near_primitives_core::checked_feature;
 use near_primitives_core::hash::CryptoHash;
 use near_primitives_core::types::AccountId;
-use near_primitives_core::version::PROTOCOL_VERSION;
+use near_primitives_core::version::{ProtocolFeature, PROTOCOL_VERSION};
 use nearcore::test_utils::TestEnvNightshadeSetupExt;
 
 struct OrphanWitnessTestEnv {
@@ -36,7 +36,12 @@ struct OrphanWitnessTestEnv {
 /// have `block1` which is required to process the witness, so it becomes an orphaned state witness.
 fn setup_orphan_witness_test() -> OrphanWitnessTestEnv {
     let accounts: Vec<AccountId> = (0..4).map(|i| format!("test{i}").parse().unwrap()).collect();
-    let genesis = Genesis::test(accounts.clone(), accounts.len().try_into().unwrap());
+    let mut genesis = Genesis::test(accounts.clone(), accounts.len().try_into().unwrap());
+    // The test intercepts the witness sent as a whole, not as erasure-coded parts.
+    genesis.config.protocol_version = std::cmp::min(
+        PROTOCOL_VERSION,
+        ProtocolFeature::PartialEncodedStateWitness.protocol_version() - 1,
+    );
     let mut env = TestEnv::builder(&genesis.config)
         .clients(accounts.clone())
         .validators(accounts.clone())
@@ -404,6 +409,11 @@ fn modify_witness_header_inner(
 }
 
 fn resign_witness(witness: &mut ChunkStateWitness, signer: &Client) {
-    witness.signature =
-        signer.validator_signer.as_ref().unwrap().sign_chunk_state_witness(&witness.inner).0;
+    witness.signature = signer
+        .validator_signer
+        .as_ref()
+        .unwrap()
+        .sign_chunk_state_witness(&witness.inner)
+        .unwrap()
+        .0;
 }
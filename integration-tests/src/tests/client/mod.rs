@@ -15,4 +15,5 @@ mod sandbox;
 mod state_dump;
 mod state_snapshot;
 mod sync_state_nodes;
+mod tracked_shards;
 mod undo_block;
@@ -58,7 +58,9 @@ use near_primitives::transaction::{
 use near_primitives::trie_key::TrieKey;
 use near_primitives::types::validator_stake::ValidatorStake;
 use near_primitives::types::{AccountId, BlockHeight, EpochId, NumBlocks, ProtocolVersion};
-use near_primitives::validator_signer::ValidatorSigner;
+use near_primitives::validator_signer::{
+    InMemoryValidatorSigner, RotatingValidatorSigner, ValidatorSigner,
+};
 use near_primitives::version::PROTOCOL_VERSION;
 use near_primitives::views::{
     BlockHeaderView, FinalExecutionStatus, QueryRequest, QueryResponseKind,
@@ -347,7 +349,8 @@ fn receive_network_block() {
                 last_block.header.next_bp_hash,
                 block_merkle_tree.root(),
                 Clock::real().now_utc(),
-            );
+            )
+            .unwrap();
             actor_handles.client_actor.do_send(
                 BlockResponse { block, peer_id: PeerInfo::random().id, was_requested: false }
                     .with_span_context(),
@@ -433,7 +436,8 @@ fn produce_block_with_approvals() {
                 last_block.header.next_bp_hash,
                 block_merkle_tree.root(),
                 Clock::real().now_utc(),
-            );
+            )
+            .unwrap();
             actor_handles.client_actor.do_send(
                 BlockResponse {
                     block: block.clone(),
@@ -456,7 +460,8 @@ fn produce_block_with_approvals() {
                     block.header().height(),
                     10, // the height at which "test1" is producing
                     &signer,
-                );
+                )
+                .unwrap();
                 actor_handles
                     .client_actor
                     .do_send(BlockApproval(approval, PeerInfo::random().id).with_span_context());
@@ -649,7 +654,8 @@ fn invalid_blocks_common(is_requested: bool) {
                 last_block.header.next_bp_hash,
                 block_merkle_tree.root(),
                 Clock::real().now_utc(),
-            );
+            )
+            .unwrap();
             // Send block with invalid chunk mask
             let mut block = valid_block.clone();
             block.mut_header().get_mut().inner_rest.chunk_mask = vec![];
@@ -992,6 +998,7 @@ fn client_sync_headers() {
                     connection_established_time: near_async::time::Instant::now(),
                     peer_type: PeerType::Outbound,
                     nonce: 1,
+                    send_classes: vec![],
                 }],
                 num_connected_peers: 1,
                 peer_max_count: 1,
@@ -1646,7 +1653,7 @@ fn test_gc_after_state_sync() {
     // mimic what we do in possible_targets
     assert!(env.clients[1].epoch_manager.get_epoch_id_from_prev_block(&prev_block_hash).is_ok());
     let tries = env.clients[1].runtime_adapter.get_tries();
-    env.clients[1].chain.clear_data(tries, &Default::default()).unwrap();
+    env.clients[1].chain.clear_data(tries, &Default::default(), &None).unwrap();
 }
 
 #[test]
@@ -1831,6 +1838,79 @@ fn test_tx_forward_around_epoch_boundary() {
     );
 }
 
+/// The validator stakes with a new key which its node already holds, and the node
+/// switches to the new key right at the epoch boundary without a restart.
+#[test]
+fn test_rotate_validator_key_at_epoch_boundary() {
+    init_test_logger();
+    let epoch_length = 5;
+    let mut genesis = Genesis::test(vec!["test0".parse().unwrap()], 1);
+    genesis.config.epoch_length = epoch_length;
+    let old_signer: Arc<dyn ValidatorSigner> = Arc::new(create_test_signer("test0"));
+    let new_signer: Arc<dyn ValidatorSigner> = Arc::new(InMemoryValidatorSigner::from_seed(
+        "test0".parse().unwrap(),
+        KeyType::ED25519,
+        "new",
+    ));
+    let rotating: Arc<dyn ValidatorSigner> =
+        Arc::new(RotatingValidatorSigner::new(vec![old_signer.clone(), new_signer.clone()]));
+    let mut env = TestEnv::builder(&genesis.config)
+        .validator_signers(vec![rotating])
+        .nightshade_runtimes(&genesis)
+        .build();
+
+    let genesis_hash = *env.clients[0].chain.genesis().hash();
+    let account_signer =
+        InMemorySigner::from_seed("test0".parse().unwrap(), KeyType::ED25519, "test0");
+    let tx = SignedTransaction::stake(
+        1,
+        "test0".parse().unwrap(),
+        &account_signer,
+        TESTING_INIT_STAKE,
+        new_signer.public_key(),
+        genesis_hash,
+    );
+    assert_eq!(env.clients[0].process_tx(tx, false, false), ProcessTxResponse::ValidTx);
+
+    let mut last_old_key_height = None;
+    let mut first_new_key_height = None;
+    for height in 1..=epoch_length * 4 {
+        // Every block must be produced: no height is skipped because of a wrong key.
+        let block = env.clients[0].produce_block(height).unwrap().unwrap();
+        env.process_block(0, block.clone(), Provenance::PRODUCED);
+
+        let epoch_manager = &env.clients[0].epoch_manager;
+        let (validator, _) = epoch_manager
+            .get_validator_by_account_id(
+                block.header().epoch_id(),
+                block.header().prev_hash(),
+                &"test0".parse().unwrap(),
+            )
+            .unwrap();
+        assert!(block.header().verify_block_producer(validator.public_key()));
+        if validator.public_key() == &old_signer.public_key() {
+            assert!(first_new_key_height.is_none());
+            last_old_key_height = Some(height);
+        } else {
+            assert_eq!(validator.public_key(), &new_signer.public_key());
+            first_new_key_height.get_or_insert(height);
+        }
+    }
+    let last_old_key_height = last_old_key_height.unwrap();
+    let first_new_key_height = first_new_key_height.unwrap();
+    assert_eq!(first_new_key_height, last_old_key_height + 1);
+    let epoch_manager = &env.clients[0].epoch_manager;
+    let first_new_key_block =
+        env.clients[0].chain.get_block_by_height(first_new_key_height).unwrap();
+    assert!(epoch_manager
+        .is_next_block_epoch_start(first_new_key_block.header().prev_hash())
+        .unwrap());
+    assert_eq!(
+        env.clients[0].validator_signer.as_ref().unwrap().public_key(),
+        new_signer.public_key()
+    );
+}
+
 /// Blocks that have already been gc'ed should not be accepted again.
 #[test]
 fn test_not_resync_old_blocks() {
@@ -2260,7 +2340,8 @@ fn test_validate_chunk_extra() {
         block.mut_header().get_mut().inner_rest.chunk_mask = vec![true];
         block.mut_header().get_mut().inner_lite.prev_outcome_root =
             Block::compute_outcome_root(block.chunks().iter());
-        let endorsement = ChunkEndorsement::new(chunk_header.chunk_hash(), &validator_signer);
+        let endorsement =
+            ChunkEndorsement::new(chunk_header.chunk_hash(), &validator_signer).unwrap();
         block.set_chunk_endorsements(vec![vec![Some(Box::new(endorsement.signature))]]);
         block.mut_header().get_mut().inner_rest.block_body_hash =
             block.compute_block_body_hash().unwrap();
@@ -2683,7 +2764,8 @@ fn test_execution_metadata() {
         near_vm_runner::logic::ContractPrepareVersion::V1 => 2,
         // We spend two wasm instructions (call & drop), plus 8 ops for initializing function
         // operand stack (8 bytes worth to hold the return value.)
-        near_vm_runner::logic::ContractPrepareVersion::V2 => 10,
+        near_vm_runner::logic::ContractPrepareVersion::V2
+        | near_vm_runner::logic::ContractPrepareVersion::V3 => 10,
     };
 
     // Profile for what's happening *inside* wasm vm during function call.
@@ -3114,11 +3196,13 @@ fn test_header_version_downgrade() {
             BlockHeader::BlockHeaderV1(header) => {
                 let header = Arc::make_mut(header);
                 header.inner_rest.latest_protocol_version = PROTOCOL_VERSION;
-                let (hash, signature) = validator_signer.sign_block_header_parts(
-                    header.prev_hash,
-                    &borsh::to_vec(&header.inner_lite).expect("Failed to serialize"),
-                    &borsh::to_vec(&header.inner_rest).expect("Failed to serialize"),
-                );
+                let (hash, signature) = validator_signer
+                    .sign_block_header_parts(
+                        header.prev_hash,
+                        &borsh::to_vec(&header.inner_lite).expect("Failed to serialize"),
+                        &borsh::to_vec(&header.inner_rest).expect("Failed to serialize"),
+                    )
+                    .unwrap();
                 header.hash = hash;
                 header.signature = signature;
             }
@@ -21,7 +21,7 @@ fn test_pending_approvals() {
     let mut env = TestEnv::builder(&genesis.config).nightshade_runtimes(&genesis).build();
     let signer = create_test_signer("test0");
     let parent_hash = hash(&[1]);
-    let approval = Approval::new(parent_hash, 0, 1, &signer);
+    let approval = Approval::new(parent_hash, 0, 1, &signer).unwrap();
     let peer_id = PeerId::random();
     env.clients[0].collect_block_approval(&approval, ApprovalType::PeerApproval(peer_id.clone()));
     let approvals = env.clients[0].pending_approvals.pop(&ApprovalInner::Endorsement(parent_hash));
@@ -43,7 +43,7 @@ fn test_invalid_approvals() {
     let signer = create_test_signer("random");
     let parent_hash = hash(&[1]);
     // Approval not from a validator. Should be dropped
-    let approval = Approval::new(parent_hash, 1, 3, &signer);
+    let approval = Approval::new(parent_hash, 1, 3, &signer).unwrap();
     let peer_id = PeerId::random();
     env.clients[0].collect_block_approval(&approval, ApprovalType::PeerApproval(peer_id.clone()));
     assert_eq!(env.clients[0].pending_approvals.len(), 0);
@@ -51,7 +51,7 @@ fn test_invalid_approvals() {
     let signer =
         InMemoryValidatorSigner::from_seed("test0".parse().unwrap(), KeyType::ED25519, "random");
     let genesis_hash = *env.clients[0].chain.genesis().hash();
-    let approval = Approval::new(genesis_hash, 0, 1, &signer);
+    let approval = Approval::new(genesis_hash, 0, 1, &signer).unwrap();
     env.clients[0].collect_block_approval(&approval, ApprovalType::PeerApproval(peer_id));
     assert_eq!(env.clients[0].pending_approvals.len(), 0);
 }
@@ -0,0 +1,185 @@
+use near_async::messaging::Sender;
+use near_chain::chain::ApplyStatePartsRequest;
+use near_chain::{ChainStoreAccess, Provenance};
+use near_chain_configs::Genesis;
+use near_client::test_utils::TestEnv;
+use near_epoch_manager::shard_tracker::TrackedConfig;
+use near_o11y::testonly::init_test_logger;
+use near_primitives::hash::CryptoHash;
+use near_primitives::shard_layout::{account_id_to_shard_id, ShardUId};
+use near_primitives::state_part::PartId;
+use near_primitives::state_sync::StatePartKey;
+use near_primitives::types::{AccountId, BlockHeight, ShardId};
+use near_store::{DBCol, TrieConfig};
+use nearcore::test_utils::TestEnvNightshadeSetupExt;
+use std::sync::Arc;
+
+/// Produces a block on the first client, which tracks all shards, and processes it on both
+/// clients, distributing the chunks.
+fn produce_and_process_block(env: &mut TestEnv, height: BlockHeight) {
+    let block = env.clients[0].produce_block(height).unwrap().unwrap();
+    env.process_block(0, block.clone(), Provenance::PRODUCED);
+    // The second client may need to request the chunks of the shards it tracks first.
+    let _ = env.clients[1].process_block_test(block.clone().into(), Provenance::NONE);
+    env.process_partial_encoded_chunks();
+    for i in 0..env.clients.len() {
+        env.process_shards_manager_responses_and_finish_processing_blocks(i);
+    }
+    env.propagate_chunk_state_witnesses_and_endorsements(false);
+    assert_eq!(env.clients[1].chain.head().unwrap().last_block_hash, *block.hash());
+}
+
+/// Whether the second client has the trie, the flat storage and the in-memory trie of the shard.
+fn has_state(env: &TestEnv, shard_uid: ShardUId) -> (bool, bool, bool) {
+    let runtime = &env.clients[1].runtime_adapter;
+    let trie = runtime.store().iter_prefix(DBCol::State, &shard_uid.to_bytes()).next().is_some();
+    let flat_storage =
+        runtime.get_flat_storage_manager().get_flat_storage_for_shard(shard_uid).is_some();
+    let mem_trie = runtime.get_tries().get_mem_tries(shard_uid).is_some();
+    (trie, flat_storage, mem_trie)
+}
+
+/// Syncs the state of the shard of the second client from the first one, as catchup would.
+fn sync_shard_state(env: &mut TestEnv, shard_id: ShardId, sync_hash: CryptoHash) {
+    let state_sync_header =
+        env.clients[0].chain.get_state_response_header(shard_id, sync_hash).unwrap();
+    let num_parts = state_sync_header.num_state_parts();
+    let state_sync_parts = (0..num_parts)
+        .map(|i| env.clients[0].chain.get_state_response_part(shard_id, i, sync_hash).unwrap())
+        .collect::<Vec<_>>();
+
+    env.clients[1].chain.set_state_header(shard_id, sync_hash, state_sync_header).unwrap();
+    for i in 0..num_parts {
+        env.clients[1]
+            .chain
+            .set_state_part(
+                shard_id,
+                sync_hash,
+                PartId::new(i, num_parts),
+                &state_sync_parts[i as usize],
+            )
+            .unwrap();
+    }
+    let rt = Arc::clone(&env.clients[1].runtime_adapter);
+    let f = Sender::from_fn(move |msg: ApplyStatePartsRequest| {
+        // The flat storage of the shard was removed by garbage collection.
+        assert!(rt.get_flat_storage_manager().get_flat_storage_for_shard(msg.shard_uid).is_none());
+        let store = rt.store();
+        for part_id in 0..msg.num_parts {
+            let key = borsh::to_vec(&StatePartKey(msg.sync_hash, shard_id, part_id)).unwrap();
+            let part = store.get(DBCol::StateParts, &key).unwrap().unwrap();
+            rt.apply_state_part(
+                shard_id,
+                &msg.state_root,
+                PartId::new(part_id, msg.num_parts),
+                &part,
+                &msg.epoch_id,
+            )
+            .unwrap();
+        }
+    });
+    env.clients[1].chain.schedule_apply_state_parts(shard_id, sync_hash, num_parts, &f).unwrap();
+    env.clients[1].chain.set_state_finalize(shard_id, sync_hash, Ok(())).unwrap();
+}
+
+/// Stop tracking all shards but one while the node is running. Check that the state of the other
+/// shards is garbage collected together with the last epoch in which they were tracked, while
+/// the tracked shard is kept. Then track all shards again and check that the state of the
+/// removed shards is caught up.
+#[test]
+fn test_gc_and_catchup_after_tracked_shards_update() {
+    init_test_logger();
+
+    let epoch_length = 5;
+    let accounts: Vec<AccountId> = vec!["test0".parse().unwrap(), "test1".parse().unwrap()];
+    // Only the first account is a validator, so the second client tracks shards only because of
+    // its config.
+    let mut genesis = Genesis::test_sharded_new_version(accounts.clone(), 1, vec![1; 4]);
+    genesis.config.epoch_length = epoch_length;
+    let mut env = TestEnv::builder(&genesis.config)
+        .clients(accounts.clone())
+        .use_state_snapshots()
+        .real_stores()
+        .track_all_shards()
+        .nightshade_runtimes_with_trie_config(
+            &genesis,
+            vec![
+                TrieConfig::default(),
+                TrieConfig { load_mem_tries_for_all_shards: true, ..Default::default() },
+            ],
+        )
+        .build();
+
+    let shard_layout = env.clients[1].epoch_manager.get_shard_layout(&Default::default()).unwrap();
+    let tracked_shard_id = account_id_to_shard_id(&accounts[0], &shard_layout);
+    let untracked_shard_uids: Vec<_> = shard_layout
+        .shard_uids()
+        .filter(|shard_uid| shard_uid.shard_id() != tracked_shard_id)
+        .collect();
+    for shard_uid in shard_layout.shard_uids() {
+        assert_eq!(has_state(&env, shard_uid), (true, true, true));
+    }
+
+    let mut height = 1;
+    produce_and_process_block(&mut env, height);
+    let head = env.clients[1].chain.head().unwrap();
+    env.clients[1]
+        .shard_tracker
+        .update_tracked_config(TrackedConfig::Accounts(vec![accounts[0].clone()]), &head.epoch_id)
+        .unwrap();
+
+    // The shards are tracked until the epoch after the next one, which is then kept for
+    // `gc_num_epochs_to_keep` epochs.
+    let gc_num_epochs_to_keep = env.clients[1].config.gc.gc_num_epochs_to_keep();
+    let gc_height = height + epoch_length * (gc_num_epochs_to_keep + 3);
+    while height < gc_height {
+        height += 1;
+        produce_and_process_block(&mut env, height);
+        if height < epoch_length * 3 {
+            for shard_uid in &untracked_shard_uids {
+                assert!(has_state(&env, *shard_uid).0);
+            }
+        }
+    }
+    for shard_uid in &untracked_shard_uids {
+        assert_eq!(has_state(&env, *shard_uid), (false, false, false));
+    }
+    let tracked_shard_uid = ShardUId::from_shard_id_and_layout(tracked_shard_id, &shard_layout);
+    assert_eq!(has_state(&env, tracked_shard_uid), (true, true, true));
+
+    // Track all shards again, which makes the node catch up the state of the removed shards in
+    // the next epoch.
+    let head = env.clients[1].chain.head().unwrap();
+    env.clients[1]
+        .shard_tracker
+        .update_tracked_config(TrackedConfig::AllShards, &head.epoch_id)
+        .unwrap();
+    let state_sync_info = loop {
+        height += 1;
+        produce_and_process_block(&mut env, height);
+        let state_sync_infos =
+            env.clients[1].chain.chain_store().iterate_state_sync_infos().unwrap();
+        if let Some((_, state_sync_info)) = state_sync_infos.into_iter().next() {
+            break state_sync_info;
+        }
+        assert!(height < gc_height + epoch_length * 2, "catchup didn't start");
+    };
+    let sync_hash = state_sync_info.epoch_tail_hash;
+    let mut synced_shard_ids: Vec<_> =
+        state_sync_info.shards.iter().map(|shard_info| shard_info.0).collect();
+    synced_shard_ids.sort();
+    assert_eq!(
+        synced_shard_ids,
+        untracked_shard_uids.iter().map(|shard_uid| shard_uid.shard_id()).collect::<Vec<_>>()
+    );
+
+    let sync_prev_hash = *env.clients[1].chain.get_block_header(&sync_hash).unwrap().prev_hash();
+    for shard_uid in &untracked_shard_uids {
+        sync_shard_state(&mut env, shard_uid.shard_id(), sync_hash);
+        assert_eq!(has_state(&env, *shard_uid), (true, true, false));
+        assert_eq!(
+            env.clients[1].chain.get_chunk_extra(&sync_prev_hash, shard_uid).unwrap(),
+            env.clients[0].chain.get_chunk_extra(&sync_prev_hash, shard_uid).unwrap()
+        );
+    }
+}
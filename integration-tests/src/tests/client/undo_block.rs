@@ -1,38 +1,52 @@
+use near_chain::types::RuntimeAdapter;
 use near_chain::{ChainStore, ChainStoreAccess, Provenance};
 use near_chain_configs::Genesis;
 use near_client::test_utils::TestEnv;
 use near_epoch_manager::EpochManagerAdapter;
 use near_o11y::testonly::init_test_logger;
+use near_primitives::types::BlockHeight;
 use near_store::test_utils::create_test_store;
-use near_store::Store;
-use near_undo_block::undo_block;
+use near_store::{ShardTries, Store};
+use near_undo_block::{undo_block, undo_blocks};
 use nearcore::test_utils::TestEnvNightshadeSetupExt;
 use std::sync::Arc;
 
 /// Setup environment with one Near client for testing.
-fn setup_env(genesis: &Genesis, store: Store) -> (TestEnv, Arc<dyn EpochManagerAdapter>) {
+fn setup_env(
+    genesis: &Genesis,
+    store: Store,
+) -> (TestEnv, Arc<dyn EpochManagerAdapter>, ShardTries) {
     let env =
         TestEnv::builder(&genesis.config).stores(vec![store]).nightshade_runtimes(genesis).build();
     let epoch_manager = env.clients[0].epoch_manager.clone();
-    (env, epoch_manager)
+    let tries = env.clients[0].runtime_adapter.get_tries();
+    (env, epoch_manager, tries)
 }
 
-// Checks that Near client can successfully undo block on given height and then produce and process block normally after restart
-fn test_undo_block(epoch_length: u64, stop_height: u64) {
+/// Setup environment and produce blocks up to `stop_height`.
+fn setup_env_with_blocks(
+    epoch_length: u64,
+    stop_height: BlockHeight,
+) -> (Genesis, Store, Arc<dyn EpochManagerAdapter>, ShardTries) {
     init_test_logger();
 
-    let save_trie_changes = true;
-
     let mut genesis = Genesis::test(vec!["test0".parse().unwrap(), "test1".parse().unwrap()], 1);
     genesis.config.epoch_length = epoch_length;
 
     let store = create_test_store();
-    let (mut env, epoch_manager) = setup_env(&genesis, store.clone());
+    let (mut env, epoch_manager, tries) = setup_env(&genesis, store.clone());
 
     for i in 1..=stop_height {
         let block = env.clients[0].produce_block(i).unwrap().unwrap();
         env.process_block(0, block, Provenance::PRODUCED);
     }
+    (genesis, store, epoch_manager, tries)
+}
+
+// Checks that Near client can successfully undo block on given height and then produce and process block normally after restart
+fn test_undo_block(epoch_length: u64, stop_height: u64) {
+    let save_trie_changes = true;
+    let (genesis, store, epoch_manager, tries) = setup_env_with_blocks(epoch_length, stop_height);
 
     let mut chain_store =
         ChainStore::new(store.clone(), genesis.config.genesis_height, save_trie_changes);
@@ -40,14 +54,14 @@ fn test_undo_block(epoch_length: u64, stop_height: u64) {
     let current_head = chain_store.head().unwrap();
     let prev_block_hash = current_head.prev_block_hash;
 
-    undo_block(&mut chain_store, &*epoch_manager).unwrap();
+    undo_block(&mut chain_store, &*epoch_manager, &tries).unwrap();
 
     // after undo, the current head should be the prev_block_hash
     assert_eq!(chain_store.head().unwrap().last_block_hash.as_bytes(), prev_block_hash.as_bytes());
     assert_eq!(chain_store.head().unwrap().height, stop_height - 1);
 
     // set up an environment again with the same store
-    let (mut env, _) = setup_env(&genesis, store);
+    let (mut env, _, _) = setup_env(&genesis, store);
     // the new env should be able to produce block normally
     let block = env.clients[0].produce_block(stop_height).unwrap().unwrap();
     env.process_block(0, block, Provenance::PRODUCED);
@@ -70,3 +84,49 @@ fn test_undo_block_end_of_epoch() {
 fn test_undo_block_start_of_epoch() {
     test_undo_block(5, 6)
 }
+
+// Checks that all non-final blocks can be undone at once, and produced and processed again after restart
+fn test_undo_blocks(epoch_length: u64, stop_height: u64) {
+    let save_trie_changes = true;
+    let (genesis, store, epoch_manager, tries) = setup_env_with_blocks(epoch_length, stop_height);
+
+    let mut chain_store =
+        ChainStore::new(store.clone(), genesis.config.genesis_height, save_trie_changes);
+    let final_head = chain_store.final_head().unwrap();
+    assert!(final_head.height + 1 < stop_height);
+
+    // dry run doesn't change anything
+    undo_blocks(&mut chain_store, &*epoch_manager, &tries, final_head.height, true).unwrap();
+    assert_eq!(chain_store.head().unwrap().height, stop_height);
+
+    // can't undo the final block
+    assert!(undo_blocks(&mut chain_store, &*epoch_manager, &tries, final_head.height - 1, false)
+        .is_err());
+    assert_eq!(chain_store.head().unwrap().height, stop_height);
+
+    undo_blocks(&mut chain_store, &*epoch_manager, &tries, final_head.height, false).unwrap();
+    assert_eq!(chain_store.head().unwrap(), final_head);
+    assert_eq!(chain_store.header_head().unwrap(), final_head);
+    for height in final_head.height + 1..=stop_height {
+        assert!(chain_store.get_block_hash_by_height(height).is_err());
+    }
+
+    // set up an environment again with the same store
+    let (mut env, _, _) = setup_env(&genesis, store);
+    // the new env should be able to produce the undone blocks again
+    for height in final_head.height + 1..=stop_height {
+        let block = env.clients[0].produce_block(height).unwrap().unwrap();
+        env.process_block(0, block, Provenance::PRODUCED);
+    }
+    assert_eq!(chain_store.head().unwrap().height, stop_height);
+}
+
+#[test]
+fn test_undo_blocks_middle_of_epoch() {
+    test_undo_blocks(5, 4)
+}
+
+#[test]
+fn test_undo_blocks_across_epoch_boundary() {
+    test_undo_blocks(5, 7)
+}
@@ -0,0 +1,90 @@
+use crate::test_helpers::heavy_test;
+use actix::System;
+use near_actix_test_utils::{run_actix, spawn_interruptible};
+use near_async::time;
+use near_chain_configs::Genesis;
+use near_client::GetBlock;
+use near_network::raw;
+use near_network::tcp;
+use near_network::test_utils::{convert_boot_nodes, wait_or_timeout};
+use near_o11y::testonly::init_integration_logger;
+use near_o11y::WithSpanContextExt;
+use near_primitives::types::{BlockId, BlockReference};
+use nearcore::{load_test_config, start_with_config};
+use std::ops::ControlFlow;
+
+/// A light client bootstrapped from the genesis block follows the chain of a node over P2P.
+#[test]
+#[cfg_attr(not(feature = "expensive_tests"), ignore)]
+fn light_client_sync_over_p2p() {
+    heavy_test(|| {
+        init_integration_logger();
+
+        let epoch_length = 10;
+        let mut genesis = Genesis::test(vec!["test1".parse().unwrap()], 1);
+        genesis.config.epoch_length = epoch_length;
+        let addr = tcp::ListenerAddr::reserve_for_test();
+        let mut near = load_test_config("test1", addr, genesis.clone());
+        near.network_config.peer_store.boot_nodes = convert_boot_nodes(vec![]);
+        near.client_config.min_num_peers = 0;
+        let peer_id = near.network_config.node_id();
+        let dir = tempfile::Builder::new().prefix("light_client").tempdir().unwrap();
+
+        run_actix(async move {
+            let nearcore::NearNode { view_client, .. } =
+                start_with_config(dir.path(), near).expect("start_with_config");
+            spawn_interruptible(async move {
+                // Wait until the chain spans a few epochs.
+                wait_or_timeout(100, 60000, || async {
+                    match view_client.send(GetBlock::latest().with_span_context()).await {
+                        Ok(Ok(b)) if b.header.height > 4 * epoch_length => ControlFlow::Break(()),
+                        _ => ControlFlow::Continue(()),
+                    }
+                })
+                .await
+                .unwrap();
+                let genesis_hash = view_client
+                    .send(
+                        GetBlock(BlockReference::BlockId(BlockId::Height(
+                            genesis.config.genesis_height,
+                        )))
+                        .with_span_context(),
+                    )
+                    .await
+                    .unwrap()
+                    .unwrap()
+                    .header
+                    .hash;
+
+                let mut conn = raw::Connection::connect(
+                    *addr,
+                    peer_id,
+                    None,
+                    &genesis.config.chain_id,
+                    genesis_hash,
+                    0,
+                    vec![],
+                    time::Duration::seconds(10),
+                )
+                .await
+                .unwrap();
+                let mut client =
+                    near_light_client::bootstrap(&mut conn, genesis_hash).await.unwrap();
+                let verified = near_light_client::sync(&mut conn, &mut client).await.unwrap();
+                assert!(verified >= 2, "verified {verified} blocks");
+
+                // The head of the light client is a block of the node's chain.
+                let head = view_client
+                    .send(
+                        GetBlock(BlockReference::BlockId(BlockId::Hash(client.head_hash())))
+                            .with_span_context(),
+                    )
+                    .await
+                    .unwrap()
+                    .unwrap();
+                assert!(head.header.height > 3 * epoch_length);
+                System::current().stop();
+            });
+        });
+    });
+}
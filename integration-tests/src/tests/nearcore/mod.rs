@@ -1,3 +1,6 @@
+// Light client requests are served only by the nodes supporting the nightly protocol.
+#[cfg(feature = "nightly")]
+mod light_client;
 mod node_cluster;
 mod rpc_error_structs;
 mod rpc_nodes;
@@ -19,9 +19,10 @@ use near_chain_configs::{
     default_view_client_threads, default_view_client_throttle_period, get_initial_supply,
     ChunkDistributionNetworkConfig, ClientConfig, GCConfig, Genesis, GenesisConfig,
     GenesisValidationMode, LogSummaryStyle, MutableConfigValue, ReshardingConfig, StateSyncConfig,
-    BLOCK_PRODUCER_KICKOUT_THRESHOLD, CHUNK_PRODUCER_KICKOUT_THRESHOLD, EXPECTED_EPOCH_LENGTH,
-    FISHERMEN_THRESHOLD, GAS_PRICE_ADJUSTMENT_RATE, GENESIS_CONFIG_FILENAME, INITIAL_GAS_LIMIT,
-    MAX_INFLATION_RATE, MIN_BLOCK_PRODUCTION_DELAY, MIN_GAS_PRICE, NEAR_BASE, NUM_BLOCKS_PER_YEAR,
+    TransactionSelectionStrategy, BLOCK_PRODUCER_KICKOUT_THRESHOLD,
+    CHUNK_PRODUCER_KICKOUT_THRESHOLD, EXPECTED_EPOCH_LENGTH, FISHERMEN_THRESHOLD,
+    GAS_PRICE_ADJUSTMENT_RATE, GENESIS_CONFIG_FILENAME, INITIAL_GAS_LIMIT, MAX_INFLATION_RATE,
+    MIN_BLOCK_PRODUCTION_DELAY, MIN_GAS_PRICE, NEAR_BASE, NUM_BLOCKS_PER_YEAR,
     NUM_BLOCK_PRODUCER_SEATS, PROTOCOL_REWARD_RATE, PROTOCOL_UPGRADE_STAKE_THRESHOLD,
     TRANSACTION_VALIDITY_PERIOD,
 };
@@ -41,10 +42,13 @@ use near_primitives::types::{
     ShardId,
 };
 use near_primitives::utils::{from_timestamp, get_num_seats_per_shard};
-use near_primitives::validator_signer::{InMemoryValidatorSigner, ValidatorSigner};
+use near_primitives::validator_signer::{
+    InMemoryValidatorSigner, RotatingValidatorSigner, ValidatorSigner,
+};
 use near_primitives::version::PROTOCOL_VERSION;
 #[cfg(feature = "rosetta_rpc")]
 use near_rosetta_rpc::RosettaRpcConfig;
+use near_signer::RemoteValidatorSigner;
 use near_store::config::StateSnapshotType;
 use near_store::{StateSnapshotConfig, Store, TrieConfig};
 use near_telemetry::TelemetryConfig;
@@ -206,6 +210,17 @@ pub struct Config {
     pub genesis_file: String,
     pub genesis_records_file: Option<String>,
     pub validator_key_file: String,
+    /// Files with other keys of the validator. The node signs with the key
+    /// which is the validator's key in the current epoch, so a new key can be
+    /// added here (and the config reloaded with SIGHUP) before staking with it,
+    /// and the node switches to it once the stake takes effect.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub additional_validator_key_files: Vec<String>,
+    /// If set, the validator key is held by a remote signer (see `tools/signer`)
+    /// and `validator_key_file` is not used. The node authenticates itself
+    /// to the signer with its node key.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_signer: Option<near_signer::RemoteSignerConfig>,
     pub node_key_file: String,
     #[cfg(feature = "json_rpc")]
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -220,6 +235,10 @@ pub struct Config {
     pub tracked_shards: Vec<ShardId>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tracked_shard_schedule: Option<Vec<Vec<ShardId>>>,
+    /// Patterns of accounts whose shards are tracked, e.g. `aurora`, `sweat.*.near` or
+    /// `regex:^sweat_.*`. Patterns without a literal prefix, like `*.sweat`, track all shards.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tracked_account_patterns: Vec<String>,
     #[serde(skip_serializing_if = "is_false")]
     pub archive: bool,
     /// If save_trie_changes is not set it will get inferred from the `archive` field as follows:
@@ -230,6 +249,19 @@ pub struct Config {
     /// needs trie changes in order to do garbage collection on hot and populate cold State column.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub save_trie_changes: Option<bool>,
+    /// Whether to save per-receipt execution reports (gas, wall time, trie node reads) of
+    /// applied chunks. The reports are available through the entity debug API and are
+    /// garbage collected together with the blocks.
+    #[serde(skip_serializing_if = "is_false")]
+    pub save_chunk_execution_reports: bool,
+    /// Whether to save chunk state witnesses which failed validation, so that they can be
+    /// replayed with `neard view-state validate-witness`. Only the most recent ones are kept.
+    #[serde(skip_serializing_if = "is_false")]
+    pub save_invalid_witnesses: bool,
+    /// Number of the most recently validated chunk state witnesses to save, whether valid
+    /// or not. Zero disables saving them.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub save_latest_witnesses: usize,
     pub log_summary_style: LogSummaryStyle,
     #[serde(with = "near_async::time::serde_duration_as_std")]
     pub log_summary_period: Duration,
@@ -271,6 +303,11 @@ pub struct Config {
     /// Setting this value too low (<1MB) on the validator might lead to production of smaller
     /// chunks and underutilizing the capacity of the network.
     pub transaction_pool_size_limit: Option<u64>,
+    /// Order in which a chunk producer takes transactions from the pool: `fifo_per_group`
+    /// takes one transaction per signer access key in turns, `prepaid_gas_descending` takes the
+    /// transactions which prepay the most gas for function calls first, and `fair_share_per_signer` takes one
+    /// transaction per signer account in turns.
+    pub transaction_selection_strategy: TransactionSelectionStrategy,
     // Configuration for resharding.
     pub resharding_config: ReshardingConfig,
     /// If the node is not a chunk producer within that many blocks, then route
@@ -297,12 +334,19 @@ pub struct Config {
 fn is_false(value: &bool) -> bool {
     !*value
 }
+
+fn is_zero(value: &usize) -> bool {
+    *value == 0
+}
+
 impl Default for Config {
     fn default() -> Self {
         Config {
             genesis_file: GENESIS_CONFIG_FILENAME.to_string(),
             genesis_records_file: None,
             validator_key_file: VALIDATOR_KEY_FILE.to_string(),
+            additional_validator_key_files: vec![],
+            remote_signer: None,
             node_key_file: NODE_KEY_FILE.to_string(),
             #[cfg(feature = "json_rpc")]
             rpc: Some(RpcConfig::default()),
@@ -314,8 +358,12 @@ impl Default for Config {
             tracked_accounts: vec![],
             tracked_shards: vec![],
             tracked_shard_schedule: None,
+            tracked_account_patterns: vec![],
             archive: false,
             save_trie_changes: None,
+            save_chunk_execution_reports: false,
+            save_invalid_witnesses: false,
+            save_latest_witnesses: 0,
             log_summary_style: LogSummaryStyle::Colored,
             log_summary_period: default_log_summary_period(),
             gc: GCConfig::default(),
@@ -331,6 +379,7 @@ impl Default for Config {
             state_sync: default_state_sync(),
             state_sync_enabled: default_state_sync_enabled(),
             transaction_pool_size_limit: default_transaction_pool_size_limit(),
+            transaction_selection_strategy: TransactionSelectionStrategy::default(),
             enable_multiline_logging: default_enable_multiline_logging(),
             resharding_config: ReshardingConfig::default(),
             tx_routing_height_horizon: default_tx_routing_height_horizon(),
@@ -527,8 +576,12 @@ impl NearConfig {
                 tracked_accounts: config.tracked_accounts,
                 tracked_shards: config.tracked_shards,
                 tracked_shard_schedule: config.tracked_shard_schedule.unwrap_or(vec![]),
+                tracked_account_patterns: config.tracked_account_patterns,
                 archive: config.archive,
                 save_trie_changes: config.save_trie_changes.unwrap_or(!config.archive),
+                save_chunk_execution_reports: config.save_chunk_execution_reports,
+                save_invalid_witnesses: config.save_invalid_witnesses,
+                save_latest_witnesses: config.save_latest_witnesses,
                 log_summary_style: config.log_summary_style,
                 gc: config.gc,
                 view_client_threads: config.view_client_threads,
@@ -543,6 +596,7 @@ impl NearConfig {
                 state_sync_enabled: config.state_sync_enabled,
                 state_sync: config.state_sync.unwrap_or_default(),
                 transaction_pool_size_limit: config.transaction_pool_size_limit,
+                transaction_selection_strategy: config.transaction_selection_strategy,
                 enable_multiline_logging: config.enable_multiline_logging.unwrap_or(true),
                 resharding_config: MutableConfigValue::new(
                     config.resharding_config,
@@ -646,6 +700,7 @@ impl NightshadeRuntime {
             config.config.gc.gc_num_epochs_to_keep(),
             TrieConfig::from_store_config(&config.config.store),
             state_snapshot_config,
+            config.client_config.save_chunk_execution_reports,
         )
     }
 }
@@ -1186,6 +1241,37 @@ impl From<NodeKeyFile> for KeyFile {
     }
 }
 
+/// Reads the validator keys from `validator_key_file` and `additional_validator_key_files`.
+/// Returns `None` if the node is not a validator, i.e. `validator_key_file` doesn't exist.
+pub fn load_validator_signers(
+    dir: &Path,
+    config: &Config,
+) -> anyhow::Result<Option<Vec<Arc<dyn ValidatorSigner>>>> {
+    let validator_file = dir.join(&config.validator_key_file);
+    if !validator_file.exists() {
+        return Ok(None);
+    }
+    let mut signers: Vec<Arc<dyn ValidatorSigner>> = vec![];
+    for file in std::iter::once(&config.validator_key_file)
+        .chain(config.additional_validator_key_files.iter())
+    {
+        let path = dir.join(file);
+        let signer = InMemoryValidatorSigner::from_file(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        if let Some(first) = signers.first() {
+            anyhow::ensure!(
+                first.validator_id() == signer.validator_id(),
+                "{} holds a key of {}, expected a key of {}",
+                path.display(),
+                signer.validator_id(),
+                first.validator_id()
+            );
+        }
+        signers.push(Arc::new(signer));
+    }
+    Ok(Some(signers))
+}
+
 pub fn load_config(
     dir: &Path,
     genesis_validation: GenesisValidationMode,
@@ -1199,21 +1285,20 @@ pub fn load_config(
         validation_errors.push_errors(e)
     };
 
-    let validator_file = dir.join(&config.validator_key_file);
-    let validator_signer = if validator_file.exists() {
-        match InMemoryValidatorSigner::from_file(&validator_file) {
-            Ok(signer) => Some(Arc::new(signer) as Arc<dyn ValidatorSigner>),
-            Err(_) => {
-                let error_message = format!(
-                    "Failed initializing validator signer from {}",
-                    validator_file.display()
-                );
+    let validator_signer = if config.remote_signer.is_some() {
+        // Remote signer is created once the node key is loaded.
+        None
+    } else {
+        match load_validator_signers(dir, &config) {
+            Ok(signers) => signers.map(|signers| {
+                Arc::new(RotatingValidatorSigner::new(signers)) as Arc<dyn ValidatorSigner>
+            }),
+            Err(err) => {
+                let error_message = format!("Failed initializing validator signer: {err:#}");
                 validation_errors.push_validator_key_file_error(error_message);
                 None
             }
         }
-    } else {
-        None
     };
 
     let node_key_path = dir.join(&config.node_key_file);
@@ -1227,6 +1312,23 @@ pub fn load_config(
             None
         }
     };
+    let validator_signer = match (&config.remote_signer, &network_signer) {
+        (Some(remote_signer), Some(node_key_file)) => Some(Arc::new(RemoteValidatorSigner::new(
+            remote_signer.clone(),
+            node_key_file.secret_key.clone(),
+        )) as Arc<dyn ValidatorSigner>),
+        // Never fall back to running without a validator key: the node would
+        // silently stop validating.
+        (Some(_), None) => {
+            let error_message = format!(
+                "remote_signer requires the node key, which failed to load from {}",
+                node_key_path.display()
+            );
+            validation_errors.push_validator_key_file_error(error_message);
+            None
+        }
+        (None, _) => validator_signer,
+    };
 
     let genesis_file = dir.join(&config.genesis_file);
     let genesis_result = match &config.genesis_records_file {
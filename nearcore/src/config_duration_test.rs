@@ -58,6 +58,16 @@ fn test_config_duration_all_std() {
                     routed_message_ttl: Some(0),
                     routing_table_update_rate_limit_burst: Some(0),
                     routing_table_update_rate_limit_qps: Some(0.0),
+                    sync_responses_send_rate_limit_burst: Some(0),
+                    sync_responses_send_rate_limit_qps: Some(0.0),
+                    state_parts_send_rate_limit_burst: Some(0),
+                    state_parts_send_rate_limit_qps: Some(0.0),
+                    routed_chunks_send_rate_limit_burst: Some(0),
+                    routed_chunks_send_rate_limit_qps: Some(0.0),
+                    light_client_requests_rate_limit_burst: Some(0),
+                    light_client_requests_rate_limit_qps: Some(0.0),
+                    epoch_sync_requests_rate_limit_burst: Some(0),
+                    epoch_sync_requests_rate_limit_qps: Some(0.0),
                 },
                 ..Default::default()
             },
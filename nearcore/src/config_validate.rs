@@ -1,5 +1,6 @@
 use near_chain_configs::{ExternalStorageLocation, SyncConfig};
 use near_config_utils::{ValidationError, ValidationErrors};
+use near_epoch_manager::shard_tracker::AccountPattern;
 use std::collections::HashSet;
 use std::path::Path;
 
@@ -157,6 +158,13 @@ impl<'a> ConfigValidator<'a> {
             let error_message = format!("'config.tx_routing_height_horizon' can't be too high to avoid spamming the network. Keep it below 100. Got {tx_routing_height_horizon}.");
             self.validation_errors.push_config_semantics_error(error_message);
         }
+
+        for pattern in &self.config.tracked_account_patterns {
+            if let Err(err) = pattern.parse::<AccountPattern>() {
+                let error_message = format!("'config.tracked_account_patterns': {err}.");
+                self.validation_errors.push_config_semantics_error(error_message);
+            }
+        }
     }
 
     fn result_with_full_error(&self) -> Result<(), ValidationError> {
@@ -250,4 +258,21 @@ mod tests {
         config.tx_routing_height_horizon = 1_000_000_000;
         validate_config(&config).unwrap();
     }
+
+    #[test]
+    #[should_panic(
+        expected = "\\nconfig.json semantic issue: 'config.tracked_account_patterns': invalid regex in account pattern"
+    )]
+    fn test_tracked_account_patterns_invalid_regex() {
+        let mut config = Config::default();
+        config.tracked_account_patterns = vec!["sweat*".to_string(), "regex:sweat_(".to_string()];
+        validate_config(&config).unwrap();
+    }
+
+    #[test]
+    fn test_tracked_account_patterns_without_prefix() {
+        let mut config = Config::default();
+        config.tracked_account_patterns = vec!["*.sweat".to_string()];
+        validate_config(&config).unwrap();
+    }
 }
@@ -1,4 +1,4 @@
-use crate::config::Config;
+use crate::config::{load_validator_signers, Config};
 use near_chain_configs::UpdateableClientConfig;
 use near_dyn_configs::{UpdateableConfigLoaderError, UpdateableConfigs};
 use near_o11y::log_config::LogConfig;
@@ -20,10 +20,26 @@ pub fn read_updateable_configs(
         }
     };
     let updateable_client_config =
-        match Config::from_file(&home_dir.join(crate::config::CONFIG_FILENAME))
-            .map(get_updateable_client_config)
-        {
-            Ok(config) => Some(config),
+        match Config::from_file(&home_dir.join(crate::config::CONFIG_FILENAME)) {
+            Ok(config) => {
+                let validator_signers = if config.remote_signer.is_some() {
+                    None
+                } else {
+                    match load_validator_signers(home_dir, &config) {
+                        Ok(signers) => signers,
+                        Err(err) => {
+                            errs.push(UpdateableConfigLoaderError::ConfigFileError {
+                                file: PathBuf::from(&config.validator_key_file),
+                                err,
+                            });
+                            None
+                        }
+                    }
+                };
+                let mut client_config = get_updateable_client_config(config);
+                client_config.validator_signers = validator_signers;
+                Some(client_config)
+            }
             Err(err) => {
                 errs.push(UpdateableConfigLoaderError::ConfigFileError {
                     file: PathBuf::from(crate::config::CONFIG_FILENAME),
@@ -49,6 +65,11 @@ pub fn get_updateable_client_config(config: Config) -> UpdateableClientConfig {
         expected_shutdown: config.expected_shutdown,
         resharding_config: config.resharding_config,
         produce_chunk_add_transactions_time_limit: config.produce_chunk_add_transactions_time_limit,
+        tracked_accounts: config.tracked_accounts,
+        tracked_shards: config.tracked_shards,
+        tracked_shard_schedule: config.tracked_shard_schedule.unwrap_or_default(),
+        tracked_account_patterns: config.tracked_account_patterns,
+        validator_signers: None,
     }
 }
 
@@ -11,6 +11,7 @@ use near_jsonrpc_primitives::types::entity_debug::{
 };
 use near_primitives::block::Tip;
 use near_primitives::challenge::{PartialState, TrieValue};
+use near_primitives::execution_report::ChunkExecutionReport;
 use near_primitives::hash::{hash, CryptoHash};
 use near_primitives::receipt::Receipt;
 use near_primitives::sharding::ShardChunk;
@@ -98,6 +99,27 @@ impl EntityDebugHandlerImpl {
                 )?;
                 Ok(serialize_entity(&ChunkView::from_author_chunk(author, chunk)))
             }
+            EntityQuery::ChunkExecutionReportByBlockHash { block_hash } => {
+                let block = self
+                    .store
+                    .get_ser::<Block>(DBCol::Block, &borsh::to_vec(&block_hash).unwrap())?
+                    .ok_or_else(|| anyhow!("Block not found"))?;
+                let shard_layout =
+                    self.epoch_manager.get_shard_layout(block.header().epoch_id())?;
+                let mut reports = EntityDataStruct::new();
+                for shard_id in shard_layout.shard_ids() {
+                    // Reports are only saved for new chunks and only if enabled in the config.
+                    let Some(report) = self.store.get_ser::<ChunkExecutionReport>(
+                        DBCol::ChunkExecutionReport,
+                        &get_block_shard_id(&block_hash, shard_id),
+                    )?
+                    else {
+                        continue;
+                    };
+                    reports.add(&shard_id.to_string(), serialize_entity(&report));
+                }
+                Ok(EntityDataValue::Struct(reports.into()))
+            }
             EntityQuery::EpochInfoByEpochId { epoch_id } => {
                 let epoch_info = self.epoch_manager.get_epoch_info(&epoch_id)?;
                 Ok(serialize_entity(&*epoch_info))
@@ -396,6 +396,9 @@ pub fn start_with_config_and_synchronization(
 
     let hot_store = storage.get_hot_store();
 
+    if let Some(traffic_capture) = &mut config.network_config.traffic_capture {
+        traffic_capture.dir = home_dir.join(&traffic_capture.dir);
+    }
     let mut rpc_servers = Vec::new();
     let network_actor = PeerManagerActor::spawn(
         time::Clock::real(),
@@ -13,6 +13,7 @@ use near_fork_network::cli::ForkNetworkCommand;
 use near_jsonrpc_primitives::types::light_client::RpcLightClientExecutionProofResponse;
 use near_mirror::MirrorCommand;
 use near_network::tcp;
+use near_network_replay::NetworkCommand;
 use near_o11y::tracing_subscriber::EnvFilter;
 use near_o11y::{
     default_subscriber, default_subscriber_with_opentelemetry, BuildEnvFilterError,
@@ -146,6 +147,9 @@ impl NeardCmd {
             NeardSubCommand::StatePartsDumpCheck(cmd) => {
                 cmd.run()?;
             }
+            NeardSubCommand::Network(cmd) => {
+                cmd.run(&mut std::io::stdout().lock())?;
+            }
             #[cfg(feature = "new_epoch_sync")]
             NeardSubCommand::EpochSync(cmd) => {
                 cmd.run(&home_dir)?;
@@ -279,6 +283,10 @@ pub(super) enum NeardSubCommand {
     /// Check completeness of dumped state parts of an epoch
     StatePartsDumpCheck(StatePartsDumpCheckCommand),
 
+    /// Tools for the network traffic captured by a node, e.g. replaying it
+    /// against another node.
+    Network(NetworkCommand),
+
     #[cfg(feature = "new_epoch_sync")]
     /// Testing tool for epoch sync
     EpochSync(EpochSyncCommand),
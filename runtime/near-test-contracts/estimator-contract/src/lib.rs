@@ -178,6 +178,15 @@ extern "C" {
     fn storage_read(key_len: u64, key_ptr: u64, register_id: u64) -> u64;
     fn storage_remove(key_len: u64, key_ptr: u64, register_id: u64) -> u64;
     fn storage_has_key(key_len: u64, key_ptr: u64) -> u64;
+    #[cfg(feature = "nightly")]
+    fn storage_range(
+        start_len: u64,
+        start_ptr: u64,
+        end_len: u64,
+        end_ptr: u64,
+        limit: u64,
+        register_id: u64,
+    ) -> u64;
 }
 
 // Function that does not do anything at all.
@@ -849,6 +858,50 @@ storage_bench!(key, 10, value, 10240, 1000, storage_has_key_10b_key_10kib_value_
     storage_has_key(10, key.as_ptr() as _);
 });
 
+// Storage range.
+
+// Function to measure `storage_range_base`.
+// Reads a range with a single entry 1k times.
+#[cfg(feature = "nightly")]
+storage_bench!(key, 10, value, 10, 1000, storage_range_10b_key_10b_value_1k, {
+    let mut end = key;
+    end[9] = 1;
+    storage_range(10, key.as_ptr() as _, 10, end.as_ptr() as _, 1, 0);
+});
+
+// Function to measure `storage_range_base + storage_range_key_byte`.
+// Reads a range with a single entry with 10kib key 1k times.
+#[cfg(feature = "nightly")]
+storage_bench!(key, 10240, value, 10, 1000, storage_range_10kib_key_10b_value_1k, {
+    let mut end = key;
+    end[10239] = 1;
+    storage_range(10240, key.as_ptr() as _, 10240, end.as_ptr() as _, 1, 0);
+});
+
+// Function to measure `storage_range_base + storage_range_value_byte`.
+// Reads a range with a single entry with 10kib value 1k times.
+#[cfg(feature = "nightly")]
+storage_bench!(key, 10, value, 10240, 1000, storage_range_10b_key_10kib_value_1k, {
+    let mut end = key;
+    end[9] = 1;
+    storage_range(10, key.as_ptr() as _, 10, end.as_ptr() as _, 1, 0);
+});
+
+// Function to measure `storage_range_item`.
+// Reads 1k entries written by `storage_write_10b_key_10b_value_1k` in 10 ranges of 100 entries.
+// The first byte of the written keys is `i % 256`, so starting every range 25 values of the
+// first byte after the previous one skips exactly 100 keys.
+#[cfg(feature = "nightly")]
+#[no_mangle]
+pub unsafe fn storage_range_10b_key_10b_value_1k_items() {
+    let end = [u8::MAX; 10];
+    for i in 0..10 {
+        let mut start = [0u8; 10];
+        start[0] = i * 25;
+        storage_range(10, start.as_ptr() as _, 10, end.as_ptr() as _, 100, 0);
+    }
+}
+
 // Function to measure `promise_and_base`.
 #[no_mangle]
 pub unsafe fn promise_and_100k() {
@@ -0,0 +1,34 @@
+#![no_main]
+
+use near_parameters::vm::VMKind;
+use near_parameters::RuntimeConfigStore;
+use near_primitives::version::PROTOCOL_VERSION;
+use near_vm_runner::internal::VMKindExt;
+use near_vm_runner::logic::mocks::mock_external::MockedExternal;
+use near_vm_runner::logic::{ContractPrepareVersion, VMOutcome};
+use near_vm_runner::ContractCode;
+use near_vm_runner_fuzz::{create_context, find_entry_point, ArbitraryModule, ModuleConfigV3};
+
+libfuzzer_sys::fuzz_target!(|module: ArbitraryModule<ModuleConfigV3>| {
+    let code = ContractCode::new(module.0.module.to_bytes(), None);
+    let _result = run_fuzz(&code);
+});
+
+fn run_fuzz(code: &ContractCode) -> VMOutcome {
+    let mut fake_external = MockedExternal::new();
+    let mut context = create_context(vec![]);
+    context.prepaid_gas = 10u64.pow(14);
+    let config_store = RuntimeConfigStore::new(None);
+    let config = config_store.get_config(PROTOCOL_VERSION);
+    let fees = &config.fees;
+    let mut wasm_config = config.wasm_config.clone();
+    wasm_config.limit_config.contract_prepare_version = ContractPrepareVersion::V3;
+    let promise_results = vec![];
+
+    let method_name = find_entry_point(code).unwrap_or_else(|| "main".to_string());
+    VMKind::NearVm
+        .runtime(wasm_config)
+        .unwrap()
+        .run(code, &method_name, &mut fake_external, context, fees, &promise_results, None)
+        .unwrap_or_else(|err| panic!("fatal error: {err:?}"))
+}
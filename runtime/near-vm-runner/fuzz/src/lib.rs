@@ -70,16 +70,43 @@ impl wasm_smith::Config for ModuleConfig {
     }
 }
 
+/// Same as [`ModuleConfig`], but additionally generates the instructions
+/// accepted by [`ContractPrepareVersion::V3`].
+///
+/// Multi-value is not enabled: V3 only accepts multi-value block types, while
+/// wasm-smith would also generate functions with multiple results, which are
+/// rejected during preparation.
+///
+/// [`ContractPrepareVersion::V3`]: near_vm_runner::logic::ContractPrepareVersion::V3
+#[derive(arbitrary::Arbitrary, Debug)]
+pub struct ModuleConfigV3 {}
+
+impl wasm_smith::Config for ModuleConfigV3 {
+    fn available_imports(&self) -> Option<std::borrow::Cow<'_, [u8]>> {
+        Some(near_test_contracts::rs_contract().into())
+    }
+
+    fn bulk_memory_enabled(&self) -> bool {
+        true
+    }
+
+    fn saturating_float_to_int_enabled(&self) -> bool {
+        true
+    }
+}
+
 /// Wrapper to get more useful Debug.
-pub struct ArbitraryModule(pub wasm_smith::ConfiguredModule<ModuleConfig>);
+pub struct ArbitraryModule<C = ModuleConfig>(pub wasm_smith::ConfiguredModule<C>);
 
-impl<'a> arbitrary::Arbitrary<'a> for ArbitraryModule {
+impl<'a, C: wasm_smith::Config + arbitrary::Arbitrary<'a>> arbitrary::Arbitrary<'a>
+    for ArbitraryModule<C>
+{
     fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
-        wasm_smith::ConfiguredModule::<ModuleConfig>::arbitrary(u).map(ArbitraryModule)
+        wasm_smith::ConfiguredModule::<C>::arbitrary(u).map(ArbitraryModule)
     }
 }
 
-impl fmt::Debug for ArbitraryModule {
+impl<C> fmt::Debug for ArbitraryModule<C> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let bytes = self.0.module.to_bytes();
         write!(f, "{:?}", bytes)?;
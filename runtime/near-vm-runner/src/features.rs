@@ -23,6 +23,9 @@ use opts::*;
 #[derive(Clone, Copy, PartialEq, Eq)]
 pub(crate) struct WasmFeatures {
     sign_extension: bool,
+    bulk_memory: bool,
+    multi_value: bool,
+    saturating_float_to_int: bool,
 }
 
 impl From<crate::logic::ContractPrepareVersion> for WasmFeatures {
@@ -31,8 +34,31 @@ impl From<crate::logic::ContractPrepareVersion> for WasmFeatures {
             crate::logic::ContractPrepareVersion::V0 => false,
             crate::logic::ContractPrepareVersion::V1 => false,
             crate::logic::ContractPrepareVersion::V2 => true,
+            crate::logic::ContractPrepareVersion::V3 => true,
         };
-        WasmFeatures { sign_extension }
+        // Proposals that modern toolchains emit by default.
+        let modern = match version {
+            crate::logic::ContractPrepareVersion::V0 => false,
+            crate::logic::ContractPrepareVersion::V1 => false,
+            crate::logic::ContractPrepareVersion::V2 => false,
+            crate::logic::ContractPrepareVersion::V3 => true,
+        };
+        WasmFeatures {
+            sign_extension,
+            bulk_memory: BULK_MEMORY || modern,
+            multi_value: MULTI_VALUE || modern,
+            saturating_float_to_int: SATURATING_FLOAT_TO_INT || modern,
+        }
+    }
+}
+
+impl WasmFeatures {
+    pub(crate) fn bulk_memory(&self) -> bool {
+        self.bulk_memory
+    }
+
+    pub(crate) fn multi_value(&self) -> bool {
+        self.multi_value
     }
 }
 
@@ -43,18 +69,19 @@ impl From<WasmFeatures> for finite_wasm::wasmparser::WasmFeatures {
             floats: true,
             mutable_global: true,
             sign_extension: f.sign_extension,
+            // wasmer singlepass compiler requires multi_value return values to be disabled, so
+            // only NearVM-exclusive preparation versions may enable it.
+            multi_value: f.multi_value,
+            bulk_memory: f.bulk_memory,
+            saturating_float_to_int: f.saturating_float_to_int,
 
             reference_types: REFERENCE_TYPES,
-            // wasmer singlepass compiler requires multi_value return values to be disabled.
-            multi_value: MULTI_VALUE,
-            bulk_memory: BULK_MEMORY,
             simd: SIMD,
             threads: THREADS,
             tail_call: TAIL_CALL,
             multi_memory: MULTI_MEMORY,
             exceptions: EXCEPTIONS,
             memory64: MEMORY64,
-            saturating_float_to_int: SATURATING_FLOAT_TO_INT,
             relaxed_simd: RELAXED_SIMD,
             extended_const: EXTENDED_COST,
             component_model: COMPONENT_MODEL,
@@ -67,7 +94,7 @@ impl From<WasmFeatures> for finite_wasm::wasmparser::WasmFeatures {
 
 #[cfg(feature = "wasmparser")]
 impl From<WasmFeatures> for wasmparser::WasmFeatures {
-    fn from(_: WasmFeatures) -> Self {
+    fn from(f: WasmFeatures) -> Self {
         // /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\
         //
         // There are features that this version of wasmparser enables by default, but pwasm
@@ -83,11 +110,11 @@ impl From<WasmFeatures> for wasmparser::WasmFeatures {
         // /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\ /!\
         wasmparser::WasmFeatures {
             deterministic_only: false,
+            multi_value: f.multi_value,
+            bulk_memory: f.bulk_memory,
 
             module_linking: false, // old version of component model
             reference_types: REFERENCE_TYPES,
-            multi_value: MULTI_VALUE,
-            bulk_memory: BULK_MEMORY,
             simd: SIMD,
             threads: THREADS,
             tail_call: TAIL_CALL,
@@ -104,17 +131,17 @@ impl From<WasmFeatures> for near_vm_types::Features {
         Self {
             mutable_global: true,
             sign_extension: f.sign_extension,
+            bulk_memory: f.bulk_memory,
+            multi_value: f.multi_value,
+            saturating_float_to_int: f.saturating_float_to_int,
 
             threads: THREADS,
             reference_types: REFERENCE_TYPES,
             simd: SIMD,
-            bulk_memory: BULK_MEMORY,
-            multi_value: MULTI_VALUE,
             tail_call: TAIL_CALL,
             multi_memory: MULTI_MEMORY,
             memory64: MEMORY64,
             exceptions: EXCEPTIONS,
-            saturating_float_to_int: SATURATING_FLOAT_TO_INT,
         }
     }
 }
@@ -152,13 +179,13 @@ impl From<WasmFeatures> for wasmer_types::Features {
 
 #[cfg(feature = "wasmtime_vm")]
 impl From<WasmFeatures> for wasmtime::Config {
-    fn from(_: WasmFeatures) -> Self {
+    fn from(f: WasmFeatures) -> Self {
         let mut config = wasmtime::Config::default();
         config.wasm_threads(THREADS);
         config.wasm_reference_types(REFERENCE_TYPES);
         config.wasm_simd(SIMD);
-        config.wasm_bulk_memory(BULK_MEMORY);
-        config.wasm_multi_value(MULTI_VALUE);
+        config.wasm_bulk_memory(f.bulk_memory);
+        config.wasm_multi_value(f.multi_value);
         config.wasm_multi_memory(MULTI_MEMORY);
         config.wasm_memory64(MEMORY64);
         config
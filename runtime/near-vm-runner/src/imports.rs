@@ -267,6 +267,14 @@ imports! {
     storage_iter_prefix<[prefix_len: u64, prefix_ptr: u64] -> [u64]>,
     storage_iter_range<[start_len: u64, start_ptr: u64, end_len: u64, end_ptr: u64] -> [u64]>,
     storage_iter_next<[iterator_id: u64, key_register_id: u64, value_register_id: u64] -> [u64]>,
+    #[storage_range_host_functions] storage_range<[
+        start_len: u64,
+        start_ptr: u64,
+        end_len: u64,
+        end_ptr: u64,
+        limit: u64,
+        register_id: u64
+    ] -> [u64]>,
     // Function for the injected gas counter. Automatically called by the gas meter.
     @as gas: gas_seen_from_wasm<[gas_amount: u32] -> []>,
     // ###############
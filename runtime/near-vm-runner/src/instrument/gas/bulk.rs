@@ -0,0 +1,469 @@
+//! Gas metering of the bulk memory and table operations.
+//!
+//! The gas costs computed by the finite-wasm analysis only depend on the instructions being
+//! executed, while the work done by `memory.copy`, `memory.fill`, `memory.init`, `table.copy`,
+//! `table.fill` and `table.init` is proportional to their length operand. This module charges for
+//! that work in the Wasm code itself, so that it is charged the same way by every runtime.
+
+use crate::logic::errors::PrepareError;
+use finite_wasm::wasmparser as wp;
+use std::ops::Range;
+use wasm_encoder::{Encode, Instruction, Section, SectionId, ValType};
+
+/// A range of the input module to replace with the given bytes.
+type Patch = (Range<usize>, Vec<u8>);
+
+#[derive(Clone, Copy)]
+enum BulkOperation {
+    Memory,
+    Table,
+}
+
+impl BulkOperation {
+    fn of(operator: &wp::Operator) -> Option<BulkOperation> {
+        match operator {
+            wp::Operator::MemoryInit { .. }
+            | wp::Operator::MemoryCopy { .. }
+            | wp::Operator::MemoryFill { .. } => Some(BulkOperation::Memory),
+            wp::Operator::TableInit { .. }
+            | wp::Operator::TableCopy { .. }
+            | wp::Operator::TableFill { .. } => Some(BulkOperation::Table),
+            _ => None,
+        }
+    }
+}
+
+/// Counts of the module entities which are shifted by the instrumentation.
+#[derive(Default)]
+struct Layout {
+    types: u32,
+    imported_functions: u32,
+    functions: u32,
+    has_bulk_operations: bool,
+}
+
+impl Layout {
+    fn of(code: &[u8]) -> Result<Layout, wp::BinaryReaderError> {
+        let mut layout = Layout::default();
+        for payload in wp::Parser::new(0).parse_all(code) {
+            match payload? {
+                wp::Payload::TypeSection(reader) => layout.types = reader.count(),
+                wp::Payload::ImportSection(reader) => {
+                    for import in reader {
+                        if let wp::TypeRef::Func(_) = import?.ty {
+                            layout.imported_functions += 1;
+                        }
+                    }
+                }
+                wp::Payload::FunctionSection(reader) => layout.functions = reader.count(),
+                wp::Payload::CodeSectionEntry(body) => {
+                    let mut operators = body.get_operators_reader()?;
+                    while !layout.has_bulk_operations && !operators.eof() {
+                        layout.has_bulk_operations =
+                            BulkOperation::of(&operators.read()?).is_some();
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(layout)
+    }
+}
+
+struct BulkGasCounter<'a> {
+    code: &'a [u8],
+    /// Gas charged for every 8 bytes of memory or every table element.
+    word_cost: u32,
+    /// Index of the imported gas function. Every function from this index on is shifted by one.
+    gas_func: u32,
+    /// Index of the generated function charging for bulk memory operations.
+    memory_charge_func: u32,
+    /// Index of the generated function charging for bulk table operations.
+    table_charge_func: u32,
+}
+
+impl BulkGasCounter<'_> {
+    fn func_index(&self, index: u32) -> u32 {
+        if index < self.gas_func {
+            index
+        } else {
+            index + 1
+        }
+    }
+
+    /// Replaces the function index encoded at `offset`.
+    fn func_index_patch(&self, offset: usize, index: u32) -> Result<Patch, PrepareError> {
+        let mut reader = wp::BinaryReader::new_with_offset(&self.code[offset..], offset);
+        reader.read_var_u32().map_err(invalid)?;
+        let mut bytes = Vec::new();
+        self.func_index(index).encode(&mut bytes);
+        Ok((offset..reader.original_position(), bytes))
+    }
+
+    /// Updates the function indices used by the operators and inserts a call to the charging
+    /// function before every bulk operation.
+    ///
+    /// The charging functions take the length operand from the top of the stack and put it back,
+    /// so the bulk operations are executed with the same operands.
+    fn patch_operators(
+        &self,
+        mut operators: wp::OperatorsReader<'_>,
+        patches: &mut Vec<Patch>,
+    ) -> Result<(), PrepareError> {
+        while !operators.eof() {
+            let (operator, offset) = operators.read_with_offset().map_err(invalid)?;
+            match operator {
+                // Both opcodes take a single byte.
+                wp::Operator::Call { function_index }
+                | wp::Operator::RefFunc { function_index } => {
+                    patches.push(self.func_index_patch(offset + 1, function_index)?);
+                }
+                operator => {
+                    let charge_func = match BulkOperation::of(&operator) {
+                        None => continue,
+                        Some(BulkOperation::Memory) => self.memory_charge_func,
+                        Some(BulkOperation::Table) => self.table_charge_func,
+                    };
+                    let mut bytes = Vec::new();
+                    Instruction::Call(charge_func).encode(&mut bytes);
+                    patches.push((offset..offset, bytes));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Function charging `word_cost` for every `1 << units_per_word_log2` units (rounded up) of
+    /// its only parameter and returning it.
+    fn charge_func(&self, units_per_word_log2: i64) -> wasm_encoder::Function {
+        let mut func = wasm_encoder::Function::new(Vec::new());
+        func.instruction(&Instruction::LocalGet(0));
+        func.instruction(&Instruction::I64ExtendI32U);
+        if units_per_word_log2 > 0 {
+            func.instruction(&Instruction::I64Const((1 << units_per_word_log2) - 1));
+            func.instruction(&Instruction::I64Add);
+            func.instruction(&Instruction::I64Const(units_per_word_log2));
+            func.instruction(&Instruction::I64ShrU);
+        }
+        // Neither the rounding nor the product can overflow, as both the length and the cost
+        // are 32-bit integers.
+        func.instruction(&Instruction::I64Const(i64::from(self.word_cost)));
+        func.instruction(&Instruction::I64Mul);
+        func.instruction(&Instruction::Call(self.gas_func));
+        func.instruction(&Instruction::LocalGet(0));
+        func.instruction(&Instruction::End);
+        func
+    }
+}
+
+/// Instruments the module to charge `word_cost` gas for every 8 bytes (rounded up) processed by a
+/// bulk memory operation and for every element processed by a bulk table operation.
+///
+/// The gas is charged before the operation, by calling a function added to the module, which
+/// passes the amount to the `internal.finite_wasm_gas` import. Modules without bulk operations are
+/// returned unchanged.
+///
+/// The module must already be validated and have an import section, as is the case after the
+/// early preparation.
+pub(crate) fn inject_bulk_gas_counter(
+    code: &[u8],
+    word_cost: u32,
+) -> Result<Vec<u8>, PrepareError> {
+    let layout = Layout::of(code).map_err(invalid)?;
+    if !layout.has_bulk_operations {
+        return Ok(code.to_vec());
+    }
+    let gas_type = layout.types;
+    let charge_type = layout.types + 1;
+    // The gas function is imported after all the other functions, so only the functions defined
+    // in the module are shifted. The charging functions are defined after all of them.
+    let gas_func = layout.imported_functions;
+    let memory_charge_func = gas_func + 1 + layout.functions;
+    let counter = BulkGasCounter {
+        code,
+        word_cost,
+        gas_func,
+        memory_charge_func,
+        table_charge_func: memory_charge_func + 1,
+    };
+
+    let mut output = Vec::with_capacity(code.len());
+    // The code section being rebuilt and the number of its entries still to be read.
+    let mut code_section: Option<(Vec<u8>, u32)> = None;
+    for payload in wp::Parser::new(0).parse_all(code) {
+        match payload.map_err(invalid)? {
+            wp::Payload::Version { range, .. } => output.extend_from_slice(&code[range]),
+            wp::Payload::End(_) => {}
+            wp::Payload::TypeSection(reader) => {
+                let mut section = with_count(code, reader.range(), layout.types + 2)?;
+                let types: [(&[ValType], &[ValType]); 2] =
+                    [(&[ValType::I64], &[]), (&[ValType::I32], &[ValType::I32])];
+                for (params, results) in types {
+                    section.push(0x60);
+                    params.encode(&mut section);
+                    results.encode(&mut section);
+                }
+                append_section(&mut output, SectionId::Type, &section);
+            }
+            wp::Payload::ImportSection(reader) => {
+                let mut section = wasm_encoder::ImportSection::new();
+                for import in reader {
+                    let import = import.map_err(invalid)?;
+                    let ty = match import.ty {
+                        wp::TypeRef::Func(ty) => wasm_encoder::EntityType::Function(ty),
+                        wp::TypeRef::Memory(ty) => {
+                            wasm_encoder::EntityType::Memory(wasm_encoder::MemoryType {
+                                minimum: ty.initial,
+                                maximum: ty.maximum,
+                                memory64: ty.memory64,
+                                shared: ty.shared,
+                            })
+                        }
+                        // The early preparation only leaves function and memory imports.
+                        wp::TypeRef::Table(_) | wp::TypeRef::Global(_) | wp::TypeRef::Tag(_) => {
+                            return Err(PrepareError::GasInstrumentation);
+                        }
+                    };
+                    section.import(import.module, import.name, ty);
+                }
+                section.import(
+                    "internal",
+                    "finite_wasm_gas",
+                    wasm_encoder::EntityType::Function(gas_type),
+                );
+                section.append_to(&mut output);
+            }
+            wp::Payload::FunctionSection(reader) => {
+                let mut section = with_count(code, reader.range(), layout.functions + 2)?;
+                charge_type.encode(&mut section);
+                charge_type.encode(&mut section);
+                append_section(&mut output, SectionId::Function, &section);
+            }
+            wp::Payload::GlobalSection(reader) => {
+                let mut patches = Vec::new();
+                for global in reader.clone() {
+                    let init_expr = global.map_err(invalid)?.init_expr;
+                    counter.patch_operators(init_expr.get_operators_reader(), &mut patches)?;
+                }
+                let section = patch(code, reader.range(), &patches);
+                append_section(&mut output, SectionId::Global, &section);
+            }
+            wp::Payload::ExportSection(reader) => {
+                let mut section = wasm_encoder::ExportSection::new();
+                for export in reader {
+                    let export = export.map_err(invalid)?;
+                    let (kind, index) = match export.kind {
+                        wp::ExternalKind::Func => {
+                            (wasm_encoder::ExportKind::Func, counter.func_index(export.index))
+                        }
+                        wp::ExternalKind::Table => (wasm_encoder::ExportKind::Table, export.index),
+                        wp::ExternalKind::Memory => {
+                            (wasm_encoder::ExportKind::Memory, export.index)
+                        }
+                        wp::ExternalKind::Global => {
+                            (wasm_encoder::ExportKind::Global, export.index)
+                        }
+                        wp::ExternalKind::Tag => (wasm_encoder::ExportKind::Tag, export.index),
+                    };
+                    section.export(export.name, kind, index);
+                }
+                section.append_to(&mut output);
+            }
+            wp::Payload::StartSection { func, .. } => {
+                wasm_encoder::StartSection { function_index: counter.func_index(func) }
+                    .append_to(&mut output);
+            }
+            wp::Payload::ElementSection(reader) => {
+                let mut patches = Vec::new();
+                for element in reader.clone() {
+                    match element.map_err(invalid)?.items {
+                        wp::ElementItems::Functions(funcs) => {
+                            for func in funcs.into_iter_with_offsets() {
+                                let (offset, index) = func.map_err(invalid)?;
+                                patches.push(counter.func_index_patch(offset, index)?);
+                            }
+                        }
+                        wp::ElementItems::Expressions(exprs) => {
+                            for expr in exprs {
+                                let operators = expr.map_err(invalid)?.get_operators_reader();
+                                counter.patch_operators(operators, &mut patches)?;
+                            }
+                        }
+                    }
+                }
+                let section = patch(code, reader.range(), &patches);
+                append_section(&mut output, SectionId::Element, &section);
+            }
+            wp::Payload::CodeSectionStart { count, .. } => {
+                let mut section = Vec::new();
+                (count + 2).encode(&mut section);
+                code_section = Some((section, count));
+            }
+            wp::Payload::CodeSectionEntry(body) => {
+                let (section, remaining) =
+                    code_section.as_mut().ok_or(PrepareError::GasInstrumentation)?;
+                let mut patches = Vec::new();
+                let operators = body.get_operators_reader().map_err(invalid)?;
+                counter.patch_operators(operators, &mut patches)?;
+                let body = patch(code, body.range(), &patches);
+                body.len().encode(section);
+                section.extend_from_slice(&body);
+                *remaining -= 1;
+                if *remaining == 0 {
+                    counter.charge_func(3).encode(section);
+                    counter.charge_func(0).encode(section);
+                    append_section(&mut output, SectionId::Code, section);
+                    code_section = None;
+                }
+            }
+            // Function names in the `name` section are not updated, they are only used for
+            // debugging.
+            wp::Payload::CustomSection(reader) => {
+                append_section(&mut output, SectionId::Custom, &code[reader.range()]);
+            }
+            wp::Payload::TableSection(reader) => {
+                append_section(&mut output, SectionId::Table, &code[reader.range()]);
+            }
+            wp::Payload::MemorySection(reader) => {
+                append_section(&mut output, SectionId::Memory, &code[reader.range()]);
+            }
+            wp::Payload::DataCountSection { range, .. } => {
+                append_section(&mut output, SectionId::DataCount, &code[range]);
+            }
+            wp::Payload::DataSection(reader) => {
+                append_section(&mut output, SectionId::Data, &code[reader.range()]);
+            }
+            // The early preparation rejects all the other sections.
+            _ => return Err(PrepareError::GasInstrumentation),
+        }
+    }
+    Ok(output)
+}
+
+fn invalid(_: wp::BinaryReaderError) -> PrepareError {
+    PrepareError::GasInstrumentation
+}
+
+/// Copies the `range` of the input module, with its leading count replaced by `count`.
+fn with_count(code: &[u8], range: Range<usize>, count: u32) -> Result<Vec<u8>, PrepareError> {
+    let mut reader = wp::BinaryReader::new_with_offset(&code[range.clone()], range.start);
+    reader.read_var_u32().map_err(invalid)?;
+    let mut section = Vec::new();
+    count.encode(&mut section);
+    section.extend_from_slice(&code[reader.original_position()..range.end]);
+    Ok(section)
+}
+
+/// Copies the `range` of the input module with the given patches, which must be ordered.
+fn patch(code: &[u8], range: Range<usize>, patches: &[Patch]) -> Vec<u8> {
+    let mut output = Vec::with_capacity(range.len());
+    let mut position = range.start;
+    for (patched, bytes) in patches {
+        output.extend_from_slice(&code[position..patched.start]);
+        output.extend_from_slice(bytes);
+        position = patched.end;
+    }
+    output.extend_from_slice(&code[position..range.end]);
+    output
+}
+
+fn append_section(output: &mut Vec<u8>, id: SectionId, section: &[u8]) {
+    id.encode(output);
+    section.len().encode(output);
+    output.extend_from_slice(section);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::inject_bulk_gas_counter;
+    use crate::logic::ContractPrepareVersion;
+    use finite_wasm::wasmparser as wp;
+
+    fn validate(code: &[u8]) {
+        let features = crate::features::WasmFeatures::from(ContractPrepareVersion::V3);
+        wp::Validator::new_with_features(features.into()).validate_all(code).unwrap();
+    }
+
+    #[test]
+    fn test_unchanged_without_bulk_operations() {
+        let code = wat::parse_str(
+            r#"(module (import "env" "memory" (memory 1)) (func (export "main") (nop)))"#,
+        )
+        .unwrap();
+        assert_eq!(inject_bulk_gas_counter(&code, 1).unwrap(), code);
+    }
+
+    #[test]
+    fn test_function_indices_are_shifted() {
+        let code = wat::parse_str(
+            r#"
+(module
+  (import "env" "input" (func $input (param i64)))
+  (import "env" "memory" (memory 1))
+  (table 2 funcref)
+  (elem (i32.const 0) $f $main)
+  (start $f)
+  (func $f (call $input (i64.const 0)))
+  (func $main (export "main")
+    (call $f)
+    (memory.fill (i32.const 0) (i32.const 0) (i32.const 16))
+    (table.copy (i32.const 0) (i32.const 1) (i32.const 1))))
+"#,
+        )
+        .unwrap();
+        let instrumented = inject_bulk_gas_counter(&code, 1).unwrap();
+        validate(&instrumented);
+
+        let mut imports = vec![];
+        let mut calls = vec![];
+        for payload in wp::Parser::new(0).parse_all(&instrumented) {
+            match payload.unwrap() {
+                wp::Payload::ImportSection(reader) => {
+                    for import in reader {
+                        let import = import.unwrap();
+                        imports.push(format!("{}.{}", import.module, import.name));
+                    }
+                }
+                wp::Payload::StartSection { func, .. } => assert_eq!(func, 2),
+                wp::Payload::ExportSection(reader) => {
+                    let export = reader.into_iter().next().unwrap().unwrap();
+                    assert_eq!((export.name, export.index), ("main", 3));
+                }
+                wp::Payload::ElementSection(reader) => {
+                    let element = reader.into_iter().next().unwrap().unwrap();
+                    let wp::ElementItems::Functions(funcs) = element.items else {
+                        panic!("expected function indices");
+                    };
+                    let funcs: Vec<u32> = funcs.into_iter().map(Result::unwrap).collect();
+                    assert_eq!(funcs, [2, 3]);
+                }
+                wp::Payload::CodeSectionEntry(body) => {
+                    let mut operators = body.get_operators_reader().unwrap();
+                    let mut previous = None;
+                    while !operators.eof() {
+                        let operator = operators.read().unwrap();
+                        match (&previous, &operator) {
+                            (Some(func), wp::Operator::MemoryFill { .. }) => {
+                                calls.push(format!("{func} memory.fill"))
+                            }
+                            (Some(func), wp::Operator::TableCopy { .. }) => {
+                                calls.push(format!("{func} table.copy"))
+                            }
+                            (Some(func), _) => calls.push(format!("{func}")),
+                            (None, _) => {}
+                        }
+                        previous = match operator {
+                            wp::Operator::Call { function_index } => Some(function_index),
+                            _ => None,
+                        };
+                    }
+                }
+                _ => {}
+            }
+        }
+        assert_eq!(imports, ["env.input", "env.memory", "internal.finite_wasm_gas"]);
+        // `$f` calls `$input`, `$main` calls `$f`, the charging functions and the bulk
+        // operations, and the charging functions call the gas function.
+        assert_eq!(calls, ["0", "2", "4 memory.fill", "5 table.copy", "1", "1"]);
+    }
+}
@@ -4,6 +4,7 @@
 //! module into one that charges gas for code to be executed. See function documentation for usage
 //! and details.
 
+pub(crate) mod bulk;
 #[cfg(test)]
 mod validation;
 
@@ -10,6 +10,7 @@ mod instrument;
 pub mod logic;
 #[cfg(all(feature = "wasmer0_vm", target_arch = "x86_64"))]
 mod memory;
+mod metrics;
 #[cfg(all(feature = "near_vm", target_arch = "x86_64"))]
 mod near_vm_runner;
 #[cfg(feature = "prepare")]
@@ -29,6 +30,7 @@ mod wasmtime_runner;
 pub use crate::logic::with_ext_cost_counter;
 pub use cache::{get_contract_cache_key, precompile_contract, MockCompiledContractCache};
 pub use code::ContractCode;
+pub use metrics::{report_metrics, reset_metrics, Metrics};
 pub use profile::ProfileDataV3;
 pub use runner::{run, VM};
 
@@ -255,6 +255,43 @@ pub trait External {
     /// ```
     fn storage_has_key(&mut self, key: &[u8], mode: StorageGetMode) -> Result<bool>;
 
+    /// Visits key-value pairs with keys in `[start, end)` range of the storage trie associated
+    /// with the current account, in ascending key order.  If `end` is `None`, the range is
+    /// unbounded from above.
+    ///
+    /// `visit` is called for every entry and returns whether the iteration should continue.
+    /// Entries are read lazily, so stopping early doesn’t require reading the rest of the range.
+    ///
+    /// # Errors
+    ///
+    /// This function could return [`near_vm_runner::logic::VMError`] or any error returned by
+    /// `visit`.
+    ///
+    /// # Example
+    /// ```
+    /// # use near_vm_runner::logic::mocks::mock_external::MockedExternal;
+    /// # use near_vm_runner::logic::External;
+    ///
+    /// # let mut external = MockedExternal::new();
+    /// external.storage_set(b"key1", b"value1").unwrap();
+    /// external.storage_set(b"key2", b"value2").unwrap();
+    /// external.storage_set(b"key3", b"value3").unwrap();
+    /// let mut keys = Vec::new();
+    /// external
+    ///     .storage_range(b"key2", None, &mut |key, _value| {
+    ///         keys.push(key.to_vec());
+    ///         Ok(true)
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(keys, vec![b"key2".to_vec(), b"key3".to_vec()]);
+    /// ```
+    fn storage_range(
+        &mut self,
+        start: &[u8],
+        end: Option<&[u8]>,
+        visit: &mut dyn FnMut(&[u8], &[u8]) -> Result<bool>,
+    ) -> Result<()>;
+
     fn generate_data_id(&mut self) -> CryptoHash;
 
     /// Returns amount of touched trie nodes by storage operations
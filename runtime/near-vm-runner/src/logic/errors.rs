@@ -136,6 +136,8 @@ pub enum PrepareError {
     TooManyFunctions,
     /// Contract contains too many locals.
     TooManyLocals,
+    /// Contract contains a function type with more than one result, which is not supported yet.
+    MultiValueResult,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, strum::IntoStaticStr)]
@@ -290,6 +292,7 @@ impl fmt::Display for PrepareError {
             Memory => "Error creating memory.",
             TooManyFunctions => "Too many functions in contract.",
             TooManyLocals => "Too many locals declared in the contract.",
+            MultiValueResult => "Functions with multiple results are not supported.",
         })
     }
 }
@@ -2915,6 +2915,90 @@ impl<'a> VMLogic<'a> {
         }))
     }
 
+    /// Reads up to `limit` key-value pairs with keys in `[start, end)` range from the storage of
+    /// the current account, in ascending key order, and writes them into `register_id` as a
+    /// borsh-serialized `Vec<(Vec<u8>, Vec<u8>)>`.  An empty `end` means the range is unbounded
+    /// from above, while a non-empty `end` which is not greater than `start` gives an empty range.
+    /// Returns the number of entries written, which is never larger than
+    /// `max_storage_range_items`.  The register is written even if no entries are found.
+    ///
+    /// To continue the scan, call the function again with `start` set to the last returned key
+    /// followed by a zero byte.
+    ///
+    /// # Errors
+    ///
+    /// * If `start_len + start_ptr` or `end_len + end_ptr` exceeds the memory container or points
+    ///   to an unused register it returns `MemoryAccessViolation`.
+    /// * If the length of `start` or `end` exceeds `max_length_storage_key` returns
+    ///   `KeyLengthExceeded`.
+    /// * If the registers exceed the memory limit returns `MemoryAccessViolation`.
+    ///
+    /// # Cost
+    ///
+    /// `base + storage_range_base + cost of reading start and end + storage_range_item * num_steps
+    ///  + (storage_range_key_byte * num_key_bytes + storage_range_value_byte * num_value_bytes)
+    ///  for each returned entry + cost of writing the result into the register`.
+    ///
+    /// Every step of the scan is paid for before it is made, so `num_steps` is the number of
+    /// returned entries, plus one if the scan reached the end of the range before the limit.
+    /// The entries are read from flat storage where available, so the cost of a step doesn't
+    /// depend on the trie nodes visited and is the same on chunk producers and validators.
+    pub fn storage_range(
+        &mut self,
+        start_len: u64,
+        start_ptr: u64,
+        end_len: u64,
+        end_ptr: u64,
+        limit: u64,
+        register_id: u64,
+    ) -> Result<u64> {
+        self.gas_counter.pay_base(base)?;
+        self.gas_counter.pay_base(storage_range_base)?;
+        let start = get_memory_or_register!(self, start_ptr, start_len)?;
+        let end = get_memory_or_register!(self, end_ptr, end_len)?;
+        let max_length_storage_key = self.config.limit_config.max_length_storage_key;
+        for key in [&start, &end] {
+            if key.len() as u64 > max_length_storage_key {
+                return Err(HostError::KeyLengthExceeded {
+                    length: key.len() as u64,
+                    limit: max_length_storage_key,
+                }
+                .into());
+            }
+        }
+        let limit = limit.min(self.config.limit_config.max_storage_range_items);
+        let mut entries: Vec<(Vec<u8>, Vec<u8>)> = Vec::new();
+        let end = if end.is_empty() { None } else { Some(end.as_ref()) };
+        let is_empty_range = end.map_or(false, |end| end <= start.as_ref());
+        if limit > 0 && !is_empty_range {
+            let gas_counter = &mut self.gas_counter;
+            gas_counter.pay_base(storage_range_item)?;
+            self.ext.storage_range(&start, end, &mut |key, value| {
+                gas_counter.pay_per(storage_range_key_byte, key.len() as u64)?;
+                gas_counter.pay_per(storage_range_value_byte, value.len() as u64)?;
+                entries.push((key.to_vec(), value.to_vec()));
+                let more = (entries.len() as u64) < limit;
+                if more {
+                    gas_counter.pay_base(storage_range_item)?;
+                }
+                Ok(more)
+            })?;
+        }
+
+        #[cfg(feature = "io_trace")]
+        tracing::trace!(
+            target = "io_tracer",
+            storage_op = "range",
+            start = base64(&start),
+            count = entries.len(),
+        );
+
+        let count = entries.len() as u64;
+        let data = borsh::to_vec(&entries).expect("serializing into a vector can't fail");
+        self.registers.set(&mut self.gas_counter, &self.config.limit_config, register_id, data)?;
+        Ok(count)
+    }
+
     /// Computes the outcome of the execution.
     ///
     /// If `FunctionCallWeight` protocol feature (127) is enabled, unused gas will be
@@ -138,6 +138,28 @@ impl External for MockedExternal {
         Ok(self.fake_trie.contains_key(key))
     }
 
+    fn storage_range(
+        &mut self,
+        start: &[u8],
+        end: Option<&[u8]>,
+        visit: &mut dyn FnMut(&[u8], &[u8]) -> Result<bool>,
+    ) -> Result<()> {
+        let mut entries: Vec<_> = self
+            .fake_trie
+            .iter()
+            .filter(|(key, _)| {
+                key.as_slice() >= start && end.map_or(true, |end| key.as_slice() < end)
+            })
+            .collect();
+        entries.sort();
+        for (key, value) in entries {
+            if !visit(key, value)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
     fn generate_data_id(&mut self) -> CryptoHash {
         // Generates some hash for the data ID to receive data. This hash should not be functionally
         // used in any mocked contexts.
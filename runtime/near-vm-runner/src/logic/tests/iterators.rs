@@ -1,5 +1,8 @@
+use crate::logic::tests::helpers::*;
 use crate::logic::tests::vm_logic_builder::VMLogicBuilder;
-use crate::logic::{HostError, VMLogicError};
+use crate::logic::{External, HostError, VMLogicError};
+use crate::map;
+use near_parameters::ExtCosts;
 
 #[test]
 fn test_iterator_deprecated() {
@@ -24,3 +27,132 @@ fn test_iterator_deprecated() {
         logic.storage_iter_next(0, 0, 1)
     );
 }
+
+fn range_builder() -> VMLogicBuilder {
+    let mut logic_builder = VMLogicBuilder::default();
+    for key in [b"a".as_slice(), b"b", b"ba", b"c", b"d"] {
+        logic_builder.ext.storage_set(key, &[key, b"-value"].concat()).unwrap();
+    }
+    logic_builder
+}
+
+fn entries(keys: &[&[u8]]) -> Vec<u8> {
+    let entries: Vec<(Vec<u8>, Vec<u8>)> =
+        keys.iter().map(|&key| (key.to_vec(), [key, b"-value"].concat())).collect();
+    borsh::to_vec(&entries).unwrap()
+}
+
+#[test]
+fn test_storage_range() {
+    let mut logic_builder = range_builder();
+    let mut logic = logic_builder.build();
+
+    let start = logic.internal_mem_write(b"b");
+    let end = logic.internal_mem_write(b"d");
+    reset_costs_counter();
+    assert_eq!(logic.storage_range(start.len, start.ptr, end.len, end.ptr, 10, 0), Ok(3));
+    let want = entries(&[b"b", b"ba", b"c"]);
+    assert_costs(map! {
+        ExtCosts::base: 1,
+        ExtCosts::storage_range_base: 1,
+        ExtCosts::read_memory_base: 2,
+        ExtCosts::read_memory_byte: 2,
+        // Three steps returning entries and one reaching the end of the range.
+        ExtCosts::storage_range_item: 4,
+        ExtCosts::storage_range_key_byte: 4,
+        ExtCosts::storage_range_value_byte: 4 + 3 * 6,
+        ExtCosts::write_register_base: 1,
+        ExtCosts::write_register_byte: want.len() as u64,
+    });
+    logic.assert_read_register(&want, 0);
+
+    // Empty `end` means the range is unbounded.
+    let start = logic.internal_mem_write(b"bb");
+    assert_eq!(logic.storage_range(start.len, start.ptr, 0, 0, 10, 0), Ok(2));
+    logic.assert_read_register(&entries(&[b"c", b"d"]), 0);
+
+    // Nothing in range still writes an empty list into the register.
+    let start = logic.internal_mem_write(b"e");
+    assert_eq!(logic.storage_range(start.len, start.ptr, 0, 0, 10, 0), Ok(0));
+    logic.assert_read_register(&entries(&[]), 0);
+}
+
+#[test]
+fn test_storage_range_limit() {
+    let mut logic_builder = range_builder();
+    logic_builder.config.limit_config.max_storage_range_items = 2;
+    let mut logic = logic_builder.build();
+
+    let start = logic.internal_mem_write(b"");
+    reset_costs_counter();
+    assert_eq!(logic.storage_range(start.len, start.ptr, 0, 0, 1, 0), Ok(1));
+    // No step is paid for after the limit is reached.
+    assert_costs(map! {
+        ExtCosts::base: 1,
+        ExtCosts::storage_range_base: 1,
+        ExtCosts::read_memory_base: 2,
+        ExtCosts::read_memory_byte: 0,
+        ExtCosts::storage_range_item: 1,
+        ExtCosts::storage_range_key_byte: 1,
+        ExtCosts::storage_range_value_byte: 7,
+        ExtCosts::write_register_base: 1,
+        ExtCosts::write_register_byte: entries(&[b"a"]).len() as u64,
+    });
+    logic.assert_read_register(&entries(&[b"a"]), 0);
+
+    // The per-call limit is capped by `max_storage_range_items`.
+    assert_eq!(logic.storage_range(start.len, start.ptr, 0, 0, u64::MAX, 0), Ok(2));
+    logic.assert_read_register(&entries(&[b"a", b"b"]), 0);
+
+    // Continue the scan after the last returned key.
+    let start = logic.internal_mem_write(b"b\0");
+    assert_eq!(logic.storage_range(start.len, start.ptr, 0, 0, u64::MAX, 0), Ok(2));
+    logic.assert_read_register(&entries(&[b"ba", b"c"]), 0);
+
+    reset_costs_counter();
+    assert_eq!(logic.storage_range(start.len, start.ptr, 0, 0, 0, 0), Ok(0));
+    assert_costs(map! {
+        ExtCosts::base: 1,
+        ExtCosts::storage_range_base: 1,
+        ExtCosts::read_memory_base: 2,
+        ExtCosts::read_memory_byte: 2,
+        ExtCosts::write_register_base: 1,
+        ExtCosts::write_register_byte: 4,
+    });
+}
+
+#[test]
+fn test_storage_range_empty_bounds() {
+    let mut logic_builder = range_builder();
+    let mut logic = logic_builder.build();
+
+    for (start, end) in [(b"b".as_slice(), b"b".as_slice()), (b"d", b"a"), (b"ba", b"b")] {
+        let start = logic.internal_mem_write(start);
+        let end = logic.internal_mem_write(end);
+        reset_costs_counter();
+        assert_eq!(logic.storage_range(start.len, start.ptr, end.len, end.ptr, 10, 0), Ok(0));
+        assert_costs(map! {
+            ExtCosts::base: 1,
+            ExtCosts::storage_range_base: 1,
+            ExtCosts::read_memory_base: 2,
+            ExtCosts::read_memory_byte: start.len + end.len,
+            ExtCosts::write_register_base: 1,
+            ExtCosts::write_register_byte: 4,
+        });
+        logic.assert_read_register(&entries(&[]), 0);
+    }
+}
+
+#[test]
+fn test_storage_range_key_length_exceeded() {
+    let mut logic_builder = range_builder();
+    logic_builder.config.limit_config.max_length_storage_key = 1;
+    let mut logic = logic_builder.build();
+
+    let start = logic.internal_mem_write(b"a");
+    let end = logic.internal_mem_write(b"ba");
+    assert_eq!(
+        logic.storage_range(start.len, start.ptr, end.len, end.ptr, 10, 0),
+        Err(HostError::KeyLengthExceeded { length: 2, limit: 1 }.into())
+    );
+}
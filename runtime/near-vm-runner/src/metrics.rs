@@ -0,0 +1,52 @@
+//! Contract loading statistics collected on the current thread.
+//!
+//! The runtime resets them before processing a receipt and reads them back afterwards, so that
+//! the time spent compiling contracts and the compiled contract cache hits can be attributed to
+//! individual receipts.
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+/// Contract loading statistics collected on the current thread since the last
+/// [`reset_metrics`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Metrics {
+    /// Time spent compiling contracts which were not found in the compiled contract cache.
+    pub compilation_time: Duration,
+    /// Number of lookups in the compiled contract cache.
+    pub compiled_contract_cache_lookups: u64,
+    /// Number of lookups which found the contract in the compiled contract cache.
+    pub compiled_contract_cache_hits: u64,
+}
+
+thread_local! {
+    static METRICS: RefCell<Metrics> = Default::default();
+}
+
+/// Clears the statistics collected on the current thread.
+pub fn reset_metrics() {
+    METRICS.with(|metrics| *metrics.borrow_mut() = Metrics::default());
+}
+
+/// Returns the statistics collected on the current thread since the last [`reset_metrics`].
+pub fn report_metrics() -> Metrics {
+    METRICS.with(|metrics| *metrics.borrow())
+}
+
+/// Runs `compile` and accounts the time it took as compilation time.
+pub(crate) fn record_compilation<T>(compile: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = compile();
+    let elapsed = start.elapsed();
+    METRICS.with(|metrics| metrics.borrow_mut().compilation_time += elapsed);
+    result
+}
+
+pub(crate) fn record_compiled_contract_cache_lookup(hit: bool) {
+    METRICS.with(|metrics| {
+        let mut metrics = metrics.borrow_mut();
+        metrics.compiled_contract_cache_lookups += 1;
+        if hit {
+            metrics.compiled_contract_cache_hits += 1;
+        }
+    });
+}
@@ -322,7 +322,8 @@ impl NearVM {
         code: &ContractCode,
         cache: Option<&dyn CompiledContractCache>,
     ) -> Result<Result<UniversalExecutable, CompilationError>, CacheError> {
-        let executable_or_error = self.compile_uncached(code);
+        let executable_or_error =
+            crate::metrics::record_compilation(|| self.compile_uncached(code));
         let key = get_contract_cache_key(code, &self.config);
 
         if let Some(cache) = cache {
@@ -357,6 +358,9 @@ impl NearVM {
             let _span = tracing::debug_span!(target:"vm", "NearVM::read_cache_record").entered();
             cache.map(|cache| cache.get(&key)).transpose().map_err(CacheError::ReadError)?.flatten()
         };
+        if cache.is_some() {
+            crate::metrics::record_compiled_contract_cache_lookup(cache_record.is_some());
+        }
 
         let stored_artifact: Option<VMArtifact> = match cache_record {
             None => None,
@@ -12,6 +12,10 @@ struct PrepareContext<'a> {
     validator: wp::Validator,
     func_validator_allocations: wp::FuncValidatorAllocations,
     before_import_section: bool,
+    /// Whether the multi-value proposal is enabled.
+    multi_value: bool,
+    /// For each type in the type section, whether it is a function type with multiple results.
+    multi_result_types: Vec<bool>,
 }
 
 impl<'a> PrepareContext<'a> {
@@ -28,6 +32,8 @@ impl<'a> PrepareContext<'a> {
             validator: wp::Validator::new_with_features(features.into()),
             func_validator_allocations: wp::FuncValidatorAllocations::default(),
             before_import_section: true,
+            multi_value: features.multi_value(),
+            multi_result_types: Vec::new(),
         }
     }
 
@@ -60,6 +66,13 @@ impl<'a> PrepareContext<'a> {
                     self.validator
                         .type_section(&reader)
                         .map_err(|_| PrepareError::Deserialization)?;
+                    for ty in reader.clone() {
+                        let ty = ty.map_err(|_| PrepareError::Deserialization)?;
+                        let multi_result = match ty {
+                            wp::Type::Func(func_type) => func_type.results().len() > 1,
+                        };
+                        self.multi_result_types.push(multi_result);
+                    }
                     self.copy_section(SectionId::Type, reader.range())?;
                 }
 
@@ -76,6 +89,10 @@ impl<'a> PrepareContext<'a> {
                     self.validator
                         .function_section(&reader)
                         .map_err(|_| PrepareError::Deserialization)?;
+                    for type_index in reader.clone() {
+                        let type_index = type_index.map_err(|_| PrepareError::Deserialization)?;
+                        self.ensure_single_result(type_index)?;
+                    }
                     self.copy_section(SectionId::Function, reader.range())?;
                 }
                 wp::Payload::TableSection(reader) => {
@@ -171,6 +188,18 @@ impl<'a> PrepareContext<'a> {
                     let mut func_validator = func_validator.into_validator(allocs);
                     func_validator.validate(&func).map_err(|_| PrepareError::Deserialization)?;
                     self.func_validator_allocations = func_validator.into_allocations();
+
+                    if self.multi_value {
+                        let operators = func
+                            .get_operators_reader()
+                            .map_err(|_| PrepareError::Deserialization)?;
+                        for op in operators {
+                            let op = op.map_err(|_| PrepareError::Deserialization)?;
+                            if let wp::Operator::CallIndirect { type_index, .. } = op {
+                                self.ensure_single_result(type_index)?;
+                            }
+                        }
+                    }
                 }
                 wp::Payload::CustomSection(reader) => {
                     self.ensure_import_section();
@@ -212,6 +241,7 @@ impl<'a> PrepareContext<'a> {
             let new_type = match import.ty {
                 wp::TypeRef::Func(id) => {
                     // TODO: validate imported function types here.
+                    self.ensure_single_result(id)?;
                     self.function_limit =
                         self.function_limit.checked_sub(1).ok_or(PrepareError::TooManyFunctions)?;
                     wasm_encoder::EntityType::Function(id)
@@ -229,6 +259,24 @@ impl<'a> PrepareContext<'a> {
         Ok(())
     }
 
+    /// Functions returning multiple values are not supported by the NearVM singlepass compiler,
+    /// so even when the multi-value proposal is enabled, types with multiple results may only be
+    /// used as block types. Functions, imports and `call_indirect`s using them are rejected with
+    /// [`PrepareError::MultiValueResult`].
+    fn ensure_single_result(&self, type_index: u32) -> Result<(), PrepareError> {
+        if !self.multi_value {
+            return Ok(());
+        }
+        match self.multi_result_types.get(type_index as usize) {
+            Some(false) => Ok(()),
+            Some(true) => {
+                tracing::trace!(type_index, "input module contains a multi-value function type");
+                Err(PrepareError::MultiValueResult)
+            }
+            None => Err(PrepareError::Deserialization),
+        }
+    }
+
     fn ensure_import_section(&mut self) {
         if self.before_import_section {
             self.before_import_section = false;
@@ -270,7 +318,15 @@ pub(crate) fn prepare_contract(
     config: &Config,
     kind: VMKind,
 ) -> Result<Vec<u8>, PrepareError> {
-    let lightly_steamed = PrepareContext::new(original_code, features, config).run()?;
+    let mut lightly_steamed = PrepareContext::new(original_code, features, config).run()?;
+    if features.bulk_memory() {
+        // The length of the bulk operations is only known at runtime, so they are charged by
+        // code injected into the module for every runtime alike.
+        lightly_steamed = crate::instrument::gas::bulk::inject_bulk_gas_counter(
+            &lightly_steamed,
+            config.regular_op_cost,
+        )?;
+    }
 
     if kind == VMKind::NearVm {
         // Built-in near-vm code instruments code for itself.
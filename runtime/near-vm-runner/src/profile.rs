@@ -266,9 +266,9 @@ mod test {
             keccak256_byte -> 18 [0% host]
             keccak512_base -> 19 [0% host]
             keccak512_byte -> 20 [0% host]
-            ripemd160_base -> 21 [1% host]
-            ripemd160_block -> 22 [1% host]
-            ecrecover_base -> 23 [1% host]
+            ripemd160_base -> 21 [0% host]
+            ripemd160_block -> 22 [0% host]
+            ecrecover_base -> 23 [0% host]
             log_base -> 24 [1% host]
             log_byte -> 25 [1% host]
             storage_write_base -> 26 [1% host]
@@ -287,11 +287,11 @@ mod test {
             storage_iter_create_prefix_byte -> 39 [1% host]
             storage_iter_create_range_base -> 40 [1% host]
             storage_iter_create_from_byte -> 41 [1% host]
-            storage_iter_create_to_byte -> 42 [2% host]
-            storage_iter_next_base -> 43 [2% host]
-            storage_iter_next_key_byte -> 44 [2% host]
-            storage_iter_next_value_byte -> 45 [2% host]
-            touching_trie_node -> 46 [2% host]
+            storage_iter_create_to_byte -> 42 [1% host]
+            storage_iter_next_base -> 43 [1% host]
+            storage_iter_next_key_byte -> 44 [1% host]
+            storage_iter_next_value_byte -> 45 [1% host]
+            touching_trie_node -> 46 [1% host]
             read_cached_trie_node -> 47 [2% host]
             promise_and_base -> 48 [2% host]
             promise_and_per_promise -> 49 [2% host]
@@ -308,8 +308,12 @@ mod test {
             ed25519_verify_byte -> 60 [2% host]
             yield_create_base -> 61 [2% host]
             yield_create_byte -> 62 [2% host]
-            yield_resume_base -> 63 [3% host]
-            yield_resume_byte -> 64 [3% host]
+            yield_resume_base -> 63 [2% host]
+            yield_resume_byte -> 64 [2% host]
+            storage_range_base -> 65 [2% host]
+            storage_range_item -> 66 [2% host]
+            storage_range_key_byte -> 67 [2% host]
+            storage_range_value_byte -> 68 [2% host]
             ------ Actions --------
             create_account -> 1000
             delete_account -> 1001
@@ -327,6 +331,9 @@ mod test {
             new_data_receipt_base -> 1013
             new_data_receipt_byte -> 1014
             delegate -> 1015
+            deploy_global_contract_base -> 1016
+            deploy_global_contract_byte -> 1017
+            use_global_contract -> 1018
             ------------------------------
         "#]]
         .assert_eq(&pretty_debug_str)
@@ -1,6 +1,7 @@
 mod cache;
 mod compile_errors;
 mod fuzzers;
+mod prepare_v3;
 mod regression_tests;
 mod rs_contract;
 mod runtime_errors;
@@ -31,18 +32,27 @@ pub(crate) fn with_vm_variants(
     #[allow(unused)] cfg: &near_parameters::vm::Config,
     runner: impl Fn(VMKind) -> (),
 ) {
+    #[allow(unused)]
+    use near_parameters::vm::ContractPrepareVersion;
+    #[allow(unused)]
+    let prepare_version = cfg.limit_config.contract_prepare_version;
+
+    // V3 is not supported by the wasmer singlepass compilers.
     #[cfg(all(feature = "wasmer0_vm", target_arch = "x86_64"))]
-    runner(VMKind::Wasmer0);
+    if prepare_version != ContractPrepareVersion::V3 {
+        runner(VMKind::Wasmer0);
+    }
 
     #[cfg(feature = "wasmtime_vm")]
     runner(VMKind::Wasmtime);
 
     #[cfg(all(feature = "wasmer2_vm", target_arch = "x86_64"))]
-    runner(VMKind::Wasmer2);
+    if prepare_version != ContractPrepareVersion::V3 {
+        runner(VMKind::Wasmer2);
+    }
 
     #[cfg(all(feature = "near_vm", target_arch = "x86_64"))]
-    if cfg.limit_config.contract_prepare_version == near_parameters::vm::ContractPrepareVersion::V2
-    {
+    if matches!(prepare_version, ContractPrepareVersion::V2 | ContractPrepareVersion::V3) {
         runner(VMKind::NearVm);
     }
 }
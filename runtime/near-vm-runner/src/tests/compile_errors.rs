@@ -438,6 +438,7 @@ fn extension_saturating_float_to_int() {
             "#,
         )
         .protocol_version(FIX_CONTRACT_LOADING_COST)
+        .protocol_version(ProtocolFeature::PreparationV3.protocol_version())
         .expects(&[
             expect![[r#"
                 VMOutcome: balance 4 storage_usage 12 return data None burnt gas 0 used gas 0
@@ -447,6 +448,10 @@ fn extension_saturating_float_to_int() {
                 VMOutcome: balance 4 storage_usage 12 return data None burnt gas 48450963 used gas 48450963
                 Err: PrepareError: Error happened while deserializing the module.
             "#]],
+            expect![[r#"
+                VMOutcome: balance 4 storage_usage 12 return data None burnt gas 48450963 used gas 48450963
+                Err: MethodNotFound
+            "#]],
         ]);
 }
 
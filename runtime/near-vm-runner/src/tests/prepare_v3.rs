@@ -0,0 +1,299 @@
+//! Behavior of the instructions only accepted by [`ContractPrepareVersion::V3`].
+//!
+//! V3 is only enabled by a nightly protocol version, so these tests override the contract
+//! preparation version of the test config instead of using the `test_builder`.
+use crate::logic::errors::{FunctionCallError, PrepareError, WasmTrap};
+use crate::logic::mocks::mock_external::MockedExternal;
+use crate::logic::types::ReturnData;
+use crate::logic::{Config, VMOutcome};
+use crate::prepare::prepare_contract;
+use crate::runner::VMKindExt;
+use crate::tests::{create_context, test_vm_config, with_vm_variants};
+use crate::ContractCode;
+use near_parameters::vm::{ContractPrepareVersion, VMKind};
+use near_parameters::RuntimeFeesConfig;
+
+fn v3_config() -> Config {
+    let mut config = test_vm_config();
+    config.limit_config.contract_prepare_version = ContractPrepareVersion::V3;
+    config
+}
+
+#[track_caller]
+fn run(config: &Config, vm_kind: VMKind, wat: &str) -> VMOutcome {
+    let code = ContractCode::new(wat::parse_str(wat).unwrap(), None);
+    let runtime = vm_kind.runtime(config.clone()).expect("runtime has not been compiled");
+    runtime
+        .run(
+            &code,
+            "main",
+            &mut MockedExternal::new(),
+            create_context(vec![]),
+            &RuntimeFeesConfig::test(),
+            &[],
+            None,
+        )
+        .expect("execution failed")
+}
+
+#[track_caller]
+fn assert_out_of_bounds(outcome: &VMOutcome) {
+    assert_eq!(
+        outcome.aborted,
+        Some(FunctionCallError::WasmTrap(WasmTrap::MemoryOutOfBounds)),
+        "{outcome:?}"
+    );
+}
+
+/// Checks that the gas burnt by `wat(len)` grows by `words_per_len(len)` regular operations.
+///
+/// Every pair of lengths is encoded with the same number of bytes, so that the contract loading
+/// cost is the same for both runs.
+#[track_caller]
+fn assert_bulk_gas(
+    config: &Config,
+    vm_kind: VMKind,
+    wat: impl Fn(u32) -> String,
+    lens: &[(u32, u32)],
+    words_per_len: impl Fn(u32) -> u64,
+) {
+    for &(base_len, len) in lens {
+        let base = run(config, vm_kind, &wat(base_len));
+        let outcome = run(config, vm_kind, &wat(len));
+        assert_eq!(base.aborted, None, "{base:?}");
+        assert_eq!(outcome.aborted, None, "{outcome:?}");
+        let words = words_per_len(len) - words_per_len(base_len);
+        assert_eq!(
+            outcome.burnt_gas - base.burnt_gas,
+            words * u64::from(config.regular_op_cost),
+            "len {len} compared to len {base_len}"
+        );
+    }
+}
+
+fn memory_words(len: u32) -> u64 {
+    (u64::from(len) + 7) / 8
+}
+
+fn table_elements(len: u32) -> u64 {
+    u64::from(len)
+}
+
+/// Pairs of lengths which are encoded with the same number of bytes.
+const MEMORY_LENS: &[(u32, u32)] = &[(0, 1), (1, 8), (8, 9), (0, 63), (8192, 65536)];
+
+#[test]
+fn test_multi_value_blocks() {
+    let config = v3_config();
+    with_vm_variants(&config, |vm_kind| {
+        let outcome = run(
+            &config,
+            vm_kind,
+            r#"
+(module
+  (import "env" "value_return" (func $value_return (param i64 i64)))
+  (memory 1)
+  (func (export "main") (local $t i64)
+    (i32.const 0)
+    ;; Branch out of a block with multiple results.
+    (block (result i64 i64) (i64.const 40) (i64.const 2) (br 0))
+    ;; Block with multiple params.
+    (block (param i64 i64) (result i64) (i64.sub))
+    ;; 38
+    (i32.const 1)
+    (if (param i64) (result i64 i64) (then (i64.const 4)) (else (i64.const 5)))
+    (i64.add)
+    ;; 42
+    (i64.const 3)
+    (i64.add)
+    ;; Branch back to a loop with params until the value drops to 42.
+    (loop (param i64) (result i64)
+      (i64.const 1)
+      (i64.sub)
+      (local.tee $t)
+      (local.get $t)
+      (i64.const 42)
+      (i64.gt_s)
+      (br_if 0))
+    (i64.store)
+    (call $value_return (i64.const 8) (i64.const 0))))
+"#,
+        );
+        assert_eq!(outcome.aborted, None, "{outcome:?}");
+        assert_eq!(outcome.return_data, ReturnData::Value(42u64.to_le_bytes().to_vec()));
+    });
+}
+
+#[test]
+fn test_multi_value_functions_are_rejected() {
+    let config = v3_config();
+    with_vm_variants(&config, |vm_kind| {
+        let wasm = wat::parse_str(
+            r#"
+(module
+  (func $pair (result i64 i64) (i64.const 1) (i64.const 2))
+  (func (export "main")))
+"#,
+        )
+        .unwrap();
+        assert_eq!(prepare_contract(&wasm, &config, vm_kind), Err(PrepareError::MultiValueResult));
+    });
+}
+
+/// The gas metering of bulk operations adds an import, which shifts the indices of the functions
+/// defined in the contract.
+#[test]
+fn test_bulk_operations_keep_function_indices() {
+    let config = v3_config();
+    with_vm_variants(&config, |vm_kind| {
+        let outcome = run(
+            &config,
+            vm_kind,
+            r#"
+(module
+  (import "env" "value_return" (func $value_return (param i64 i64)))
+  (type $get (func (result i64)))
+  (memory 1)
+  (table 1 funcref)
+  (elem (i32.const 0) $seven)
+  (global $started (mut i64) (i64.const 0))
+  (start $start)
+  (func $start (global.set $started (i64.const 1)))
+  (func $seven (result i64) (i64.const 7))
+  (func $six (result i64) (i64.const 6))
+  (func (export "main")
+    (memory.fill (i32.const 0) (i32.const 0) (i32.const 8))
+    (i64.store (i32.const 0)
+      (i64.add
+        (global.get $started)
+        (i64.mul (call $six) (call_indirect (type $get) (i32.const 0)))))
+    (call $value_return (i64.const 8) (i64.const 0))))
+"#,
+        );
+        assert_eq!(outcome.aborted, None, "{outcome:?}");
+        assert_eq!(outcome.return_data, ReturnData::Value(43u64.to_le_bytes().to_vec()));
+    });
+}
+
+#[test]
+fn test_memory_fill_gas() {
+    let config = v3_config();
+    with_vm_variants(&config, |vm_kind| {
+        let wat = |len: u32| {
+            format!(
+                r#"(module (memory 1) (func (export "main")
+                    (memory.fill (i32.const 0) (i32.const 42) (i32.const {len}))))"#
+            )
+        };
+        assert_bulk_gas(&config, vm_kind, wat, MEMORY_LENS, memory_words);
+    });
+}
+
+#[test]
+fn test_memory_copy_gas() {
+    let config = v3_config();
+    with_vm_variants(&config, |vm_kind| {
+        let wat = |len: u32| {
+            format!(
+                r#"(module (memory 1) (func (export "main")
+                    (memory.copy (i32.const 1) (i32.const 0) (i32.const {len}))))"#
+            )
+        };
+        assert_bulk_gas(&config, vm_kind, wat, MEMORY_LENS, memory_words);
+    });
+}
+
+#[test]
+fn test_memory_bounds() {
+    let config = v3_config();
+    with_vm_variants(&config, |vm_kind| {
+        let end = "(i32.mul (memory.size) (i32.const 65536))";
+        let before_end = |n: u32| format!("(i32.sub {end} (i32.const {n}))");
+        let fill =
+            |dest: &str, len: u32| format!("(memory.fill {dest} (i32.const 0) (i32.const {len}))");
+        let copy = |dest: &str, src: &str, len: u32| {
+            format!("(memory.copy {dest} {src} (i32.const {len}))")
+        };
+        let zero = "(i32.const 0)";
+        let in_bounds = [
+            fill(&before_end(8), 8),
+            fill(end, 0),
+            copy(zero, &before_end(8), 8),
+            copy(end, zero, 0),
+        ];
+        let out_of_bounds = [
+            fill(&before_end(4), 8),
+            fill(&format!("(i32.add {end} (i32.const 1))"), 0),
+            copy(&before_end(4), zero, 8),
+            copy(zero, &before_end(4), 8),
+        ];
+        let wat = |body: &str| format!(r#"(module (memory 1) (func (export "main") {body}))"#);
+        for body in &in_bounds {
+            let outcome = run(&config, vm_kind, &wat(body));
+            assert_eq!(outcome.aborted, None, "{body}: {outcome:?}");
+        }
+        for body in &out_of_bounds {
+            assert_out_of_bounds(&run(&config, vm_kind, &wat(body)));
+        }
+    });
+}
+
+/// A table of 16 elements and a passive segment of 8 elements.
+fn table_module(body: &str) -> String {
+    format!(
+        r#"
+(module
+  (table 16 funcref)
+  (elem func $f $f $f $f $f $f $f $f)
+  (func $f)
+  (func (export "main") {body}))
+"#
+    )
+}
+
+#[test]
+fn test_table_init_gas() {
+    let config = v3_config();
+    with_vm_variants(&config, |vm_kind| {
+        let wat = |len: u32| {
+            table_module(&format!("(table.init 0 (i32.const 0) (i32.const 0) (i32.const {len}))"))
+        };
+        assert_bulk_gas(&config, vm_kind, wat, &[(0, 1), (1, 8)], table_elements);
+    });
+}
+
+#[test]
+fn test_table_copy_gas() {
+    let config = v3_config();
+    with_vm_variants(&config, |vm_kind| {
+        let wat = |len: u32| {
+            table_module(&format!("(table.copy (i32.const 8) (i32.const 0) (i32.const {len}))"))
+        };
+        assert_bulk_gas(&config, vm_kind, wat, &[(0, 1), (1, 8)], table_elements);
+    });
+}
+
+#[test]
+fn test_table_bounds() {
+    let config = v3_config();
+    with_vm_variants(&config, |vm_kind| {
+        let out_of_bounds = [
+            // The segment only has 8 elements.
+            "(table.init 0 (i32.const 0) (i32.const 0) (i32.const 9))",
+            "(table.init 0 (i32.const 0) (i32.const 1) (i32.const 8))",
+            // The table only has 16 elements.
+            "(table.init 0 (i32.const 9) (i32.const 0) (i32.const 8))",
+            "(table.copy (i32.const 9) (i32.const 0) (i32.const 8))",
+            "(table.copy (i32.const 0) (i32.const 9) (i32.const 8))",
+        ];
+        for body in out_of_bounds {
+            assert_out_of_bounds(&run(&config, vm_kind, &table_module(body)));
+        }
+        let outcome = run(
+            &config,
+            vm_kind,
+            &table_module("(table.copy (i32.const 8) (i32.const 8) (i32.const 8))"),
+        );
+        assert_eq!(outcome.aborted, None, "{outcome:?}");
+    });
+}
@@ -205,9 +205,19 @@ impl TestBuilder {
                 let runtime_config = runtime_config_store.get_config(protocol_version);
 
                 // NearVM includes a different contract preparation algorithm, that is not supported on old protocol versions
+                let prepare_version =
+                    runtime_config.wasm_config.limit_config.contract_prepare_version;
                 if vm_kind == VMKind::NearVm
-                    && runtime_config.wasm_config.limit_config.contract_prepare_version
-                        != ContractPrepareVersion::V2
+                    && !matches!(
+                        prepare_version,
+                        ContractPrepareVersion::V2 | ContractPrepareVersion::V3
+                    )
+                {
+                    continue;
+                }
+                // While V3 is not supported by the wasmer singlepass compilers.
+                if matches!(vm_kind, VMKind::Wasmer0 | VMKind::Wasmer2)
+                    && prepare_version == ContractPrepareVersion::V3
                 {
                     continue;
                 }
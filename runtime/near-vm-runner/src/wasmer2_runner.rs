@@ -295,7 +295,8 @@ impl Wasmer2VM {
         code: &ContractCode,
         cache: Option<&dyn CompiledContractCache>,
     ) -> Result<Result<UniversalExecutable, CompilationError>, CacheError> {
-        let executable_or_error = self.compile_uncached(code);
+        let executable_or_error =
+            crate::metrics::record_compilation(|| self.compile_uncached(code));
         let key = get_contract_cache_key(code, &self.config);
 
         if let Some(cache) = cache {
@@ -339,6 +340,9 @@ impl Wasmer2VM {
                 .transpose()
                 .map_err(CacheError::ReadError)?
                 .flatten();
+            if cache.is_some() {
+                crate::metrics::record_compiled_contract_cache_lookup(cache_record.is_some());
+            }
 
             let stored_artifact: Option<VMArtifact> = match cache_record {
                 None => None,
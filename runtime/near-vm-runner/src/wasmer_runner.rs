@@ -262,7 +262,7 @@ impl Wasmer0VM {
         code: &ContractCode,
         cache: Option<&dyn CompiledContractCache>,
     ) -> Result<Result<wasmer_runtime::Module, CompilationError>, CacheError> {
-        let module_or_error = self.compile_uncached(code);
+        let module_or_error = crate::metrics::record_compilation(|| self.compile_uncached(code));
         let key = get_contract_cache_key(code, &self.config);
 
         if let Some(cache) = cache {
@@ -302,6 +302,9 @@ impl Wasmer0VM {
                     .transpose()
                     .map_err(CacheError::ReadError)?
                     .flatten();
+                if cache.is_some() {
+                    crate::metrics::record_compiled_contract_cache_lookup(cache_record.is_some());
+                }
 
                 let stored_module: Option<wasmer_runtime::Module> = match cache_record {
                     None => None,
@@ -185,10 +185,12 @@ impl crate::runner::VM for WasmtimeVM {
                 Ok(code) => code,
                 Err(err) => return Ok(VMOutcome::abort(logic, FunctionCallError::from(err))),
             };
-        let module = match Module::new(&engine, prepared_code) {
-            Ok(module) => module,
-            Err(err) => return Ok(VMOutcome::abort(logic, err.into_vm_error()?)),
-        };
+        // Wasmtime contracts are not cached, so every call compiles the contract.
+        let module =
+            match crate::metrics::record_compilation(|| Module::new(&engine, prepared_code)) {
+                Ok(module) => module,
+                Err(err) => return Ok(VMOutcome::abort(logic, err.into_vm_error()?)),
+            };
         let mut linker = Linker::new(&engine);
 
         let result = logic.after_loading_executable(code.code().len());
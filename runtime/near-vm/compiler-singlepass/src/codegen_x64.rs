@@ -93,6 +93,12 @@ pub(crate) struct FuncGen<'a> {
     /// Cost for initializing the stack of the function
     stack_init_gas_cost: u64,
 
+    /// Number of hidden locals reserved to pass multiple values across block boundaries.
+    branch_value_slots: u32,
+
+    /// Index of the first hidden local reserved to pass multiple values across block boundaries.
+    branch_value_slots_base: u32,
+
     /// Iterator over the gas instrumentation points
     gas_iter: iter::Peekable<iter::Zip<slice::Iter<'a, usize>, slice::Iter<'a, u64>>>,
 
@@ -226,11 +232,27 @@ pub(crate) struct ControlFrame {
 
     pub(crate) loop_like: bool,
     pub(crate) if_else: IfElseState,
+    pub(crate) params: SmallVec<[WpType; 1]>,
     pub(crate) returns: SmallVec<[WpType; 1]>,
+    /// Depth of the value stack at the beginning of the block, excluding block parameters.
     pub(crate) value_stack_depth: usize,
     pub(crate) fp_stack_depth: usize,
 }
 
+impl ControlFrame {
+    /// Types of the values carried by a branch targetting this frame.
+    ///
+    /// Branches to a loop jump back to its beginning and thus carry its parameters; branches to
+    /// any other block carry its results.
+    fn branch_types(&self) -> SmallVec<[WpType; 1]> {
+        if self.loop_like {
+            self.params.clone()
+        } else {
+            self.returns.clone()
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone)]
 pub(crate) enum IfElseState {
     None,
@@ -267,6 +289,95 @@ impl<'a> FuncGen<'a> {
         self.get_location_released(loc)
     }
 
+    /// Number of entries of the FP stack that describe values below `depth` in the value stack.
+    fn fp_stack_depth_at(&self, depth: usize) -> usize {
+        self.fp_stack.iter().take_while(|fp| fp.depth < depth).count()
+    }
+
+    /// Parameter and result types of a `block`, `loop` or `if`.
+    fn block_signature(
+        &self,
+        blockty: WpBlockType,
+    ) -> (SmallVec<[WpType; 1]>, SmallVec<[WpType; 1]>) {
+        match blockty {
+            WpBlockType::Empty => (smallvec![], smallvec![]),
+            WpBlockType::Type(inner_ty) => (smallvec![], smallvec![inner_ty]),
+            WpBlockType::FuncType(type_index) => {
+                let sig = &self.module.signatures[SignatureIndex::new(type_index as usize)];
+                (
+                    sig.params().iter().cloned().map(type_to_wp_type).collect(),
+                    sig.results().iter().cloned().map(type_to_wp_type).collect(),
+                )
+            }
+        }
+    }
+
+    /// The location through which the `index`-th out of `count` values is passed across a block
+    /// boundary.
+    ///
+    /// A single value is passed in RAX, multiple values are passed through hidden locals.
+    fn branch_value_location(&self, index: usize, count: usize) -> Location {
+        if count == 1 {
+            Location::GPR(GPR::RAX)
+        } else {
+            assert!(count <= self.branch_value_slots as usize, "not enough branch value slots");
+            self.machine.get_local_location(self.branch_value_slots_base + index as u32)
+        }
+    }
+
+    /// Move the values at the top of the value stack to the locations where the target of a branch
+    /// (or the code following the end of a block) expects them, canonicalizing NaNs if necessary.
+    ///
+    /// The value stack itself is left untouched.
+    fn emit_branch_values(&mut self, tys: &[WpType]) -> Result<(), CodegenError> {
+        let count = tys.len();
+        let base = self.value_stack.len().checked_sub(count).ok_or_else(|| CodegenError {
+            message: "branch: not enough values on the value stack".to_string(),
+        })?;
+        for (index, ty) in tys.iter().enumerate() {
+            let depth = base + index;
+            let loc = self.value_stack[depth];
+            let dst = self.branch_value_location(index, count);
+            let canonicalization = if ty.is_float() {
+                self.fp_stack
+                    .iter()
+                    .rev()
+                    .find(|fp| fp.depth == depth)
+                    .and_then(|fp| fp.canonicalization)
+            } else {
+                None
+            };
+            match canonicalization {
+                Some(cncl)
+                    if self.assembler.arch_supports_canonicalize_nan()
+                        && self.config.enable_nan_canonicalization =>
+                {
+                    self.canonicalize_nan(cncl.to_size(), loc, dst);
+                }
+                _ => {
+                    self.emit_relaxed_binop(Assembler::emit_mov, Size::S64, loc, dst);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Counterpart to [`emit_branch_values`](Self::emit_branch_values): push the values passed
+    /// across a block boundary onto the value stack.
+    fn receive_branch_values(&mut self, tys: &[WpType]) {
+        let count = tys.len();
+        let locs = self.machine.acquire_locations(self.assembler, tys, false);
+        for (index, (ty, loc)) in tys.iter().zip(locs).enumerate() {
+            let src = self.branch_value_location(index, count);
+            self.emit_relaxed_binop(Assembler::emit_mov, Size::S64, src, loc);
+            self.value_stack.push(loc);
+            if ty.is_float() {
+                // we already canonicalized when passing the values along.
+                self.fp_stack.push(FloatValue::new(self.value_stack.len() - 1));
+            }
+        }
+    }
+
     /// Prepare data for binary operator with 2 inputs and 1 output.
     fn i2o1_prepare(&mut self, ty: WpType) -> I2O1 {
         let loc_b = self.pop_value_released();
@@ -283,6 +394,11 @@ impl<'a> FuncGen<'a> {
             sig.params().iter().cloned().map(type_to_wp_type).collect();
         let return_types: SmallVec<[WpType; 1]> =
             sig.results().iter().cloned().map(type_to_wp_type).collect();
+        if return_types.len() > 1 {
+            return Err(CodegenError {
+                message: "Call: multi-value returns not yet implemented".to_string(),
+            });
+        }
 
         let params: SmallVec<[_; 8]> =
             self.value_stack.drain(self.value_stack.len() - param_types.len()..).collect();
@@ -1626,9 +1742,50 @@ impl<'a> FuncGen<'a> {
         self.assembler.emit_label(end);
     }
 
+    /// Close the `then` branch of the innermost `if` and begin its `else` branch.
+    fn emit_else(&mut self, was_unreachable: bool) -> Result<(), CodegenError> {
+        let frame = self.control_stack.last().unwrap();
+        let returns = frame.returns.clone();
+        let value_stack_depth = frame.value_stack_depth;
+        // The `then` branch operated on a copy of the block parameters.
+        let then_depth = value_stack_depth + frame.params.len();
+
+        if !was_unreachable {
+            self.emit_branch_values(&returns)?;
+        }
+
+        let released: &[Location] = &self.value_stack[then_depth..];
+        self.machine.release_locations(self.assembler, released);
+        self.value_stack.truncate(then_depth);
+        let fp_stack_depth = self.fp_stack_depth_at(then_depth);
+        self.fp_stack.truncate(fp_stack_depth);
+
+        let frame = self.control_stack.last_mut().unwrap();
+        match frame.if_else {
+            IfElseState::If(label) => {
+                // The original block parameters remain as the parameters of the `else` branch,
+                // but the `then` branch must still leave them behind.
+                let released = &self.value_stack[value_stack_depth..];
+                self.machine.release_locations_keep_state(self.assembler, released);
+                self.assembler.emit_jmp(Condition::None, frame.br_label);
+                self.assembler.emit_label(label);
+                frame.if_else = IfElseState::Else;
+                Ok(())
+            }
+            _ => Err(CodegenError { message: "Else: frame.if_else unreachable code".to_string() }),
+        }
+    }
+
     pub(crate) fn emit_head(&mut self) -> Result<(), CodegenError> {
         // TODO: Patchpoint is not emitted for now, and ARM trampoline is not prepended.
 
+        // Reserve the hidden locals through which multiple values are passed across block
+        // boundaries.
+        self.branch_value_slots_base = self.local_count();
+        if self.branch_value_slots > 0 {
+            self.feed_local(self.branch_value_slots, WpType::I64);
+        }
+
         // Normal x86 entry prologue.
         self.assembler.emit_push(Size::S64, Location::GPR(GPR::RBP));
         self.assembler.emit_mov(Size::S64, Location::GPR(GPR::RSP), Location::GPR(GPR::RBP));
@@ -1671,6 +1828,7 @@ impl<'a> FuncGen<'a> {
             br_label: self.assembler.get_label(),
             loop_like: false,
             if_else: IfElseState::None,
+            params: smallvec![],
             returns: self.signature.results().iter().map(|&x| type_to_wp_type(x)).collect(),
             value_stack_depth: 0,
             fp_stack_depth: 0,
@@ -1708,6 +1866,23 @@ impl<'a> FuncGen<'a> {
         let func_index = module.func_index(local_func_index);
         let sig_index = module.functions[func_index];
         let signature = module.signatures[sig_index].clone();
+        if signature.results().len() > 1 {
+            return Err(CodegenError {
+                message: "Function: multi-value returns not yet implemented".to_string(),
+            });
+        }
+        // Block types may refer to any of the signatures, so reserve enough space to pass around as
+        // many values as any of them has parameters or results.
+        let branch_value_slots = module
+            .signatures
+            .values()
+            .map(|sig| std::cmp::max(sig.params().len(), sig.results().len()))
+            .filter(|&count| count > 1)
+            .max()
+            .unwrap_or(0);
+        let branch_value_slots = u32::try_from(branch_value_slots).map_err(|_| CodegenError {
+            message: "a signature has more than u32::MAX parameters or results".to_string(),
+        })?;
 
         let special_labels = SpecialLabelSet {
             integer_division_by_zero: assembler.get_label(),
@@ -1740,6 +1915,8 @@ impl<'a> FuncGen<'a> {
             calling_convention,
             signature,
             stack_init_gas_cost,
+            branch_value_slots,
+            branch_value_slots_base: 0,
             gas_iter: gas_offsets.iter().zip(gas_costs.iter()).peekable(),
             stack_size: u32::try_from(stack_size).map_err(|_| CodegenError {
                 message: "one function has a stack more than u32::MAX deep".to_string(),
@@ -4694,6 +4871,12 @@ impl<'a> FuncGen<'a> {
                     sig.params().iter().cloned().map(type_to_wp_type).collect();
                 let return_types: SmallVec<[WpType; 1]> =
                     sig.results().iter().cloned().map(type_to_wp_type).collect();
+                if return_types.len() > 1 {
+                    return Err(CodegenError {
+                        message: "CallIndirect: multi-value returns not yet implemented"
+                            .to_string(),
+                    });
+                }
 
                 let func_index = self.pop_value_released();
 
@@ -4872,86 +5055,35 @@ impl<'a> FuncGen<'a> {
                 let label_else = self.assembler.get_label();
 
                 let cond = self.pop_value_released();
+                let (params, returns) = self.block_signature(blockty);
+                let value_stack_depth =
+                    self.value_stack.len().checked_sub(params.len()).ok_or_else(|| {
+                        CodegenError { message: "If: not enough block parameters".to_string() }
+                    })?;
 
                 let frame = ControlFrame {
                     br_label: label_end,
                     loop_like: false,
                     if_else: IfElseState::If(label_else),
-                    returns: match blockty {
-                        WpBlockType::Empty => smallvec![],
-                        WpBlockType::Type(inner_ty) => smallvec![inner_ty],
-                        WpBlockType::FuncType(_) => {
-                            return Err(CodegenError {
-                                message: "If: multi-value returns not yet implemented".to_string(),
-                            })
-                        }
-                    },
-                    value_stack_depth: self.value_stack.len(),
-                    fp_stack_depth: self.fp_stack.len(),
+                    params: params.clone(),
+                    returns,
+                    value_stack_depth,
+                    fp_stack_depth: self.fp_stack_depth_at(value_stack_depth),
                 };
                 self.control_stack.push(frame);
                 self.emit_relaxed_binop(Assembler::emit_cmp, Size::S32, Location::Imm32(0), cond);
                 self.assembler.emit_jmp(Condition::Equal, label_else);
-            }
-            Operator::Else => {
-                let frame = self.control_stack.last_mut().unwrap();
 
-                if !was_unreachable && !frame.returns.is_empty() {
-                    let first_return = frame.returns[0];
-                    let loc = *self.value_stack.last().unwrap();
-                    if first_return.is_float() {
-                        let fp = self.fp_stack.peek1()?;
-                        if self.assembler.arch_supports_canonicalize_nan()
-                            && self.config.enable_nan_canonicalization
-                            && fp.canonicalization.is_some()
-                        {
-                            self.canonicalize_nan(
-                                match first_return {
-                                    WpType::F32 => Size::S32,
-                                    WpType::F64 => Size::S64,
-                                    _ => unreachable!(),
-                                },
-                                loc,
-                                Location::GPR(GPR::RAX),
-                            );
-                        } else {
-                            self.emit_relaxed_binop(
-                                Assembler::emit_mov,
-                                Size::S64,
-                                loc,
-                                Location::GPR(GPR::RAX),
-                            );
-                        }
-                    } else {
-                        self.emit_relaxed_binop(
-                            Assembler::emit_mov,
-                            Size::S64,
-                            loc,
-                            Location::GPR(GPR::RAX),
-                        );
-                    }
-                }
-
-                let frame = self.control_stack.last_mut().unwrap();
-
-                let released: &[Location] = &self.value_stack[frame.value_stack_depth..];
-                self.machine.release_locations(self.assembler, released);
-                self.value_stack.truncate(frame.value_stack_depth);
-                self.fp_stack.truncate(frame.fp_stack_depth);
-
-                match frame.if_else {
-                    IfElseState::If(label) => {
-                        self.assembler.emit_jmp(Condition::None, frame.br_label);
-                        self.assembler.emit_label(label);
-                        frame.if_else = IfElseState::Else;
-                    }
-                    _ => {
-                        return Err(CodegenError {
-                            message: "Else: frame.if_else unreachable code".to_string(),
-                        })
-                    }
+                // The `else` branch needs the block parameters too, so the `then` branch operates
+                // on a copy of them, pushed on top of the originals.
+                if !params.is_empty() {
+                    self.emit_branch_values(&params)?;
+                    self.receive_branch_values(&params);
                 }
             }
+            Operator::Else => {
+                self.emit_else(was_unreachable)?;
+            }
             // `TypedSelect` must be used for extern refs so ref counting should
             // be done with TypedSelect. But otherwise they're the same.
             Operator::TypedSelect { .. } | Operator::Select => {
@@ -5010,26 +5142,38 @@ impl<'a> FuncGen<'a> {
                 self.assembler.emit_label(end_label);
             }
             Operator::Block { blockty } => {
+                let (params, returns) = self.block_signature(blockty);
+                let value_stack_depth =
+                    self.value_stack.len().checked_sub(params.len()).ok_or_else(|| {
+                        CodegenError { message: "Block: not enough block parameters".to_string() }
+                    })?;
                 let frame = ControlFrame {
                     br_label: self.assembler.get_label(),
                     loop_like: false,
                     if_else: IfElseState::None,
-                    returns: match blockty {
-                        WpBlockType::Empty => smallvec![],
-                        WpBlockType::Type(inner_ty) => smallvec![inner_ty],
-                        WpBlockType::FuncType(_) => {
-                            return Err(CodegenError {
-                                message: "Block: multi-value returns not yet implemented"
-                                    .to_string(),
-                            })
-                        }
-                    },
-                    value_stack_depth: self.value_stack.len(),
-                    fp_stack_depth: self.fp_stack.len(),
+                    params,
+                    returns,
+                    value_stack_depth,
+                    fp_stack_depth: self.fp_stack_depth_at(value_stack_depth),
                 };
                 self.control_stack.push(frame);
             }
             Operator::Loop { blockty } => {
+                let (params, returns) = self.block_signature(blockty);
+                let value_stack_depth =
+                    self.value_stack.len().checked_sub(params.len()).ok_or_else(|| {
+                        CodegenError { message: "Loop: not enough block parameters".to_string() }
+                    })?;
+                let fp_stack_depth = self.fp_stack_depth_at(value_stack_depth);
+
+                // Loop parameters are passed to the beginning of the loop the same way as the
+                // values carried by the branches back to it.
+                self.emit_branch_values(&params)?;
+                let released = &self.value_stack[value_stack_depth..];
+                self.machine.release_locations(self.assembler, released);
+                self.value_stack.truncate(value_stack_depth);
+                self.fp_stack.truncate(fp_stack_depth);
+
                 // Pad with NOPs to the next 16-byte boundary.
                 // Here we don't use the dynasm `.align 16` attribute because it pads the alignment with single-byte nops
                 // which may lead to efficiency problems.
@@ -5048,20 +5192,13 @@ impl<'a> FuncGen<'a> {
                     br_label,
                     loop_like: true,
                     if_else: IfElseState::None,
-                    returns: match blockty {
-                        WpBlockType::Empty => smallvec![],
-                        WpBlockType::Type(inner_ty) => smallvec![inner_ty],
-                        WpBlockType::FuncType(_) => {
-                            return Err(CodegenError {
-                                message: "Loop: multi-value returns not yet implemented"
-                                    .to_string(),
-                            })
-                        }
-                    },
-                    value_stack_depth: self.value_stack.len(),
-                    fp_stack_depth: self.fp_stack.len(),
+                    params: params.clone(),
+                    returns,
+                    value_stack_depth,
+                    fp_stack_depth,
                 });
                 self.assembler.emit_label(br_label);
+                self.receive_branch_values(&params);
 
                 // TODO: Re-enable interrupt signal check without branching
             }
@@ -5699,42 +5836,8 @@ impl<'a> FuncGen<'a> {
             Operator::Br { relative_depth } => {
                 let frame =
                     &self.control_stack[self.control_stack.len() - 1 - (relative_depth as usize)];
-                if !frame.loop_like && !frame.returns.is_empty() {
-                    if frame.returns.len() != 1 {
-                        return Err(CodegenError {
-                            message: "Br: incorrect frame.returns".to_string(),
-                        });
-                    }
-                    let first_return = frame.returns[0];
-                    let loc = *self.value_stack.last().unwrap();
-
-                    if first_return.is_float() {
-                        let fp = self.fp_stack.peek1()?;
-                        if self.assembler.arch_supports_canonicalize_nan()
-                            && self.config.enable_nan_canonicalization
-                            && fp.canonicalization.is_some()
-                        {
-                            self.canonicalize_nan(
-                                match first_return {
-                                    WpType::F32 => Size::S32,
-                                    WpType::F64 => Size::S64,
-                                    _ => unreachable!(),
-                                },
-                                loc,
-                                Location::GPR(GPR::RAX),
-                            );
-                        } else {
-                            self.emit_relaxed_binop(
-                                Assembler::emit_mov,
-                                Size::S64,
-                                loc,
-                                Location::GPR(GPR::RAX),
-                            );
-                        }
-                    } else {
-                        self.assembler.emit_mov(Size::S64, loc, Location::GPR(GPR::RAX));
-                    }
-                }
+                let branch_types = frame.branch_types();
+                self.emit_branch_values(&branch_types)?;
                 let frame =
                     &self.control_stack[self.control_stack.len() - 1 - (relative_depth as usize)];
 
@@ -5751,42 +5854,8 @@ impl<'a> FuncGen<'a> {
 
                 let frame =
                     &self.control_stack[self.control_stack.len() - 1 - (relative_depth as usize)];
-                if !frame.loop_like && !frame.returns.is_empty() {
-                    if frame.returns.len() != 1 {
-                        return Err(CodegenError {
-                            message: "BrIf: incorrect frame.returns".to_string(),
-                        });
-                    }
-
-                    let first_return = frame.returns[0];
-                    let loc = *self.value_stack.last().unwrap();
-                    if first_return.is_float() {
-                        let fp = self.fp_stack.peek1()?;
-                        if self.assembler.arch_supports_canonicalize_nan()
-                            && self.config.enable_nan_canonicalization
-                            && fp.canonicalization.is_some()
-                        {
-                            self.canonicalize_nan(
-                                match first_return {
-                                    WpType::F32 => Size::S32,
-                                    WpType::F64 => Size::S64,
-                                    _ => unreachable!(),
-                                },
-                                loc,
-                                Location::GPR(GPR::RAX),
-                            );
-                        } else {
-                            self.emit_relaxed_binop(
-                                Assembler::emit_mov,
-                                Size::S64,
-                                loc,
-                                Location::GPR(GPR::RAX),
-                            );
-                        }
-                    } else {
-                        self.assembler.emit_mov(Size::S64, loc, Location::GPR(GPR::RAX));
-                    }
-                }
+                let branch_types = frame.branch_types();
+                self.emit_branch_values(&branch_types)?;
                 let frame =
                     &self.control_stack[self.control_stack.len() - 1 - (relative_depth as usize)];
                 let released = &self.value_stack[frame.value_stack_depth..];
@@ -5830,45 +5899,8 @@ impl<'a> FuncGen<'a> {
                     table.push(label);
                     let frame =
                         &self.control_stack[self.control_stack.len() - 1 - (*target as usize)];
-                    if !frame.loop_like && !frame.returns.is_empty() {
-                        if frame.returns.len() != 1 {
-                            return Err(CodegenError {
-                                message: format!(
-                                    "BrTable: incorrect frame.returns for {:?}",
-                                    target
-                                ),
-                            });
-                        }
-
-                        let first_return = frame.returns[0];
-                        let loc = *self.value_stack.last().unwrap();
-                        if first_return.is_float() {
-                            let fp = self.fp_stack.peek1()?;
-                            if self.assembler.arch_supports_canonicalize_nan()
-                                && self.config.enable_nan_canonicalization
-                                && fp.canonicalization.is_some()
-                            {
-                                self.canonicalize_nan(
-                                    match first_return {
-                                        WpType::F32 => Size::S32,
-                                        WpType::F64 => Size::S64,
-                                        _ => unreachable!(),
-                                    },
-                                    loc,
-                                    Location::GPR(GPR::RAX),
-                                );
-                            } else {
-                                self.emit_relaxed_binop(
-                                    Assembler::emit_mov,
-                                    Size::S64,
-                                    loc,
-                                    Location::GPR(GPR::RAX),
-                                );
-                            }
-                        } else {
-                            self.assembler.emit_mov(Size::S64, loc, Location::GPR(GPR::RAX));
-                        }
-                    }
+                    let branch_types = frame.branch_types();
+                    self.emit_branch_values(&branch_types)?;
                     let frame =
                         &self.control_stack[self.control_stack.len() - 1 - (*target as usize)];
                     let released = &self.value_stack[frame.value_stack_depth..];
@@ -5880,42 +5912,8 @@ impl<'a> FuncGen<'a> {
                 {
                     let frame = &self.control_stack
                         [self.control_stack.len() - 1 - (default_target as usize)];
-                    if !frame.loop_like && !frame.returns.is_empty() {
-                        if frame.returns.len() != 1 {
-                            return Err(CodegenError {
-                                message: "BrTable: incorrect frame.returns".to_string(),
-                            });
-                        }
-
-                        let first_return = frame.returns[0];
-                        let loc = *self.value_stack.last().unwrap();
-                        if first_return.is_float() {
-                            let fp = self.fp_stack.peek1()?;
-                            if self.assembler.arch_supports_canonicalize_nan()
-                                && self.config.enable_nan_canonicalization
-                                && fp.canonicalization.is_some()
-                            {
-                                self.canonicalize_nan(
-                                    match first_return {
-                                        WpType::F32 => Size::S32,
-                                        WpType::F64 => Size::S64,
-                                        _ => unreachable!(),
-                                    },
-                                    loc,
-                                    Location::GPR(GPR::RAX),
-                                );
-                            } else {
-                                self.emit_relaxed_binop(
-                                    Assembler::emit_mov,
-                                    Size::S64,
-                                    loc,
-                                    Location::GPR(GPR::RAX),
-                                );
-                            }
-                        } else {
-                            self.assembler.emit_mov(Size::S64, loc, Location::GPR(GPR::RAX));
-                        }
-                    }
+                    let branch_types = frame.branch_types();
+                    self.emit_branch_values(&branch_types)?;
                     let frame = &self.control_stack
                         [self.control_stack.len() - 1 - (default_target as usize)];
                     let released = &self.value_stack[frame.value_stack_depth..];
@@ -5938,41 +5936,19 @@ impl<'a> FuncGen<'a> {
                 }
             }
             Operator::End => {
+                let mut was_unreachable = was_unreachable;
+                let frame = self.control_stack.last().unwrap();
+                if matches!(frame.if_else, IfElseState::If(_)) && !frame.params.is_empty() {
+                    // An `if` with parameters but without an `else` branch passes its parameters
+                    // through as its results when the condition is false. Emit that as an empty
+                    // `else` branch.
+                    self.emit_else(was_unreachable)?;
+                    was_unreachable = false;
+                }
                 let frame = self.control_stack.pop().unwrap();
 
-                if !was_unreachable && !frame.returns.is_empty() {
-                    let loc = *self.value_stack.last().unwrap();
-                    if frame.returns[0].is_float() {
-                        let fp = self.fp_stack.peek1()?;
-                        if self.assembler.arch_supports_canonicalize_nan()
-                            && self.config.enable_nan_canonicalization
-                            && fp.canonicalization.is_some()
-                        {
-                            self.canonicalize_nan(
-                                match frame.returns[0] {
-                                    WpType::F32 => Size::S32,
-                                    WpType::F64 => Size::S64,
-                                    _ => unreachable!(),
-                                },
-                                loc,
-                                Location::GPR(GPR::RAX),
-                            );
-                        } else {
-                            self.emit_relaxed_binop(
-                                Assembler::emit_mov,
-                                Size::S64,
-                                loc,
-                                Location::GPR(GPR::RAX),
-                            );
-                        }
-                    } else {
-                        self.emit_relaxed_binop(
-                            Assembler::emit_mov,
-                            Size::S64,
-                            loc,
-                            Location::GPR(GPR::RAX),
-                        );
-                    }
+                if !was_unreachable {
+                    self.emit_branch_values(&frame.returns)?;
                 }
 
                 if self.control_stack.is_empty() {
@@ -6028,24 +6004,7 @@ impl<'a> FuncGen<'a> {
                         self.assembler.emit_label(label);
                     }
 
-                    if !frame.returns.is_empty() {
-                        if frame.returns.len() != 1 {
-                            return Err(CodegenError {
-                                message: "End: incorrect frame.returns".to_string(),
-                            });
-                        }
-                        let loc = self.machine.acquire_locations(
-                            self.assembler,
-                            &[(frame.returns[0])],
-                            false,
-                        )[0];
-                        self.assembler.emit_mov(Size::S64, Location::GPR(GPR::RAX), loc);
-                        self.value_stack.push(loc);
-                        if frame.returns[0].is_float() {
-                            self.fp_stack.push(FloatValue::new(self.value_stack.len() - 1));
-                            // we already canonicalized at the `Br*` instruction or here previously.
-                        }
-                    }
+                    self.receive_branch_values(&frame.returns);
                 }
             }
             Operator::AtomicFence => {
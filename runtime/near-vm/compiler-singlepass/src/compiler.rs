@@ -61,9 +61,6 @@ impl Compiler for SinglepassCompiler {
         if !target.cpu_features().contains(CpuFeature::AVX) {
             return Err(CompileError::UnsupportedTarget("x86_64 without AVX".to_string()));
         }
-        if compile_info.features.multi_value {
-            return Err(CompileError::UnsupportedFeature("multivalue".to_string()));
-        }
         let calling_convention = match target.triple().default_calling_convention() {
             Ok(CallingConvention::WindowsFastcall) => CallingConvention::WindowsFastcall,
             Ok(CallingConvention::SystemV) => CallingConvention::SystemV,
@@ -712,6 +712,34 @@ pub enum Cost {
     /// arguments.
     YieldCreateByte,
 
+    // `storage_range` reads a range of key-value pairs from flat storage,
+    // taking the prospective changes into account.
+    /// Estimates `ExtCost::storage_range_base` which is charged once per call
+    /// to `storage_range`.
+    ///
+    /// Estimation: Contract call that reads N ranges with one small value each
+    /// and divide the cost by N.
+    StorageRangeBase,
+    /// Estimates `ExtCost::storage_range_item` which is charged for each step
+    /// of the scan of `storage_range`, that is for each returned entry and for
+    /// reaching the end of the range.
+    ///
+    /// Estimation: Contract call that reads N small values in ranges of 100
+    /// entries and divide the cost by N.
+    StorageRangeItem,
+    /// Estimates `ExtCost::storage_range_key_byte` which is charged for each
+    /// byte in keys returned by `storage_range`.
+    ///
+    /// Estimation: Contract call that reads N ranges with one small value with
+    /// a big key (10kiB) each and divide the cost by total number of key bytes.
+    StorageRangeKeyByte,
+    /// Estimates `ExtCost::storage_range_value_byte` which is charged for each
+    /// byte in values returned by `storage_range`.
+    ///
+    /// Estimation: Contract call that reads N ranges with one big value
+    /// (10kiB) each and divide the cost by total number of value bytes.
+    StorageRangeValueByte,
+
     __Count,
 }
 
@@ -53,6 +53,7 @@ fn runtime_fees_config(cost_table: &CostTable) -> anyhow::Result<RuntimeFeesConf
 
     let config_store = RuntimeConfigStore::new(None);
     let actual_fees_config = &config_store.get_config(PROTOCOL_VERSION).fees;
+    let actual_fee = |cost: ActionCosts| actual_fees_config.fee(cost).clone();
     let res = RuntimeFeesConfig {
         action_fees: enum_map::enum_map! {
             ActionCosts::create_account => fee(Cost::ActionCreateAccount)?,
@@ -71,6 +72,10 @@ fn runtime_fees_config(cost_table: &CostTable) -> anyhow::Result<RuntimeFeesConf
             ActionCosts::new_action_receipt => fee(Cost::ActionReceiptCreation)?,
             ActionCosts::new_data_receipt_base => fee(Cost::DataReceiptCreationBase)?,
             ActionCosts::new_data_receipt_byte => fee(Cost::DataReceiptCreationPerByte)?,
+            // Global contracts are not estimated yet, keep the configured fees.
+            ActionCosts::deploy_global_contract_base => actual_fee(ActionCosts::deploy_global_contract_base),
+            ActionCosts::deploy_global_contract_byte => actual_fee(ActionCosts::deploy_global_contract_byte),
+            ActionCosts::use_global_contract => actual_fee(ActionCosts::use_global_contract),
         },
         ..actual_fees_config.clone()
     };
@@ -154,6 +159,10 @@ fn estimation(cost: ExtCosts) -> Option<Cost> {
         ExtCosts::alt_bn128_pairing_check_element => Cost::AltBn128PairingCheckElement,
         ExtCosts::yield_create_base => Cost::YieldCreateBase,
         ExtCosts::yield_create_byte => Cost::YieldCreateByte,
+        ExtCosts::storage_range_base => Cost::StorageRangeBase,
+        ExtCosts::storage_range_item => Cost::StorageRangeItem,
+        ExtCosts::storage_range_key_byte => Cost::StorageRangeKeyByte,
+        ExtCosts::storage_range_value_byte => Cost::StorageRangeValueByte,
         _ => return None,
     })
 }
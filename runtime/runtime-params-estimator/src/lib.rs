@@ -239,6 +239,14 @@ static ALL_COSTS: &[(Cost, fn(&mut EstimatorContext) -> GasCost)] = &[
     (Cost::StorageRemoveBase, storage_remove_base),
     (Cost::StorageRemoveKeyByte, storage_remove_key_byte),
     (Cost::StorageRemoveRetValueByte, storage_remove_ret_value_byte),
+    #[cfg(feature = "nightly")]
+    (Cost::StorageRangeBase, storage_range_base),
+    #[cfg(feature = "nightly")]
+    (Cost::StorageRangeItem, storage_range_item),
+    #[cfg(feature = "nightly")]
+    (Cost::StorageRangeKeyByte, storage_range_key_byte),
+    #[cfg(feature = "nightly")]
+    (Cost::StorageRangeValueByte, storage_range_value_byte),
     (Cost::TouchingTrieNode, touching_trie_node),
     (Cost::ReadCachedTrieNode, read_cached_trie_node),
     (Cost::ApplyBlock, apply_block_cost),
@@ -1115,6 +1123,47 @@ fn storage_read_value_byte(ctx: &mut EstimatorContext) -> GasCost {
     )
 }
 
+#[cfg(feature = "nightly")]
+fn storage_range_base(ctx: &mut EstimatorContext) -> GasCost {
+    fn_cost_with_setup(
+        ctx,
+        "storage_write_10b_key_10b_value_1k",
+        "storage_range_10b_key_10b_value_1k",
+        ExtCosts::storage_range_base,
+        1000,
+    )
+}
+#[cfg(feature = "nightly")]
+fn storage_range_item(ctx: &mut EstimatorContext) -> GasCost {
+    fn_cost_with_setup(
+        ctx,
+        "storage_write_10b_key_10b_value_1k",
+        "storage_range_10b_key_10b_value_1k_items",
+        ExtCosts::storage_range_item,
+        1000,
+    )
+}
+#[cfg(feature = "nightly")]
+fn storage_range_key_byte(ctx: &mut EstimatorContext) -> GasCost {
+    fn_cost_with_setup(
+        ctx,
+        "storage_write_10kib_key_10b_value_1k",
+        "storage_range_10kib_key_10b_value_1k",
+        ExtCosts::storage_range_key_byte,
+        10 * 1024 * 1000,
+    )
+}
+#[cfg(feature = "nightly")]
+fn storage_range_value_byte(ctx: &mut EstimatorContext) -> GasCost {
+    fn_cost_with_setup(
+        ctx,
+        "storage_write_10b_key_10kib_value_1k",
+        "storage_range_10b_key_10kib_value_1k",
+        ExtCosts::storage_range_value_byte,
+        10 * 1024 * 1000,
+    )
+}
+
 fn storage_write_base(ctx: &mut EstimatorContext) -> GasCost {
     fn_cost(ctx, "storage_write_10b_key_10b_value_1k", ExtCosts::storage_write_base, 1000)
 }
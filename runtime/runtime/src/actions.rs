@@ -12,13 +12,16 @@ use near_primitives::account::{AccessKey, AccessKeyPermission, Account};
 use near_primitives::action::delegate::{DelegateAction, SignedDelegateAction};
 use near_primitives::checked_feature;
 use near_primitives::config::ViewConfig;
+use near_primitives::errors::IntegerOverflowError;
 use near_primitives::errors::{ActionError, ActionErrorKind, InvalidAccessKeyError, RuntimeError};
 use near_primitives::hash::CryptoHash;
 use near_primitives::receipt::{ActionReceipt, DataReceipt, Receipt, ReceiptEnum};
 use near_primitives::transaction::{
     Action, AddKeyAction, DeleteAccountAction, DeleteKeyAction, DeployContractAction,
-    FunctionCallAction, StakeAction,
+    DeployContractWithCodeHashCheckAction, DeployGlobalContractAction, FunctionCallAction,
+    StakeAction, UseGlobalContractAction,
 };
+use near_primitives::trie_key::TrieKey;
 use near_primitives::types::validator_stake::ValidatorStake;
 use near_primitives::types::{
     AccountId, Balance, BlockHeight, EpochInfoProvider, Gas, TrieCacheMode,
@@ -33,6 +36,7 @@ use near_store::{
     remove_access_key, remove_account, set_access_key, set_code, set_yielded_promise_indices,
     StorageError, TrieUpdate,
 };
+use near_store::{has_global_contract_code, set_global_contract_code};
 use near_vm_runner::logic::errors::{
     CompilationError, FunctionCallError, InconsistentStateError, VMRunnerError,
 };
@@ -617,7 +621,41 @@ pub(crate) fn action_deploy_contract(
     apply_state: &ApplyState,
 ) -> Result<(), StorageError> {
     let _span = tracing::debug_span!(target: "runtime", "action_deploy_contract").entered();
-    let code = ContractCode::new(deploy_contract.code.clone(), None);
+    deploy_contract_code(state_update, account, account_id, &deploy_contract.code, apply_state)
+}
+
+pub(crate) fn action_deploy_contract_with_code_hash_check(
+    state_update: &mut TrieUpdate,
+    account: &mut Account,
+    account_id: &AccountId,
+    deploy_contract: &DeployContractWithCodeHashCheckAction,
+    apply_state: &ApplyState,
+    result: &mut ActionResult,
+) -> Result<(), StorageError> {
+    let _span =
+        tracing::debug_span!(target: "runtime", "action_deploy_contract_with_code_hash_check")
+            .entered();
+    if account.code_hash() != deploy_contract.expected_code_hash {
+        result.result = Err(ActionErrorKind::DeployContractCodeHashMismatch {
+            account_id: account_id.clone(),
+            expected_code_hash: deploy_contract.expected_code_hash,
+            actual_code_hash: account.code_hash(),
+        }
+        .into());
+        return Ok(());
+    }
+    deploy_contract_code(state_update, account, account_id, &deploy_contract.code, apply_state)
+}
+
+/// Replaces the code deployed on the account, updating its storage usage accordingly.
+fn deploy_contract_code(
+    state_update: &mut TrieUpdate,
+    account: &mut Account,
+    account_id: &AccountId,
+    code: &[u8],
+    apply_state: &ApplyState,
+) -> Result<(), StorageError> {
+    let code = ContractCode::new(code.to_vec(), None);
     let prev_code = get_code(state_update, account_id, Some(account.code_hash()))?;
     let prev_code_length = prev_code.map(|code| code.code().len() as u64).unwrap_or_default();
     account.set_storage_usage(account.storage_usage().saturating_sub(prev_code_length));
@@ -638,6 +676,77 @@ pub(crate) fn action_deploy_contract(
     Ok(())
 }
 
+/// Stores the code under its hash so that any account on the same shard can refer to it with
+/// `UseGlobalContract`.
+///
+/// The code does not belong to any account and outlives the deploying account, so its storage
+/// can't be staked like the storage of a regular deployment, which `DeleteAccount` releases.
+/// Instead, the storage cost of the code is burnt from the balance of the deploying account the
+/// first time the code is deployed.
+pub(crate) fn action_deploy_global_contract(
+    state_update: &mut TrieUpdate,
+    account: &mut Account,
+    account_id: &AccountId,
+    deploy_contract: &DeployGlobalContractAction,
+    apply_state: &ApplyState,
+    result: &mut ActionResult,
+) -> Result<(), RuntimeError> {
+    let _span = tracing::debug_span!(target: "runtime", "action_deploy_global_contract").entered();
+    let code = ContractCode::new(deploy_contract.code.clone(), None);
+    if has_global_contract_code(state_update, *code.hash())? {
+        return Ok(());
+    }
+    let storage_cost = apply_state
+        .config
+        .storage_amount_per_byte()
+        .checked_mul(code.code().len() as Balance)
+        .ok_or(IntegerOverflowError {})?;
+    let Some(amount) = account.amount().checked_sub(storage_cost) else {
+        result.result = Err(ActionErrorKind::LackBalanceForState {
+            account_id: account_id.clone(),
+            amount: storage_cost - account.amount(),
+        }
+        .into());
+        return Ok(());
+    };
+    account.set_amount(amount);
+    result.other_burnt_amount = storage_cost;
+    set_global_contract_code(state_update, &code);
+    precompile_contract(&code, &apply_state.config.wasm_config, apply_state.cache.as_deref()).ok();
+    Ok(())
+}
+
+/// Makes the account execute the globally deployed code with the given hash. Code previously
+/// deployed directly on the account is removed and its storage is released.
+///
+/// Global code is only stored in the state of the shard it was deployed on, so the action fails
+/// with `GlobalContractDoesNotExist` for code deployed on another shard.
+pub(crate) fn action_use_global_contract(
+    state_update: &mut TrieUpdate,
+    account: &mut Account,
+    account_id: &AccountId,
+    use_contract: &UseGlobalContractAction,
+    result: &mut ActionResult,
+) -> Result<(), StorageError> {
+    let _span = tracing::debug_span!(target: "runtime", "action_use_global_contract").entered();
+    if !has_global_contract_code(state_update, use_contract.code_hash)? {
+        result.result = Err(ActionErrorKind::GlobalContractDoesNotExist {
+            account_id: account_id.clone(),
+            code_hash: use_contract.code_hash,
+        }
+        .into());
+        return Ok(());
+    }
+    if let Some(prev_code) = get_code(state_update, account_id, Some(account.code_hash()))? {
+        account.set_storage_usage(
+            account.storage_usage().saturating_sub(prev_code.code().len() as u64),
+        );
+        state_update.remove(TrieKey::ContractCode { account_id: account_id.clone() });
+    }
+    account.set_code_hash(use_contract.code_hash);
+    Ok(())
+}
+
 pub(crate) fn action_delete_account(
     state_update: &mut TrieUpdate,
     account: &mut Option<Account>,
@@ -987,6 +1096,24 @@ pub(crate) fn check_actor_permissions(
                 .into());
             }
         }
+        Action::DeployContractWithCodeHashCheck(_) => {
+            if actor_id != account_id {
+                return Err(ActionErrorKind::ActorNoPermission {
+                    account_id: account_id.clone(),
+                    actor_id: actor_id.clone(),
+                }
+                .into());
+            }
+        }
+        Action::DeployGlobalContract(_) | Action::UseGlobalContract(_) => {
+            if actor_id != account_id {
+                return Err(ActionErrorKind::ActorNoPermission {
+                    account_id: account_id.clone(),
+                    actor_id: actor_id.clone(),
+                }
+                .into());
+            }
+        }
         Action::DeleteAccount(_) => {
             if actor_id != account_id {
                 return Err(ActionErrorKind::ActorNoPermission {
@@ -1098,6 +1225,22 @@ pub(crate) fn check_account_existence(
                 .into());
             }
         }
+        Action::DeployContractWithCodeHashCheck(_) => {
+            if account.is_none() {
+                return Err(ActionErrorKind::AccountDoesNotExist {
+                    account_id: account_id.clone(),
+                }
+                .into());
+            }
+        }
+        Action::DeployGlobalContract(_) | Action::UseGlobalContract(_) => {
+            if account.is_none() {
+                return Err(ActionErrorKind::AccountDoesNotExist {
+                    account_id: account_id.clone(),
+                }
+                .into());
+            }
+        }
     };
     Ok(())
 }
@@ -1376,6 +1519,7 @@ mod tests {
             is_new_chunk: false,
             migration_data: Arc::default(),
             migration_flags: MigrationFlags::default(),
+            record_execution_report: false,
         }
     }
 
@@ -136,6 +136,25 @@ pub fn total_send_fees(
             },
             DeleteKey(_) => fees.fee(ActionCosts::delete_key).send_fee(sender_is_receiver),
             DeleteAccount(_) => fees.fee(ActionCosts::delete_account).send_fee(sender_is_receiver),
+            // TODO(deploy_contract_code_hash_check) Before stabilizing, consider charging for
+            // reading the code hash of the account in addition to the regular deployment cost.
+            DeployContractWithCodeHashCheck(action) => {
+                let num_bytes = action.code.len() as u64;
+                fees.fee(ActionCosts::deploy_contract_base).send_fee(sender_is_receiver)
+                    + fees.fee(ActionCosts::deploy_contract_byte).send_fee(sender_is_receiver)
+                        * num_bytes
+            }
+            DeployGlobalContract(action) => {
+                let num_bytes = action.code.len() as u64;
+                fees.fee(ActionCosts::deploy_global_contract_base).send_fee(sender_is_receiver)
+                    + fees
+                        .fee(ActionCosts::deploy_global_contract_byte)
+                        .send_fee(sender_is_receiver)
+                        * num_bytes
+            }
+            UseGlobalContract(_) => {
+                fees.fee(ActionCosts::use_global_contract).send_fee(sender_is_receiver)
+            }
             Delegate(signed_delegate_action) => {
                 let delegate_cost = fees.fee(ActionCosts::delegate).send_fee(sender_is_receiver);
                 let delegate_action = &signed_delegate_action.delegate_action;
@@ -238,6 +257,17 @@ pub fn exec_fee(config: &RuntimeConfig, action: &Action, receiver_id: &AccountId
         },
         DeleteKey(_) => fees.fee(ActionCosts::delete_key).exec_fee(),
         DeleteAccount(_) => fees.fee(ActionCosts::delete_account).exec_fee(),
+        DeployContractWithCodeHashCheck(action) => {
+            let num_bytes = action.code.len() as u64;
+            fees.fee(ActionCosts::deploy_contract_base).exec_fee()
+                + fees.fee(ActionCosts::deploy_contract_byte).exec_fee() * num_bytes
+        }
+        DeployGlobalContract(action) => {
+            let num_bytes = action.code.len() as u64;
+            fees.fee(ActionCosts::deploy_global_contract_base).exec_fee()
+                + fees.fee(ActionCosts::deploy_global_contract_byte).exec_fee() * num_bytes
+        }
+        UseGlobalContract(_) => fees.fee(ActionCosts::use_global_contract).exec_fee(),
         Delegate(_) => fees.fee(ActionCosts::delegate).exec_fee(),
     }
 }
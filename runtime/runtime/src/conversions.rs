@@ -34,6 +34,7 @@ mod prepare_error {
                 From::Memory => Self::Memory,
                 From::TooManyFunctions => Self::TooManyFunctions,
                 From::TooManyLocals => Self::TooManyLocals,
+                From::MultiValueResult => Self::MultiValueResult,
             }
         }
     }
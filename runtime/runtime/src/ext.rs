@@ -6,7 +6,9 @@ use near_primitives::trie_key::{trie_key_parsers, TrieKey};
 use near_primitives::types::{AccountId, Balance, EpochId, EpochInfoProvider, Gas, TrieCacheMode};
 use near_primitives::utils::create_receipt_id_from_action_hash;
 use near_primitives::version::ProtocolVersion;
-use near_store::{get_code, has_yielded_promise, KeyLookupMode, TrieUpdate, TrieUpdateValuePtr};
+use near_store::{
+    get_account_code, has_yielded_promise, KeyLookupMode, TrieUpdate, TrieUpdateValuePtr,
+};
 use near_vm_runner::logic::errors::{AnyError, VMLogicError};
 use near_vm_runner::logic::types::ReceiptIndex;
 use near_vm_runner::logic::{External, StorageGetMode, ValuePtr};
@@ -85,7 +87,7 @@ impl<'a> RuntimeExt<'a> {
     }
 
     pub fn get_code(&self, code_hash: CryptoHash) -> Result<Option<ContractCode>, StorageError> {
-        get_code(self.trie_update, self.account_id, Some(code_hash))
+        get_account_code(self.trie_update, self.account_id, code_hash)
     }
 
     pub fn create_storage_key(&self, key: &[u8]) -> TrieKey {
@@ -172,6 +174,42 @@ impl<'a> External for RuntimeExt<'a> {
         Ok(())
     }
 
+    fn storage_range(
+        &mut self,
+        start: &[u8],
+        end: Option<&[u8]>,
+        visit: &mut dyn FnMut(&[u8], &[u8]) -> ExtResult<bool>,
+    ) -> ExtResult<()> {
+        let raw_start = trie_key_parsers::get_raw_prefix_for_contract_data(self.account_id, start);
+        let raw_end = match end {
+            Some(end) => trie_key_parsers::get_raw_prefix_for_contract_data(self.account_id, end),
+            None => {
+                // The prefix ends with the account data separator, so incrementing its last
+                // byte gives the smallest key past all contract data of the account.
+                let mut raw_end =
+                    trie_key_parsers::get_raw_prefix_for_contract_data(self.account_id, &[]);
+                *raw_end.last_mut().expect("prefix is never empty") += 1;
+                raw_end
+            }
+        };
+        let iter =
+            self.trie_update.iter_range(&raw_start, Some(&raw_end)).map_err(wrap_storage_error)?;
+        for item in iter {
+            let (raw_key, value) = item.map_err(wrap_storage_error)?;
+            let key =
+                trie_key_parsers::parse_data_key_from_contract_data_key(&raw_key, self.account_id)
+                    .map_err(|_e| {
+                        wrap_storage_error(StorageError::StorageInconsistentState(
+                            "Can't parse data key from raw key for ContractData".to_string(),
+                        ))
+                    })?;
+            if !visit(key, &value)? {
+                break;
+            }
+        }
+        Ok(())
+    }
+
     fn generate_data_id(&mut self) -> CryptoHash {
         let data_id = create_receipt_id_from_action_hash(
             self.current_protocol_version,
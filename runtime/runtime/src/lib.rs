@@ -16,6 +16,7 @@ pub use near_primitives;
 use near_primitives::account::Account;
 use near_primitives::checked_feature;
 use near_primitives::errors::{ActionError, ActionErrorKind, RuntimeError, TxExecutionError};
+use near_primitives::execution_report::{ChunkExecutionReport, ReceiptExecutionReport};
 use near_primitives::hash::CryptoHash;
 use near_primitives::receipt::{
     ActionReceipt, DataReceipt, DelayedReceiptIndices, Receipt, ReceiptEnum, ReceivedData,
@@ -106,6 +107,9 @@ pub struct ApplyState {
     pub migration_data: Arc<MigrationData>,
     /// Flags for migrations indicating whether they can be applied at this block
     pub migration_flags: MigrationFlags,
+    /// Whether to collect a `ChunkExecutionReport` with per-receipt execution statistics.
+    /// Only used for debugging, the report doesn't affect the state transition.
+    pub record_execution_report: bool,
 }
 
 /// Contains information to update validators accounts at the first block of a new epoch.
@@ -158,6 +162,8 @@ pub struct ApplyResult {
     pub proof: Option<PartialStorage>,
     pub delayed_receipts_count: u64,
     pub metrics: Option<metrics::ApplyMetrics>,
+    /// Present iff `ApplyState::record_execution_report` is set and the chunk is new.
+    pub execution_report: Option<ChunkExecutionReport>,
 }
 
 #[derive(Debug)]
@@ -171,6 +177,8 @@ pub struct ActionResult {
     pub new_receipts: Vec<Receipt>,
     pub validator_proposals: Vec<ValidatorStake>,
     pub profile: Box<ProfileDataV3>,
+    /// Balance burnt by the actions besides the gas, only counted if the receipt succeeds.
+    pub other_burnt_amount: Balance,
 }
 
 impl ActionResult {
@@ -199,9 +207,12 @@ impl ActionResult {
         if self.result.is_ok() {
             self.new_receipts.append(&mut next_result.new_receipts);
             self.validator_proposals.append(&mut next_result.validator_proposals);
+            self.other_burnt_amount =
+                safe_add_balance(self.other_burnt_amount, next_result.other_burnt_amount)?;
         } else {
             self.new_receipts.clear();
             self.validator_proposals.clear();
+            self.other_burnt_amount = 0;
         }
         Ok(())
     }
@@ -219,6 +230,7 @@ impl Default for ActionResult {
             new_receipts: vec![],
             validator_proposals: vec![],
             profile: Default::default(),
+            other_burnt_amount: 0,
         }
     }
 }
@@ -492,6 +504,35 @@ impl Runtime {
                     &mut result,
                 )?;
             }
+            Action::DeployContractWithCodeHashCheck(deploy_contract) => {
+                action_deploy_contract_with_code_hash_check(
+                    state_update,
+                    account.as_mut().expect(EXPECT_ACCOUNT_EXISTS),
+                    account_id,
+                    deploy_contract,
+                    apply_state,
+                    &mut result,
+                )?;
+            }
+            Action::DeployGlobalContract(deploy_contract) => {
+                action_deploy_global_contract(
+                    state_update,
+                    account.as_mut().expect(EXPECT_ACCOUNT_EXISTS),
+                    account_id,
+                    deploy_contract,
+                    apply_state,
+                    &mut result,
+                )?;
+            }
+            Action::UseGlobalContract(use_contract) => {
+                action_use_global_contract(
+                    state_update,
+                    account.as_mut().expect(EXPECT_ACCOUNT_EXISTS),
+                    account_id,
+                    use_contract,
+                    &mut result,
+                )?;
+            }
         };
         Ok(result)
     }
@@ -681,6 +722,8 @@ impl Runtime {
         // Committing or rolling back state.
         match &result.result {
             Ok(_) => {
+                stats.other_burnt_amount =
+                    safe_add_balance(stats.other_burnt_amount, result.other_burnt_amount)?;
                 state_update.commit(StateChangeCause::ReceiptProcessing {
                     receipt_hash: receipt.get_hash(),
                 });
@@ -1350,6 +1393,7 @@ impl Runtime {
                 proof,
                 delayed_receipts_count: delayed_receipts_indices.len(),
                 metrics: None,
+                execution_report: None,
             });
         }
 
@@ -1364,6 +1408,8 @@ impl Runtime {
         let mut total_gas_burnt = gas_used_for_migrations;
         let mut total_compute_usage = total_gas_burnt;
         let mut metrics = metrics::ApplyMetrics::default();
+        let mut execution_report =
+            apply_state.record_execution_report.then(ChunkExecutionReport::default);
 
         for signed_transaction in transactions {
             let (receipt, outcome_with_id) = self.process_transaction(
@@ -1412,6 +1458,10 @@ impl Runtime {
                 id = %receipt.receipt_id,
             )
             .entered();
+            let start_time = execution_report.is_some().then(|| {
+                near_vm_runner::reset_metrics();
+                std::time::Instant::now()
+            });
             let node_counter_before = state_update.trie().get_trie_nodes_count();
             let result = self.process_receipt(
                 state_update,
@@ -1425,7 +1475,38 @@ impl Runtime {
             let node_counter_after = state_update.trie().get_trie_nodes_count();
             tracing::trace!(target: "runtime", ?node_counter_before, ?node_counter_after);
 
-            if let Some(outcome_with_id) = result? {
+            let outcome = result?;
+            if let (Some(report), Some(start_time)) = (&mut execution_report, start_time) {
+                let outcome = outcome.as_ref().map(|outcome_with_id| &outcome_with_id.outcome);
+                let wall_time = start_time.elapsed();
+                let vm_metrics = near_vm_runner::report_metrics();
+                let lookups = vm_metrics.compiled_contract_cache_lookups;
+                let contract_cache_hit =
+                    (lookups > 0).then(|| vm_metrics.compiled_contract_cache_hits == lookups);
+                report.receipts.push(ReceiptExecutionReport {
+                    receipt_id: receipt.receipt_id,
+                    predecessor_id: receipt.predecessor_id.clone(),
+                    receiver_id: receipt.receiver_id.clone(),
+                    method_names: receipt_method_names(receipt),
+                    gas_burnt: outcome.map_or(0, |outcome| outcome.gas_burnt),
+                    compute_usage: outcome
+                        .and_then(|outcome| outcome.compute_usage)
+                        .unwrap_or_default(),
+                    wall_time_ns: wall_time.as_nanos() as u64,
+                    compilation_time_ns: vm_metrics.compilation_time.as_nanos() as u64,
+                    execution_time_ns: wall_time
+                        .saturating_sub(vm_metrics.compilation_time)
+                        .as_nanos() as u64,
+                    contract_cache_hit,
+                    trie_db_reads: node_counter_after
+                        .db_reads
+                        .saturating_sub(node_counter_before.db_reads),
+                    trie_mem_reads: node_counter_after
+                        .mem_reads
+                        .saturating_sub(node_counter_before.mem_reads),
+                });
+            }
+            if let Some(outcome_with_id) = outcome {
                 *total_gas_burnt =
                     safe_add_gas(*total_gas_burnt, outcome_with_id.outcome.gas_burnt)?;
                 *total_compute_usage = safe_add_compute(
@@ -1672,6 +1753,7 @@ impl Runtime {
             proof,
             delayed_receipts_count: delayed_receipts_indices.len(),
             metrics: Some(metrics),
+            execution_report,
         })
     }
 
@@ -1751,6 +1833,23 @@ fn action_transfer_or_implicit_account_creation(
     })
 }
 
+/// Returns the names of the methods called by the function call actions of the receipt.
+fn receipt_method_names(receipt: &Receipt) -> Vec<String> {
+    match &receipt.receipt {
+        ReceiptEnum::Action(action_receipt) | ReceiptEnum::PromiseYield(action_receipt) => {
+            action_receipt
+                .actions
+                .iter()
+                .filter_map(|action| match action {
+                    Action::FunctionCall(function_call) => Some(function_call.method_name.clone()),
+                    _ => None,
+                })
+                .collect()
+        }
+        ReceiptEnum::Data(_) | ReceiptEnum::PromiseResume(_) => vec![],
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
@@ -1761,7 +1860,8 @@ mod tests {
     use near_primitives::shard_layout::ShardUId;
     use near_primitives::test_utils::{account_new, MockEpochInfoProvider};
     use near_primitives::transaction::{
-        AddKeyAction, DeleteKeyAction, DeployContractAction, FunctionCallAction, TransferAction,
+        AddKeyAction, DeleteKeyAction, DeployContractAction, DeployContractWithCodeHashCheckAction,
+        DeployGlobalContractAction, FunctionCallAction, TransferAction, UseGlobalContractAction,
     };
     use near_primitives::types::MerkleHash;
     use near_primitives::version::PROTOCOL_VERSION;
@@ -1881,6 +1981,7 @@ mod tests {
             is_new_chunk: true,
             migration_data: Arc::new(MigrationData::default()),
             migration_flags: MigrationFlags::default(),
+            record_execution_report: false,
         };
 
         (runtime, tries, root, apply_state, signer, MockEpochInfoProvider::default())
@@ -1977,6 +2078,46 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_execution_report() {
+        let (runtime, tries, root, mut apply_state, _, epoch_info_provider) =
+            setup_runtime(to_yocto(1_000_000), 0, 10u64.pow(15));
+        let receipts = generate_receipts(to_yocto(1), 3);
+        let apply = |apply_state: &ApplyState| {
+            runtime
+                .apply(
+                    tries.get_trie_for_shard(ShardUId::single_shard(), root),
+                    &None,
+                    apply_state,
+                    &receipts,
+                    &[],
+                    &epoch_info_provider,
+                    Default::default(),
+                )
+                .unwrap()
+        };
+
+        assert!(apply(&apply_state).execution_report.is_none());
+
+        apply_state.record_execution_report = true;
+        let apply_result = apply(&apply_state);
+        let report = apply_result.execution_report.unwrap();
+        assert_eq!(report.receipts.len(), receipts.len());
+        for ((receipt_report, receipt), outcome) in
+            report.receipts.iter().zip(&receipts).zip(&apply_result.outcomes)
+        {
+            assert_eq!(receipt_report.receipt_id, receipt.receipt_id);
+            assert_eq!(receipt_report.predecessor_id, bob_account());
+            assert_eq!(receipt_report.receiver_id, alice_account());
+            assert!(receipt_report.method_names.is_empty());
+            assert_eq!(receipt_report.gas_burnt, outcome.outcome.gas_burnt);
+            // Transfers neither compile nor load contracts.
+            assert_eq!(receipt_report.compilation_time_ns, 0);
+            assert_eq!(receipt_report.execution_time_ns, receipt_report.wall_time_ns);
+            assert_eq!(receipt_report.contract_cache_hit, None);
+        }
+    }
+
     #[test]
     fn test_apply_delayed_receipts_feed_all_at_once() {
         let initial_balance = to_yocto(1_000_000);
@@ -2702,6 +2843,135 @@ mod tests {
             .expect("Compilation result should be non-empty");
     }
 
+    #[test]
+    fn test_deploy_contract_with_code_hash_check() {
+        let (runtime, tries, root, apply_state, signer, epoch_info_provider) =
+            setup_runtime(to_yocto(1_000_000), 0, 10u64.pow(15));
+
+        let code = near_test_contracts::trivial_contract().to_vec();
+        let deploy_with_expected_code_hash = |expected_code_hash| {
+            create_receipt_with_actions(
+                alice_account(),
+                signer.clone(),
+                vec![Action::DeployContractWithCodeHashCheck(Box::new(
+                    DeployContractWithCodeHashCheckAction {
+                        code: code.clone(),
+                        expected_code_hash,
+                    },
+                ))],
+            )
+        };
+        // The account starts out with the hash of an empty code.
+        let receipts = vec![
+            deploy_with_expected_code_hash(hash(&code)),
+            deploy_with_expected_code_hash(hash(&[])),
+        ];
+
+        let apply_result = runtime
+            .apply(
+                tries.get_trie_for_shard(ShardUId::single_shard(), root),
+                &None,
+                &apply_state,
+                &receipts,
+                &[],
+                &epoch_info_provider,
+                Default::default(),
+            )
+            .unwrap();
+        assert_matches!(
+            &apply_result.outcomes[0].outcome.status,
+            ExecutionStatus::Failure(TxExecutionError::ActionError(ActionError {
+                kind: ActionErrorKind::DeployContractCodeHashMismatch { .. },
+                ..
+            }))
+        );
+        assert_matches!(apply_result.outcomes[1].outcome.status, ExecutionStatus::SuccessValue(_));
+
+        let mut store_update = tries.store_update();
+        let root = tries.apply_all(
+            &apply_result.trie_changes,
+            ShardUId::single_shard(),
+            &mut store_update,
+        );
+        store_update.commit().unwrap();
+        let state_update = tries.new_trie_update(ShardUId::single_shard(), root);
+        let account = get_account(&state_update, &alice_account()).unwrap().unwrap();
+        assert_eq!(account.code_hash(), hash(&code));
+    }
+
+    #[test]
+    fn test_global_contract() {
+        let (runtime, tries, root, apply_state, signer, epoch_info_provider) =
+            setup_runtime(to_yocto(1_000_000), 0, 10u64.pow(15));
+
+        let code = near_test_contracts::trivial_contract().to_vec();
+        let code_hash = hash(&code);
+        let receipts = vec![
+            create_receipt_with_actions(
+                alice_account(),
+                signer.clone(),
+                vec![Action::UseGlobalContract(Box::new(UseGlobalContractAction { code_hash }))],
+            ),
+            create_receipt_with_actions(
+                alice_account(),
+                signer.clone(),
+                vec![Action::DeployGlobalContract(DeployGlobalContractAction {
+                    code: code.clone(),
+                })],
+            ),
+            create_receipt_with_actions(
+                alice_account(),
+                signer,
+                vec![Action::UseGlobalContract(Box::new(UseGlobalContractAction { code_hash }))],
+            ),
+        ];
+
+        let apply_result = runtime
+            .apply(
+                tries.get_trie_for_shard(ShardUId::single_shard(), root),
+                &None,
+                &apply_state,
+                &receipts,
+                &[],
+                &epoch_info_provider,
+                Default::default(),
+            )
+            .unwrap();
+        assert_matches!(
+            &apply_result.outcomes[0].outcome.status,
+            ExecutionStatus::Failure(TxExecutionError::ActionError(ActionError {
+                kind: ActionErrorKind::GlobalContractDoesNotExist { .. },
+                ..
+            }))
+        );
+        assert_matches!(apply_result.outcomes[1].outcome.status, ExecutionStatus::SuccessValue(_));
+        assert_matches!(apply_result.outcomes[2].outcome.status, ExecutionStatus::SuccessValue(_));
+        // The storage of the global code is burnt rather than staked by the deploying account.
+        assert_eq!(
+            apply_result.stats.other_burnt_amount,
+            code.len() as Balance * apply_state.config.storage_amount_per_byte()
+        );
+
+        let mut store_update = tries.store_update();
+        let root = tries.apply_all(
+            &apply_result.trie_changes,
+            ShardUId::single_shard(),
+            &mut store_update,
+        );
+        store_update.commit().unwrap();
+        let state_update = tries.new_trie_update(ShardUId::single_shard(), root);
+        let account = get_account(&state_update, &alice_account()).unwrap().unwrap();
+        assert_eq!(account.code_hash(), code_hash);
+        // The code is not stored on the account itself but is still resolved through its hash.
+        assert!(near_store::get_code(&state_update, &alice_account(), Some(code_hash))
+            .unwrap()
+            .is_none());
+        let resolved = near_store::get_account_code(&state_update, &alice_account(), code_hash)
+            .unwrap()
+            .unwrap();
+        assert_eq!(resolved.code(), code.as_slice());
+    }
+
     #[test]
     fn test_compute_usage_limit() {
         let (runtime, tries, root, mut apply_state, signer, epoch_info_provider) =
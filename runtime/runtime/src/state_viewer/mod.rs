@@ -15,7 +15,7 @@ use near_primitives::types::{AccountId, EpochInfoProvider, Gas};
 use near_primitives::version::PROTOCOL_VERSION;
 use near_primitives::views::{StateItem, ViewApplyState, ViewStateResult};
 use near_primitives_core::config::ViewConfig;
-use near_store::{get_access_key, get_account, get_code, TrieUpdate};
+use near_store::{get_access_key, get_account, get_account_code, get_code, TrieUpdate};
 use near_vm_runner::logic::ReturnData;
 use near_vm_runner::ContractCode;
 use std::{str, sync::Arc, time::Instant};
@@ -64,7 +64,7 @@ impl TrieViewer {
         account_id: &AccountId,
     ) -> Result<ContractCode, errors::ViewContractCodeError> {
         let account = self.view_account(state_update, account_id)?;
-        get_code(state_update, account_id, Some(account.code_hash()))?.ok_or_else(|| {
+        get_account_code(state_update, account_id, account.code_hash())?.ok_or_else(|| {
             errors::ViewContractCodeError::NoContractCode {
                 contract_account_id: account_id.clone(),
             }
@@ -205,6 +205,7 @@ impl TrieViewer {
             is_new_chunk: false,
             migration_data: Arc::new(MigrationData::default()),
             migration_flags: MigrationFlags::default(),
+            record_execution_report: false,
         };
         let action_receipt = ActionReceipt {
             signer_id: originator_id.clone(),
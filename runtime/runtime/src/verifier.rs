@@ -410,6 +410,20 @@ pub fn validate_action(
         Action::DeleteKey(_) => Ok(()),
         Action::DeleteAccount(a) => validate_delete_action(a),
         Action::Delegate(a) => validate_delegate_action(limit_config, a, current_protocol_version),
+        Action::DeployContractWithCodeHashCheck(a) => {
+            check_feature_enabled(
+                ProtocolFeature::DeployContractCodeHashCheck,
+                current_protocol_version,
+            )?;
+            validate_contract_size(limit_config, &a.code)
+        }
+        Action::DeployGlobalContract(a) => {
+            check_feature_enabled(ProtocolFeature::GlobalContracts, current_protocol_version)?;
+            validate_contract_size(limit_config, &a.code)
+        }
+        Action::UseGlobalContract(_) => {
+            check_feature_enabled(ProtocolFeature::GlobalContracts, current_protocol_version)
+        }
     }
 }
 
@@ -428,9 +442,17 @@ fn validate_deploy_contract_action(
     limit_config: &LimitConfig,
     action: &DeployContractAction,
 ) -> Result<(), ActionsValidationError> {
-    if action.code.len() as u64 > limit_config.max_contract_size {
+    validate_contract_size(limit_config, &action.code)
+}
+
+/// Checks that the given contract size doesn't exceed the limit.
+fn validate_contract_size(
+    limit_config: &LimitConfig,
+    code: &[u8],
+) -> Result<(), ActionsValidationError> {
+    if code.len() as u64 > limit_config.max_contract_size {
         return Err(ActionsValidationError::ContractSizeExceeded {
-            size: action.code.len() as u64,
+            size: code.len() as u64,
             limit: limit_config.max_contract_size,
         });
     }
@@ -534,7 +556,6 @@ fn validate_delete_action(action: &DeleteAccountAction) -> Result<(), ActionsVal
     Ok(())
 }
 
-#[cfg(feature = "protocol_feature_nonrefundable_transfer_nep491")]
 fn check_feature_enabled(
     feature: ProtocolFeature,
     current_protocol_version: ProtocolVersion,
@@ -568,7 +589,9 @@ mod tests {
     use near_primitives::hash::{hash, CryptoHash};
     use near_primitives::test_utils::account_new;
     use near_primitives::transaction::{
-        CreateAccountAction, DeleteAccountAction, DeleteKeyAction, StakeAction, TransferAction,
+        CreateAccountAction, DeleteAccountAction, DeleteKeyAction,
+        DeployContractWithCodeHashCheckAction, DeployGlobalContractAction, StakeAction,
+        TransferAction, UseGlobalContractAction,
     };
     use near_primitives::types::{AccountId, Balance, MerkleHash, StateChangeCause};
     use near_primitives::version::PROTOCOL_VERSION;
@@ -1850,6 +1873,48 @@ mod tests {
         .expect("valid action");
     }
 
+    #[test]
+    fn test_validate_action_deploy_contract_with_code_hash_check() {
+        let feature = ProtocolFeature::DeployContractCodeHashCheck;
+        let action = Action::DeployContractWithCodeHashCheck(Box::new(
+            DeployContractWithCodeHashCheckAction {
+                code: vec![1; 5],
+                expected_code_hash: CryptoHash::default(),
+            },
+        ));
+        assert_eq!(
+            validate_action(&test_limit_config(), &action, feature.protocol_version() - 1),
+            Err(ActionsValidationError::UnsupportedProtocolFeature {
+                protocol_feature: "DeployContractCodeHashCheck".to_string(),
+                version: feature.protocol_version(),
+            }),
+        );
+        validate_action(&test_limit_config(), &action, feature.protocol_version())
+            .expect("valid action");
+    }
+
+    #[test]
+    fn test_validate_action_global_contracts() {
+        let feature = ProtocolFeature::GlobalContracts;
+        let actions = [
+            Action::DeployGlobalContract(DeployGlobalContractAction { code: vec![1; 5] }),
+            Action::UseGlobalContract(Box::new(UseGlobalContractAction {
+                code_hash: CryptoHash::default(),
+            })),
+        ];
+        for action in &actions {
+            assert_eq!(
+                validate_action(&test_limit_config(), action, feature.protocol_version() - 1),
+                Err(ActionsValidationError::UnsupportedProtocolFeature {
+                    protocol_feature: "GlobalContracts".to_string(),
+                    version: feature.protocol_version(),
+                }),
+            );
+            validate_action(&test_limit_config(), action, feature.protocol_version())
+                .expect("valid action");
+        }
+    }
+
     #[test]
     fn test_delegate_action_must_be_only_one() {
         let signed_delegate_action = SignedDelegateAction {
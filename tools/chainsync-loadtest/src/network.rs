@@ -238,6 +238,10 @@ impl Network {
             transaction: noop().into_sender(),
             block_request: Sender::from_async_fn(|_| None),
             block_headers_request: Sender::from_async_fn(|_| None),
+            light_client_block_request: Sender::from_async_fn(|_| None),
+            epoch_block_producers_request: Sender::from_async_fn(|_| None),
+            epoch_sync_request: Sender::from_async_fn(|_| None),
+            epoch_sync_response: noop().into_sender(),
             block: Sender::from_async_fn(move |block: BlockResponse| {
                 blocks.get(&block.block.hash().clone()).map(|p| p.set(block.block));
             }),
@@ -265,6 +269,8 @@ impl Network {
             }),
             chunk_state_witness: noop().into_sender(),
             chunk_endorsement: noop().into_sender(),
+            partial_encoded_state_witness: noop().into_sender(),
+            partial_encoded_state_witness_forward: noop().into_sender(),
         }
     }
 }
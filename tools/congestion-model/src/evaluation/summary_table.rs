@@ -2,12 +2,17 @@ use crate::{Model, PGAS};
 
 pub fn print_summary_header() {
     println!(
-        "{:<25}{:<25}{:>25}{:>25}{:>25}",
-        "WORKLOAD", "STRATEGY", "BURNT GAS", "TRANSACTIONS FINISHED", "MAX QUEUE LEN",
+        "{:<25}{:<25}{:<25}{:>25}{:>25}{:>25}",
+        "WORKLOAD",
+        "STRATEGY",
+        "TX SELECTION",
+        "BURNT GAS",
+        "TRANSACTIONS FINISHED",
+        "MAX QUEUE LEN",
     );
 }
 
-pub fn print_summary_row(model: &Model, workload: &str, strategy: &str) {
+pub fn print_summary_row(model: &Model, workload: &str, strategy: &str, tx_selection: &str) {
     let queues = model.queue_lengths();
     let throughput = model.gas_throughput();
     let progress = model.progress();
@@ -19,7 +24,7 @@ pub fn print_summary_row(model: &Model, workload: &str, strategy: &str) {
     }
 
     println!(
-        "{workload:<25}{strategy:<25}{:>20} PGas{:>25}{:>25}",
+        "{workload:<25}{strategy:<25}{tx_selection:<25}{:>20} PGas{:>25}{:>25}",
         throughput.total / PGAS,
         progress.finished_transactions,
         max_queue_len
@@ -1,10 +1,12 @@
 mod evaluation;
 mod model;
+pub mod selection;
 pub mod strategy;
 pub mod workload;
 
 pub use evaluation::{summary_table, StatsWriter, TransactionStatus};
 pub use model::{Model, Queue, QueueId, Receipt, ShardId, TransactionId};
+pub use selection::TransactionSelection;
 pub use strategy::CongestionStrategy;
 pub use workload::{ReceiptDefinition, ReceiptId, TransactionBuilder};
 
@@ -14,6 +16,10 @@ pub(crate) use model::Transaction;
 /// even for long simulations.
 type GGas = u64;
 type Round = u64;
+type SignerId = u64;
+type AccessKeyId = u64;
+/// The account and access key signing a transaction.
+type TransactionSigner = (SignerId, AccessKeyId);
 
 pub const GGAS: GGas = 10u64.pow(0);
 pub const TGAS: GGas = 10u64.pow(3);
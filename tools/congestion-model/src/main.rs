@@ -1,13 +1,17 @@
 use chrono::Utc;
 use std::time::Duration;
 
+use congestion_model::selection::PoolSelection;
 use congestion_model::strategy::{GlobalTxStopShard, NewTxLast, NoQueueShard, SimpleBackpressure};
 use congestion_model::workload::{
-    AllForOneProducer, BalancedProducer, LinearImbalanceProducer, Producer,
+    AllForOneProducer, BalancedProducer, LinearImbalanceProducer, Producer, SpamProducer,
+};
+use congestion_model::{
+    summary_table, CongestionStrategy, Model, StatsWriter, TransactionSelection, PGAS,
 };
-use congestion_model::{summary_table, CongestionStrategy, Model, StatsWriter, PGAS};
 
 use clap::Parser;
+use near_chain_configs::TransactionSelectionStrategy;
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
@@ -27,17 +31,23 @@ struct Args {
     #[clap(long, default_value = "all")]
     strategy: String,
 
+    /// Can be used to select a single transaction selection strategy or "all"
+    /// to run all of them.
+    #[clap(long, default_value = "FIFO per group")]
+    tx_selection: String,
+
     /// If enabled the model will write stats into a csv file that can be used
     /// to visualize the evaluation of the model over time.
     #[clap(long, default_value = "false")]
     write_stats: bool,
 
     /// Optional path the file where the stats should be saved. By default
-    /// the stats will be saved to a file name with prefix "stats", the strategy
-    /// and workload name concatenated and ".csv" extension. This option can
-    /// only be used when a single strategy and a single workflow are selected
-    /// otherwise the stats from different evaluations would overwrite each
-    /// other.
+    /// the stats will be saved to a file name with prefix "stats", the workload,
+    /// strategy and transaction selection strategy name concatenated and
+    /// ".csv" extension. This option can only be used when a single strategy,
+    /// a single transaction selection strategy and a single workflow are
+    /// selected otherwise the stats from different evaluations would overwrite
+    /// each other.
     #[clap(long)]
     write_stats_filepath: Option<String>,
 }
@@ -49,23 +59,34 @@ fn main() {
 
     let workload_names = parse_workload_names(args.workload.as_ref());
     let strategy_names = parse_strategy_names(args.strategy.as_ref());
+    let tx_selection_names = parse_tx_selection_names(args.tx_selection.as_ref());
 
     if args.write_stats_filepath.is_some()
-        && (workload_names.len() != 1 || strategy_names.len() != 1)
+        && (workload_names.len() != 1 || strategy_names.len() != 1 || tx_selection_names.len() != 1)
     {
-        panic!("write_stats_filepath can only be used with single workload and strategy. Parsed {:?} workloads and {:?} strategies. ", workload_names, strategy_names);
+        panic!("write_stats_filepath can only be used with single workload, strategy and transaction selection strategy. Parsed {:?} workloads, {:?} strategies and {:?} transaction selection strategies. ", workload_names, strategy_names, tx_selection_names);
     }
 
     for workload_name in &workload_names {
         for strategy_name in &strategy_names {
-            let stats_writer = parse_stats_writer(
-                args.write_stats,
-                args.write_stats_filepath.clone(),
-                workload_name,
-                strategy_name,
-            );
-
-            run_model(&strategy_name, &workload_name, args.shards, args.rounds, stats_writer);
+            for tx_selection_name in &tx_selection_names {
+                let stats_writer = parse_stats_writer(
+                    args.write_stats,
+                    args.write_stats_filepath.clone(),
+                    workload_name,
+                    strategy_name,
+                    tx_selection_name,
+                );
+
+                run_model(
+                    &strategy_name,
+                    &tx_selection_name,
+                    &workload_name,
+                    args.shards,
+                    args.rounds,
+                    stats_writer,
+                );
+            }
         }
     }
 }
@@ -75,12 +96,14 @@ fn parse_stats_writer(
     write_stats_filepath: Option<String>,
     workload_name: &String,
     strategy_name: &String,
+    tx_selection_name: &String,
 ) -> StatsWriter {
     if !write_stats {
         return None;
     }
 
-    let default_path = format!("stats_{}_{}.csv", workload_name, strategy_name);
+    let default_path =
+        format!("stats_{}_{}_{}.csv", workload_name, strategy_name, tx_selection_name);
     let path = write_stats_filepath.unwrap_or(default_path);
     let stats_writer = Box::new(csv::Writer::from_path(path).unwrap());
     Some(stats_writer)
@@ -88,14 +111,16 @@ fn parse_stats_writer(
 
 fn run_model(
     strategy_name: &str,
+    tx_selection_name: &str,
     workload_name: &str,
     num_shards: usize,
     num_rounds: usize,
     mut stats_writer: StatsWriter,
 ) {
     let strategy = strategy(strategy_name, num_shards);
+    let tx_selection = tx_selection(tx_selection_name);
     let workload = workload(workload_name);
-    let mut model = Model::new(strategy, workload);
+    let mut model = Model::new(strategy, tx_selection, workload);
 
     // Set the start time to an half hour ago to make it visible by default in
     // grafana. Each round is 1 virtual second so hald an hour is good for
@@ -109,7 +134,7 @@ fn run_model(
         model.write_stats_values(&mut stats_writer, start_time, round);
         model.step();
     }
-    summary_table::print_summary_row(&model, workload_name, strategy_name);
+    summary_table::print_summary_row(&model, workload_name, strategy_name, tx_selection_name);
 }
 
 fn normalize_cmdline_arg(value: &str) -> String {
@@ -123,6 +148,7 @@ fn workload(workload_name: &str) -> Box<dyn Producer> {
         "All To One" => Box::new(AllForOneProducer::one_hop_only()),
         "Indirect All To One" => Box::<AllForOneProducer>::default(),
         "Linear Imbalance" => Box::<LinearImbalanceProducer>::default(),
+        "Spam" => Box::<SpamProducer>::default(),
         _ => panic!("unknown workload: {}", workload_name),
     }
 }
@@ -145,12 +171,24 @@ fn strategy(strategy_name: &str, num_shards: usize) -> Vec<Box<dyn CongestionStr
     result
 }
 
+// Add transaction selection strategies here to simulate them with `cargo run`.
+fn tx_selection(tx_selection_name: &str) -> Box<dyn TransactionSelection> {
+    let strategy = match tx_selection_name {
+        "FIFO per group" => TransactionSelectionStrategy::FifoPerGroup,
+        "Prepaid gas descending" => TransactionSelectionStrategy::PrepaidGasDescending,
+        "Fair share per signer" => TransactionSelectionStrategy::FairSharePerSigner,
+        _ => panic!("unknown transaction selection strategy: {}", tx_selection_name),
+    };
+    Box::new(PoolSelection::new(strategy))
+}
+
 fn parse_workload_names(workload_name: &str) -> Vec<String> {
     let available: Vec<String> = vec![
         "Balanced".to_string(),
         "All To One".to_string(),
         "Indirect All To One".to_string(),
         "Linear Imbalance".to_string(),
+        "Spam".to_string(),
     ];
 
     if workload_name == "all" {
@@ -185,6 +223,25 @@ fn parse_strategy_names(strategy_name: &str) -> Vec<String> {
     panic!("The requested strategy name did not match any available strategies. Requested strategy name {:?}, The available strategies are: {:?}", strategy_name, available);
 }
 
+fn parse_tx_selection_names(tx_selection_name: &str) -> Vec<String> {
+    let available: Vec<String> = vec![
+        "FIFO per group".to_string(),
+        "Prepaid gas descending".to_string(),
+        "Fair share per signer".to_string(),
+    ];
+
+    if tx_selection_name == "all" {
+        return available;
+    }
+
+    for name in &available {
+        if normalize_cmdline_arg(name.as_ref()) == normalize_cmdline_arg(tx_selection_name) {
+            return vec![name.to_string()];
+        }
+    }
+    panic!("The requested transaction selection strategy name did not match any available strategies. Requested name {:?}, The available strategies are: {:?}", tx_selection_name, available);
+}
+
 // for looking at more details during execution, call print_report
 #[allow(dead_code)]
 fn print_report(model: &Model) {
@@ -14,8 +14,9 @@ pub use transaction_registry::TransactionId;
 
 pub(crate) use transaction::Transaction;
 
+use crate::selection::PendingTransaction;
 use crate::workload::Producer;
-use crate::{CongestionStrategy, Round};
+use crate::{CongestionStrategy, Round, TransactionSelection};
 use std::collections::BTreeMap;
 use transaction_registry::TransactionRegistry;
 
@@ -31,6 +32,7 @@ pub struct Model {
     pub(crate) shard_ids: Vec<ShardId>,
     pub(crate) block_info: BTreeMap<ShardId, BlockInfo>,
     pub(crate) queues: QueueBundle,
+    pub(crate) tx_selection: Box<dyn TransactionSelection>,
 
     // Workload state
     pub(crate) transactions: TransactionRegistry,
@@ -43,6 +45,7 @@ pub struct ShardId(usize);
 impl Model {
     pub fn new(
         mut shards: Vec<Box<dyn CongestionStrategy>>,
+        tx_selection: Box<dyn TransactionSelection>,
         mut producer: Box<dyn Producer>,
     ) -> Self {
         let num_shards = shards.len();
@@ -63,6 +66,7 @@ impl Model {
             producer,
             round: 0,
             queues,
+            tx_selection,
         }
     }
 
@@ -77,6 +81,23 @@ impl Model {
             self.queues.incoming_transactions_mut(shard_id).push_back(tx_id);
         }
 
+        // Order the transactions in which chunk producers take them from their pool.
+        for &shard_id in &self.shard_ids {
+            let queue = self.queues.incoming_transactions_mut(shard_id);
+            let pending = queue
+                .drain(..)
+                .map(|id| {
+                    let tx = &self.transactions[id];
+                    PendingTransaction {
+                        id,
+                        signer: tx.signer,
+                        attached_gas: tx.initial_receipt_gas(),
+                    }
+                })
+                .collect();
+            *queue = self.tx_selection.order(pending).into();
+        }
+
         // Give each shard a chance to their computations and buffer all created outputs.
         let mut outgoing = vec![];
         let mut next_block = BTreeMap::new();
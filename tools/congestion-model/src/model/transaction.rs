@@ -1,4 +1,4 @@
-use crate::{GGas, ReceiptId, Round, ShardId, TransactionId};
+use crate::{GGas, ReceiptId, Round, ShardId, TransactionId, TransactionSigner};
 use std::collections::{HashMap, HashSet};
 
 /// Model-internal representation of a transaction, as in, the entire graph of
@@ -11,6 +11,7 @@ pub(crate) struct Transaction {
 
     /// Where the transaction is converted to the first receipt.
     pub(crate) sender_shard: ShardId,
+    pub(crate) signer: Option<TransactionSigner>,
     /// Where the transaction's first receipt is sent to.
     pub(crate) initial_receipt_receiver: ShardId,
 
@@ -0,0 +1,119 @@
+use crate::{GGas, SignerId, TransactionId, TransactionSigner};
+use near_chain_configs::TransactionSelectionStrategy;
+use near_pool::selection::{new_selector, SelectionGroup};
+use std::collections::{HashMap, VecDeque};
+
+/// Implement the order in which a chunk producer takes transactions from its
+/// transaction pool, like the `transaction_selection_strategy` of nearcore.
+///
+/// Before each round, the model reorders the incoming transactions of every
+/// shard with it. Congestion strategies then accept transactions from the front
+/// of the incoming transactions queue.
+pub trait TransactionSelection {
+    /// Returns the IDs of pending transactions in the order they should be
+    /// accepted. Pending transactions are given in the order they arrived.
+    fn order(&mut self, pending: Vec<PendingTransaction>) -> Vec<TransactionId>;
+}
+
+/// A transaction in the incoming transactions queue of a shard.
+#[derive(Clone, Copy, Debug)]
+pub struct PendingTransaction {
+    pub id: TransactionId,
+    pub signer: Option<TransactionSigner>,
+    /// Gas attached to the transaction, which is what nearcore ranks
+    /// transactions by with `prepaid_gas_descending`.
+    pub attached_gas: GGas,
+}
+
+/// Orders transactions with the selector nearcore uses for the given
+/// `transaction_selection_strategy`.
+///
+/// Transactions are grouped by signer access key like in the pool of nearcore,
+/// and the groups are pulled in the order of their first pending transaction,
+/// where nearcore pulls them in a random order.
+pub struct PoolSelection {
+    strategy: TransactionSelectionStrategy,
+}
+
+impl PoolSelection {
+    pub fn new(strategy: TransactionSelectionStrategy) -> Self {
+        Self { strategy }
+    }
+}
+
+impl TransactionSelection for PoolSelection {
+    fn order(&mut self, pending: Vec<PendingTransaction>) -> Vec<TransactionId> {
+        let mut groups: VecDeque<PendingGroup> = VecDeque::new();
+        let mut group_index = HashMap::new();
+        for tx in pending {
+            match tx.signer {
+                Some((signer, access_key)) => {
+                    let index = *group_index.entry((signer, access_key)).or_insert_with(|| {
+                        groups.push_back(PendingGroup {
+                            signer: GroupSigner::Account(signer),
+                            transactions: VecDeque::new(),
+                        });
+                        groups.len() - 1
+                    });
+                    groups[index].transactions.push_back(tx);
+                }
+                None => groups.push_back(PendingGroup {
+                    signer: GroupSigner::Unsigned(tx.id),
+                    transactions: VecDeque::from([tx]),
+                }),
+            }
+        }
+
+        // Take one transaction at a time, like `PoolIteratorWrapper` in nearcore.
+        let mut selector = new_selector::<PendingGroup>(self.strategy);
+        let mut pulled: Vec<PendingGroup> = vec![];
+        let mut out = vec![];
+        loop {
+            let pool_drained = groups.is_empty();
+            match selector.select(&pulled, pool_drained) {
+                Some(index) => {
+                    if let Some(tx) = pulled[index].transactions.pop_front() {
+                        out.push(tx.id);
+                    }
+                }
+                None if pool_drained => break,
+                None => {
+                    let group = groups.pop_front().expect("the pool is checked to be not empty");
+                    selector.add_group(pulled.len(), &group);
+                    pulled.push(group);
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Signer of a group of pending transactions. Transactions without a signer
+/// are treated as signed by accounts which sign nothing else.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum GroupSigner {
+    Account(SignerId),
+    Unsigned(TransactionId),
+}
+
+/// Pending transactions of the same signer access key, in arrival order.
+struct PendingGroup {
+    signer: GroupSigner,
+    transactions: VecDeque<PendingTransaction>,
+}
+
+impl SelectionGroup for PendingGroup {
+    type Signer = GroupSigner;
+
+    fn signer(&self) -> Option<GroupSigner> {
+        (!self.transactions.is_empty()).then_some(self.signer)
+    }
+
+    fn next_prepaid_gas(&self) -> Option<GGas> {
+        self.transactions.front().map(|tx| tx.attached_gas)
+    }
+
+    fn is_empty(&self) -> bool {
+        self.transactions.is_empty()
+    }
+}
@@ -1,12 +1,14 @@
 mod all_for_one;
 mod balanced;
 mod linear_imbalance;
+mod spam;
 mod transaction_builder;
 mod utils;
 
 pub use all_for_one::AllForOneProducer;
 pub use balanced::BalancedProducer;
 pub use linear_imbalance::LinearImbalanceProducer;
+pub use spam::SpamProducer;
 pub use transaction_builder::{ReceiptDefinition, ReceiptId, TransactionBuilder};
 
 use crate::{Round, ShardId};
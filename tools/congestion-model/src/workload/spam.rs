@@ -0,0 +1,81 @@
+use crate::{GGas, ReceiptDefinition, ShardId, TransactionBuilder, TGAS};
+
+use super::{utils, Producer};
+
+/// One spammer on each shard floods its shard with cheap transactions from
+/// many access keys, while many users each send one transaction with plenty of
+/// gas attached.
+///
+/// Together the transactions need more than the chunk space for transactions,
+/// so the transaction selection strategy decides whether the users or the
+/// spammer get their transactions in.
+pub struct SpamProducer {
+    pub receipt_size: u64,
+    pub conversion_gas: GGas,
+    pub spam_attached_gas: GGas,
+    pub user_attached_gas: GGas,
+    pub n_spam_access_keys: u64,
+    pub n_spam_tx_per_access_key: usize,
+    pub n_users: u64,
+}
+
+impl Producer for SpamProducer {
+    fn init(&mut self, _shards: &[ShardId]) {}
+
+    fn produce_transactions(
+        &mut self,
+        _round: crate::Round,
+        shards: &[ShardId],
+        tx_factory: &mut dyn FnMut(ShardId) -> TransactionBuilder,
+    ) -> Vec<TransactionBuilder> {
+        let mut out = vec![];
+        for (shard_index, &shard) in shards.iter().enumerate() {
+            // Signers of each shard are distinct, the spammer is the first one.
+            let first_signer = shard_index as u64 * (self.n_users + 1);
+            for _ in 0..self.n_spam_tx_per_access_key {
+                for access_key in 0..self.n_spam_access_keys {
+                    let mut tx = tx_factory(shard);
+                    tx.set_signer(first_signer, access_key);
+                    self.produce_one_tx(shard, self.spam_attached_gas, &mut tx);
+                    out.push(tx);
+                }
+            }
+            for user in 1..=self.n_users {
+                let mut tx = tx_factory(shard);
+                tx.set_signer(first_signer + user, 0);
+                self.produce_one_tx(shard, self.user_attached_gas, &mut tx);
+                out.push(tx);
+            }
+        }
+        out
+    }
+}
+
+impl SpamProducer {
+    fn produce_one_tx(&self, receiver: ShardId, attached_gas: GGas, tx: &mut TransactionBuilder) {
+        let receipt = ReceiptDefinition {
+            receiver,
+            size: self.receipt_size,
+            attached_gas,
+            execution_gas: attached_gas,
+        };
+        let first = tx.add_first_receipt(receipt, self.conversion_gas);
+        tx.new_outgoing_receipt(first, utils::refund_receipt(tx.sender_shard()));
+    }
+}
+
+impl Default for SpamProducer {
+    fn default() -> Self {
+        // 200 spam and 20 user transactions per shard and round need 1100 TGas
+        // for conversion, more than twice the 500 TGas available.
+        Self {
+            receipt_size: 1024,
+            conversion_gas: 5 * TGAS,
+            spam_attached_gas: 5 * TGAS,
+            user_attached_gas: 20 * TGAS,
+            n_spam_access_keys: 20,
+            n_spam_tx_per_access_key: 10,
+            n_users: 20,
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::{GGas, Receipt, ShardId, Transaction, TransactionId};
+use crate::{
+    AccessKeyId, GGas, Receipt, ShardId, SignerId, Transaction, TransactionId, TransactionSigner,
+};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct ReceiptId(TransactionId, usize);
@@ -25,6 +27,7 @@ pub struct TransactionBuilder {
     pub id: TransactionId,
     /// Where the transaction is converted to the first receipt.
     sender_shard: ShardId,
+    signer: Option<TransactionSigner>,
     /// Gas burnt for converting the transaction to the first receipt.
     tx_conversion_cost: GGas,
     /// All receipts that belong to the transitive receipts DAG rooted at the transaction.
@@ -41,6 +44,7 @@ impl TransactionBuilder {
             id,
             tx_conversion_cost: 0,
             sender_shard,
+            signer: None,
             receipts: vec![],
             outgoing: vec![],
             dependencies: vec![],
@@ -107,6 +111,15 @@ impl TransactionBuilder {
         self.dependencies[successor.1].push(predecessor);
     }
 
+    /// Set the account and access key signing the transaction.
+    ///
+    /// Transaction selection strategies group transactions by their signer.
+    /// Transactions without a signer are treated as signed by accounts which
+    /// sign nothing else.
+    pub fn set_signer(&mut self, signer: SignerId, access_key: AccessKeyId) {
+        self.signer = Some((signer, access_key));
+    }
+
     /// Unique ID of the transaction within the model execution.
     pub fn id(&self) -> TransactionId {
         self.id
@@ -170,6 +183,7 @@ impl TransactionBuilder {
         Transaction {
             id: self.id,
             sender_shard: self.sender_shard,
+            signer: self.signer,
             initial_receipt_receiver: receipts[&initial_receipt].receiver,
             initial_receipt_gas: receipts[&initial_receipt].attached_gas,
             initial_receipt,
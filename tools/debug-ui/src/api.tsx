@@ -87,6 +87,14 @@ export interface PeerInfoView {
     connection_established_time_millis: number;
     is_outbound_peer: boolean;
     nonce: number;
+    send_classes: PeerSendClassView[];
+}
+
+export interface PeerSendClassView {
+    class: string;
+    sent_bytes: number;
+    delayed_messages: number;
+    dropped_messages: number;
 }
 
 export interface KnownProducerView {
@@ -234,6 +242,7 @@ export type ValidatorKickoutReason =
 
 export interface PeerStoreView {
     peer_states: KnownPeerStateView[];
+    blacklist: string[];
 }
 
 export interface KnownPeerStateView {
@@ -243,6 +243,25 @@ const stateTransitionData = {
     array: chunkStateTransitionData,
 };
 
+const receiptExecutionReport = {
+    struct: {
+        receipt_id: receiptId,
+        predecessor_id: accountId,
+        receiver_id: accountId,
+    },
+    titleKey: 'receiver_id',
+};
+
+const chunkExecutionReport = {
+    struct: {
+        receipts: { array: receiptExecutionReport },
+    },
+};
+
+const chunkExecutionReports = {
+    array: chunkExecutionReport,
+};
+
 const oneValidatorAssignment = {
     struct: {
         account_id: accountId,
@@ -264,6 +283,7 @@ export const fieldSemantics: Record<EntityType, FieldSemantic> = {
     BlockHash: blockHash,
     BlockHeader: blockHeader,
     Chunk: chunk,
+    ChunkExecutionReport: chunkExecutionReports,
     EpochInfo: epochInfo,
     ExecutionOutcome: executionOutcome,
     FlatState: undefined,
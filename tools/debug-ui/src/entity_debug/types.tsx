@@ -22,6 +22,7 @@ export type EntityType =
     | 'BlockHash'
     | 'BlockHeader'
     | 'Chunk'
+    | 'ChunkExecutionReport'
     | 'EpochInfo'
     | 'ExecutionOutcome'
     | 'FlatState'
@@ -125,6 +126,7 @@ export type EntityQuery = {
     BlockHashByHeight?: { block_height: number };
     BlockHeaderByHash?: { block_hash: string };
     ChunkByHash?: { chunk_hash: string };
+    ChunkExecutionReportByBlockHash?: { block_hash: string };
     EpochInfoByEpochId?: { epoch_id: string };
     FlatStateByTrieKey?: { trie_key: string };
     FlatStateChangesByBlockHash?: { block_hash: string };
@@ -161,6 +163,7 @@ export const entityQueryTypes: EntityQueryType[] = [
     'BlockHashByHeight',
     'BlockHeaderByHash',
     'ChunkByHash',
+    'ChunkExecutionReportByBlockHash',
     'EpochInfoByEpochId',
     'FlatStateByTrieKey',
     'FlatStateChangesByBlockHash',
@@ -221,6 +224,7 @@ export const entityQueryKeyTypes: Record<EntityQueryType, EntityQueryKeySpec[]>
     BlockHashByHeight: [queryKey('block_height')],
     BlockHeaderByHash: [queryKey('block_hash')],
     ChunkByHash: [queryKey('chunk_hash')],
+    ChunkExecutionReportByBlockHash: [queryKey('block_hash')],
     EpochInfoByEpochId: [queryKey('epoch_id')],
     FlatStateByTrieKey: [queryKey('trie_key'), implicitQueryKey('shard_uid')],
     FlatStateChangesByBlockHash: [queryKey('block_hash'), implicitQueryKey('shard_uid')],
@@ -255,6 +259,7 @@ export const entityQueryOutputType: Record<EntityQueryType, EntityType> = {
     BlockHashByHeight: 'BlockHash',
     BlockHeaderByHash: 'BlockHeader',
     ChunkByHash: 'Chunk',
+    ChunkExecutionReportByBlockHash: 'ChunkExecutionReport',
     EpochInfoByEpochId: 'EpochInfo',
     FlatStateByTrieKey: 'FlatState',
     FlatStateChangesByBlockHash: 'FlatStateChanges',
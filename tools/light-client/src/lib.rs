@@ -0,0 +1,91 @@
+//! Minimal light client, which follows the chain over the P2P protocol
+//! (`LightClientBlockRequest` and `EpochBlockProducersRequest` messages), rather than
+//! over the JSON-RPC of a trusted node.
+//!
+//! The client connects to a single full node, bootstraps from a trusted block hash,
+//! and then verifies every `LightClientBlockView` it receives before advancing its head.
+use anyhow::Context;
+use near_network::raw::{Connection, DirectMessage, Message};
+use near_primitives::hash::CryptoHash;
+use std::time::Duration;
+
+mod verify;
+
+pub use verify::{Error, LightClient};
+
+/// How long to wait for the response to a request, before giving up on the peer.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Sends `request` to the peer and waits for the first message accepted by `select`.
+/// Other messages received in the meantime are dropped.
+/// Fails if no such message arrives within `REQUEST_TIMEOUT`.
+async fn request<T>(
+    conn: &mut Connection,
+    request: DirectMessage,
+    mut select: impl FnMut(DirectMessage) -> Option<T>,
+) -> anyhow::Result<T> {
+    conn.send_message(request).await?;
+    let response = async {
+        loop {
+            if let (Message::Direct(msg), _) = conn.recv().await? {
+                if let Some(resp) = select(msg) {
+                    return Ok(resp);
+                }
+            }
+        }
+    };
+    tokio::time::timeout(REQUEST_TIMEOUT, response)
+        .await
+        .with_context(|| format!("peer didn't respond within {REQUEST_TIMEOUT:?}"))?
+}
+
+/// Initializes a light client, with the block `trusted_hash` as its head.
+/// The block producers of the next epoch are fetched from the peer and verified
+/// against the header of the trusted block.
+pub async fn bootstrap(
+    conn: &mut Connection,
+    trusted_hash: CryptoHash,
+) -> anyhow::Result<LightClient> {
+    let block_producers =
+        request(conn, DirectMessage::EpochBlockProducersRequest(trusted_hash), |msg| match msg {
+            DirectMessage::EpochBlockProducersResponse(resp) if resp.block_hash == trusted_hash => {
+                Some(resp.block_producers)
+            }
+            _ => None,
+        })
+        .await?
+        .with_context(|| format!("peer doesn't know the block {trusted_hash}"))?;
+    Ok(LightClient::new(&trusted_hash, block_producers)?)
+}
+
+/// Advances the head of the light client, until the peer has no newer block which could be
+/// verified. Returns the number of verified blocks.
+pub async fn sync(conn: &mut Connection, client: &mut LightClient) -> anyhow::Result<usize> {
+    let mut verified = 0;
+    loop {
+        let last_block_hash = client.head_hash();
+        let request_msg = DirectMessage::LightClientBlockRequest(last_block_hash);
+        let block = request(conn, request_msg, |msg| match msg {
+            DirectMessage::LightClientBlockResponse(resp)
+                if resp.last_block_hash == last_block_hash =>
+            {
+                Some(resp.block)
+            }
+            _ => None,
+        })
+        .await?;
+        let Some(block) = block else {
+            return Ok(verified);
+        };
+        match client.validate_and_update_head(&block) {
+            Ok(()) => verified += 1,
+            // The peer's head is still in the epoch of the trusted block,
+            // whose block producers are not known to the client.
+            Err(Error::UnknownEpoch(epoch_id)) if epoch_id == client.head().inner_lite.epoch_id => {
+                return Ok(verified);
+            }
+            Err(err) => return Err(err).context("peer sent an invalid light client block"),
+        }
+        tracing::debug!(target: "light-client", height = client.head().inner_lite.height, head = %client.head_hash(), "verified a light client block");
+    }
+}
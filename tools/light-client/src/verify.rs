@@ -0,0 +1,325 @@
+//! Verification of the light client blocks, as described in
+//! https://nomicon.io/ChainSpec/LightClient
+use near_network::types::EpochBlockProducers;
+use near_primitives::block::{Approval, ApprovalInner};
+use near_primitives::hash::CryptoHash;
+use near_primitives::merkle::combine_hash;
+use near_primitives::types::{AccountId, Balance, BlockHeight};
+use near_primitives::views::validator_stake_view::ValidatorStakeView;
+use near_primitives::views::{LightClientBlockLiteView, LightClientBlockView};
+use std::collections::HashMap;
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    #[error("header has hash {got}, while {want} was expected")]
+    UnexpectedHeader { got: CryptoHash, want: CryptoHash },
+    #[error("block producers don't match next_bp_hash")]
+    BlockProducersMismatch,
+    #[error("block at height {height} is not newer than the head at height {head_height}")]
+    NotNewer { height: BlockHeight, head_height: BlockHeight },
+    #[error("block from epoch {0} which is neither the epoch of the head nor the next one")]
+    UnexpectedEpoch(CryptoHash),
+    #[error("block producers of epoch {0} are not known")]
+    UnknownEpoch(CryptoHash),
+    #[error("the first block of the next epoch doesn't contain next_bps")]
+    MissingNextBlockProducers,
+    #[error("block has {got} approvals, while there are {want} block producers")]
+    ApprovalsCountMismatch { got: usize, want: usize },
+    #[error("invalid approval signature of {0}")]
+    InvalidSignature(AccountId),
+    #[error("approved stake {approved} is not more than 2/3 of the total stake {total}")]
+    NotEnoughStake { approved: Balance, total: Balance },
+}
+
+/// Hash of the ordered list of block producers, as stored in `next_bp_hash` of the block header.
+fn bp_hash(bps: &[ValidatorStakeView]) -> CryptoHash {
+    CryptoHash::hash_borsh_iter(bps.iter().cloned().map(ValidatorStakeView::into_validator_stake))
+}
+
+/// State of a light client: the last verified block and the block producers
+/// of its epoch and the next one.
+pub struct LightClient {
+    head: LightClientBlockLiteView,
+    /// Ordered block producers by epoch id.
+    epoch_block_producers: HashMap<CryptoHash, Vec<ValidatorStakeView>>,
+}
+
+impl LightClient {
+    /// Initializes the light client with the block `trusted_hash` as the head.
+    /// `bps` is the response of a peer to the `EpochBlockProducersRequest` for that block.
+    ///
+    /// Note that only the block producers of the next epoch are known this way,
+    /// so the blocks of the epoch of `trusted_hash` cannot be verified.
+    pub fn new(trusted_hash: &CryptoHash, bps: EpochBlockProducers) -> Result<Self, Error> {
+        let got = bps.header.hash();
+        if &got != trusted_hash {
+            return Err(Error::UnexpectedHeader { got, want: *trusted_hash });
+        }
+        if bp_hash(&bps.next_bps) != bps.header.inner_lite.next_bp_hash {
+            return Err(Error::BlockProducersMismatch);
+        }
+        let next_epoch_id = bps.header.inner_lite.next_epoch_id;
+        Ok(Self { head: bps.header, epoch_block_producers: [(next_epoch_id, bps.next_bps)].into() })
+    }
+
+    pub fn head(&self) -> &LightClientBlockLiteView {
+        &self.head
+    }
+
+    pub fn head_hash(&self) -> CryptoHash {
+        self.head.hash()
+    }
+
+    /// Verifies the block and makes it the new head.
+    pub fn validate_and_update_head(&mut self, block: &LightClientBlockView) -> Result<(), Error> {
+        let head = &self.head.inner_lite;
+        if block.inner_lite.height <= head.height {
+            return Err(Error::NotNewer {
+                height: block.inner_lite.height,
+                head_height: head.height,
+            });
+        }
+        let epoch_id = block.inner_lite.epoch_id;
+        if epoch_id != head.epoch_id && epoch_id != head.next_epoch_id {
+            return Err(Error::UnexpectedEpoch(epoch_id));
+        }
+        if epoch_id == head.next_epoch_id && block.next_bps.is_none() {
+            return Err(Error::MissingNextBlockProducers);
+        }
+        let bps = self.epoch_block_producers.get(&epoch_id).ok_or(Error::UnknownEpoch(epoch_id))?;
+
+        // The block is approved by the block producers with the approvals of the block after
+        // the next one, which endorse the next block.
+        let header = LightClientBlockLiteView {
+            prev_block_hash: block.prev_block_hash,
+            inner_rest_hash: block.inner_rest_hash,
+            inner_lite: block.inner_lite.clone(),
+        };
+        let next_block_hash = combine_hash(&block.next_block_inner_hash, &header.hash());
+        let approval_message = Approval::get_data_for_sig(
+            &ApprovalInner::Endorsement(next_block_hash),
+            block.inner_lite.height + 2,
+        );
+        // Approvals are ordered like the block producers, so a shorter list would silently
+        // exclude the stake of the remaining block producers from the total.
+        if block.approvals_after_next.len() != bps.len() {
+            return Err(Error::ApprovalsCountMismatch {
+                got: block.approvals_after_next.len(),
+                want: bps.len(),
+            });
+        }
+        let total: Balance = bps.iter().map(|ValidatorStakeView::V1(bp)| bp.stake).sum();
+        let mut approved: Balance = 0;
+        for (signature, bp) in block.approvals_after_next.iter().zip(bps) {
+            let ValidatorStakeView::V1(bp) = bp;
+            let Some(signature) = signature else { continue };
+            if !signature.verify(&approval_message, &bp.public_key) {
+                return Err(Error::InvalidSignature(bp.account_id.clone()));
+            }
+            approved += bp.stake;
+        }
+        if approved <= total * 2 / 3 {
+            return Err(Error::NotEnoughStake { approved, total });
+        }
+
+        if let Some(next_bps) = &block.next_bps {
+            if bp_hash(next_bps) != block.inner_lite.next_bp_hash {
+                return Err(Error::BlockProducersMismatch);
+            }
+            self.epoch_block_producers.insert(block.inner_lite.next_epoch_id, next_bps.clone());
+        }
+        self.head = header;
+        let head = &self.head.inner_lite;
+        self.epoch_block_producers
+            .retain(|id, _| id == &head.epoch_id || id == &head.next_epoch_id);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{bp_hash, Error, LightClient};
+    use near_crypto::KeyType;
+    use near_network::types::EpochBlockProducers;
+    use near_primitives::block::ApprovalInner;
+    use near_primitives::hash::{hash, CryptoHash};
+    use near_primitives::merkle::combine_hash;
+    use near_primitives::types::validator_stake::ValidatorStake;
+    use near_primitives::types::{AccountId, BlockHeight};
+    use near_primitives::validator_signer::{InMemoryValidatorSigner, ValidatorSigner};
+    use near_primitives::views::validator_stake_view::ValidatorStakeView;
+    use near_primitives::views::{
+        BlockHeaderInnerLiteView, LightClientBlockLiteView, LightClientBlockView,
+    };
+
+    struct Epoch {
+        id: CryptoHash,
+        signers: Vec<InMemoryValidatorSigner>,
+        bps: Vec<ValidatorStakeView>,
+    }
+
+    fn make_epoch(index: usize) -> Epoch {
+        let signers: Vec<_> = (0..3)
+            .map(|i| {
+                let account_id: AccountId = format!("bp{index}-{i}").parse().unwrap();
+                InMemoryValidatorSigner::from_seed(
+                    account_id.clone(),
+                    KeyType::ED25519,
+                    account_id.as_str(),
+                )
+            })
+            .collect();
+        let bps = signers
+            .iter()
+            .map(|s| ValidatorStake::new_v1(s.validator_id().clone(), s.public_key(), 100).into())
+            .collect();
+        Epoch { id: hash(&index.to_le_bytes()), signers, bps }
+    }
+
+    fn make_inner_lite(
+        height: BlockHeight,
+        epoch: &Epoch,
+        next: &Epoch,
+    ) -> BlockHeaderInnerLiteView {
+        BlockHeaderInnerLiteView {
+            height,
+            epoch_id: epoch.id,
+            next_epoch_id: next.id,
+            prev_state_root: CryptoHash::default(),
+            outcome_root: CryptoHash::default(),
+            timestamp: 0,
+            timestamp_nanosec: 0,
+            next_bp_hash: bp_hash(&next.bps),
+            block_merkle_root: CryptoHash::default(),
+        }
+    }
+
+    /// Makes a block of `epoch` at `height`, approved by the first `approvals` block producers.
+    fn make_block(
+        height: BlockHeight,
+        epoch: &Epoch,
+        next: &Epoch,
+        approvals: usize,
+    ) -> LightClientBlockView {
+        let header = LightClientBlockLiteView {
+            prev_block_hash: hash(&height.to_le_bytes()),
+            inner_rest_hash: CryptoHash::default(),
+            inner_lite: make_inner_lite(height, epoch, next),
+        };
+        let next_block_inner_hash = hash(&(height + 1).to_le_bytes());
+        let next_block_hash = combine_hash(&next_block_inner_hash, &header.hash());
+        let approvals_after_next = epoch
+            .signers
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                (i < approvals).then(|| {
+                    Box::new(
+                        s.sign_approval(&ApprovalInner::Endorsement(next_block_hash), height + 2)
+                            .unwrap(),
+                    )
+                })
+            })
+            .collect();
+        LightClientBlockView {
+            prev_block_hash: header.prev_block_hash,
+            next_block_inner_hash,
+            inner_lite: header.inner_lite,
+            inner_rest_hash: header.inner_rest_hash,
+            next_bps: Some(next.bps.clone()),
+            approvals_after_next,
+        }
+    }
+
+    #[test]
+    fn bootstrap() {
+        let (e0, e1) = (make_epoch(0), make_epoch(1));
+        let header = LightClientBlockLiteView {
+            prev_block_hash: CryptoHash::default(),
+            inner_rest_hash: CryptoHash::default(),
+            inner_lite: make_inner_lite(10, &e0, &e1),
+        };
+        let trusted_hash = header.hash();
+        let bps = EpochBlockProducers { header: header.clone(), next_bps: e1.bps.clone() };
+        let client = LightClient::new(&trusted_hash, bps.clone()).unwrap();
+        assert_eq!(client.head_hash(), trusted_hash);
+
+        let want = CryptoHash::default();
+        assert_eq!(
+            LightClient::new(&want, bps).err(),
+            Some(Error::UnexpectedHeader { got: trusted_hash, want })
+        );
+        let bps = EpochBlockProducers { header, next_bps: e0.bps };
+        assert_eq!(LightClient::new(&trusted_hash, bps).err(), Some(Error::BlockProducersMismatch));
+    }
+
+    #[test]
+    fn validate_blocks() {
+        let epochs: Vec<_> = (0..4).map(make_epoch).collect();
+        let header = LightClientBlockLiteView {
+            prev_block_hash: CryptoHash::default(),
+            inner_rest_hash: CryptoHash::default(),
+            inner_lite: make_inner_lite(10, &epochs[0], &epochs[1]),
+        };
+        let bps = EpochBlockProducers { header: header.clone(), next_bps: epochs[1].bps.clone() };
+        let mut client = LightClient::new(&header.hash(), bps).unwrap();
+
+        // Block producers of the epoch of the trusted block are not known.
+        let block = make_block(11, &epochs[0], &epochs[1], 3);
+        assert_eq!(client.validate_and_update_head(&block), Err(Error::UnknownEpoch(epochs[0].id)));
+        // Epochs cannot be skipped.
+        let block = make_block(30, &epochs[2], &epochs[3], 3);
+        assert_eq!(
+            client.validate_and_update_head(&block),
+            Err(Error::UnexpectedEpoch(epochs[2].id))
+        );
+
+        // 2/3 of the stake is not enough.
+        let block = make_block(20, &epochs[1], &epochs[2], 2);
+        assert_eq!(
+            client.validate_and_update_head(&block),
+            Err(Error::NotEnoughStake { approved: 200, total: 300 })
+        );
+        // Approvals have to endorse the block after the verified one.
+        let mut block = make_block(20, &epochs[1], &epochs[2], 3);
+        block.approvals_after_next[1] =
+            make_block(21, &epochs[1], &epochs[2], 3).approvals_after_next[1].clone();
+        assert_eq!(
+            client.validate_and_update_head(&block),
+            Err(Error::InvalidSignature(epochs[1].signers[1].validator_id().clone()))
+        );
+        // Every block producer has to be listed in the approvals, approving or not.
+        let mut block = make_block(20, &epochs[1], &epochs[2], 3);
+        block.approvals_after_next.truncate(2);
+        assert_eq!(
+            client.validate_and_update_head(&block),
+            Err(Error::ApprovalsCountMismatch { got: 2, want: 3 })
+        );
+        let mut block = make_block(20, &epochs[1], &epochs[2], 3);
+        block.approvals_after_next.push(None);
+        assert_eq!(
+            client.validate_and_update_head(&block),
+            Err(Error::ApprovalsCountMismatch { got: 4, want: 3 })
+        );
+        // next_bps have to match the header.
+        let mut block = make_block(20, &epochs[1], &epochs[2], 3);
+        block.next_bps = Some(epochs[3].bps.clone());
+        assert_eq!(client.validate_and_update_head(&block), Err(Error::BlockProducersMismatch));
+
+        let block = make_block(20, &epochs[1], &epochs[2], 3);
+        client.validate_and_update_head(&block).unwrap();
+        assert_eq!(client.head().inner_lite, block.inner_lite);
+        assert_eq!(
+            client.validate_and_update_head(&block),
+            Err(Error::NotNewer { height: 20, head_height: 20 })
+        );
+        // Blocks of the same epoch don't need next_bps.
+        let mut block = make_block(21, &epochs[1], &epochs[2], 3);
+        block.next_bps = None;
+        client.validate_and_update_head(&block).unwrap();
+        // The next epoch is verified with the block producers from the previous block.
+        let block = make_block(30, &epochs[2], &epochs[3], 3);
+        client.validate_and_update_head(&block).unwrap();
+        assert_eq!(client.head().inner_lite.epoch_id, epochs[2].id);
+    }
+}
@@ -0,0 +1,207 @@
+//! Tools working with the network traffic captured by a node
+//! (see `experimental.traffic_capture_dir` in the network config).
+use anyhow::Context;
+use near_async::time;
+use near_crypto::PublicKey;
+use near_network::capture::{self, CapturedMessage};
+use near_network::raw::Connection;
+use near_network::types::PeerInfo;
+use near_primitives::hash::CryptoHash;
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Types of messages which make sense only as a part of the connection they were
+/// captured on, so they are never replayed.
+const CONNECTION_MESSAGES: &[&str] =
+    &["Tier1Handshake", "Tier2Handshake", "HandshakeFailure", "LastEdge", "Disconnect"];
+
+#[derive(clap::Parser)]
+pub struct NetworkCommand {
+    #[clap(subcommand)]
+    subcmd: NetworkSubCommand,
+}
+
+#[derive(clap::Subcommand)]
+enum NetworkSubCommand {
+    /// Prints the messages stored in a traffic capture.
+    Dump(DumpCommand),
+    /// Connects to a node and sends it the messages stored in a traffic capture,
+    /// preserving the intervals between them. The node can be a fresh node or a node
+    /// run by the mock-node harness, so that sync and consensus issues observed by
+    /// the capturing node can be reproduced offline.
+    Replay(ReplayCommand),
+}
+
+impl NetworkCommand {
+    pub fn run(self, out: &mut impl Write) -> anyhow::Result<()> {
+        match self.subcmd {
+            NetworkSubCommand::Dump(cmd) => cmd.run(out),
+            NetworkSubCommand::Replay(cmd) => cmd.run(),
+        }
+    }
+}
+
+/// Selects the captured messages to process.
+#[derive(clap::Args)]
+struct CaptureFilter {
+    /// Capture file, or a directory with capture files (which are then read from
+    /// the oldest to the newest).
+    capture: PathBuf,
+    /// Comma-separated list of message types to select, e.g. "Block,BlockHeaders".
+    /// For routed messages, the type of the routed message body is matched.
+    /// By default all the types are selected.
+    #[clap(long, value_delimiter = ',')]
+    types: Vec<String>,
+    /// Comma-separated list of public keys of the peers, whose messages should be selected.
+    /// By default messages from all the peers are selected.
+    #[clap(long, value_delimiter = ',')]
+    peers: Vec<PublicKey>,
+}
+
+impl CaptureFilter {
+    fn files(&self) -> anyhow::Result<Vec<PathBuf>> {
+        if self.capture.is_dir() {
+            capture::list_files(&self.capture)
+                .with_context(|| format!("failed to list {}", self.capture.display()))
+        } else {
+            Ok(vec![self.capture.clone()])
+        }
+    }
+
+    fn matches(&self, msg: &CapturedMessage) -> bool {
+        let types: HashSet<&str> = self.types.iter().map(String::as_str).collect();
+        (self.peers.is_empty() || self.peers.contains(msg.peer_id.public_key()))
+            && (types.is_empty() || msg.message_type().map_or(false, |t| types.contains(t)))
+    }
+
+    /// Calls `f` for every selected message, in the order in which they were captured.
+    fn for_each(
+        &self,
+        mut f: impl FnMut(&Path, CapturedMessage) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        for path in self.files()? {
+            let reader = capture::Reader::open(&path)
+                .with_context(|| format!("failed to open {}", path.display()))?;
+            for msg in reader {
+                let msg = match msg {
+                    Ok(msg) => msg,
+                    // The last record of a file might be truncated, if the node was killed.
+                    Err(err) => {
+                        tracing::warn!(target: "network-replay", ?err, ?path, "failed to read a message, skipping the rest of the file");
+                        break;
+                    }
+                };
+                if self.matches(&msg) {
+                    f(&path, msg)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(clap::Parser)]
+struct DumpCommand {
+    #[clap(flatten)]
+    filter: CaptureFilter,
+}
+
+impl DumpCommand {
+    fn run(self, out: &mut impl Write) -> anyhow::Result<()> {
+        self.filter.for_each(|_, msg| {
+            writeln!(
+                out,
+                "{} {:?} {} {}",
+                msg.timestamp(),
+                msg.tier,
+                msg.peer_id,
+                msg.message_type().unwrap_or("<invalid>")
+            )?;
+            Ok(())
+        })
+    }
+}
+
+#[derive(clap::Parser)]
+struct ReplayCommand {
+    #[clap(flatten)]
+    filter: CaptureFilter,
+    /// Node to send the messages to, in the format {public key}@{socket addr}, e.g.
+    /// ed25519:7PGseFbWxvYVgZ89K1uTJKYoKetWs7BJtbyXDzfbAcqX@127.0.0.1:24567
+    #[clap(long)]
+    peer: String,
+    /// Chain id to use in the Handshake we send.
+    #[clap(long)]
+    chain_id: String,
+    /// Genesis hash to use in the Handshake we send.
+    #[clap(long)]
+    genesis_hash: CryptoHash,
+    /// Head height to use in the Handshake we send.
+    #[clap(long, default_value = "0")]
+    head_height: u64,
+    /// Replay speed, relative to the speed at which the messages were captured.
+    /// 0 means sending the messages as fast as possible.
+    #[clap(long, default_value = "1")]
+    speed: f64,
+}
+
+impl ReplayCommand {
+    fn run(self) -> anyhow::Result<()> {
+        let peer = PeerInfo::from_str(&self.peer)
+            .map_err(|err| anyhow::anyhow!("failed to parse --peer {}: {:?}", self.peer, err))?;
+        let Some(addr) = peer.addr else {
+            anyhow::bail!("--peer should be in the form [public key]@[socket addr]");
+        };
+        if !(self.speed >= 0.) {
+            anyhow::bail!("--speed has to be >=0");
+        }
+        // Read the whole capture upfront, so that reading files doesn't distort the timing.
+        let mut msgs = vec![];
+        self.filter.for_each(|_, msg| {
+            if !msg.message_type().map_or(false, |t| CONNECTION_MESSAGES.contains(&t)) {
+                msgs.push(msg);
+            }
+            Ok(())
+        })?;
+        tracing::info!(target: "network-replay", "replaying {} messages", msgs.len());
+
+        let runtime = tokio::runtime::Runtime::new()?;
+        runtime.block_on(async {
+            let mut conn = Connection::connect(
+                addr,
+                peer.id.clone(),
+                None,
+                &self.chain_id,
+                self.genesis_hash,
+                self.head_height,
+                vec![],
+                time::Duration::days(1),
+            )
+            .await
+            .with_context(|| format!("failed to connect to {}", self.peer))?;
+            let start = tokio::time::Instant::now();
+            let first = msgs.first().map(|msg| msg.timestamp());
+            for (i, msg) in msgs.iter().enumerate() {
+                if let (Some(first), true) = (first, self.speed > 0.) {
+                    let offset = ((msg.timestamp() - first) / self.speed).max(time::Duration::ZERO);
+                    let due = start + offset.unsigned_abs();
+                    // Keep reading (and discarding) the messages sent by the node, so that
+                    // it doesn't drop the connection due to a full send queue.
+                    loop {
+                        tokio::select! {
+                            _ = tokio::time::sleep_until(due) => break,
+                            res = conn.recv() => { res.context("connection closed")?; }
+                        }
+                    }
+                }
+                conn.send_captured_message(msg)
+                    .await
+                    .with_context(|| format!("failed to send message #{i}"))?;
+            }
+            tracing::info!(target: "network-replay", "replayed {} messages", msgs.len());
+            anyhow::Ok(())
+        })
+    }
+}
@@ -0,0 +1,406 @@
+//! `ValidatorSigner` which delegates signing to a signer daemon.
+//!
+//! Signing fails when the daemon is unreachable, doesn't respond in time or
+//! refuses to sign (e.g. because that could get the validator slashed). The error
+//! is logged, counted in `near_remote_signer_errors_total` and returned to the
+//! caller, which doesn't publish the message.
+//!
+//! Every request blocks the calling thread, usually the `ClientActor`, until the
+//! daemon responds, for at most `RemoteSignerConfig::timeout`. Connecting to the
+//! daemon happens on a separate thread, so that it doesn't add to the wait.
+use crate::metrics;
+use crate::protocol::{
+    client_auth_data, read_frame, server_auth_data, write_frame, ClientAuth, ClientHello, Nonce,
+    Request, Response, ServerHello, MAX_HANDSHAKE_FRAME_SIZE, PROTOCOL_VERSION,
+};
+use crate::transport::{SignerAddr, Stream};
+use near_async::time::Duration;
+use near_crypto::{PublicKey, SecretKey, Signature};
+use near_primitives::block::{ApprovalInner, BlockHeader};
+use near_primitives::challenge::ChallengeBody;
+use near_primitives::hash::CryptoHash;
+use near_primitives::network::PeerId;
+use near_primitives::sharding::ChunkHash;
+use near_primitives::stateless_validation::{
+    ChunkEndorsementInner, ChunkStateWitnessInner, PartialEncodedStateWitnessHeader,
+};
+use near_primitives::telemetry::TelemetryInfo;
+use near_primitives::types::{AccountId, BlockHeight, EpochId};
+use near_primitives::validator_signer::{ValidatorSigner, ValidatorSignerError};
+use std::path::Path;
+use std::sync::mpsc;
+use std::time::Instant;
+
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug)]
+pub struct RemoteSignerConfig {
+    /// Address of the signer daemon: `unix:<path>` or `<host>:<port>`.
+    pub addr: SignerAddr,
+    /// Account id of the validator.
+    pub account_id: AccountId,
+    /// Public key of the validator key, held by the signer daemon.
+    pub public_key: PublicKey,
+    /// Public key the signer daemon authenticates itself with.
+    pub signer_public_key: PublicKey,
+    /// Timeout of connecting to the signer daemon and of every request.
+    #[serde(with = "near_async::time::serde_duration_as_std", default = "default_timeout")]
+    pub timeout: Duration,
+}
+
+fn default_timeout() -> Duration {
+    Duration::seconds(1)
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum RemoteSignerError {
+    #[error("i/o error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to authenticate the signer: {0}")]
+    Auth(String),
+    #[error("the signer refused the request: {0}")]
+    Refused(String),
+    #[error("unexpected response of the signer")]
+    UnexpectedResponse,
+    #[error("the signer didn't respond in time")]
+    Timeout,
+    #[error("not connected to the signer")]
+    Unavailable,
+}
+
+impl From<RemoteSignerError> for ValidatorSignerError {
+    fn from(err: RemoteSignerError) -> Self {
+        ValidatorSignerError(Box::new(err))
+    }
+}
+
+impl RemoteSignerError {
+    fn label(&self) -> &'static str {
+        match self {
+            RemoteSignerError::Io(_) => "io",
+            RemoteSignerError::Auth(_) => "auth",
+            RemoteSignerError::Refused(_) => "refused",
+            RemoteSignerError::UnexpectedResponse => "unexpected_response",
+            RemoteSignerError::Timeout => "timeout",
+            RemoteSignerError::Unavailable => "unavailable",
+        }
+    }
+}
+
+/// Interval between the attempts to reconnect to the signer daemon.
+const RECONNECT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// Request waiting for the worker thread.
+struct Job {
+    request: Request,
+    /// Time after which the caller doesn't wait for the response anymore.
+    deadline: Instant,
+    response: mpsc::SyncSender<Result<Response, RemoteSignerError>>,
+}
+
+/// Owns the connection to the signer daemon. All the i/o happens on the worker thread,
+/// so that a caller waits at most `RemoteSignerConfig::timeout` for a response,
+/// regardless of how long connecting or reconnecting to the daemon takes.
+struct Worker {
+    config: RemoteSignerConfig,
+    /// Key the node authenticates itself with to the signer daemon.
+    client_key: SecretKey,
+    connection: Option<Stream>,
+    /// Time of the last failed attempt to connect.
+    last_failure: Option<Instant>,
+}
+
+impl Worker {
+    fn connect(&self) -> Result<Stream, RemoteSignerError> {
+        let timeout = self.config.timeout.unsigned_abs();
+        let mut stream = Stream::connect(&self.config.addr, timeout)?;
+        let nonce: Nonce = rand::random();
+        let hello = ClientHello {
+            version: PROTOCOL_VERSION,
+            client_key: self.client_key.public_key(),
+            nonce,
+        };
+        write_frame(&mut stream, &hello)?;
+        let hello: ServerHello = read_frame(&mut stream, MAX_HANDSHAKE_FRAME_SIZE)?;
+        if hello.version != PROTOCOL_VERSION {
+            return Err(RemoteSignerError::Auth(format!(
+                "unsupported protocol version {}",
+                hello.version
+            )));
+        }
+        if hello.server_key != self.config.signer_public_key {
+            return Err(RemoteSignerError::Auth(format!("unexpected key {}", hello.server_key)));
+        }
+        if !hello.signature.verify(&server_auth_data(&nonce, &hello.nonce), &hello.server_key) {
+            return Err(RemoteSignerError::Auth("invalid handshake signature".to_string()));
+        }
+        if hello.validator_id != self.config.account_id
+            || hello.validator_key != self.config.public_key
+        {
+            return Err(RemoteSignerError::Auth(format!(
+                "the signer holds key {} of {}, expected key {} of {}",
+                hello.validator_key,
+                hello.validator_id,
+                self.config.public_key,
+                self.config.account_id
+            )));
+        }
+        let signature = self.client_key.sign(&client_auth_data(&nonce, &hello.nonce));
+        write_frame(&mut stream, &ClientAuth { signature })?;
+        tracing::info!(target: "remote_signer", addr = %self.config.addr, "connected to the signer");
+        Ok(stream)
+    }
+
+    /// Connects to the signer daemon, unless the last attempt failed recently.
+    fn reconnect(&mut self) -> Result<&mut Stream, RemoteSignerError> {
+        if self.connection.is_none() {
+            if self.last_failure.is_some_and(|t| t.elapsed() < RECONNECT_INTERVAL) {
+                return Err(RemoteSignerError::Unavailable);
+            }
+            match self.connect() {
+                Ok(stream) => {
+                    self.last_failure = None;
+                    self.connection = Some(stream);
+                }
+                Err(err) => {
+                    self.last_failure = Some(Instant::now());
+                    return Err(err);
+                }
+            }
+        }
+        Ok(self.connection.as_mut().unwrap())
+    }
+
+    fn exchange(stream: &mut Stream, request: &Request) -> std::io::Result<Response> {
+        write_frame(stream, request)?;
+        read_frame(stream, MAX_HANDSHAKE_FRAME_SIZE)
+    }
+
+    fn handle(&mut self, request: &Request) -> Result<Response, RemoteSignerError> {
+        if let Some(stream) = self.connection.as_mut() {
+            // The connection may have been closed in the meantime, e.g. because
+            // the signer has been restarted, so the request is retried on a new connection.
+            // Retrying is safe: the signer signs the same block or approval again.
+            match Self::exchange(stream, request) {
+                Ok(response) => return Ok(response),
+                Err(err) => {
+                    tracing::debug!(target: "remote_signer", ?err, "reconnecting to the signer");
+                    self.connection = None;
+                }
+            }
+        }
+        let result = Self::exchange(self.reconnect()?, request);
+        if result.is_err() {
+            self.connection = None;
+        }
+        Ok(result?)
+    }
+
+    fn run(mut self, jobs: mpsc::Receiver<Job>) {
+        loop {
+            match jobs.recv_timeout(RECONNECT_INTERVAL) {
+                Ok(job) => {
+                    // The caller gave up on the request, e.g. because the previous
+                    // request took too long.
+                    if Instant::now() >= job.deadline {
+                        continue;
+                    }
+                    let _ = job.response.send(self.handle(&job.request));
+                }
+                // Connect in the background, so that the requests don't have to wait for it.
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    if let Err(err) = self.reconnect() {
+                        tracing::debug!(target: "remote_signer", %err, "failed to reconnect to the signer");
+                    }
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => return,
+            }
+        }
+    }
+}
+
+pub struct RemoteValidatorSigner {
+    config: RemoteSignerConfig,
+    jobs: mpsc::Sender<Job>,
+}
+
+impl RemoteValidatorSigner {
+    /// Starts a thread which connects to the signer daemon.
+    /// The thread stops once the signer is dropped.
+    pub fn new(config: RemoteSignerConfig, client_key: SecretKey) -> Self {
+        let (jobs, receiver) = mpsc::channel();
+        let worker =
+            Worker { config: config.clone(), client_key, connection: None, last_failure: None };
+        std::thread::Builder::new()
+            .name("remote_signer".to_string())
+            .spawn(move || worker.run(receiver))
+            .expect("failed to spawn the remote signer thread");
+        Self { config, jobs }
+    }
+
+    fn request_inner(&self, request: Request) -> Result<Response, RemoteSignerError> {
+        let timeout = self.config.timeout.unsigned_abs();
+        let (response, receiver) = mpsc::sync_channel(1);
+        let job = Job { request, deadline: Instant::now() + timeout, response };
+        self.jobs.send(job).map_err(|_| RemoteSignerError::Unavailable)?;
+        match receiver.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(mpsc::RecvTimeoutError::Timeout) => Err(RemoteSignerError::Timeout),
+            Err(mpsc::RecvTimeoutError::Disconnected) => Err(RemoteSignerError::Unavailable),
+        }
+    }
+
+    /// Sends the request to the signer daemon and waits for the response.
+    pub fn request(&self, request: Request) -> Result<Response, RemoteSignerError> {
+        let kind = request.kind();
+        let _timer =
+            metrics::REMOTE_SIGNER_REQUEST_LATENCY.with_label_values(&[kind]).start_timer();
+        let result = match self.request_inner(request) {
+            Ok(Response::Refused(reason)) => Err(RemoteSignerError::Refused(reason)),
+            result => result,
+        };
+        if let Err(err) = &result {
+            metrics::REMOTE_SIGNER_ERRORS.with_label_values(&[kind, err.label()]).inc();
+            tracing::error!(target: "remote_signer", kind, %err, "request to the signer failed");
+        }
+        result
+    }
+
+    pub fn sign(&self, request: Request) -> Result<Signature, RemoteSignerError> {
+        let kind = request.kind();
+        match self.request(request)? {
+            Response::Signature(signature) => Ok(signature),
+            _ => {
+                metrics::REMOTE_SIGNER_ERRORS
+                    .with_label_values(&[kind, "unexpected_response"])
+                    .inc();
+                Err(RemoteSignerError::UnexpectedResponse)
+            }
+        }
+    }
+
+    pub fn compute_vrf(
+        &self,
+        data: &[u8],
+    ) -> Result<(near_crypto::vrf::Value, near_crypto::vrf::Proof), RemoteSignerError> {
+        match self.request(Request::ComputeVrfWithProof(data.to_vec()))? {
+            Response::Vrf(value, proof) => Ok((value, proof)),
+            _ => {
+                metrics::REMOTE_SIGNER_ERRORS
+                    .with_label_values(&["vrf", "unexpected_response"])
+                    .inc();
+                Err(RemoteSignerError::UnexpectedResponse)
+            }
+        }
+    }
+}
+
+impl ValidatorSigner for RemoteValidatorSigner {
+    fn validator_id(&self) -> &AccountId {
+        &self.config.account_id
+    }
+
+    fn public_key(&self) -> PublicKey {
+        self.config.public_key.clone()
+    }
+
+    fn sign_telemetry(
+        &self,
+        info: &TelemetryInfo,
+    ) -> Result<serde_json::Value, ValidatorSignerError> {
+        let mut value = serde_json::to_value(info).expect("Telemetry must serialize to JSON");
+        let content = serde_json::to_string(&value).expect("Telemetry must serialize to JSON");
+        value["signature"] = self.sign(Request::SignTelemetry(content))?.to_string().into();
+        Ok(value)
+    }
+
+    fn sign_block_header_parts(
+        &self,
+        prev_hash: CryptoHash,
+        inner_lite: &[u8],
+        inner_rest: &[u8],
+    ) -> Result<(CryptoHash, Signature), ValidatorSignerError> {
+        let hash = BlockHeader::compute_hash(prev_hash, inner_lite, inner_rest);
+        let signature = self.sign(Request::SignBlockHeader {
+            prev_hash,
+            inner_lite: inner_lite.to_vec(),
+            inner_rest: inner_rest.to_vec(),
+        })?;
+        Ok((hash, signature))
+    }
+
+    fn sign_chunk_hash(&self, chunk_hash: &ChunkHash) -> Result<Signature, ValidatorSignerError> {
+        Ok(self.sign(Request::SignChunkHash(chunk_hash.clone()))?)
+    }
+
+    fn sign_approval(
+        &self,
+        inner: &ApprovalInner,
+        target_height: BlockHeight,
+    ) -> Result<Signature, ValidatorSignerError> {
+        Ok(self.sign(Request::SignApproval { inner: inner.clone(), target_height })?)
+    }
+
+    fn sign_chunk_endorsement(
+        &self,
+        inner: &ChunkEndorsementInner,
+    ) -> Result<Signature, ValidatorSignerError> {
+        Ok(self.sign(Request::SignChunkEndorsement(inner.clone()))?)
+    }
+
+    fn sign_chunk_state_witness(
+        &self,
+        inner: &ChunkStateWitnessInner,
+    ) -> Result<(Signature, usize), ValidatorSignerError> {
+        let data = borsh::to_vec(inner).unwrap();
+        let size = data.len();
+        Ok((self.sign(Request::SignChunkStateWitness(data))?, size))
+    }
+
+    fn sign_partial_encoded_state_witness_header(
+        &self,
+        header: &PartialEncodedStateWitnessHeader,
+    ) -> Result<Signature, ValidatorSignerError> {
+        Ok(self.sign(Request::SignPartialEncodedStateWitnessHeader(header.clone()))?)
+    }
+
+    fn sign_challenge(
+        &self,
+        challenge_body: &ChallengeBody,
+    ) -> Result<(CryptoHash, Signature), ValidatorSignerError> {
+        let hash = CryptoHash::hash_borsh(challenge_body);
+        Ok((hash, self.sign(Request::SignChallenge(challenge_body.clone()))?))
+    }
+
+    fn sign_account_announce(
+        &self,
+        account_id: &AccountId,
+        peer_id: &PeerId,
+        epoch_id: &EpochId,
+    ) -> Result<Signature, ValidatorSignerError> {
+        Ok(self.sign(Request::SignAccountAnnounce {
+            account_id: account_id.clone(),
+            peer_id: peer_id.clone(),
+            epoch_id: epoch_id.clone(),
+        })?)
+    }
+
+    fn sign_account_key_payload(
+        &self,
+        proto_bytes: &[u8],
+    ) -> Result<Signature, ValidatorSignerError> {
+        Ok(self.sign(Request::SignAccountKeyPayload(proto_bytes.to_vec()))?)
+    }
+
+    fn compute_vrf_with_proof(
+        &self,
+        data: &[u8],
+    ) -> Result<(near_crypto::vrf::Value, near_crypto::vrf::Proof), ValidatorSignerError> {
+        Ok(self.compute_vrf(data)?)
+    }
+
+    fn write_to_file(&self, _path: &Path) -> std::io::Result<()> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "the validator key is held by the remote signer",
+        ))
+    }
+}
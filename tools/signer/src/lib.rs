@@ -0,0 +1,20 @@
+//! Remote validator signer.
+//!
+//! Keeps the validator key off the node host: the key is held by a signer daemon
+//! (`near-signer` binary), and the node uses `RemoteValidatorSigner` to request
+//! signatures from it over a Unix or TCP socket. The node and the daemon
+//! authenticate each other with their keys (see `protocol`), and the daemon
+//! refuses to sign conflicting blocks and approvals (see `slashing_protection`).
+pub mod client;
+mod metrics;
+pub mod protocol;
+pub mod server;
+pub mod slashing_protection;
+mod transport;
+
+#[cfg(test)]
+mod tests;
+
+pub use client::{RemoteSignerConfig, RemoteSignerError, RemoteValidatorSigner};
+pub use server::SignerServer;
+pub use transport::{Listener, SignerAddr};
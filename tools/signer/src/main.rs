@@ -0,0 +1,112 @@
+use anyhow::Context;
+use near_crypto::{InMemorySigner, KeyFile, KeyType, PublicKey, SecretKey};
+use near_o11y::metrics::{prometheus, Encoder, TextEncoder};
+use near_signer::server::DEFAULT_MAX_CONNECTIONS;
+use near_signer::slashing_protection::SlashingProtection;
+use near_signer::{Listener, SignerAddr, SignerServer};
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Holds a validator key and signs blocks, approvals and other messages
+/// for the nodes configured with `remote_signer`. Refuses to sign blocks
+/// and approvals which could get the validator slashed.
+#[derive(clap::Parser)]
+struct Cli {
+    /// Validator key file, in the `validator_key.json` format.
+    #[clap(long)]
+    validator_key: PathBuf,
+    /// Key file of the signer, which it authenticates itself with to the nodes.
+    /// A new key is generated if the file doesn't exist.
+    #[clap(long)]
+    signer_key: PathBuf,
+    /// Comma-separated list of public keys of the nodes allowed to use the signer
+    /// (the keys from their `node_key.json`).
+    #[clap(long, value_delimiter = ',', required = true)]
+    allowed_clients: Vec<PublicKey>,
+    /// Address to listen on: `unix:<path>` or `<host>:<port>`.
+    #[clap(long)]
+    listen: SignerAddr,
+    /// File with the record of the signed blocks and approvals.
+    #[clap(long)]
+    slashing_protection: PathBuf,
+    /// Address to serve the Prometheus metrics on.
+    #[clap(long)]
+    metrics_addr: Option<SocketAddr>,
+    /// Maximum number of connections served at the same time.
+    #[clap(long, default_value_t = DEFAULT_MAX_CONNECTIONS)]
+    max_connections: usize,
+}
+
+fn load_or_generate_signer_key(path: &Path) -> anyhow::Result<SecretKey> {
+    if path.exists() {
+        let key_file = KeyFile::from_file(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        return Ok(key_file.secret_key);
+    }
+    let secret_key = SecretKey::from_random(KeyType::ED25519);
+    let key_file = KeyFile {
+        account_id: "signer".parse().unwrap(),
+        public_key: secret_key.public_key(),
+        secret_key: secret_key.clone(),
+    };
+    key_file.write_to_file(path).with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(secret_key)
+}
+
+/// Serves the metrics in the Prometheus text format, in response to any HTTP request.
+fn serve_metrics(listener: TcpListener) {
+    for stream in listener.incoming() {
+        let Ok(mut stream) = stream else { continue };
+        // The request itself doesn't matter, only its beginning is consumed.
+        let _ = stream.read(&mut [0u8; 1024]);
+        let mut buffer = vec![];
+        TextEncoder::new().encode(&prometheus::gather(), &mut buffer).unwrap();
+        let header = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            buffer.len()
+        );
+        let _ = stream.write_all(header.as_bytes()).and_then(|()| stream.write_all(&buffer));
+    }
+}
+
+impl Cli {
+    fn run(self) -> anyhow::Result<()> {
+        let validator_signer = InMemorySigner::from_file(&self.validator_key)
+            .with_context(|| format!("failed to read {}", self.validator_key.display()))?;
+        let server_key = load_or_generate_signer_key(&self.signer_key)?;
+        let slashing_protection = SlashingProtection::open(&self.slashing_protection)
+            .with_context(|| format!("failed to open {}", self.slashing_protection.display()))?;
+        if let Some(addr) = self.metrics_addr {
+            let listener = TcpListener::bind(addr)
+                .with_context(|| format!("failed to bind the metrics address {addr}"))?;
+            std::thread::spawn(move || serve_metrics(listener));
+        }
+        let listener = Listener::bind(&self.listen)
+            .with_context(|| format!("failed to bind {}", self.listen))?;
+        tracing::info!(
+            target: "signer",
+            validator = %validator_signer.account_id,
+            signer_key = %server_key.public_key(),
+            listen = %self.listen,
+            "serving"
+        );
+        let server = SignerServer::new(
+            validator_signer,
+            server_key,
+            self.allowed_clients,
+            slashing_protection,
+            self.max_connections,
+        );
+        Arc::new(server).serve(listener);
+        Ok(())
+    }
+}
+
+fn main() -> anyhow::Result<()> {
+    let env_filter = near_o11y::EnvFilterBuilder::from_env().finish().unwrap();
+    let _subscriber = near_o11y::default_subscriber(env_filter, &Default::default()).global();
+    let cli: Cli = clap::Parser::parse();
+    cli.run()
+}
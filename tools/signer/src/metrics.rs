@@ -0,0 +1,42 @@
+use near_o11y::metrics::{
+    exponential_buckets, try_create_histogram_vec, try_create_int_counter_vec, HistogramVec,
+    IntCounterVec,
+};
+use once_cell::sync::Lazy;
+
+pub(crate) static REMOTE_SIGNER_REQUEST_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    try_create_histogram_vec(
+        "near_remote_signer_request_latency_seconds",
+        "Latency of the requests sent by the node to the remote validator signer, by request type",
+        &["type"],
+        Some(exponential_buckets(0.0001, 2.0, 16).unwrap()),
+    )
+    .unwrap()
+});
+
+pub(crate) static REMOTE_SIGNER_ERRORS: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_remote_signer_errors_total",
+        "Number of the requests to the remote validator signer which failed, by request type and error",
+        &["type", "error"],
+    )
+    .unwrap()
+});
+
+pub(crate) static SIGNER_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_signer_requests_total",
+        "Number of the requests handled by the signer daemon, by request type and result",
+        &["type", "result"],
+    )
+    .unwrap()
+});
+
+pub(crate) static SIGNER_HANDSHAKE_FAILURES: Lazy<IntCounterVec> = Lazy::new(|| {
+    try_create_int_counter_vec(
+        "near_signer_handshake_failures_total",
+        "Number of the connections to the signer daemon rejected during the handshake",
+        &["reason"],
+    )
+    .unwrap()
+});
@@ -0,0 +1,168 @@
+//! Wire protocol between a `RemoteValidatorSigner` and the signer daemon.
+//!
+//! Every message is a borsh-encoded frame, prefixed with its length (u32, little endian).
+//! A connection starts with a handshake, in which both sides prove that they own
+//! the keys the other side expects:
+//! 1. the client sends `ClientHello` with its public key and a random nonce,
+//! 2. the signer replies with `ServerHello`, which contains the signer's public key,
+//!    the validator key it signs with and a signature of both nonces,
+//! 3. the client verifies the signer's key and signature and sends `ClientAuth`
+//!    with its own signature of both nonces.
+//!
+//! Afterwards the client sends `Request`s and the signer replies to each of them
+//! with a `Response`.
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_crypto::{PublicKey, Signature};
+use near_primitives::block::ApprovalInner;
+use near_primitives::challenge::ChallengeBody;
+use near_primitives::hash::CryptoHash;
+use near_primitives::network::PeerId;
+use near_primitives::sharding::ChunkHash;
+use near_primitives::stateless_validation::{
+    ChunkEndorsementInner, PartialEncodedStateWitnessHeader,
+};
+use near_primitives::types::{AccountId, BlockHeight, EpochId};
+use std::io;
+use std::io::Read as _;
+
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Upper bound on the size of a request. Chunk state witnesses are sent as a whole,
+/// so it has to be well above the size of the largest witness.
+pub const MAX_FRAME_SIZE: usize = 64 * 1024 * 1024;
+
+/// Upper bound on the size of the handshake messages and of the responses.
+/// The signer reads at most this much from a client until it is authenticated.
+pub const MAX_HANDSHAKE_FRAME_SIZE: usize = 64 * 1024;
+
+pub type Nonce = [u8; 32];
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct ClientHello {
+    pub version: u32,
+    pub client_key: PublicKey,
+    pub nonce: Nonce,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct ServerHello {
+    pub version: u32,
+    pub server_key: PublicKey,
+    pub validator_id: AccountId,
+    pub validator_key: PublicKey,
+    pub nonce: Nonce,
+    /// Signature of `server_auth_data(client nonce, server nonce)` with the server key.
+    pub signature: Signature,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub struct ClientAuth {
+    /// Signature of `client_auth_data(client nonce, server nonce)` with the client key.
+    pub signature: Signature,
+}
+
+/// Data signed by the signer during the handshake. The prefixes make sure that
+/// the handshake signatures can't be confused with each other, nor with
+/// the signatures the validator key makes.
+pub fn server_auth_data(client_nonce: &Nonce, server_nonce: &Nonce) -> Vec<u8> {
+    [b"near-signer:server".as_ref(), client_nonce, server_nonce].concat()
+}
+
+/// Data signed by the client during the handshake.
+pub fn client_auth_data(client_nonce: &Nonce, server_nonce: &Nonce) -> Vec<u8> {
+    [b"near-signer:client".as_ref(), client_nonce, server_nonce].concat()
+}
+
+/// Requests to sign data with the validator key. The requests are typed,
+/// so that the signer knows what it signs and can protect the validator
+/// against signing conflicting blocks and approvals.
+#[derive(BorshSerialize, BorshDeserialize, Debug)]
+pub enum Request {
+    SignBlockHeader {
+        prev_hash: CryptoHash,
+        inner_lite: Vec<u8>,
+        inner_rest: Vec<u8>,
+    },
+    SignChunkHash(ChunkHash),
+    SignApproval {
+        inner: ApprovalInner,
+        target_height: BlockHeight,
+    },
+    SignChunkEndorsement(ChunkEndorsementInner),
+    /// Borsh-serialized `ChunkStateWitnessInner`. It is sent serialized,
+    /// so that the node doesn't have to clone the witness.
+    SignChunkStateWitness(Vec<u8>),
+    SignChallenge(ChallengeBody),
+    SignAccountAnnounce {
+        account_id: AccountId,
+        peer_id: PeerId,
+        epoch_id: EpochId,
+    },
+    /// Proto-serialized `AccountKeyPayload`.
+    SignAccountKeyPayload(Vec<u8>),
+    /// JSON-serialized telemetry info.
+    SignTelemetry(String),
+    ComputeVrfWithProof(Vec<u8>),
+    SignPartialEncodedStateWitnessHeader(PartialEncodedStateWitnessHeader),
+}
+
+impl Request {
+    /// Name of the request, used as a metric label.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Request::SignBlockHeader { .. } => "block_header",
+            Request::SignChunkHash(_) => "chunk_hash",
+            Request::SignApproval { .. } => "approval",
+            Request::SignChunkEndorsement(_) => "chunk_endorsement",
+            Request::SignChunkStateWitness(_) => "chunk_state_witness",
+            Request::SignChallenge(_) => "challenge",
+            Request::SignAccountAnnounce { .. } => "account_announce",
+            Request::SignAccountKeyPayload(_) => "account_key_payload",
+            Request::SignTelemetry(_) => "telemetry",
+            Request::ComputeVrfWithProof(_) => "vrf",
+            Request::SignPartialEncodedStateWitnessHeader(_) => "partial_encoded_state_witness",
+        }
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+pub enum Response {
+    Signature(Signature),
+    Vrf(near_crypto::vrf::Value, near_crypto::vrf::Proof),
+    /// The signer refused to handle the request, e.g. because signing it
+    /// could get the validator slashed.
+    Refused(String),
+}
+
+pub fn write_frame<T: BorshSerialize>(w: &mut impl io::Write, msg: &T) -> io::Result<()> {
+    let data = borsh::to_vec(msg)?;
+    if data.len() > MAX_FRAME_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("frame of {} bytes exceeds the limit", data.len()),
+        ));
+    }
+    w.write_all(&(data.len() as u32).to_le_bytes())?;
+    w.write_all(&data)?;
+    w.flush()
+}
+
+/// Reads a frame of at most `max_size` bytes. The buffer grows as the data arrives,
+/// so the announced length alone doesn't make the reader allocate memory.
+pub fn read_frame<T: BorshDeserialize>(r: &mut impl io::Read, max_size: usize) -> io::Result<T> {
+    let mut len = [0u8; 4];
+    r.read_exact(&mut len)?;
+    let len = u32::from_le_bytes(len) as usize;
+    if len > max_size {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("frame of {len} bytes exceeds the limit of {max_size} bytes"),
+        ));
+    }
+    let mut data = vec![];
+    r.take(len as u64).read_to_end(&mut data)?;
+    if data.len() < len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    T::try_from_slice(&data)
+}
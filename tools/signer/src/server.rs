@@ -0,0 +1,262 @@
+//! The signer daemon: holds the validator key and signs the requests of the nodes
+//! allowed to use it.
+//!
+//! The nodes are trusted to send well-formed requests, the daemon doesn't validate
+//! e.g. that the chunk hashes it signs belong to actual chunks. What it does guarantee
+//! is that it never signs two conflicting blocks or approvals, even when multiple nodes
+//! use it at the same time (see `slashing_protection`).
+use crate::metrics;
+use crate::protocol::{
+    client_auth_data, read_frame, server_auth_data, write_frame, ClientAuth, ClientHello, Nonce,
+    Request, Response, ServerHello, MAX_FRAME_SIZE, MAX_HANDSHAKE_FRAME_SIZE, PROTOCOL_VERSION,
+};
+use crate::slashing_protection::{SlashingProtection, SlashingProtectionError};
+use crate::transport::{Listener, Stream};
+use borsh::BorshDeserialize;
+use near_crypto::{InMemorySigner, PublicKey, SecretKey, Signer};
+use near_primitives::block::{Approval, BlockHeader};
+use near_primitives::block_header::BlockHeaderInnerLite;
+use near_primitives::hash::CryptoHash;
+use near_primitives::network::AnnounceAccount;
+use near_primitives::stateless_validation::ChunkStateWitnessInner;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Time the client has to complete the handshake.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Default limit of the number of connections served at the same time.
+/// Every connection is served on a separate thread.
+pub const DEFAULT_MAX_CONNECTIONS: usize = 16;
+
+#[derive(thiserror::Error, Debug)]
+enum HandshakeError {
+    #[error("i/o error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("unsupported protocol version {0}")]
+    UnsupportedVersion(u32),
+    #[error("client {0} is not allowed")]
+    UnknownClient(PublicKey),
+    #[error("invalid handshake signature")]
+    InvalidSignature,
+    #[error("too many connections")]
+    TooManyConnections,
+}
+
+impl HandshakeError {
+    fn label(&self) -> &'static str {
+        match self {
+            HandshakeError::Io(_) => "io",
+            HandshakeError::UnsupportedVersion(_) => "unsupported_version",
+            HandshakeError::UnknownClient(_) => "unknown_client",
+            HandshakeError::InvalidSignature => "invalid_signature",
+            HandshakeError::TooManyConnections => "too_many_connections",
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+enum SignError {
+    #[error(transparent)]
+    SlashingProtection(#[from] SlashingProtectionError),
+    #[error("malformed request: {0}")]
+    Malformed(String),
+}
+
+pub struct SignerServer {
+    /// The validator key.
+    validator_signer: InMemorySigner,
+    /// Key the signer authenticates itself with to the nodes.
+    server_key: SecretKey,
+    /// Keys of the nodes allowed to use the signer.
+    allowed_clients: Vec<PublicKey>,
+    slashing_protection: Mutex<SlashingProtection>,
+    max_connections: usize,
+    /// Number of the connections being served.
+    connections: AtomicUsize,
+}
+
+/// Slot of a connection being served, released when the connection is closed.
+struct ConnectionSlot(Arc<SignerServer>);
+
+impl ConnectionSlot {
+    fn acquire(server: &Arc<SignerServer>) -> Option<Self> {
+        if server.connections.fetch_add(1, Ordering::Relaxed) >= server.max_connections {
+            server.connections.fetch_sub(1, Ordering::Relaxed);
+            return None;
+        }
+        Some(Self(server.clone()))
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.connections.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl SignerServer {
+    pub fn new(
+        validator_signer: InMemorySigner,
+        server_key: SecretKey,
+        allowed_clients: Vec<PublicKey>,
+        slashing_protection: SlashingProtection,
+        max_connections: usize,
+    ) -> Self {
+        Self {
+            validator_signer,
+            server_key,
+            allowed_clients,
+            slashing_protection: Mutex::new(slashing_protection),
+            max_connections,
+            connections: AtomicUsize::new(0),
+        }
+    }
+
+    /// Accepts connections and serves each of them on a separate thread.
+    /// Connections above `max_connections` are closed right away.
+    pub fn serve(self: Arc<Self>, listener: Listener) {
+        loop {
+            let stream = match listener.accept() {
+                Ok(stream) => stream,
+                Err(err) => {
+                    tracing::warn!(target: "signer", %err, "failed to accept a connection");
+                    std::thread::sleep(Duration::from_millis(100));
+                    continue;
+                }
+            };
+            let Some(slot) = ConnectionSlot::acquire(&self) else {
+                let err = HandshakeError::TooManyConnections;
+                metrics::SIGNER_HANDSHAKE_FAILURES.with_label_values(&[err.label()]).inc();
+                tracing::warn!(target: "signer", %err, "rejected connection");
+                continue;
+            };
+            std::thread::spawn(move || {
+                slot.0.handle_connection(stream);
+                drop(slot);
+            });
+        }
+    }
+
+    fn handle_connection(&self, mut stream: Stream) {
+        let client = match self.handshake(&mut stream) {
+            Ok(client) => client,
+            Err(err) => {
+                metrics::SIGNER_HANDSHAKE_FAILURES.with_label_values(&[err.label()]).inc();
+                tracing::warn!(target: "signer", %err, "rejected connection");
+                return;
+            }
+        };
+        tracing::info!(target: "signer", %client, "client connected");
+        loop {
+            let request: Request = match read_frame(&mut stream, MAX_FRAME_SIZE) {
+                Ok(request) => request,
+                Err(err) => {
+                    if err.kind() != std::io::ErrorKind::UnexpectedEof {
+                        tracing::warn!(target: "signer", %client, %err, "failed to read request");
+                    }
+                    break;
+                }
+            };
+            let response = self.handle_request(request);
+            if let Err(err) = write_frame(&mut stream, &response) {
+                tracing::warn!(target: "signer", %client, %err, "failed to send response");
+                break;
+            }
+        }
+        tracing::info!(target: "signer", %client, "client disconnected");
+    }
+
+    /// Authenticates the client and the signer to each other.
+    /// Returns the public key of the client.
+    fn handshake(&self, stream: &mut Stream) -> Result<PublicKey, HandshakeError> {
+        stream.set_timeout(Some(HANDSHAKE_TIMEOUT))?;
+        // The client is not authenticated yet, so only small frames are accepted.
+        let hello: ClientHello = read_frame(stream, MAX_HANDSHAKE_FRAME_SIZE)?;
+        if hello.version != PROTOCOL_VERSION {
+            return Err(HandshakeError::UnsupportedVersion(hello.version));
+        }
+        if !self.allowed_clients.contains(&hello.client_key) {
+            return Err(HandshakeError::UnknownClient(hello.client_key));
+        }
+        let nonce: Nonce = rand::random();
+        let reply = ServerHello {
+            version: PROTOCOL_VERSION,
+            server_key: self.server_key.public_key(),
+            validator_id: self.validator_signer.account_id.clone(),
+            validator_key: self.validator_signer.public_key(),
+            nonce,
+            signature: self.server_key.sign(&server_auth_data(&hello.nonce, &nonce)),
+        };
+        write_frame(stream, &reply)?;
+        let auth: ClientAuth = read_frame(stream, MAX_HANDSHAKE_FRAME_SIZE)?;
+        if !auth.signature.verify(&client_auth_data(&hello.nonce, &nonce), &hello.client_key) {
+            return Err(HandshakeError::InvalidSignature);
+        }
+        // Connections stay open between the requests.
+        stream.set_timeout(None)?;
+        Ok(hello.client_key)
+    }
+
+    pub(crate) fn handle_request(&self, request: Request) -> Response {
+        let kind = request.kind();
+        match self.sign(request) {
+            Ok(response) => {
+                metrics::SIGNER_REQUESTS.with_label_values(&[kind, "signed"]).inc();
+                response
+            }
+            Err(err) => {
+                metrics::SIGNER_REQUESTS.with_label_values(&[kind, "refused"]).inc();
+                tracing::warn!(target: "signer", kind, %err, "refused to sign");
+                Response::Refused(err.to_string())
+            }
+        }
+    }
+
+    fn sign(&self, request: Request) -> Result<Response, SignError> {
+        let signer = &self.validator_signer;
+        let signature = match request {
+            Request::SignBlockHeader { prev_hash, inner_lite, inner_rest } => {
+                let height = BlockHeaderInnerLite::try_from_slice(&inner_lite)
+                    .map_err(|err| SignError::Malformed(err.to_string()))?
+                    .height;
+                let hash = BlockHeader::compute_hash(prev_hash, &inner_lite, &inner_rest);
+                self.slashing_protection.lock().unwrap().check_block(height, hash)?;
+                signer.sign(hash.as_ref())
+            }
+            Request::SignChunkHash(chunk_hash) => signer.sign(chunk_hash.as_ref()),
+            Request::SignApproval { inner, target_height } => {
+                self.slashing_protection.lock().unwrap().check_approval(&inner, target_height)?;
+                signer.sign(&Approval::get_data_for_sig(&inner, target_height))
+            }
+            Request::SignChunkEndorsement(inner) => signer.sign(&borsh::to_vec(&inner).unwrap()),
+            Request::SignChunkStateWitness(data) => {
+                ChunkStateWitnessInner::try_from_slice(&data)
+                    .map_err(|err| SignError::Malformed(err.to_string()))?;
+                signer.sign(&data)
+            }
+            Request::SignChallenge(challenge_body) => {
+                signer.sign(CryptoHash::hash_borsh(&challenge_body).as_ref())
+            }
+            Request::SignAccountAnnounce { account_id, peer_id, epoch_id } => {
+                let hash = AnnounceAccount::build_header_hash(&account_id, &peer_id, &epoch_id);
+                signer.sign(hash.as_ref())
+            }
+            Request::SignAccountKeyPayload(proto_bytes) => signer.sign(&proto_bytes),
+            Request::SignTelemetry(content) => {
+                serde_json::from_str::<serde_json::Value>(&content)
+                    .map_err(|err| SignError::Malformed(err.to_string()))?;
+                signer.sign(content.as_bytes())
+            }
+            Request::ComputeVrfWithProof(data) => {
+                let (value, proof) = signer.compute_vrf_with_proof(&data);
+                return Ok(Response::Vrf(value, proof));
+            }
+            Request::SignPartialEncodedStateWitnessHeader(header) => {
+                signer.sign(&borsh::to_vec(&header).unwrap())
+            }
+        };
+        Ok(Response::Signature(signature))
+    }
+}
@@ -0,0 +1,190 @@
+//! Persistent protection against signing conflicting blocks and approvals.
+//!
+//! A validator gets slashed for producing two different blocks at the same height,
+//! or for approving two different things for the same target height. This can
+//! happen e.g. if two nodes are accidentally run with the same validator key,
+//! or if a node is restored from an old backup. The signer remembers the highest
+//! block and approval it has signed and refuses to sign anything conflicting
+//! with them. The record is persisted before the signature is returned,
+//! so that the protection survives restarts of the signer.
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_primitives::block::ApprovalInner;
+use near_primitives::hash::CryptoHash;
+use near_primitives::types::BlockHeight;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(thiserror::Error, Debug)]
+pub enum SlashingProtectionError {
+    #[error("a different block at height {height} has already been signed ({signed})")]
+    ConflictingBlock { height: BlockHeight, signed: CryptoHash },
+    #[error("block height {height} is lower than the last signed block height {last}")]
+    BlockHeightTooLow { height: BlockHeight, last: BlockHeight },
+    #[error("a different approval for target height {target_height} has already been signed ({signed:?})")]
+    ConflictingApproval { target_height: BlockHeight, signed: ApprovalInner },
+    #[error(
+        "approval target height {target_height} is lower than the last signed target height {last}"
+    )]
+    ApprovalHeightTooLow { target_height: BlockHeight, last: BlockHeight },
+    #[error("failed to persist the slashing protection record: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Default, Debug, Clone, PartialEq, Eq)]
+struct Record {
+    /// Height and hash of the highest block signed.
+    last_block: Option<(BlockHeight, CryptoHash)>,
+    /// Target height and inner of the approval with the highest target height signed.
+    last_approval: Option<(BlockHeight, ApprovalInner)>,
+}
+
+pub struct SlashingProtection {
+    path: PathBuf,
+    record: Record,
+}
+
+impl SlashingProtection {
+    /// Opens the slashing protection record stored at `path`,
+    /// or starts a new one if the file doesn't exist.
+    pub fn open(path: &Path) -> std::io::Result<Self> {
+        let record = match std::fs::read(path) {
+            Ok(data) => Record::try_from_slice(&data)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Record::default(),
+            Err(err) => return Err(err),
+        };
+        Ok(Self { path: path.to_path_buf(), record })
+    }
+
+    /// Checks that a block at `height` with hash `hash` can be signed and records it.
+    /// Signing the same block again is allowed, so that a node can retry a request
+    /// for which it didn't receive the response.
+    pub fn check_block(
+        &mut self,
+        height: BlockHeight,
+        hash: CryptoHash,
+    ) -> Result<(), SlashingProtectionError> {
+        if let Some((last, signed)) = self.record.last_block {
+            if height == last && hash != signed {
+                return Err(SlashingProtectionError::ConflictingBlock { height, signed });
+            }
+            if height < last {
+                return Err(SlashingProtectionError::BlockHeightTooLow { height, last });
+            }
+        }
+        let mut record = self.record.clone();
+        record.last_block = Some((height, hash));
+        self.update(record)
+    }
+
+    /// Checks that an approval of `inner` for `target_height` can be signed and records it.
+    pub fn check_approval(
+        &mut self,
+        inner: &ApprovalInner,
+        target_height: BlockHeight,
+    ) -> Result<(), SlashingProtectionError> {
+        if let Some((last, signed)) = &self.record.last_approval {
+            if target_height == *last && inner != signed {
+                return Err(SlashingProtectionError::ConflictingApproval {
+                    target_height,
+                    signed: signed.clone(),
+                });
+            }
+            if target_height < *last {
+                return Err(SlashingProtectionError::ApprovalHeightTooLow {
+                    target_height,
+                    last: *last,
+                });
+            }
+        }
+        let mut record = self.record.clone();
+        record.last_approval = Some((target_height, inner.clone()));
+        self.update(record)
+    }
+
+    fn update(&mut self, record: Record) -> Result<(), SlashingProtectionError> {
+        if record != self.record {
+            Self::persist(&self.path, &record)?;
+            self.record = record;
+        }
+        Ok(())
+    }
+
+    /// Atomically replaces the file at `path` with the record.
+    fn persist(path: &Path, record: &Record) -> std::io::Result<()> {
+        let tmp_path = path.with_extension("tmp");
+        let mut file = std::fs::File::create(&tmp_path)?;
+        file.write_all(&borsh::to_vec(record)?)?;
+        file.sync_all()?;
+        std::fs::rename(&tmp_path, path)?;
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::File::open(dir)?.sync_all()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SlashingProtection, SlashingProtectionError};
+    use near_primitives::block::ApprovalInner;
+    use near_primitives::hash::hash;
+
+    #[test]
+    fn test_block() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("slashing_protection");
+        let mut protection = SlashingProtection::open(&path).unwrap();
+        let a = hash(b"a");
+        let b = hash(b"b");
+
+        protection.check_block(10, a).unwrap();
+        // Signing the same block again is fine.
+        protection.check_block(10, a).unwrap();
+        assert!(matches!(
+            protection.check_block(10, b),
+            Err(SlashingProtectionError::ConflictingBlock { height: 10, .. })
+        ));
+        assert!(matches!(
+            protection.check_block(9, b),
+            Err(SlashingProtectionError::BlockHeightTooLow { height: 9, last: 10 })
+        ));
+        protection.check_block(11, b).unwrap();
+
+        // The record survives restarts.
+        let mut protection = SlashingProtection::open(&path).unwrap();
+        assert!(matches!(
+            protection.check_block(11, a),
+            Err(SlashingProtectionError::ConflictingBlock { height: 11, .. })
+        ));
+        protection.check_block(11, b).unwrap();
+    }
+
+    #[test]
+    fn test_approval() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("slashing_protection");
+        let mut protection = SlashingProtection::open(&path).unwrap();
+        let endorsement = ApprovalInner::Endorsement(hash(b"a"));
+        let skip = ApprovalInner::Skip(8);
+
+        protection.check_approval(&endorsement, 10).unwrap();
+        protection.check_approval(&endorsement, 10).unwrap();
+        assert!(matches!(
+            protection.check_approval(&skip, 10),
+            Err(SlashingProtectionError::ConflictingApproval { target_height: 10, .. })
+        ));
+        assert!(matches!(
+            protection.check_approval(&skip, 9),
+            Err(SlashingProtectionError::ApprovalHeightTooLow { target_height: 9, last: 10 })
+        ));
+        protection.check_approval(&skip, 11).unwrap();
+
+        let mut protection = SlashingProtection::open(&path).unwrap();
+        assert!(matches!(
+            protection.check_approval(&endorsement, 11),
+            Err(SlashingProtectionError::ConflictingApproval { target_height: 11, .. })
+        ));
+        // Blocks and approvals are tracked independently.
+        protection.check_block(5, hash(b"c")).unwrap();
+    }
+}
@@ -0,0 +1,150 @@
+use crate::protocol::{write_frame, ClientHello, Request, MAX_FRAME_SIZE, PROTOCOL_VERSION};
+use crate::server::DEFAULT_MAX_CONNECTIONS;
+use crate::slashing_protection::SlashingProtection;
+use crate::{
+    Listener, RemoteSignerConfig, RemoteSignerError, RemoteValidatorSigner, SignerAddr,
+    SignerServer,
+};
+use near_async::time::Duration;
+use near_crypto::{InMemorySigner, KeyType, SecretKey};
+use near_primitives::block::{Approval, ApprovalInner};
+use near_primitives::hash::hash;
+use near_primitives::validator_signer::ValidatorSigner;
+use std::io::{Read, Write};
+use std::sync::Arc;
+
+struct Setup {
+    _dir: tempfile::TempDir,
+    validator: InMemorySigner,
+    server_key: SecretKey,
+    client_key: SecretKey,
+    addr: SignerAddr,
+}
+
+fn start_signer(max_connections: usize) -> Setup {
+    let dir = tempfile::tempdir().unwrap();
+    let validator = InMemorySigner::from_seed("test0".parse().unwrap(), KeyType::ED25519, "test0");
+    let server_key = SecretKey::from_random(KeyType::ED25519);
+    let client_key = SecretKey::from_random(KeyType::ED25519);
+    let addr = SignerAddr::Tcp("127.0.0.1:0".to_string());
+    let listener = Listener::bind(&addr).unwrap();
+    let addr = match &listener {
+        Listener::Tcp(l) => SignerAddr::Tcp(l.local_addr().unwrap().to_string()),
+        #[cfg(unix)]
+        Listener::Unix(_) => unreachable!(),
+    };
+    let server = Arc::new(SignerServer::new(
+        validator.clone(),
+        server_key.clone(),
+        vec![client_key.public_key()],
+        SlashingProtection::open(&dir.path().join("slashing_protection")).unwrap(),
+        max_connections,
+    ));
+    std::thread::spawn(move || server.serve(listener));
+    Setup { _dir: dir, validator, server_key, client_key, addr }
+}
+
+fn config(setup: &Setup) -> RemoteSignerConfig {
+    RemoteSignerConfig {
+        addr: setup.addr.clone(),
+        account_id: setup.validator.account_id.clone(),
+        public_key: setup.validator.public_key.clone(),
+        signer_public_key: setup.server_key.public_key(),
+        timeout: Duration::seconds(5),
+    }
+}
+
+#[test]
+fn test_sign_approval() {
+    let setup = start_signer(DEFAULT_MAX_CONNECTIONS);
+    let signer = RemoteValidatorSigner::new(config(&setup), setup.client_key.clone());
+
+    let endorsement = ApprovalInner::Endorsement(hash(b"block"));
+    let signature = signer.sign_approval(&endorsement, 10).unwrap();
+    assert!(signature
+        .verify(&Approval::get_data_for_sig(&endorsement, 10), &setup.validator.public_key));
+    // Conflicting approval is refused.
+    assert!(signer.sign_approval(&ApprovalInner::Skip(8), 10).is_err());
+    let request = Request::SignApproval { inner: ApprovalInner::Skip(8), target_height: 10 };
+    assert!(matches!(signer.sign(request), Err(RemoteSignerError::Refused(_))));
+    // The signer keeps serving the following requests.
+    let skip = ApprovalInner::Skip(9);
+    let signature = signer.sign_approval(&skip, 11).unwrap();
+    assert!(signature.verify(&Approval::get_data_for_sig(&skip, 11), &setup.validator.public_key));
+}
+
+#[test]
+fn test_authentication() {
+    let setup = start_signer(DEFAULT_MAX_CONNECTIONS);
+    let request = || Request::SignTelemetry("{}".to_string());
+
+    // Unknown client.
+    let signer =
+        RemoteValidatorSigner::new(config(&setup), SecretKey::from_random(KeyType::ED25519));
+    assert!(matches!(signer.request(request()), Err(RemoteSignerError::Io(_))));
+
+    // Unexpected signer key.
+    let mut bad_config = config(&setup);
+    bad_config.signer_public_key = SecretKey::from_random(KeyType::ED25519).public_key();
+    let signer = RemoteValidatorSigner::new(bad_config, setup.client_key.clone());
+    assert!(matches!(signer.request(request()), Err(RemoteSignerError::Auth(_))));
+
+    // Unexpected validator key.
+    let mut bad_config = config(&setup);
+    bad_config.account_id = "test1".parse().unwrap();
+    let signer = RemoteValidatorSigner::new(bad_config, setup.client_key.clone());
+    assert!(matches!(signer.request(request()), Err(RemoteSignerError::Auth(_))));
+}
+
+#[test]
+fn test_unreachable_signer() {
+    let setup = start_signer(DEFAULT_MAX_CONNECTIONS);
+    let mut config = config(&setup);
+    // Nothing listens on the port.
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    config.addr = SignerAddr::Tcp(listener.local_addr().unwrap().to_string());
+    drop(listener);
+    let signer = RemoteValidatorSigner::new(config, setup.client_key.clone());
+    let request = || Request::SignTelemetry("{}".to_string());
+    assert!(signer.request(request()).is_err());
+    // The signer isn't contacted again right after a failure.
+    assert!(matches!(signer.request(request()), Err(RemoteSignerError::Unavailable)));
+    // Signing errors are returned via the ValidatorSigner interface.
+    assert!(signer.sign_approval(&ApprovalInner::Skip(1), 2).is_err());
+}
+
+#[test]
+fn test_too_many_connections() {
+    let setup = start_signer(1);
+    let SignerAddr::Tcp(addr) = &setup.addr else { unreachable!() };
+    // An unauthenticated connection takes the only slot.
+    let mut stream = std::net::TcpStream::connect(addr).unwrap();
+    let hello = ClientHello {
+        version: PROTOCOL_VERSION,
+        client_key: setup.client_key.public_key(),
+        nonce: [0; 32],
+    };
+    write_frame(&mut stream, &hello).unwrap();
+    let signer = RemoteValidatorSigner::new(config(&setup), setup.client_key.clone());
+    let request = || Request::SignTelemetry("{}".to_string());
+    assert!(signer.request(request()).is_err());
+
+    // Once the connection is closed, the node reconnects.
+    drop(stream);
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    while signer.request(request()).is_err() {
+        assert!(std::time::Instant::now() < deadline, "failed to reconnect");
+        std::thread::sleep(std::time::Duration::from_millis(100));
+    }
+}
+
+#[test]
+fn test_oversized_handshake_frame() {
+    let setup = start_signer(DEFAULT_MAX_CONNECTIONS);
+    let SignerAddr::Tcp(addr) = &setup.addr else { unreachable!() };
+    let mut stream = std::net::TcpStream::connect(addr).unwrap();
+    // An unauthenticated client announces a huge frame, which the signer refuses to read.
+    stream.write_all(&(MAX_FRAME_SIZE as u32).to_le_bytes()).unwrap();
+    stream.set_read_timeout(Some(std::time::Duration::from_secs(10))).unwrap();
+    assert_eq!(0, stream.read(&mut [0; 1]).unwrap());
+}
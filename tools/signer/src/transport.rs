@@ -0,0 +1,160 @@
+use std::fmt;
+use std::io;
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Address of the signer daemon: `unix:<path>` for a Unix socket,
+/// `<host>:<port>` for a TCP socket.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SignerAddr {
+    Unix(PathBuf),
+    Tcp(String),
+}
+
+impl FromStr for SignerAddr {
+    type Err = std::convert::Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.strip_prefix("unix:") {
+            Some(path) => SignerAddr::Unix(path.into()),
+            None => SignerAddr::Tcp(s.to_string()),
+        })
+    }
+}
+
+impl fmt::Display for SignerAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignerAddr::Unix(path) => write!(f, "unix:{}", path.display()),
+            SignerAddr::Tcp(addr) => f.write_str(addr),
+        }
+    }
+}
+
+impl serde::Serialize for SignerAddr {
+    fn serialize<S: serde::Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+        s.collect_str(self)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for SignerAddr {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+        let s = <String as serde::Deserialize>::deserialize(d)?;
+        Ok(s.parse().unwrap())
+    }
+}
+
+/// Connection between a node and the signer daemon.
+pub(crate) enum Stream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(std::os::unix::net::UnixStream),
+}
+
+impl Stream {
+    pub(crate) fn connect(addr: &SignerAddr, timeout: Duration) -> io::Result<Self> {
+        let stream = match addr {
+            SignerAddr::Tcp(addr) => {
+                let addr = addr.to_socket_addrs()?.next().ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::NotFound, format!("{addr} didn't resolve"))
+                })?;
+                let stream = TcpStream::connect_timeout(&addr, timeout)?;
+                stream.set_nodelay(true)?;
+                Stream::Tcp(stream)
+            }
+            #[cfg(unix)]
+            SignerAddr::Unix(path) => Stream::Unix(std::os::unix::net::UnixStream::connect(path)?),
+            #[cfg(not(unix))]
+            SignerAddr::Unix(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "unix sockets are not supported",
+                ))
+            }
+        };
+        stream.set_timeout(Some(timeout))?;
+        Ok(stream)
+    }
+
+    pub(crate) fn set_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        match self {
+            Stream::Tcp(s) => {
+                s.set_read_timeout(timeout)?;
+                s.set_write_timeout(timeout)
+            }
+            #[cfg(unix)]
+            Stream::Unix(s) => {
+                s.set_read_timeout(timeout)?;
+                s.set_write_timeout(timeout)
+            }
+        }
+    }
+}
+
+impl io::Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(s) => s.read(buf),
+            #[cfg(unix)]
+            Stream::Unix(s) => s.read(buf),
+        }
+    }
+}
+
+impl io::Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(s) => s.write(buf),
+            #[cfg(unix)]
+            Stream::Unix(s) => s.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Stream::Tcp(s) => s.flush(),
+            #[cfg(unix)]
+            Stream::Unix(s) => s.flush(),
+        }
+    }
+}
+
+pub enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(std::os::unix::net::UnixListener),
+}
+
+impl Listener {
+    pub fn bind(addr: &SignerAddr) -> io::Result<Self> {
+        match addr {
+            SignerAddr::Tcp(addr) => Ok(Listener::Tcp(TcpListener::bind(addr)?)),
+            #[cfg(unix)]
+            SignerAddr::Unix(path) => {
+                // A socket file left by a previous run would make the bind fail.
+                if path.exists() {
+                    std::fs::remove_file(path)?;
+                }
+                Ok(Listener::Unix(std::os::unix::net::UnixListener::bind(path)?))
+            }
+            #[cfg(not(unix))]
+            SignerAddr::Unix(_) => {
+                Err(io::Error::new(io::ErrorKind::Unsupported, "unix sockets are not supported"))
+            }
+        }
+    }
+
+    pub(crate) fn accept(&self) -> io::Result<Stream> {
+        match self {
+            Listener::Tcp(l) => {
+                let (stream, _) = l.accept()?;
+                stream.set_nodelay(true)?;
+                Ok(Stream::Tcp(stream))
+            }
+            #[cfg(unix)]
+            Listener::Unix(l) => Ok(Stream::Unix(l.accept()?.0)),
+        }
+    }
+}
@@ -2,6 +2,7 @@ use crate::commands::*;
 use crate::contract_accounts::ContractAccountFilter;
 use crate::rocksdb_stats::get_rocksdb_stats;
 use crate::trie_iteration_benchmark::TrieIterationBenchmarkCmd;
+use crate::validate_witness::ValidateWitnessCmd;
 
 use near_chain_configs::{GenesisChangeConfig, GenesisValidationMode};
 use near_primitives::account::id::AccountId;
@@ -88,6 +89,9 @@ pub enum StateViewerSubCommand {
     StateStats(StateStatsCmd),
     /// Benchmark how long does it take to iterate the trie.
     TrieIterationBenchmark(TrieIterationBenchmarkCmd),
+    /// Re-validates chunk state witnesses saved by the node, printing the differences
+    /// between the expected and computed results.
+    ValidateWitness(ValidateWitnessCmd),
     /// View head of the storage.
     #[clap(alias = "view_chain")]
     ViewChain(ViewChainCmd),
@@ -151,6 +155,7 @@ impl StateViewerSubCommand {
             StateViewerSubCommand::ViewChain(cmd) => cmd.run(near_config, store),
             StateViewerSubCommand::ViewTrie(cmd) => cmd.run(store),
             StateViewerSubCommand::TrieIterationBenchmark(cmd) => cmd.run(near_config, store),
+            StateViewerSubCommand::ValidateWitness(cmd) => cmd.run(home_dir, near_config, store),
         }
     }
 }
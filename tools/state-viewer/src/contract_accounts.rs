@@ -347,6 +347,11 @@ fn try_find_actions_spawned_by_receipt(
                                     Action::DeleteKey(_) => ActionType::DeleteKey,
                                     Action::DeleteAccount(_) => ActionType::DeleteAccount,
                                     Action::Delegate(_) => ActionType::Delegate,
+                                    Action::DeployContractWithCodeHashCheck(_) => {
+                                        ActionType::DeployContract
+                                    }
+                                    Action::DeployGlobalContract(_)
+                                    | Action::UseGlobalContract(_) => ActionType::DeployContract,
                                 };
                                 entry
                                     .actions
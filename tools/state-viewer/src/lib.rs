@@ -13,5 +13,6 @@ mod state_dump;
 mod state_parts;
 mod trie_iteration_benchmark;
 mod tx_dump;
+mod validate_witness;
 
 pub use cli::StateViewerSubCommand;
@@ -0,0 +1,202 @@
+use near_async::time::Clock;
+use near_chain::stateless_validation::saved_witnesses::{
+    get_saved_chunk_state_witnesses, SavedWitnessKind,
+};
+use near_chain::validate::validate_chunk_with_chunk_extra_and_receipts_root;
+use near_chain::{Chain, ChainGenesis, ChainStoreAccess, DoomslugThresholdMode};
+use near_client::{apply_chunk_state_witness, pre_validate_chunk_state_witness};
+use near_epoch_manager::shard_tracker::{ShardTracker, TrackedConfig};
+use near_epoch_manager::EpochManager;
+use near_primitives::hash::CryptoHash;
+use near_primitives::sharding::ShardChunkHeader;
+use near_primitives::stateless_validation::SavedChunkStateWitness;
+use near_primitives::types::chunk_extra::ChunkExtra;
+use near_primitives::types::{BlockHeight, ShardId};
+use near_store::Store;
+use nearcore::{NearConfig, NightshadeRuntime, NightshadeRuntimeExt};
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+use std::path::Path;
+
+#[derive(clap::Parser)]
+pub struct ValidateWitnessCmd {
+    /// Height at which the chunk of the witness was created.
+    #[clap(long)]
+    height: Option<BlockHeight>,
+    /// Shard of the chunk of the witness.
+    #[clap(long)]
+    shard_id: Option<ShardId>,
+    /// Use the witnesses saved because of `save_latest_witnesses` instead of the ones
+    /// saved because of `save_invalid_witnesses`.
+    #[clap(long)]
+    latest: bool,
+}
+
+impl ValidateWitnessCmd {
+    pub fn run(self, home_dir: &Path, near_config: NearConfig, store: Store) {
+        let kind = if self.latest { SavedWitnessKind::Latest } else { SavedWitnessKind::Invalid };
+        let witnesses =
+            get_saved_chunk_state_witnesses(&store, kind, self.height, self.shard_id).unwrap();
+        if witnesses.is_empty() {
+            println!("No saved {:?} witnesses found", kind);
+            return;
+        }
+
+        let epoch_manager =
+            EpochManager::new_arc_handle(store.clone(), &near_config.genesis.config);
+        let shard_tracker = ShardTracker::new(
+            TrackedConfig::from_config(&near_config.client_config),
+            epoch_manager.clone(),
+        );
+        let runtime = NightshadeRuntime::from_config(
+            home_dir,
+            store.clone(),
+            &near_config,
+            epoch_manager.clone(),
+        );
+        let chain = Chain::new_for_view_client(
+            Clock::real(),
+            epoch_manager,
+            shard_tracker,
+            runtime,
+            &ChainGenesis::new(&near_config.genesis.config),
+            DoomslugThresholdMode::TwoThirds,
+            false,
+        )
+        .unwrap();
+
+        for witness in witnesses {
+            validate_witness(&chain, witness);
+        }
+    }
+}
+
+fn validate_witness(chain: &Chain, saved: SavedChunkStateWitness) {
+    let state_witness = saved.witness.inner;
+    let chunk_header = state_witness.chunk_header.clone();
+    println!(
+        "Witness for chunk {:?} at height {} in shard {}",
+        chunk_header.chunk_hash(),
+        chunk_header.height_created(),
+        chunk_header.shard_id()
+    );
+    println!("  Saved validation error: {:?}", saved.validation_error);
+
+    let epoch_manager = chain.epoch_manager.as_ref();
+    let runtime_adapter = chain.runtime_adapter.as_ref();
+    let pre_validation_output = match pre_validate_chunk_state_witness(
+        &state_witness,
+        chain,
+        epoch_manager,
+        runtime_adapter,
+    ) {
+        Ok(pre_validation_output) => pre_validation_output,
+        Err(err) => {
+            println!("  Pre-validation failed: {}", err);
+            return;
+        }
+    };
+    let num_transitions = 1 + state_witness.implicit_transitions.len();
+    let applied = match apply_chunk_state_witness(
+        state_witness,
+        pre_validation_output,
+        epoch_manager,
+        runtime_adapter,
+    ) {
+        Ok(applied) => applied,
+        Err(err) => {
+            println!("  Applying the state transitions failed: {}", err);
+            return;
+        }
+    };
+
+    println!("  Post state roots:");
+    for (index, root) in applied.post_state_roots.iter().enumerate() {
+        let transition = if index == 0 { "main" } else { "implicit" };
+        let status = if root.expected == root.actual { "OK" } else { "MISMATCH" };
+        println!(
+            "    {} transition at block {}: expected {}, actual {} {}",
+            transition, root.block_hash, root.expected, root.actual, status
+        );
+    }
+    let num_skipped = num_transitions.saturating_sub(applied.post_state_roots.len());
+    if num_skipped > 0 {
+        println!("    {} following transitions were not applied", num_skipped);
+    }
+
+    println!("  Chunk extra compared with the chunk header:");
+    print_chunk_extra_diff(&applied.chunk_extra, &chunk_header, &applied.outgoing_receipts_root);
+
+    println!("  Outgoing receipts:");
+    let main_block_hash = applied.post_state_roots[0].block_hash;
+    let computed_ids =
+        applied.outgoing_receipts.iter().map(|receipt| receipt.receipt_id).collect::<Vec<_>>();
+    match chain.chain_store().get_outgoing_receipts(&main_block_hash, chunk_header.shard_id()) {
+        Ok(stored) => {
+            let computed = computed_ids.iter().collect::<BTreeSet<_>>();
+            let stored = stored.iter().map(|receipt| &receipt.receipt_id).collect::<BTreeSet<_>>();
+            println!(
+                "    {} computed, {} stored for block {}",
+                computed.len(),
+                stored.len(),
+                main_block_hash
+            );
+            for receipt_id in computed.difference(&stored) {
+                println!("    + {} computed but not stored", receipt_id);
+            }
+            for receipt_id in stored.difference(&computed) {
+                println!("    - {} stored but not computed", receipt_id);
+            }
+        }
+        Err(err) => {
+            println!("    {} computed, stored receipts unavailable: {}", computed_ids.len(), err);
+            for receipt_id in computed_ids {
+                println!("    {}", receipt_id);
+            }
+        }
+    }
+
+    let result = match applied.first_post_state_root_mismatch() {
+        Some(_) => Err("post state root mismatch".to_string()),
+        None => validate_chunk_with_chunk_extra_and_receipts_root(
+            &applied.chunk_extra,
+            &chunk_header,
+            &applied.outgoing_receipts_root,
+        )
+        .map_err(|err| err.to_string()),
+    };
+    match result {
+        Ok(()) => println!("  Result: valid"),
+        Err(err) => println!("  Result: invalid, {}", err),
+    }
+}
+
+fn print_chunk_extra_diff(
+    chunk_extra: &ChunkExtra,
+    chunk_header: &ShardChunkHeader,
+    outgoing_receipts_root: &CryptoHash,
+) {
+    print_field("state root", chunk_extra.state_root(), &chunk_header.prev_state_root());
+    print_field("outcome root", chunk_extra.outcome_root(), &chunk_header.prev_outcome_root());
+    print_field("gas used", &chunk_extra.gas_used(), &chunk_header.prev_gas_used());
+    print_field("gas limit", &chunk_extra.gas_limit(), &chunk_header.gas_limit());
+    print_field("balance burnt", &chunk_extra.balance_burnt(), &chunk_header.prev_balance_burnt());
+    print_field(
+        "validator proposals",
+        &chunk_extra.validator_proposals().collect::<Vec<_>>(),
+        &chunk_header.prev_validator_proposals().collect::<Vec<_>>(),
+    );
+    print_field(
+        "outgoing receipts root",
+        outgoing_receipts_root,
+        &chunk_header.prev_outgoing_receipts_root(),
+    );
+}
+
+fn print_field<T: Debug + PartialEq>(name: &str, computed: &T, header: &T) {
+    if computed == header {
+        println!("    {}: {:?} OK", name, computed);
+    } else {
+        println!("    {}: computed {:?}, header {:?} MISMATCH", name, computed, header);
+    }
+}
@@ -1,15 +1,26 @@
+use near_chain::types::RuntimeAdapter;
 use near_chain::ChainStore;
 use near_chain_configs::GenesisValidationMode;
 use near_epoch_manager::EpochManager;
+use near_primitives::types::{BlockHeight, NumBlocks};
 use near_store::{Mode, NodeStorage};
-use nearcore::load_config;
+use nearcore::{load_config, NightshadeRuntime, NightshadeRuntimeExt};
 use std::path::Path;
 
 #[derive(clap::Parser)]
 pub struct UndoBlockCommand {
     /// Only reset the block head to the tail block. Does not reset the header head.
-    #[arg(short, long)]
+    #[arg(short, long, conflicts_with_all = ["to_height", "blocks", "dry_run"])]
     reset_only_body: bool,
+    /// Roll back all blocks above the given height. Defaults to undoing only the head block.
+    #[arg(long, conflicts_with = "blocks")]
+    to_height: Option<BlockHeight>,
+    /// Roll back the given number of blocks from the head.
+    #[arg(long)]
+    blocks: Option<NumBlocks>,
+    /// Only report the blocks which would be rolled back, without changing the database.
+    #[arg(long)]
+    dry_run: bool,
 }
 
 impl UndoBlockCommand {
@@ -33,6 +44,13 @@ impl UndoBlockCommand {
 
         let epoch_manager =
             EpochManager::new_arc_handle(store.clone(), &near_config.genesis.config);
+        let runtime = NightshadeRuntime::from_config(
+            home_dir,
+            store.clone(),
+            &near_config,
+            epoch_manager.clone(),
+        );
+        let tries = runtime.get_tries();
 
         let mut chain_store = ChainStore::new(
             store,
@@ -41,9 +59,12 @@ impl UndoBlockCommand {
         );
 
         if self.reset_only_body {
-            crate::undo_only_block_head(&mut chain_store, &*epoch_manager)
-        } else {
-            crate::undo_block(&mut chain_store, &*epoch_manager)
+            return crate::undo_only_block_head(&mut chain_store, &*epoch_manager, &tries);
         }
+        let new_head_height = match (self.to_height, self.blocks) {
+            (Some(height), _) => height,
+            (None, blocks) => crate::ancestor_height(&chain_store, blocks.unwrap_or(1))?,
+        };
+        crate::undo_blocks(&mut chain_store, &*epoch_manager, &tries, new_head_height, self.dry_run)
     }
 }
@@ -2,39 +2,86 @@ use chrono::Utc;
 use near_chain::types::{EpochManagerAdapter, LatestKnown};
 use near_chain::{ChainStore, ChainStoreAccess, ChainStoreUpdate};
 use near_primitives::block::Tip;
+use near_primitives::shard_layout::get_block_shard_uid;
+use near_primitives::types::{BlockHeight, NumBlocks, ShardId};
 use near_primitives::utils::to_timestamp;
+use near_store::{DBCol, ShardTries};
 
 pub mod cli;
 
 pub fn undo_block(
     chain_store: &mut ChainStore,
     epoch_manager: &dyn EpochManagerAdapter,
+    tries: &ShardTries,
+) -> anyhow::Result<()> {
+    let prev_block_height = ancestor_height(chain_store, 1)?;
+    undo_blocks(chain_store, epoch_manager, tries, prev_block_height, false)
+}
+
+/// Returns the height of the block `num_blocks` blocks below the current head.
+pub fn ancestor_height(
+    chain_store: &ChainStore,
+    num_blocks: NumBlocks,
+) -> anyhow::Result<BlockHeight> {
+    let mut header = chain_store.head_header()?;
+    for _ in 0..num_blocks {
+        header = chain_store.get_block_header(header.prev_hash())?;
+    }
+    Ok(header.height())
+}
+
+/// Rolls back all blocks above `new_head_height`, so that the head is the last block on the
+/// canonical chain with height not greater than `new_head_height`.
+///
+/// All blocks are undone in a single store update, so either all of them are undone or none.
+/// With `dry_run` nothing is written, only the blocks and the trie changes that would be
+/// reverted are reported.
+pub fn undo_blocks(
+    chain_store: &mut ChainStore,
+    epoch_manager: &dyn EpochManagerAdapter,
+    tries: &ShardTries,
+    new_head_height: BlockHeight,
+    dry_run: bool,
 ) -> anyhow::Result<()> {
     let current_head = chain_store.head()?;
-    let current_head_hash = current_head.last_block_hash;
-    let prev_block_hash = current_head.prev_block_hash;
-    let prev_header = chain_store.get_block_header(&prev_block_hash)?;
-    let prev_tip = Tip::from_header(&prev_header);
     let current_head_height = current_head.height;
-    let prev_block_height = prev_tip.height;
+    let final_head_height = chain_store.final_head()?.height;
 
-    tracing::info!(target: "neard", ?prev_block_hash, ?current_head_hash, ?prev_block_height, ?current_head_height, "Trying to update head");
+    tracing::info!(target: "neard", ?current_head_height, ?new_head_height, ?final_head_height, dry_run, "Trying to update head");
 
-    // stop if it's already the final block
-    if chain_store.final_head()?.height >= current_head.height {
-        return Err(anyhow::anyhow!("Cannot revert past final block"));
+    if new_head_height >= current_head_height {
+        tracing::info!(target: "neard", "Head is already at or below the requested height.");
+        return Ok(());
+    }
+    // stop if it would revert the final block
+    if new_head_height < final_head_height {
+        return Err(anyhow::anyhow!(
+            "Cannot revert past final block at height {}",
+            final_head_height
+        ));
     }
 
     let mut chain_store_update = ChainStoreUpdate::new(chain_store);
-
-    chain_store_update.clear_head_block_data(epoch_manager)?;
-
-    chain_store_update.save_head(&prev_tip)?;
-
+    let new_tip = loop {
+        let head = chain_store_update.head()?;
+        if head.height <= new_head_height {
+            break head;
+        }
+        report_block(&chain_store_update, epoch_manager, &head)?;
+        let prev_header = chain_store_update.get_block_header(&head.prev_block_hash)?;
+        chain_store_update.clear_head_block_data(epoch_manager, tries)?;
+        chain_store_update.save_head(&Tip::from_header(&prev_header))?;
+    };
+
+    let new_head_height = new_tip.height;
+    if dry_run {
+        tracing::info!(target: "neard", ?new_head_height, "Dry run, nothing was changed");
+        return Ok(());
+    }
     chain_store_update.commit()?;
 
     chain_store.save_latest_known(LatestKnown {
-        height: prev_tip.height,
+        height: new_tip.height,
         seen: to_timestamp(Utc::now()),
     })?;
 
@@ -47,9 +94,31 @@ pub fn undo_block(
     Ok(())
 }
 
+/// Logs the block about to be undone, together with the shards whose trie changes are reverted.
+fn report_block(
+    chain_store_update: &ChainStoreUpdate,
+    epoch_manager: &dyn EpochManagerAdapter,
+    head: &Tip,
+) -> anyhow::Result<()> {
+    let block_hash = head.last_block_hash;
+    let block_height = head.height;
+    let num_shards = chain_store_update.get_block_header(&block_hash)?.chunk_mask().len();
+    let mut shards_with_trie_changes = vec![];
+    for shard_id in 0..num_shards as ShardId {
+        let shard_uid = epoch_manager.shard_id_to_uid(shard_id, &head.epoch_id)?;
+        let key = get_block_shard_uid(&block_hash, &shard_uid);
+        if chain_store_update.store().exists(DBCol::TrieChanges, &key)? {
+            shards_with_trie_changes.push(shard_id);
+        }
+    }
+    tracing::info!(target: "neard", ?block_height, ?block_hash, ?shards_with_trie_changes, "Undoing block");
+    Ok(())
+}
+
 pub fn undo_only_block_head(
     chain_store: &mut ChainStore,
     epoch_manager: &dyn EpochManagerAdapter,
+    tries: &ShardTries,
 ) -> anyhow::Result<()> {
     let current_head = chain_store.head()?;
     let current_head_height = current_head.height;
@@ -68,7 +137,7 @@ pub fn undo_only_block_head(
     }
 
     let mut chain_store_update = ChainStoreUpdate::new(chain_store);
-    chain_store_update.clear_head_block_data(epoch_manager)?;
+    chain_store_update.clear_head_block_data(epoch_manager, tries)?;
     chain_store_update.save_body_head(&new_head)?;
     chain_store_update.commit()?;
 